@@ -6,21 +6,29 @@ use clap::{Parser, Subcommand};
 
 use super::cert::{cert_delete, cert_list, cert_show, cert_trust};
 use super::console::console;
+use super::convert::{ConvertFormat, convert};
 use super::destroy::destroy;
+use super::diagram::{DiagramFormat, diagram};
 use super::down::down;
 use super::download::download;
 use super::image::{ImageCommands, parse_image_commands};
 use super::init::init;
 use super::inspect::inspect;
-use super::login::{login, logout, whoami};
+use super::leases::leases;
+use super::link::{LinkCommands, link};
+use super::login::{login, login_sso, logout, whoami};
 use super::new::new;
+use super::node::{NodeCommands, node};
 use super::redeploy::redeploy;
 use super::resume::resume;
 use super::server::{OutputFormat, ServerCommands, run_server};
+use super::share::{ShareCommands, share};
 use super::ssh::ssh;
 use super::ssh_config::{ssh_config_clean, ssh_config_inspect};
+use super::token::{TokenCommands, token};
 use super::up::up;
 use super::validate::validate_manifest;
+use super::verify::{VerifyCommands, verify};
 
 use crate::token::load_token;
 use crate::ws_client::{RpcRequest, WebSocketClient};
@@ -173,11 +181,20 @@ pub struct Cli {
 #[derive(Default, Debug, Subcommand)]
 enum Commands {
     /// Login to Sherpa server
-    Login,
+    Login {
+        /// Sign in with the server's single sign-on provider in a browser
+        #[arg(long)]
+        sso: bool,
+    },
     /// Logout from Sherpa server
     Logout,
     /// Show current authentication status
     Whoami,
+    /// Manage personal API tokens for automation
+    Token {
+        #[command(subcommand)]
+        commands: TokenCommands,
+    },
 
     /// Create a new example manifest.toml in the current directory
     New {
@@ -212,6 +229,15 @@ enum Commands {
         #[arg(short, long)]
         node: String,
     },
+    /// Node management commands
+    Node {
+        /// Lab ID (defaults to the lab in the current directory)
+        #[arg(long)]
+        lab_id: Option<String>,
+
+        #[command(subcommand)]
+        commands: NodeCommands,
+    },
     /// Destroy environment
     Destroy {
         /// Skip confirmation prompt
@@ -234,6 +260,68 @@ enum Commands {
     /// Validate configurations
     Validate,
 
+    /// Render a topology diagram of the manifest
+    Diagram {
+        /// Diagram format
+        #[arg(long, value_enum, default_value = "dot")]
+        format: DiagramFormat,
+        /// Write the diagram to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Convert a containerlab, GNS3 or EVE-NG topology into a manifest
+    Convert {
+        /// Source topology format
+        #[arg(long, value_enum)]
+        from: ConvertFormat,
+        /// Source topology file
+        file: String,
+        /// Manifest file to write
+        #[arg(short, long, default_value = SHERPA_MANIFEST_FILE)]
+        output: String,
+        /// Overwrite manifest file if one exists
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        force: bool,
+    },
+
+    /// Share the lab with other users or teams
+    Share {
+        /// Lab ID (defaults to the lab in the current directory)
+        #[arg(long)]
+        lab_id: Option<String>,
+
+        #[command(subcommand)]
+        commands: ShareCommands,
+    },
+
+    /// List the DHCP leases of the lab and the nodes holding them
+    Leases {
+        /// Lab ID (defaults to the lab in the current directory)
+        #[arg(long)]
+        lab_id: Option<String>,
+    },
+
+    /// Inspect, mirror and filter the links of the lab
+    Link {
+        /// Lab ID (defaults to the lab in the current directory)
+        #[arg(long)]
+        lab_id: Option<String>,
+
+        #[command(subcommand)]
+        commands: LinkCommands,
+    },
+
+    /// Verify a running lab against its manifest
+    Verify {
+        /// Lab ID (defaults to the lab in the current directory)
+        #[arg(long)]
+        lab_id: Option<String>,
+
+        #[command(subcommand)]
+        commands: VerifyCommands,
+    },
+
     /// Connect to a device via serial console over Telnet
     Console { name: String },
 
@@ -307,7 +395,7 @@ impl Cli {
         let cli = Cli::parse();
         let sherpa = Sherpa::from_base_dir(sherpa_client_base_dir());
         match &cli.commands {
-            Commands::Login => {
+            Commands::Login { sso } => {
                 let config = load_client_config_or_default(&sherpa.config_file_path);
 
                 let server_url = cli
@@ -315,7 +403,11 @@ impl Cli {
                     .or_else(get_server_url)
                     .unwrap_or_else(|| build_client_websocket_url(&config));
 
-                login(&server_url, cli.insecure, &config).await?;
+                if *sso {
+                    login_sso(&server_url, cli.insecure, &config).await?;
+                } else {
+                    login(&server_url, cli.insecure, &config).await?;
+                }
             }
             Commands::Logout => {
                 logout()?;
@@ -330,6 +422,12 @@ impl Cli {
 
                 whoami(&server_url, cli.insecure, &config).await?;
             }
+            Commands::Token { commands } => {
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                token(commands, &server_url, &config).await?;
+            }
             Commands::New { force } => {
                 new(*force)?;
             }
@@ -407,6 +505,67 @@ impl Cli {
             Commands::Validate => {
                 validate_manifest(SHERPA_MANIFEST_FILE)?;
             }
+            Commands::Diagram { format, output } => {
+                diagram(SHERPA_MANIFEST_FILE, *format, output.as_deref())?;
+            }
+            Commands::Convert {
+                from,
+                file,
+                output,
+                force,
+            } => {
+                convert(*from, file, output, *force)?;
+            }
+            Commands::Node { lab_id, commands } => {
+                let lab_id = match lab_id {
+                    Some(lab_id) => lab_id.clone(),
+                    None => resolve_lab_identity()?.id,
+                };
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                node(commands, &lab_id, &server_url, &config).await?;
+            }
+            Commands::Share { lab_id, commands } => {
+                let lab_id = match lab_id {
+                    Some(lab_id) => lab_id.clone(),
+                    None => resolve_lab_identity()?.id,
+                };
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                share(commands, &lab_id, &server_url, &config).await?;
+            }
+            Commands::Leases { lab_id } => {
+                let lab_id = match lab_id {
+                    Some(lab_id) => lab_id.clone(),
+                    None => resolve_lab_identity()?.id,
+                };
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                leases(&lab_id, &server_url, &config).await?;
+            }
+            Commands::Link { lab_id, commands } => {
+                let lab_id = match lab_id {
+                    Some(lab_id) => lab_id.clone(),
+                    None => resolve_lab_identity()?.id,
+                };
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                link(commands, &lab_id, &server_url, &config).await?;
+            }
+            Commands::Verify { lab_id, commands } => {
+                let lab_id = match lab_id {
+                    Some(lab_id) => lab_id.clone(),
+                    None => resolve_lab_identity()?.id,
+                };
+                let config = load_client_config_for_command(&sherpa.config_file_path, cli.insecure);
+
+                let server_url = resolve_server_url(cli.server_url, &config);
+                verify(commands, &lab_id, &server_url, &config).await?;
+            }
             Commands::Console { name } => {
                 let manifest_obj = Manifest::load_file(SHERPA_MANIFEST_FILE)?;
                 let lab_id = get_id(&manifest_obj.name)?;
@@ -466,6 +625,8 @@ impl Cli {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cmd::link::{FilterCommands, MirrorCommands};
+    use shared::data::{L2Match, L2Protocol, LabRole, MirrorDirection, TokenScope};
 
     #[test]
     fn test_apply_insecure_override_sets_config_flag() {
@@ -515,4 +676,346 @@ mod tests {
             other => panic!("unexpected command: {other:?}"),
         }
     }
+
+    #[test]
+    fn test_parse_convert_command() {
+        let cli =
+            Cli::try_parse_from(["sherpa", "convert", "--from", "clab", "lab.clab.yml"]).unwrap();
+        match cli.commands {
+            Commands::Convert {
+                from,
+                file,
+                output,
+                force,
+            } => {
+                assert_eq!(from, ConvertFormat::Clab);
+                assert_eq!(file, "lab.clab.yml");
+                assert_eq!(output, SHERPA_MANIFEST_FILE);
+                assert!(!force);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_convert_command_rejects_unknown_format() {
+        assert!(Cli::try_parse_from(["sherpa", "convert", "--from", "netbox", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_diagram_command_defaults_to_dot() {
+        let cli = Cli::try_parse_from(["sherpa", "diagram"]).unwrap();
+        match cli.commands {
+            Commands::Diagram { format, output } => {
+                assert_eq!(format, DiagramFormat::Dot);
+                assert_eq!(output, None);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_diagram_command_with_format_and_output() {
+        let cli =
+            Cli::try_parse_from(["sherpa", "diagram", "--format", "svg", "-o", "lab.svg"]).unwrap();
+        match cli.commands {
+            Commands::Diagram { format, output } => {
+                assert_eq!(format, DiagramFormat::Svg);
+                assert_eq!(output.as_deref(), Some("lab.svg"));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_node_commit_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "node",
+            "commit",
+            "r1",
+            "--as-version",
+            "17.9-golden",
+            "--default",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Node {
+                lab_id: None,
+                commands:
+                    NodeCommands::Commit {
+                        name,
+                        as_version,
+                        default,
+                    },
+            } => {
+                assert_eq!(name, "r1");
+                assert_eq!(as_version, "17.9-golden");
+                assert!(default);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_share_add_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "share",
+            "--lab-id",
+            "abcd1234",
+            "add",
+            "team:students",
+            "--role",
+            "operator",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Share { lab_id, commands } => {
+                assert_eq!(lab_id.as_deref(), Some("abcd1234"));
+                match commands {
+                    ShareCommands::Add { grantee, role } => {
+                        assert_eq!(grantee, "team:students");
+                        assert_eq!(role, LabRole::Operator);
+                    }
+                    other => panic!("unexpected share command: {other:?}"),
+                }
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_share_add_defaults_to_viewer() {
+        let cli = Cli::try_parse_from(["sherpa", "share", "add", "alice"]).unwrap();
+        match cli.commands {
+            Commands::Share {
+                lab_id: None,
+                commands: ShareCommands::Add { role, .. },
+            } => assert_eq!(role, LabRole::Viewer),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_share_add_rejects_owner_role() {
+        assert!(
+            Cli::try_parse_from(["sherpa", "share", "add", "alice", "--role", "owner"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_leases_command() {
+        let cli = Cli::try_parse_from(["sherpa", "leases", "--lab-id", "abcd1234"]).unwrap();
+        match cli.commands {
+            Commands::Leases { lab_id } => assert_eq!(lab_id.as_deref(), Some("abcd1234")),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_verify_cabling_command() {
+        let cli = Cli::try_parse_from(["sherpa", "verify", "cabling", "--wait", "70"]).unwrap();
+        match cli.commands {
+            Commands::Verify {
+                lab_id,
+                commands: VerifyCommands::Cabling { wait },
+            } => {
+                assert_eq!(lab_id, None);
+                assert_eq!(wait, Some(70));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_link_stats_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "link",
+            "--lab-id",
+            "abcd1234",
+            "stats",
+            "--watch",
+            "--interval",
+            "5",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Link { lab_id, commands } => {
+                assert_eq!(lab_id.as_deref(), Some("abcd1234"));
+                match commands {
+                    LinkCommands::Stats { watch, interval } => {
+                        assert!(watch);
+                        assert_eq!(interval, 5);
+                    }
+                    other => panic!("unexpected link command: {other:?}"),
+                }
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["sherpa", "link", "stats"]).unwrap();
+        assert!(matches!(
+            cli.commands,
+            Commands::Link {
+                lab_id: None,
+                commands: LinkCommands::Stats {
+                    watch: false,
+                    interval: 2
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_link_mirror_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "link",
+            "mirror",
+            "add",
+            "r1::eth1",
+            "ids01::eth1",
+            "--direction",
+            "egress",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Link {
+                commands:
+                    LinkCommands::Mirror {
+                        commands:
+                            MirrorCommands::Add {
+                                source,
+                                destination,
+                                direction,
+                            },
+                    },
+                ..
+            } => {
+                assert_eq!(source, "r1::eth1");
+                assert_eq!(destination, "ids01::eth1");
+                assert_eq!(direction, MirrorDirection::Egress);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "link",
+            "mirror",
+            "remove",
+            "r1::eth1",
+            "ids01::eth1",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.commands,
+            Commands::Link {
+                commands: LinkCommands::Mirror {
+                    commands: MirrorCommands::Remove {
+                        direction: MirrorDirection::Both,
+                        ..
+                    }
+                },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_link_filter_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa", "link", "filter", "set", "r1::eth1", "--drop", "lacp", "--drop", "vlan:20",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Link {
+                commands:
+                    LinkCommands::Filter {
+                        commands: FilterCommands::Set { link, drop, allow },
+                    },
+                ..
+            } => {
+                assert_eq!(link, "r1::eth1");
+                assert_eq!(
+                    drop,
+                    vec![L2Match::Protocol(L2Protocol::Lacp), L2Match::Vlan(20)]
+                );
+                assert!(allow.is_empty());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        assert!(
+            Cli::try_parse_from([
+                "sherpa", "link", "filter", "set", "r1::eth1", "--drop", "bgp"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_login_sso_flag() {
+        let cli = Cli::try_parse_from(["sherpa", "login", "--sso"]).unwrap();
+        assert!(matches!(cli.commands, Commands::Login { sso: true }));
+
+        let cli = Cli::try_parse_from(["sherpa", "login"]).unwrap();
+        assert!(matches!(cli.commands, Commands::Login { sso: false }));
+    }
+
+    #[test]
+    fn test_parse_token_create_command() {
+        let cli = Cli::try_parse_from([
+            "sherpa",
+            "token",
+            "create",
+            "ci",
+            "--scope",
+            "read-only",
+            "--scope",
+            "lab-operate",
+            "--expires-in-days",
+            "30",
+        ])
+        .unwrap();
+        match cli.commands {
+            Commands::Token {
+                commands:
+                    TokenCommands::Create {
+                        name,
+                        scopes,
+                        expires_in_days,
+                    },
+            } => {
+                assert_eq!(name, "ci");
+                assert_eq!(scopes, vec![TokenScope::ReadOnly, TokenScope::LabOperate]);
+                assert_eq!(expires_in_days, 30);
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_token_create_requires_scope() {
+        assert!(Cli::try_parse_from(["sherpa", "token", "create", "ci"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_token_create_rejects_long_expiry() {
+        assert!(
+            Cli::try_parse_from([
+                "sherpa",
+                "token",
+                "create",
+                "ci",
+                "--scope",
+                "read-only",
+                "--expires-in-days",
+                "366",
+            ])
+            .is_err()
+        );
+    }
 }
@@ -0,0 +1,293 @@
+//! containerlab (`.clab.yml`) topology conversion.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use serde_derive::Deserialize;
+
+use shared::data::{NodeKind, NodeModel};
+
+use super::{Conversion, ManifestBuilder, model_from_keyword, split_image_tag};
+
+#[derive(Debug, Deserialize)]
+struct ClabTopologyFile {
+    name: String,
+    topology: ClabTopology,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ClabTopology {
+    defaults: ClabNode,
+    kinds: HashMap<String, ClabNode>,
+    nodes: BTreeMap<String, ClabNode>,
+    links: Vec<ClabLink>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+struct ClabNode {
+    kind: Option<String>,
+    image: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ClabLink {
+    #[serde(rename = "type")]
+    link_type: Option<String>,
+    endpoints: Vec<ClabEndpoint>,
+}
+
+/// Link endpoints come in a brief (`"r1:eth1"`) and an extended form.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ClabEndpoint {
+    Brief(String),
+    Extended { node: String, interface: String },
+}
+
+impl ClabEndpoint {
+    fn split(&self) -> Option<(&str, &str)> {
+        match self {
+            ClabEndpoint::Brief(value) => value.split_once(':'),
+            ClabEndpoint::Extended { node, interface } => Some((node, interface)),
+        }
+    }
+}
+
+/// Map a containerlab node kind to a Sherpa model.
+fn model_from_kind(kind: &str) -> Option<NodeModel> {
+    let model = match kind {
+        "ceos" | "arista_ceos" => NodeModel::AristaCeos,
+        "vr-veos" | "arista_veos" => NodeModel::AristaVeos,
+        "srl" | "nokia_srlinux" => NodeModel::NokiaSrlinux,
+        "vr-xrv9k" | "cisco_xrv9k" => NodeModel::CiscoIosxrv9000,
+        "vr-n9kv" | "cisco_n9kv" => NodeModel::CiscoNexus9300v,
+        "vr-csr" | "cisco_csr1000v" => NodeModel::CiscoCsr1000v,
+        "cisco_c8000v" => NodeModel::CiscoCat8000v,
+        "cisco_cat9kv" => NodeModel::CiscoCat9000v,
+        "vr-ftdv" | "cisco_ftdv" => NodeModel::CiscoFtdv,
+        "cisco_asav" => NodeModel::CiscoAsav,
+        "juniper_vjunosrouter" => NodeModel::JuniperVrouter,
+        "juniper_vjunosswitch" => NodeModel::JuniperVswitch,
+        "juniper_vjunosevolved" => NodeModel::JuniperVevolved,
+        "vr-vsrx" | "juniper_vsrx" => NodeModel::JuniperVsrxv3,
+        "vr-ros" | "mikrotik_ros" => NodeModel::MikrotikChr,
+        "vr-pan" | "paloalto_panos" => NodeModel::PaloaltoPanos,
+        "vr-aoscx" | "aruba_aoscx" => NodeModel::ArubaAoscx,
+        "cvx" | "cumulus_cvx" => NodeModel::CumulusLinux,
+        "sonic-vs" | "sonic-vm" => NodeModel::SonicLinux,
+        _ => return None,
+    };
+    Some(model)
+}
+
+fn is_bridge_kind(kind: &str) -> bool {
+    matches!(kind, "bridge" | "ovs-bridge")
+}
+
+pub(super) fn convert(contents: &str) -> Result<Conversion> {
+    let file: ClabTopologyFile =
+        serde_yaml::from_str(contents).context("Invalid containerlab topology")?;
+    let topology = file.topology;
+
+    let mut builder = ManifestBuilder::default();
+    let mut bridges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for (name, node) in &topology.nodes {
+        let Some(kind) = node.kind.clone().or_else(|| topology.defaults.kind.clone()) else {
+            builder.warn(format!("Node '{name}' has no kind, skipped"));
+            continue;
+        };
+        if is_bridge_kind(&kind) {
+            bridges.insert(name.clone(), vec![]);
+            continue;
+        }
+        let image = node
+            .image
+            .clone()
+            .or_else(|| topology.kinds.get(&kind).and_then(|k| k.image.clone()))
+            .or_else(|| topology.defaults.image.clone());
+
+        let model = match model_from_kind(&kind) {
+            Some(model) => model,
+            None if kind == "linux" => match image.as_deref().and_then(model_from_keyword) {
+                Some(model) => model,
+                None => {
+                    builder.warn(format!(
+                        "Node '{name}' image {:?} not recognised, using generic_container",
+                        image.as_deref().unwrap_or("<none>")
+                    ));
+                    NodeModel::GenericContainer
+                }
+            },
+            None => match model_from_keyword(&kind) {
+                Some(model) => model,
+                None => {
+                    builder.warn(format!(
+                        "Node '{name}' kind '{kind}' is not supported, skipped"
+                    ));
+                    continue;
+                }
+            },
+        };
+
+        let version = image
+            .as_deref()
+            .and_then(|image| split_image_tag(image).1)
+            .filter(|tag| *tag != "latest")
+            .map(str::to_string);
+        if version.is_none() && model.kind() == NodeKind::Container {
+            builder.warn(format!(
+                "Node '{name}' has no image tag, the default version will be used"
+            ));
+        }
+
+        builder.add_node(name, name, model, version);
+    }
+
+    for link in &topology.links {
+        if let Some(link_type) = link.link_type.as_deref()
+            && link_type != "veth"
+        {
+            builder.warn(format!("Link type '{link_type}' is not supported, skipped"));
+            continue;
+        }
+        let endpoints: Vec<(&str, &str)> = link
+            .endpoints
+            .iter()
+            .filter_map(ClabEndpoint::split)
+            .collect();
+        let [(node_a, int_a), (node_b, int_b)] = endpoints[..] else {
+            builder.warn(format!(
+                "Link {:?} does not have two endpoints, skipped",
+                link.endpoints
+            ));
+            continue;
+        };
+
+        // Links to a bridge node become members of that bridge.
+        if let Some((bridge, (node, interface))) = bridges
+            .contains_key(node_a)
+            .then_some((node_a, (node_b, int_b)))
+            .or_else(|| {
+                bridges
+                    .contains_key(node_b)
+                    .then_some((node_b, (node_a, int_a)))
+            })
+        {
+            if let Some(member) = builder.endpoint_by_name(node, interface)
+                && let Some(members) = bridges.get_mut(bridge)
+            {
+                members.push(member);
+            }
+            continue;
+        }
+
+        let mut unknown = false;
+        for node in [node_a, node_b] {
+            if !builder.has_node(node) {
+                unknown = true;
+                builder.warn(format!(
+                    "Link {node_a}:{int_a} <-> {node_b}:{int_b} references '{node}' which cannot be mapped, skipped"
+                ));
+            }
+        }
+        if unknown {
+            continue;
+        }
+        let src = builder.endpoint_by_name(node_a, int_a);
+        let dst = builder.endpoint_by_name(node_b, int_b);
+        if let (Some(src), Some(dst)) = (src, dst) {
+            builder.add_link(src, dst);
+        }
+    }
+
+    for (name, members) in bridges {
+        builder.add_bridge(&name, members);
+    }
+
+    Ok(builder.finish(&file.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOPOLOGY: &str = r#"
+name: srl-ceos
+topology:
+  kinds:
+    nokia_srlinux:
+      image: ghcr.io/nokia/srlinux:24.10.1
+  nodes:
+    srl1:
+      kind: nokia_srlinux
+    ceos1:
+      kind: arista_ceos
+      image: ceos:4.32.0F
+    client:
+      kind: linux
+      image: alpine:3.20
+    sw1:
+      kind: bridge
+    fw1:
+      kind: fortinet_fortigate
+  links:
+    - endpoints: ["srl1:e1-1", "ceos1:eth1"]
+    - type: veth
+      endpoints:
+        - node: srl1
+          interface: e1-2
+        - node: client
+          interface: eth1
+    - endpoints: ["ceos1:eth2", "sw1:port1"]
+    - endpoints: ["client:eth2", "sw1:port2"]
+    - endpoints: ["ceos1:eth3", "fw1:port1"]
+    - endpoints: ["ceos1:eth4", "host:ceos1-eth4"]
+"#;
+
+    #[test]
+    fn test_convert_clab_nodes() {
+        let conversion = convert(TOPOLOGY).expect("converts");
+        let nodes = &conversion.manifest.nodes;
+        assert_eq!(conversion.manifest.name, "srl-ceos");
+        assert_eq!(nodes.len(), 3);
+
+        let ceos = nodes.iter().find(|n| n.name == "ceos1").expect("ceos1");
+        assert_eq!(ceos.model, NodeModel::AristaCeos);
+        assert_eq!(ceos.version.as_deref(), Some("4.32.0F"));
+
+        let srl = nodes.iter().find(|n| n.name == "srl1").expect("srl1");
+        assert_eq!(srl.model, NodeModel::NokiaSrlinux);
+        assert_eq!(srl.version.as_deref(), Some("24.10.1"));
+
+        let client = nodes.iter().find(|n| n.name == "client").expect("client");
+        assert_eq!(client.model, NodeModel::AlpineLinux);
+    }
+
+    #[test]
+    fn test_convert_clab_links_and_bridges() {
+        let conversion = convert(TOPOLOGY).expect("converts");
+        let links = conversion.manifest.links.expect("links");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].src, "srl1::eth-1/1");
+        assert_eq!(links[0].dst, "ceos1::eth1");
+        assert_eq!(links[1].src, "srl1::eth-1/2");
+
+        let bridges = conversion.manifest.bridges.expect("bridges");
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].name, "sw1");
+        assert_eq!(bridges[0].links, vec!["ceos1::eth2", "client::eth2"]);
+    }
+
+    #[test]
+    fn test_convert_clab_warns_on_unmapped_items() {
+        let conversion = convert(TOPOLOGY).expect("converts");
+        let warnings = conversion.warnings.join("\n");
+        assert!(warnings.contains("fortinet_fortigate"));
+        assert!(warnings.contains("references 'fw1'"));
+        assert!(warnings.contains("references 'host'"));
+    }
+}
@@ -0,0 +1,217 @@
+//! EVE-NG lab (`.unl`) topology conversion.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use roxmltree::{Document, Node as XmlNode};
+
+use super::{Conversion, ManifestBuilder, model_from_keyword, split_image_tag};
+
+/// Extract a version from an EVE-NG image directory name.
+///
+/// Images are stored as `<template>-<version>`, e.g. `veos-4.29.2F`.
+fn image_version(template: &str, image: &str) -> Option<String> {
+    image
+        .strip_prefix(template)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .filter(|version| !version.is_empty())
+        .map(str::to_string)
+}
+
+fn children<'a, 'input>(
+    node: XmlNode<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = XmlNode<'a, 'input>> + 'a {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+pub(super) fn convert(contents: &str) -> Result<Conversion> {
+    let document = Document::parse(contents).context("Invalid EVE-NG lab")?;
+    let lab = document.root_element();
+    anyhow::ensure!(lab.has_tag_name("lab"), "Invalid EVE-NG lab: missing <lab>");
+    let lab_name = lab.attribute("name").unwrap_or("eve-ng-lab");
+
+    let topology = children(lab, "topology")
+        .next()
+        .context("Invalid EVE-NG lab: missing <topology>")?;
+
+    let mut builder = ManifestBuilder::default();
+    // Members of each network keyed by network id, as (node id, interface id).
+    let mut members: BTreeMap<&str, Vec<(&str, u8)>> = BTreeMap::new();
+
+    for node in children(topology, "nodes").flat_map(|nodes| children(nodes, "node")) {
+        let Some(id) = node.attribute("id") else {
+            continue;
+        };
+        let name = node.attribute("name").unwrap_or(id);
+        let template = node.attribute("template").unwrap_or_default();
+        let image = node.attribute("image").unwrap_or_default();
+        let node_type = node.attribute("type").unwrap_or_default();
+
+        for interface in children(node, "interface") {
+            let network_id = interface.attribute("network_id");
+            let index = interface
+                .attribute("id")
+                .and_then(|id| id.parse::<u8>().ok());
+            if let (Some(network_id), Some(index)) = (network_id, index)
+                && network_id != "0"
+            {
+                members.entry(network_id).or_default().push((id, index));
+            }
+        }
+
+        // Generic templates (e.g. `linux`) only reveal the model in the image.
+        let (model, version) = if let Some(model) = model_from_keyword(template) {
+            (model, image_version(template, image))
+        } else if let Some(model) = model_from_keyword(image) {
+            (model, None)
+        } else {
+            builder.warn(format!(
+                "Node '{name}' template '{template}' is not supported, skipped"
+            ));
+            continue;
+        };
+
+        let version = match node_type {
+            "docker" => split_image_tag(image)
+                .1
+                .filter(|tag| *tag != "latest")
+                .map(str::to_string),
+            _ => version,
+        };
+        if version.is_none() {
+            builder.warn(format!(
+                "Node '{name}' version unknown, the default version will be used"
+            ));
+        }
+
+        builder.add_node(id, name, model, version);
+    }
+
+    for network in children(topology, "networks").flat_map(|nets| children(nets, "network")) {
+        let Some(id) = network.attribute("id") else {
+            continue;
+        };
+        let name = network.attribute("name").unwrap_or(id);
+        let network_type = network.attribute("type").unwrap_or("bridge");
+        let Some(network_members) = members.remove(id) else {
+            continue;
+        };
+
+        if network_type.starts_with("pnet") || network_type.starts_with("cloud") {
+            builder.warn(format!(
+                "Network '{name}' ({network_type}) connects to the host, skipped"
+            ));
+            continue;
+        }
+
+        let mut endpoints = vec![];
+        for (node_id, index) in network_members {
+            if !builder.has_node(node_id) {
+                builder.warn(format!(
+                    "Network '{name}' references node '{node_id}' which cannot be mapped"
+                ));
+                continue;
+            }
+            if let Some(endpoint) = builder.endpoint_by_index(node_id, index) {
+                endpoints.push(endpoint);
+            }
+        }
+
+        match endpoints.len() {
+            0 | 1 => builder.warn(format!(
+                "Network '{name}' has fewer than two mappable members, skipped"
+            )),
+            2 => {
+                let dst = endpoints.pop().unwrap_or_default();
+                let src = endpoints.pop().unwrap_or_default();
+                builder.add_link(src, dst);
+            }
+            _ => builder.add_bridge(name, endpoints),
+        }
+    }
+
+    Ok(builder.finish(lab_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::NodeModel;
+
+    const LAB: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<lab name="Core Lab" id="0d6c2a1e" version="1" scripttimeout="300" lock="0">
+  <topology>
+    <nodes>
+      <node id="1" name="R1" type="qemu" template="vios" image="vios-adventerprisek9-m.SPA.159-3.M6">
+        <interface id="1" name="Gi0/1" type="ethernet" network_id="1"/>
+        <interface id="2" name="Gi0/2" type="ethernet" network_id="2"/>
+      </node>
+      <node id="2" name="SW1" type="qemu" template="veos" image="veos-4.29.2F">
+        <interface id="1" name="Eth1" type="ethernet" network_id="1"/>
+        <interface id="2" name="Eth2" type="ethernet" network_id="2"/>
+        <interface id="3" name="Eth3" type="ethernet" network_id="3"/>
+      </node>
+      <node id="3" name="Linux" type="qemu" template="linux" image="linux-ubuntu-server-24.04">
+        <interface id="1" name="e1" type="ethernet" network_id="2"/>
+      </node>
+      <node id="4" name="FGT" type="qemu" template="fortinet" image="fortinet-FGT-v7.4">
+        <interface id="0" name="port1" type="ethernet" network_id="0"/>
+      </node>
+    </nodes>
+    <networks>
+      <network id="1" type="bridge" name="R1-SW1"/>
+      <network id="2" type="bridge" name="LAN"/>
+      <network id="3" type="pnet0" name="Internet"/>
+    </networks>
+  </topology>
+</lab>"#;
+
+    #[test]
+    fn test_convert_eveng_nodes() {
+        let conversion = convert(LAB).expect("converts");
+        let manifest = &conversion.manifest;
+        assert_eq!(manifest.name, "Core-Lab");
+        assert_eq!(manifest.nodes.len(), 3);
+        assert_eq!(manifest.nodes[0].model, NodeModel::CiscoIosv);
+        assert_eq!(manifest.nodes[1].model, NodeModel::AristaVeos);
+        assert_eq!(manifest.nodes[1].version.as_deref(), Some("4.29.2F"));
+        assert_eq!(manifest.nodes[2].model, NodeModel::UbuntuLinux);
+    }
+
+    #[test]
+    fn test_convert_eveng_links_and_bridges() {
+        let conversion = convert(LAB).expect("converts");
+        let links = conversion.manifest.links.expect("links");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].src, "R1::gig0/1");
+        assert_eq!(links[0].dst, "SW1::eth1");
+
+        let bridges = conversion.manifest.bridges.expect("bridges");
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].name, "LAN");
+        assert_eq!(
+            bridges[0].links,
+            vec!["R1::gig0/2", "SW1::eth2", "Linux::eth1"]
+        );
+    }
+
+    #[test]
+    fn test_convert_eveng_warns_on_unmapped_items() {
+        let conversion = convert(LAB).expect("converts");
+        let warnings = conversion.warnings.join("\n");
+        assert!(warnings.contains("template 'fortinet'"));
+        assert!(warnings.contains("'Internet' (pnet0)"));
+    }
+
+    #[test]
+    fn test_image_version() {
+        assert_eq!(
+            image_version("veos", "veos-4.29.2F").as_deref(),
+            Some("4.29.2F")
+        );
+        assert_eq!(image_version("vios", "vios"), None);
+        assert_eq!(image_version("linux", "ubuntu-24.04"), None);
+    }
+}
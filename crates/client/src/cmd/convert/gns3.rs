@@ -0,0 +1,289 @@
+//! GNS3 project (`.gns3`) topology conversion.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde_derive::Deserialize;
+
+use shared::data::NodeModel;
+
+use super::{Conversion, ManifestBuilder, model_from_keyword, split_image_tag};
+
+#[derive(Debug, Deserialize)]
+struct Gns3Project {
+    name: String,
+    topology: Gns3Topology,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Gns3Topology {
+    nodes: Vec<Gns3Node>,
+    links: Vec<Gns3Link>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Gns3Node {
+    node_id: String,
+    name: String,
+    node_type: String,
+    #[serde(default)]
+    properties: Gns3NodeProperties,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Gns3NodeProperties {
+    /// Docker image or IOU/Dynamips image file.
+    image: Option<String>,
+    /// QEMU primary disk image.
+    hda_disk_image: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Gns3Link {
+    nodes: Vec<Gns3LinkEndpoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Gns3LinkEndpoint {
+    node_id: String,
+    adapter_number: u8,
+    port_number: u8,
+}
+
+/// Translate a GNS3 adapter/port pair into an interface index.
+///
+/// IOU devices expose four ports per adapter; all other node types use one
+/// adapter per interface.
+fn interface_index(node_type: &str, endpoint: &Gns3LinkEndpoint) -> Option<u8> {
+    match node_type {
+        "iou" => endpoint
+            .adapter_number
+            .checked_mul(4)?
+            .checked_add(endpoint.port_number),
+        _ => endpoint.adapter_number.checked_add(endpoint.port_number),
+    }
+}
+
+pub(super) fn convert(contents: &str) -> Result<Conversion> {
+    let project: Gns3Project = serde_json::from_str(contents).context("Invalid GNS3 project")?;
+    let topology = project.topology;
+
+    let mut builder = ManifestBuilder::default();
+    let mut node_types: BTreeMap<&str, &str> = BTreeMap::new();
+    // Switches and hubs keyed by node id, holding (name, members).
+    let mut bridges: BTreeMap<&str, (&str, Vec<String>)> = BTreeMap::new();
+
+    for node in &topology.nodes {
+        node_types.insert(&node.node_id, &node.node_type);
+        match node.node_type.as_str() {
+            "ethernet_switch" | "ethernet_hub" => {
+                bridges.insert(&node.node_id, (&node.name, vec![]));
+                continue;
+            }
+            "cloud" | "nat" => {
+                builder.warn(format!(
+                    "Node '{}' ({}) has no Sherpa equivalent, skipped",
+                    node.name, node.node_type
+                ));
+                continue;
+            }
+            "vpcs" => {
+                builder.warn(format!(
+                    "VPCS node '{}' replaced with alpine_linux",
+                    node.name
+                ));
+                builder.add_node(&node.node_id, &node.name, NodeModel::AlpineLinux, None);
+                continue;
+            }
+            _ => {}
+        }
+
+        let image = node
+            .properties
+            .image
+            .as_deref()
+            .or(node.properties.hda_disk_image.as_deref());
+        let Some(model) = image.and_then(model_from_keyword) else {
+            builder.warn(format!(
+                "Node '{}' ({}) image {:?} not recognised, skipped",
+                node.name,
+                node.node_type,
+                image.unwrap_or("<none>")
+            ));
+            continue;
+        };
+
+        // Only container images carry a usable version; disk image file
+        // names are too irregular to parse reliably.
+        let version = match node.node_type.as_str() {
+            "docker" => image
+                .and_then(|image| split_image_tag(image).1)
+                .filter(|tag| *tag != "latest")
+                .map(str::to_string),
+            _ => None,
+        };
+        if version.is_none() {
+            builder.warn(format!(
+                "Node '{}' version unknown, the default version will be used",
+                node.name
+            ));
+        }
+
+        builder.add_node(&node.node_id, &node.name, model, version);
+    }
+
+    for link in &topology.links {
+        let [a, b] = &link.nodes[..] else {
+            builder.warn("Link does not have two endpoints, skipped");
+            continue;
+        };
+
+        let mut endpoints = vec![];
+        let mut bridge_id = None;
+        for endpoint in [a, b] {
+            if bridges.contains_key(endpoint.node_id.as_str()) {
+                bridge_id = Some(endpoint.node_id.as_str());
+                continue;
+            }
+            if !builder.has_node(&endpoint.node_id) {
+                builder.warn(format!(
+                    "Link references node '{}' which cannot be mapped, skipped",
+                    endpoint.node_id
+                ));
+                continue;
+            }
+            let node_type = node_types
+                .get(endpoint.node_id.as_str())
+                .copied()
+                .unwrap_or_default();
+            let Some(index) = interface_index(node_type, endpoint) else {
+                continue;
+            };
+            if let Some(mapped) = builder.endpoint_by_index(&endpoint.node_id, index) {
+                endpoints.push(mapped);
+            }
+        }
+
+        match (bridge_id, &endpoints[..]) {
+            (Some(id), [member]) => {
+                if let Some((_, members)) = bridges.get_mut(id) {
+                    members.push(member.clone());
+                }
+            }
+            (None, [src, dst]) => builder.add_link(src.clone(), dst.clone()),
+            (Some(_), []) => {
+                builder.warn("Links between switches or hubs are not supported, skipped")
+            }
+            _ => {}
+        }
+    }
+
+    for (name, members) in bridges.into_values() {
+        builder.add_bridge(name, members);
+    }
+
+    Ok(builder.finish(&project.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = r#"{
+  "name": "campus lab",
+  "topology": {
+    "nodes": [
+      {
+        "node_id": "n1",
+        "name": "R1",
+        "node_type": "qemu",
+        "properties": { "hda_disk_image": "vios-adventerprisek9-m.vmdk.SPA.156-1.T" }
+      },
+      {
+        "node_id": "n2",
+        "name": "frr1",
+        "node_type": "docker",
+        "properties": { "image": "frrouting/frr:v8.4.1" }
+      },
+      { "node_id": "n3", "name": "Switch1", "node_type": "ethernet_switch" },
+      { "node_id": "n4", "name": "PC1", "node_type": "vpcs" },
+      { "node_id": "n5", "name": "Cloud1", "node_type": "cloud" }
+    ],
+    "links": [
+      {
+        "nodes": [
+          { "node_id": "n1", "adapter_number": 1, "port_number": 0 },
+          { "node_id": "n2", "adapter_number": 1, "port_number": 0 }
+        ]
+      },
+      {
+        "nodes": [
+          { "node_id": "n1", "adapter_number": 2, "port_number": 0 },
+          { "node_id": "n3", "adapter_number": 0, "port_number": 1 }
+        ]
+      },
+      {
+        "nodes": [
+          { "node_id": "n4", "adapter_number": 1, "port_number": 0 },
+          { "node_id": "n3", "adapter_number": 0, "port_number": 2 }
+        ]
+      },
+      {
+        "nodes": [
+          { "node_id": "n2", "adapter_number": 2, "port_number": 0 },
+          { "node_id": "n5", "adapter_number": 0, "port_number": 0 }
+        ]
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn test_convert_gns3_nodes() {
+        let conversion = convert(PROJECT).expect("converts");
+        let manifest = &conversion.manifest;
+        assert_eq!(manifest.name, "campus-lab");
+        assert_eq!(manifest.nodes.len(), 3);
+        assert_eq!(manifest.nodes[0].model, NodeModel::CiscoIosv);
+        assert_eq!(manifest.nodes[0].version, None);
+        assert_eq!(manifest.nodes[1].model, NodeModel::FrrLinux);
+        assert_eq!(manifest.nodes[1].version.as_deref(), Some("v8.4.1"));
+        assert_eq!(manifest.nodes[2].model, NodeModel::AlpineLinux);
+    }
+
+    #[test]
+    fn test_convert_gns3_links_and_bridges() {
+        let conversion = convert(PROJECT).expect("converts");
+        let links = conversion.manifest.links.expect("links");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].src, "R1::gig0/1");
+        assert_eq!(links[0].dst, "frr1::eth1");
+
+        let bridges = conversion.manifest.bridges.expect("bridges");
+        assert_eq!(bridges.len(), 1);
+        assert_eq!(bridges[0].name, "Switch1");
+        assert_eq!(bridges[0].links, vec!["R1::gig0/2", "PC1::eth1"]);
+    }
+
+    #[test]
+    fn test_convert_gns3_warns_on_cloud() {
+        let conversion = convert(PROJECT).expect("converts");
+        let warnings = conversion.warnings.join("\n");
+        assert!(warnings.contains("'Cloud1' (cloud)"));
+        assert!(warnings.contains("'n5' which cannot be mapped"));
+    }
+
+    #[test]
+    fn test_interface_index_iou() {
+        let endpoint = Gns3LinkEndpoint {
+            node_id: "n1".to_string(),
+            adapter_number: 1,
+            port_number: 2,
+        };
+        assert_eq!(interface_index("iou", &endpoint), Some(6));
+        assert_eq!(interface_index("qemu", &endpoint), Some(3));
+    }
+}
@@ -0,0 +1,440 @@
+//! Convert topologies from other lab tools into a Sherpa manifest.
+//!
+//! Supported sources:
+//! - containerlab (`.clab.yml`)
+//! - GNS3 (`.gns3`)
+//! - EVE-NG (`.unl`)
+//!
+//! Each source parser feeds a [`ManifestBuilder`], which maps node kinds and
+//! images to a [`NodeModel`], translates interface names to the Sherpa naming
+//! scheme for that model, and records a warning for anything it cannot map.
+
+mod clab;
+mod eveng;
+mod gns3;
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+
+use shared::data::NodeModel;
+use shared::util::{
+    Emoji, emoji_success, file_exists, interface_from_idx, interface_to_idx, load_file,
+    term_msg_underline,
+};
+use topology::{Bridge, Link2, Manifest, Node};
+
+/// Source topology formats supported by `sherpa convert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConvertFormat {
+    /// containerlab topology file (.clab.yml)
+    Clab,
+    /// GNS3 project file (.gns3)
+    Gns3,
+    /// EVE-NG lab file (.unl)
+    Eveng,
+}
+
+/// Result of converting a foreign topology.
+pub struct Conversion {
+    pub manifest: Manifest,
+    pub warnings: Vec<String>,
+}
+
+/// A node as known to the builder, keyed by its identifier in the source file.
+struct MappedNode {
+    name: String,
+    model: NodeModel,
+}
+
+/// Accumulates the nodes, links and bridges of a converted topology.
+///
+/// Source parsers refer to nodes by whatever identifier the source format uses
+/// (a name for containerlab, a UUID for GNS3, a numeric id for EVE-NG).
+#[derive(Default)]
+struct ManifestBuilder {
+    nodes: Vec<Node>,
+    lookup: HashMap<String, MappedNode>,
+    links: Vec<Link2>,
+    bridges: Vec<Bridge>,
+    warnings: Vec<String>,
+}
+
+impl ManifestBuilder {
+    fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Register a node. Names are sanitized to characters Sherpa accepts.
+    fn add_node(&mut self, key: &str, name: &str, model: NodeModel, version: Option<String>) {
+        let sanitized = sanitize_name(name);
+        if sanitized != name {
+            self.warn(format!("Node '{name}' renamed to '{sanitized}'"));
+        }
+        self.nodes.push(Node {
+            name: sanitized.clone(),
+            model,
+            version,
+            ..Default::default()
+        });
+        self.lookup.insert(
+            key.to_string(),
+            MappedNode {
+                name: sanitized,
+                model,
+            },
+        );
+    }
+
+    /// Whether a node with the given source identifier was registered.
+    fn has_node(&self, key: &str) -> bool {
+        self.lookup.contains_key(key)
+    }
+
+    /// Resolve a `node::interface` endpoint from a source interface name.
+    fn endpoint_by_name(&mut self, key: &str, interface: &str) -> Option<String> {
+        let node = self.lookup.get(key)?;
+        match map_interface_name(&node.model, interface) {
+            Some(mapped) => Some(format!("{}::{}", node.name, mapped)),
+            None => {
+                let message = format!(
+                    "Interface '{}' on node '{}' has no equivalent for model '{}'",
+                    interface, node.name, node.model
+                );
+                self.warn(message);
+                None
+            }
+        }
+    }
+
+    /// Resolve a `node::interface` endpoint from a zero-based interface index.
+    fn endpoint_by_index(&mut self, key: &str, index: u8) -> Option<String> {
+        let node = self.lookup.get(key)?;
+        match interface_from_idx(&node.model, index) {
+            Ok(mapped) => {
+                let endpoint = format!("{}::{}", node.name, mapped);
+                if index == 0 {
+                    self.warn(format!(
+                        "Endpoint '{endpoint}' is the management interface of '{}'",
+                        node.model
+                    ));
+                }
+                Some(endpoint)
+            }
+            Err(_) => {
+                let message = format!(
+                    "Interface index {} on node '{}' is out of range for model '{}'",
+                    index, node.name, node.model
+                );
+                self.warn(message);
+                None
+            }
+        }
+    }
+
+    fn add_link(&mut self, src: String, dst: String) {
+        self.links.push(Link2 {
+            src,
+            dst,
+            p2p: None,
+            impairment: None,
+            filter: None,
+            mtu: None,
+        });
+    }
+
+    fn add_bridge(&mut self, name: &str, links: Vec<String>) {
+        if links.is_empty() {
+            self.warn(format!("Bridge '{name}' has no mappable members, skipped"));
+            return;
+        }
+        self.bridges.push(Bridge {
+            name: sanitize_name(name),
+            links,
+            ports: vec![],
+        });
+    }
+
+    fn finish(self, name: &str) -> Conversion {
+        Conversion {
+            manifest: Manifest {
+                name: sanitize_name(name),
+                nodes: self.nodes,
+                links: (!self.links.is_empty()).then_some(self.links),
+                bridges: (!self.bridges.is_empty()).then_some(self.bridges),
+                ..Default::default()
+            },
+            warnings: self.warnings,
+        }
+    }
+}
+
+/// Replace characters that are not valid in Sherpa node, bridge or lab names.
+fn sanitize_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Map an interface name from a foreign tool to the Sherpa name for a model.
+///
+/// Names the model already understands are kept. Otherwise the trailing
+/// number is treated as the data interface index (`eth3`, `e1-3`,
+/// `ethernet-1/3` and `Gi0/3` all map to index 3).
+fn map_interface_name(model: &NodeModel, interface: &str) -> Option<String> {
+    if interface_to_idx(model, interface).is_ok() {
+        return Some(interface.to_string());
+    }
+    let digits: String = interface
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let index = digits.parse::<u8>().ok()?;
+    interface_from_idx(model, index).ok()
+}
+
+/// Split a container image reference into repository and tag.
+fn split_image_tag(image: &str) -> (&str, Option<&str>) {
+    // A ':' before the last '/' belongs to a registry port, not a tag.
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], Some(&image[name_start + i + 1..])),
+        None => (image, None),
+    }
+}
+
+/// Keywords up to this length only match at the start of a token, see
+/// [`keyword_matches`].
+const SHORT_KEYWORD_LEN: usize = 4;
+
+/// Guess a node model from an image, template or kind identifier.
+///
+/// Order matters: more specific keywords are checked before their prefixes
+/// (e.g. `viosl2` before `vios`).
+fn model_from_keyword(text: &str) -> Option<NodeModel> {
+    let text = text.to_lowercase().replace(['_', ' '], "-");
+    const KEYWORDS: &[(&str, NodeModel)] = &[
+        ("ceos", NodeModel::AristaCeos),
+        ("veos", NodeModel::AristaVeos),
+        ("aoscx", NodeModel::ArubaAoscx),
+        ("asav", NodeModel::CiscoAsav),
+        ("ftdv", NodeModel::CiscoFtdv),
+        ("csr1000v", NodeModel::CiscoCsr1000v),
+        ("c8000v", NodeModel::CiscoCat8000v),
+        ("cat8000v", NodeModel::CiscoCat8000v),
+        ("cat9kv", NodeModel::CiscoCat9000v),
+        ("cat9000v", NodeModel::CiscoCat9000v),
+        ("xrv9k", NodeModel::CiscoIosxrv9000),
+        ("nxosv9k", NodeModel::CiscoNexus9300v),
+        ("n9kv", NodeModel::CiscoNexus9300v),
+        ("nexus9300v", NodeModel::CiscoNexus9300v),
+        ("vios-l2", NodeModel::CiscoIosvl2),
+        ("viosl2", NodeModel::CiscoIosvl2),
+        ("vios", NodeModel::CiscoIosv),
+        ("cisco-ise", NodeModel::CiscoIse),
+        ("vjunos-router", NodeModel::JuniperVrouter),
+        ("vjunosrouter", NodeModel::JuniperVrouter),
+        ("vjunos-switch", NodeModel::JuniperVswitch),
+        ("vjunosswitch", NodeModel::JuniperVswitch),
+        ("vjunos-evolved", NodeModel::JuniperVevolved),
+        ("vjunosevolved", NodeModel::JuniperVevolved),
+        ("vsrxng", NodeModel::JuniperVsrxv3),
+        ("vsrx", NodeModel::JuniperVsrxv3),
+        ("srlinux", NodeModel::NokiaSrlinux),
+        ("srl", NodeModel::NokiaSrlinux),
+        ("panos", NodeModel::PaloaltoPanos),
+        ("paloalto", NodeModel::PaloaltoPanos),
+        ("pa-vm", NodeModel::PaloaltoPanos),
+        ("routeros", NodeModel::MikrotikChr),
+        ("mikrotik", NodeModel::MikrotikChr),
+        ("chr", NodeModel::MikrotikChr),
+        ("frrouting", NodeModel::FrrLinux),
+        ("frr", NodeModel::FrrLinux),
+        ("cumulus", NodeModel::CumulusLinux),
+        ("cvx", NodeModel::CumulusLinux),
+        ("sonic", NodeModel::SonicLinux),
+        ("almalinux", NodeModel::AlmaLinux),
+        ("alma", NodeModel::AlmaLinux),
+        ("rocky", NodeModel::RockyLinux),
+        ("alpine", NodeModel::AlpineLinux),
+        ("centos", NodeModel::CentosLinux),
+        ("fedora", NodeModel::FedoraLinux),
+        ("rhel", NodeModel::RedhatLinux),
+        ("opensuse", NodeModel::OpensuseLinux),
+        ("ubuntu", NodeModel::UbuntuLinux),
+        ("kali", NodeModel::KaliLinux),
+        ("flatcar", NodeModel::FlatcarLinux),
+        ("freebsd", NodeModel::FreeBsd),
+        ("openbsd", NodeModel::OpenBsd),
+        ("winserver", NodeModel::WindowsServer),
+        ("windows", NodeModel::WindowsServer),
+    ];
+    KEYWORDS
+        .iter()
+        .find(|(keyword, _)| keyword_matches(&text, keyword))
+        .map(|(_, model)| *model)
+}
+
+/// Whether a normalised identifier contains a model keyword.
+///
+/// Short keywords such as `chr` or `srl` would match inside unrelated
+/// words, so they must start a `-`, `/`, `:` or `.` separated token and
+/// not be followed by a letter: `mikrotik-chr` and `chr7` match, `chrome`
+/// does not.
+fn keyword_matches(text: &str, keyword: &str) -> bool {
+    if keyword.len() > SHORT_KEYWORD_LEN {
+        return text.contains(keyword);
+    }
+    text.split(['-', '/', ':', '.']).any(|token| {
+        token
+            .strip_prefix(keyword)
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_ascii_alphabetic()))
+    })
+}
+
+/// Parse a topology file in the given format.
+pub fn convert_topology(from: ConvertFormat, contents: &str) -> Result<Conversion> {
+    match from {
+        ConvertFormat::Clab => clab::convert(contents),
+        ConvertFormat::Gns3 => gns3::convert(contents),
+        ConvertFormat::Eveng => eveng::convert(contents),
+    }
+}
+
+/// Convert a foreign topology file and write it as a Sherpa manifest.
+pub fn convert(from: ConvertFormat, file: &str, output: &str, force: bool) -> Result<()> {
+    if file_exists(output) && !force {
+        println!("{} already exists. Use --force to overwrite.", output);
+        return Ok(());
+    }
+
+    let contents = load_file(file).context(format!("Failed to read '{}'", file))?;
+    let conversion =
+        convert_topology(from, &contents).context(format!("Failed to convert '{}'", file))?;
+
+    term_msg_underline(&format!("Converted: {}", file));
+    let manifest = &conversion.manifest;
+    println!("  Nodes:   {}", manifest.nodes.len());
+    println!("  Links:   {}", manifest.links.as_ref().map_or(0, Vec::len));
+    println!(
+        "  Bridges: {}",
+        manifest.bridges.as_ref().map_or(0, Vec::len)
+    );
+
+    if !conversion.warnings.is_empty() {
+        println!();
+        term_msg_underline(&format!(
+            "{} Warnings ({})",
+            Emoji::Warning,
+            conversion.warnings.len()
+        ));
+        for warning in &conversion.warnings {
+            println!("  - {}", warning);
+        }
+    }
+
+    manifest.write_file(output)?;
+    println!();
+    println!("{}", emoji_success(&format!("Created {}", output)));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_interface_name_keeps_native_names() {
+        assert_eq!(
+            map_interface_name(&NodeModel::NokiaSrlinux, "eth-1/3").as_deref(),
+            Some("eth-1/3")
+        );
+    }
+
+    #[test]
+    fn test_map_interface_name_uses_trailing_index() {
+        assert_eq!(
+            map_interface_name(&NodeModel::NokiaSrlinux, "e1-3").as_deref(),
+            Some("eth-1/3")
+        );
+        assert_eq!(
+            map_interface_name(&NodeModel::CiscoIosv, "Gi0/2").as_deref(),
+            Some("gig0/2")
+        );
+        assert_eq!(map_interface_name(&NodeModel::UbuntuLinux, "mgmt"), None);
+    }
+
+    #[test]
+    fn test_split_image_tag() {
+        assert_eq!(split_image_tag("ceos:4.30.1F"), ("ceos", Some("4.30.1F")));
+        assert_eq!(
+            split_image_tag("registry:5000/nokia/srlinux"),
+            ("registry:5000/nokia/srlinux", None)
+        );
+        assert_eq!(
+            split_image_tag("registry:5000/frr:9.1"),
+            ("registry:5000/frr", Some("9.1"))
+        );
+    }
+
+    #[test]
+    fn test_model_from_keyword_prefers_specific_matches() {
+        assert_eq!(
+            model_from_keyword("vios_l2-adventerprisek9"),
+            Some(NodeModel::CiscoIosvl2)
+        );
+        assert_eq!(
+            model_from_keyword("vios-adventerprisek9"),
+            Some(NodeModel::CiscoIosv)
+        );
+        assert_eq!(model_from_keyword("unknown-box"), None);
+    }
+
+    #[test]
+    fn test_model_from_keyword_anchors_short_keywords() {
+        assert_eq!(
+            model_from_keyword("mikrotik_chr"),
+            Some(NodeModel::MikrotikChr)
+        );
+        assert_eq!(model_from_keyword("chr-7.16"), Some(NodeModel::MikrotikChr));
+        assert_eq!(
+            model_from_keyword("quay.io/frrouting/frr:9.1"),
+            Some(NodeModel::FrrLinux)
+        );
+        assert_eq!(model_from_keyword("rhel9"), Some(NodeModel::RedhatLinux));
+        assert_eq!(
+            model_from_keyword("almalinux-9"),
+            Some(NodeModel::AlmaLinux)
+        );
+        assert_eq!(
+            model_from_keyword("vEOS64-lab-4.32"),
+            Some(NodeModel::AristaVeos)
+        );
+
+        // Short keywords inside unrelated names
+        assert_eq!(model_from_keyword("chromium-headless"), None);
+        assert_eq!(model_from_keyword("realmachine"), None);
+        assert_eq!(model_from_keyword("srlabs-tools"), None);
+        assert_eq!(model_from_keyword("scvx-server"), None);
+        assert_eq!(model_from_keyword("cvxopt"), None);
+    }
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("Core Switch 1"), "Core-Switch-1");
+        assert_eq!(sanitize_name("r1"), "r1");
+    }
+}
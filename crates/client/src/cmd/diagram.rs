@@ -0,0 +1,115 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+
+use super::manifest_processing::{
+    process_external_links, process_manifest_bridges, process_manifest_links,
+    process_manifest_nodes, process_mirror_ports,
+};
+use shared::util::emoji_success;
+use topology::{Diagram, Manifest};
+
+/// Output formats supported by `sherpa diagram`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagramFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Standalone SVG image
+    Svg,
+}
+
+/// Render the topology of a manifest in the requested format.
+pub fn render_diagram(manifest: &Manifest, format: DiagramFormat) -> Result<String> {
+    let nodes = process_manifest_nodes(&manifest.nodes);
+    let links = process_manifest_links(&manifest.links, &nodes)?;
+    let mut bridges = process_manifest_bridges(&manifest.bridges, &nodes, "diagram")?;
+    bridges.extend(process_external_links(
+        &manifest.links,
+        &nodes,
+        "diagram",
+        bridges.len(),
+    )?);
+    bridges.extend(process_mirror_ports(
+        &manifest.mirrors,
+        &nodes,
+        "diagram",
+        bridges.len(),
+    )?);
+    let diagram = Diagram::from_manifest(&manifest.name, &nodes, &links, &bridges);
+
+    Ok(match format {
+        DiagramFormat::Dot => diagram.to_dot(),
+        DiagramFormat::Mermaid => diagram.to_mermaid(),
+        DiagramFormat::Svg => diagram.to_svg(),
+    })
+}
+
+/// Render a manifest diagram to stdout or a file.
+pub fn diagram(manifest_path: &str, format: DiagramFormat, output: Option<&str>) -> Result<()> {
+    let manifest = Manifest::load_file(manifest_path)
+        .context(format!("Failed to load manifest from '{}'", manifest_path))?;
+    let rendered = render_diagram(&manifest, format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).context(format!("Failed to write '{}'", path))?;
+            println!("{}", emoji_success(&format!("Created {}", path)));
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+name = "diagram-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+  { name = "dev02", model = "ubuntu_linux" },
+  { name = "dev03", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "dev02::eth1", impairment = { delay = 20 } },
+]
+
+bridges = [
+  { name = "lan", links = ["dev01::eth2", "dev02::eth2", "dev03::eth1"] },
+]
+"#;
+
+    #[test]
+    fn test_render_diagram_dot() {
+        let manifest: Manifest = toml::from_str(MANIFEST).expect("parses manifest");
+        let dot = render_diagram(&manifest, DiagramFormat::Dot).expect("renders");
+        assert!(dot.contains("\"dev01\" -- \"dev02\""));
+        assert!(dot.contains("label=\"20ms\""));
+        assert!(dot.contains("\"dev03\" -- \"bridge:lan\" [taillabel=\"eth1\"];"));
+    }
+
+    #[test]
+    fn test_render_diagram_mermaid() {
+        let manifest: Manifest = toml::from_str(MANIFEST).expect("parses manifest");
+        let mermaid = render_diagram(&manifest, DiagramFormat::Mermaid).expect("renders");
+        assert!(mermaid.contains("n0 ---|\"eth1 - eth1<br/>20ms\"| n1"));
+        assert!(mermaid.contains("n2 ---|\"eth1\"| b0"));
+    }
+
+    #[test]
+    fn test_render_diagram_rejects_unknown_link_node() {
+        let mut manifest: Manifest = toml::from_str(MANIFEST).expect("parses manifest");
+        manifest.bridges = None;
+        if let Some(links) = manifest.links.as_mut() {
+            links[0].dst = "dev09::eth1".to_string();
+        }
+        assert!(render_diagram(&manifest, DiagramFormat::Svg).is_err());
+    }
+}
@@ -4,8 +4,8 @@ use std::time::Duration;
 use shared::data::{ClientConfig, InspectResponse};
 use shared::error::RpcErrorCode;
 use shared::util::{
-    Emoji, get_username, render_bridges_table, render_devices_table, render_lab_info_table,
-    render_links_table, term_msg_surround, term_msg_underline,
+    Emoji, get_username, render_bridges_table, render_cabling_table, render_devices_table,
+    render_lab_info_table, render_links_table, term_msg_surround, term_msg_underline,
 };
 
 use crate::token::load_token;
@@ -118,6 +118,13 @@ pub async fn inspect(
         println!("{}", table);
     }
 
+    // Display the neighbours of the latest cabling verification
+    if !inspect_data.cabling.is_empty() {
+        println!();
+        let table = render_cabling_table(&inspect_data.cabling);
+        println!("{}", table);
+    }
+
     // Display inactive devices
     if !inspect_data.inactive_devices.is_empty() {
         term_msg_underline("Inactive Nodes");
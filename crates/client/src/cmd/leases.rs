@@ -0,0 +1,27 @@
+use anyhow::{Context, Result};
+
+use shared::data::{ClientConfig, LabLeasesRequest, LabLeasesResponse};
+use shared::util::{render_leases_table, term_msg_surround};
+
+use super::server::rpc_call;
+
+/// List the DHCP leases of a lab and the nodes holding them.
+pub async fn leases(lab_id: &str, server_url: &str, config: &ClientConfig) -> Result<()> {
+    term_msg_surround(&format!("DHCP Leases - {lab_id}"));
+
+    let request = LabLeasesRequest {
+        lab_id: lab_id.to_string(),
+        token: String::new(),
+    };
+    let response: LabLeasesResponse =
+        rpc_call("lab.leases", request, server_url, &config.server_connection)
+            .await
+            .context("Failed to list lab leases")?;
+
+    if response.leases.is_empty() {
+        println!("No active DHCP leases");
+    } else {
+        println!("{}", render_leases_table(&response.leases));
+    }
+    Ok(())
+}
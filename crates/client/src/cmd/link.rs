@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use clap::Subcommand;
+
+use shared::data::{
+    ClientConfig, L2Match, LinkFilter, LinkFilterRequest, LinkFilterResponse, LinkMirrorRequest,
+    LinkMirrorResponse, LinkStatsRequest, LinkStatsResponse, MirrorDirection,
+};
+use shared::util::{emoji_success, render_link_stats_table, term_msg_surround};
+
+use super::server::rpc_call;
+
+/// ANSI sequence that clears the screen and moves the cursor home
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+#[derive(Debug, Subcommand)]
+pub enum LinkCommands {
+    /// Show packet, byte and drop counters of the lab's links
+    Stats {
+        /// Refresh until interrupted, showing rates between samples
+        #[arg(short, long, action = clap::ArgAction::SetTrue)]
+        watch: bool,
+        /// Seconds between samples in watch mode
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+    /// Copy the traffic of a link interface to a mirror port
+    Mirror {
+        #[command(subcommand)]
+        commands: MirrorCommands,
+    },
+    /// Drop layer 2 protocols, ethertypes or VLANs on a link
+    Filter {
+        #[command(subcommand)]
+        commands: FilterCommands,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FilterCommands {
+    /// Replace the filter of a link
+    Set {
+        /// Either end of the link, as node::interface
+        link: String,
+        /// Frames to drop: lldp, lacp, stp, cdp, dot1x, ethertype:<value> or vlan:<id>
+        #[arg(long)]
+        drop: Vec<L2Match>,
+        /// Only pass these frames, same values as --drop
+        #[arg(long)]
+        allow: Vec<L2Match>,
+    },
+    /// Remove the filter of a link
+    Clear {
+        /// Either end of the link, as node::interface
+        link: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MirrorCommands {
+    /// Start mirroring a link interface to a mirror port
+    Add {
+        /// Link interface to mirror, as node::interface
+        source: String,
+        /// Mirror port from the manifest mirrors, as node::interface
+        destination: String,
+        /// Traffic received by, sent by, or both directions of the source
+        #[arg(long, value_enum, default_value_t = MirrorDirection::Both)]
+        direction: MirrorDirection,
+    },
+    /// Stop mirroring a link interface to a mirror port
+    Remove {
+        /// Link interface being mirrored, as node::interface
+        source: String,
+        /// Mirror port from the manifest mirrors, as node::interface
+        destination: String,
+        /// Traffic received by, sent by, or both directions of the source
+        #[arg(long, value_enum, default_value_t = MirrorDirection::Both)]
+        direction: MirrorDirection,
+    },
+}
+
+async fn fetch_link_stats(
+    lab_id: &str,
+    server_url: &str,
+    config: &ClientConfig,
+) -> Result<LinkStatsResponse> {
+    let request = LinkStatsRequest {
+        lab_id: lab_id.to_string(),
+        token: String::new(),
+    };
+    rpc_call("link.stats", request, server_url, &config.server_connection)
+        .await
+        .context("Failed to read link stats")
+}
+
+/// Inspect, mirror and filter the links of a lab.
+pub async fn link(
+    command: &LinkCommands,
+    lab_id: &str,
+    server_url: &str,
+    config: &ClientConfig,
+) -> Result<()> {
+    match command {
+        LinkCommands::Stats { watch, interval } => {
+            if !watch {
+                term_msg_surround(&format!("Link Stats - {lab_id}"));
+                let response = fetch_link_stats(lab_id, server_url, config).await?;
+                print_link_stats(&response, None);
+                return Ok(());
+            }
+
+            if *interval == 0 {
+                bail!("--interval must be at least 1 second");
+            }
+
+            let mut ticker = tokio::time::interval(Duration::from_secs(*interval));
+            let mut previous: Option<LinkStatsResponse> = None;
+            loop {
+                ticker.tick().await;
+                let response = fetch_link_stats(lab_id, server_url, config).await?;
+                print!("{CLEAR_SCREEN}");
+                print_link_stats(&response, previous.as_ref());
+                println!("Refreshing every {interval}s, press Ctrl+C to stop");
+                previous = Some(response);
+            }
+        }
+        LinkCommands::Mirror { commands } => {
+            let (method, source, destination, direction) = match commands {
+                MirrorCommands::Add {
+                    source,
+                    destination,
+                    direction,
+                } => ("link.mirror_add", source, destination, direction),
+                MirrorCommands::Remove {
+                    source,
+                    destination,
+                    direction,
+                } => ("link.mirror_remove", source, destination, direction),
+            };
+            let request = LinkMirrorRequest {
+                lab_id: lab_id.to_string(),
+                source: source.clone(),
+                destination: destination.clone(),
+                direction: *direction,
+                token: String::new(),
+            };
+            let response: LinkMirrorResponse =
+                rpc_call(method, request, server_url, &config.server_connection)
+                    .await
+                    .context("Failed to update port mirror")?;
+            println!("{}", emoji_success(&response.message));
+            Ok(())
+        }
+        LinkCommands::Filter { commands } => {
+            let (link, filter) = match commands {
+                FilterCommands::Set { link, drop, allow } => {
+                    if drop.is_empty() && allow.is_empty() {
+                        bail!("Pass --drop or --allow, or use `filter clear` to remove the filter");
+                    }
+                    let filter = LinkFilter {
+                        drop: drop.clone(),
+                        allow: allow.clone(),
+                    };
+                    (link, filter)
+                }
+                FilterCommands::Clear { link } => (link, LinkFilter::default()),
+            };
+            let request = LinkFilterRequest {
+                lab_id: lab_id.to_string(),
+                link: link.clone(),
+                filter,
+                token: String::new(),
+            };
+            let response: LinkFilterResponse = rpc_call(
+                "link.update_filter",
+                request,
+                server_url,
+                &config.server_connection,
+            )
+            .await
+            .context("Failed to update link filter")?;
+            println!("{}", emoji_success(&response.message));
+            Ok(())
+        }
+    }
+}
+
+fn print_link_stats(response: &LinkStatsResponse, previous: Option<&LinkStatsResponse>) {
+    if response.links.is_empty() {
+        println!("Lab has no links");
+    } else {
+        println!("{}", render_link_stats_table(response, previous));
+    }
+}
@@ -1,13 +1,17 @@
 //! Login command - authenticate user and save JWT token.
 
 use anyhow::{Context, Result};
-use shared::data::{ClientConfig, LoginRequest, LoginResponse, ValidateResponse};
+use shared::data::{
+    ClientConfig, DeviceLoginPollRequest, DeviceLoginPollResponse, DeviceLoginStartResponse,
+    DeviceLoginStatus, LoginRequest, LoginResponse, ValidateResponse,
+};
 use shared::util::{emoji_error, emoji_success};
 use std::io::{self, Write};
 use std::time::Duration;
 use uuid::Uuid;
 
 use crate::token;
+use crate::ws_client::client::RpcClient;
 use crate::ws_client::{WebSocketClient, messages::RpcRequest};
 
 /// Execute the login command
@@ -81,7 +85,16 @@ pub async fn login(server_url: &str, insecure: bool, config: &ClientConfig) -> R
     let login_response: LoginResponse =
         serde_json::from_value(result).context("Failed to parse login response")?;
 
-    // Save token
+    save_login(&login_response)?;
+
+    // Close the WebSocket connection gracefully
+    rpc_client.close().await.ok();
+
+    Ok(())
+}
+
+/// Save the session token and print the login summary
+fn save_login(login_response: &LoginResponse) -> Result<()> {
     token::save_token(&login_response.token).context("Failed to save authentication token")?;
 
     println!("{}", emoji_success("Login successful!"));
@@ -97,7 +110,104 @@ pub async fn login(server_url: &str, insecure: bool, config: &ClientConfig) -> R
     let expires_in_days = expires_in_seconds / 86400;
     println!("   Token expires in: {} days", expires_in_days);
 
-    // Close the WebSocket connection gracefully
+    Ok(())
+}
+
+/// Send an unauthenticated RPC request and parse its result
+async fn call_unauthenticated<T: serde::de::DeserializeOwned>(
+    rpc_client: &mut RpcClient,
+    method: &str,
+    params: serde_json::Value,
+) -> Result<T> {
+    let response = rpc_client
+        .call(RpcRequest::new(method, params))
+        .await
+        .context(format!("{} request failed", method))?;
+
+    if let Some(error) = response.error {
+        match error.context {
+            Some(context) => anyhow::bail!("{}: {}", error.message, context),
+            None => anyhow::bail!("{}", error.message),
+        }
+    }
+
+    let result = response.result.context("No result in response")?;
+    serde_json::from_value(result).context(format!("Failed to parse {} response", method))
+}
+
+/// Execute the login command with single sign-on
+///
+/// Starts an OIDC device login on the server, asks the user to approve it
+/// in a browser, then polls until the login completes and saves the JWT
+/// token to ~/.sherpa/token
+pub async fn login_sso(server_url: &str, insecure: bool, config: &ClientConfig) -> Result<()> {
+    let mut server_connection = config.server_connection.clone();
+    if insecure {
+        server_connection.insecure = true;
+        eprintln!("WARNING: TLS certificate validation disabled (--insecure)");
+    }
+
+    let ws_client = WebSocketClient::new(
+        server_url.to_string(),
+        Duration::from_secs(10),
+        server_connection,
+    );
+    let mut rpc_client = ws_client
+        .connect()
+        .await
+        .context("Failed to connect to server")?;
+
+    let start: DeviceLoginStartResponse =
+        call_unauthenticated(&mut rpc_client, "auth.device_start", serde_json::json!({}))
+            .await
+            .context("Failed to start single sign-on")?;
+
+    println!("To sign in, open the following page in a browser:");
+    println!();
+    println!(
+        "   {}",
+        start
+            .verification_uri_complete
+            .as_deref()
+            .unwrap_or(&start.verification_uri)
+    );
+    println!();
+    println!("and enter the code: {}", start.user_code);
+    println!();
+    println!("Waiting for approval...");
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(start.expires_in);
+    let mut interval = Duration::from_secs(start.interval);
+    let params = serde_json::to_value(DeviceLoginPollRequest {
+        device_code: start.device_code,
+    })
+    .context("Failed to serialize device login request")?;
+
+    let login_response = loop {
+        if std::time::Instant::now() >= deadline {
+            anyhow::bail!("Single sign-on timed out, please try again");
+        }
+        tokio::time::sleep(interval).await;
+
+        let poll: DeviceLoginPollResponse =
+            call_unauthenticated(&mut rpc_client, "auth.device_poll", params.clone())
+                .await
+                .context("Single sign-on failed")?;
+
+        match poll.status {
+            DeviceLoginStatus::Pending => {}
+            // RFC 8628: increase the interval by 5 seconds
+            DeviceLoginStatus::SlowDown => interval += Duration::from_secs(5),
+            DeviceLoginStatus::Complete => {
+                break poll
+                    .login
+                    .context("Server completed the login without a token")?;
+            }
+        }
+    };
+
+    save_login(&login_response)?;
+
     rpc_client.close().await.ok();
 
     Ok(())
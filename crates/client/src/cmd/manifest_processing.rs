@@ -1,8 +1,8 @@
 use anyhow::{Result, anyhow};
 
 use shared::data;
-use shared::konst::BRIDGE_PREFIX;
-use shared::util;
+use shared::konst::{BRIDGE_PREFIX, EXTERNAL_HOST_NODE, MIRROR_PORT_PREFIX};
+use shared::util::split_node_int;
 
 /// Process manifest nodes into expanded format with indices assigned
 pub fn process_manifest_nodes(manifest_nodes: &[topology::Node]) -> Vec<topology::NodeExpanded> {
@@ -12,6 +12,8 @@ pub fn process_manifest_nodes(manifest_nodes: &[topology::Node]) -> Vec<topology
         .map(|(idx, node)| topology::NodeExpanded {
             name: node.name.clone(),
             model: node.model,
+            // Custom model definitions live on the server
+            custom_model: None,
             // Node indexes start from 1. This aligns with IP address assignment
             index: idx as u16 + 1,
             version: node.version.clone(),
@@ -39,6 +41,8 @@ pub fn process_manifest_nodes(manifest_nodes: &[topology::Node]) -> Vec<topology
             user_scripts: node.user_scripts_data.clone(),
             kernel_cmdline: node.kernel_cmdline.clone(),
             ready_port: node.ready_port,
+            mtu: node.mtu,
+            interface_mtus: vec![],
         })
         .collect()
 }
@@ -52,17 +56,24 @@ pub fn process_manifest_links(
     // links from manifest links
     let links = manifest_links
         .iter()
+        .filter(|x| !x.is_external())
         .map(|x: &topology::Link2| x.expand())
         .collect::<Result<Vec<topology::LinkExpanded>>>()?;
 
     let mut links_detailed = vec![];
     for (link_idx, link) in links.iter().enumerate() {
-        let mut this_link = topology::LinkDetailed::default();
+        let mut this_link = topology::LinkDetailed {
+            p2p: link.p2p,
+            impairment: link.impairment.clone(),
+            filter: link.filter.clone(),
+            mtu: link.mtu,
+            ..Default::default()
+        };
         for device in manifest_nodes.iter() {
             let device_model = device.model;
             // let device_index = manifest_nodes.iter().map()
             if link.node_a == device.name {
-                let int_idx = util::interface_to_idx(&device_model, &link.int_a)?;
+                let int_idx = device.interface_to_idx(&link.int_a)?;
                 let peer_node = manifest_nodes
                     .iter()
                     .find(|n| n.name == link.node_b)
@@ -79,7 +90,7 @@ pub fn process_manifest_links(
                     .iter()
                     .find(|n| n.name == link.node_a)
                     .ok_or_else(|| anyhow!("Peer node not found: {}", link.node_a))?;
-                let int_idx = util::interface_to_idx(&device_model, &link.int_b)?;
+                let int_idx = device.interface_to_idx(&link.int_b)?;
                 this_link.node_b = device.name.clone();
                 this_link.node_b_idx = device.index;
                 this_link.node_b_model = device_model;
@@ -128,7 +139,7 @@ pub fn process_manifest_bridges(
         let mut bridge_links = vec![];
         for link in bridge.links.iter() {
             if let Some(node) = manifest_nodes.iter().find(|n| n.name == link.node) {
-                let interface_idx = util::interface_to_idx(&node.model, &link.interface)?;
+                let interface_idx = node.interface_to_idx(&link.interface)?;
                 bridge_links.push(topology::BridgeLinkDetailed {
                     node_name: link.node.clone(),
                     node_model: node.model,
@@ -144,12 +155,102 @@ pub fn process_manifest_bridges(
             libvirt_name,
             index: bridge_index,
             links: bridge_links,
+            external_interface: None,
         });
     }
 
     Ok(bridges_detailed)
 }
 
+/// Process manifest links to host interfaces into single-node bridges
+///
+/// Each external link gets its own lab bridge with the host interface
+/// enslaved, numbered after the manifest bridges.
+pub fn process_external_links(
+    manifest_links: &Option<Vec<topology::Link2>>,
+    manifest_nodes: &[topology::NodeExpanded],
+    lab_id: &str,
+    first_index: usize,
+) -> Result<Vec<topology::BridgeDetailed>> {
+    let mut bridges_detailed = vec![];
+    for (offset, link) in manifest_links
+        .iter()
+        .flatten()
+        .filter(|x| x.is_external())
+        .enumerate()
+    {
+        let external = link.expand_external()?;
+        let node = manifest_nodes
+            .iter()
+            .find(|n| n.name == external.node)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Manifest link - '{}' defined in links, not defined in devices",
+                    external.node
+                )
+            })?;
+        let bridge_index = first_index + offset;
+        let name = format!("{}-{}", EXTERNAL_HOST_NODE, external.host_interface);
+
+        bridges_detailed.push(topology::BridgeDetailed {
+            libvirt_name: format!("sherpa-bridge{}-{}-{}", bridge_index, name, lab_id),
+            bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_index, lab_id),
+            manifest_name: name,
+            index: bridge_index as u16,
+            links: vec![topology::BridgeLinkDetailed {
+                node_name: node.name.clone(),
+                node_model: node.model,
+                interface_index: node.interface_to_idx(&external.interface)?,
+                interface_name: external.interface,
+            }],
+            external_interface: Some(external.host_interface),
+        });
+    }
+    Ok(bridges_detailed)
+}
+
+/// Process manifest mirror destinations into single-node bridges
+///
+/// Each distinct destination is a mirror port with its own lab bridge,
+/// numbered after the manifest bridges and external links.
+pub fn process_mirror_ports(
+    manifest_mirrors: &Option<Vec<topology::Mirror>>,
+    manifest_nodes: &[topology::NodeExpanded],
+    lab_id: &str,
+    first_index: usize,
+) -> Result<Vec<topology::BridgeDetailed>> {
+    let mut bridges_detailed = vec![];
+    for (offset, port) in topology::mirror_ports(manifest_mirrors).iter().enumerate() {
+        let (node_name, interface) = split_node_int(port)?;
+        let node = manifest_nodes
+            .iter()
+            .find(|n| n.name == node_name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Manifest mirror - '{}' defined in mirrors, not defined in devices",
+                    node_name
+                )
+            })?;
+        let bridge_index = first_index + offset;
+        let name = format!("{}-{}-{}", MIRROR_PORT_PREFIX, node.name, interface);
+
+        bridges_detailed.push(topology::BridgeDetailed {
+            libvirt_name: format!("sherpa-bridge{}-{}-{}", bridge_index, name, lab_id),
+            bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_index, lab_id),
+            manifest_name: name,
+            index: bridge_index as u16,
+            links: vec![topology::BridgeLinkDetailed {
+                node_name: node.name.clone(),
+                node_model: node.model,
+                interface_index: node.interface_to_idx(&interface)?,
+                interface_name: interface,
+            }],
+            external_interface: None,
+        });
+    }
+    Ok(bridges_detailed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,6 +348,8 @@ mod tests {
             dst: "dev02::eth0".to_string(),
             p2p: None,
             impairment: None,
+            filter: None,
+            mtu: None,
         }]);
 
         let result = process_manifest_links(&links, &expanded_nodes).unwrap();
@@ -311,12 +414,16 @@ mod tests {
                 dst: "dev02::eth0".to_string(),
                 p2p: None,
                 impairment: None,
+                filter: None,
+                mtu: None,
             },
             topology::Link2 {
                 src: "dev02::eth1".to_string(),
                 dst: "dev03::eth0".to_string(),
                 p2p: None,
                 impairment: None,
+                filter: None,
+                mtu: None,
             },
         ]);
 
@@ -411,6 +518,7 @@ mod tests {
         let bridges = Some(vec![topology::Bridge {
             name: "mgmt".to_string(),
             links: vec!["dev01::eth0".to_string(), "dev02::eth0".to_string()],
+            ports: vec![],
         }]);
 
         let result = process_manifest_bridges(&bridges, &expanded_nodes, "abc123").unwrap();
@@ -442,6 +550,7 @@ mod tests {
         let bridges = Some(vec![topology::Bridge {
             name: "lan".to_string(),
             links: vec!["dev01::eth0".to_string()],
+            ports: vec![],
         }]);
 
         let result = process_manifest_bridges(&bridges, &expanded_nodes, "xyz").unwrap();
@@ -451,4 +560,99 @@ mod tests {
         assert_eq!(result[0].links[0].node_model, NodeModel::UbuntuLinux);
         assert_eq!(result[0].links[0].interface_name, "eth0");
     }
+
+    // ============================================================================
+    // process_external_links
+    // ============================================================================
+
+    #[test]
+    fn test_process_external_links_numbers_after_bridges() {
+        let nodes = vec![topology::Node {
+            name: "r1".to_string(),
+            model: NodeModel::UbuntuLinux,
+            ..Default::default()
+        }];
+        let expanded_nodes = process_manifest_nodes(&nodes);
+
+        let links = Some(vec![topology::Link2 {
+            src: "r1::eth3".to_string(),
+            dst: "host::enp5s0.120".to_string(),
+            p2p: None,
+            impairment: None,
+            filter: None,
+            mtu: None,
+        }]);
+
+        let result = process_external_links(&links, &expanded_nodes, "abc123", 2).unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].index, 2);
+        assert_eq!(result[0].manifest_name, "host-enp5s0.120");
+        assert_eq!(result[0].bridge_name, format!("{}s2-abc123", BRIDGE_PREFIX));
+        assert_eq!(result[0].external_interface.as_deref(), Some("enp5s0.120"));
+        assert_eq!(result[0].links[0].node_name, "r1");
+        assert_eq!(result[0].links[0].interface_name, "eth3");
+    }
+
+    #[test]
+    fn test_process_external_links_rejects_unknown_node() {
+        let expanded_nodes = process_manifest_nodes(&[]);
+        let links = Some(vec![topology::Link2 {
+            src: "r1::eth3".to_string(),
+            dst: "host::enp5s0".to_string(),
+            p2p: None,
+            impairment: None,
+            filter: None,
+            mtu: None,
+        }]);
+
+        assert!(process_external_links(&links, &expanded_nodes, "abc123", 0).is_err());
+    }
+
+    // ============================================================================
+    // process_mirror_ports
+    // ============================================================================
+
+    #[test]
+    fn test_process_mirror_ports_one_bridge_per_destination() {
+        let nodes = vec![topology::Node {
+            name: "ids01".to_string(),
+            model: NodeModel::UbuntuLinux,
+            ..Default::default()
+        }];
+        let expanded_nodes = process_manifest_nodes(&nodes);
+
+        let mirror = |source: &str, destination: &str| topology::Mirror {
+            source: source.to_string(),
+            direction: data::MirrorDirection::Both,
+            destination: destination.to_string(),
+        };
+        let mirrors = Some(vec![
+            mirror("r1::eth1", "ids01::eth1"),
+            mirror("r2::eth1", "ids01::eth1"),
+            mirror("r2::eth2", "ids01::eth2"),
+        ]);
+
+        let result = process_mirror_ports(&mirrors, &expanded_nodes, "abc123", 3).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].index, 3);
+        assert_eq!(result[0].manifest_name, "mirror-ids01-eth1");
+        assert_eq!(result[0].bridge_name, format!("{}s3-abc123", BRIDGE_PREFIX));
+        assert_eq!(result[0].external_interface, None);
+        assert_eq!(result[1].index, 4);
+        assert_eq!(result[1].links[0].interface_name, "eth2");
+    }
+
+    #[test]
+    fn test_process_mirror_ports_rejects_unknown_node() {
+        let expanded_nodes = process_manifest_nodes(&[]);
+        let mirrors = Some(vec![topology::Mirror {
+            source: "r1::eth1".to_string(),
+            direction: data::MirrorDirection::Both,
+            destination: "ids01::eth1".to_string(),
+        }]);
+
+        assert!(process_mirror_ports(&mirrors, &expanded_nodes, "abc123", 0).is_err());
+    }
 }
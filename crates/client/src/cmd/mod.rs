@@ -1,21 +1,29 @@
 mod cert;
 mod cli;
 mod console;
+mod convert;
 mod destroy;
+mod diagram;
 mod down;
 mod download;
 mod image;
 mod init;
 mod inspect;
+mod leases;
+mod link;
 mod login;
 mod manifest_processing;
 mod new;
+mod node;
 mod redeploy;
 mod resume;
 pub mod server;
+mod share;
 mod ssh;
 mod ssh_config;
+mod token;
 mod up;
 mod validate;
+mod verify;
 
 pub use cli::Cli;
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{
+    ClientConfig, NodeCommitRequest, NodeCommitResponse, StatusKind, StatusMessage,
+};
+use shared::util::{Emoji, emoji_success, term_msg_surround};
+
+use super::server::rpc_call_streaming;
+
+#[derive(Debug, Subcommand)]
+pub enum NodeCommands {
+    /// Capture a node as a new image version (admin)
+    Commit {
+        /// Node to capture
+        name: String,
+        /// Version to register the image as
+        #[arg(long)]
+        as_version: String,
+        /// Set the new image as the default version
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        default: bool,
+    },
+}
+
+/// Run a node command against the lab.
+pub async fn node(
+    command: &NodeCommands,
+    lab_id: &str,
+    server_url: &str,
+    config: &ClientConfig,
+) -> Result<()> {
+    match command {
+        NodeCommands::Commit {
+            name,
+            as_version,
+            default,
+        } => {
+            let request = NodeCommitRequest {
+                lab_id: lab_id.to_string(),
+                node_name: name.clone(),
+                version: as_version.clone(),
+                default: *default,
+            };
+            commit(request, server_url, config).await
+        }
+    }
+}
+
+async fn commit(request: NodeCommitRequest, server_url: &str, config: &ClientConfig) -> Result<()> {
+    term_msg_surround(&format!(
+        "Commit node '{}' as version {}",
+        request.node_name, request.version
+    ));
+    println!();
+
+    let response: NodeCommitResponse = rpc_call_streaming(
+        "node.commit",
+        request,
+        server_url,
+        &config.server_connection,
+        |msg_text| {
+            if let Ok(status_msg) = serde_json::from_str::<StatusMessage>(msg_text)
+                && status_msg.r#type == "status"
+            {
+                let emoji = match status_msg.kind {
+                    StatusKind::Progress => Emoji::Progress,
+                    StatusKind::Done => Emoji::Success,
+                    StatusKind::Info => Emoji::Info,
+                    StatusKind::Waiting => Emoji::Hourglass,
+                };
+                println!("{} {}", emoji, status_msg.message);
+            }
+        },
+    )
+    .await
+    .context("Failed to commit node")?;
+
+    println!(
+        "\n{}",
+        emoji_success(&format!(
+            "Node '{}' committed in {}s",
+            response.node_name, response.total_time_secs
+        ))
+    );
+    println!("   Model:   {}", response.model);
+    println!("   Kind:    {}", response.kind);
+    println!("   Version: {}", response.version);
+    println!("   Image:   {}", response.image);
+    if let Some(digest) = &response.image_sha256 {
+        println!("   SHA-256: {}", digest);
+    }
+    println!(
+        "   Default: {}",
+        if response.default { "yes" } else { "no" }
+    );
+
+    Ok(())
+}
@@ -1,20 +1,36 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+
 use anyhow::{Context, Result, bail};
+use base64::Engine;
 use clap::Subcommand;
+use sha2::{Digest, Sha256};
 
 use shared::data::{
     self, NodeConfig, NodeKind, NodeModel, ServerConnection, StatusKind, StatusMessage,
 };
 use shared::konst::{
     CONTAINER_FRR_REPO, CONTAINER_GITLAB_CE_REPO, CONTAINER_HASHICORP_VAULT_REPO,
-    CONTAINER_MONGO_DB_REPO, CONTAINER_NOKIA_SRLINUX_REPO,
+    CONTAINER_MONGO_DB_REPO, CONTAINER_NOKIA_SRLINUX_REPO, IMAGE_UPLOAD_CHUNK_SIZE,
 };
 use shared::util::{
-    Emoji, emoji_success, render_image_detail_table, render_images_table,
-    render_scanned_images_table,
+    Emoji, emoji_error, emoji_success, emoji_warning, file_sha256, format_bytes,
+    render_custom_models_table, render_image_detail_table, render_image_usage_table,
+    render_images_table, render_orphaned_disks_table, render_scanned_images_table,
 };
 
 use super::OutputFormat;
-use super::{rpc_call, rpc_call_streaming};
+use super::{authenticated_params, rpc_call, rpc_call_streaming, rpc_connect, rpc_result};
+use crate::ws_client::RpcRequest;
+use crate::ws_client::client::RpcClient;
+
+/// Times an upload is resumed after a dropped connection without making progress
+const UPLOAD_MAX_RETRIES: u32 = 5;
+
+/// Bytes per MiB, the unit of `--chunk-size`
+const MIB: u64 = 1024 * 1024;
 
 #[derive(Debug, Subcommand)]
 pub enum ServerImageCommands {
@@ -60,9 +76,9 @@ pub enum ServerImageCommands {
         dry_run: bool,
     },
 
-    /// Import a disk image
+    /// Import a disk image, converting vmdk/vhdx/OVA/tar images to qcow2
     Import {
-        /// Source path of the disk image
+        /// Source path of the disk image or archive on the server
         #[arg(short, long)]
         src: String,
         /// Version of the device model
@@ -74,6 +90,55 @@ pub enum ServerImageCommands {
         /// Set this image as the default version
         #[arg(long, action = clap::ArgAction::SetTrue)]
         default: bool,
+        /// Expected digest of the source, e.g. sha256:<hex> or md5:<hex>
+        #[arg(long)]
+        checksum: Option<String>,
+        /// Compress the converted qcow2 image
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        compress: bool,
+        /// Sparsify the image after conversion (requires virt-sparsify on the server)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        sparsify: bool,
+    },
+
+    /// Upload a local image file to the server and import it
+    ///
+    /// The file is sent in checksummed chunks. An interrupted upload resumes
+    /// from where it left off when the command is run again.
+    Upload {
+        /// Path of the disk image or archive to upload
+        file: String,
+        /// Model of Device
+        #[arg(short, long, value_enum)]
+        model: NodeModel,
+        /// Version of the device model
+        #[arg(short, long)]
+        version: String,
+        /// Set this image as the default version
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        default: bool,
+        /// Compress the converted qcow2 image
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        compress: bool,
+        /// Sparsify the image after conversion (requires virt-sparsify on the server)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        sparsify: bool,
+        /// Chunk size in MiB
+        #[arg(long, default_value_t = IMAGE_UPLOAD_CHUNK_SIZE / MIB)]
+        chunk_size: u64,
+        /// Cancel an interrupted upload of this file and delete its partial data
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        cancel: bool,
+    },
+
+    /// Verify an image against the digest recorded on import
+    Verify {
+        /// Model of the device image to verify
+        #[arg(short, long, value_enum)]
+        model: NodeModel,
+        /// Version of the device image to verify
+        #[arg(short, long)]
+        version: String,
     },
 
     /// Pull a container or VM image
@@ -87,12 +152,17 @@ pub enum ServerImageCommands {
         /// Full container image reference (e.g., registry.io/account/image:version)
         #[arg(short, long)]
         repo: Option<String>,
-        /// VM image download URL
+        /// VM image download URL, or an OCI reference for VM and unikernel
+        /// images (oci://registry/repository:tag or ...@sha256:digest)
         #[arg(short, long)]
         url: Option<String>,
         /// Set this image as the default version
         #[arg(long, action = clap::ArgAction::SetTrue)]
         default: bool,
+        /// Registry username for an oci:// URL. The password is read from
+        /// SHERPA_REGISTRY_PASSWORD or prompted for.
+        #[arg(long)]
+        username: Option<String>,
     },
 
     /// Delete an imported image from disk and database
@@ -114,6 +184,48 @@ pub enum ServerImageCommands {
         #[arg(short, long)]
         version: String,
     },
+
+    /// Report image storage usage and orphaned lab disks
+    Usage,
+
+    /// Remove image versions no node uses
+    ///
+    /// Default versions are never removed.
+    Prune {
+        /// Only remove versions unused for at least this long (e.g. 30d, 12h, 2w)
+        #[arg(long, value_parser = parse_age)]
+        unused_for: Option<u64>,
+        /// Show what would be removed without making changes
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        dry_run: bool,
+    },
+
+    /// Manage custom node models
+    Model {
+        #[command(subcommand)]
+        commands: ServerImageModelCommands,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ServerImageModelCommands {
+    /// Add a custom model from a TOML definition file
+    Add {
+        /// Path to the model definition file
+        file: String,
+        /// Replace an existing model with the same name
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        replace: bool,
+    },
+
+    /// List custom models
+    List,
+
+    /// Delete a custom model
+    Delete {
+        /// Name of the model (e.g. vyos or custom:vyos)
+        name: String,
+    },
 }
 
 pub async fn image_commands(
@@ -172,17 +284,68 @@ pub async fn image_commands(
             version,
             model,
             default,
+            checksum,
+            compress,
+            sparsify,
         } => {
-            import_image(
-                src,
-                version,
-                model,
-                *default,
-                server_url,
-                server_connection,
-                output_format,
-            )
-            .await
+            let request = data::ImportRequest {
+                model: *model,
+                version: version.clone(),
+                src: src.clone(),
+                default: *default,
+                checksum: checksum.clone(),
+                compress: *compress,
+                sparsify: *sparsify,
+            };
+            import_image(request, server_url, server_connection, output_format).await
+        }
+        ServerImageCommands::Upload {
+            file,
+            model,
+            version,
+            default,
+            compress,
+            sparsify,
+            chunk_size,
+            cancel,
+        } => {
+            let path = file.clone();
+            let sha256 = tokio::task::spawn_blocking(move || file_sha256(&path))
+                .await
+                .context("File digest task failed")??;
+            let filename = Path::new(file)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .context("Upload path has no file name")?;
+            let size = std::fs::metadata(file)
+                .with_context(|| format!("Failed to read {}", file))?
+                .len();
+            let request = data::StartUploadRequest {
+                model: *model,
+                version: version.clone(),
+                filename,
+                size,
+                sha256,
+                default: *default,
+                compress: *compress,
+                sparsify: *sparsify,
+            };
+            if *cancel {
+                cancel_upload(request, server_url, server_connection, output_format).await
+            } else {
+                upload_image(
+                    file,
+                    request,
+                    *chunk_size * MIB,
+                    server_url,
+                    server_connection,
+                    output_format,
+                )
+                .await
+            }
+        }
+        ServerImageCommands::Verify { model, version } => {
+            verify_image(model, version, server_url, server_connection, output_format).await
         }
         ServerImageCommands::Pull {
             model,
@@ -190,7 +353,9 @@ pub async fn image_commands(
             repo,
             url,
             default,
+            username,
         } => {
+            let registry_auth = username.as_deref().map(registry_auth).transpose()?;
             let config = NodeConfig::get_model(*model);
             match config.kind {
                 NodeKind::Container => {
@@ -209,19 +374,35 @@ pub async fn image_commands(
                     let version = version.as_deref().ok_or_else(|| {
                         anyhow::anyhow!("--version is required for VM image downloads")
                     })?;
-                    download_vm_image(
-                        model,
-                        version,
-                        url.as_deref(),
-                        *default,
-                        server_url,
-                        server_connection,
-                        output_format,
-                    )
-                    .await
+                    let request = data::DownloadImageRequest {
+                        model: *model,
+                        version: version.to_string(),
+                        url: url.clone(),
+                        default: *default,
+                        registry_auth,
+                    };
+                    download_vm_image(request, server_url, server_connection, output_format).await
                 }
                 NodeKind::Unikernel => {
-                    bail!("Image pull is not supported for unikernel models")
+                    let version = version.as_deref().ok_or_else(|| {
+                        anyhow::anyhow!("--version is required for unikernel image pulls")
+                    })?;
+                    let url = url
+                        .as_deref()
+                        .filter(|url| url.starts_with("oci://"))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "Unikernel images can only be pulled from an OCI registry, pass --url oci://..."
+                            )
+                        })?;
+                    let request = data::DownloadImageRequest {
+                        model: *model,
+                        version: version.to_string(),
+                        url: Some(url.to_string()),
+                        default: *default,
+                        registry_auth,
+                    };
+                    download_vm_image(request, server_url, server_connection, output_format).await
                 }
             }
         }
@@ -231,6 +412,30 @@ pub async fn image_commands(
         ServerImageCommands::SetDefault { model, version } => {
             set_default_image(model, version, server_url, server_connection, output_format).await
         }
+        ServerImageCommands::Usage => {
+            image_usage(server_url, server_connection, output_format).await
+        }
+        ServerImageCommands::Prune {
+            unused_for,
+            dry_run,
+        } => {
+            let request = data::PruneImagesRequest {
+                unused_for_secs: *unused_for,
+                dry_run: *dry_run,
+            };
+            prune_images(request, server_url, server_connection, output_format).await
+        }
+        ServerImageCommands::Model { commands } => match commands {
+            ServerImageModelCommands::Add { file, replace } => {
+                add_custom_model(file, *replace, server_url, server_connection, output_format).await
+            }
+            ServerImageModelCommands::List => {
+                list_custom_models(server_url, server_connection, output_format).await
+            }
+            ServerImageModelCommands::Delete { name } => {
+                delete_custom_model(name, server_url, server_connection, output_format).await
+            }
+        },
     }
 }
 
@@ -333,46 +538,49 @@ async fn scan_images(
 }
 
 async fn import_image(
-    src: &str,
-    version: &str,
-    model: &NodeModel,
-    default: bool,
+    request: data::ImportRequest,
     server_url: &str,
     server_connection: &ServerConnection,
     output_format: &OutputFormat,
 ) -> Result<()> {
-    let request = data::ImportRequest {
-        model: *model,
-        version: version.to_string(),
-        src: src.to_string(),
-        default,
-    };
+    let default = request.default;
 
     let response: data::ImportResponse = rpc_call_streaming(
         "image.import",
         request,
         server_url,
         server_connection,
-        |msg_text| {
-            if let Ok(status_msg) = serde_json::from_str::<StatusMessage>(msg_text)
-                && status_msg.r#type == "status"
-            {
-                let emoji = match status_msg.kind {
-                    StatusKind::Progress => Emoji::Progress,
-                    StatusKind::Done => Emoji::Success,
-                    StatusKind::Info => Emoji::Info,
-                    StatusKind::Waiting => Emoji::Hourglass,
-                };
-                println!("{} {}", emoji, status_msg.message);
-            }
-        },
+        print_status_message,
     )
     .await
     .context("Failed to import image")?;
 
+    print_import_response(&response, default, output_format)
+}
+
+/// Print a status message streamed by the server during an import.
+fn print_status_message(msg_text: &str) {
+    if let Ok(status_msg) = serde_json::from_str::<StatusMessage>(msg_text)
+        && status_msg.r#type == "status"
+    {
+        let emoji = match status_msg.kind {
+            StatusKind::Progress => Emoji::Progress,
+            StatusKind::Done => Emoji::Success,
+            StatusKind::Info => Emoji::Info,
+            StatusKind::Waiting => Emoji::Hourglass,
+        };
+        println!("{} {}", emoji, status_msg.message);
+    }
+}
+
+fn print_import_response(
+    response: &data::ImportResponse,
+    default: bool,
+    output_format: &OutputFormat,
+) -> Result<()> {
     match output_format {
         OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&response)?);
+            println!("{}", serde_json::to_string_pretty(response)?);
         }
         OutputFormat::Text => {
             if response.success {
@@ -381,6 +589,9 @@ async fn import_image(
                 println!("   Kind:     {}", response.kind);
                 println!("   Version:  {}", response.version);
                 println!("   Path:     {}", response.image_path);
+                if let Some(digest) = &response.image_sha256 {
+                    println!("   SHA-256:  {}", digest);
+                }
                 println!(
                     "   DB Track: {}",
                     if response.db_tracked { "yes" } else { "no" }
@@ -395,6 +606,245 @@ async fn import_image(
     Ok(())
 }
 
+/// Why an upload attempt stopped
+enum UploadError {
+    /// The connection dropped or the server lost track of the offset; the
+    /// upload can be resumed
+    Interrupted(anyhow::Error),
+    /// The server rejected the upload
+    Failed(anyhow::Error),
+}
+
+impl UploadError {
+    /// Classify a server error, treating offset conflicts left behind by a
+    /// dropped connection as resumable.
+    fn from_rejection(error: anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        if message.contains("Invalid chunk offset") || message.contains("already in progress") {
+            UploadError::Interrupted(error)
+        } else {
+            UploadError::Failed(error)
+        }
+    }
+}
+
+/// Upload a file in chunks, resuming after dropped connections.
+async fn upload_image(
+    path: &str,
+    request: data::StartUploadRequest,
+    chunk_size: u64,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    if chunk_size == 0 {
+        bail!("--chunk-size must be at least 1 MiB");
+    }
+    let mut file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let default = request.default;
+    let show_progress = matches!(output_format, OutputFormat::Text);
+
+    let mut received = 0;
+    let mut retries = 0;
+    let response = loop {
+        let before = received;
+        match upload_attempt(
+            &mut file,
+            &request,
+            chunk_size,
+            &mut received,
+            show_progress,
+            server_url,
+            server_connection,
+        )
+        .await
+        {
+            Ok(response) => break response,
+            Err(UploadError::Failed(e)) => return Err(e).context("Failed to upload image"),
+            Err(UploadError::Interrupted(e)) => {
+                if received > before {
+                    retries = 0;
+                }
+                retries += 1;
+                if retries > UPLOAD_MAX_RETRIES {
+                    return Err(e).context("Failed to upload image: too many interruptions");
+                }
+                let delay = Duration::from_secs(1 << retries.min(5));
+                eprintln!(
+                    "\n{}",
+                    emoji_warning(&format!(
+                        "Upload interrupted at {} of {} bytes, resuming in {}s ({:#})",
+                        received,
+                        request.size,
+                        delay.as_secs(),
+                        e
+                    ))
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+    };
+
+    print_import_response(&response, default, output_format)
+}
+
+/// Run one upload attempt over a single connection.
+///
+/// Asks the server how much it already has, then sends the rest of the file.
+/// `received` tracks the acknowledged offset across attempts.
+async fn upload_attempt(
+    file: &mut File,
+    request: &data::StartUploadRequest,
+    chunk_size: u64,
+    received: &mut u64,
+    show_progress: bool,
+    server_url: &str,
+    server_connection: &ServerConnection,
+) -> Result<data::ImportResponse, UploadError> {
+    let mut client = rpc_connect(server_url, server_connection, Duration::from_secs(900))
+        .await
+        .map_err(UploadError::Interrupted)?;
+
+    let params = authenticated_params(request).map_err(UploadError::Failed)?;
+    let response = client
+        .call(RpcRequest::new("image.upload_start", params))
+        .await
+        .map_err(UploadError::Interrupted)?;
+    let status: data::UploadStatus = rpc_result(response).map_err(UploadError::Failed)?;
+
+    if status.received > 0 && status.received < status.size && *received == 0 {
+        println!(
+            "{} Resuming upload at {} of {} bytes",
+            Emoji::Info,
+            status.received,
+            status.size
+        );
+    }
+    *received = status.received;
+    let chunk_size = chunk_size.min(status.max_chunk_size);
+
+    let result = send_chunks(
+        &mut client,
+        file,
+        &status,
+        chunk_size,
+        received,
+        show_progress,
+    )
+    .await;
+    let _ = client.close().await;
+    result
+}
+
+/// Send chunks from the acknowledged offset until the server imports the image.
+async fn send_chunks(
+    client: &mut RpcClient,
+    file: &mut File,
+    status: &data::UploadStatus,
+    chunk_size: u64,
+    received: &mut u64,
+    show_progress: bool,
+) -> Result<data::ImportResponse, UploadError> {
+    file.seek(SeekFrom::Start(*received))
+        .context("Failed to seek upload file")
+        .map_err(UploadError::Failed)?;
+
+    let mut buffer = vec![0u8; chunk_size as usize];
+    loop {
+        // At the end of the file this sends an empty chunk, which makes the
+        // server (re)try the import of a complete upload
+        let len = chunk_size.min(status.size - *received) as usize;
+        file.read_exact(&mut buffer[..len])
+            .context("Failed to read upload file")
+            .map_err(UploadError::Failed)?;
+        let chunk = &buffer[..len];
+
+        let chunk_request = data::UploadChunkRequest {
+            upload_id: status.upload_id.clone(),
+            offset: *received,
+            sha256: format!("{:x}", Sha256::digest(chunk)),
+            data: base64::engine::general_purpose::STANDARD.encode(chunk),
+        };
+        let params = authenticated_params(chunk_request).map_err(UploadError::Failed)?;
+        let is_last = *received + len as u64 == status.size;
+        let response = client
+            .call_streaming(RpcRequest::new("image.upload_chunk", params), |msg_text| {
+                print_status_message(msg_text)
+            })
+            .await
+            .map_err(UploadError::Interrupted)?;
+        let response: data::UploadChunkResponse =
+            rpc_result(response).map_err(UploadError::from_rejection)?;
+        *received = response.received;
+
+        if show_progress && !is_last {
+            print!(
+                "\r{} Uploaded {} of {} bytes ({}%)",
+                Emoji::Progress,
+                response.received,
+                response.size,
+                response.received * 100 / response.size
+            );
+            let _ = io::stdout().flush();
+        } else if show_progress && len > 0 {
+            println!(
+                "\r{} Uploaded {} of {} bytes (100%)",
+                Emoji::Success,
+                response.size,
+                response.size
+            );
+        }
+
+        if let Some(import) = response.import {
+            return Ok(import);
+        }
+        if response.received >= response.size {
+            return Err(UploadError::Failed(anyhow::anyhow!(
+                "Server received the whole file but did not import it"
+            )));
+        }
+    }
+}
+
+/// Cancel an interrupted upload and delete its partial data on the server.
+async fn cancel_upload(
+    request: data::StartUploadRequest,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let status: data::UploadStatus =
+        rpc_call("image.upload_start", request, server_url, server_connection)
+            .await
+            .context("Failed to look up upload")?;
+
+    let request = data::CancelUploadRequest {
+        upload_id: status.upload_id,
+    };
+    let response: data::CancelUploadResponse = rpc_call(
+        "image.upload_cancel",
+        request,
+        server_url,
+        server_connection,
+    )
+    .await
+    .context("Failed to cancel upload")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            println!(
+                "{}",
+                emoji_success(&format!("Upload {} cancelled", response.upload_id))
+            );
+        }
+    }
+
+    Ok(())
+}
+
 async fn delete_image(
     model: &NodeModel,
     version: &str,
@@ -439,6 +889,67 @@ async fn delete_image(
     Ok(())
 }
 
+async fn verify_image(
+    model: &NodeModel,
+    version: &str,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let request = data::VerifyImageRequest {
+        model: *model,
+        version: version.to_string(),
+    };
+
+    let response: data::VerifyImageResponse =
+        rpc_call("image.verify", request, server_url, server_connection)
+            .await
+            .context("Failed to verify image")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            match response.status {
+                data::ImageIntegrity::Ok => {
+                    println!("{}", emoji_success("Image matches its recorded digest"))
+                }
+                data::ImageIntegrity::Mismatch => {
+                    println!(
+                        "{}",
+                        emoji_error(
+                            "Image does not match its recorded digest, it may be corrupt or tampered with"
+                        )
+                    )
+                }
+                data::ImageIntegrity::Untracked => {
+                    println!(
+                        "{}",
+                        emoji_warning(
+                            "No digest was recorded for this image, re-import it to track one"
+                        )
+                    )
+                }
+            }
+            println!("   Model:    {}", response.model);
+            println!("   Version:  {}", response.version);
+            println!("   Path:     {}", response.image_path);
+            println!(
+                "   Expected: {}",
+                response.expected_sha256.as_deref().unwrap_or("-")
+            );
+            println!("   Actual:   {}", response.actual_sha256);
+        }
+    }
+
+    if response.status == data::ImageIntegrity::Mismatch {
+        bail!("Image integrity check failed");
+    }
+
+    Ok(())
+}
+
 async fn set_default_image(
     model: &NodeModel,
     version: &str,
@@ -475,6 +986,248 @@ async fn set_default_image(
     Ok(())
 }
 
+async fn image_usage(
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let response: data::ImageUsageResponse = rpc_call(
+        "image.usage",
+        serde_json::json!({}),
+        server_url,
+        server_connection,
+    )
+    .await
+    .context("Failed to get image usage")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            if response.images.is_empty() {
+                println!("No images found");
+            } else {
+                println!("{}", render_image_usage_table(&response.images));
+                println!("Total: {}", format_bytes(response.total_size_bytes));
+            }
+            if !response.orphaned_disks.is_empty() {
+                println!();
+                println!(
+                    "{}",
+                    emoji_warning(&format!(
+                        "{} storage pool file(s) not linked to any node:",
+                        response.orphaned_disks.len()
+                    ))
+                );
+                println!("{}", render_orphaned_disks_table(&response.orphaned_disks));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn prune_images(
+    request: data::PruneImagesRequest,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let response: data::PruneImagesResponse =
+        rpc_call("image.prune", request, server_url, server_connection)
+            .await
+            .context("Failed to prune images")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            let verb = if response.dry_run {
+                "Would remove"
+            } else {
+                "Removed"
+            };
+            for image in &response.pruned {
+                let size = image
+                    .size_bytes
+                    .map(format_bytes)
+                    .unwrap_or_else(|| "missing".to_string());
+                println!(
+                    "   {} {} {} ({}, {})",
+                    verb, image.model, image.version, image.kind, size
+                );
+            }
+            if response.pruned.is_empty() {
+                println!("{}", emoji_success("No unused images to prune"));
+            } else {
+                println!(
+                    "{}",
+                    emoji_success(&format!(
+                        "{} {} image(s), {}",
+                        verb,
+                        response.pruned.len(),
+                        format_bytes(response.freed_bytes)
+                    ))
+                );
+            }
+            for error in &response.errors {
+                eprintln!("{}", emoji_error(error));
+            }
+        }
+    }
+
+    if !response.errors.is_empty() {
+        bail!("{} image(s) could not be pruned", response.errors.len());
+    }
+
+    Ok(())
+}
+
+/// Build registry credentials, reading the password from
+/// `SHERPA_REGISTRY_PASSWORD` or prompting for it.
+pub(super) fn registry_auth(username: &str) -> Result<data::RegistryAuth> {
+    let password = match std::env::var("SHERPA_REGISTRY_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => rpassword::prompt_password(format!("Registry password for {}: ", username))
+            .context("Failed to read password")?,
+    };
+    Ok(data::RegistryAuth {
+        username: username.to_string(),
+        password,
+    })
+}
+
+/// Parse an age such as `30d`, `12h` or `2w` into seconds.
+fn parse_age(value: &str) -> std::result::Result<u64, String> {
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{value}', expected e.g. 30d"))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format!("invalid unit '{unit}', expected s, m, h, d or w")),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("age '{value}' is too large"))
+}
+
+async fn add_custom_model(
+    file: &str,
+    replace: bool,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let definition =
+        std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))?;
+    // Catch schema mistakes before a round trip to the server
+    data::CustomModel::from_toml(&definition)?;
+
+    let request = data::AddCustomModelRequest {
+        definition,
+        replace,
+    };
+
+    let response: data::AddCustomModelResponse =
+        rpc_call("image.model_add", request, server_url, server_connection)
+            .await
+            .context("Failed to add custom model")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            let action = if response.replaced {
+                "replaced"
+            } else {
+                "added"
+            };
+            println!(
+                "{}",
+                emoji_success(&format!("Custom model {action} successfully"))
+            );
+            println!("   Model: {}", response.model.reference());
+            println!("   Base:  {}", response.model.base_model());
+            println!("   ZTP:   {}", response.model.ztp.method);
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_custom_models(
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let response: data::ListCustomModelsResponse = rpc_call(
+        "image.model_list",
+        serde_json::json!({}),
+        server_url,
+        server_connection,
+    )
+    .await
+    .context("Failed to list custom models")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            if response.models.is_empty() {
+                println!("No custom models found");
+            } else {
+                let summaries: Vec<data::CustomModelSummary> =
+                    response.models.iter().map(Into::into).collect();
+                println!("\n{}", render_custom_models_table(&summaries));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_custom_model(
+    name: &str,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let request = data::DeleteCustomModelRequest {
+        name: name.to_string(),
+    };
+
+    let response: data::DeleteCustomModelResponse =
+        rpc_call("image.model_delete", request, server_url, server_connection)
+            .await
+            .context("Failed to delete custom model")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            println!(
+                "{}",
+                emoji_success(&format!("Custom model {} deleted", response.name))
+            );
+        }
+    }
+
+    Ok(())
+}
+
 async fn pull_container_image(
     model: &NodeModel,
     version: Option<&str>,
@@ -572,20 +1325,12 @@ async fn pull_container_image(
 }
 
 async fn download_vm_image(
-    model: &NodeModel,
-    version: &str,
-    url: Option<&str>,
-    default: bool,
+    request: data::DownloadImageRequest,
     server_url: &str,
     server_connection: &ServerConnection,
     output_format: &OutputFormat,
 ) -> Result<()> {
-    let request = data::DownloadImageRequest {
-        model: *model,
-        version: version.to_string(),
-        url: url.map(|u| u.to_string()),
-        default,
-    };
+    let default = request.default;
 
     let response: data::ImportResponse = rpc_call_streaming(
         "image.download",
@@ -607,7 +1352,7 @@ async fn download_vm_image(
         },
     )
     .await
-    .context("Failed to download VM image")?;
+    .context("Failed to download image")?;
 
     match output_format {
         OutputFormat::Json => {
@@ -615,7 +1360,7 @@ async fn download_vm_image(
         }
         OutputFormat::Text => {
             if response.success {
-                println!("{}", emoji_success("VM image downloaded successfully"));
+                println!("{}", emoji_success("Image downloaded successfully"));
                 println!("   Model:    {}", response.model);
                 println!("   Kind:     {}", response.kind);
                 println!("   Version:  {}", response.version);
@@ -626,10 +1371,27 @@ async fn download_vm_image(
                 );
                 println!("   Default:  {}", if default { "yes" } else { "no" });
             } else {
-                eprintln!("VM image download failed");
+                eprintln!("Image download failed");
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d"), Ok(30 * 24 * 60 * 60));
+        assert_eq!(parse_age("12h"), Ok(12 * 60 * 60));
+        assert_eq!(parse_age("2w"), Ok(14 * 24 * 60 * 60));
+        assert_eq!(parse_age("90s"), Ok(90));
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("30y").is_err());
+        assert!(parse_age("-1d").is_err());
+    }
+}
@@ -5,14 +5,18 @@ use shared::data::ClientConfig;
 
 mod clean;
 mod image;
+mod registry;
 mod rpc;
 mod status;
+mod team;
 mod user;
 
 use clean::clean;
 use image::{ServerImageCommands, image_commands};
-pub use rpc::{rpc_call, rpc_call_streaming};
+use registry::{RegistryCommands, registry_commands};
+pub use rpc::{authenticated_params, rpc_call, rpc_call_streaming, rpc_connect, rpc_result};
 use status::status;
+use team::{TeamCommands, team_commands};
 use user::{UserCommands, user_commands};
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -32,12 +36,24 @@ pub enum ServerCommands {
         commands: UserCommands,
     },
 
+    /// Team management commands
+    Team {
+        #[command(subcommand)]
+        commands: TeamCommands,
+    },
+
     /// Image management commands (admin)
     Image {
         #[command(subcommand)]
         commands: ServerImageCommands,
     },
 
+    /// Private container registry credentials (admin)
+    Registry {
+        #[command(subcommand)]
+        commands: RegistryCommands,
+    },
+
     /// Force clean all resources for a lab (admin-only)
     Clean {
         /// Lab ID to clean
@@ -69,9 +85,15 @@ pub async fn run_server(
         ServerCommands::User { commands } => {
             user_commands(commands, server_url, &server_connection, output).await?;
         }
+        ServerCommands::Team { commands } => {
+            team_commands(commands, server_url, &server_connection, output).await?;
+        }
         ServerCommands::Image { commands } => {
             image_commands(commands, server_url, &server_connection, output).await?;
         }
+        ServerCommands::Registry { commands } => {
+            registry_commands(commands, server_url, &server_connection, output).await?;
+        }
         ServerCommands::Clean { lab_id } => {
             clean(lab_id, server_url, &server_connection).await?;
         }
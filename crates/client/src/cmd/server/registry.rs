@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{self, ServerConnection};
+use shared::util::{emoji_success, emoji_warning};
+
+use super::OutputFormat;
+use super::image::registry_auth;
+use super::rpc_call;
+
+#[derive(Debug, Subcommand)]
+pub enum RegistryCommands {
+    /// Store credentials for a private container registry (admin-only)
+    ///
+    /// The password is read from SHERPA_REGISTRY_PASSWORD or prompted for.
+    /// Image pulls from the registry use the credentials automatically.
+    Login {
+        /// Registry host (e.g. harbor.example.com or localhost:5000)
+        registry: String,
+
+        /// Registry username
+        #[arg(short, long)]
+        username: String,
+    },
+
+    /// List registries with stored credentials (admin-only)
+    List,
+
+    /// Remove the stored credentials of a registry (admin-only)
+    Logout {
+        /// Registry host
+        registry: String,
+    },
+}
+
+pub async fn registry_commands(
+    command: &RegistryCommands,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    match command {
+        RegistryCommands::Login { registry, username } => {
+            let auth = registry_auth(username)?;
+            let request = data::RegistryLoginRequest {
+                registry: registry.clone(),
+                username: auth.username,
+                password: auth.password,
+            };
+            let response: data::RegistryLoginResponse =
+                rpc_call("registry.login", request, server_url, server_connection)
+                    .await
+                    .context("Failed to log in to registry")?;
+
+            match output_format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Text => {
+                    let action = if response.replaced {
+                        "replaced"
+                    } else {
+                        "stored"
+                    };
+                    println!(
+                        "{}",
+                        emoji_success(&format!(
+                            "Credentials for {} {} (user: {})",
+                            response.registry, action, response.username
+                        ))
+                    );
+                }
+            }
+        }
+        RegistryCommands::List => {
+            let response: data::ListRegistriesResponse = rpc_call(
+                "registry.list",
+                serde_json::json!({}),
+                server_url,
+                server_connection,
+            )
+            .await
+            .context("Failed to list registries")?;
+
+            match output_format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Text => {
+                    if response.registries.is_empty() {
+                        println!("No registry credentials stored");
+                    } else {
+                        println!(
+                            "\n{} registry credential(s) stored:\n",
+                            response.registries.len()
+                        );
+                        for credential in &response.registries {
+                            let updated = jiff::Timestamp::from_second(credential.updated_at)
+                                .ok()
+                                .map(|ts| ts.strftime("%Y-%m-%d %H:%M:%S UTC").to_string())
+                                .unwrap_or_else(|| "Unknown".to_string());
+                            println!("  • {}", credential.registry);
+                            println!("    Username: {}", credential.username);
+                            println!("    Updated: {}", updated);
+                            println!();
+                        }
+                    }
+                }
+            }
+        }
+        RegistryCommands::Logout { registry } => {
+            let request = data::RegistryLogoutRequest {
+                registry: registry.clone(),
+            };
+            let response: data::RegistryLogoutResponse =
+                rpc_call("registry.logout", request, server_url, server_connection)
+                    .await
+                    .context("Failed to log out of registry")?;
+
+            match output_format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Text if response.removed => {
+                    println!(
+                        "{}",
+                        emoji_success(&format!("Credentials for {} removed", response.registry))
+                    );
+                }
+                OutputFormat::Text => {
+                    println!(
+                        "{}",
+                        emoji_warning(&format!("No credentials stored for {}", response.registry))
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
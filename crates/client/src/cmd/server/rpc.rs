@@ -5,6 +5,8 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 
 use crate::token::load_token;
+use crate::ws_client::client::RpcClient;
+use crate::ws_client::messages::RpcResponse;
 use crate::ws_client::{RpcRequest, WebSocketClient};
 
 use shared::data::ServerConnection;
@@ -20,15 +22,7 @@ where
     P: Serialize,
     R: DeserializeOwned,
 {
-    let token = load_token().context("Not authenticated. Please login first.")?;
-
-    // Inject token into params
-    let mut params_value =
-        serde_json::to_value(&params).context("Failed to serialize request params")?;
-
-    if let Some(obj) = params_value.as_object_mut() {
-        obj.insert("token".to_string(), serde_json::Value::String(token));
-    }
+    let params_value = authenticated_params(params)?;
 
     let ws_client = WebSocketClient::new(
         server_url.to_string(),
@@ -43,25 +37,7 @@ where
 
     let _ = rpc_client.close().await;
 
-    // Check for RPC error
-    if let Some(error) = response.error {
-        if let Some(ctx) = &error.context {
-            anyhow::bail!(
-                "RPC error: {} (code: {})\n  Context: {}",
-                error.message,
-                error.code,
-                ctx
-            );
-        } else {
-            anyhow::bail!("RPC error: {} (code: {})", error.message, error.code);
-        }
-    }
-
-    let result = response.result.context("No result in response")?;
-    let typed_result: R =
-        serde_json::from_value(result).context("Failed to deserialize response")?;
-
-    Ok(typed_result)
+    rpc_result(response)
 }
 
 /// Streaming RPC helper: load token, connect via WebSocket, send a streaming RPC call with
@@ -78,15 +54,7 @@ where
     R: DeserializeOwned,
     F: FnMut(&str),
 {
-    let token = load_token().context("Not authenticated. Please login first.")?;
-
-    // Inject token into params
-    let mut params_value =
-        serde_json::to_value(&params).context("Failed to serialize request params")?;
-
-    if let Some(obj) = params_value.as_object_mut() {
-        obj.insert("token".to_string(), serde_json::Value::String(token));
-    }
+    let params_value = authenticated_params(params)?;
 
     // Extended timeout for downloads (15 minutes)
     let ws_client = WebSocketClient::new(
@@ -102,7 +70,36 @@ where
 
     let _ = rpc_client.close().await;
 
-    // Check for RPC error
+    rpc_result(response)
+}
+
+/// Serialize RPC params with the saved authentication token injected.
+pub fn authenticated_params<P: Serialize>(params: P) -> Result<serde_json::Value> {
+    let token = load_token().context("Not authenticated. Please login first.")?;
+
+    let mut params_value =
+        serde_json::to_value(&params).context("Failed to serialize request params")?;
+
+    if let Some(obj) = params_value.as_object_mut() {
+        obj.insert("token".to_string(), serde_json::Value::String(token));
+    }
+
+    Ok(params_value)
+}
+
+/// Open a WebSocket RPC connection for callers making several calls.
+pub async fn rpc_connect(
+    server_url: &str,
+    server_connection: &ServerConnection,
+    timeout: Duration,
+) -> Result<RpcClient> {
+    WebSocketClient::new(server_url.to_string(), timeout, server_connection.clone())
+        .connect()
+        .await
+}
+
+/// Turn an RPC response into a typed result, failing on RPC errors.
+pub fn rpc_result<R: DeserializeOwned>(response: RpcResponse) -> Result<R> {
     if let Some(error) = response.error {
         if let Some(ctx) = &error.context {
             anyhow::bail!(
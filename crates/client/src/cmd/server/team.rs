@@ -0,0 +1,219 @@
+use std::io::{self, Write};
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{self, ServerConnection};
+use shared::util::emoji_success;
+
+use super::OutputFormat;
+use super::rpc_call;
+
+#[derive(Debug, Subcommand)]
+pub enum TeamCommands {
+    /// Create a team owned by the current user
+    Create {
+        /// Team name
+        name: String,
+
+        /// Initial members (can be specified multiple times)
+        #[arg(long = "member")]
+        members: Vec<String>,
+    },
+
+    /// List all teams
+    List,
+
+    /// Add members to a team (team owner or admin)
+    AddMember {
+        /// Team name
+        name: String,
+
+        /// Usernames to add
+        #[arg(required = true)]
+        usernames: Vec<String>,
+    },
+
+    /// Remove members from a team (team owner or admin)
+    RemoveMember {
+        /// Team name
+        name: String,
+
+        /// Usernames to remove
+        #[arg(required = true)]
+        usernames: Vec<String>,
+    },
+
+    /// Delete a team and every lab share granted to it (team owner or admin)
+    Delete {
+        /// Team name
+        name: String,
+
+        /// Skip confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub async fn team_commands(
+    command: &TeamCommands,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    match command {
+        TeamCommands::Create { name, members } => {
+            let request = data::CreateTeamRequest {
+                name: name.clone(),
+                members: members.clone(),
+                token: String::new(),
+            };
+            let team: data::TeamInfo =
+                rpc_call("team.create", request, server_url, server_connection)
+                    .await
+                    .context("Failed to create team")?;
+            print_team(&team, &format!("Team '{}' created", name), output_format)
+        }
+        TeamCommands::List => list_teams(server_url, server_connection, output_format).await,
+        TeamCommands::AddMember { name, usernames } => {
+            let request = data::UpdateTeamMembersRequest {
+                name: name.clone(),
+                add: usernames.clone(),
+                remove: vec![],
+                token: String::new(),
+            };
+            let team: data::TeamInfo =
+                rpc_call("team.update", request, server_url, server_connection)
+                    .await
+                    .context("Failed to add team members")?;
+            print_team(&team, &format!("Team '{}' updated", name), output_format)
+        }
+        TeamCommands::RemoveMember { name, usernames } => {
+            let request = data::UpdateTeamMembersRequest {
+                name: name.clone(),
+                add: vec![],
+                remove: usernames.clone(),
+                token: String::new(),
+            };
+            let team: data::TeamInfo =
+                rpc_call("team.update", request, server_url, server_connection)
+                    .await
+                    .context("Failed to remove team members")?;
+            print_team(&team, &format!("Team '{}' updated", name), output_format)
+        }
+        TeamCommands::Delete { name, force } => {
+            delete_team(name, *force, server_url, server_connection, output_format).await
+        }
+    }
+}
+
+fn print_team(team: &data::TeamInfo, message: &str, output_format: &OutputFormat) -> Result<()> {
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(team)?);
+        }
+        OutputFormat::Text => {
+            println!("{}", emoji_success(message));
+            print_team_details(team);
+        }
+    }
+    Ok(())
+}
+
+fn print_team_details(team: &data::TeamInfo) {
+    println!("  • {}", team.name);
+    println!("    Owner: {}", team.owner);
+    println!(
+        "    Members: {}",
+        if team.members.is_empty() {
+            "None".to_string()
+        } else {
+            team.members.join(", ")
+        }
+    );
+}
+
+async fn list_teams(
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    let request = data::ListTeamsRequest {
+        token: String::new(),
+    };
+
+    let response: data::ListTeamsResponse =
+        rpc_call("team.list", request, server_url, server_connection)
+            .await
+            .context("Failed to list teams")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            if response.teams.is_empty() {
+                println!("No teams found");
+            } else {
+                println!("\n{} team(s) found:\n", response.teams.len());
+                for team in &response.teams {
+                    print_team_details(team);
+                    println!();
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn delete_team(
+    name: &str,
+    force: bool,
+    server_url: &str,
+    server_connection: &ServerConnection,
+    output_format: &OutputFormat,
+) -> Result<()> {
+    // Confirm deletion unless --force flag is set
+    if !force {
+        print!(
+            "Are you sure you want to delete team '{}'? Labs shared with it will no longer be accessible to its members. [y/N]: ",
+            name
+        );
+        io::stdout().flush()?;
+
+        let mut response = String::new();
+        io::stdin()
+            .read_line(&mut response)
+            .context("Failed to read confirmation")?;
+
+        if !matches!(response.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Deletion cancelled");
+            return Ok(());
+        }
+    }
+
+    let request = data::DeleteTeamRequest {
+        name: name.to_string(),
+        token: String::new(),
+    };
+
+    let response: data::DeleteTeamResponse =
+        rpc_call("team.delete", request, server_url, server_connection)
+            .await
+            .context("Failed to delete team")?;
+
+    match output_format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+        OutputFormat::Text => {
+            println!(
+                "{}",
+                emoji_success(&format!("Team '{}' deleted", response.name))
+            );
+        }
+    }
+
+    Ok(())
+}
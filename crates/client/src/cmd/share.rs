@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{
+    ClientConfig, LabRole, ListLabSharesRequest, ListLabSharesResponse, ShareLabRequest,
+    UnshareLabRequest, split_grantee,
+};
+use shared::util::{emoji_success, term_msg_surround};
+
+use super::server::rpc_call;
+
+#[derive(Debug, Subcommand)]
+pub enum ShareCommands {
+    /// Share the lab with a user or a team (lab owner or admin)
+    Add {
+        /// Username, or `team:<name>` for a team
+        grantee: String,
+        /// Role to grant
+        #[arg(long, value_enum, default_value = "viewer")]
+        role: LabRole,
+    },
+    /// Remove a user's or team's access to the lab (lab owner or admin)
+    Remove {
+        /// Username, or `team:<name>` for a team
+        grantee: String,
+    },
+    /// List the users and teams the lab is shared with
+    List,
+}
+
+fn print_shares(response: &ListLabSharesResponse) {
+    println!("Owner: {}", response.owner);
+    if response.shares.is_empty() {
+        println!("Not shared with anyone");
+        return;
+    }
+    println!("\nShared with:");
+    for share in &response.shares {
+        println!("  • {} ({})", share.grantee(), share.role);
+    }
+}
+
+/// Manage who has access to a lab.
+pub async fn share(
+    command: &ShareCommands,
+    lab_id: &str,
+    server_url: &str,
+    config: &ClientConfig,
+) -> Result<()> {
+    let server_connection = &config.server_connection;
+
+    let response: ListLabSharesResponse = match command {
+        ShareCommands::Add { grantee, role } => {
+            let (username, team) = split_grantee(grantee);
+            let request = ShareLabRequest {
+                lab_id: lab_id.to_string(),
+                username,
+                team,
+                role: *role,
+                token: String::new(),
+            };
+            let response = rpc_call("lab.share", request, server_url, server_connection)
+                .await
+                .context("Failed to share lab")?;
+            println!(
+                "{}",
+                emoji_success(&format!(
+                    "Lab '{}' shared with {} as {}",
+                    lab_id, grantee, role
+                ))
+            );
+            response
+        }
+        ShareCommands::Remove { grantee } => {
+            let (username, team) = split_grantee(grantee);
+            let request = UnshareLabRequest {
+                lab_id: lab_id.to_string(),
+                username,
+                team,
+                token: String::new(),
+            };
+            let response = rpc_call("lab.unshare", request, server_url, server_connection)
+                .await
+                .context("Failed to remove lab share")?;
+            println!(
+                "{}",
+                emoji_success(&format!("Removed {} from lab '{}'", grantee, lab_id))
+            );
+            response
+        }
+        ShareCommands::List => {
+            term_msg_surround(&format!("Lab Shares - {lab_id}"));
+            let request = ListLabSharesRequest {
+                lab_id: lab_id.to_string(),
+                token: String::new(),
+            };
+            rpc_call("lab.shares", request, server_url, server_connection)
+                .await
+                .context("Failed to list lab shares")?
+        }
+    };
+
+    print_shares(&response);
+    Ok(())
+}
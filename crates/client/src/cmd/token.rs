@@ -0,0 +1,131 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{
+    ApiTokenInfo, ClientConfig, CreateApiTokenRequest, CreateApiTokenResponse,
+    ListApiTokensRequest, ListApiTokensResponse, RevokeApiTokenRequest, RevokeApiTokenResponse,
+    TokenScope,
+};
+use shared::konst::{API_TOKEN_DEFAULT_EXPIRY_DAYS, API_TOKEN_MAX_EXPIRY_DAYS};
+use shared::util::{emoji_success, emoji_warning, term_msg_surround};
+
+use super::server::rpc_call;
+
+#[derive(Debug, Subcommand)]
+pub enum TokenCommands {
+    /// Create a personal API token for automation (e.g. CI pipelines)
+    Create {
+        /// Token name, unique per user
+        name: String,
+        /// Scope granted to the token (can be specified multiple times)
+        #[arg(long = "scope", value_enum, required = true)]
+        scopes: Vec<TokenScope>,
+        /// Days until the token expires
+        #[arg(
+            long,
+            default_value_t = API_TOKEN_DEFAULT_EXPIRY_DAYS,
+            value_parser = clap::value_parser!(u32).range(1..=i64::from(API_TOKEN_MAX_EXPIRY_DAYS))
+        )]
+        expires_in_days: u32,
+    },
+    /// List your API tokens
+    List,
+    /// Revoke one of your API tokens
+    Revoke {
+        /// Token name
+        name: String,
+    },
+}
+
+fn format_timestamp(seconds: i64) -> String {
+    jiff::Timestamp::from_second(seconds)
+        .ok()
+        .map(|ts| ts.strftime("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn print_token(token: &ApiTokenInfo) {
+    let scopes: Vec<String> = token.scopes.iter().map(ToString::to_string).collect();
+    println!("  • {} ({}…)", token.name, token.prefix);
+    println!("    Scopes: {}", scopes.join(", "));
+    println!("    Expires: {}", format_timestamp(token.expires_at));
+    println!(
+        "    Last used: {}",
+        token
+            .last_used_at
+            .map(format_timestamp)
+            .unwrap_or_else(|| "Never".to_string())
+    );
+}
+
+/// Manage personal API tokens.
+pub async fn token(command: &TokenCommands, server_url: &str, config: &ClientConfig) -> Result<()> {
+    let server_connection = &config.server_connection;
+
+    match command {
+        TokenCommands::Create {
+            name,
+            scopes,
+            expires_in_days,
+        } => {
+            let request = CreateApiTokenRequest {
+                name: name.clone(),
+                scopes: scopes.clone(),
+                expires_in_days: *expires_in_days,
+                token: String::new(),
+            };
+            let response: CreateApiTokenResponse =
+                rpc_call("token.create", request, server_url, server_connection)
+                    .await
+                    .context("Failed to create API token")?;
+
+            println!(
+                "{}",
+                emoji_success(&format!("API token '{}' created", response.info.name))
+            );
+            print_token(&response.info);
+            println!("\n{}\n", response.api_token);
+            println!(
+                "{}",
+                emoji_warning("Copy this token now, it will not be shown again.")
+            );
+            println!("Use it by setting the SHERPA_TOKEN environment variable.");
+        }
+        TokenCommands::List => {
+            term_msg_surround("API Tokens");
+            let request = ListApiTokensRequest {
+                token: String::new(),
+            };
+            let response: ListApiTokensResponse =
+                rpc_call("token.list", request, server_url, server_connection)
+                    .await
+                    .context("Failed to list API tokens")?;
+
+            if response.tokens.is_empty() {
+                println!("No API tokens found");
+            } else {
+                println!("\n{} token(s) found:\n", response.tokens.len());
+                for token in &response.tokens {
+                    print_token(token);
+                    println!();
+                }
+            }
+        }
+        TokenCommands::Revoke { name } => {
+            let request = RevokeApiTokenRequest {
+                name: name.clone(),
+                token: String::new(),
+            };
+            let response: RevokeApiTokenResponse =
+                rpc_call("token.revoke", request, server_url, server_connection)
+                    .await
+                    .context("Failed to revoke API token")?;
+            println!(
+                "{}",
+                emoji_success(&format!("API token '{}' revoked", response.name))
+            );
+        }
+    }
+
+    Ok(())
+}
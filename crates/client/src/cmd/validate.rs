@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
 
 use super::manifest_processing::{
-    get_node_image, process_manifest_bridges, process_manifest_links, process_manifest_nodes,
+    get_node_image, process_external_links, process_manifest_bridges, process_manifest_links,
+    process_manifest_nodes, process_mirror_ports,
 };
 use shared::data::{NodeConfig, NodeModel};
 use shared::util;
@@ -30,13 +33,36 @@ pub fn validate_manifest(manifest_path: &str) -> Result<()> {
     // Process manifest data (same as up.rs)
     let nodes_expanded = process_manifest_nodes(&manifest.nodes);
     let links_detailed = process_manifest_links(&manifest.links, &nodes_expanded)?;
-    let bridges_detailed =
+    let mut bridges_detailed =
         process_manifest_bridges(&manifest.bridges, &nodes_expanded, "validate")?;
+    bridges_detailed.extend(process_external_links(
+        &manifest.links,
+        &nodes_expanded,
+        "validate",
+        bridges_detailed.len(),
+    )?);
+    bridges_detailed.extend(process_mirror_ports(
+        &manifest.mirrors,
+        &nodes_expanded,
+        "validate",
+        bridges_detailed.len(),
+    )?);
 
     // Per-node validators
     println!("→ Checking interface configurations...");
+    let mut image_mtus = HashMap::new();
     for node in &nodes_expanded {
+        // Custom model definitions live on the server, which checks their
+        // interfaces when the lab is created.
+        if node.model == NodeModel::Custom {
+            println!(
+                "  - Skipping {} (custom model, checked by the server)",
+                node.name
+            );
+            continue;
+        }
         let node_image = get_node_image(&node.model, &node_images)?;
+        image_mtus.insert(node.name.clone(), node_image.interface_mtu);
 
         // Management interface check
         if !node_image.dedicated_management_interface {
@@ -72,11 +98,33 @@ pub fn validate_manifest(manifest_path: &str) -> Result<()> {
         println!("  ✓ All linked devices exist");
     }
 
+    // MTU validators
+    println!("→ Checking MTUs...");
+    validate::check_mtus(&manifest.nodes, &links_detailed, &image_mtus)?;
+    println!("  ✓ MTUs are valid");
+
     // Bridge validators
     if !bridges_detailed.is_empty() {
         println!("→ Checking bridge configurations...");
         validate::check_bridge_device(&manifest.nodes, &bridges_detailed)?;
         println!("  ✓ All bridge devices exist");
+
+        if let Some(bridges) = &manifest.bridges {
+            validate::check_bridge_ports(bridges)?;
+            println!("  ✓ Bridge VLAN ports are valid");
+        }
+    }
+
+    // External link validators
+    println!("→ Checking external links...");
+    validate::check_external_links(&manifest.nodes, &bridges_detailed)?;
+    println!("  ✓ External links are valid (host interfaces are checked by the server)");
+
+    // Mirror validators
+    if let Some(mirrors) = &manifest.mirrors {
+        println!("→ Checking port mirrors...");
+        validate::check_mirrors(mirrors, &links_detailed)?;
+        println!("  ✓ Port mirrors are valid");
     }
 
     println!();
@@ -118,6 +166,137 @@ links = [
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_manifest_accepts_custom_model() {
+        let manifest = r#"
+name = "custom-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+  { name = "r1", model = "custom:junos" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "r1::ge-0/0/0" },
+]
+"#;
+        let path = write_temp_manifest("custom-pass", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_validate_manifest_accepts_external_link() {
+        let manifest = r#"
+name = "external-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "host::enp5s0.120" },
+]
+"#;
+        let path = write_temp_manifest("external-pass", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_validate_manifest_rejects_shared_host_interface() {
+        let manifest = r#"
+name = "external-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+  { name = "dev02", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "host::enp5s0" },
+  { src = "dev02::eth1", dst = "host::enp5s0" },
+]
+"#;
+        let path = write_temp_manifest("external-fail", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_manifest_rejects_mtu_mismatch() {
+        let manifest = r#"
+name = "mtu-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux", data_interface_count = 2, mtu = 9216 },
+  { name = "dev02", model = "ubuntu_linux", data_interface_count = 2, mtu = 9000 },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "dev02::eth1" },
+  { src = "dev01::eth2", dst = "dev02::eth2", mtu = 9216 },
+]
+"#;
+        let path = write_temp_manifest("mtu-fail", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        let err = result.expect_err("mismatched link ends are rejected");
+        assert!(err.to_string().contains("dev01::eth1 <-> dev02::eth1"));
+    }
+
+    #[test]
+    fn test_validate_manifest_accepts_mirror() {
+        let manifest = r#"
+name = "mirror-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+  { name = "dev02", model = "ubuntu_linux" },
+  { name = "ids01", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "dev02::eth1" },
+]
+
+mirrors = [
+  { source = "dev01::eth1", direction = "both", destination = "ids01::eth1" },
+]
+"#;
+        let path = write_temp_manifest("mirror-pass", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    #[test]
+    fn test_validate_manifest_rejects_mirror_port_in_use() {
+        let manifest = r#"
+name = "mirror-lab"
+
+nodes = [
+  { name = "dev01", model = "ubuntu_linux" },
+  { name = "dev02", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "dev01::eth1", dst = "dev02::eth1" },
+]
+
+mirrors = [
+  { source = "dev01::eth1", destination = "dev02::eth1" },
+]
+"#;
+        let path = write_temp_manifest("mirror-fail", manifest);
+        let result = validate_manifest(path.to_str().expect("temp path is utf-8"));
+        fs::remove_file(path).ok();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_validate_manifest_rejects_link_past_default_interface_count() {
         let manifest = r#"
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+
+use shared::data::{CablingStatus, ClientConfig, VerifyCablingRequest, VerifyCablingResponse};
+use shared::util::{emoji_success, emoji_warning, render_cabling_table, term_msg_surround};
+
+use super::server::rpc_call;
+
+#[derive(Debug, Subcommand)]
+pub enum VerifyCommands {
+    /// Compare the LLDP/CDP neighbours the nodes advertise with the manifest
+    Cabling {
+        /// Seconds to listen for advertisements (server default 35)
+        #[arg(long)]
+        wait: Option<u64>,
+    },
+}
+
+/// Verify a running lab against its manifest.
+pub async fn verify(
+    command: &VerifyCommands,
+    lab_id: &str,
+    server_url: &str,
+    config: &ClientConfig,
+) -> Result<()> {
+    match command {
+        VerifyCommands::Cabling { wait } => {
+            term_msg_surround(&format!("Verify Cabling - {lab_id}"));
+            println!("Listening for LLDP/CDP advertisements...");
+
+            let request = VerifyCablingRequest {
+                lab_id: lab_id.to_string(),
+                wait_secs: *wait,
+                token: String::new(),
+            };
+            let response: VerifyCablingResponse = rpc_call(
+                "lab.verify_cabling",
+                request,
+                server_url,
+                &config.server_connection,
+            )
+            .await
+            .context("Failed to verify cabling")?;
+
+            if response.checks.is_empty() {
+                println!("Lab has no links");
+                return Ok(());
+            }
+            println!("{}", render_cabling_table(&response.checks));
+
+            let verified = response.count(CablingStatus::Verified);
+            let summary = format!(
+                "{verified} verified, {} mismatched, {} not seen, {} unsupported",
+                response.count(CablingStatus::Mismatch),
+                response.count(CablingStatus::NotSeen),
+                response.count(CablingStatus::Unsupported),
+            );
+            if response.count(CablingStatus::Mismatch) > 0 {
+                println!("{}", emoji_warning(&summary));
+            } else {
+                println!("{}", emoji_success(&summary));
+            }
+            Ok(())
+        }
+    }
+}
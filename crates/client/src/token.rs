@@ -1,7 +1,9 @@
 //! Token storage and management for the Sherpa CLI client.
 //!
 //! This module handles reading and writing JWT tokens to ~/.sherpa/token
-//! with appropriate file permissions (0600).
+//! with appropriate file permissions (0600). The `SHERPA_TOKEN` environment
+//! variable takes precedence over the file, so CI pipelines can supply a
+//! personal API token without logging in.
 
 use anyhow::{Context, Result};
 use std::fs;
@@ -53,10 +55,11 @@ pub fn save_token(token: &str) -> Result<()> {
     Ok(())
 }
 
-/// Load a JWT token from ~/.sherpa/token
+/// Load a token from `SHERPA_TOKEN` or ~/.sherpa/token
 ///
 /// # Returns
-/// The token string if the file exists and is readable
+/// The `SHERPA_TOKEN` value if set and non-empty, otherwise the token file
+/// contents
 ///
 /// # Errors
 /// Returns an error if:
@@ -64,6 +67,12 @@ pub fn save_token(token: &str) -> Result<()> {
 /// - Token file doesn't exist
 /// - File read fails
 pub fn load_token() -> Result<String> {
+    if let Ok(env_token) = std::env::var("SHERPA_TOKEN")
+        && !env_token.trim().is_empty()
+    {
+        return Ok(env_token.trim().to_string());
+    }
+
     let token_path = get_token_path()?;
 
     if !token_path.exists() {
@@ -180,4 +189,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    #[ignore] // Uses unsafe env::set_var
+    fn test_load_token_prefers_env() {
+        let original_token = env::var("SHERPA_TOKEN").ok();
+
+        // SAFETY: This test is single-threaded and restores the original value
+        unsafe {
+            env::set_var("SHERPA_TOKEN", "sherpa_pat_0123456789abcdef\n");
+        }
+
+        let loaded = load_token().expect("Failed to load token");
+        assert_eq!(loaded, "sherpa_pat_0123456789abcdef");
+
+        // Restore original SHERPA_TOKEN
+        // SAFETY: Restoring the original value
+        unsafe {
+            match original_token {
+                Some(token) => env::set_var("SHERPA_TOKEN", token),
+                None => env::remove_var("SHERPA_TOKEN"),
+            }
+        }
+    }
 }
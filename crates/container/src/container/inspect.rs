@@ -29,3 +29,19 @@ pub async fn get_container_pid(docker: &Docker, container_name: &str) -> Result<
 
     Ok(pid as u32)
 }
+
+/// Get the image reference a container was created from, e.g.
+/// `ghcr.io/nokia/srlinux:24.10.1`.
+#[instrument(skip(docker), fields(%container_name), level = "debug")]
+pub async fn get_container_image(docker: &Docker, container_name: &str) -> Result<String> {
+    let options = Some(InspectContainerOptions { size: false });
+    let details = docker
+        .inspect_container(container_name, options)
+        .await
+        .with_context(|| format!("failed to inspect container {container_name}"))?;
+
+    details
+        .config
+        .and_then(|c| c.image)
+        .ok_or_else(|| anyhow!("no image found for container {container_name}"))
+}
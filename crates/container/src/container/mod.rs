@@ -9,7 +9,7 @@ mod stop;
 pub use create::run_container;
 pub use delete::{kill_container, remove_container};
 pub use exec::{exec_container, exec_container_detached, exec_container_with_retry};
-pub use inspect::get_container_pid;
+pub use inspect::{get_container_image, get_container_pid};
 pub use list::list_containers;
 pub use start::{start_container, unpause_container};
 pub use stop::{pause_container, stop_container};
@@ -0,0 +1,35 @@
+use anyhow::{Context, Result};
+use bollard::Docker;
+use bollard::models::ContainerConfig;
+use bollard::query_parameters::CommitContainerOptionsBuilder;
+use tracing::instrument;
+
+/// Create an image from a container's current filesystem.
+///
+/// Equivalent to `docker commit <name> <repo>:<tag>`. The container is
+/// paused while the image is written.
+///
+/// # Returns
+/// The ID of the new image
+#[instrument(skip(docker), level = "debug")]
+pub async fn commit_container(
+    docker: &Docker,
+    name: &str,
+    repo: &str,
+    tag: &str,
+) -> Result<String> {
+    let options = CommitContainerOptionsBuilder::default()
+        .container(name)
+        .repo(repo)
+        .tag(tag)
+        .pause(true)
+        .build();
+
+    let response = docker
+        .commit_container(options, ContainerConfig::default())
+        .await
+        .with_context(|| format!("Failed to commit container {name} to {repo}:{tag}"))?;
+
+    tracing::info!(container_name = %name, image = %format!("{repo}:{tag}"), "Committed container");
+    Ok(response.id)
+}
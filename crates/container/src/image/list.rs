@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use bollard::Docker;
 use bollard::query_parameters::ListImagesOptions;
@@ -24,6 +26,27 @@ pub async fn get_local_images(docker_conn: &Docker) -> Result<Vec<String>> {
     Ok(image_list)
 }
 
+/// Get the size in bytes of each local image tag
+#[instrument(skip(docker_conn), level = "debug")]
+pub async fn get_local_image_sizes(docker_conn: &Docker) -> Result<HashMap<String, u64>> {
+    let container_images = docker_conn
+        .list_images(Some(ListImagesOptions {
+            all: true,
+            ..Default::default()
+        }))
+        .await?;
+
+    let mut sizes = HashMap::new();
+    for image in container_images {
+        let size = u64::try_from(image.size).unwrap_or_default();
+        for tag in image.repo_tags {
+            sizes.insert(tag, size);
+        }
+    }
+
+    Ok(sizes)
+}
+
 /// List all container images (logs to console)
 #[instrument(skip(docker_conn), level = "debug")]
 pub async fn list_images(docker_conn: &Docker) -> Result<()> {
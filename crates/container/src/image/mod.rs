@@ -1,9 +1,13 @@
+mod commit;
 mod list;
 mod load;
 mod pull;
+mod remove;
 mod save;
 
-pub use list::{get_local_images, list_images};
+pub use commit::commit_container;
+pub use list::{get_local_image_sizes, get_local_images, list_images};
 pub use load::load_image;
 pub use pull::{pull_container_image, pull_image};
+pub use remove::remove_image;
 pub use save::save_container_image;
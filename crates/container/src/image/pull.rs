@@ -2,20 +2,27 @@ use anyhow::Result;
 use async_compression::Level;
 use async_compression::tokio::write::GzipEncoder;
 use bollard::Docker;
+use bollard::auth::DockerCredentials;
 use bollard::query_parameters::CreateImageOptionsBuilder;
 use futures_util::StreamExt;
 use tokio::io::AsyncWriteExt;
 use tracing::instrument;
 
-use shared::data::{Config as SherpaConfig, ContainerImage};
+use shared::data::{Config as SherpaConfig, ContainerImage, RegistryAuth, image_registry};
 
 /// Pull a container image from an OCI registry and save to local Docker daemon
 /// Similar to `docker pull` command
 ///
+/// `auth` is sent to the registry of `repo` for private images.
 /// The optional `on_progress` callback is invoked with human-readable status
 /// strings as the pull progresses.
-#[instrument(skip(on_progress), level = "debug")]
-pub async fn pull_image<F>(repo: &str, tag: &str, on_progress: F) -> Result<()>
+#[instrument(skip(auth, on_progress), level = "debug")]
+pub async fn pull_image<F>(
+    repo: &str,
+    tag: &str,
+    auth: Option<&RegistryAuth>,
+    on_progress: F,
+) -> Result<()>
 where
     F: Fn(&str),
 {
@@ -33,7 +40,13 @@ where
         .build();
 
     // Pull the image - this saves directly to Docker's local image store
-    let mut pull_stream = docker.create_image(Some(options), None, None);
+    let credentials = auth.map(|auth| DockerCredentials {
+        username: Some(auth.username.clone()),
+        password: Some(auth.password.clone()),
+        serveraddress: Some(image_registry(repo)),
+        ..Default::default()
+    });
+    let mut pull_stream = docker.create_image(Some(options), None, credentials);
 
     while let Some(pull_result) = pull_stream.next().await {
         match pull_result {
@@ -0,0 +1,20 @@
+use anyhow::{Context, Result};
+use bollard::Docker;
+use bollard::query_parameters::RemoveImageOptionsBuilder;
+use tracing::instrument;
+
+/// Remove a local image tag.
+///
+/// Equivalent to `docker rmi <image>`. Docker refuses while any container,
+/// running or stopped, still uses the image.
+#[instrument(skip(docker), level = "debug")]
+pub async fn remove_image(docker: &Docker, image: &str) -> Result<()> {
+    let options = RemoveImageOptionsBuilder::default().force(false).build();
+    docker
+        .remove_image(image, Some(options), None)
+        .await
+        .with_context(|| format!("Failed to remove image {image}"))?;
+
+    tracing::info!(image = %image, "Removed image");
+    Ok(())
+}
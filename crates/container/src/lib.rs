@@ -11,9 +11,9 @@ pub use connect::docker_connection;
 
 // Re-export container operations
 pub use container::{
-    exec_container, exec_container_detached, exec_container_with_retry, get_container_pid,
-    kill_container, list_containers, pause_container, remove_container, run_container,
-    start_container, stop_container, unpause_container,
+    exec_container, exec_container_detached, exec_container_with_retry, get_container_image,
+    get_container_pid, kill_container, list_containers, pause_container, remove_container,
+    run_container, start_container, stop_container, unpause_container,
 };
 
 // Re-export network operations
@@ -24,8 +24,8 @@ pub use network::{
 
 // Re-export image operations
 pub use image::{
-    get_local_images, list_images, load_image, pull_container_image, pull_image,
-    save_container_image,
+    commit_container, get_local_image_sizes, get_local_images, list_images, load_image,
+    pull_container_image, pull_image, remove_image, save_container_image,
 };
 
 // Re-export Docker type for convenience
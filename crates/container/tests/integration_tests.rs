@@ -350,7 +350,7 @@ async fn test_pull_image() -> Result<()> {
     use container::pull_image;
 
     let count = std::sync::atomic::AtomicU32::new(0);
-    pull_image("alpine", "latest", |_msg| {
+    pull_image("alpine", "latest", None, |_msg| {
         count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     })
     .await?;
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result, anyhow};
+use shared::data::DbApiToken;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::ApiTokenRow;
+
+/// Validate token name format according to schema constraints
+///
+/// Rules:
+/// - Minimum 3 characters
+/// - Only alphanumeric characters plus ._-
+fn validate_api_token_name(name: &str) -> Result<()> {
+    if name.len() < 3 {
+        return Err(anyhow!(
+            "Token name must be at least 3 characters long, got: {}",
+            name.len()
+        ));
+    }
+
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-');
+
+    if !valid_chars {
+        return Err(anyhow!(
+            "Token name can only contain alphanumeric characters and ._- symbols. Got: '{}'",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Create a new API token in the database
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `token` - DbApiToken to store (its `id` is ignored)
+///
+/// # Returns
+/// The created DbApiToken with assigned ID
+///
+/// # Errors
+/// - If token name validation fails
+/// - If no scope is granted
+/// - If the user already has a token with this name (unique constraint violation)
+/// - If there's a database error during creation
+#[instrument(skip(db, token), fields(name = %token.name), level = "debug")]
+pub async fn create_api_token(db: &Arc<Surreal<Any>>, token: DbApiToken) -> Result<DbApiToken> {
+    validate_api_token_name(&token.name)?;
+    if token.scopes.is_empty() {
+        return Err(anyhow!(
+            "Token '{}' must have at least one scope",
+            token.name
+        ));
+    }
+
+    let token = DbApiToken { id: None, ..token };
+
+    let created: Option<ApiTokenRow> = db
+        .create("api_token")
+        .content(ApiTokenRow::try_from(&token)?)
+        .await
+        .context(format!("Failed to create API token: '{}'", token.name))?;
+
+    created
+        .map(DbApiToken::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("API token was not created: '{}'", token.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_api_token_name() {
+        assert!(validate_api_token_name("ci").is_err());
+        assert!(validate_api_token_name("gitlab-ci.main_1").is_ok());
+        assert!(validate_api_token_name("ci token").is_err());
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use shared::data::RecordId;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::{ApiTokenRow, to_surreal_id};
+
+/// Delete (revoke) a user's API token by name
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `user_id` - RecordId of the user owning the token
+/// * `name` - Token name
+///
+/// # Returns
+/// `true` if a token was deleted, `false` if the user has no token with this name
+///
+/// # Errors
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn delete_api_token(
+    db: &Arc<Surreal<Any>>,
+    user_id: &RecordId,
+    name: &str,
+) -> Result<bool> {
+    let mut response = db
+        .query("DELETE api_token WHERE user = $user_id AND name = $name RETURN BEFORE")
+        .bind(("user_id", to_surreal_id(user_id)))
+        .bind(("name", name.to_string()))
+        .await
+        .context(format!(
+            "Failed to delete API token: user_id={:?}, name={}",
+            user_id, name
+        ))?;
+
+    let deleted: Vec<ApiTokenRow> = response.take(0)?;
+    Ok(!deleted.is_empty())
+}
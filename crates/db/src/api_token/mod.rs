@@ -0,0 +1,22 @@
+//! Personal API token operations
+//!
+//! This module provides create, read, update, and delete operations
+//! for API token records. Tokens are looked up by the SHA-256 digest
+//! of the token presented by a client.
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+// Public exports - CREATE operations
+pub use create::create_api_token;
+
+// Public exports - READ operations
+pub use read::{get_api_token_by_hash, list_api_tokens_by_user};
+
+// Public exports - UPDATE operations
+pub use update::touch_api_token;
+
+// Public exports - DELETE operations
+pub use delete::delete_api_token;
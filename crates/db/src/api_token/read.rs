@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use shared::data::{DbApiToken, RecordId};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::{ApiTokenRow, to_surreal_id};
+
+/// Get an API token by the digest of the token
+///
+/// Expiry is not checked here, callers must compare `expires_at`.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `token_hash` - Hex encoded SHA-256 digest of the token
+///
+/// # Returns
+/// The DbApiToken if one matches, `None` otherwise
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db, token_hash), level = "debug")]
+pub async fn get_api_token_by_hash(
+    db: &Arc<Surreal<Any>>,
+    token_hash: &str,
+) -> Result<Option<DbApiToken>> {
+    let mut response = db
+        .query("SELECT * FROM ONLY api_token WHERE token_hash = $token_hash LIMIT 1")
+        .bind(("token_hash", token_hash.to_string()))
+        .await
+        .context("Failed to query API token from database")?;
+
+    let token: Option<ApiTokenRow> = response.take(0)?;
+    token.map(DbApiToken::try_from).transpose()
+}
+
+/// List the API tokens of a user
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `user_id` - RecordId of the user
+///
+/// # Returns
+/// Vector of DbApiToken ordered by name
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_api_tokens_by_user(
+    db: &Arc<Surreal<Any>>,
+    user_id: &RecordId,
+) -> Result<Vec<DbApiToken>> {
+    let mut response = db
+        .query("SELECT * FROM api_token WHERE user = $user_id ORDER BY name")
+        .bind(("user_id", to_surreal_id(user_id)))
+        .await
+        .context(format!(
+            "Failed to list API tokens from database: user_id={:?}",
+            user_id
+        ))?;
+
+    let tokens: Vec<ApiTokenRow> = response.take(0)?;
+    tokens.into_iter().map(DbApiToken::try_from).collect()
+}
@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use shared::data::RecordId;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::{ApiTokenRow, to_surreal_id};
+
+/// Record that an API token was used to authenticate
+///
+/// Sets `last_used_at` to the current time.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `token_id` - RecordId of the API token
+///
+/// # Errors
+/// - If there's a database error during the update
+#[instrument(skip(db), level = "debug")]
+pub async fn touch_api_token(db: &Arc<Surreal<Any>>, token_id: &RecordId) -> Result<()> {
+    let mut response = db
+        .query("UPDATE $token_id SET last_used_at = time::now()")
+        .bind(("token_id", to_surreal_id(token_id)))
+        .await
+        .context(format!(
+            "Failed to update API token: token_id={:?}",
+            token_id
+        ))?;
+
+    let _: Vec<ApiTokenRow> = response.take(0)?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+//! Table export and import for server backups
+//!
+//! Records are exported as SurrealQL `UPSERT` statements, one per record, so
+//! record links and datetimes keep their types on restore. Computed fields
+//! are left out because the database derives them. An `INSERT` would fail
+//! in the restore transaction on the IDs of the records it just deleted.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb_types::{ToSql, Value};
+use tracing::instrument;
+
+/// A table included in backups
+#[derive(Debug, Clone, Copy)]
+pub struct BackupTable {
+    pub name: &'static str,
+    /// Computed fields, left out of the export
+    pub computed: &'static [&'static str],
+}
+
+/// Tables included in backups, in restore (dependency) order
+pub const BACKUP_TABLES: &[BackupTable] = &[
+    BackupTable {
+        name: "user",
+        computed: &["labs"],
+    },
+    BackupTable {
+        name: "node_image",
+        computed: &["nodes"],
+    },
+    BackupTable {
+        name: "lab",
+        computed: &["nodes", "links", "bridges"],
+    },
+    BackupTable {
+        name: "node",
+        computed: &["links", "bridges"],
+    },
+    BackupTable {
+        name: "link",
+        computed: &[],
+    },
+    BackupTable {
+        name: "bridge",
+        computed: &[],
+    },
+    BackupTable {
+        name: "team",
+        computed: &[],
+    },
+    BackupTable {
+        name: "lab_share",
+        computed: &[],
+    },
+    BackupTable {
+        name: "api_token",
+        computed: &[],
+    },
+    BackupTable {
+        name: "registry_credential",
+        computed: &[],
+    },
+    BackupTable {
+        name: "schema_migration",
+        computed: &[],
+    },
+];
+
+/// Export every record of a table as a SurrealQL `UPSERT` statement
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn export_table(db: &Arc<Surreal<Any>>, table: &BackupTable) -> Result<Vec<String>> {
+    let omit = if table.computed.is_empty() {
+        String::new()
+    } else {
+        format!(" OMIT {}", table.computed.join(", "))
+    };
+
+    let mut response = db
+        .query(format!("SELECT *{omit} FROM {} ORDER BY id", table.name))
+        .await
+        .context(format!("Failed to export table: {}", table.name))?;
+
+    let records: Vec<Value> = response.take(0)?;
+    Ok(records
+        .iter()
+        .map(|record| format!("UPSERT {} CONTENT {};", table.name, record.to_sql()))
+        .collect())
+}
+
+/// Count the records of a table
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn count_table(db: &Arc<Surreal<Any>>, table: &str) -> Result<usize> {
+    let mut response = db
+        .query(format!("SELECT count() FROM {table} GROUP ALL"))
+        .await
+        .context(format!("Failed to count table: {table}"))?;
+
+    let count: Option<usize> = response.take("count")?;
+    Ok(count.unwrap_or(0))
+}
+
+/// List the record IDs of a table in SurrealQL form (e.g. `node_image:abc`)
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_record_ids(db: &Arc<Surreal<Any>>, table: &str) -> Result<Vec<String>> {
+    let mut response = db
+        .query(format!("SELECT VALUE id FROM {table}"))
+        .await
+        .context(format!("Failed to list record IDs of table: {table}"))?;
+
+    let ids: Vec<Value> = response.take(0)?;
+    Ok(ids.iter().map(ToSql::to_sql).collect())
+}
+
+/// List the distinct values of a record link field in SurrealQL form
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_record_links(
+    db: &Arc<Surreal<Any>>,
+    table: &str,
+    field: &str,
+) -> Result<Vec<String>> {
+    let mut response = db
+        .query(format!(
+            "SELECT VALUE {field} FROM {table} WHERE {field} != NONE"
+        ))
+        .await
+        .context(format!("Failed to list {field} links of table: {table}"))?;
+
+    let links: Vec<Value> = response.take(0)?;
+    let mut links: Vec<String> = links.iter().map(ToSql::to_sql).collect();
+    links.sort();
+    links.dedup();
+    Ok(links)
+}
+
+/// Replace the records of the given tables in one transaction
+///
+/// Tables are cleared in reverse order, so cascading deletes never remove
+/// restored records, and then filled in the given order. Tables not listed
+/// are left untouched.
+///
+/// # Errors
+/// - If any statement fails, in which case nothing is changed
+#[instrument(skip(db, tables), level = "debug")]
+pub async fn restore_tables(db: &Arc<Surreal<Any>>, tables: &[(&str, Vec<String>)]) -> Result<()> {
+    let mut query = String::from("BEGIN TRANSACTION;\n");
+    for (name, _) in tables.iter().rev() {
+        query.push_str(&format!("DELETE {name};\n"));
+    }
+    for (_, statements) in tables {
+        for statement in statements {
+            query.push_str(statement);
+            query.push('\n');
+        }
+    }
+    query.push_str("COMMIT TRANSACTION;\n");
+
+    db.query(query)
+        .await
+        .and_then(|response| response.check())
+        .context("Failed to restore database tables")?;
+    Ok(())
+}
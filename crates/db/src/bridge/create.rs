@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use shared::data::{DbBridge, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::BridgeRow;
@@ -29,7 +29,7 @@ use crate::persistence::BridgeRow;
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
 pub async fn create_bridge(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     index: u16,
     bridge_name: String,
     network_name: String,
@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use shared::data::RecordId;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::to_surreal_id;
@@ -22,7 +22,7 @@ use crate::persistence::to_surreal_id;
 /// - If the bridge doesn't exist
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_bridge(db: &Arc<Surreal<Client>>, bridge_id: &RecordId) -> Result<()> {
+pub async fn delete_bridge(db: &Arc<Surreal<Any>>, bridge_id: &RecordId) -> Result<()> {
     let _: Option<surrealdb_types::RecordId> = db
         .delete::<Option<surrealdb_types::RecordId>>(to_surreal_id(bridge_id))
         .await
@@ -48,7 +48,7 @@ pub async fn delete_bridge(db: &Arc<Surreal<Client>>, bridge_id: &RecordId) -> R
 /// # Errors
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_bridges(db: &Arc<Surreal<Client>>, lab_id: &RecordId) -> Result<()> {
+pub async fn delete_lab_bridges(db: &Arc<Surreal<Any>>, lab_id: &RecordId) -> Result<()> {
     db.query("DELETE FROM bridge WHERE lab = $lab_id")
         .bind(("lab_id", to_surreal_id(lab_id)))
         .await
@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use shared::data::{DbBridge, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{BridgeRow, to_surreal_id};
@@ -20,7 +20,7 @@ use crate::persistence::{BridgeRow, to_surreal_id};
 /// - If the bridge doesn't exist
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn get_bridge(db: &Arc<Surreal<Client>>, bridge_id: &RecordId) -> Result<DbBridge> {
+pub async fn get_bridge(db: &Arc<Surreal<Any>>, bridge_id: &RecordId) -> Result<DbBridge> {
     let bridge: Option<BridgeRow> = db
         .select::<Option<BridgeRow>>(to_surreal_id(bridge_id))
         .await
@@ -47,7 +47,7 @@ pub async fn get_bridge(db: &Arc<Surreal<Client>>, bridge_id: &RecordId) -> Resu
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
 pub async fn get_bridge_by_index(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     index: u16,
     lab_id: &RecordId,
 ) -> Result<DbBridge> {
@@ -81,7 +81,7 @@ pub async fn get_bridge_by_index(
 /// # Errors
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn list_bridges(db: &Arc<Surreal<Client>>, lab_id: &RecordId) -> Result<Vec<DbBridge>> {
+pub async fn list_bridges(db: &Arc<Surreal<Any>>, lab_id: &RecordId) -> Result<Vec<DbBridge>> {
     let mut result = db
         .query("SELECT * FROM bridge WHERE lab = $lab_id ORDER BY index")
         .bind(("lab_id", to_surreal_id(lab_id)))
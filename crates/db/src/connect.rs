@@ -1,29 +1,32 @@
 use std::ops::Deref;
 use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::{Client, Ws};
+use surrealdb::engine::any::{self, Any};
 use surrealdb::opt::auth::Root;
 use tracing::instrument;
 
+use shared::data::DatabaseEngine;
 use shared::konst::SHERPA_DB_USER;
 
 /// Shared database connection handle.
 ///
 /// The concrete SurrealDB client stays owned and named by this crate so callers
-/// do not need a direct SurrealDB dependency.
+/// do not need a direct SurrealDB dependency. The handle is the same for a
+/// remote server and an embedded engine.
 #[derive(Clone)]
-pub struct Database(Arc<Surreal<Client>>);
+pub struct Database(Arc<Surreal<Any>>);
 
 impl Deref for Database {
-    type Target = Arc<Surreal<Client>>;
+    type Target = Arc<Surreal<Any>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
+/// Connect to a separate SurrealDB server over WebSocket.
 #[instrument(skip(password), level = "debug")]
 pub async fn connect(
     host: &str,
@@ -32,7 +35,7 @@ pub async fn connect(
     database: &str,
     password: &str,
 ) -> Result<Database> {
-    let db = Surreal::new::<Ws>(format!("{host}:{port}/rpc"))
+    let db = any::connect(format!("ws://{host}:{port}"))
         .await
         .context("Failed to connect to SurrealDB")?;
 
@@ -43,6 +46,37 @@ pub async fn connect(
     .await
     .context("There was a problem with database authentication")?;
 
+    use_database(db, namespace, database).await
+}
+
+/// Open a database embedded in the current process.
+///
+/// `path` is the data directory of the on-disk engines and is ignored by the
+/// in-memory engine. Embedded engines have no users, so there is no sign in.
+/// Each engine is compiled in by the crate feature of the same name, such as
+/// `kv-rocksdb`; opening an engine that is not compiled in fails.
+#[instrument(level = "debug")]
+pub async fn connect_embedded(
+    engine: DatabaseEngine,
+    path: &str,
+    namespace: &str,
+    database: &str,
+) -> Result<Database> {
+    let endpoint = match engine {
+        DatabaseEngine::Memory => "mem://".to_string(),
+        DatabaseEngine::Rocksdb => format!("rocksdb://{path}"),
+        DatabaseEngine::Surrealkv => format!("surrealkv://{path}"),
+        DatabaseEngine::Remote => bail!("The remote database engine is not embedded"),
+    };
+
+    let db = any::connect(endpoint)
+        .await
+        .context(format!("Failed to open embedded {engine:?} database"))?;
+
+    use_database(db, namespace, database).await
+}
+
+async fn use_database(db: Surreal<Any>, namespace: &str, database: &str) -> Result<Database> {
     db.use_ns(namespace)
         .use_db(database)
         .await
@@ -1,7 +1,8 @@
 use anyhow::{Context, Result, anyhow};
+use shared::data::LabRole;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use surrealdb_types::SurrealValue;
 use tracing::instrument;
 
@@ -21,7 +22,7 @@ use tracing::instrument;
 /// - If the user record cannot be found
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn get_lab_owner_username(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<String> {
+pub async fn get_lab_owner_username(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<String> {
     let mut response = db
         .query("SELECT user.username AS username FROM ONLY lab WHERE lab_id = $lab_id")
         .bind(("lab_id", lab_id.to_string()))
@@ -41,3 +42,51 @@ pub async fn get_lab_owner_username(db: &Arc<Surreal<Client>>, lab_id: &str) ->
         .map(|r| r.username)
         .ok_or_else(|| anyhow!("Lab with lab_id not found or owner not found: {}", lab_id))
 }
+
+/// Get the role a user has on a lab
+///
+/// The lab owner always has the `owner` role. Other users get the highest
+/// role granted by a share, either directly or through one of their teams.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `lab_id` - The unique lab_id string
+/// * `username` - The username to resolve the role for
+///
+/// # Returns
+/// The user's role on the lab, or `None` if the lab is not shared with them
+///
+/// # Errors
+/// - If lab with lab_id not found
+/// - If a stored role cannot be parsed
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn get_lab_role(
+    db: &Arc<Surreal<Any>>,
+    lab_id: &str,
+    username: &str,
+) -> Result<Option<LabRole>> {
+    if get_lab_owner_username(db, lab_id).await? == username {
+        return Ok(Some(LabRole::Owner));
+    }
+
+    let mut response = db
+        .query(
+            "SELECT VALUE role FROM lab_share WHERE lab.lab_id = $lab_id \
+             AND (user.username = $username OR team.members.username CONTAINS $username)",
+        )
+        .bind(("lab_id", lab_id.to_string()))
+        .bind(("username", username.to_string()))
+        .await
+        .context(format!(
+            "Failed to query lab shares from database: lab_id={}, username={}",
+            lab_id, username
+        ))?;
+
+    let roles: Vec<String> = response.take(0)?;
+    let roles = roles
+        .iter()
+        .map(|role| role.parse::<LabRole>())
+        .collect::<Result<Vec<_>>>()?;
+    Ok(roles.into_iter().max())
+}
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbLab, DbUser, LabState};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::helpers::get_user_id;
@@ -67,7 +67,7 @@ pub fn validate_lab_id(lab_id: &str) -> Result<()> {
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip(db), level = "debug")]
 pub async fn create_lab(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     name: &str,
     lab_id: &str,
     user: &DbUser,
@@ -128,7 +128,7 @@ pub async fn create_lab(
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn upsert_lab(db: &Arc<Surreal<Client>>, lab: DbLab) -> Result<DbLab> {
+pub async fn upsert_lab(db: &Arc<Surreal<Any>>, lab: DbLab) -> Result<DbLab> {
     // Validate lab_id format
     validate_lab_id(&lab.lab_id)?;
 
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::RecordId;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::helpers::get_lab_id;
@@ -27,7 +27,7 @@ use crate::persistence::{LabRow, LinkRow, NodeRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
+pub async fn delete_lab(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<()> {
     let lab = get_lab(db, lab_id).await?;
     let lab_record_id = get_lab_id(&lab)?;
 
@@ -54,7 +54,7 @@ pub async fn delete_lab(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_lab_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     let _deleted: Option<LabRow> = db
         .delete(to_surreal_id(&id))
         .await
@@ -78,7 +78,7 @@ pub async fn delete_lab_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_nodes(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
+pub async fn delete_lab_nodes(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<()> {
     let lab = get_lab(db, lab_id).await?;
     let lab_record_id = get_lab_id(&lab)?;
 
@@ -107,7 +107,7 @@ pub async fn delete_lab_nodes(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_links(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
+pub async fn delete_lab_links(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<()> {
     let lab = get_lab(db, lab_id).await?;
     let lab_record_id = get_lab_id(&lab)?;
 
@@ -136,7 +136,7 @@ pub async fn delete_lab_links(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_cascade(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
+pub async fn delete_lab_cascade(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<()> {
     delete_lab(db, lab_id).await
 }
 
@@ -157,7 +157,7 @@ pub async fn delete_lab_cascade(db: &Arc<Surreal<Client>>, lab_id: &str) -> Resu
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_lab_safe(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<()> {
+pub async fn delete_lab_safe(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<()> {
     // Get the lab to verify it exists
     let lab = get_lab(db, lab_id).await?;
     let lab_record_id = get_lab_id(&lab)?;
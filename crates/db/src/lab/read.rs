@@ -5,7 +5,7 @@ use anyhow::{Context, Result, anyhow};
 use ipnet::{Ipv4Net, Ipv6Net};
 use shared::data::{DbLab, RecordId};
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{LabRow, to_surreal_id};
@@ -24,7 +24,7 @@ use crate::persistence::{LabRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_lab(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<DbLab> {
+pub async fn get_lab(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<DbLab> {
     let mut response = db
         .query("SELECT * FROM ONLY lab WHERE lab_id = $lab_id")
         .bind(("lab_id", lab_id.to_string()))
@@ -52,7 +52,7 @@ pub async fn get_lab(db: &Arc<Surreal<Client>>, lab_id: &str) -> Result<DbLab> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_lab_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbLab> {
+pub async fn get_lab_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<DbLab> {
     let lab: Option<LabRow> = db
         .select(to_surreal_id(&id))
         .await
@@ -79,7 +79,7 @@ pub async fn get_lab_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<Db
 ///
 #[instrument(skip(db), level = "debug")]
 pub async fn get_lab_by_name_and_user(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     name: &str,
     user_id: RecordId,
 ) -> Result<DbLab> {
@@ -109,7 +109,7 @@ pub async fn get_lab_by_name_and_user(
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_labs(db: &Arc<Surreal<Client>>) -> Result<Vec<DbLab>> {
+pub async fn list_labs(db: &Arc<Surreal<Any>>) -> Result<Vec<DbLab>> {
     let labs: Vec<LabRow> = db
         .select("lab")
         .await
@@ -131,7 +131,7 @@ pub async fn list_labs(db: &Arc<Surreal<Client>>) -> Result<Vec<DbLab>> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_labs_by_user(db: &Arc<Surreal<Client>>, user_id: RecordId) -> Result<Vec<DbLab>> {
+pub async fn list_labs_by_user(db: &Arc<Surreal<Any>>, user_id: RecordId) -> Result<Vec<DbLab>> {
     let mut response = db
         .query("SELECT * FROM lab WHERE user = $user_id")
         .bind(("user_id", to_surreal_id(&user_id)))
@@ -154,7 +154,7 @@ pub async fn list_labs_by_user(db: &Arc<Surreal<Client>>, user_id: RecordId) ->
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_labs(db: &Arc<Surreal<Client>>) -> Result<usize> {
+pub async fn count_labs(db: &Arc<Surreal<Any>>) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM lab GROUP ALL")
         .await
@@ -177,7 +177,7 @@ pub async fn count_labs(db: &Arc<Surreal<Client>>) -> Result<usize> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_labs_by_user(db: &Arc<Surreal<Client>>, user_id: RecordId) -> Result<usize> {
+pub async fn count_labs_by_user(db: &Arc<Surreal<Any>>, user_id: RecordId) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM lab WHERE user = $user_id GROUP ALL")
         .bind(("user_id", to_surreal_id(&user_id)))
@@ -194,7 +194,7 @@ pub async fn count_labs_by_user(db: &Arc<Surreal<Client>>, user_id: RecordId) ->
 /// in use by existing labs. Used when allocating a new loopback subnet
 /// to avoid collisions.
 #[instrument(skip(db), level = "debug")]
-pub async fn get_used_loopback_networks(db: &Arc<Surreal<Client>>) -> Result<Vec<Ipv4Net>> {
+pub async fn get_used_loopback_networks(db: &Arc<Surreal<Any>>) -> Result<Vec<Ipv4Net>> {
     let labs: Vec<LabRow> = db
         .select("lab")
         .await
@@ -220,7 +220,7 @@ pub async fn get_used_loopback_networks(db: &Arc<Surreal<Client>>) -> Result<Vec
 /// in use by existing labs. Used when allocating a new management subnet
 /// to avoid collisions.
 #[instrument(skip(db), level = "debug")]
-pub async fn get_used_management_networks(db: &Arc<Surreal<Client>>) -> Result<Vec<Ipv4Net>> {
+pub async fn get_used_management_networks(db: &Arc<Surreal<Any>>) -> Result<Vec<Ipv4Net>> {
     let labs: Vec<LabRow> = db
         .select("lab")
         .await
@@ -245,7 +245,7 @@ pub async fn get_used_management_networks(db: &Arc<Surreal<Client>>) -> Result<V
 /// Returns a vector of `Ipv6Net` representing the IPv6 management subnets
 /// in use by existing labs. Labs without IPv6 are skipped.
 #[instrument(skip(db), level = "debug")]
-pub async fn get_used_ipv6_management_networks(db: &Arc<Surreal<Client>>) -> Result<Vec<Ipv6Net>> {
+pub async fn get_used_ipv6_management_networks(db: &Arc<Surreal<Any>>) -> Result<Vec<Ipv6Net>> {
     let labs: Vec<LabRow> = db
         .select("lab")
         .await
@@ -272,7 +272,7 @@ pub async fn get_used_ipv6_management_networks(db: &Arc<Surreal<Client>>) -> Res
 /// Returns a vector of `Ipv6Net` representing the IPv6 loopback subnets
 /// in use by existing labs. Labs without IPv6 are skipped.
 #[instrument(skip(db), level = "debug")]
-pub async fn get_used_ipv6_loopback_networks(db: &Arc<Surreal<Client>>) -> Result<Vec<Ipv6Net>> {
+pub async fn get_used_ipv6_loopback_networks(db: &Arc<Surreal<Any>>) -> Result<Vec<Ipv6Net>> {
     let labs: Vec<LabRow> = db
         .select("lab")
         .await
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbLab, LabState, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::lab::validate_lab_id;
@@ -29,7 +29,7 @@ use crate::persistence::{LabRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn update_lab(db: &Arc<Surreal<Client>>, lab: DbLab) -> Result<DbLab> {
+pub async fn update_lab(db: &Arc<Surreal<Any>>, lab: DbLab) -> Result<DbLab> {
     // Require id field for updates
     let id = lab
         .id
@@ -84,7 +84,7 @@ pub async fn update_lab(db: &Arc<Surreal<Client>>, lab: DbLab) -> Result<DbLab>
 /// The updated DbLab record
 #[instrument(skip(db), level = "debug")]
 pub async fn update_lab_state(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     lab_id: RecordId,
     state: LabState,
 ) -> Result<DbLab> {
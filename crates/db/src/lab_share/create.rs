@@ -0,0 +1,119 @@
+use anyhow::{Context, Result, anyhow};
+use jiff::Timestamp;
+use shared::data::{DbLabShare, LabRole, RecordId};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::{LabShareRow, to_surreal_id};
+
+/// Name of the lab_share field that references the grantee's table.
+pub(super) fn grantee_field(grantee: &RecordId) -> Result<&'static str> {
+    match grantee.table.as_str() {
+        "user" => Ok("user"),
+        "team" => Ok("team"),
+        other => Err(anyhow!(
+            "Labs can only be shared with users or teams, got record from table '{}'",
+            other
+        )),
+    }
+}
+
+/// Share a lab with a user or a team
+///
+/// If the grantee already has a share on the lab its role is replaced,
+/// so a grantee never holds more than one share per lab.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `lab` - RecordId of the lab to share
+/// * `grantee` - RecordId of a `user` or `team` record
+/// * `role` - Role to grant (`viewer` or `operator`)
+///
+/// # Returns
+/// The created or updated DbLabShare
+///
+/// # Errors
+/// - If the grantee is not a user or team record
+/// - If the role is `owner` (ownership cannot be shared)
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn upsert_lab_share(
+    db: &Arc<Surreal<Any>>,
+    lab: &RecordId,
+    grantee: &RecordId,
+    role: LabRole,
+) -> Result<DbLabShare> {
+    let field = grantee_field(grantee)?;
+    if !LabRole::shareable().contains(&role) {
+        return Err(anyhow!("Role '{}' cannot be granted through a share", role));
+    }
+
+    let mut response = db
+        .query(format!(
+            "SELECT * FROM lab_share WHERE lab = $lab AND {field} = $grantee"
+        ))
+        .bind(("lab", to_surreal_id(lab)))
+        .bind(("grantee", to_surreal_id(grantee)))
+        .await
+        .context(format!(
+            "Failed to query lab share: lab={:?}, grantee={:?}",
+            lab, grantee
+        ))?;
+    let existing: Vec<LabShareRow> = response.take(0)?;
+
+    let share = match existing.into_iter().next() {
+        Some(row) => DbLabShare {
+            role,
+            ..DbLabShare::try_from(row)?
+        },
+        None => DbLabShare {
+            id: None,
+            lab: lab.clone(),
+            user: (field == "user").then(|| grantee.clone()),
+            team: (field == "team").then(|| grantee.clone()),
+            role,
+            created_at: Timestamp::now(),
+        },
+    };
+    let row = LabShareRow::try_from(&share)?;
+
+    let saved: Option<LabShareRow> = match &share.id {
+        Some(id) => db
+            .update(to_surreal_id(id))
+            .content(row)
+            .await
+            .context(format!("Failed to update lab share: {:?}", id))?,
+        None => db.create("lab_share").content(row).await.context(format!(
+            "Failed to create lab share: lab={:?}, grantee={:?}",
+            lab, grantee
+        ))?,
+    };
+
+    saved.map(DbLabShare::try_from).transpose()?.ok_or_else(|| {
+        anyhow!(
+            "Lab share was not saved: lab={:?}, grantee={:?}",
+            lab,
+            grantee
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grantee_field() {
+        assert_eq!(
+            grantee_field(&RecordId::new("user", "alice")).unwrap(),
+            "user"
+        );
+        assert_eq!(
+            grantee_field(&RecordId::new("team", "students")).unwrap(),
+            "team"
+        );
+        assert!(grantee_field(&RecordId::new("lab", "lab1")).is_err());
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use shared::data::RecordId;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use super::create::grantee_field;
+use crate::persistence::{LabShareRow, to_surreal_id};
+
+/// Remove the share of a lab for a user or a team
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `lab` - RecordId of the shared lab
+/// * `grantee` - RecordId of the `user` or `team` record
+///
+/// # Returns
+/// `true` if a share was removed, `false` if the grantee had no share
+///
+/// # Errors
+/// - If the grantee is not a user or team record
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn delete_lab_share(
+    db: &Arc<Surreal<Any>>,
+    lab: &RecordId,
+    grantee: &RecordId,
+) -> Result<bool> {
+    let field = grantee_field(grantee)?;
+
+    let mut response = db
+        .query(format!(
+            "DELETE lab_share WHERE lab = $lab AND {field} = $grantee RETURN BEFORE"
+        ))
+        .bind(("lab", to_surreal_id(lab)))
+        .bind(("grantee", to_surreal_id(grantee)))
+        .await
+        .context(format!(
+            "Failed to delete lab share: lab={:?}, grantee={:?}",
+            lab, grantee
+        ))?;
+
+    let deleted: Vec<LabShareRow> = response.take(0)?;
+    Ok(!deleted.is_empty())
+}
@@ -0,0 +1,18 @@
+//! Lab share CRUD operations
+//!
+//! This module provides create, read, and delete operations for lab
+//! share records. A share grants a user or a team a role on a lab
+//! owned by someone else.
+
+mod create;
+mod delete;
+mod read;
+
+// Public exports - CREATE operations
+pub use create::upsert_lab_share;
+
+// Public exports - READ operations
+pub use read::{list_lab_shares, list_labs_shared_with_user};
+
+// Public exports - DELETE operations
+pub use delete::delete_lab_share;
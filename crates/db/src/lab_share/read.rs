@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use shared::data::{DbLab, LabShareInfo, RecordId};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb_types::SurrealValue;
+use tracing::instrument;
+
+use crate::persistence::{LabRow, to_surreal_id};
+
+/// List the shares of a lab with grantee names resolved
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `lab_id` - The unique lab_id string
+///
+/// # Returns
+/// Vector of LabShareInfo, oldest share first
+///
+/// # Errors
+/// - If a stored role cannot be parsed
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn list_lab_shares(db: &Arc<Surreal<Any>>, lab_id: &str) -> Result<Vec<LabShareInfo>> {
+    let mut response = db
+        .query(
+            "SELECT user.username AS username, team.name AS team, role, created_at \
+             FROM lab_share WHERE lab.lab_id = $lab_id ORDER BY created_at",
+        )
+        .bind(("lab_id", lab_id.to_string()))
+        .await
+        .context(format!("Failed to list shares for lab: {}", lab_id))?;
+
+    #[derive(serde::Deserialize, SurrealValue)]
+    struct ShareResult {
+        username: Option<String>,
+        team: Option<String>,
+        role: String,
+    }
+
+    let rows: Vec<ShareResult> = response.take(0)?;
+    rows.into_iter()
+        .map(|row| {
+            Ok(LabShareInfo {
+                username: row.username,
+                team: row.team,
+                role: row.role.parse()?,
+            })
+        })
+        .collect()
+}
+
+/// List labs shared with a user, directly or through one of their teams
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `user_id` - RecordId of the user
+///
+/// # Returns
+/// Vector of DbLab records ordered by name, without duplicates
+///
+/// # Errors
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn list_labs_shared_with_user(
+    db: &Arc<Surreal<Any>>,
+    user_id: &RecordId,
+) -> Result<Vec<DbLab>> {
+    let mut response = db
+        .query(
+            "SELECT * FROM lab WHERE id IN \
+             (SELECT VALUE lab FROM lab_share WHERE user = $user_id OR team.members CONTAINS $user_id) \
+             ORDER BY name",
+        )
+        .bind(("user_id", to_surreal_id(user_id)))
+        .await
+        .context(format!("Failed to list labs shared with user: {:?}", user_id))?;
+
+    let labs: Vec<LabRow> = response.take(0)?;
+    labs.into_iter().map(DbLab::try_from).collect()
+}
@@ -1,20 +1,28 @@
 #![deny(clippy::unwrap_used, clippy::expect_used)]
 #![cfg_attr(not(test), forbid(unsafe_code))]
 
+pub mod api_token;
+pub mod backup;
 pub mod bridge;
 mod connect;
 mod helpers;
 pub mod lab;
+pub mod lab_share;
 pub mod link;
+pub mod migration;
 pub mod node;
 pub mod node_image;
 mod persistence;
+pub mod registry_credential;
 pub mod schema;
 pub mod seed;
+pub mod team;
 pub mod user;
 
-pub use connect::{Database, connect};
-pub use shared::data::{DbBridge, DbLab, DbLink, DbNode, DbUser, NodeConfig};
+pub use connect::{Database, connect, connect_embedded};
+pub use shared::data::{
+    DbApiToken, DbBridge, DbLab, DbLabShare, DbLink, DbNode, DbTeam, DbUser, NodeConfig,
+};
 
 // Helper functions for extracting IDs safely
 pub use helpers::{get_image_id, get_lab_id, get_node_id, get_user_id};
@@ -23,17 +31,18 @@ pub use helpers::{get_image_id, get_lab_id, get_node_id, get_user_id};
 pub use lab::{
     count_labs, count_labs_by_user, create_lab, delete_lab, delete_lab_by_id, delete_lab_cascade,
     delete_lab_links, delete_lab_nodes, delete_lab_safe, get_lab, get_lab_by_id,
-    get_lab_by_name_and_user, get_lab_owner_username, get_used_ipv6_loopback_networks,
-    get_used_ipv6_management_networks, get_used_loopback_networks, get_used_management_networks,
-    list_labs, list_labs_by_user, update_lab, update_lab_state, upsert_lab, validate_lab_id,
+    get_lab_by_name_and_user, get_lab_owner_username, get_lab_role,
+    get_used_ipv6_loopback_networks, get_used_ipv6_management_networks, get_used_loopback_networks,
+    get_used_management_networks, list_labs, list_labs_by_user, update_lab, update_lab_state,
+    upsert_lab, validate_lab_id,
 };
 
 // Node image CRUD operations
 pub use node_image::{
     count_node_images, create_node_image, delete_node_image, get_default_node_image,
     get_node_image_by_id, get_node_image_by_model_kind_version, get_node_image_versions,
-    list_node_images, list_node_images_by_ids, list_node_images_by_kind, update_node_image,
-    upsert_node_image,
+    list_node_image_usage, list_node_images, list_node_images_by_ids, list_node_images_by_kind,
+    update_node_image, upsert_node_image,
 };
 
 // Node CRUD operations
@@ -52,15 +61,50 @@ pub use link::{
 };
 
 pub use schema::apply_schema;
+
+// Backup export and import
+pub use backup::{
+    BACKUP_TABLES, BackupTable, count_table, export_table, list_record_ids, list_record_links,
+    restore_tables,
+};
+
+// Schema migrations
+pub use migration::{
+    MigrationStatus, MigrationStep, check_schema_version, list_applied_migrations, migrate,
+    migration_status,
+};
 pub use seed::admin_user::seed_admin_user;
 
 // User CRUD operations
 pub use user::{
     count_users, create_user, delete_user, delete_user_by_username, delete_user_safe, get_user,
-    get_user_by_id, get_user_for_auth, list_users, update_user, upsert_user,
+    get_user_by_id, get_user_for_auth, list_users, provision_external_user, update_user,
+    upsert_user,
 };
 
 // Bridge CRUD operations
 pub use bridge::{
     create_bridge, delete_bridge, delete_lab_bridges, get_bridge, get_bridge_by_index, list_bridges,
 };
+
+// Team CRUD operations
+pub use team::{
+    create_team, delete_team, get_team_by_name, get_team_owner_username, list_teams, update_team,
+};
+
+// Lab share operations
+pub use lab_share::{
+    delete_lab_share, list_lab_shares, list_labs_shared_with_user, upsert_lab_share,
+};
+
+// API token operations
+pub use api_token::{
+    create_api_token, delete_api_token, get_api_token_by_hash, list_api_tokens_by_user,
+    touch_api_token,
+};
+
+// Registry credential operations
+pub use registry_credential::{
+    delete_registry_credential, get_registry_credential, list_registry_credentials,
+    upsert_registry_credential,
+};
@@ -1,8 +1,8 @@
 use anyhow::{Context, Result};
-use shared::data::{BridgeKind, DbLink, RecordId};
+use shared::data::{BridgeKind, DbLink, LinkFilter, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::LinkRow;
@@ -33,7 +33,7 @@ use crate::persistence::LinkRow;
 #[allow(clippy::too_many_arguments)]
 #[instrument(skip(db), level = "debug")]
 pub async fn create_link(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     index: u16,
     kind: BridgeKind,
     node_a_id: RecordId,
@@ -68,6 +68,7 @@ pub async fn create_link(
         loss_percent: 0.0,
         reorder_percent: 0.0,
         corrupt_percent: 0.0,
+        filter: LinkFilter::default(),
     };
     let link: Option<LinkRow> = db
         .create("link")
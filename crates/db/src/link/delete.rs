@@ -2,7 +2,7 @@ use anyhow::{Context, Result};
 use shared::data::RecordId;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::link::read::get_link;
@@ -19,7 +19,7 @@ use crate::persistence::{LinkRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_link(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_link(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // Verify link exists
     let _ = get_link(db, id.clone()).await?;
 
@@ -41,7 +41,7 @@ pub async fn delete_link(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()>
 /// - If link not found
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_link_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_link_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     delete_link(db, id).await
 }
 
@@ -57,7 +57,7 @@ pub async fn delete_link_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Resul
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<()> {
+pub async fn delete_links_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<()> {
     let _deleted: Vec<LinkRow> = db
         .query("DELETE link WHERE lab = $lab_id")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -81,7 +81,7 @@ pub async fn delete_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) ->
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_links_by_node(db: &Arc<Surreal<Client>>, node_id: RecordId) -> Result<()> {
+pub async fn delete_links_by_node(db: &Arc<Surreal<Any>>, node_id: RecordId) -> Result<()> {
     let _deleted: Vec<LinkRow> = db
         .query("DELETE link WHERE node_a = $node_id OR node_b = $node_id")
         .bind(("node_id", to_surreal_id(&node_id)))
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbLink, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{LinkRow, to_surreal_id};
@@ -21,7 +21,7 @@ use crate::persistence::{LinkRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_link(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbLink> {
+pub async fn get_link(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<DbLink> {
     let link: Option<LinkRow> = db
         .select(to_surreal_id(&id))
         .await
@@ -45,7 +45,7 @@ pub async fn get_link(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbLink>
 /// - If link with id not found
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn get_link_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbLink> {
+pub async fn get_link_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<DbLink> {
     get_link(db, id).await
 }
 
@@ -67,7 +67,7 @@ pub async fn get_link_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<D
 ///
 #[instrument(skip(db), level = "debug")]
 pub async fn get_link_by_peers(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_a_id: RecordId,
     node_b_id: RecordId,
     int_a: &str,
@@ -111,7 +111,7 @@ pub async fn get_link_by_peers(
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_links(db: &Arc<Surreal<Client>>) -> Result<Vec<DbLink>> {
+pub async fn list_links(db: &Arc<Surreal<Any>>) -> Result<Vec<DbLink>> {
     let links: Vec<LinkRow> = db
         .select("link")
         .await
@@ -133,7 +133,7 @@ pub async fn list_links(db: &Arc<Surreal<Client>>) -> Result<Vec<DbLink>> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<Vec<DbLink>> {
+pub async fn list_links_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<Vec<DbLink>> {
     let mut response = db
         .query("SELECT * FROM link WHERE lab = $lab_id")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -159,10 +159,7 @@ pub async fn list_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> R
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_links_by_node(
-    db: &Arc<Surreal<Client>>,
-    node_id: RecordId,
-) -> Result<Vec<DbLink>> {
+pub async fn list_links_by_node(db: &Arc<Surreal<Any>>, node_id: RecordId) -> Result<Vec<DbLink>> {
     let mut response = db
         .query("SELECT * FROM link WHERE node_a = $node_id OR node_b = $node_id")
         .bind(("node_id", to_surreal_id(&node_id)))
@@ -185,7 +182,7 @@ pub async fn list_links_by_node(
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_links(db: &Arc<Surreal<Client>>) -> Result<usize> {
+pub async fn count_links(db: &Arc<Surreal<Any>>) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM link GROUP ALL")
         .await
@@ -208,7 +205,7 @@ pub async fn count_links(db: &Arc<Surreal<Client>>) -> Result<usize> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<usize> {
+pub async fn count_links_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM link WHERE lab = $lab_id GROUP ALL")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -234,7 +231,7 @@ pub async fn count_links_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) ->
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_links_by_node(db: &Arc<Surreal<Client>>, node_id: RecordId) -> Result<usize> {
+pub async fn count_links_by_node(db: &Arc<Surreal<Any>>, node_id: RecordId) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM link WHERE node_a = $node_id OR node_b = $node_id GROUP ALL")
         .bind(("node_id", to_surreal_id(&node_id)))
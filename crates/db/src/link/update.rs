@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::DbLink;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::link::read::get_link;
@@ -30,7 +30,7 @@ use crate::persistence::{LinkRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn update_link(db: &Arc<Surreal<Client>>, link: DbLink) -> Result<DbLink> {
+pub async fn update_link(db: &Arc<Surreal<Any>>, link: DbLink) -> Result<DbLink> {
     // Require id field for updates
     let id = link
         .id
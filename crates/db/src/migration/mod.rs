@@ -0,0 +1,105 @@
+//! Versioned schema migrations
+//!
+//! `apply_schema` defines the current tables on every startup, but it cannot
+//! rename fields or fill in data for records written by older releases.
+//! Migrations do that. Each step runs once, in version order, inside a
+//! transaction that also records it in the `schema_migration` table.
+//!
+//! Add new steps to the end of `MIGRATIONS` with the next version number.
+//! Released steps must never be changed or removed.
+
+mod run;
+
+pub use run::{
+    MigrationStatus, MigrationStep, check_schema_version, list_applied_migrations, migrate,
+    migration_status,
+};
+
+/// A single schema migration step
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version the database is at once the step is applied
+    pub version: u32,
+    /// Short name shown by `sherpad migrate status`
+    pub name: &'static str,
+    /// SurrealQL run after `apply_schema`, in the same transaction that
+    /// records the step
+    pub up: &'static str,
+}
+
+/// All migration steps, in version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        up: "",
+    },
+    Migration {
+        version: 2,
+        name: "backfill_link_taps_and_impairments",
+        // Links created before p2p taps and impairments were stored lack
+        // these fields, and field defaults only apply to new records. All
+        // fields are set in one statement, as the whole record is checked
+        // against the schema.
+        up: r#"
+UPDATE link SET
+    tap_a = tap_a ?? '',
+    tap_b = tap_b ?? '',
+    delay_us = delay_us ?? 0,
+    jitter_us = jitter_us ?? 0,
+    loss_percent = loss_percent ?? 0.0,
+    reorder_percent = reorder_percent ?? 0.0,
+    corrupt_percent = corrupt_percent ?? 0.0
+WHERE tap_a = NONE OR tap_b = NONE OR delay_us = NONE OR jitter_us = NONE
+    OR loss_percent = NONE OR reorder_percent = NONE OR corrupt_percent = NONE;
+"#,
+    },
+    Migration {
+        version: 3,
+        name: "backfill_user_auth_provider",
+        // Users created before external identity providers existed
+        up: r#"
+UPDATE user SET auth_provider = 'local' WHERE auth_provider = NONE;
+"#,
+    },
+    Migration {
+        version: 4,
+        name: "backfill_link_filters",
+        // Links created before layer 2 filters existed. Both fields are set
+        // together, as in version 2.
+        up: r#"
+UPDATE link SET filter_drop = filter_drop ?? [], filter_allow = filter_allow ?? []
+    WHERE filter_drop = NONE OR filter_allow = NONE;
+"#,
+    },
+];
+
+/// Schema version this binary migrates databases to
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        for (idx, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version as usize, idx + 1, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn test_migration_names_are_unique() {
+        let mut names: Vec<&str> = MIGRATIONS.iter().map(|m| m.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_latest_version() {
+        assert_eq!(latest_version(), MIGRATIONS.len() as u32);
+    }
+}
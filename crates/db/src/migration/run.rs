@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use jiff::Timestamp;
+use shared::data::DbSchemaMigration;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use super::{MIGRATIONS, Migration, latest_version};
+use crate::persistence::SchemaMigrationRow;
+
+/// Migration state of a database compared to this binary
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    /// Highest migration version applied to the database
+    pub current_version: u32,
+    /// Highest migration version known to this binary
+    pub latest_version: u32,
+    /// Known and applied steps, in version order
+    pub steps: Vec<MigrationStep>,
+}
+
+impl MigrationStatus {
+    /// Number of steps known to this binary that are not applied yet
+    pub fn pending(&self) -> usize {
+        self.steps.iter().filter(|s| s.applied_at.is_none()).count()
+    }
+}
+
+/// A migration step and when it was applied
+#[derive(Debug, Clone)]
+pub struct MigrationStep {
+    pub version: u32,
+    pub name: String,
+    /// `None` while the step is pending
+    pub applied_at: Option<Timestamp>,
+}
+
+/// List the migrations applied to the database
+///
+/// # Arguments
+/// * `db` - Database connection
+///
+/// # Returns
+/// Vector of DbSchemaMigration ordered by version
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_applied_migrations(db: &Arc<Surreal<Any>>) -> Result<Vec<DbSchemaMigration>> {
+    let mut response = db
+        .query("SELECT * FROM schema_migration ORDER BY version")
+        .await
+        .context("Failed to list schema migrations from database")?;
+
+    let migrations: Vec<SchemaMigrationRow> = response.take(0)?;
+    migrations
+        .into_iter()
+        .map(DbSchemaMigration::try_from)
+        .collect()
+}
+
+/// Report which migrations are applied and which are pending
+///
+/// Steps applied by a newer binary are included with the name recorded in
+/// the database.
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn migration_status(db: &Arc<Surreal<Any>>) -> Result<MigrationStatus> {
+    let applied = list_applied_migrations(db).await?;
+    Ok(build_status(MIGRATIONS, &applied))
+}
+
+/// Refuse to use a database migrated by a newer binary
+///
+/// An older binary would redefine the schema of the newer release and
+/// write records the newer release does not expect.
+///
+/// # Errors
+/// - If the database schema version is newer than this binary
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn check_schema_version(db: &Arc<Surreal<Any>>) -> Result<()> {
+    let applied = list_applied_migrations(db).await?;
+    let current = current_version(&applied);
+    let latest = latest_version();
+    if current > latest {
+        bail!(
+            "Database schema version {} is newer than this sherpad supports (version {}). \
+             Upgrade sherpad before using this database.",
+            current,
+            latest
+        );
+    }
+    Ok(())
+}
+
+/// Apply all pending migrations in version order
+///
+/// Run after `apply_schema`, so each step sees the current table
+/// definitions. A failed step is rolled back and stops the run, leaving the
+/// database at the last applied version.
+///
+/// # Returns
+/// The versions applied by this run
+///
+/// # Errors
+/// - If the database schema version is newer than this binary
+/// - If a migration step fails
+#[instrument(skip(db), level = "debug")]
+pub async fn migrate(db: &Arc<Surreal<Any>>) -> Result<Vec<u32>> {
+    check_schema_version(db).await?;
+
+    let applied: HashSet<u32> = list_applied_migrations(db)
+        .await?
+        .into_iter()
+        .map(|m| m.version)
+        .collect();
+
+    let mut versions = vec![];
+    for migration in MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)) {
+        apply_migration(db, migration).await?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+async fn apply_migration(db: &Arc<Surreal<Any>>, migration: &Migration) -> Result<()> {
+    let query = format!(
+        "BEGIN TRANSACTION;\n{}\nCREATE schema_migration CONTENT {{ \
+         version: $version, name: $name, applied_at: time::now() }};\nCOMMIT TRANSACTION;",
+        migration.up
+    );
+
+    db.query(query)
+        .bind(("version", migration.version))
+        .bind(("name", migration.name.to_string()))
+        .await
+        .and_then(|response| response.check())
+        .context(format!(
+            "Failed to apply schema migration {} ({})",
+            migration.version, migration.name
+        ))?;
+
+    tracing::info!(
+        version = migration.version,
+        name = %migration.name,
+        "Applied schema migration"
+    );
+    Ok(())
+}
+
+fn current_version(applied: &[DbSchemaMigration]) -> u32 {
+    applied.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+fn build_status(migrations: &[Migration], applied: &[DbSchemaMigration]) -> MigrationStatus {
+    let mut steps: Vec<MigrationStep> = migrations
+        .iter()
+        .map(|m| MigrationStep {
+            version: m.version,
+            name: m.name.to_string(),
+            applied_at: applied
+                .iter()
+                .find(|a| a.version == m.version)
+                .map(|a| a.applied_at),
+        })
+        .collect();
+
+    // Steps from a newer binary
+    steps.extend(
+        applied
+            .iter()
+            .filter(|a| !migrations.iter().any(|m| m.version == a.version))
+            .map(|a| MigrationStep {
+                version: a.version,
+                name: a.name.clone(),
+                applied_at: Some(a.applied_at),
+            }),
+    );
+    steps.sort_by_key(|s| s.version);
+
+    MigrationStatus {
+        current_version: current_version(applied),
+        latest_version: migrations.last().map(|m| m.version).unwrap_or(0),
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(version: u32, name: &str) -> DbSchemaMigration {
+        DbSchemaMigration {
+            id: None,
+            version,
+            name: name.to_string(),
+            applied_at: Timestamp::UNIX_EPOCH,
+        }
+    }
+
+    const STEPS: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "baseline",
+            up: "",
+        },
+        Migration {
+            version: 2,
+            name: "backfill",
+            up: "",
+        },
+    ];
+
+    #[test]
+    fn test_build_status_pending_steps() {
+        let status = build_status(STEPS, &[applied(1, "baseline")]);
+        assert_eq!(status.current_version, 1);
+        assert_eq!(status.latest_version, 2);
+        assert_eq!(status.pending(), 1);
+        assert!(status.steps[0].applied_at.is_some());
+        assert!(status.steps[1].applied_at.is_none());
+    }
+
+    #[test]
+    fn test_build_status_fresh_database() {
+        let status = build_status(STEPS, &[]);
+        assert_eq!(status.current_version, 0);
+        assert_eq!(status.pending(), 2);
+    }
+
+    #[test]
+    fn test_build_status_includes_newer_steps() {
+        let status = build_status(
+            STEPS,
+            &[
+                applied(1, "baseline"),
+                applied(2, "backfill"),
+                applied(3, "from_newer_release"),
+            ],
+        );
+        assert_eq!(status.current_version, 3);
+        assert_eq!(status.latest_version, 2);
+        assert_eq!(status.pending(), 0);
+        assert_eq!(status.steps.len(), 3);
+        assert_eq!(status.steps[2].name, "from_newer_release");
+    }
+}
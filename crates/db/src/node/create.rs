@@ -4,7 +4,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbNode, NodeState, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::NodeRow;
@@ -37,7 +37,7 @@ use crate::persistence::NodeRow;
 /// - Database operation fails
 #[instrument(skip(db), level = "debug")]
 pub async fn create_node(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     name: &str,
     index: u16,
     image_id: RecordId,
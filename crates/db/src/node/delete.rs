@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::RecordId;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::node::read::get_node;
@@ -26,7 +26,7 @@ use crate::persistence::{LinkRow, NodeRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_node(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // Verify node exists
     let _ = get_node(db, id.clone()).await?;
 
@@ -49,7 +49,7 @@ pub async fn delete_node(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()>
 /// - If node has associated links
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_node_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     delete_node(db, id).await
 }
 
@@ -69,7 +69,7 @@ pub async fn delete_node_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Resul
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_nodes_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<()> {
+pub async fn delete_nodes_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<()> {
     let _deleted: Vec<NodeRow> = db
         .query("DELETE node WHERE lab = $lab_id")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -94,7 +94,7 @@ pub async fn delete_nodes_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) ->
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node_links(db: &Arc<Surreal<Client>>, node_id: RecordId) -> Result<()> {
+pub async fn delete_node_links(db: &Arc<Surreal<Any>>, node_id: RecordId) -> Result<()> {
     let _deleted: Vec<LinkRow> = db
         .query("DELETE link WHERE node_a = $node_id OR node_b = $node_id")
         .bind(("node_id", to_surreal_id(&node_id)))
@@ -120,7 +120,7 @@ pub async fn delete_node_links(db: &Arc<Surreal<Client>>, node_id: RecordId) ->
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node_cascade(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_node_cascade(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     delete_node(db, id).await
 }
 
@@ -142,7 +142,7 @@ pub async fn delete_node_cascade(db: &Arc<Surreal<Client>>, id: RecordId) -> Res
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node_safe(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_node_safe(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // Get the node to verify it exists
     let node = get_node(db, id.clone()).await?;
 
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbNode, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{NodeRow, to_surreal_id};
@@ -21,7 +21,7 @@ use crate::persistence::{NodeRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_node(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbNode> {
+pub async fn get_node(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<DbNode> {
     let node: Option<NodeRow> = db
         .select(to_surreal_id(&id))
         .await
@@ -45,7 +45,7 @@ pub async fn get_node(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbNode>
 /// - If node with id not found
 /// - If there's a database error
 #[instrument(skip(db), level = "debug")]
-pub async fn get_node_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<DbNode> {
+pub async fn get_node_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<DbNode> {
     get_node(db, id).await
 }
 
@@ -65,7 +65,7 @@ pub async fn get_node_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<D
 ///
 #[instrument(skip(db), level = "debug")]
 pub async fn get_node_by_name_and_lab(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     name: &str,
     lab_id: RecordId,
 ) -> Result<DbNode> {
@@ -94,7 +94,7 @@ pub async fn get_node_by_name_and_lab(
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_nodes(db: &Arc<Surreal<Client>>) -> Result<Vec<DbNode>> {
+pub async fn list_nodes(db: &Arc<Surreal<Any>>) -> Result<Vec<DbNode>> {
     let nodes: Vec<NodeRow> = db
         .select("node")
         .await
@@ -116,7 +116,7 @@ pub async fn list_nodes(db: &Arc<Surreal<Client>>) -> Result<Vec<DbNode>> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_nodes_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<Vec<DbNode>> {
+pub async fn list_nodes_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<Vec<DbNode>> {
     let mut response = db
         .query("SELECT * FROM node WHERE lab = $lab_id ORDER BY name ASC")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -139,7 +139,7 @@ pub async fn list_nodes_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> R
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_nodes(db: &Arc<Surreal<Client>>) -> Result<usize> {
+pub async fn count_nodes(db: &Arc<Surreal<Any>>) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM node GROUP ALL")
         .await
@@ -162,7 +162,7 @@ pub async fn count_nodes(db: &Arc<Surreal<Client>>) -> Result<usize> {
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_nodes_by_lab(db: &Arc<Surreal<Client>>, lab_id: RecordId) -> Result<usize> {
+pub async fn count_nodes_by_lab(db: &Arc<Surreal<Any>>, lab_id: RecordId) -> Result<usize> {
     let mut response = db
         .query("SELECT count() FROM node WHERE lab = $lab_id GROUP ALL")
         .bind(("lab_id", to_surreal_id(&lab_id)))
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbNode, NodeState, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::node::read::get_node;
@@ -29,7 +29,7 @@ use crate::persistence::{NodeRow, to_surreal_id};
 /// - If there's a database error
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn update_node(db: &Arc<Surreal<Client>>, node: DbNode) -> Result<DbNode> {
+pub async fn update_node(db: &Arc<Surreal<Any>>, node: DbNode) -> Result<DbNode> {
     // Require id field for updates
     let id = node
         .id
@@ -79,7 +79,7 @@ pub async fn update_node(db: &Arc<Surreal<Client>>, node: DbNode) -> Result<DbNo
 /// - If the database update fails
 #[instrument(skip(db), level = "debug")]
 pub async fn update_node_mgmt_ipv4(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_id: RecordId,
     mgmt_ipv4: &str,
 ) -> Result<DbNode> {
@@ -119,7 +119,7 @@ pub async fn update_node_mgmt_ipv4(
 /// - If the database update fails
 #[instrument(skip(db), level = "debug")]
 pub async fn update_node_mgmt_ipv6(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_id: RecordId,
     mgmt_ipv6: &str,
 ) -> Result<DbNode> {
@@ -159,7 +159,7 @@ pub async fn update_node_mgmt_ipv6(
 /// - If the database update fails
 #[instrument(skip(db), level = "debug")]
 pub async fn update_node_mgmt_mac(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_id: RecordId,
     mgmt_mac: &str,
 ) -> Result<DbNode> {
@@ -196,7 +196,7 @@ pub async fn update_node_mgmt_mac(
 /// - If the database update fails
 #[instrument(skip(db), level = "debug")]
 pub async fn update_node_state(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_id: RecordId,
     state: NodeState,
 ) -> Result<DbNode> {
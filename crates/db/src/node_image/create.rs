@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::NodeConfig;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::node_image::get_node_image_by_model_kind_version;
@@ -10,10 +10,7 @@ use crate::persistence::{NodeImageRow, to_surreal_id};
 
 /// Create a node_image record in the database with auto-generated ID
 #[instrument(skip(db), level = "debug")]
-pub async fn create_node_image(
-    db: &Arc<Surreal<Client>>,
-    config: NodeConfig,
-) -> Result<NodeConfig> {
+pub async fn create_node_image(db: &Arc<Surreal<Any>>, config: NodeConfig) -> Result<NodeConfig> {
     let row = NodeImageRow::try_from(&config)?;
     let created_config: Option<NodeImageRow> =
         db.create("node_image").content(row).await.context(format!(
@@ -40,10 +37,7 @@ pub async fn create_node_image(
 /// of the same (model, kind) combination.
 /// SurrealDB will auto-generate IDs for new records.
 #[instrument(skip(db), level = "debug")]
-pub async fn upsert_node_image(
-    db: &Arc<Surreal<Client>>,
-    config: NodeConfig,
-) -> Result<NodeConfig> {
+pub async fn upsert_node_image(db: &Arc<Surreal<Any>>, config: NodeConfig) -> Result<NodeConfig> {
     // If setting default=true, first unset default on other versions of same (model, kind)
     if config.default {
         db.query(
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::RecordId;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{NodeImageRow, to_surreal_id};
@@ -25,7 +25,7 @@ use crate::persistence::{NodeImageRow, to_surreal_id};
 /// - If the record is referenced by nodes (REFERENCE ON DELETE REJECT constraint)
 /// - If there's a database error during deletion
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_node_image(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_node_image(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // Execute DELETE query
     let deleted: Option<NodeImageRow> = db
         .delete(to_surreal_id(&id))
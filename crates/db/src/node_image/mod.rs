@@ -9,8 +9,8 @@ pub use create::{create_node_image, upsert_node_image};
 // Public exports - READ operations
 pub use read::{
     count_node_images, get_default_node_image, get_node_image_by_id,
-    get_node_image_by_model_kind_version, get_node_image_versions, list_node_images,
-    list_node_images_by_ids, list_node_images_by_kind,
+    get_node_image_by_model_kind_version, get_node_image_versions, list_node_image_usage,
+    list_node_images, list_node_images_by_ids, list_node_images_by_kind,
 };
 
 // Public exports - UPDATE operations
@@ -1,15 +1,15 @@
 use anyhow::{Context, Result, anyhow};
-use shared::data::{NodeConfig, NodeKind, NodeModel, RecordId};
+use shared::data::{DbNodeImageUsage, NodeConfig, NodeKind, NodeModel, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
-use crate::persistence::{NodeImageRow, to_surreal_id};
+use crate::persistence::{NodeImageRow, NodeImageUsageRow, to_surreal_id};
 
 /// List all node_image records from the database ordered by model
 #[instrument(skip(db), level = "debug")]
-pub async fn list_node_images(db: &Arc<Surreal<Client>>) -> Result<Vec<NodeConfig>> {
+pub async fn list_node_images(db: &Arc<Surreal<Any>>) -> Result<Vec<NodeConfig>> {
     let mut response = db
         .query("SELECT * FROM node_image ORDER BY model ASC")
         .await
@@ -22,7 +22,7 @@ pub async fn list_node_images(db: &Arc<Surreal<Client>>) -> Result<Vec<NodeConfi
 /// Get node_image by model, kind, and version
 #[instrument(skip(db), level = "debug")]
 pub async fn get_node_image_by_model_kind_version(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     model: &NodeModel,
     kind: &NodeKind,
     version: &str,
@@ -45,7 +45,7 @@ pub async fn get_node_image_by_model_kind_version(
 /// Get the default node_image for a specific model and kind
 #[instrument(skip(db), level = "debug")]
 pub async fn get_default_node_image(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     model: &NodeModel,
     kind: &NodeKind,
 ) -> Result<Option<NodeConfig>> {
@@ -66,7 +66,7 @@ pub async fn get_default_node_image(
 /// List all node_image records filtered by kind
 #[instrument(skip(db), level = "debug")]
 pub async fn list_node_images_by_kind(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     kind: &NodeKind,
 ) -> Result<Vec<NodeConfig>> {
     let mut response = db
@@ -85,7 +85,7 @@ pub async fn list_node_images_by_kind(
 /// Get all versions of a node_image for a specific model and kind
 #[instrument(skip(db), level = "debug")]
 pub async fn get_node_image_versions(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     model: &NodeModel,
     kind: &NodeKind,
 ) -> Result<Vec<NodeConfig>> {
@@ -106,7 +106,7 @@ pub async fn get_node_image_versions(
 /// Get multiple node_images by a list of RecordIds in a single query
 #[instrument(skip(db), level = "debug")]
 pub async fn list_node_images_by_ids(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     ids: Vec<RecordId>,
 ) -> Result<Vec<NodeConfig>> {
     if ids.is_empty() {
@@ -126,7 +126,7 @@ pub async fn list_node_images_by_ids(
 /// Get node_image by RecordId
 #[instrument(skip(db), level = "debug")]
 pub async fn get_node_image_by_id(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     id: RecordId,
 ) -> Result<Option<NodeConfig>> {
     let config: Option<NodeImageRow> = db
@@ -142,7 +142,7 @@ pub async fn get_node_image_by_id(
 #[allow(dead_code)]
 #[instrument(skip(db), level = "debug")]
 pub(crate) async fn get_node_image(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     node_model: &NodeModel,
 ) -> Result<NodeConfig> {
     let mut response = db
@@ -161,9 +161,29 @@ pub(crate) async fn get_node_image(
         .ok_or_else(|| anyhow!("Node image not found for model: {node_model}"))
 }
 
+/// List every node_image with the labs and nodes that use it, ordered by
+/// model and version
+#[instrument(skip(db), level = "debug")]
+pub async fn list_node_image_usage(db: &Arc<Surreal<Any>>) -> Result<Vec<DbNodeImageUsage>> {
+    let mut response = db
+        .query(
+            "SELECT id, model, kind, version, repo, default, last_used_at,
+                array::sort(array::distinct(
+                    (SELECT VALUE lab.lab_id FROM node WHERE image = $parent.id)
+                )) AS labs,
+                array::len((SELECT VALUE id FROM node WHERE image = $parent.id)) AS node_count
+             FROM node_image ORDER BY model ASC, version ASC",
+        )
+        .await
+        .context("Failed to query node_image usage from database")?;
+
+    let rows: Vec<NodeImageUsageRow> = response.take(0)?;
+    rows.into_iter().map(DbNodeImageUsage::try_from).collect()
+}
+
 /// Count total number of node_image records in the database
 #[instrument(skip(db), level = "debug")]
-pub async fn count_node_images(db: &Arc<Surreal<Client>>) -> Result<usize> {
+pub async fn count_node_images(db: &Arc<Surreal<Any>>) -> Result<usize> {
     let configs: Vec<NodeImageRow> = db
         .select("node_image")
         .await
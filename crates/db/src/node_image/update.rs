@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::NodeConfig;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::helpers::get_image_id;
@@ -25,10 +25,7 @@ use crate::persistence::{NodeImageRow, to_surreal_id};
 /// - If the record doesn't exist in the database
 /// - If there's a database error during the update
 #[instrument(skip(db), level = "debug")]
-pub async fn update_node_image(
-    db: &Arc<Surreal<Client>>,
-    config: NodeConfig,
-) -> Result<NodeConfig> {
+pub async fn update_node_image(db: &Arc<Surreal<Any>>, config: NodeConfig) -> Result<NodeConfig> {
     // Extract and validate the ID
     let id = get_image_id(&config)?;
 
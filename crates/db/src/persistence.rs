@@ -1,7 +1,11 @@
 use anyhow::{Context, Result, anyhow};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use shared::data::{DbBridge, DbLab, DbLink, DbNode, DbUser, NodeConfig, RecordId, RecordIdKey};
+use shared::data::{
+    DbApiToken, DbBridge, DbLab, DbLabShare, DbLink, DbNode, DbNodeImageUsage,
+    DbRegistryCredential, DbSchemaMigration, DbTeam, DbUser, L2Match, LinkFilter, NodeConfig,
+    RecordId, RecordIdKey,
+};
 use surrealdb_types::{
     Datetime, RecordId as SurrealRecordId, RecordIdKey as SurrealRecordIdKey, SurrealValue,
 };
@@ -13,6 +17,8 @@ pub(crate) struct UserRow {
     pub password_hash: String,
     pub is_admin: bool,
     pub ssh_keys: Vec<String>,
+    /// Missing on users created before external identity providers existed
+    pub auth_provider: Option<serde_json::Value>,
     pub created_at: Datetime,
     pub updated_at: Datetime,
 }
@@ -68,6 +74,8 @@ pub(crate) struct LinkRow {
     pub loss_percent: f32,
     pub reorder_percent: f32,
     pub corrupt_percent: f32,
+    pub filter_drop: Vec<String>,
+    pub filter_allow: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
@@ -80,6 +88,57 @@ pub(crate) struct BridgeRow {
     pub nodes: Vec<SurrealRecordId>,
 }
 
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct TeamRow {
+    pub id: Option<SurrealRecordId>,
+    pub name: String,
+    pub owner: SurrealRecordId,
+    pub members: Vec<SurrealRecordId>,
+    pub created_at: Datetime,
+    pub updated_at: Datetime,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct LabShareRow {
+    pub id: Option<SurrealRecordId>,
+    pub lab: SurrealRecordId,
+    pub user: Option<SurrealRecordId>,
+    pub team: Option<SurrealRecordId>,
+    pub role: serde_json::Value,
+    pub created_at: Datetime,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct ApiTokenRow {
+    pub id: Option<SurrealRecordId>,
+    pub name: String,
+    pub user: SurrealRecordId,
+    pub token_hash: String,
+    pub prefix: String,
+    pub scopes: Vec<serde_json::Value>,
+    pub expires_at: Datetime,
+    pub last_used_at: Option<Datetime>,
+    pub created_at: Datetime,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct RegistryCredentialRow {
+    pub id: Option<SurrealRecordId>,
+    pub registry: String,
+    pub username: String,
+    pub password_encrypted: String,
+    pub created_at: Datetime,
+    pub updated_at: Datetime,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct SchemaMigrationRow {
+    pub id: Option<SurrealRecordId>,
+    pub version: u32,
+    pub name: String,
+    pub applied_at: Datetime,
+}
+
 #[derive(Clone, Debug, Deserialize, SurrealValue)]
 pub(crate) struct NodeImageRow {
     pub id: Option<SurrealRecordId>,
@@ -113,6 +172,20 @@ pub(crate) struct NodeImageRow {
     pub reserved_interface_count: u8,
     pub default: bool,
     pub boot_mode: Option<serde_json::Value>,
+    pub image_sha256: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, SurrealValue)]
+pub(crate) struct NodeImageUsageRow {
+    pub id: SurrealRecordId,
+    pub model: serde_json::Value,
+    pub kind: serde_json::Value,
+    pub version: String,
+    pub repo: Option<String>,
+    pub default: bool,
+    pub labs: Vec<String>,
+    pub node_count: usize,
+    pub last_used_at: Option<Datetime>,
 }
 
 pub(crate) fn to_surreal_id(id: &RecordId) -> SurrealRecordId {
@@ -172,6 +245,7 @@ impl TryFrom<&DbUser> for UserRow {
             password_hash: value.password_hash.clone(),
             is_admin: value.is_admin,
             ssh_keys: value.ssh_keys.clone(),
+            auth_provider: Some(encode(value.auth_provider, "auth_provider")?),
             created_at: to_datetime(value.created_at, "created_at")?,
             updated_at: to_datetime(value.updated_at, "updated_at")?,
         })
@@ -188,6 +262,11 @@ impl TryFrom<UserRow> for DbUser {
             password_hash: value.password_hash,
             is_admin: value.is_admin,
             ssh_keys: value.ssh_keys,
+            auth_provider: value
+                .auth_provider
+                .map(|provider| decode(provider, "auth_provider"))
+                .transpose()?
+                .unwrap_or_default(),
             created_at: from_datetime(value.created_at, "created_at")?,
             updated_at: from_datetime(value.updated_at, "updated_at")?,
         })
@@ -298,6 +377,8 @@ impl TryFrom<&DbLink> for LinkRow {
             loss_percent: value.loss_percent,
             reorder_percent: value.reorder_percent,
             corrupt_percent: value.corrupt_percent,
+            filter_drop: value.filter.drop.iter().map(L2Match::to_string).collect(),
+            filter_allow: value.filter.allow.iter().map(L2Match::to_string).collect(),
         })
     }
 }
@@ -326,10 +407,21 @@ impl TryFrom<LinkRow> for DbLink {
             loss_percent: value.loss_percent,
             reorder_percent: value.reorder_percent,
             corrupt_percent: value.corrupt_percent,
+            filter: LinkFilter {
+                drop: parse_filter_entries(&value.filter_drop)?,
+                allow: parse_filter_entries(&value.filter_allow)?,
+            },
         })
     }
 }
 
+fn parse_filter_entries(entries: &[String]) -> Result<Vec<L2Match>> {
+    entries
+        .iter()
+        .map(|entry| entry.parse().context("Invalid link filter entry"))
+        .collect()
+}
+
 impl TryFrom<&DbBridge> for BridgeRow {
     type Error = anyhow::Error;
 
@@ -364,6 +456,184 @@ impl TryFrom<BridgeRow> for DbBridge {
     }
 }
 
+impl TryFrom<&DbTeam> for TeamRow {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DbTeam) -> Result<Self> {
+        Ok(Self {
+            id: value.id.as_ref().map(to_surreal_id),
+            name: value.name.clone(),
+            owner: to_surreal_id(&value.owner),
+            members: value.members.iter().map(to_surreal_id).collect(),
+            created_at: to_datetime(value.created_at, "created_at")?,
+            updated_at: to_datetime(value.updated_at, "updated_at")?,
+        })
+    }
+}
+
+impl TryFrom<TeamRow> for DbTeam {
+    type Error = anyhow::Error;
+
+    fn try_from(value: TeamRow) -> Result<Self> {
+        Ok(Self {
+            id: value.id.map(from_surreal_id).transpose()?,
+            name: value.name,
+            owner: from_surreal_id(value.owner)?,
+            members: value
+                .members
+                .into_iter()
+                .map(from_surreal_id)
+                .collect::<Result<Vec<_>>>()?,
+            created_at: from_datetime(value.created_at, "created_at")?,
+            updated_at: from_datetime(value.updated_at, "updated_at")?,
+        })
+    }
+}
+
+impl TryFrom<&DbLabShare> for LabShareRow {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DbLabShare) -> Result<Self> {
+        Ok(Self {
+            id: value.id.as_ref().map(to_surreal_id),
+            lab: to_surreal_id(&value.lab),
+            user: value.user.as_ref().map(to_surreal_id),
+            team: value.team.as_ref().map(to_surreal_id),
+            role: encode(value.role, "role")?,
+            created_at: to_datetime(value.created_at, "created_at")?,
+        })
+    }
+}
+
+impl TryFrom<LabShareRow> for DbLabShare {
+    type Error = anyhow::Error;
+
+    fn try_from(value: LabShareRow) -> Result<Self> {
+        Ok(Self {
+            id: value.id.map(from_surreal_id).transpose()?,
+            lab: from_surreal_id(value.lab)?,
+            user: value.user.map(from_surreal_id).transpose()?,
+            team: value.team.map(from_surreal_id).transpose()?,
+            role: decode(value.role, "role")?,
+            created_at: from_datetime(value.created_at, "created_at")?,
+        })
+    }
+}
+
+impl TryFrom<&DbApiToken> for ApiTokenRow {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DbApiToken) -> Result<Self> {
+        Ok(Self {
+            id: value.id.as_ref().map(to_surreal_id),
+            name: value.name.clone(),
+            user: to_surreal_id(&value.user),
+            token_hash: value.token_hash.clone(),
+            prefix: value.prefix.clone(),
+            scopes: value
+                .scopes
+                .iter()
+                .map(|scope| encode(scope, "scopes"))
+                .collect::<Result<Vec<_>>>()?,
+            expires_at: to_datetime(value.expires_at, "expires_at")?,
+            last_used_at: value
+                .last_used_at
+                .map(|value| to_datetime(value, "last_used_at"))
+                .transpose()?,
+            created_at: to_datetime(value.created_at, "created_at")?,
+        })
+    }
+}
+
+impl TryFrom<ApiTokenRow> for DbApiToken {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ApiTokenRow) -> Result<Self> {
+        Ok(Self {
+            id: value.id.map(from_surreal_id).transpose()?,
+            name: value.name,
+            user: from_surreal_id(value.user)?,
+            token_hash: value.token_hash,
+            prefix: value.prefix,
+            scopes: value
+                .scopes
+                .into_iter()
+                .map(|scope| decode(scope, "scopes"))
+                .collect::<Result<Vec<_>>>()?,
+            expires_at: from_datetime(value.expires_at, "expires_at")?,
+            last_used_at: value
+                .last_used_at
+                .map(|value| from_datetime(value, "last_used_at"))
+                .transpose()?,
+            created_at: from_datetime(value.created_at, "created_at")?,
+        })
+    }
+}
+
+impl TryFrom<&DbRegistryCredential> for RegistryCredentialRow {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DbRegistryCredential) -> Result<Self> {
+        Ok(Self {
+            id: value.id.as_ref().map(to_surreal_id),
+            registry: value.registry.clone(),
+            username: value.username.clone(),
+            password_encrypted: value.password_encrypted.clone(),
+            created_at: to_datetime(value.created_at, "created_at")?,
+            updated_at: to_datetime(value.updated_at, "updated_at")?,
+        })
+    }
+}
+
+impl TryFrom<RegistryCredentialRow> for DbRegistryCredential {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RegistryCredentialRow) -> Result<Self> {
+        Ok(Self {
+            id: value.id.map(from_surreal_id).transpose()?,
+            registry: value.registry,
+            username: value.username,
+            password_encrypted: value.password_encrypted,
+            created_at: from_datetime(value.created_at, "created_at")?,
+            updated_at: from_datetime(value.updated_at, "updated_at")?,
+        })
+    }
+}
+
+impl TryFrom<SchemaMigrationRow> for DbSchemaMigration {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SchemaMigrationRow) -> Result<Self> {
+        Ok(Self {
+            id: value.id.map(from_surreal_id).transpose()?,
+            version: value.version,
+            name: value.name,
+            applied_at: from_datetime(value.applied_at, "applied_at")?,
+        })
+    }
+}
+
+impl TryFrom<NodeImageUsageRow> for DbNodeImageUsage {
+    type Error = anyhow::Error;
+
+    fn try_from(value: NodeImageUsageRow) -> Result<Self> {
+        Ok(Self {
+            id: from_surreal_id(value.id)?,
+            model: decode(value.model, "model")?,
+            kind: decode(value.kind, "kind")?,
+            version: value.version,
+            repo: value.repo,
+            default: value.default,
+            labs: value.labs,
+            node_count: value.node_count,
+            last_used_at: value
+                .last_used_at
+                .map(|value| from_datetime(value, "last_used_at"))
+                .transpose()?,
+        })
+    }
+}
+
 impl TryFrom<&NodeConfig> for NodeImageRow {
     type Error = anyhow::Error;
 
@@ -404,6 +674,7 @@ impl TryFrom<&NodeConfig> for NodeImageRow {
                 .as_ref()
                 .map(|mode| encode(mode, "boot_mode"))
                 .transpose()?,
+            image_sha256: value.image_sha256.clone(),
         })
     }
 }
@@ -447,6 +718,7 @@ impl TryFrom<NodeImageRow> for NodeConfig {
                 .boot_mode
                 .map(|mode| decode(mode, "boot_mode"))
                 .transpose()?,
+            image_sha256: value.image_sha256,
         })
     }
 }
@@ -454,7 +726,7 @@ impl TryFrom<NodeImageRow> for NodeConfig {
 #[cfg(test)]
 mod tests {
     use jiff::Timestamp;
-    use shared::data::{NodeKind, NodeModel};
+    use shared::data::{AuthProvider, LabRole, NodeKind, NodeModel, TokenScope};
 
     use super::*;
 
@@ -477,6 +749,7 @@ mod tests {
             password_hash: "hash".to_owned(),
             is_admin: true,
             ssh_keys: Vec::new(),
+            auth_provider: AuthProvider::Ldap,
             created_at,
             updated_at: created_at,
         };
@@ -486,6 +759,79 @@ mod tests {
 
         assert_eq!(converted.created_at, original.created_at);
         assert_eq!(converted.updated_at, original.updated_at);
+        assert_eq!(converted.auth_provider, AuthProvider::Ldap);
+    }
+
+    #[test]
+    fn lab_share_round_trip_preserves_role_and_grantee() {
+        let original = DbLabShare {
+            id: None,
+            lab: RecordId::new("lab", "lab1"),
+            user: None,
+            team: Some(RecordId::new("team", "students")),
+            role: LabRole::Operator,
+            created_at: Timestamp::now(),
+        };
+
+        let row = LabShareRow::try_from(&original).unwrap();
+        assert_eq!(row.role, serde_json::json!("operator"));
+        let converted = DbLabShare::try_from(row).unwrap();
+
+        assert_eq!(converted.role, original.role);
+        assert_eq!(converted.user, None);
+        assert_eq!(converted.team, original.team);
+    }
+
+    #[test]
+    fn api_token_round_trip_preserves_scopes_and_timestamps() {
+        let created_at = Timestamp::now();
+        let original = DbApiToken {
+            id: None,
+            name: "ci".to_owned(),
+            user: RecordId::new("user", "alice"),
+            token_hash: "digest".to_owned(),
+            prefix: "sherpa_pat_01234567".to_owned(),
+            scopes: vec![TokenScope::ReadOnly, TokenScope::LabOperate],
+            expires_at: created_at,
+            last_used_at: None,
+            created_at,
+        };
+
+        let row = ApiTokenRow::try_from(&original).unwrap();
+        assert_eq!(
+            row.scopes,
+            vec![
+                serde_json::json!("read-only"),
+                serde_json::json!("lab-operate")
+            ]
+        );
+        let converted = DbApiToken::try_from(row).unwrap();
+
+        assert_eq!(converted.scopes, original.scopes);
+        assert_eq!(converted.expires_at, original.expires_at);
+        assert_eq!(converted.last_used_at, None);
+    }
+
+    #[test]
+    fn registry_credential_round_trip_preserves_fields() {
+        let created_at = Timestamp::now();
+        let original = DbRegistryCredential {
+            id: Some(RecordId::new("registry_credential", "harbor")),
+            registry: "harbor.example.com".to_owned(),
+            username: "robot$sherpa".to_owned(),
+            password_encrypted: "ciphertext".to_owned(),
+            created_at,
+            updated_at: created_at,
+        };
+
+        let row = RegistryCredentialRow::try_from(&original).unwrap();
+        let converted = DbRegistryCredential::try_from(row).unwrap();
+
+        assert_eq!(converted.id, original.id);
+        assert_eq!(converted.registry, original.registry);
+        assert_eq!(converted.username, original.username);
+        assert_eq!(converted.password_encrypted, original.password_encrypted);
+        assert_eq!(converted.updated_at, original.updated_at);
     }
 
     #[test]
@@ -501,4 +847,26 @@ mod tests {
         assert_eq!(converted.model, original.model);
         assert_eq!(converted.kind, original.kind);
     }
+
+    #[test]
+    fn node_image_usage_row_decodes_enums_and_timestamp() {
+        let last_used_at = Timestamp::now();
+        let row = NodeImageUsageRow {
+            id: to_surreal_id(&RecordId::new("node_image", "ceos")),
+            model: serde_json::json!("arista_ceos"),
+            kind: serde_json::json!("container"),
+            version: "4.33.0F".to_owned(),
+            repo: Some("ceos".to_owned()),
+            default: false,
+            labs: vec!["abcd1234".to_owned()],
+            node_count: 2,
+            last_used_at: Some(to_datetime(last_used_at, "last_used_at").unwrap()),
+        };
+        let converted = DbNodeImageUsage::try_from(row).unwrap();
+
+        assert_eq!(converted.model, NodeModel::AristaCeos);
+        assert_eq!(converted.kind, NodeKind::Container);
+        assert_eq!(converted.node_count, 2);
+        assert_eq!(converted.last_used_at, Some(last_used_at));
+    }
 }
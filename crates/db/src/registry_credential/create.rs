@@ -0,0 +1,79 @@
+use anyhow::{Context, Result, anyhow};
+use jiff::Timestamp;
+use shared::data::DbRegistryCredential;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use super::get_registry_credential;
+use crate::persistence::{RegistryCredentialRow, to_surreal_id};
+
+/// Store the credentials for a registry
+///
+/// If credentials already exist for the registry they are replaced,
+/// keeping their original `created_at`.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `registry` - Normalized registry host
+/// * `username` - Registry username
+/// * `password_encrypted` - Password, already encrypted by the caller
+///
+/// # Returns
+/// The created or updated DbRegistryCredential
+///
+/// # Errors
+/// - If the registry or username is empty
+/// - If there's a database error
+#[instrument(skip(db, password_encrypted), level = "debug")]
+pub async fn upsert_registry_credential(
+    db: &Arc<Surreal<Any>>,
+    registry: &str,
+    username: &str,
+    password_encrypted: &str,
+) -> Result<DbRegistryCredential> {
+    if registry.is_empty() || username.is_empty() {
+        return Err(anyhow!("Registry and username must not be empty"));
+    }
+
+    let now = Timestamp::now();
+    let credential = match get_registry_credential(db, registry).await? {
+        Some(existing) => DbRegistryCredential {
+            username: username.to_string(),
+            password_encrypted: password_encrypted.to_string(),
+            updated_at: now,
+            ..existing
+        },
+        None => DbRegistryCredential {
+            id: None,
+            registry: registry.to_string(),
+            username: username.to_string(),
+            password_encrypted: password_encrypted.to_string(),
+            created_at: now,
+            updated_at: now,
+        },
+    };
+    let row = RegistryCredentialRow::try_from(&credential)?;
+
+    let saved: Option<RegistryCredentialRow> = match &credential.id {
+        Some(id) => db
+            .update(to_surreal_id(id))
+            .content(row)
+            .await
+            .context(format!("Failed to update registry credential: {:?}", id))?,
+        None => db
+            .create("registry_credential")
+            .content(row)
+            .await
+            .context(format!(
+                "Failed to create registry credential: registry={}",
+                registry
+            ))?,
+    };
+
+    saved
+        .map(DbRegistryCredential::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("Registry credential was not saved: registry={}", registry))
+}
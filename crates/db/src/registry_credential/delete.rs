@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::RegistryCredentialRow;
+
+/// Delete the credentials for a registry
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `registry` - Normalized registry host
+///
+/// # Returns
+/// `true` if credentials were deleted, `false` if none were stored
+///
+/// # Errors
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn delete_registry_credential(db: &Arc<Surreal<Any>>, registry: &str) -> Result<bool> {
+    let mut response = db
+        .query("DELETE registry_credential WHERE registry = $registry RETURN BEFORE")
+        .bind(("registry", registry.to_string()))
+        .await
+        .context(format!(
+            "Failed to delete registry credential: registry={}",
+            registry
+        ))?;
+
+    let deleted: Vec<RegistryCredentialRow> = response.take(0)?;
+    Ok(!deleted.is_empty())
+}
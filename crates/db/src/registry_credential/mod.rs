@@ -0,0 +1,18 @@
+//! Private container registry credential operations
+//!
+//! This module provides create, read, and delete operations for
+//! registry credential records. Credentials are keyed by the normalized
+//! registry host, passwords are stored encrypted by the caller.
+
+mod create;
+mod delete;
+mod read;
+
+// Public exports - CREATE operations
+pub use create::upsert_registry_credential;
+
+// Public exports - READ operations
+pub use read::{get_registry_credential, list_registry_credentials};
+
+// Public exports - DELETE operations
+pub use delete::delete_registry_credential;
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use shared::data::DbRegistryCredential;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::RegistryCredentialRow;
+
+/// Get the credentials for a registry
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `registry` - Normalized registry host
+///
+/// # Returns
+/// The DbRegistryCredential if one is stored, `None` otherwise
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn get_registry_credential(
+    db: &Arc<Surreal<Any>>,
+    registry: &str,
+) -> Result<Option<DbRegistryCredential>> {
+    let mut response = db
+        .query("SELECT * FROM ONLY registry_credential WHERE registry = $registry LIMIT 1")
+        .bind(("registry", registry.to_string()))
+        .await
+        .context(format!(
+            "Failed to query registry credential: registry={}",
+            registry
+        ))?;
+
+    let credential: Option<RegistryCredentialRow> = response.take(0)?;
+    credential.map(DbRegistryCredential::try_from).transpose()
+}
+
+/// List all stored registry credentials
+///
+/// # Arguments
+/// * `db` - Database connection
+///
+/// # Returns
+/// Vector of DbRegistryCredential ordered by registry
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_registry_credentials(
+    db: &Arc<Surreal<Any>>,
+) -> Result<Vec<DbRegistryCredential>> {
+    let mut response = db
+        .query("SELECT * FROM registry_credential ORDER BY registry")
+        .await
+        .context("Failed to list registry credentials from database")?;
+
+    let credentials: Vec<RegistryCredentialRow> = response.take(0)?;
+    credentials
+        .into_iter()
+        .map(DbRegistryCredential::try_from)
+        .collect()
+}
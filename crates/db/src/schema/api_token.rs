@@ -0,0 +1,57 @@
+//! API token table schema definition
+//!
+//! The api_token table stores personal access tokens used by automation
+//! (e.g. CI pipelines) instead of a password login. Only the SHA-256 digest
+//! of a token is stored.
+//!
+//! ## Fields
+//! - `name`: Token name, unique per user (min 3 chars, alphanumeric + ._-)
+//! - `user`: Foreign key reference to the user owning the token
+//! - `token_hash`: Hex encoded SHA-256 digest of the token
+//! - `prefix`: First characters of the token, for display
+//! - `scopes`: Non-empty array of `TokenScope` values
+//! - `expires_at`: Timestamp after which the token is rejected
+//! - `last_used_at`: Optional timestamp of the last successful authentication
+//! - `created_at`: Timestamp when the token was created (set by application)
+//!
+//! ## Constraints
+//! - `token_hash` must be unique across all tokens
+//! - `name` must be unique per user
+//!
+//! ## Relationships
+//! - Many-to-one with `user` table
+//!
+//! ## Cascade Deletion
+//! The `user` field uses `REFERENCE ON DELETE CASCADE` so that tokens are
+//! revoked when their user is deleted.
+
+use shared::data::TokenScope;
+
+/// Generate the api_token table schema.
+pub(crate) fn generate_api_token_schema() -> String {
+    let scopes = super::helpers::vec_to_str(TokenScope::all());
+
+    format!(
+        r#"
+DEFINE TABLE OVERWRITE api_token SCHEMAFULL;
+DEFINE FIELD OVERWRITE name ON TABLE api_token TYPE string
+    ASSERT string::len($value) >= 3
+    AND $value = /^[a-zA-Z0-9._-]+$/;
+DEFINE FIELD OVERWRITE user ON TABLE api_token TYPE record<user> REFERENCE ON DELETE CASCADE;
+DEFINE FIELD OVERWRITE token_hash ON TABLE api_token TYPE string;
+DEFINE FIELD OVERWRITE prefix ON TABLE api_token TYPE string;
+DEFINE FIELD OVERWRITE scopes ON TABLE api_token TYPE array<string>
+    ASSERT array::len($value) > 0;
+DEFINE FIELD OVERWRITE scopes.* ON TABLE api_token TYPE string
+    ASSERT $value IN [{scopes}];
+DEFINE FIELD OVERWRITE expires_at ON TABLE api_token TYPE datetime;
+DEFINE FIELD OVERWRITE last_used_at ON TABLE api_token TYPE option<datetime>;
+DEFINE FIELD OVERWRITE created_at ON TABLE api_token TYPE datetime;
+
+DEFINE INDEX OVERWRITE unique_token_hash
+  ON TABLE api_token FIELDS token_hash UNIQUE;
+DEFINE INDEX OVERWRITE unique_user_token_name
+  ON TABLE api_token FIELDS user, name UNIQUE;
+"#
+    )
+}
@@ -8,14 +8,21 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
+use crate::migration::check_schema_version;
+
+use super::api_token::generate_api_token_schema;
 use super::bridge::generate_bridge_schema;
 use super::lab::generate_lab_schema;
+use super::lab_share::generate_lab_share_schema;
 use super::link::generate_link_schema;
 use super::node::generate_node_schema;
 use super::node_image::generate_node_image_schema;
+use super::registry_credential::generate_registry_credential_schema;
+use super::schema_migration::generate_schema_migration_schema;
+use super::team::generate_team_schema;
 use super::user::generate_user_schema;
 
 /// Apply a single schema section to the database.
@@ -35,7 +42,7 @@ use super::user::generate_user_schema;
 /// * `Err` if the schema application failed
 ///
 async fn apply_schema_section(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     section_name: &str,
     schema: &str,
 ) -> Result<()> {
@@ -60,12 +67,20 @@ async fn apply_schema_section(
 ///
 /// # Order of Execution
 ///
+/// The `schema_migration` table is defined first, and the function refuses
+/// to continue if the database was migrated by a newer binary.
+///
 /// Tables are created in dependency order to satisfy foreign key relationships:
 /// 1. **user** (no dependencies)
 /// 2. **node_image** (no dependencies)
 /// 3. **lab** (depends on: user)
 /// 4. **node** (depends on: node_image, lab)
 /// 5. **link** (depends on: node, lab)
+/// 6. **bridge** (depends on: node, lab)
+/// 7. **team** (depends on: user)
+/// 8. **lab_share** (depends on: lab, user, team)
+/// 9. **api_token** (depends on: user)
+/// 10. **registry_credential** (no dependencies)
 ///
 /// # Parameters
 ///
@@ -74,7 +89,8 @@ async fn apply_schema_section(
 /// # Returns
 ///
 /// * `Ok(())` if all schemas were applied successfully
-/// * `Err` if any schema application failed
+/// * `Err` if any schema application failed, or the database schema version
+///   is newer than this binary
 ///
 /// # Error Handling
 ///
@@ -84,7 +100,7 @@ async fn apply_schema_section(
 /// after fixing any issues.
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn apply_schema(db: &Arc<Surreal<Client>>) -> Result<()> {
+pub async fn apply_schema(db: &Arc<Surreal<Any>>) -> Result<()> {
     // Generate schemas dynamically from individual schema modules
     let user_schema = generate_user_schema();
     let node_image_schema = generate_node_image_schema();
@@ -92,6 +108,14 @@ pub async fn apply_schema(db: &Arc<Surreal<Client>>) -> Result<()> {
     let node_schema = generate_node_schema();
     let link_schema = generate_link_schema();
     let bridge_schema = generate_bridge_schema();
+    let team_schema = generate_team_schema();
+    let lab_share_schema = generate_lab_share_schema();
+    let api_token_schema = generate_api_token_schema();
+    let registry_credential_schema = generate_registry_credential_schema();
+
+    // Refuse to redefine the schema of a newer release
+    apply_schema_section(db, "schema_migration", &generate_schema_migration_schema()).await?;
+    check_schema_version(db).await?;
 
     // Apply schemas in dependency order
     apply_schema_section(db, "user", &user_schema).await?;
@@ -100,6 +124,10 @@ pub async fn apply_schema(db: &Arc<Surreal<Client>>) -> Result<()> {
     apply_schema_section(db, "node", &node_schema).await?;
     apply_schema_section(db, "link", &link_schema).await?;
     apply_schema_section(db, "bridge", &bridge_schema).await?;
+    apply_schema_section(db, "team", &team_schema).await?;
+    apply_schema_section(db, "lab_share", &lab_share_schema).await?;
+    apply_schema_section(db, "api_token", &api_token_schema).await?;
+    apply_schema_section(db, "registry_credential", &registry_credential_schema).await?;
 
     Ok(())
 }
@@ -0,0 +1,47 @@
+//! Lab share table schema definition
+//!
+//! The lab_share table grants users other than the owner access to a lab.
+//! A share targets either a single user or a team and carries the role
+//! granted to the grantee.
+//!
+//! ## Fields
+//! - `lab`: Foreign key reference to the shared lab
+//! - `user`: Optional reference to the user the lab is shared with
+//! - `team`: Optional reference to the team the lab is shared with
+//! - `role`: Granted role (`viewer` or `operator`)
+//! - `created_at`: Timestamp when the share was created (set by application)
+//!
+//! ## Constraints
+//! - Exactly one of `user` or `team` is set (validated in application)
+//! - `role` must be a shareable `LabRole` (the owner role cannot be granted)
+//! - A grantee has at most one share per lab (enforced in application)
+//!
+//! ## Relationships
+//! - Many-to-one with `lab` table
+//! - Many-to-one with `user` table
+//! - Many-to-one with `team` table
+//!
+//! ## Cascade Deletion
+//! All references use `REFERENCE ON DELETE CASCADE` so that a share is
+//! removed when its lab, user or team is deleted.
+
+use shared::data::LabRole;
+
+/// Generate the lab_share table schema.
+pub(crate) fn generate_lab_share_schema() -> String {
+    let roles = super::helpers::vec_to_str(LabRole::shareable());
+
+    format!(
+        r#"
+DEFINE TABLE OVERWRITE lab_share SCHEMAFULL;
+DEFINE FIELD OVERWRITE lab ON TABLE lab_share TYPE record<lab> REFERENCE ON DELETE CASCADE;
+DEFINE FIELD OVERWRITE user ON TABLE lab_share TYPE option<record<user>>
+    REFERENCE ON DELETE CASCADE;
+DEFINE FIELD OVERWRITE team ON TABLE lab_share TYPE option<record<team>>
+    REFERENCE ON DELETE CASCADE;
+DEFINE FIELD OVERWRITE role ON TABLE lab_share TYPE string
+    ASSERT $value IN [{roles}];
+DEFINE FIELD OVERWRITE created_at ON TABLE lab_share TYPE datetime;
+"#
+    )
+}
@@ -11,6 +11,7 @@
 //! - `int_a`, `int_b`: Interface names on each node
 //! - `bridge_a`, `bridge_b`: Linux bridge names on the host
 //! - `veth_a`, `veth_b`: Virtual ethernet pair names
+//! - `filter_drop`, `filter_allow`: Layer 2 filter entries, e.g. `lacp` or `vlan:100`
 //! - `kind`: Bridge type (enum: OVS or Linux)
 //! - `lab`: Foreign key reference to the owning lab
 //!
@@ -87,6 +88,8 @@ DEFINE FIELD OVERWRITE jitter_us ON TABLE link TYPE number DEFAULT 0;
 DEFINE FIELD OVERWRITE loss_percent ON TABLE link TYPE number DEFAULT 0;
 DEFINE FIELD OVERWRITE reorder_percent ON TABLE link TYPE number DEFAULT 0;
 DEFINE FIELD OVERWRITE corrupt_percent ON TABLE link TYPE number DEFAULT 0;
+DEFINE FIELD OVERWRITE filter_drop ON TABLE link TYPE array<string> DEFAULT [];
+DEFINE FIELD OVERWRITE filter_allow ON TABLE link TYPE array<string> DEFAULT [];
 DEFINE FIELD OVERWRITE kind ON TABLE link TYPE string
     ASSERT $value IN [{}];
 DEFINE FIELD OVERWRITE lab ON TABLE link TYPE record<lab> REFERENCE ON DELETE CASCADE;
@@ -12,6 +12,11 @@
 //! - `lab`: Network lab table schema
 //! - `node`: Network node table schema
 //! - `link`: Network link (connection) table schema
+//! - `team`: User team table schema
+//! - `lab_share`: Lab share (access grant) table schema
+//! - `api_token`: Personal API token table schema
+//! - `registry_credential`: Private container registry credential table schema
+//! - `schema_migration`: Applied schema migration table schema
 //! - `apply`: Schema application and orchestration
 //!
 //! ## Usage
@@ -20,13 +25,18 @@
 //! all tables in the correct dependency order:
 //!
 
+mod api_token;
 mod apply;
 mod bridge;
 mod helpers;
 mod lab;
+mod lab_share;
 mod link;
 mod node;
 mod node_image;
+mod registry_credential;
+mod schema_migration;
+mod team;
 mod user;
 
 // Public API - only schema application function is exposed outside the crate
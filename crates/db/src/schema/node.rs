@@ -23,6 +23,10 @@
 //! - `links`: Reverse reference to all links connected to this node (`array::union(<~(link FIELD node_a), <~(link FIELD node_b))`)
 //! - `bridges`: Reverse reference to all bridges this node connects to (`<~(bridge FIELD nodes)`)
 //!
+//! ## Events
+//! - Creating or deleting a node stamps `last_used_at` on its node_image, so
+//!   images can be pruned by how long they have gone unused.
+//!
 //! ## Relationships
 //! - Many-to-one with `node_image` table (multiple nodes can use same image)
 //! - Many-to-one with `lab` table (each node belongs to one lab)
@@ -95,6 +99,12 @@ DEFINE INDEX OVERWRITE unique_node_name_per_lab
 
 DEFINE INDEX OVERWRITE unique_node_index_per_lab
   ON TABLE node FIELDS lab, index UNIQUE;
+
+DEFINE EVENT OVERWRITE node_image_used_on_create ON TABLE node
+  WHEN $event = "CREATE" THEN (UPDATE $after.image SET last_used_at = time::now());
+
+DEFINE EVENT OVERWRITE node_image_used_on_delete ON TABLE node
+  WHEN $event = "DELETE" THEN (UPDATE $before.image SET last_used_at = time::now());
 "#
     )
 }
@@ -15,6 +15,10 @@
 //! - Network interfaces: `data_interface_count`, `interface_prefix`, `interface_type`, `interface_mtu`,
 //!   `first_interface_index`, `dedicated_management_interface`, `management_interface`, `reserved_interface_count`
 //! - Version control: `default` (boolean indicating if this is the default version for the model/kind)
+//! - Integrity: `image_sha256` (SHA-256 of the stored image file, recorded on import)
+//! - Usage: `last_used_at` (set when the image is created and, by events on the
+//!   `node` table, whenever a node using it is created or deleted; full record
+//!   updates keep the previous value)
 //!
 //! ## Constraints
 //! - All enum fields are validated against their respective Rust enum variants
@@ -48,7 +52,7 @@ use super::helpers::vec_to_str;
 /// # Schema Details
 ///
 /// - **Table**: `node_image` (SCHEMAFULL)
-/// - **Fields**: 29 fields covering model, hardware, and network configuration
+/// - **Fields**: model, hardware, network configuration and image integrity
 /// - **Indexes**:
 ///   - `unique_node_image_model_kind_version`: Ensures unique (model, kind, version) combinations
 ///
@@ -144,6 +148,12 @@ DEFINE FIELD OVERWRITE default ON TABLE node_image TYPE bool;
 DEFINE FIELD OVERWRITE boot_mode ON TABLE node_image TYPE option<string>
     ASSERT $value == NONE OR $value IN [{}];
 
+DEFINE FIELD OVERWRITE image_sha256 ON TABLE node_image TYPE option<string>
+    ASSERT $value == NONE OR $value = /^[0-9a-f]{{64}}$/;
+
+DEFINE FIELD OVERWRITE last_used_at ON TABLE node_image TYPE option<datetime>
+    VALUE $value OR $before OR time::now();
+
 DEFINE FIELD OVERWRITE nodes ON TABLE node_image COMPUTED <~(node FIELD image);
 
 DEFINE INDEX OVERWRITE unique_node_image_model_kind_version
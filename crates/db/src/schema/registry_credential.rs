@@ -0,0 +1,36 @@
+//! Registry credential table schema definition
+//!
+//! The registry_credential table stores the credentials used to pull
+//! container images from private registries. Passwords are encrypted by
+//! the server before they are stored.
+//!
+//! ## Fields
+//! - `registry`: Normalized registry host (e.g. harbor.example.com)
+//! - `username`: Registry username
+//! - `password_encrypted`: Base64 encoded nonce and AES-256-GCM ciphertext
+//! - `created_at`: Timestamp when the credentials were first stored
+//! - `updated_at`: Timestamp when the credentials were last replaced
+//!
+//! ## Constraints
+//! - `registry` must be unique across all credentials
+//!
+//! ## Relationships
+//! - None
+
+/// Generate the registry_credential table schema.
+pub(crate) fn generate_registry_credential_schema() -> String {
+    r#"
+DEFINE TABLE OVERWRITE registry_credential SCHEMAFULL;
+DEFINE FIELD OVERWRITE registry ON TABLE registry_credential TYPE string
+    ASSERT string::len($value) > 0;
+DEFINE FIELD OVERWRITE username ON TABLE registry_credential TYPE string
+    ASSERT string::len($value) > 0;
+DEFINE FIELD OVERWRITE password_encrypted ON TABLE registry_credential TYPE string;
+DEFINE FIELD OVERWRITE created_at ON TABLE registry_credential TYPE datetime;
+DEFINE FIELD OVERWRITE updated_at ON TABLE registry_credential TYPE datetime;
+
+DEFINE INDEX OVERWRITE unique_registry
+  ON TABLE registry_credential FIELDS registry UNIQUE;
+"#
+    .to_string()
+}
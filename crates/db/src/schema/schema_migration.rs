@@ -0,0 +1,31 @@
+//! Schema migration table schema definition
+//!
+//! The schema_migration table records which migration steps have been
+//! applied, so the database version is the highest recorded version.
+//!
+//! ## Fields
+//! - `version`: Migration version, ordered from 1
+//! - `name`: Migration name
+//! - `applied_at`: Timestamp when the migration was applied
+//!
+//! ## Constraints
+//! - `version` must be unique across all migrations
+//!
+//! ## Relationships
+//! - None
+
+/// Generate the schema_migration table schema.
+pub(crate) fn generate_schema_migration_schema() -> String {
+    r#"
+DEFINE TABLE OVERWRITE schema_migration SCHEMAFULL;
+DEFINE FIELD OVERWRITE version ON TABLE schema_migration TYPE int
+    ASSERT $value > 0;
+DEFINE FIELD OVERWRITE name ON TABLE schema_migration TYPE string
+    ASSERT string::len($value) > 0;
+DEFINE FIELD OVERWRITE applied_at ON TABLE schema_migration TYPE datetime;
+
+DEFINE INDEX OVERWRITE unique_version
+  ON TABLE schema_migration FIELDS version UNIQUE;
+"#
+    .to_string()
+}
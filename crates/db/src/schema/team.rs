@@ -0,0 +1,45 @@
+//! Team table schema definition
+//!
+//! The team table stores named groups of users. Labs can be shared with a
+//! team, granting every member the role of the share (e.g. a class of
+//! students working on an instructor's lab).
+//!
+//! ## Fields
+//! - `name`: Unique team name (min 3 chars, alphanumeric + ._-)
+//! - `owner`: Foreign key reference to the user who manages the team
+//! - `members`: Array of references to the member users
+//! - `created_at`: Timestamp when team was created (set by application)
+//! - `updated_at`: Timestamp of last update (set by application)
+//!
+//! ## Constraints
+//! - Team name must be at least 3 characters long
+//! - Team name must match pattern: `[a-zA-Z0-9._-]+`
+//! - Team name must be unique across all teams
+//!
+//! ## Relationships
+//! - Many-to-one with `user` table (each team has one owner)
+//! - Many-to-many with `user` table (team members)
+//!
+//! ## Cascade Deletion
+//! The `owner` field uses `REFERENCE ON DELETE CASCADE` so that a team is
+//! removed with its owner. Deleted users are removed from `members` via
+//! `REFERENCE ON DELETE UNSET`.
+
+/// Generate the team table schema.
+pub(crate) fn generate_team_schema() -> String {
+    r#"
+DEFINE TABLE OVERWRITE team SCHEMAFULL;
+DEFINE FIELD OVERWRITE name ON TABLE team TYPE string
+    ASSERT string::len($value) >= 3
+    AND $value = /^[a-zA-Z0-9._-]+$/;
+DEFINE FIELD OVERWRITE owner ON TABLE team TYPE record<user> REFERENCE ON DELETE CASCADE;
+DEFINE FIELD OVERWRITE members ON TABLE team TYPE array<record<user>> REFERENCE ON DELETE UNSET
+    DEFAULT [];
+DEFINE FIELD OVERWRITE created_at ON TABLE team TYPE datetime;
+DEFINE FIELD OVERWRITE updated_at ON TABLE team TYPE datetime;
+
+DEFINE INDEX OVERWRITE unique_team_name
+  ON TABLE team FIELDS name UNIQUE;
+"#
+    .to_string()
+}
@@ -5,9 +5,10 @@
 //!
 //! ## Fields
 //! - `username`: Unique username (min 3 chars, alphanumeric + @._-)
-//! - `password_hash`: Argon2id password hash for authentication
+//! - `password_hash`: Argon2id password hash for authentication (empty for external users)
 //! - `is_admin`: Boolean flag indicating admin privileges
 //! - `ssh_keys`: Array of SSH public keys for authentication
+//! - `auth_provider`: Where the user authenticates (`local`, `ldap` or `oidc`)
 //! - `created_at`: Timestamp when user was created (set by application)
 //! - `updated_at`: Timestamp of last update (set by application)
 //!
@@ -22,6 +23,8 @@
 //! ## Relationships
 //! - One-to-many with `lab` table (user owns multiple labs)
 
+use shared::data::AuthProvider;
+
 /// Generate the user table schema.
 ///
 /// Creates the user table with username validation, password authentication, and SSH key storage.
@@ -38,13 +41,16 @@
 ///   - `password_hash`: string containing Argon2id hash
 ///   - `is_admin`: boolean flag for admin privileges (default: false)
 ///   - `ssh_keys`: array of strings (default: empty array)
+///   - `auth_provider`: one of the `AuthProvider` values (default: `local`)
 ///   - `created_at`: datetime timestamp (set by application on creation)
 ///   - `updated_at`: datetime timestamp (set by application on updates)
 /// - **Indexes**:
 ///   - `unique_username`: Ensures username uniqueness
 ///
 pub(crate) fn generate_user_schema() -> String {
-    r#"
+    let auth_providers = super::helpers::vec_to_str(AuthProvider::all());
+    format!(
+        r#"
 DEFINE TABLE OVERWRITE user SCHEMAFULL;
 DEFINE FIELD OVERWRITE username ON TABLE user TYPE string
     ASSERT string::len($value) >= 3
@@ -52,6 +58,8 @@ DEFINE FIELD OVERWRITE username ON TABLE user TYPE string
 DEFINE FIELD OVERWRITE password_hash ON TABLE user TYPE string;
 DEFINE FIELD OVERWRITE is_admin ON TABLE user TYPE bool DEFAULT false;
 DEFINE FIELD OVERWRITE ssh_keys ON TABLE user TYPE array<string> DEFAULT [];
+DEFINE FIELD OVERWRITE auth_provider ON TABLE user TYPE string DEFAULT "local"
+    ASSERT $value IN [{auth_providers}];
 DEFINE FIELD OVERWRITE created_at ON TABLE user TYPE datetime;
 DEFINE FIELD OVERWRITE updated_at ON TABLE user TYPE datetime;
 
@@ -60,5 +68,5 @@ DEFINE FIELD OVERWRITE labs ON TABLE user COMPUTED <~(lab FIELD user);
 DEFINE INDEX OVERWRITE unique_username
   ON TABLE user FIELDS username UNIQUE;
 "#
-    .to_string()
+    )
 }
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::user::{create_user, get_user};
@@ -26,7 +26,7 @@ use crate::user::{create_user, get_user};
 /// provided via secure means (environment variable, not config file).
 ///
 #[instrument(skip(db, password), level = "debug")]
-pub async fn seed_admin_user(db: &Arc<Surreal<Client>>, password: &str) -> Result<bool> {
+pub async fn seed_admin_user(db: &Arc<Surreal<Any>>, password: &str) -> Result<bool> {
     // Check if admin user already exists
     match get_user(db, "admin").await {
         Ok(_) => {
@@ -50,7 +50,9 @@ pub async fn seed_admin_user(db: &Arc<Surreal<Client>>, password: &str) -> Resul
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Database;
     use crate::user::{count_users, get_user};
+    use shared::data::DatabaseEngine;
     use shared::konst::SHERPA_PASSWORD;
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -70,17 +72,32 @@ mod tests {
         format!("test_ns_{ts}_{test_name}")
     }
 
-    #[tokio::test]
-    #[ignore] // Requires running SurrealDB instance
-    async fn test_seed_admin_user_creates_when_empty() -> Result<()> {
-        let db = crate::connect(
+    /// Connect to the `dev/testdb` container, or to an embedded in-memory
+    /// database with `SHERPA_TEST_DB_ENGINE=memory`
+    async fn connect_test_db(test_name: &str) -> Result<Database> {
+        if std::env::var("SHERPA_TEST_DB_ENGINE").is_ok_and(|v| v == "memory") {
+            return crate::connect_embedded(
+                DatabaseEngine::Memory,
+                "",
+                &unique_ns(test_name),
+                "test_db",
+            )
+            .await;
+        }
+        crate::connect(
             "localhost",
             test_db_port(),
-            &unique_ns("seed_admin_empty"),
+            &unique_ns(test_name),
             "test_db",
             SHERPA_PASSWORD,
         )
-        .await?;
+        .await
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires running SurrealDB instance
+    async fn test_seed_admin_user_creates_when_empty() -> Result<()> {
+        let db = connect_test_db("seed_admin_empty").await?;
         crate::schema::apply_schema(&db).await?;
 
         let created = seed_admin_user(&db, "AdminPass123!").await?;
@@ -97,14 +114,7 @@ mod tests {
     #[tokio::test]
     #[ignore] // Requires running SurrealDB instance
     async fn test_seed_admin_user_creates_when_other_users_exist() -> Result<()> {
-        let db = crate::connect(
-            "localhost",
-            test_db_port(),
-            &unique_ns("seed_admin_others"),
-            "test_db",
-            SHERPA_PASSWORD,
-        )
-        .await?;
+        let db = connect_test_db("seed_admin_others").await?;
         crate::schema::apply_schema(&db).await?;
 
         // Create a regular user first
@@ -128,14 +138,7 @@ mod tests {
     #[tokio::test]
     #[ignore] // Requires running SurrealDB instance
     async fn test_seed_admin_user_validates_password() -> Result<()> {
-        let db = crate::connect(
-            "localhost",
-            test_db_port(),
-            &unique_ns("seed_admin_validate"),
-            "test_db",
-            SHERPA_PASSWORD,
-        )
-        .await?;
+        let db = connect_test_db("seed_admin_validate").await?;
         crate::schema::apply_schema(&db).await?;
 
         // Try with invalid password
@@ -155,14 +158,7 @@ mod tests {
     #[tokio::test]
     #[ignore] // Requires running SurrealDB instance
     async fn test_seed_admin_user_idempotent() -> Result<()> {
-        let db = crate::connect(
-            "localhost",
-            test_db_port(),
-            &unique_ns("seed_admin_idem"),
-            "test_db",
-            SHERPA_PASSWORD,
-        )
-        .await?;
+        let db = connect_test_db("seed_admin_idem").await?;
         crate::schema::apply_schema(&db).await?;
 
         // First call should create
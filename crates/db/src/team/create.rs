@@ -0,0 +1,107 @@
+use anyhow::{Context, Result, anyhow};
+use jiff::Timestamp;
+use shared::data::{DbTeam, RecordId};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::TeamRow;
+
+/// Validate team name format according to schema constraints
+///
+/// Rules:
+/// - Minimum 3 characters
+/// - Only alphanumeric characters plus ._-
+fn validate_team_name(name: &str) -> Result<()> {
+    if name.len() < 3 {
+        return Err(anyhow!(
+            "Team name must be at least 3 characters long, got: {}",
+            name.len()
+        ));
+    }
+
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-');
+
+    if !valid_chars {
+        return Err(anyhow!(
+            "Team name can only contain alphanumeric characters and ._- symbols. Got: '{}'",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Create a new team in the database
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `name` - Team name (min 3 chars, alphanumeric + ._-)
+/// * `owner` - RecordId of the user managing the team
+/// * `members` - RecordIds of the initial team members
+///
+/// # Returns
+/// The created DbTeam with assigned ID
+///
+/// # Errors
+/// - If team name validation fails
+/// - If the team name already exists (unique constraint violation)
+/// - If there's a database error during creation
+#[instrument(skip(db), level = "debug")]
+pub async fn create_team(
+    db: &Arc<Surreal<Any>>,
+    name: &str,
+    owner: RecordId,
+    members: Vec<RecordId>,
+) -> Result<DbTeam> {
+    validate_team_name(name)?;
+
+    let now = Timestamp::now();
+    let team = DbTeam {
+        id: None,
+        name: name.to_string(),
+        owner,
+        members,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created: Option<TeamRow> = db
+        .create("team")
+        .content(TeamRow::try_from(&team)?)
+        .await
+        .context(format!("Failed to create team: '{}'", name))?;
+
+    created
+        .map(DbTeam::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("Team was not created: '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_team_name_valid() {
+        assert!(validate_team_name("students").is_ok());
+        assert!(validate_team_name("class-2026.q1").is_ok());
+        assert!(validate_team_name("net_ops").is_ok());
+    }
+
+    #[test]
+    fn test_validate_team_name_too_short() {
+        let result = validate_team_name("ab");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("at least 3"));
+    }
+
+    #[test]
+    fn test_validate_team_name_invalid_chars() {
+        assert!(validate_team_name("team name").is_err());
+        assert!(validate_team_name("team@corp").is_err());
+    }
+}
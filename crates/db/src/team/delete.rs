@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use shared::data::RecordId;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::to_surreal_id;
+
+/// Delete a team
+///
+/// Lab shares granted to the team are removed by the database
+/// (`REFERENCE ON DELETE CASCADE`).
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `team_id` - RecordId of the team to delete
+///
+/// # Returns
+/// Ok(()) if successful
+///
+/// # Errors
+/// - If there's a database error
+#[instrument(skip(db), level = "debug")]
+pub async fn delete_team(db: &Arc<Surreal<Any>>, team_id: &RecordId) -> Result<()> {
+    let _: Option<surrealdb_types::RecordId> = db
+        .delete::<Option<surrealdb_types::RecordId>>(to_surreal_id(team_id))
+        .await
+        .context(format!("Failed to delete team: team_id={:?}", team_id))?;
+
+    Ok(())
+}
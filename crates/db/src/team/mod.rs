@@ -0,0 +1,22 @@
+//! Team CRUD operations
+//!
+//! This module provides create, read, update, and delete operations
+//! for team records. Teams group users so that labs can be shared
+//! with all members at once.
+
+mod create;
+mod delete;
+mod read;
+mod update;
+
+// Public exports - CREATE operations
+pub use create::create_team;
+
+// Public exports - READ operations
+pub use read::{get_team_by_name, get_team_owner_username, list_teams};
+
+// Public exports - UPDATE operations
+pub use update::update_team;
+
+// Public exports - DELETE operations
+pub use delete::delete_team;
@@ -0,0 +1,107 @@
+use anyhow::{Context, Result, anyhow};
+use shared::data::{DbTeam, TeamInfo};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use surrealdb_types::SurrealValue;
+use tracing::instrument;
+
+use crate::persistence::TeamRow;
+
+/// Get a team by name
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `name` - The team name to search for
+///
+/// # Returns
+/// The DbTeam if found
+///
+/// # Errors
+/// - If the team is not found
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn get_team_by_name(db: &Arc<Surreal<Any>>, name: &str) -> Result<DbTeam> {
+    let mut response = db
+        .query("SELECT * FROM ONLY team WHERE name = $name")
+        .bind(("name", name.to_string()))
+        .await
+        .context(format!("Failed to query team from database: {}", name))?;
+
+    let team: Option<TeamRow> = response.take(0)?;
+    team.map(DbTeam::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("Team not found: {}", name))
+}
+
+/// Get the username of a team's owner
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `name` - The team name
+///
+/// # Returns
+/// The username of the team owner
+///
+/// # Errors
+/// - If the team is not found
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn get_team_owner_username(db: &Arc<Surreal<Any>>, name: &str) -> Result<String> {
+    let mut response = db
+        .query("SELECT owner.username AS username FROM ONLY team WHERE name = $name")
+        .bind(("name", name.to_string()))
+        .await
+        .context(format!(
+            "Failed to query team owner from database: {}",
+            name
+        ))?;
+
+    #[derive(serde::Deserialize, SurrealValue)]
+    struct OwnerResult {
+        username: String,
+    }
+
+    let result: Option<OwnerResult> = response.take(0)?;
+    result
+        .map(|r| r.username)
+        .ok_or_else(|| anyhow!("Team not found or owner not found: {}", name))
+}
+
+/// List all teams with their owner and member usernames
+///
+/// # Arguments
+/// * `db` - Database connection
+///
+/// # Returns
+/// Vector of TeamInfo ordered by team name
+///
+/// # Errors
+/// - If there's a database error during the query
+#[instrument(skip(db), level = "debug")]
+pub async fn list_teams(db: &Arc<Surreal<Any>>) -> Result<Vec<TeamInfo>> {
+    let mut response = db
+        .query(
+            "SELECT name, owner.username AS owner, members.username AS members \
+             FROM team ORDER BY name",
+        )
+        .await
+        .context("Failed to list teams from database")?;
+
+    #[derive(serde::Deserialize, SurrealValue)]
+    struct TeamInfoRow {
+        name: String,
+        owner: String,
+        members: Vec<String>,
+    }
+
+    let rows: Vec<TeamInfoRow> = response.take(0)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| TeamInfo {
+            name: row.name,
+            owner: row.owner,
+            members: row.members,
+        })
+        .collect())
+}
@@ -0,0 +1,49 @@
+use anyhow::{Context, Result, anyhow};
+use jiff::Timestamp;
+use shared::data::DbTeam;
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::Any;
+use tracing::instrument;
+
+use crate::persistence::{TeamRow, to_surreal_id};
+
+/// Update an existing team in the database
+///
+/// This performs a full replacement of all fields and bumps `updated_at`.
+/// The DbTeam must have a valid `id` field set.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `team` - DbTeam with updated fields and a valid `id`
+///
+/// # Returns
+/// The updated DbTeam on success
+///
+/// # Errors
+/// - If the team has no ID
+/// - If the record doesn't exist in the database
+/// - If there's a database error during the update
+#[instrument(skip(db, team), fields(team = %team.name), level = "debug")]
+pub async fn update_team(db: &Arc<Surreal<Any>>, team: DbTeam) -> Result<DbTeam> {
+    let id = team
+        .id
+        .clone()
+        .ok_or_else(|| anyhow!("Team record has no ID: '{}'", team.name))?;
+
+    let team = DbTeam {
+        updated_at: Timestamp::now(),
+        ..team
+    };
+
+    let updated: Option<TeamRow> = db
+        .update(to_surreal_id(&id))
+        .content(TeamRow::try_from(&team)?)
+        .await
+        .context(format!("Failed to update team: '{}'", team.name))?;
+
+    updated
+        .map(DbTeam::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("Team not found for update: '{}'", team.name))
+}
@@ -1,12 +1,14 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use jiff::Timestamp;
 use shared::auth::password::hash_password;
-use shared::data::DbUser;
+use shared::data::{AuthProvider, DbUser};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
+use super::read::get_user;
+use super::update::update_user;
 use crate::persistence::UserRow;
 
 /// Validate username format according to schema constraints
@@ -58,7 +60,7 @@ fn validate_username(username: &str) -> Result<()> {
 ///
 #[instrument(skip(db, password), level = "debug")]
 pub async fn create_user(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     username: String,
     password: &str,
     is_admin: bool,
@@ -78,6 +80,7 @@ pub async fn create_user(
         password_hash,
         is_admin,
         ssh_keys,
+        auth_provider: AuthProvider::Local,
         created_at: now,
         updated_at: now,
     };
@@ -118,7 +121,7 @@ pub async fn create_user(
 ///
 #[instrument(skip(db, password), level = "debug")]
 pub async fn upsert_user(
-    db: &Arc<Surreal<Client>>,
+    db: &Arc<Surreal<Any>>,
     username: String,
     password: &str,
     is_admin: bool,
@@ -150,6 +153,7 @@ pub async fn upsert_user(
         password_hash,
         is_admin,
         ssh_keys,
+        auth_provider: AuthProvider::Local,
         created_at,
         updated_at: now,
     };
@@ -168,6 +172,84 @@ pub async fn upsert_user(
         .ok_or_else(|| anyhow!("User was not upserted: '{}'", username))
 }
 
+/// Create or update a user authenticated by an external identity provider
+///
+/// New users are created with an empty password hash, so they can never log
+/// in with a local password. Existing users keep their SSH keys, and their admin
+/// flag is synced with the identity provider's group membership on every login.
+///
+/// # Arguments
+/// * `db` - Database connection
+/// * `username` - Username reported by the identity provider
+/// * `provider` - Identity provider that authenticated the user (not `Local`)
+/// * `is_admin` - Whether the identity provider grants admin privileges
+///
+/// # Returns
+/// The created or updated DbUser
+///
+/// # Errors
+/// - If `provider` is `AuthProvider::Local`
+/// - If username validation fails
+/// - If the username already belongs to a user of another auth provider
+/// - If there's a database error during the operation
+///
+#[instrument(skip(db), level = "debug")]
+pub async fn provision_external_user(
+    db: &Arc<Surreal<Any>>,
+    username: &str,
+    provider: AuthProvider,
+    is_admin: bool,
+) -> Result<DbUser> {
+    if provider == AuthProvider::Local {
+        bail!("Local users cannot be provisioned by an identity provider");
+    }
+
+    let existing = get_user(db, username).await.ok();
+
+    if let Some(mut user) = existing {
+        // Never let an identity provider take over an account it did not create
+        if user.auth_provider != provider {
+            bail!(
+                "User '{}' already exists with {} authentication",
+                username,
+                user.auth_provider
+            );
+        }
+        if user.is_admin == is_admin {
+            return Ok(user);
+        }
+        user.is_admin = is_admin;
+        user.updated_at = Timestamp::now();
+        return update_user(db, user).await;
+    }
+
+    validate_username(username)?;
+
+    let now = Timestamp::now();
+    let db_user = DbUser {
+        id: None,
+        username: username.to_string(),
+        password_hash: String::new(),
+        is_admin,
+        ssh_keys: vec![],
+        auth_provider: provider,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let row = UserRow::try_from(&db_user)?;
+    let created: Option<UserRow> = db
+        .create("user")
+        .content(row)
+        .await
+        .context(format!("Failed to provision user: '{}'", username))?;
+
+    created
+        .map(DbUser::try_from)
+        .transpose()?
+        .ok_or_else(|| anyhow!("User was not provisioned: '{}'", username))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow, bail};
 use shared::data::{DbLab, DbUser, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::helpers::get_user_id;
@@ -26,7 +26,7 @@ use crate::persistence::{LabRow, UserRow, to_surreal_id};
 /// - If there's a database error during deletion
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_user(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_user(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // Execute DELETE query
     let deleted: Option<UserRow> = db.delete(to_surreal_id(&id)).await.context(format!(
         "Failed to delete user: {:?}\nNote: This will cascade delete all labs owned by this user",
@@ -57,7 +57,7 @@ pub async fn delete_user(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()>
 /// - If there's a database error during deletion
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_user_by_username(db: &Arc<Surreal<Client>>, username: &str) -> Result<()> {
+pub async fn delete_user_by_username(db: &Arc<Surreal<Any>>, username: &str) -> Result<()> {
     // First get the user to obtain their ID
     let mut response = db
         .query("SELECT * FROM ONLY user WHERE username = $username")
@@ -96,7 +96,7 @@ pub async fn delete_user_by_username(db: &Arc<Surreal<Client>>, username: &str)
 /// - If there's a database error during the operation
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn delete_user_safe(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<()> {
+pub async fn delete_user_safe(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<()> {
     // First check if the user exists
     let user: Option<UserRow> = db
         .select(to_surreal_id(&id))
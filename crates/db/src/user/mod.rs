@@ -4,7 +4,7 @@ mod read;
 mod update;
 
 // Public exports - CREATE operations
-pub use create::{create_user, upsert_user};
+pub use create::{create_user, provision_external_user, upsert_user};
 
 // Public exports - READ operations
 pub use read::{count_users, get_user, get_user_by_id, get_user_for_auth, list_users};
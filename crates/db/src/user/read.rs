@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::{DbUser, RecordId};
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::persistence::{UserRow, to_surreal_id};
@@ -23,7 +23,7 @@ use crate::persistence::{UserRow, to_surreal_id};
 /// - If there's a database error during the query
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_user(db: &Arc<Surreal<Client>>, username: &str) -> Result<DbUser> {
+pub async fn get_user(db: &Arc<Surreal<Any>>, username: &str) -> Result<DbUser> {
     let mut response = db
         .query("SELECT * FROM ONLY user WHERE username = $username")
         .bind(("username", username.to_string()))
@@ -49,7 +49,7 @@ pub async fn get_user(db: &Arc<Surreal<Client>>, username: &str) -> Result<DbUse
 /// - If there's a database error during the query
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn get_user_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<Option<DbUser>> {
+pub async fn get_user_by_id(db: &Arc<Surreal<Any>>, id: RecordId) -> Result<Option<DbUser>> {
     let user: Option<UserRow> = db
         .select(to_surreal_id(&id))
         .await
@@ -70,7 +70,7 @@ pub async fn get_user_by_id(db: &Arc<Surreal<Client>>, id: RecordId) -> Result<O
 /// - If there's a database error during the query
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn list_users(db: &Arc<Surreal<Client>>) -> Result<Vec<DbUser>> {
+pub async fn list_users(db: &Arc<Surreal<Any>>) -> Result<Vec<DbUser>> {
     let users: Vec<UserRow> = db
         .select("user")
         .await
@@ -91,7 +91,7 @@ pub async fn list_users(db: &Arc<Surreal<Client>>) -> Result<Vec<DbUser>> {
 /// - If there's a database error during the query
 ///
 #[instrument(skip(db), level = "debug")]
-pub async fn count_users(db: &Arc<Surreal<Client>>) -> Result<usize> {
+pub async fn count_users(db: &Arc<Surreal<Any>>) -> Result<usize> {
     let users: Vec<UserRow> = db
         .select("user")
         .await
@@ -102,7 +102,8 @@ pub async fn count_users(db: &Arc<Surreal<Client>>) -> Result<usize> {
 
 /// Get a user by username for authentication purposes
 ///
-/// This is the same as get_user but explicitly named for authentication use cases.
+/// Unlike get_user, a missing user is not an error, so callers can tell it apart
+/// from a failed query.
 /// Returns the full user record including password_hash field needed for verification.
 ///
 /// # Arguments
@@ -110,16 +111,23 @@ pub async fn count_users(db: &Arc<Surreal<Client>>) -> Result<usize> {
 /// * `username` - The username to search for
 ///
 /// # Returns
-/// The DbUser if found, including password_hash for authentication
+/// Some(DbUser) if found, including password_hash for authentication,
+/// None if not found
 ///
 /// # Errors
-/// - If the user is not found
 /// - If there's a database error during the query
 ///
 /// # Security Note
 /// This function returns the password hash. Only use it for authentication purposes
 /// and never expose the hash in API responses or logs.
 #[instrument(skip(db), level = "debug")]
-pub async fn get_user_for_auth(db: &Arc<Surreal<Client>>, username: &str) -> Result<DbUser> {
-    get_user(db, username).await
+pub async fn get_user_for_auth(db: &Arc<Surreal<Any>>, username: &str) -> Result<Option<DbUser>> {
+    let mut response = db
+        .query("SELECT * FROM ONLY user WHERE username = $username")
+        .bind(("username", username.to_string()))
+        .await
+        .context(format!("Failed to query user from database: {}", username))?;
+
+    let user: Option<UserRow> = response.take(0)?;
+    user.map(DbUser::try_from).transpose()
 }
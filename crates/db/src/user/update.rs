@@ -2,7 +2,7 @@ use anyhow::{Context, Result, anyhow};
 use shared::data::DbUser;
 use std::sync::Arc;
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::Client;
+use surrealdb::engine::any::Any;
 use tracing::instrument;
 
 use crate::helpers::get_user_id;
@@ -30,7 +30,7 @@ use crate::persistence::{UserRow, to_surreal_id};
 /// - If there's a database error during the update
 ///
 #[instrument(skip(db, user), level = "debug")]
-pub async fn update_user(db: &Arc<Surreal<Client>>, user: DbUser) -> Result<DbUser> {
+pub async fn update_user(db: &Arc<Surreal<Any>>, user: DbUser) -> Result<DbUser> {
     // Extract and validate the ID
     let id = get_user_id(&user)?;
 
@@ -0,0 +1,82 @@
+/// CREATE operation tests for api_token
+use anyhow::Result;
+use db::{create_api_token, create_user};
+use jiff::{Timestamp, ToSpan};
+use shared::data::{DbApiToken, RecordId, TokenScope};
+
+use crate::{setup_db, teardown_db};
+
+pub(crate) fn test_token(user: RecordId, name: &str, token_hash: &str) -> DbApiToken {
+    let now = Timestamp::now();
+    DbApiToken {
+        id: None,
+        name: name.to_string(),
+        user,
+        token_hash: token_hash.to_string(),
+        prefix: "sherpa_pat_01234567".to_string(),
+        scopes: vec![TokenScope::LabOperate],
+        expires_at: now + 24.hours(),
+        last_used_at: None,
+        created_at: now,
+    }
+}
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_create_api_token() -> Result<()> {
+    let db = setup_db("test_create_api_token").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let user_id = user.id.expect("user id");
+
+    let token = create_api_token(&db, test_token(user_id.clone(), "gitlab-ci", "digest1")).await?;
+
+    assert!(token.id.is_some());
+    assert_eq!(token.name, "gitlab-ci");
+    assert_eq!(token.user, user_id);
+    assert_eq!(token.scopes, vec![TokenScope::LabOperate]);
+    assert_eq!(token.last_used_at, None);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_create_api_token_duplicate_name_fails() -> Result<()> {
+    let db = setup_db("test_create_api_token_duplicate_name_fails").await?;
+
+    let alice = create_user(&db, "alice".to_string(), "TestPass123!", false, vec![]).await?;
+    let bob = create_user(&db, "bob".to_string(), "TestPass123!", false, vec![]).await?;
+    let alice_id = alice.id.expect("alice id");
+
+    create_api_token(&db, test_token(alice_id.clone(), "ci-token", "digest1")).await?;
+    let result = create_api_token(&db, test_token(alice_id, "ci-token", "digest2")).await;
+    assert!(result.is_err());
+
+    // The same name is fine for another user
+    create_api_token(
+        &db,
+        test_token(bob.id.expect("bob id"), "ci-token", "digest3"),
+    )
+    .await?;
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_create_api_token_without_scopes_fails() -> Result<()> {
+    let db = setup_db("test_create_api_token_without_scopes_fails").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let token = DbApiToken {
+        scopes: vec![],
+        ..test_token(user.id.expect("user id"), "ci-token", "digest1")
+    };
+    assert!(create_api_token(&db, token).await.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
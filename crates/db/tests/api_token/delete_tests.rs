@@ -0,0 +1,41 @@
+/// DELETE operation tests for api_token
+use anyhow::Result;
+use db::{create_api_token, create_user, delete_api_token, delete_user, get_api_token_by_hash};
+
+use super::create_tests::test_token;
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_delete_api_token() -> Result<()> {
+    let db = setup_db("test_delete_api_token").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let user_id = user.id.expect("user id");
+    create_api_token(&db, test_token(user_id.clone(), "ci-token", "digest1")).await?;
+
+    assert!(delete_api_token(&db, &user_id, "ci-token").await?);
+    assert!(get_api_token_by_hash(&db, "digest1").await?.is_none());
+
+    // Revoking again reports that nothing was deleted
+    assert!(!delete_api_token(&db, &user_id, "ci-token").await?);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_delete_user_revokes_api_tokens() -> Result<()> {
+    let db = setup_db("test_delete_user_revokes_api_tokens").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let user_id = user.id.expect("user id");
+    create_api_token(&db, test_token(user_id.clone(), "ci-token", "digest1")).await?;
+
+    delete_user(&db, user_id).await?;
+    assert!(get_api_token_by_hash(&db, "digest1").await?.is_none());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+/// READ and UPDATE operation tests for api_token
+use anyhow::Result;
+use db::{
+    create_api_token, create_user, get_api_token_by_hash, list_api_tokens_by_user, touch_api_token,
+};
+
+use super::create_tests::test_token;
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_get_api_token_by_hash() -> Result<()> {
+    let db = setup_db("test_get_api_token_by_hash").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let created = create_api_token(
+        &db,
+        test_token(user.id.expect("user id"), "ci-token", "digest1"),
+    )
+    .await?;
+
+    let found = get_api_token_by_hash(&db, "digest1")
+        .await?
+        .expect("token should be found");
+    assert_eq!(found.id, created.id);
+
+    assert!(get_api_token_by_hash(&db, "unknown").await?.is_none());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_list_api_tokens_by_user() -> Result<()> {
+    let db = setup_db("test_list_api_tokens_by_user").await?;
+
+    let alice = create_user(&db, "alice".to_string(), "TestPass123!", false, vec![]).await?;
+    let bob = create_user(&db, "bob".to_string(), "TestPass123!", false, vec![]).await?;
+    let alice_id = alice.id.expect("alice id");
+
+    create_api_token(&db, test_token(alice_id.clone(), "zeta", "digest1")).await?;
+    create_api_token(&db, test_token(alice_id.clone(), "alpha", "digest2")).await?;
+    create_api_token(&db, test_token(bob.id.expect("bob id"), "other", "digest3")).await?;
+
+    let tokens = list_api_tokens_by_user(&db, &alice_id).await?;
+    let names: Vec<&str> = tokens.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["alpha", "zeta"]);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_touch_api_token_sets_last_used() -> Result<()> {
+    let db = setup_db("test_touch_api_token_sets_last_used").await?;
+
+    let user = create_user(&db, "ci-user".to_string(), "TestPass123!", false, vec![]).await?;
+    let created = create_api_token(
+        &db,
+        test_token(user.id.expect("user id"), "ci-token", "digest1"),
+    )
+    .await?;
+
+    touch_api_token(&db, &created.id.expect("token id")).await?;
+
+    let found = get_api_token_by_hash(&db, "digest1")
+        .await?
+        .expect("token should be found");
+    assert!(found.last_used_at.is_some());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
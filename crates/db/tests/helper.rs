@@ -1,6 +1,6 @@
 use anyhow::Result;
 use db::{Database, apply_schema};
-use shared::data::{DbLab, DbNode, NodeConfig, NodeModel};
+use shared::data::{DatabaseEngine, DbLab, DbNode, NodeConfig, NodeModel};
 use shared::konst::SHERPA_PASSWORD;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -16,9 +16,17 @@ fn generate_test_namespace(test_name: &str) -> String {
 }
 
 /// Helper to setup test database connection with a unique namespace
-/// This ensures test isolation by using a dedicated namespace per test run
+/// This ensures test isolation by using a dedicated namespace per test run.
+/// Set `SHERPA_TEST_DB_ENGINE=memory` to use an embedded in-memory database
+/// instead of the `dev/testdb` container.
 pub async fn setup_db(namespace: &str) -> Result<Database> {
     let namespace = generate_test_namespace(namespace);
+    if std::env::var("SHERPA_TEST_DB_ENGINE").is_ok_and(|v| v == "memory") {
+        let db = db::connect_embedded(DatabaseEngine::Memory, "", &namespace, "test_cases").await?;
+        apply_schema(&db).await?;
+        return Ok(db);
+    }
+
     let db_password =
         std::env::var("SHERPA_DB_PASSWORD").unwrap_or_else(|_| SHERPA_PASSWORD.to_string());
     let db_port: u16 = std::env::var("SHERPA_DEV_DB_PORT")
@@ -41,12 +49,16 @@ pub async fn teardown_db(db: &Database) -> Result<()> {
     // so we'll use a query to remove all records from tables we created
 
     // Delete all test data in dependency order (children before parents)
+    db.query("DELETE api_token").await?;
+    db.query("DELETE lab_share").await?;
+    db.query("DELETE team").await?;
     db.query("DELETE link").await?;
     db.query("DELETE bridge").await?;
     db.query("DELETE node").await?;
     db.query("DELETE lab").await?;
     db.query("DELETE node_image").await?;
     db.query("DELETE user").await?;
+    db.query("DELETE schema_migration").await?;
 
     Ok(())
 }
@@ -70,7 +82,7 @@ pub async fn create_test_node_with_model(
     use db::{create_node, get_node_image_by_model_kind_version};
 
     // Get the model kind from the NodeModel
-    let config_template = NodeConfig::get_model(model.clone());
+    let config_template = NodeConfig::get_model(model);
     let kind = config_template.kind;
     let version = config_template.version;
 
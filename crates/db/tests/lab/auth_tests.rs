@@ -1,6 +1,9 @@
 /// Authentication/authorization tests for lab operations
 use anyhow::Result;
-use db::{create_lab, create_user, get_lab_owner_username};
+use db::{
+    create_lab, create_team, create_user, get_lab_owner_username, get_lab_role, upsert_lab_share,
+};
+use shared::data::LabRole;
 
 use crate::{setup_db, teardown_db};
 
@@ -46,3 +49,52 @@ async fn test_get_lab_owner_username_not_found() -> Result<()> {
     teardown_db(&db).await?;
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_get_lab_role() -> Result<()> {
+    let db = setup_db("test_get_lab_role").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let viewer = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    create_user(&db, "outsider".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let lab_id = lab.id.expect("lab id");
+    let viewer_id = viewer.id.expect("viewer id");
+
+    upsert_lab_share(&db, &lab_id, &viewer_id, LabRole::Viewer).await?;
+
+    // Team membership grants a higher role than the direct share
+    let team = create_team(
+        &db,
+        "operators",
+        owner.id.expect("owner id"),
+        vec![viewer_id],
+    )
+    .await?;
+    upsert_lab_share(&db, &lab_id, &team.id.expect("team id"), LabRole::Operator).await?;
+
+    assert_eq!(
+        get_lab_role(&db, "lab-0001", "instructor").await?,
+        Some(LabRole::Owner)
+    );
+    assert_eq!(
+        get_lab_role(&db, "lab-0001", "student1").await?,
+        Some(LabRole::Operator)
+    );
+    assert_eq!(get_lab_role(&db, "lab-0001", "outsider").await?, None);
+    assert!(get_lab_role(&db, "no-exist", "instructor").await.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+/// CREATE operation tests for lab_share
+use anyhow::Result;
+use db::{create_lab, create_team, create_user, list_lab_shares, upsert_lab_share};
+use shared::data::{LabRole, RecordId};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_upsert_lab_share_with_user() -> Result<()> {
+    let db = setup_db("test_upsert_lab_share_with_user").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let lab_id = lab.id.expect("lab id");
+    let student_id = student.id.expect("student id");
+
+    let share = upsert_lab_share(&db, &lab_id, &student_id, LabRole::Viewer).await?;
+    assert!(share.id.is_some());
+    assert_eq!(share.user, Some(student_id.clone()));
+    assert_eq!(share.team, None);
+    assert_eq!(share.role, LabRole::Viewer);
+
+    // Sharing again replaces the role instead of adding a second share
+    let updated = upsert_lab_share(&db, &lab_id, &student_id, LabRole::Operator).await?;
+    assert_eq!(updated.id, share.id);
+    assert_eq!(updated.role, LabRole::Operator);
+
+    let shares = list_lab_shares(&db, "lab-0001").await?;
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[0].username.as_deref(), Some("student1"));
+    assert_eq!(shares[0].role, LabRole::Operator);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_upsert_lab_share_with_team() -> Result<()> {
+    let db = setup_db("test_upsert_lab_share_with_team").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let team = create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+    let team_id = team.id.expect("team id");
+
+    let share = upsert_lab_share(&db, &lab.id.expect("lab id"), &team_id, LabRole::Viewer).await?;
+    assert_eq!(share.user, None);
+    assert_eq!(share.team, Some(team_id));
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_upsert_lab_share_rejects_owner_role() -> Result<()> {
+    let db = setup_db("test_upsert_lab_share_rejects_owner_role").await?;
+
+    let result = upsert_lab_share(
+        &db,
+        &RecordId::new("lab", "lab1"),
+        &RecordId::new("user", "user1"),
+        LabRole::Owner,
+    )
+    .await;
+    assert!(result.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
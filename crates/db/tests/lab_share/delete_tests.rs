@@ -0,0 +1,68 @@
+/// DELETE operation tests for lab_share
+use anyhow::Result;
+use db::{
+    create_lab, create_user, delete_lab_share, delete_user, list_lab_shares, upsert_lab_share,
+};
+use shared::data::LabRole;
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_delete_lab_share() -> Result<()> {
+    let db = setup_db("test_delete_lab_share").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let lab_id = lab.id.expect("lab id");
+    let student_id = student.id.expect("student id");
+
+    upsert_lab_share(&db, &lab_id, &student_id, LabRole::Viewer).await?;
+
+    assert!(delete_lab_share(&db, &lab_id, &student_id).await?);
+    assert!(!delete_lab_share(&db, &lab_id, &student_id).await?);
+    assert!(list_lab_shares(&db, "lab-0001").await?.is_empty());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_delete_user_cascades_to_lab_share() -> Result<()> {
+    let db = setup_db("test_delete_user_cascades_to_lab_share").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let student_id = student.id.expect("student id");
+
+    upsert_lab_share(&db, &lab.id.expect("lab id"), &student_id, LabRole::Viewer).await?;
+    delete_user(&db, student_id).await?;
+
+    assert!(list_lab_shares(&db, "lab-0001").await?.is_empty());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,3 @@
+mod create_tests;
+mod delete_tests;
+mod read_tests;
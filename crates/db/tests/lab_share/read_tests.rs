@@ -0,0 +1,124 @@
+/// READ operation tests for lab_share
+use anyhow::Result;
+use db::{
+    create_lab, create_team, create_user, list_lab_shares, list_labs_shared_with_user,
+    upsert_lab_share,
+};
+use shared::data::LabRole;
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_list_lab_shares_resolves_grantees() -> Result<()> {
+    let db = setup_db("test_list_lab_shares_resolves_grantees").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Test Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let lab_id = lab.id.expect("lab id");
+    let team = create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+
+    upsert_lab_share(
+        &db,
+        &lab_id,
+        &student.id.expect("student id"),
+        LabRole::Operator,
+    )
+    .await?;
+    upsert_lab_share(&db, &lab_id, &team.id.expect("team id"), LabRole::Viewer).await?;
+
+    let shares = list_lab_shares(&db, "lab-0001").await?;
+    assert_eq!(shares.len(), 2);
+    assert_eq!(shares[0].grantee(), "student1");
+    assert_eq!(shares[0].role, LabRole::Operator);
+    assert_eq!(shares[1].grantee(), "team:students");
+    assert_eq!(shares[1].role, LabRole::Viewer);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_list_labs_shared_with_user() -> Result<()> {
+    let db = setup_db("test_list_labs_shared_with_user").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let student_id = student.id.expect("student id");
+
+    let direct = create_lab(
+        &db,
+        "Direct Lab",
+        "lab-0001",
+        &owner,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let via_team = create_lab(
+        &db,
+        "Team Lab",
+        "lab-0002",
+        &owner,
+        "127.127.2.0/24",
+        "172.31.2.0/24",
+        "172.31.2.1",
+        "172.31.2.2",
+    )
+    .await?;
+    create_lab(
+        &db,
+        "Private Lab",
+        "lab-0003",
+        &owner,
+        "127.127.3.0/24",
+        "172.31.3.0/24",
+        "172.31.3.1",
+        "172.31.3.2",
+    )
+    .await?;
+
+    let team = create_team(
+        &db,
+        "students",
+        owner.id.expect("owner id"),
+        vec![student_id.clone()],
+    )
+    .await?;
+
+    upsert_lab_share(
+        &db,
+        &direct.id.expect("lab id"),
+        &student_id,
+        LabRole::Viewer,
+    )
+    .await?;
+    upsert_lab_share(
+        &db,
+        &via_team.id.expect("lab id"),
+        &team.id.expect("team id"),
+        LabRole::Viewer,
+    )
+    .await?;
+
+    let labs = list_labs_shared_with_user(&db, &student_id).await?;
+    let lab_ids: Vec<&str> = labs.iter().map(|lab| lab.lab_id.as_str()).collect();
+    assert_eq!(lab_ids, vec!["lab-0001", "lab-0002"]);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -65,9 +65,46 @@ mod node;
 /// - Only DELETE tests: cargo test --package db link::delete_tests -- --ignored --test-threads=1
 mod link;
 
+/// Integration tests for team CRUD operations
+///
+/// These tests require a running SurrealDB instance.
+/// Run: surreal start --log trace --user sherpa --pass 'Everest1953!' memory
+///
+/// To run these tests:
+/// - All team tests: cargo test --package db team -- --ignored
+mod team;
+
+/// Integration tests for lab share operations
+///
+/// These tests require a running SurrealDB instance.
+/// Run: surreal start --log trace --user sherpa --pass 'Everest1953!' memory
+///
+/// To run these tests:
+/// - All lab share tests: cargo test --package db lab_share -- --ignored
+mod lab_share;
+
+/// Integration tests for API token operations
+///
+/// These tests require a running SurrealDB instance.
+/// Run: surreal start --log trace --user sherpa --pass 'Everest1953!' memory
+///
+/// To run these tests:
+/// - All API token tests: cargo test --package db api_token -- --ignored
+mod api_token;
+
+/// Integration tests for registry credential operations
+///
+/// These tests require a running SurrealDB instance.
+/// Run: surreal start --log trace --user sherpa --pass 'Everest1953!' memory
+///
+/// To run these tests:
+/// - All registry credential tests: cargo test --package db registry_credential -- --ignored
+mod registry_credential;
+
 /// Schema and seeding tests
 ///
-/// To run: cargo test --package db schema -- --ignored
+/// The embedded in-memory engine tests always run.
+/// To run the rest: cargo test --package db schema -- --ignored
 mod schema;
 
 /// Cross-table relationship tests (cascade deletes, query isolation)
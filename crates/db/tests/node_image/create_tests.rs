@@ -60,3 +60,25 @@ async fn test_upsert_node_image() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_create_node_image_with_sha256() -> Result<()> {
+    let db = setup_db("test_create_node_image_with_sha256").await?;
+
+    let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    let mut test_config = create_test_config(NodeModel::CiscoCat8000v);
+    test_config.image_sha256 = Some(digest.to_string());
+    let created = create_node_image(&db, test_config).await?;
+    assert_eq!(created.image_sha256.as_deref(), Some(digest));
+
+    // Digests must be lowercase hex SHA-256
+    let mut invalid = create_test_config(NodeModel::CiscoCat9000v);
+    invalid.image_sha256 = Some("not-a-digest".to_string());
+    assert!(create_node_image(&db, invalid).await.is_err());
+
+    // Cleanup
+    teardown_db(&db).await?;
+
+    Ok(())
+}
@@ -1,8 +1,9 @@
 /// READ operation tests for node_image
 use anyhow::Result;
 use db::{
-    count_node_images, create_node_image, get_node_image_by_id,
-    get_node_image_by_model_kind_version, list_node_images,
+    count_node_images, create_lab, create_node, create_node_image, create_user, delete_node,
+    get_node_image_by_id, get_node_image_by_model_kind_version, list_node_image_usage,
+    list_node_images,
 };
 use shared::data::{NodeKind, NodeModel};
 
@@ -131,3 +132,68 @@ async fn test_list_configs_returns_all_fields() -> Result<()> {
     teardown_db(&db).await?;
     Ok(())
 }
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_list_node_image_usage() -> Result<()> {
+    let db = setup_db("test_list_node_image_usage").await?;
+
+    let user = create_user(&db, "usage".to_string(), "TestPass123!", false, vec![]).await?;
+    let lab = create_lab(
+        &db,
+        "Usage Lab",
+        "usage001",
+        &user,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let image = create_node_image(&db, create_test_config(NodeModel::UbuntuLinux)).await?;
+    let unused = create_node_image(&db, create_test_config(NodeModel::AlpineLinux)).await?;
+
+    let created = list_node_image_usage(&db).await?;
+    let stamped_at = created
+        .iter()
+        .find(|usage| Some(&usage.id) == image.id.as_ref())
+        .and_then(|usage| usage.last_used_at)
+        .expect("New image should have a last used time");
+
+    let node = create_node(
+        &db,
+        "node1",
+        1,
+        image.id.clone().unwrap(),
+        lab.id.clone().unwrap(),
+    )
+    .await?;
+
+    let usage = list_node_image_usage(&db).await?;
+    let used = usage
+        .iter()
+        .find(|usage| Some(&usage.id) == image.id.as_ref())
+        .expect("Should find the used image");
+    assert_eq!(used.labs, vec!["usage001".to_string()]);
+    assert_eq!(used.node_count, 1);
+    assert!(used.last_used_at.unwrap() >= stamped_at);
+
+    let idle = usage
+        .iter()
+        .find(|usage| Some(&usage.id) == unused.id.as_ref())
+        .expect("Should find the unused image");
+    assert!(idle.labs.is_empty());
+    assert_eq!(idle.node_count, 0);
+
+    delete_node(&db, node.id.unwrap()).await?;
+    let usage = list_node_image_usage(&db).await?;
+    let released = usage
+        .iter()
+        .find(|usage| Some(&usage.id) == image.id.as_ref())
+        .expect("Should find the released image");
+    assert_eq!(released.node_count, 0);
+    assert!(released.last_used_at.unwrap() >= used.last_used_at.unwrap());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+/// CREATE operation tests for registry_credential
+use anyhow::Result;
+use db::{get_registry_credential, upsert_registry_credential};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_upsert_registry_credential_creates() -> Result<()> {
+    let db = setup_db("test_upsert_registry_credential_creates").await?;
+
+    let created =
+        upsert_registry_credential(&db, "harbor.example.com", "robot", "encrypted1").await?;
+
+    assert!(created.id.is_some());
+    assert_eq!(created.registry, "harbor.example.com");
+    assert_eq!(created.username, "robot");
+    assert_eq!(created.password_encrypted, "encrypted1");
+    assert_eq!(created.created_at, created.updated_at);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_upsert_registry_credential_replaces() -> Result<()> {
+    let db = setup_db("test_upsert_registry_credential_replaces").await?;
+
+    let first =
+        upsert_registry_credential(&db, "harbor.example.com", "robot", "encrypted1").await?;
+    let second =
+        upsert_registry_credential(&db, "harbor.example.com", "admin", "encrypted2").await?;
+
+    assert_eq!(second.id, first.id);
+    assert_eq!(second.created_at, first.created_at);
+
+    let stored = get_registry_credential(&db, "harbor.example.com")
+        .await?
+        .expect("stored credential");
+    assert_eq!(stored.username, "admin");
+    assert_eq!(stored.password_encrypted, "encrypted2");
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_upsert_registry_credential_rejects_empty_username() -> Result<()> {
+    let db = setup_db("test_upsert_registry_credential_rejects_empty_username").await?;
+
+    assert!(
+        upsert_registry_credential(&db, "harbor.example.com", "", "encrypted1")
+            .await
+            .is_err()
+    );
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+/// DELETE operation tests for registry_credential
+use anyhow::Result;
+use db::{delete_registry_credential, get_registry_credential, upsert_registry_credential};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_delete_registry_credential() -> Result<()> {
+    let db = setup_db("test_delete_registry_credential").await?;
+
+    upsert_registry_credential(&db, "harbor.example.com", "robot", "encrypted1").await?;
+
+    assert!(delete_registry_credential(&db, "harbor.example.com").await?);
+    assert!(
+        get_registry_credential(&db, "harbor.example.com")
+            .await?
+            .is_none()
+    );
+
+    // Logging out again reports that nothing was deleted
+    assert!(!delete_registry_credential(&db, "harbor.example.com").await?);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
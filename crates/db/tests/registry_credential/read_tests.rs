@@ -0,0 +1,35 @@
+/// READ operation tests for registry_credential
+use anyhow::Result;
+use db::{get_registry_credential, list_registry_credentials, upsert_registry_credential};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_get_registry_credential_missing() -> Result<()> {
+    let db = setup_db("test_get_registry_credential_missing").await?;
+
+    assert!(get_registry_credential(&db, "ghcr.io").await?.is_none());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_list_registry_credentials_ordered() -> Result<()> {
+    let db = setup_db("test_list_registry_credentials_ordered").await?;
+
+    upsert_registry_credential(&db, "harbor.example.com", "robot", "encrypted1").await?;
+    upsert_registry_credential(&db, "ghcr.io", "octocat", "encrypted2").await?;
+
+    let registries: Vec<String> = list_registry_credentials(&db)
+        .await?
+        .into_iter()
+        .map(|credential| credential.registry)
+        .collect();
+    assert_eq!(registries, vec!["ghcr.io", "harbor.example.com"]);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
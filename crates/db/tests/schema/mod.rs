@@ -1,7 +1,31 @@
 use anyhow::Result;
 
 use crate::{setup_db, teardown_db};
-use db::{apply_schema, count_users, create_user};
+use db::{
+    apply_schema, connect_embedded, count_users, create_user, list_applied_migrations, migrate,
+    migration_status,
+};
+use shared::data::DatabaseEngine;
+
+/// The embedded in-memory engine opens and takes the schema without a
+/// SurrealDB server.
+#[tokio::test]
+async fn test_memory_engine_applies_schema() -> Result<()> {
+    let db = connect_embedded(DatabaseEngine::Memory, "", "test_memory", "test_cases").await?;
+
+    apply_schema(&db).await?;
+
+    create_user(
+        &db,
+        "memory_user".to_string(),
+        "TestPass123!",
+        false,
+        vec![],
+    )
+    .await?;
+    assert_eq!(count_users(&db).await?, 1);
+    Ok(())
+}
 
 /// Schema application is idempotent — applying twice does not fail or corrupt data.
 /// setup_db already applies schema once. We apply it again and verify no error.
@@ -106,3 +130,214 @@ async fn test_schema_creates_all_tables() -> Result<()> {
     teardown_db(&db).await?;
     Ok(())
 }
+
+/// Migrations apply once in order, and a second run is a no-op.
+#[tokio::test]
+#[ignore]
+async fn test_migrate_applies_pending_once() -> Result<()> {
+    let db = setup_db("test_migrate_applies_pending_once").await?;
+
+    let applied = migrate(&db).await?;
+    let expected: Vec<u32> = db::migration::MIGRATIONS
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(applied, expected);
+
+    let status = migration_status(&db).await?;
+    assert_eq!(status.current_version, db::migration::latest_version());
+    assert_eq!(status.pending(), 0);
+
+    assert!(migrate(&db).await?.is_empty());
+    assert_eq!(list_applied_migrations(&db).await?.len(), expected.len());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+/// Create a lab with one P2p link between two nodes.
+async fn create_migration_link(db: &db::Database) -> Result<()> {
+    use db::{create_lab, create_node_image};
+    use shared::data::{BridgeKind, NodeConfig, NodeModel};
+
+    let user = create_user(
+        db,
+        "migration_user".to_string(),
+        "TestPass123!",
+        false,
+        vec![],
+    )
+    .await?;
+    create_node_image(db, NodeConfig::get_model(NodeModel::UbuntuLinux)).await?;
+    let lab = create_lab(
+        db,
+        "Migration Lab",
+        "migr0001",
+        &user,
+        "127.127.1.0/24",
+        "172.31.1.0/24",
+        "172.31.1.1",
+        "172.31.1.2",
+    )
+    .await?;
+    let node_a =
+        crate::create_test_node_with_model(db, "node1", 1, NodeModel::UbuntuLinux, &lab).await?;
+    let node_b =
+        crate::create_test_node_with_model(db, "node2", 2, NodeModel::UbuntuLinux, &lab).await?;
+    db::create_link(
+        db,
+        0,
+        BridgeKind::P2p,
+        node_a.id.expect("node has id"),
+        node_b.id.expect("node2 has id"),
+        "eth1".to_string(),
+        "eth1".to_string(),
+        "br-0".to_string(),
+        "br-0".to_string(),
+        "veth-a".to_string(),
+        "veth-b".to_string(),
+        "tpa0-migr0001".to_string(),
+        "tpb0-migr0001".to_string(),
+        lab.id.expect("lab has id"),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Migration 4 fills in the layer 2 filters of links written before they
+/// existed.
+#[tokio::test]
+async fn test_migrate_backfills_link_filters() -> Result<()> {
+    let db = connect_embedded(DatabaseEngine::Memory, "", "test_migrate", "test_cases").await?;
+    apply_schema(&db).await?;
+    create_migration_link(&db).await?;
+
+    // Strip the filters the way a link from before version 4 was stored. The
+    // fields are removed first so their defaults do not fill them back in.
+    db.query(
+        "REMOVE FIELD filter_drop ON TABLE link; \
+         REMOVE FIELD filter_allow ON TABLE link; \
+         UPDATE link UNSET filter_drop, filter_allow;",
+    )
+    .await?
+    .check()?;
+    apply_schema(&db).await?;
+
+    let unfiltered = "SELECT VALUE count() FROM link \
+         WHERE filter_drop = NONE OR filter_allow = NONE GROUP ALL";
+    let before: Option<usize> = db.query(unfiltered).await?.take(0)?;
+    assert_eq!(before, Some(1));
+
+    let applied = migrate(&db).await?;
+    assert!(applied.contains(&4));
+
+    let after: Option<usize> = db.query(unfiltered).await?.take(0)?;
+    assert_eq!(after.unwrap_or(0), 0);
+    let link = db::list_links(&db).await?.remove(0);
+    assert!(link.filter.is_empty());
+    Ok(())
+}
+
+/// Migration 2 fills in the taps and impairments of links written before
+/// they existed.
+#[tokio::test]
+async fn test_migrate_backfills_link_taps_and_impairments() -> Result<()> {
+    let db = connect_embedded(DatabaseEngine::Memory, "", "test_migrate", "test_cases").await?;
+    apply_schema(&db).await?;
+    create_migration_link(&db).await?;
+
+    let fields = [
+        "tap_a",
+        "tap_b",
+        "delay_us",
+        "jitter_us",
+        "loss_percent",
+        "reorder_percent",
+        "corrupt_percent",
+    ];
+    let strip: String = fields
+        .iter()
+        .map(|field| format!("REMOVE FIELD {field} ON TABLE link; "))
+        .collect();
+    db.query(format!("{strip}UPDATE link UNSET {};", fields.join(", ")))
+        .await?
+        .check()?;
+    apply_schema(&db).await?;
+
+    let missing = format!(
+        "SELECT VALUE count() FROM link WHERE {} GROUP ALL",
+        fields.map(|field| format!("{field} = NONE")).join(" OR ")
+    );
+    let before: Option<usize> = db.query(missing.as_str()).await?.take(0)?;
+    assert_eq!(before, Some(1));
+
+    let applied = migrate(&db).await?;
+    assert!(applied.contains(&2));
+
+    let after: Option<usize> = db.query(missing.as_str()).await?.take(0)?;
+    assert_eq!(after.unwrap_or(0), 0);
+    let link = db::list_links(&db).await?.remove(0);
+    assert_eq!(link.tap_a, "");
+    assert_eq!(link.delay_us, 0);
+    Ok(())
+}
+
+/// A database migrated by a newer binary is refused.
+#[tokio::test]
+#[ignore]
+async fn test_apply_schema_refuses_newer_database() -> Result<()> {
+    let db = setup_db("test_apply_schema_refuses_newer").await?;
+
+    db.query("CREATE schema_migration CONTENT { version: $version, name: 'from_future', applied_at: time::now() }")
+        .bind(("version", db::migration::latest_version() + 1))
+        .await?
+        .check()?;
+
+    assert!(apply_schema(&db).await.is_err());
+    assert!(migrate(&db).await.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+/// Exported records restore with their IDs and typed fields intact.
+#[tokio::test]
+#[ignore]
+async fn test_backup_round_trip() -> Result<()> {
+    use db::{BACKUP_TABLES, count_table, export_table, get_user, restore_tables};
+
+    let db = setup_db("test_backup_round_trip").await?;
+    let user = create_user(
+        &db,
+        "backup_user".to_string(),
+        "TestPass123!",
+        false,
+        vec![],
+    )
+    .await?;
+
+    let table = BACKUP_TABLES
+        .iter()
+        .find(|t| t.name == "user")
+        .expect("user table is backed up");
+    let statements = export_table(&db, table).await?;
+    assert_eq!(statements.len(), 1);
+
+    create_user(
+        &db,
+        "not_in_backup".to_string(),
+        "TestPass123!",
+        false,
+        vec![],
+    )
+    .await?;
+    restore_tables(&db, &[("user", statements)]).await?;
+
+    assert_eq!(count_table(&db, "user").await?, 1);
+    let restored = get_user(&db, "backup_user").await?;
+    assert_eq!(restored.id, user.id);
+    assert_eq!(restored.created_at, user.created_at);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
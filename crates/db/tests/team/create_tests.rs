@@ -0,0 +1,59 @@
+/// CREATE operation tests for team
+use anyhow::Result;
+use db::{create_team, create_user};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_create_team() -> Result<()> {
+    let db = setup_db("test_create_team").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+
+    let team = create_team(
+        &db,
+        "students",
+        owner.id.clone().expect("owner id"),
+        vec![student.id.clone().expect("student id")],
+    )
+    .await?;
+
+    assert!(team.id.is_some());
+    assert_eq!(team.name, "students");
+    assert_eq!(team.owner, owner.id.expect("owner id"));
+    assert_eq!(team.members.len(), 1);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_create_team_duplicate_name_fails() -> Result<()> {
+    let db = setup_db("test_create_team_duplicate_name_fails").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let owner_id = owner.id.expect("owner id");
+
+    create_team(&db, "students", owner_id.clone(), vec![]).await?;
+    let result = create_team(&db, "students", owner_id, vec![]).await;
+    assert!(result.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_create_team_invalid_name_fails() -> Result<()> {
+    let db = setup_db("test_create_team_invalid_name_fails").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let result = create_team(&db, "a b", owner.id.expect("owner id"), vec![]).await;
+    assert!(result.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
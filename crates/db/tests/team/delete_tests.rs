@@ -0,0 +1,35 @@
+/// DELETE operation tests for team
+use anyhow::Result;
+use db::{create_team, create_user, delete_team, delete_user, get_team_by_name};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_delete_team() -> Result<()> {
+    let db = setup_db("test_delete_team").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let team = create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+
+    delete_team(&db, &team.id.expect("team id")).await?;
+    assert!(get_team_by_name(&db, "students").await.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_delete_owner_cascades_to_team() -> Result<()> {
+    let db = setup_db("test_delete_owner_cascades_to_team").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    create_team(&db, "students", owner.id.clone().expect("owner id"), vec![]).await?;
+
+    delete_user(&db, owner.id.expect("owner id")).await?;
+    assert!(get_team_by_name(&db, "students").await.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
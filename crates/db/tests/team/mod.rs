@@ -0,0 +1,4 @@
+mod create_tests;
+mod delete_tests;
+mod read_tests;
+mod update_tests;
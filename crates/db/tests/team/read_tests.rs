@@ -0,0 +1,70 @@
+/// READ operation tests for team
+use anyhow::Result;
+use db::{create_team, create_user, get_team_by_name, get_team_owner_username, list_teams};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_get_team_by_name() -> Result<()> {
+    let db = setup_db("test_get_team_by_name").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let created = create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+
+    let team = get_team_by_name(&db, "students").await?;
+    assert_eq!(team.id, created.id);
+
+    let missing = get_team_by_name(&db, "no-exist").await;
+    assert!(missing.is_err());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_get_team_owner_username() -> Result<()> {
+    let db = setup_db("test_get_team_owner_username").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+
+    assert_eq!(
+        get_team_owner_username(&db, "students").await?,
+        "instructor"
+    );
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_list_teams() -> Result<()> {
+    let db = setup_db("test_list_teams").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let owner_id = owner.id.expect("owner id");
+
+    create_team(&db, "zeta", owner_id.clone(), vec![]).await?;
+    create_team(
+        &db,
+        "alpha",
+        owner_id,
+        vec![student.id.expect("student id")],
+    )
+    .await?;
+
+    let teams = list_teams(&db).await?;
+    assert_eq!(teams.len(), 2);
+    assert_eq!(teams[0].name, "alpha");
+    assert_eq!(teams[0].owner, "instructor");
+    assert_eq!(teams[0].members, vec!["student1".to_string()]);
+    assert_eq!(teams[1].name, "zeta");
+    assert!(teams[1].members.is_empty());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+/// UPDATE operation tests for team
+use anyhow::Result;
+use db::{create_team, create_user, get_team_by_name, update_team};
+
+use crate::{setup_db, teardown_db};
+
+#[tokio::test]
+#[ignore] // Requires running SurrealDB instance
+async fn test_update_team_members() -> Result<()> {
+    let db = setup_db("test_update_team_members").await?;
+
+    let owner = create_user(&db, "instructor".to_string(), "TestPass123!", false, vec![]).await?;
+    let student = create_user(&db, "student1".to_string(), "TestPass123!", false, vec![]).await?;
+    let mut team = create_team(&db, "students", owner.id.expect("owner id"), vec![]).await?;
+
+    team.members.push(student.id.expect("student id"));
+    let updated = update_team(&db, team).await?;
+    assert_eq!(updated.members.len(), 1);
+
+    let fetched = get_team_by_name(&db, "students").await?;
+    assert_eq!(fetched.members, updated.members);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
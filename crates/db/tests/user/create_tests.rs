@@ -1,6 +1,7 @@
 /// CREATE operation tests for user
 use anyhow::Result;
-use db::{create_user, get_user, list_users, upsert_user};
+use db::{create_user, get_user, list_users, provision_external_user, upsert_user};
+use shared::data::AuthProvider;
 
 use crate::{setup_db, teardown_db};
 
@@ -201,3 +202,46 @@ async fn test_upsert_user_updates_existing() -> Result<()> {
     teardown_db(&db).await?;
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_provision_external_user_creates_and_syncs_admin() -> Result<()> {
+    let db = setup_db("test_provision_external_user").await?;
+
+    let user = provision_external_user(&db, "carol", AuthProvider::Ldap, false).await?;
+    assert_eq!(user.auth_provider, AuthProvider::Ldap);
+    assert!(user.password_hash.is_empty());
+    assert!(!user.is_admin);
+
+    // A later login with admin group membership promotes the same record
+    let promoted = provision_external_user(&db, "carol", AuthProvider::Ldap, true).await?;
+    assert_eq!(promoted.id, user.id);
+    assert!(promoted.is_admin);
+    assert_eq!(list_users(&db).await?.len(), 1);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_provision_external_user_rejects_other_provider() -> Result<()> {
+    let db = setup_db("test_provision_external_user_conflict").await?;
+
+    create_user(&db, "dave".to_string(), "TestPass123!", false, vec![]).await?;
+
+    let result = provision_external_user(&db, "dave", AuthProvider::Oidc, false).await;
+    assert!(result.is_err(), "Should not take over a local account");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("already exists with local authentication")
+    );
+
+    let user = get_user(&db, "dave").await?;
+    assert_eq!(user.auth_provider, AuthProvider::Local);
+
+    teardown_db(&db).await?;
+    Ok(())
+}
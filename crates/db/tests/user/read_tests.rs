@@ -1,6 +1,6 @@
 /// READ operation tests for user
 use anyhow::Result;
-use db::{count_users, create_user, get_user, get_user_by_id, list_users};
+use db::{count_users, create_user, get_user, get_user_by_id, get_user_for_auth, list_users};
 use shared::data::RecordId;
 
 use crate::{setup_db, teardown_db};
@@ -85,6 +85,26 @@ async fn test_get_user_by_id_not_found() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_get_user_for_auth() -> Result<()> {
+    let db = setup_db("test_get_user_for_auth").await?;
+
+    create_user(&db, "carol".to_string(), "TestPass123!", false, vec![]).await?;
+
+    let user = get_user_for_auth(&db, "carol")
+        .await?
+        .expect("User should exist");
+    assert_eq!(user.username, "carol");
+    assert!(!user.password_hash.is_empty());
+
+    // A missing user is not an error, so login can tell it from a failed query
+    assert!(get_user_for_auth(&db, "nonexistent").await?.is_none());
+
+    teardown_db(&db).await?;
+    Ok(())
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_list_users() -> Result<()> {
@@ -2,7 +2,7 @@
 use anyhow::Result;
 use db::{create_user, get_user, update_user};
 use jiff::Timestamp;
-use shared::data::{DbUser, RecordId};
+use shared::data::{AuthProvider, DbUser, RecordId};
 
 use crate::{setup_db, teardown_db};
 
@@ -112,6 +112,7 @@ async fn test_update_nonexistent_user_fails() -> Result<()> {
         password_hash: "$argon2id$v=19$m=19456,t=2,p=1$test$test".to_string(),
         is_admin: false,
         ssh_keys: vec![],
+        auth_provider: AuthProvider::Local,
         created_at: Timestamp::now(),
         updated_at: Timestamp::now(),
     };
@@ -2,10 +2,10 @@
 #![no_main]
 
 use aya_ebpf::{
-    bindings::TC_ACT_PIPE,
+    bindings::{TC_ACT_PIPE, TC_ACT_SHOT},
     helpers::bpf_redirect,
     macros::{classifier, map},
-    maps::HashMap,
+    maps::{Array, HashMap, PerCpuArray},
     programs::TcContext,
 };
 
@@ -14,16 +14,87 @@ use aya_ebpf::{
 #[map]
 static PEER_IFINDEX: HashMap<u32, u32> = HashMap::with_max_entries(1, 0);
 
+/// Per-CPU traffic counters, indexed by the `STAT_*` constants.
+/// Userspace sums the per-CPU values to get the totals.
+#[map]
+static LINK_STATS: PerCpuArray<u64> = PerCpuArray::with_max_entries(3, 0);
+
+/// Mirror destinations, as interface indexes. Every ingress packet is
+/// cloned to the egress of each non-zero slot before it is redirected.
+#[map]
+static MIRROR_IFINDEX: Array<u32> = Array::with_max_entries(MAX_MIRRORS, 0);
+
+/// Mirror slots per interface, keep in sync with `MIRROR_MAX_PER_INTERFACE`
+const MAX_MIRRORS: u32 = 4;
+
+/// Layer 2 filter rules, evaluated in slot order up to the first empty
+/// slot. The first rule matching a packet decides whether it is dropped.
+///
+/// Each rule is `kind << 56 | drop << 48 | value`, where value is an
+/// ethertype, a destination MAC or a VLAN ID depending on the kind.
+#[map]
+static FILTER_RULES: Array<u64> = Array::with_max_entries(MAX_FILTER_RULES, 0);
+
+/// Filter slots, keep in sync with `FILTER_MAX_RULES`
+const MAX_FILTER_RULES: u32 = 16;
+
+const FILTER_EMPTY: u8 = 0;
+const FILTER_ETHERTYPE: u8 = 1;
+const FILTER_DEST_MAC: u8 = 2;
+const FILTER_VLAN: u8 = 3;
+const FILTER_ANY: u8 = 4;
+const FILTER_DROP: u64 = 1 << 48;
+const FILTER_VALUE_MASK: u64 = (1 << 48) - 1;
+
+const ETH_P_8021Q: u16 = 0x8100;
+const ETH_P_8021AD: u16 = 0x88a8;
+
+/// Packets seen on ingress
+const STAT_PACKETS: u32 = 0;
+/// Bytes seen on ingress
+const STAT_BYTES: u32 = 1;
+/// Packets that could not be redirected to the peer
+const STAT_REDIRECT_FAILURES: u32 = 2;
+
 /// TC classifier program that redirects all ingress packets
 /// to the peer interface's egress path.
 ///
 /// This enables protocol-transparent point-to-point forwarding
 /// between two network interfaces without using Linux bridges.
+/// Packets matching a drop rule of the link filter are not forwarded.
 #[classifier]
-pub fn p2p_redirect(_ctx: TcContext) -> i32 {
+pub fn p2p_redirect(ctx: TcContext) -> i32 {
+    count(STAT_PACKETS, 1);
+    count(STAT_BYTES, ctx.len() as u64);
+    if filtered(&ctx) {
+        return TC_ACT_SHOT as i32;
+    }
+    mirror(&ctx);
+
     match try_redirect() {
-        Ok(action) => action,
-        Err(_) => TC_ACT_PIPE as i32,
+        Ok(action) => {
+            if action == TC_ACT_SHOT as i32 {
+                count(STAT_REDIRECT_FAILURES, 1);
+            }
+            action
+        }
+        Err(_) => {
+            count(STAT_REDIRECT_FAILURES, 1);
+            TC_ACT_PIPE as i32
+        }
+    }
+}
+
+/// TC classifier program that drops the packets matching `FILTER_RULES`
+/// and passes the rest on unchanged.
+///
+/// Attached to the veths joining the two bridges of a bridged link.
+#[classifier]
+pub fn l2_filter(ctx: TcContext) -> i32 {
+    if filtered(&ctx) {
+        TC_ACT_SHOT as i32
+    } else {
+        TC_ACT_PIPE as i32
     }
 }
 
@@ -34,6 +105,95 @@ fn try_redirect() -> Result<i32, ()> {
     Ok(ret as i32)
 }
 
+/// Header fields the filter rules match on.
+struct Frame {
+    dest_mac: u64,
+    /// Inner ethertype of VLAN tagged frames
+    ethertype: u16,
+    /// 0 for untagged frames
+    vlan: u16,
+}
+
+impl Frame {
+    fn parse(ctx: &TcContext) -> Result<Frame, ()> {
+        let dest: [u8; 6] = ctx.load(0).map_err(|_| ())?;
+        let dest_mac = dest
+            .iter()
+            .fold(0u64, |mac, byte| (mac << 8) | u64::from(*byte));
+        let mut ethertype = u16::from_be(ctx.load::<u16>(12).map_err(|_| ())?);
+        let mut vlan = 0;
+
+        // The tag is either offloaded to the skb or still in the frame
+        let skb = unsafe { &*ctx.skb.skb };
+        if skb.vlan_present != 0 {
+            vlan = (skb.vlan_tci & 0x0fff) as u16;
+        } else if ethertype == ETH_P_8021Q || ethertype == ETH_P_8021AD {
+            vlan = u16::from_be(ctx.load::<u16>(14).map_err(|_| ())?) & 0x0fff;
+            ethertype = u16::from_be(ctx.load::<u16>(16).map_err(|_| ())?);
+        }
+
+        Ok(Frame {
+            dest_mac,
+            ethertype,
+            vlan,
+        })
+    }
+}
+
+/// Whether the first filter rule matching the packet drops it.
+#[inline(always)]
+fn filtered(ctx: &TcContext) -> bool {
+    // Skip parsing entirely on links without a filter
+    match FILTER_RULES.get(0) {
+        Some(&rule) if rule != 0 => {}
+        _ => return false,
+    }
+    let Ok(frame) = Frame::parse(ctx) else {
+        return false;
+    };
+
+    for slot in 0..MAX_FILTER_RULES {
+        let Some(&rule) = FILTER_RULES.get(slot) else {
+            break;
+        };
+        let value = rule & FILTER_VALUE_MASK;
+        let matched = match (rule >> 56) as u8 {
+            FILTER_EMPTY => break,
+            FILTER_ETHERTYPE => u64::from(frame.ethertype) == value,
+            FILTER_DEST_MAC => frame.dest_mac == value,
+            FILTER_VLAN => frame.vlan != 0 && u64::from(frame.vlan) == value,
+            FILTER_ANY => true,
+            _ => false,
+        };
+        if matched {
+            return rule & FILTER_DROP != 0;
+        }
+    }
+    false
+}
+
+/// Clone the packet to every configured mirror destination.
+#[inline(always)]
+fn mirror(ctx: &TcContext) {
+    for slot in 0..MAX_MIRRORS {
+        if let Some(&ifindex) = MIRROR_IFINDEX.get(slot) {
+            if ifindex != 0 {
+                // A vanished mirror destination must not affect the link itself.
+                let _ = ctx.clone_redirect(ifindex, 0);
+            }
+        }
+    }
+}
+
+/// Add `value` to this CPU's slot of a `LINK_STATS` counter.
+#[inline(always)]
+fn count(index: u32, value: u64) {
+    if let Some(counter) = LINK_STATS.get_ptr_mut(index) {
+        // Per-CPU slot: no other CPU writes it, so no atomics are needed.
+        unsafe { *counter += value };
+    }
+}
+
 #[panic_handler]
 fn panic(_info: &core::panic::PanicInfo) -> ! {
     loop {}
@@ -0,0 +1,381 @@
+use std::io::{ErrorKind, Read};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use socket2::{Domain, Protocol, SockFilter, Socket, Type};
+use tracing::instrument;
+
+use shared::data::{DiscoveredNeighbour, DiscoveryProtocol};
+
+use crate::tc::TcHook;
+
+const ETHERTYPE_LLDP: u16 = 0x88cc;
+const CDP_DEST_MAC: [u8; 6] = [0x01, 0x00, 0x0c, 0xcc, 0xcc, 0xcc];
+/// LLC/SNAP header of CDP: DSAP, SSAP, control, Cisco OUI and protocol ID
+const CDP_SNAP_HEADER: [u8; 8] = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x0c, 0x20, 0x00];
+
+/// Link-layer protocol receiving every frame
+const ETH_P_ALL: u16 = 0x0003;
+/// Packet type of frames the host transmits
+const PACKET_OUTGOING: u32 = 4;
+/// Offsets of the ancillary data a classic BPF program can load
+const SKF_AD_PKTTYPE: u32 = 0xffff_f000 + 4;
+const SKF_AD_IFINDEX: u32 = 0xffff_f000 + 8;
+
+// Classic BPF opcodes
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_LD_H_ABS: u16 = 0x28;
+const BPF_JEQ_K: u16 = 0x15;
+const BPF_RET_K: u16 = 0x06;
+
+/// How often the capture sockets are polled
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Large enough for jumbo frames, advertisements are far smaller
+const CAPTURE_BUFFER_LEN: usize = 9216;
+
+/// Listen for LLDP and CDP advertisements on the hooks of host interfaces.
+///
+/// Each target is an interface index and the direction to capture, so a
+/// node's own advertisements are told apart from the ones it receives.
+/// Returns the first advertisement decoded on each target, in the order of
+/// `targets`, as soon as every target has one or once `window` has passed.
+#[instrument(skip(targets), fields(targets = targets.len(), window_secs = window.as_secs()))]
+pub fn capture_neighbours(
+    targets: &[(u32, TcHook)],
+    window: Duration,
+) -> Result<Vec<Option<DiscoveredNeighbour>>> {
+    let sockets = targets
+        .iter()
+        .map(|&(ifindex, hook)| open_capture(ifindex, hook))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut found = vec![None; targets.len()];
+    let mut buffer = vec![0u8; CAPTURE_BUFFER_LEN];
+    let deadline = Instant::now() + window;
+
+    while found.iter().any(Option::is_none) && Instant::now() < deadline {
+        for (socket, slot) in sockets.iter().zip(found.iter_mut()) {
+            loop {
+                match (&*socket).read(&mut buffer) {
+                    Ok(len) => {
+                        if slot.is_none() {
+                            *slot = decode_frame(&buffer[..len]);
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e).context("Failed to read from capture socket"),
+                }
+            }
+        }
+        std::thread::sleep(CAPTURE_POLL_INTERVAL);
+    }
+
+    tracing::debug!(
+        found = found.iter().filter(|n| n.is_some()).count(),
+        "Finished neighbour capture"
+    );
+    Ok(found)
+}
+
+/// Open a non-blocking packet socket passing only the discovery frames on
+/// one hook of an interface.
+fn open_capture(ifindex: u32, hook: TcHook) -> Result<Socket> {
+    let protocol = Protocol::from(i32::from(ETH_P_ALL.to_be()));
+    let socket = Socket::new(Domain::PACKET, Type::RAW, Some(protocol))
+        .context("Failed to open packet socket")?;
+
+    let program: Vec<SockFilter> = discovery_program(ifindex, hook)
+        .into_iter()
+        .map(|(code, jt, jf, k)| SockFilter::new(code, jt, jf, k))
+        .collect();
+    socket.attach_filter(&program).context(format!(
+        "Failed to attach capture filter for ifindex {ifindex}"
+    ))?;
+    socket
+        .set_nonblocking(true)
+        .context("Failed to set capture socket non-blocking")?;
+
+    // Frames queued before the filter was attached can be from any interface.
+    let mut buffer = vec![0u8; CAPTURE_BUFFER_LEN];
+    loop {
+        match (&socket).read(&mut buffer) {
+            Ok(_) => continue,
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e).context("Failed to drain capture socket"),
+        }
+    }
+    Ok(socket)
+}
+
+/// Classic BPF program accepting LLDP and CDP frames on one hook of an
+/// interface, as `(code, jt, jf, k)` instructions.
+fn discovery_program(ifindex: u32, hook: TcHook) -> Vec<(u16, u8, u8, u32)> {
+    let cdp_mac_high = u32::from_be_bytes([
+        CDP_DEST_MAC[0],
+        CDP_DEST_MAC[1],
+        CDP_DEST_MAC[2],
+        CDP_DEST_MAC[3],
+    ]);
+    let cdp_mac_low = u32::from(u16::from_be_bytes([CDP_DEST_MAC[4], CDP_DEST_MAC[5]]));
+    // Jump offsets from the packet type test to the final `drop`.
+    let (outgoing_jt, outgoing_jf) = match hook {
+        TcHook::Ingress => (7, 0),
+        TcHook::Egress => (0, 7),
+    };
+
+    vec![
+        (BPF_LD_W_ABS, 0, 0, SKF_AD_IFINDEX),
+        (BPF_JEQ_K, 0, 9, ifindex),
+        (BPF_LD_W_ABS, 0, 0, SKF_AD_PKTTYPE),
+        (BPF_JEQ_K, outgoing_jt, outgoing_jf, PACKET_OUTGOING),
+        (BPF_LD_H_ABS, 0, 0, 12),
+        (BPF_JEQ_K, 4, 0, u32::from(ETHERTYPE_LLDP)),
+        (BPF_LD_W_ABS, 0, 0, 0),
+        (BPF_JEQ_K, 0, 3, cdp_mac_high),
+        (BPF_LD_H_ABS, 0, 0, 4),
+        (BPF_JEQ_K, 0, 1, cdp_mac_low),
+        (BPF_RET_K, 0, 0, 0xffff),
+        (BPF_RET_K, 0, 0, 0),
+    ]
+}
+
+/// Decode an LLDP or CDP frame.
+pub fn decode_frame(frame: &[u8]) -> Option<DiscoveredNeighbour> {
+    if frame.len() < 14 {
+        return None;
+    }
+    if u16::from_be_bytes([frame[12], frame[13]]) == ETHERTYPE_LLDP {
+        decode_lldp(&frame[14..])
+    } else if frame[..6] == CDP_DEST_MAC && frame.get(14..22) == Some(&CDP_SNAP_HEADER[..]) {
+        // Version, TTL and checksum precede the TLVs.
+        decode_cdp(frame.get(26..)?)
+    } else {
+        None
+    }
+}
+
+/// Decode the TLVs of an LLDPDU.
+fn decode_lldp(mut tlvs: &[u8]) -> Option<DiscoveredNeighbour> {
+    let mut chassis_id = None;
+    let mut port_id = None;
+    let mut system_name = None;
+    let mut port_description = None;
+
+    while tlvs.len() >= 2 {
+        let header = u16::from_be_bytes([tlvs[0], tlvs[1]]);
+        let (kind, len) = (header >> 9, usize::from(header & 0x01ff));
+        let value = tlvs.get(2..2 + len)?;
+        match kind {
+            0 => break,
+            1 => chassis_id = subtyped_id(value, 4),
+            2 => port_id = subtyped_id(value, 3),
+            4 => port_description = Some(text(value)),
+            5 => system_name = Some(text(value)),
+            _ => {}
+        }
+        tlvs = &tlvs[2 + len..];
+    }
+
+    Some(DiscoveredNeighbour {
+        protocol: DiscoveryProtocol::Lldp,
+        chassis_id: chassis_id?,
+        system_name: system_name.filter(|name| !name.is_empty()),
+        port_id: port_id?,
+        port_description: port_description.filter(|description| !description.is_empty()),
+    })
+}
+
+/// Decode the TLVs of a CDP packet.
+fn decode_cdp(mut tlvs: &[u8]) -> Option<DiscoveredNeighbour> {
+    let mut device_id = None;
+    let mut port_id = None;
+
+    while tlvs.len() >= 4 {
+        let kind = u16::from_be_bytes([tlvs[0], tlvs[1]]);
+        let len = usize::from(u16::from_be_bytes([tlvs[2], tlvs[3]]));
+        if len < 4 {
+            return None;
+        }
+        let value = tlvs.get(4..len)?;
+        match kind {
+            0x0001 => device_id = Some(text(value)),
+            0x0003 => port_id = Some(text(value)),
+            _ => {}
+        }
+        tlvs = &tlvs[len..];
+    }
+
+    let device_id = device_id?;
+    Some(DiscoveredNeighbour {
+        protocol: DiscoveryProtocol::Cdp,
+        chassis_id: device_id.clone(),
+        system_name: Some(device_id),
+        port_id: port_id?,
+        port_description: None,
+    })
+}
+
+/// Chassis or port ID, whose first byte is the subtype. `mac_subtype` is
+/// the subtype of a MAC address, which is formatted rather than read as text.
+fn subtyped_id(value: &[u8], mac_subtype: u8) -> Option<String> {
+    let (&subtype, id) = value.split_first()?;
+    if subtype == mac_subtype && id.len() == 6 {
+        Some(
+            id.iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(":"),
+        )
+    } else {
+        Some(text(id))
+    }
+}
+
+fn text(value: &[u8]) -> String {
+    String::from_utf8_lossy(value)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lldp_tlv(kind: u16, value: &[u8]) -> Vec<u8> {
+        let header = (kind << 9) | value.len() as u16;
+        [&header.to_be_bytes()[..], value].concat()
+    }
+
+    fn lldp_frame(tlvs: &[Vec<u8>]) -> Vec<u8> {
+        let mut frame = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e];
+        frame.extend([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        frame.extend(ETHERTYPE_LLDP.to_be_bytes());
+        for tlv in tlvs {
+            frame.extend(tlv);
+        }
+        frame.extend([0, 0]);
+        frame
+    }
+
+    fn cdp_tlv(kind: u16, value: &[u8]) -> Vec<u8> {
+        let len = (value.len() + 4) as u16;
+        [&kind.to_be_bytes()[..], &len.to_be_bytes(), value].concat()
+    }
+
+    fn cdp_frame(tlvs: &[Vec<u8>]) -> Vec<u8> {
+        let mut frame = CDP_DEST_MAC.to_vec();
+        frame.extend([0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        frame.extend([0x00, 0x00]);
+        frame.extend(CDP_SNAP_HEADER);
+        frame.extend([0x02, 0xb4, 0x00, 0x00]);
+        for tlv in tlvs {
+            frame.extend(tlv);
+        }
+        frame
+    }
+
+    /// Run a classic BPF program over a frame, supporting the instructions
+    /// `discovery_program` uses.
+    fn run_program(
+        program: &[(u16, u8, u8, u32)],
+        frame: &[u8],
+        ifindex: u32,
+        pkttype: u32,
+    ) -> u32 {
+        let (mut pc, mut acc) = (0, 0u32);
+        loop {
+            let (code, jt, jf, k) = program[pc];
+            pc += 1;
+            match code {
+                BPF_LD_W_ABS => {
+                    acc = match k {
+                        SKF_AD_IFINDEX => ifindex,
+                        SKF_AD_PKTTYPE => pkttype,
+                        k => {
+                            let at = k as usize;
+                            u32::from_be_bytes(frame[at..at + 4].try_into().unwrap())
+                        }
+                    }
+                }
+                BPF_LD_H_ABS => {
+                    let at = k as usize;
+                    acc = u32::from(u16::from_be_bytes([frame[at], frame[at + 1]]));
+                }
+                BPF_JEQ_K => pc += usize::from(if acc == k { jt } else { jf }),
+                BPF_RET_K => return k,
+                code => panic!("unexpected opcode {code:#x}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_lldp() {
+        let frame = lldp_frame(&[
+            lldp_tlv(1, &[4, 0x52, 0x54, 0x00, 0xaa, 0xbb, 0xcc]),
+            lldp_tlv(2, b"\x05Ethernet1"),
+            lldp_tlv(3, &[0, 120]),
+            lldp_tlv(4, b"to dev02"),
+            lldp_tlv(5, b"dev01"),
+        ]);
+
+        assert_eq!(
+            decode_frame(&frame),
+            Some(DiscoveredNeighbour {
+                protocol: DiscoveryProtocol::Lldp,
+                chassis_id: "52:54:00:aa:bb:cc".to_string(),
+                system_name: Some("dev01".to_string()),
+                port_id: "Ethernet1".to_string(),
+                port_description: Some("to dev02".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_lldp_truncated() {
+        let mut frame = lldp_frame(&[lldp_tlv(1, b"\x07dev01"), lldp_tlv(2, b"\x05eth1")]);
+        assert_eq!(
+            decode_frame(&frame).map(|n| n.port_id),
+            Some("eth1".to_string())
+        );
+
+        frame.truncate(frame.len() - 5);
+        assert_eq!(decode_frame(&frame), None);
+    }
+
+    #[test]
+    fn test_decode_cdp() {
+        let frame = cdp_frame(&[
+            cdp_tlv(0x0001, b"sw01.lab.local"),
+            cdp_tlv(0x0003, b"GigabitEthernet0/1"),
+            cdp_tlv(0x0006, b"cisco IOSv"),
+        ]);
+
+        let neighbour = decode_frame(&frame).unwrap();
+        assert_eq!(neighbour.protocol, DiscoveryProtocol::Cdp);
+        assert_eq!(neighbour.system_name.as_deref(), Some("sw01.lab.local"));
+        assert_eq!(neighbour.port_id, "GigabitEthernet0/1");
+        assert!(neighbour.is_from("sw01", "GigabitEthernet0/1"));
+    }
+
+    #[test]
+    fn test_discovery_program() {
+        let lldp = lldp_frame(&[lldp_tlv(1, b"\x07dev01"), lldp_tlv(2, b"\x05eth1")]);
+        let cdp = cdp_frame(&[cdp_tlv(0x0001, b"sw01"), cdp_tlv(0x0003, b"Gi0/1")]);
+        let mut other = lldp.clone();
+        other[12..14].copy_from_slice(&0x0800u16.to_be_bytes());
+
+        let ingress = discovery_program(7, TcHook::Ingress);
+        assert_eq!(run_program(&ingress, &lldp, 7, 2), 0xffff);
+        assert_eq!(run_program(&ingress, &cdp, 7, 2), 0xffff);
+        assert_eq!(run_program(&ingress, &other, 7, 2), 0);
+        assert_eq!(run_program(&ingress, &lldp, 8, 2), 0);
+        assert_eq!(run_program(&ingress, &lldp, 7, PACKET_OUTGOING), 0);
+
+        let egress = discovery_program(7, TcHook::Egress);
+        assert_eq!(run_program(&egress, &cdp, 7, PACKET_OUTGOING), 0xffff);
+        assert_eq!(run_program(&egress, &cdp, 7, 2), 0);
+    }
+}
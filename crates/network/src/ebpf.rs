@@ -1,10 +1,20 @@
-use anyhow::{Context, Result};
+use std::borrow::BorrowMut;
+use std::collections::HashMap as StdHashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
 use aya::Ebpf;
-use aya::maps::HashMap;
-use aya::programs::tc::qdisc_detach_program;
-use aya::programs::{SchedClassifier, TcAttachType};
+use aya::maps::{Array, HashMap, Map, MapData, MapInfo, PerCpuArray};
+use aya::programs::tc::{NlOptions, TcAttachOptions, qdisc_add_clsact, qdisc_detach_program};
+use aya::programs::{SchedClassifier, TcAttachType, loaded_programs};
 use tracing::instrument;
 
+use shared::data::{FilterVerdict, FrameMatch, LinkFilter};
+use shared::konst::{FILTER_MAX_RULES, MIRROR_MAX_PER_INTERFACE};
+
+/// tc priority of the `l2_filter` program, ahead of the mirror filters so
+/// dropped frames are not mirrored.
+const FILTER_TC_PRIORITY: u16 = 1;
+
 /// Wrapper to ensure include_bytes!() data is 8-byte aligned.
 /// The ELF parser requires naturally-aligned data, but include_bytes!()
 /// only guarantees 1-byte alignment.
@@ -92,3 +102,378 @@ pub fn attach_p2p_redirect(iface_name: &str, peer_ifindex: u32) -> Result<()> {
 
     Ok(())
 }
+
+/// Traffic counters of one attached `p2p_redirect` program.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct P2pRedirectStats {
+    /// Packets that arrived on the interface
+    pub packets: u64,
+    /// Bytes that arrived on the interface
+    pub bytes: u64,
+    /// Packets that could not be redirected to the peer
+    pub redirect_failures: u64,
+}
+
+/// A leaked `p2p_redirect` program found in the kernel.
+struct RedirectProgram {
+    peer_ifindex: u32,
+    /// Map IDs keyed by map name
+    maps: StdHashMap<String, u32>,
+}
+
+impl RedirectProgram {
+    fn map(&self, name: &str) -> Option<MapData> {
+        let id = *self.maps.get(name)?;
+        MapData::from_id(id).ok()
+    }
+}
+
+/// Find every loaded `p2p_redirect` program, oldest first.
+///
+/// The programs are leaked by [`attach_p2p_redirect`], so they are found by
+/// enumerating loaded BPF programs rather than through an `Ebpf` handle.
+/// Programs whose peer map cannot be read are skipped.
+fn redirect_programs() -> Result<Vec<RedirectProgram>> {
+    let mut programs: Vec<_> = loaded_programs()
+        .filter_map(|program| program.ok())
+        .filter(|program| program.name_as_str() == Some("p2p_redirect"))
+        .collect();
+    programs.sort_by_key(|program| program.id());
+
+    let mut found = Vec::with_capacity(programs.len());
+    for program in programs {
+        let Some(map_ids) = program
+            .map_ids()
+            .context("failed to list maps of p2p_redirect program")?
+        else {
+            continue;
+        };
+
+        let mut maps = StdHashMap::new();
+        for id in map_ids {
+            let info = MapInfo::from_id(id).context("failed to read BPF map info")?;
+            if let Some(name) = info.name_as_str() {
+                maps.insert(name.to_string(), id);
+            }
+        }
+
+        let Some(&peer_map_id) = maps.get("PEER_IFINDEX") else {
+            continue;
+        };
+        let peer_map: HashMap<MapData, u32, u32> =
+            HashMap::try_from(Map::HashMap(MapData::from_id(peer_map_id)?))
+                .context("failed to open PEER_IFINDEX map")?;
+        let Ok(peer_ifindex) = peer_map.get(&0, 0) else {
+            continue;
+        };
+
+        found.push(RedirectProgram { peer_ifindex, maps });
+    }
+    Ok(found)
+}
+
+/// Read the counters of every loaded `p2p_redirect` program, keyed by the
+/// peer ifindex the program redirects to.
+///
+/// Programs built before the `LINK_STATS` map existed are skipped. When a
+/// stale program still redirects to the same peer, the newest one wins.
+pub fn p2p_redirect_stats() -> Result<StdHashMap<u32, P2pRedirectStats>> {
+    let mut stats = StdHashMap::new();
+    for program in redirect_programs()? {
+        let Some(stats_map) = program.map("LINK_STATS") else {
+            continue;
+        };
+        let counters: PerCpuArray<MapData, u64> =
+            PerCpuArray::try_from(Map::PerCpuArray(stats_map))
+                .context("failed to open LINK_STATS map")?;
+        let total = |index: u32| -> Result<u64> {
+            let values = counters
+                .get(&index, 0)
+                .context("failed to read LINK_STATS counter")?;
+            Ok(values.iter().sum())
+        };
+
+        stats.insert(
+            program.peer_ifindex,
+            P2pRedirectStats {
+                packets: total(0)?,
+                bytes: total(1)?,
+                redirect_failures: total(2)?,
+            },
+        );
+    }
+
+    Ok(stats)
+}
+
+/// Open the mirror slots of the newest program redirecting to `peer_ifindex`.
+fn mirror_slots(peer_ifindex: u32) -> Result<Array<MapData, u32>> {
+    let program = redirect_programs()?
+        .into_iter()
+        .rfind(|program| program.peer_ifindex == peer_ifindex)
+        .ok_or_else(|| anyhow!("no p2p_redirect program redirects to ifindex {peer_ifindex}"))?;
+    let map = program.map("MIRROR_IFINDEX").ok_or_else(|| {
+        anyhow!(
+            "p2p_redirect program predates port mirroring, redeploy the lab to load the current program"
+        )
+    })?;
+    Array::try_from(Map::Array(map)).context("failed to open MIRROR_IFINDEX map")
+}
+
+/// Clone the packets redirected to `peer_ifindex` to `target_ifindex` as well.
+///
+/// The mirror is added to the map of the running program, so the link
+/// itself is not touched. Adding an existing mirror is a no-op.
+#[instrument(level = "debug")]
+pub fn add_p2p_mirror(peer_ifindex: u32, target_ifindex: u32) -> Result<()> {
+    let mut slots = mirror_slots(peer_ifindex)?;
+    let mut free = None;
+    for slot in 0..MIRROR_MAX_PER_INTERFACE {
+        match slots.get(&slot, 0).context("failed to read mirror slot")? {
+            ifindex if ifindex == target_ifindex => return Ok(()),
+            0 if free.is_none() => free = Some(slot),
+            _ => {}
+        }
+    }
+    let slot = free.ok_or_else(|| {
+        anyhow!("interface already has the maximum of {MIRROR_MAX_PER_INTERFACE} mirrors")
+    })?;
+    slots
+        .set(slot, target_ifindex, 0)
+        .context("failed to write mirror slot")
+}
+
+/// Stop cloning the packets redirected to `peer_ifindex` to `target_ifindex`.
+///
+/// Removing a mirror that is not configured is a no-op.
+#[instrument(level = "debug")]
+pub fn remove_p2p_mirror(peer_ifindex: u32, target_ifindex: u32) -> Result<()> {
+    let mut slots = mirror_slots(peer_ifindex)?;
+    for slot in 0..MIRROR_MAX_PER_INTERFACE {
+        if slots.get(&slot, 0).context("failed to read mirror slot")? == target_ifindex {
+            slots
+                .set(slot, 0, 0)
+                .context("failed to clear mirror slot")?;
+        }
+    }
+    Ok(())
+}
+
+/// Encode a link filter as `FILTER_RULES` map values.
+///
+/// Each rule is `kind << 56 | drop << 48 | value`, see the eBPF program.
+fn encode_filter_rules(filter: &LinkFilter) -> Result<Vec<u64>> {
+    filter.validate()?;
+    Ok(filter
+        .rules()
+        .into_iter()
+        .map(|(frame, verdict)| {
+            let (kind, value): (u64, u64) = match frame {
+                FrameMatch::Ethertype(ethertype) => (1, u64::from(ethertype)),
+                FrameMatch::DestMac(mac) => (
+                    2,
+                    mac.iter()
+                        .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+                ),
+                FrameMatch::Vlan(vlan) => (3, u64::from(vlan)),
+                FrameMatch::Any => (4, 0),
+            };
+            let drop = match verdict {
+                FilterVerdict::Drop => 1 << 48,
+                FilterVerdict::Pass => 0,
+            };
+            (kind << 56) | drop | value
+        })
+        .collect())
+}
+
+/// Write encoded rules to every slot of a `FILTER_RULES` map, clearing the
+/// slots after the last rule.
+fn write_filter_rules<T: BorrowMut<MapData>>(map: &mut Array<T, u64>, rules: &[u64]) -> Result<()> {
+    for slot in 0..FILTER_MAX_RULES {
+        let rule = rules.get(slot as usize).copied().unwrap_or(0);
+        map.set(slot, rule, 0)
+            .context("failed to write filter rule")?;
+    }
+    Ok(())
+}
+
+/// Replace the filter of the newest program redirecting to `peer_ifindex`.
+///
+/// Filters the packets the program forwards, so a P2p link needs the
+/// filter set on the programs of both taps. An empty filter passes every
+/// packet.
+#[instrument(level = "debug", skip(filter))]
+pub fn set_p2p_filter(peer_ifindex: u32, filter: &LinkFilter) -> Result<()> {
+    let rules = encode_filter_rules(filter)?;
+    let program = redirect_programs()?
+        .into_iter()
+        .rfind(|program| program.peer_ifindex == peer_ifindex)
+        .ok_or_else(|| anyhow!("no p2p_redirect program redirects to ifindex {peer_ifindex}"))?;
+    let Some(map) = program.map("FILTER_RULES") else {
+        if rules.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "p2p_redirect program predates link filters, redeploy the lab to load the current program"
+        );
+    };
+    let mut slots: Array<MapData, u64> =
+        Array::try_from(Map::Array(map)).context("failed to open FILTER_RULES map")?;
+    write_filter_rules(&mut slots, &rules)
+}
+
+/// Attach an `l2_filter` program to an interface's ingress, replacing any
+/// existing one. An empty filter only detaches the existing program.
+///
+/// Like [`attach_p2p_redirect`], the program is leaked and lives until
+/// it is detached or the interface is deleted.
+#[instrument(skip(filter), fields(%iface_name))]
+pub fn set_l2_filter(iface_name: &str, filter: &LinkFilter) -> Result<()> {
+    let rules = encode_filter_rules(filter)?;
+
+    match qdisc_detach_program(iface_name, TcAttachType::Ingress, "l2_filter") {
+        Ok(()) => {
+            tracing::debug!(interface = %iface_name, "detached existing l2_filter program");
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(e).context(format!("failed to detach l2_filter from {iface_name}"));
+        }
+    }
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    let mut bpf =
+        Ebpf::load(&EBPF_REDIRECT_ELF.0).context("failed to load eBPF redirect program")?;
+    {
+        let map = bpf
+            .map_mut("FILTER_RULES")
+            .context("FILTER_RULES map not found in eBPF ELF")?;
+        let mut slots: Array<&mut MapData, u64> =
+            Array::try_from(map).context("failed to open FILTER_RULES map")?;
+        write_filter_rules(&mut slots, &rules)?;
+    }
+    {
+        let program: &mut SchedClassifier = bpf
+            .program_mut("l2_filter")
+            .context("l2_filter program not found in eBPF ELF")?
+            .try_into()
+            .context("failed to convert to SchedClassifier")?;
+
+        program
+            .load()
+            .context(format!("failed to load TC program for {iface_name}"))?;
+
+        // Netlink attach so the program sorts by priority with the mirror
+        // filters and can be found again by qdisc_detach_program. Unlike a
+        // TCX attach it needs a clsact qdisc on the interface.
+        match qdisc_add_clsact(iface_name) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e).context(format!("failed to add clsact qdisc to {iface_name}"));
+            }
+        }
+        program
+            .attach_with_options(
+                iface_name,
+                TcAttachType::Ingress,
+                TcAttachOptions::Netlink(NlOptions {
+                    priority: FILTER_TC_PRIORITY,
+                    handle: 0,
+                }),
+            )
+            .context(format!("failed to attach TC program to {iface_name}"))?;
+    }
+    std::mem::forget(bpf);
+
+    tracing::info!(
+        interface = %iface_name,
+        %filter,
+        "attached eBPF l2 filter program"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::{L2Match, L2Protocol};
+
+    #[test]
+    fn test_embedded_elf_maps_and_programs() {
+        // The embedded ELF is prebuilt, so check it is in step with the
+        // maps and programs this module looks up.
+        let object = aya_obj::Object::parse(&EBPF_REDIRECT_ELF.0).unwrap();
+        for map in [
+            "PEER_IFINDEX",
+            "LINK_STATS",
+            "MIRROR_IFINDEX",
+            "FILTER_RULES",
+        ] {
+            assert!(object.maps.contains_key(map), "missing map {map}");
+        }
+        for program in ["p2p_redirect", "l2_filter"] {
+            assert!(
+                object.programs.contains_key(program),
+                "missing program {program}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_embedded_elf_mirror_slots() {
+        // add_p2p_mirror walks every slot, so the map must hold exactly
+        // the number of mirrors the API allows.
+        let object = aya_obj::Object::parse(&EBPF_REDIRECT_ELF.0).unwrap();
+        assert_eq!(
+            object.maps["MIRROR_IFINDEX"].max_entries(),
+            MIRROR_MAX_PER_INTERFACE
+        );
+    }
+
+    #[test]
+    fn test_embedded_elf_filter_slots() {
+        // write_filter_rules writes every slot, and l2_filter reads the
+        // same map, so both must agree on the rule count.
+        let object = aya_obj::Object::parse(&EBPF_REDIRECT_ELF.0).unwrap();
+        assert_eq!(object.maps["FILTER_RULES"].max_entries(), FILTER_MAX_RULES);
+    }
+
+    #[test]
+    fn test_encode_filter_rules() {
+        let filter = LinkFilter {
+            drop: vec![
+                L2Match::Protocol(L2Protocol::Lacp),
+                L2Match::Protocol(L2Protocol::Cdp),
+            ],
+            allow: vec![L2Match::Vlan(10)],
+        };
+        let rules = encode_filter_rules(&filter).unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                (1 << 56) | (1 << 48) | 0x8809,
+                (2 << 56) | (1 << 48) | 0x0100_0ccc_cccc,
+                (3 << 56) | 10,
+                (4 << 56) | (1 << 48),
+            ]
+        );
+        assert!(
+            encode_filter_rules(&LinkFilter::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_encode_filter_rules_rejects_invalid() {
+        let filter = LinkFilter {
+            drop: vec![L2Match::Vlan(5000)],
+            allow: vec![],
+        };
+        assert!(encode_filter_rules(&filter).is_err());
+    }
+}
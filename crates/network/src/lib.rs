@@ -3,15 +3,23 @@
 
 pub(crate) mod linux;
 
+pub mod discovery;
 pub mod ebpf;
 pub mod tap;
 pub mod tc;
 
 pub use linux::{
-    create_bridge, create_veth_pair, delete_interface, enslave_to_bridge, find_interfaces_fuzzy,
-    set_link_down,
+    InterfaceStats, add_address, attach_host_interface, check_host_interface, create_bridge,
+    create_veth_pair, create_vlan_bridge, delete_interface, enslave_to_bridge,
+    find_interfaces_fuzzy, interface_stats, set_bridge_port_vlans, set_link_down,
 };
 
-pub use ebpf::attach_p2p_redirect;
+pub use discovery::{capture_neighbours, decode_frame};
+pub use ebpf::{
+    P2pRedirectStats, add_p2p_mirror, attach_p2p_redirect, p2p_redirect_stats, remove_p2p_mirror,
+    set_l2_filter, set_p2p_filter,
+};
 pub use tap::{create_tap, get_ifindex, move_to_netns};
-pub use tc::{LinkImpairment, apply_netem, remove_netem, update_netem};
+pub use tc::{
+    LinkImpairment, TcHook, add_mirror, apply_netem, remove_mirror, remove_netem, update_netem,
+};
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
 use anyhow::{Context, Result, anyhow};
 use futures::TryStreamExt;
-use rtnetlink::packet_route::link::{LinkAttribute, LinkFlags, LinkMessage};
-use rtnetlink::{Handle, LinkBridge, LinkVeth, new_connection};
-use shared::konst::MTU_JUMBO_NET;
+use rtnetlink::packet_route::address::AddressScope;
+use rtnetlink::packet_route::link::{BridgeVlanInfoFlags, LinkAttribute, LinkFlags, LinkMessage};
+use rtnetlink::{Handle, LinkBridge, LinkBridgeVlan, LinkVeth, new_connection};
+use shared::data::PortVlan;
 use tracing::instrument;
 
 /// Helper to set up netlink connection
@@ -23,7 +27,6 @@ pub(crate) async fn get_link_index(handle: &Handle, name: &str) -> Result<u32> {
     }
 }
 
-#[allow(dead_code)]
 /// Helper to set a link to up state
 async fn enable_link(handle: &Handle, name: &str, index: u32) -> Result<()> {
     let mut msg = LinkMessage::default();
@@ -134,7 +137,19 @@ pub(crate) async fn set_link_properties(
 }
 
 /// Create a bridge interface
-pub async fn create_bridge(name: &str, alias_name: &str) -> Result<()> {
+pub async fn create_bridge(name: &str, alias_name: &str, mtu: u16) -> Result<()> {
+    add_bridge(name, alias_name, mtu, false).await
+}
+
+/// Create a bridge interface that forwards frames by VLAN.
+///
+/// Ports join untagged in the default VLAN 1 until `set_bridge_port_vlans`
+/// replaces their VLANs.
+pub async fn create_vlan_bridge(name: &str, alias_name: &str, mtu: u16) -> Result<()> {
+    add_bridge(name, alias_name, mtu, true).await
+}
+
+async fn add_bridge(name: &str, alias_name: &str, mtu: u16, vlan_filtering: bool) -> Result<()> {
     let handle = setup_netlink().await?;
 
     // https://interestingtraffic.nl/2017/11/21/an-oddly-specific-post-about-group_fwd_mask/
@@ -149,17 +164,22 @@ pub async fn create_bridge(name: &str, alias_name: &str) -> Result<()> {
     // 01-80-C2-00-00-02 	Link Aggregation Control Protocol (LACP)
     let mask: u16 = 0xFFF8;
 
-    tracing::info!(bridge_name = %name, alias = %alias_name, "Creating bridge");
+    let mut bridge = LinkBridge::new(name).group_fwd_mask(mask);
+    if vlan_filtering {
+        bridge = bridge.vlan_filtering(true);
+    }
+
+    tracing::info!(bridge_name = %name, alias = %alias_name, vlan_filtering, "Creating bridge");
     handle
         .link()
-        .add(LinkBridge::new(name).group_fwd_mask(mask).build())
+        .add(bridge.build())
         .execute()
         .await
         .context(format!("Error creating bridge: {name}"))?;
 
     let idx = get_link_index(&handle, name).await?;
 
-    set_link_properties(&handle, name, idx, alias_name, MTU_JUMBO_NET as u32).await?;
+    set_link_properties(&handle, name, idx, alias_name, mtu as u32).await?;
 
     Ok(())
 }
@@ -169,6 +189,7 @@ pub async fn create_veth_pair(
     dst_name: &str,
     src_alias_name: &str,
     dst_alias_name: &str,
+    mtu: u16,
 ) -> Result<()> {
     let handle = setup_netlink().await?;
 
@@ -184,24 +205,10 @@ pub async fn create_veth_pair(
         ))?;
 
     let src_idx = get_link_index(&handle, src_name).await?;
-    set_link_properties(
-        &handle,
-        src_name,
-        src_idx,
-        src_alias_name,
-        MTU_JUMBO_NET as u32,
-    )
-    .await?;
+    set_link_properties(&handle, src_name, src_idx, src_alias_name, mtu as u32).await?;
 
     let dst_idx = get_link_index(&handle, dst_name).await?;
-    set_link_properties(
-        &handle,
-        dst_name,
-        dst_idx,
-        dst_alias_name,
-        MTU_JUMBO_NET as u32,
-    )
-    .await?;
+    set_link_properties(&handle, dst_name, dst_idx, dst_alias_name, mtu as u32).await?;
 
     Ok(())
 }
@@ -238,6 +245,136 @@ pub async fn enslave_to_bridge(int_name: &str, bridge_name: &str) -> Result<()>
     Ok(())
 }
 
+/// Join `port` to a VLAN-aware bridge and replace its VLANs with `vlans`.
+///
+/// Ports join the bridge in the default VLAN 1, which is removed unless
+/// `vlans` includes it. Setting a VLAN again updates its native flags.
+#[instrument(fields(%port, %bridge_name), level = "debug")]
+pub async fn set_bridge_port_vlans(
+    port: &str,
+    bridge_name: &str,
+    vlans: &[PortVlan],
+) -> Result<()> {
+    const DEFAULT_VLAN: u16 = 1;
+
+    enslave_to_bridge(port, bridge_name).await?;
+
+    let handle = setup_netlink().await?;
+    let port_idx = get_link_index(&handle, port).await?;
+
+    if !vlans.iter().any(|vlan| vlan.vlans.contains(DEFAULT_VLAN)) {
+        let message = LinkBridgeVlan::new(port_idx)
+            .vlan(DEFAULT_VLAN, BridgeVlanInfoFlags::empty())
+            .build();
+        // Already gone when the VLANs of the port are set again
+        if let Err(e) = handle.link().del_with_message(message).execute().await {
+            tracing::debug!(port = %port, error = %e, "Default VLAN not removed from port");
+        }
+    }
+
+    if vlans.is_empty() {
+        return Ok(());
+    }
+    let mut message = LinkBridgeVlan::new(port_idx);
+    for vlan in vlans {
+        let range = vlan.vlans;
+        message = if vlan.native {
+            message.vlan(
+                range.first,
+                BridgeVlanInfoFlags::Pvid | BridgeVlanInfoFlags::Untagged,
+            )
+        } else if range.first == range.last {
+            message.vlan(range.first, BridgeVlanInfoFlags::empty())
+        } else {
+            message
+                .vlan_range_start(range.first, BridgeVlanInfoFlags::empty())
+                .vlan_range_end(range.last, BridgeVlanInfoFlags::empty())
+        };
+    }
+
+    let summary = vlans
+        .iter()
+        .map(PortVlan::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    tracing::info!(port = %port, bridge = %bridge_name, vlans = %summary, "Setting bridge port VLANs");
+    handle
+        .link()
+        .set(message.build())
+        .execute()
+        .await
+        .context(format!(
+            "Error setting VLANs {summary} on bridge port: {port}"
+        ))?;
+
+    Ok(())
+}
+
+/// Check that a host interface can be bridged into a lab.
+///
+/// The interface must exist, must not be a port of a bridge (another lab
+/// or the host may be using it) and must not carry global IP addresses.
+#[instrument(fields(%name), level = "debug")]
+pub async fn check_host_interface(name: &str) -> Result<()> {
+    let handle = setup_netlink().await?;
+
+    let mut links = handle.link().get().match_name(name.to_string()).execute();
+    let Ok(Some(link)) = links.try_next().await else {
+        anyhow::bail!("Host interface {name} does not exist");
+    };
+
+    if let Some(controller) = link.attributes.iter().find_map(|attr| match attr {
+        LinkAttribute::Controller(index) => Some(*index),
+        _ => None,
+    }) {
+        anyhow::bail!("Host interface {name} is already in use by bridge index {controller}");
+    }
+
+    let mut addresses = handle
+        .address()
+        .get()
+        .set_link_index_filter(link.header.index)
+        .execute();
+    while let Some(address) = addresses.try_next().await? {
+        if address.header.scope == AddressScope::Universe {
+            anyhow::bail!("Host interface {name} has IP addresses and is in use by the host");
+        }
+    }
+
+    Ok(())
+}
+
+/// Enslave a host interface to a lab bridge and bring it up.
+#[instrument(fields(%name, %bridge_name), level = "debug")]
+pub async fn attach_host_interface(name: &str, bridge_name: &str) -> Result<()> {
+    enslave_to_bridge(name, bridge_name).await?;
+
+    let handle = setup_netlink().await?;
+    let index = get_link_index(&handle, name).await?;
+    enable_link(&handle, name, index).await
+}
+
+/// Add an IP address to an interface.
+///
+/// Replaces the address if it is already assigned, so calling this again is harmless.
+#[instrument(fields(%name, %address), level = "debug")]
+pub async fn add_address(name: &str, address: IpAddr, prefix_len: u8) -> Result<()> {
+    let handle = setup_netlink().await?;
+    let index = get_link_index(&handle, name).await?;
+
+    tracing::debug!(link_name = %name, %address, prefix_len, "Adding address to link");
+    handle
+        .address()
+        .add(index, address, prefix_len)
+        .replace()
+        .execute()
+        .await
+        .context(format!(
+            "Error adding address {address}/{prefix_len} to: {name}"
+        ))?;
+    Ok(())
+}
+
 /// Delete and interface
 pub async fn delete_interface(name: &str) -> Result<()> {
     let handle = setup_netlink().await?;
@@ -254,6 +391,59 @@ pub async fn delete_interface(name: &str) -> Result<()> {
     }
 }
 
+/// Traffic counters of a host interface, from the kernel's 64-bit link stats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceStats {
+    pub ifindex: u32,
+    pub rx_packets: u64,
+    pub rx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_bytes: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// Get the counters of every interface whose name contains `fuzzy_name`,
+/// keyed by interface name.
+pub async fn interface_stats(fuzzy_name: &str) -> Result<HashMap<String, InterfaceStats>> {
+    let handle = setup_netlink().await?;
+
+    let mut links = handle.link().get().execute();
+
+    let mut stats = HashMap::new();
+
+    while let Some(link) = links.try_next().await? {
+        let mut name = None;
+        let mut counters = None;
+        for attr in link.attributes {
+            match attr {
+                LinkAttribute::IfName(n) => name = Some(n),
+                LinkAttribute::Stats64(s) => counters = Some(s),
+                _ => {}
+            }
+        }
+
+        if let (Some(name), Some(s)) = (name, counters)
+            && name.contains(fuzzy_name)
+        {
+            stats.insert(
+                name,
+                InterfaceStats {
+                    ifindex: link.header.index,
+                    rx_packets: s.rx_packets,
+                    rx_bytes: s.rx_bytes,
+                    tx_packets: s.tx_packets,
+                    tx_bytes: s.tx_bytes,
+                    rx_dropped: s.rx_dropped,
+                    tx_dropped: s.tx_dropped,
+                },
+            );
+        }
+    }
+
+    Ok(stats)
+}
+
 /// Find interfaces by fuzzy match
 pub async fn find_interfaces_fuzzy(fuzzy_name: &str) -> Result<Vec<String>> {
     let handle = setup_netlink().await?;
@@ -1,17 +1,16 @@
 use anyhow::{Context, Result};
 use rtnetlink::packet_route::link::{InfoKind, LinkAttribute, LinkInfo, LinkMessage};
-use shared::konst::MTU_JUMBO_NET;
 use tracing::instrument;
 
 use crate::linux::{get_link_index, set_link_properties, setup_netlink};
 
 /// Create a persistent tap device.
 ///
-/// The tap device is created in UP state with jumbo MTU.
+/// The tap device is created in UP state with the given MTU.
 /// It can be used by libvirt via `<interface type='ethernet'><target dev='name'/>`
 /// or by eBPF programs for packet redirection.
-#[instrument(fields(%name, %alias_name))]
-pub async fn create_tap(name: &str, alias_name: &str) -> Result<()> {
+#[instrument(fields(%name, %alias_name, mtu))]
+pub async fn create_tap(name: &str, alias_name: &str, mtu: u16) -> Result<()> {
     let handle = setup_netlink().await?;
 
     let mut msg = LinkMessage::default();
@@ -28,7 +27,7 @@ pub async fn create_tap(name: &str, alias_name: &str) -> Result<()> {
         .context(format!("failed to create tap device: {name}"))?;
 
     let idx = get_link_index(&handle, name).await?;
-    set_link_properties(&handle, name, idx, alias_name, MTU_JUMBO_NET as u32).await?;
+    set_link_properties(&handle, name, idx, alias_name, mtu as u32).await?;
 
     Ok(())
 }
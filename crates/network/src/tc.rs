@@ -1,10 +1,15 @@
 use anyhow::{Context, Result};
 use rtnetlink::packet_core::DefaultNla;
 use rtnetlink::packet_core::{
-    NLM_F_ACK, NLM_F_CREATE, NLM_F_REPLACE, NLM_F_REQUEST, NetlinkMessage, NetlinkPayload,
+    NLM_F_ACK, NLM_F_CREATE, NLM_F_EXCL, NLM_F_REPLACE, NLM_F_REQUEST, NetlinkMessage,
+    NetlinkPayload,
 };
 use rtnetlink::packet_route::RouteNetlinkMessage;
-use rtnetlink::packet_route::tc::{TcAttribute, TcHandle, TcMessage};
+use rtnetlink::packet_route::tc::{
+    TcAction, TcActionAttribute, TcActionMirror, TcActionMirrorOption, TcActionOption,
+    TcActionType, TcAttribute, TcFilterU32, TcFilterU32Option, TcHandle, TcMessage, TcMirror,
+    TcMirrorActionType, TcOption, TcU32Key, TcU32Selector, TcU32SelectorFlags,
+};
 use tracing::instrument;
 
 use crate::linux::setup_netlink;
@@ -36,8 +41,22 @@ fn percent_to_kernel(pct: f32) -> u32 {
     ((pct as f64 / 100.0) * u32::MAX as f64) as u32
 }
 
+/// `ETH_P_ALL`, in network byte order as TC filters expect it.
+const ETH_P_ALL_BE: u16 = 0x0003u16.to_be();
+const ENOENT: i32 = 2;
+const EEXIST: i32 = 17;
+const EINVAL: i32 = 22;
+
 /// Send a raw TC netlink message and check for errors in the response.
 async fn send_tc_message(msg: NetlinkMessage<RouteNetlinkMessage>) -> Result<()> {
+    send_tc_message_ignoring(msg, &[]).await
+}
+
+/// Send a raw TC netlink message, treating the listed errnos as success.
+async fn send_tc_message_ignoring(
+    msg: NetlinkMessage<RouteNetlinkMessage>,
+    ignored: &[i32],
+) -> Result<()> {
     let mut handle = setup_netlink().await?;
     let mut response = handle
         .request(msg)
@@ -45,8 +64,11 @@ async fn send_tc_message(msg: NetlinkMessage<RouteNetlinkMessage>) -> Result<()>
 
     while let Some(message) = futures::StreamExt::next(&mut response).await {
         if let NetlinkPayload::Error(err) = message.payload
-            && err.code.is_some()
+            && let Some(code) = err.code
         {
+            if ignored.contains(&-code.get()) {
+                continue;
+            }
             return Err(anyhow::anyhow!("TC netlink error: {err}"));
         }
     }
@@ -130,6 +152,127 @@ pub async fn update_netem(iface_index: i32, impairment: &LinkImpairment) -> Resu
     apply_netem(iface_index, impairment).await
 }
 
+/// Direction on an interface: the clsact hook a mirror filter is attached
+/// to, or the frames a neighbour capture listens for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TcHook {
+    /// Packets received by the interface
+    Ingress,
+    /// Packets transmitted by the interface
+    Egress,
+}
+
+impl TcHook {
+    fn parent(self) -> TcHandle {
+        TcHandle {
+            major: 0xffff,
+            minor: match self {
+                TcHook::Ingress => TcHandle::MIN_INGRESS,
+                TcHook::Egress => TcHandle::MIN_EGRESS,
+            },
+        }
+    }
+}
+
+/// Add a clsact qdisc to an interface unless it already has one.
+async fn ensure_clsact(iface_index: i32) -> Result<()> {
+    let mut msg = TcMessage::default();
+    msg.header.index = iface_index;
+    msg.header.handle = TcHandle {
+        major: 0xffff,
+        minor: 0,
+    };
+    msg.header.parent = TcHandle::CLSACT;
+    msg.attributes.push(TcAttribute::Kind("clsact".to_string()));
+
+    let mut req = NetlinkMessage::from(RouteNetlinkMessage::NewQueueDiscipline(msg));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | NLM_F_EXCL;
+
+    send_tc_message_ignoring(req, &[EEXIST]).await
+}
+
+/// Build a match-all u32 filter message on `hook` at `priority`.
+fn mirror_filter_message(iface_index: i32, hook: TcHook, priority: u16) -> TcMessage {
+    let mut msg = TcMessage::default();
+    msg.header.index = iface_index;
+    msg.header.parent = hook.parent();
+    msg.header.info = u32::from(TcHandle {
+        major: priority,
+        minor: ETH_P_ALL_BE,
+    });
+    msg
+}
+
+/// Mirror every packet on one hook of an interface to another interface's
+/// egress, equivalent to
+/// `tc filter add dev IFACE {ingress|egress} prio PRIO matchall action mirred egress mirror dev TARGET`.
+///
+/// Each mirror owns its `priority`, which must sort before the P2p redirect
+/// program's filter so packets are copied before they are redirected. An
+/// existing filter at that priority is replaced.
+#[instrument(level = "debug")]
+pub async fn add_mirror(
+    iface_index: i32,
+    hook: TcHook,
+    target_ifindex: u32,
+    priority: u16,
+) -> Result<()> {
+    ensure_clsact(iface_index).await?;
+    remove_mirror(iface_index, hook, priority).await?;
+
+    let mut selector = TcU32Selector::default();
+    selector.flags = TcU32SelectorFlags::Terminal;
+    selector.nkeys = 1;
+    selector.keys = vec![TcU32Key::default()];
+
+    let mut mirror = TcMirror::default();
+    mirror.generic.action = TcActionType::Pipe;
+    mirror.eaction = TcMirrorActionType::EgressMirror;
+    mirror.ifindex = target_ifindex;
+
+    let mut action = TcAction::default();
+    action.attributes = vec![
+        TcActionAttribute::Kind(TcActionMirror::KIND.to_string()),
+        TcActionAttribute::Options(vec![TcActionOption::Mirror(TcActionMirrorOption::Parms(
+            mirror,
+        ))]),
+    ];
+
+    let mut msg = mirror_filter_message(iface_index, hook, priority);
+    msg.attributes
+        .push(TcAttribute::Kind(TcFilterU32::KIND.to_string()));
+    msg.attributes.push(TcAttribute::Options(vec![
+        TcOption::U32(TcFilterU32Option::Selector(selector)),
+        TcOption::U32(TcFilterU32Option::Action(vec![action])),
+    ]));
+
+    let mut req = NetlinkMessage::from(RouteNetlinkMessage::NewTrafficFilter(msg));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK | NLM_F_CREATE | NLM_F_EXCL;
+
+    tracing::info!(
+        iface_index = iface_index,
+        hook = ?hook,
+        target_ifindex = target_ifindex,
+        priority = priority,
+        "adding mirror filter"
+    );
+
+    send_tc_message(req).await
+}
+
+/// Remove the mirror filter at `priority` from one hook of an interface.
+///
+/// Removing a mirror that does not exist is a no-op.
+#[instrument(level = "debug")]
+pub async fn remove_mirror(iface_index: i32, hook: TcHook, priority: u16) -> Result<()> {
+    let msg = mirror_filter_message(iface_index, hook, priority);
+    let mut req = NetlinkMessage::from(RouteNetlinkMessage::DelTrafficFilter(msg));
+    req.header.flags = NLM_F_REQUEST | NLM_F_ACK;
+
+    // EINVAL: the interface has no clsact qdisc yet
+    send_tc_message_ignoring(req, &[ENOENT, EINVAL]).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
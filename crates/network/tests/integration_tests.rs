@@ -12,9 +12,11 @@ use anyhow::Result;
 
 use network::{
     LinkImpairment, apply_netem, attach_p2p_redirect, create_bridge, create_tap, create_veth_pair,
-    delete_interface, enslave_to_bridge, find_interfaces_fuzzy, get_ifindex, remove_netem,
-    set_link_down, update_netem,
+    create_vlan_bridge, delete_interface, enslave_to_bridge, find_interfaces_fuzzy, get_ifindex,
+    remove_netem, set_bridge_port_vlans, set_l2_filter, set_link_down, update_netem,
 };
+use shared::data::{L2Match, L2Protocol, LinkFilter, PortVlan, VlanRange};
+use shared::konst::MTU_JUMBO_NET;
 
 // ============================================================================
 // Helper
@@ -33,7 +35,7 @@ async fn cleanup_interface(name: &str) {
 async fn tap_rtnetlink_unsupported() -> bool {
     let probe = "st-tap-probe";
     let _ = delete_interface(probe).await;
-    match create_tap(probe, "probe").await {
+    match create_tap(probe, "probe", MTU_JUMBO_NET).await {
         Ok(()) => {
             let _ = delete_interface(probe).await;
             false
@@ -96,7 +98,7 @@ async fn test_create_bridge() -> Result<()> {
 
     cleanup_interface(name).await;
 
-    create_bridge(name, alias).await?;
+    create_bridge(name, alias, MTU_JUMBO_NET).await?;
 
     // Verify bridge exists via fuzzy search
     let found = find_interfaces_fuzzy(name).await?;
@@ -128,7 +130,7 @@ async fn test_bridge_mtu_and_up_state() -> Result<()> {
 
     cleanup_interface(name).await;
 
-    create_bridge(name, alias).await?;
+    create_bridge(name, alias, MTU_JUMBO_NET).await?;
 
     assert_eq!(
         interface_mtu(name),
@@ -154,9 +156,9 @@ async fn test_create_bridge_duplicate() -> Result<()> {
 
     cleanup_interface(name).await;
 
-    create_bridge(name, alias).await?;
+    create_bridge(name, alias, MTU_JUMBO_NET).await?;
 
-    let result = create_bridge(name, alias).await;
+    let result = create_bridge(name, alias, MTU_JUMBO_NET).await;
     assert!(
         result.is_err(),
         "Creating a duplicate bridge should fail with an error"
@@ -180,7 +182,7 @@ async fn test_create_veth_pair() -> Result<()> {
     cleanup_interface(src).await;
     cleanup_interface(dst).await;
 
-    create_veth_pair(src, dst, "test-src-alias", "test-dst-alias").await?;
+    create_veth_pair(src, dst, "test-src-alias", "test-dst-alias", MTU_JUMBO_NET).await?;
 
     // Verify both ends exist
     let found = find_interfaces_fuzzy("st-veth0").await?;
@@ -200,6 +202,33 @@ async fn test_create_veth_pair() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_veth_pair_mtu() -> Result<()> {
+    let src = "st-veth-mtu-a";
+    let dst = "st-veth-mtu-b";
+
+    cleanup_interface(src).await;
+    cleanup_interface(dst).await;
+
+    create_veth_pair(src, dst, "test-mtu-src", "test-mtu-dst", 9216).await?;
+
+    assert_eq!(
+        interface_mtu(src),
+        Some(9216),
+        "src veth MTU should be 9216"
+    );
+    assert_eq!(
+        interface_mtu(dst),
+        Some(9216),
+        "dst veth MTU should be 9216"
+    );
+
+    delete_interface(src).await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_veth_pair_up_state() -> Result<()> {
@@ -209,7 +238,7 @@ async fn test_veth_pair_up_state() -> Result<()> {
     cleanup_interface(src).await;
     cleanup_interface(dst).await;
 
-    create_veth_pair(src, dst, "test-up-src", "test-up-dst").await?;
+    create_veth_pair(src, dst, "test-up-src", "test-up-dst", MTU_JUMBO_NET).await?;
 
     assert!(
         interface_is_up(src),
@@ -234,9 +263,9 @@ async fn test_create_veth_pair_duplicate() -> Result<()> {
     cleanup_interface(src).await;
     cleanup_interface(dst).await;
 
-    create_veth_pair(src, dst, "test-dup-src", "test-dup-dst").await?;
+    create_veth_pair(src, dst, "test-dup-src", "test-dup-dst", MTU_JUMBO_NET).await?;
 
-    let result = create_veth_pair(src, dst, "test-dup-src", "test-dup-dst").await;
+    let result = create_veth_pair(src, dst, "test-dup-src", "test-dup-dst", MTU_JUMBO_NET).await;
     assert!(
         result.is_err(),
         "Creating a duplicate veth pair should fail with an error"
@@ -262,8 +291,15 @@ async fn test_enslave_veth_to_bridge() -> Result<()> {
     cleanup_interface(src).await;
 
     // Create bridge and veth pair
-    create_bridge(br, "test-bridge-enslave").await?;
-    create_veth_pair(src, dst, "test-enslave-src", "test-enslave-dst").await?;
+    create_bridge(br, "test-bridge-enslave", MTU_JUMBO_NET).await?;
+    create_veth_pair(
+        src,
+        dst,
+        "test-enslave-src",
+        "test-enslave-dst",
+        MTU_JUMBO_NET,
+    )
+    .await?;
 
     // Enslave src end to bridge
     enslave_to_bridge(src, br).await?;
@@ -278,6 +314,52 @@ async fn test_enslave_veth_to_bridge() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_set_bridge_port_vlans() -> Result<()> {
+    let br = "st-brv";
+    let src = "st-vethva";
+    let dst = "st-vethvb";
+
+    cleanup_interface(br).await;
+    cleanup_interface(src).await;
+
+    create_vlan_bridge(br, "test-bridge-vlan", MTU_JUMBO_NET).await?;
+    create_veth_pair(src, dst, "test-vlan-src", "test-vlan-dst", MTU_JUMBO_NET).await?;
+
+    // Trunk carrying 100-199 tagged and 10 untagged, set twice
+    let vlans = [
+        PortVlan {
+            vlans: VlanRange {
+                first: 100,
+                last: 199,
+            },
+            native: false,
+        },
+        PortVlan {
+            vlans: VlanRange::single(10),
+            native: true,
+        },
+    ];
+    set_bridge_port_vlans(src, br, &vlans).await?;
+    set_bridge_port_vlans(src, br, &vlans).await?;
+
+    let output = std::process::Command::new("bridge")
+        .args(["vlan", "show", "dev", src])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default();
+    if !output.is_empty() {
+        assert!(output.contains("10 PVID"), "native VLAN missing: {output}");
+        assert!(output.contains("100"), "trunk VLANs missing: {output}");
+        assert!(!output.contains(" 1 PVID"), "default VLAN kept: {output}");
+    }
+
+    delete_interface(src).await?;
+    delete_interface(br).await?;
+    Ok(())
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_enslave_nonexistent_interface_fails() -> Result<()> {
@@ -285,7 +367,7 @@ async fn test_enslave_nonexistent_interface_fails() -> Result<()> {
 
     cleanup_interface(br).await;
 
-    create_bridge(br, "test-bridge-noexist").await?;
+    create_bridge(br, "test-bridge-noexist", MTU_JUMBO_NET).await?;
 
     // Try to enslave an interface that doesn't exist
     let result = enslave_to_bridge("st-noexist", br).await;
@@ -307,7 +389,7 @@ async fn test_enslave_to_nonexistent_bridge() -> Result<()> {
     cleanup_interface(src).await;
     cleanup_interface(dst).await;
 
-    create_veth_pair(src, dst, "test-enslv-src", "test-enslv-dst").await?;
+    create_veth_pair(src, dst, "test-enslv-src", "test-enslv-dst", MTU_JUMBO_NET).await?;
 
     // Try to enslave to a bridge that doesn't exist
     let result = enslave_to_bridge(src, "st-nobridge").await;
@@ -357,8 +439,8 @@ async fn test_find_interfaces_fuzzy_multiple_matches() -> Result<()> {
     cleanup_interface(br1).await;
     cleanup_interface(br2).await;
 
-    create_bridge(br1, "fuzzy-test-1").await?;
-    create_bridge(br2, "fuzzy-test-2").await?;
+    create_bridge(br1, "fuzzy-test-1", MTU_JUMBO_NET).await?;
+    create_bridge(br2, "fuzzy-test-2", MTU_JUMBO_NET).await?;
 
     // Both should match "st-fz-"
     let found = find_interfaces_fuzzy("st-fz-").await?;
@@ -392,7 +474,7 @@ async fn test_create_tap() -> Result<()> {
 
     cleanup_interface(name).await;
 
-    create_tap(name, "test-tap-alias").await?;
+    create_tap(name, "test-tap-alias", MTU_JUMBO_NET).await?;
 
     let found = find_interfaces_fuzzy(name).await?;
     assert!(
@@ -436,7 +518,7 @@ async fn test_get_ifindex() -> Result<()> {
 
     cleanup_interface(name).await;
 
-    create_tap(name, "test-ifindex").await?;
+    create_tap(name, "test-ifindex", MTU_JUMBO_NET).await?;
 
     let idx = get_ifindex(name).await?;
     assert!(idx > 0, "ifindex should be positive, got: {}", idx);
@@ -469,7 +551,7 @@ async fn test_apply_netem_to_veth() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "netem-src", "netem-dst").await?;
+    create_veth_pair(src, dst, "netem-src", "netem-dst", MTU_JUMBO_NET).await?;
 
     let idx = get_ifindex(src).await?;
 
@@ -508,7 +590,7 @@ async fn test_remove_netem() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "netem-rm-src", "netem-rm-dst").await?;
+    create_veth_pair(src, dst, "netem-rm-src", "netem-rm-dst", MTU_JUMBO_NET).await?;
 
     let idx = get_ifindex(src).await?;
 
@@ -553,7 +635,7 @@ async fn test_ebpf_redirect_between_veths() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "ebpf-src", "ebpf-dst").await?;
+    create_veth_pair(src, dst, "ebpf-src", "ebpf-dst", MTU_JUMBO_NET).await?;
 
     let idx_src = get_ifindex(src).await?;
     let idx_dst = get_ifindex(dst).await?;
@@ -605,7 +687,7 @@ async fn test_ebpf_redirect_idempotent() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "ebpf-id-src", "ebpf-id-dst").await?;
+    create_veth_pair(src, dst, "ebpf-id-src", "ebpf-id-dst", MTU_JUMBO_NET).await?;
 
     let idx_src = get_ifindex(src).await?;
     let idx_dst = get_ifindex(dst).await?;
@@ -658,8 +740,8 @@ async fn test_ebpf_redirect_between_taps() -> Result<()> {
     cleanup_interface(tap_a).await;
     cleanup_interface(tap_b).await;
 
-    create_tap(tap_a, "ebpf-tap-a").await?;
-    create_tap(tap_b, "ebpf-tap-b").await?;
+    create_tap(tap_a, "ebpf-tap-a", MTU_JUMBO_NET).await?;
+    create_tap(tap_b, "ebpf-tap-b", MTU_JUMBO_NET).await?;
 
     let idx_a = get_ifindex(tap_a).await?;
     let idx_b = get_ifindex(tap_b).await?;
@@ -693,7 +775,7 @@ async fn test_ebpf_and_netem_coexist() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "ebpf-nm-src", "ebpf-nm-dst").await?;
+    create_veth_pair(src, dst, "ebpf-nm-src", "ebpf-nm-dst", MTU_JUMBO_NET).await?;
 
     let idx_src = get_ifindex(src).await?;
     let idx_dst = get_ifindex(dst).await?;
@@ -761,7 +843,7 @@ async fn test_set_link_down() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "down-src", "down-dst").await?;
+    create_veth_pair(src, dst, "down-src", "down-dst", MTU_JUMBO_NET).await?;
 
     // Both should be UP after creation
     assert!(
@@ -816,7 +898,7 @@ async fn test_update_netem() -> Result<()> {
 
     cleanup_interface(src).await;
 
-    create_veth_pair(src, dst, "netem-up-src", "netem-up-dst").await?;
+    create_veth_pair(src, dst, "netem-up-src", "netem-up-dst", MTU_JUMBO_NET).await?;
 
     let idx = get_ifindex(src).await?;
 
@@ -862,3 +944,45 @@ async fn test_update_netem() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_l2_filter_on_veth_without_qdisc() -> Result<()> {
+    let src = "st-l2f-a";
+    let dst = "st-l2f-b";
+
+    cleanup_interface(src).await;
+
+    create_veth_pair(src, dst, "l2f-src", "l2f-dst", MTU_JUMBO_NET).await?;
+
+    // A fresh veth has no clsact qdisc for the netlink attach
+    let filter = LinkFilter {
+        drop: vec![L2Match::Protocol(L2Protocol::Lacp)],
+        allow: vec![],
+    };
+    set_l2_filter(src, &filter)?;
+
+    let output = std::process::Command::new("tc")
+        .args(["filter", "show", "dev", src, "ingress"])
+        .output()
+        .expect("tc command failed");
+    let tc_output = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        tc_output.contains("l2_filter"),
+        "l2_filter should be attached on {}, got: {}",
+        src,
+        tc_output
+    );
+
+    // An empty filter detaches the program
+    set_l2_filter(src, &LinkFilter::default())?;
+    let output = std::process::Command::new("tc")
+        .args(["filter", "show", "dev", src, "ingress"])
+        .output()
+        .expect("tc command failed");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("l2_filter"));
+
+    delete_interface(src).await?;
+
+    Ok(())
+}
@@ -1,11 +1,13 @@
-use axum::extract::FromRequestParts;
+use axum::extract::{FromRequestParts, MatchedPath};
 use axum::http::header;
 use axum::http::request::Parts;
 use axum::response::{IntoResponse, Redirect, Response};
 use jsonwebtoken::errors::{Error as JwtError, ErrorKind};
+use shared::auth::api_token::is_api_token;
 use shared::auth::jwt::Claims;
+use shared::konst::RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE;
 
-use crate::auth::{context::AuthContext, cookies, jwt};
+use crate::auth::{context::AuthContext, cookies, jwt, middleware};
 use crate::daemon::state::AppState;
 
 use super::errors::ApiError;
@@ -232,14 +234,21 @@ pub(super) fn trace_rejected_session(path: &str, error: &CookieSessionError, act
     }
 }
 
+// Extractor logic helper: extract the token from an `Authorization: Bearer` header
+fn bearer_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+}
+
 // Extractor logic helper: extract and validate token from Authorization header or Cookie
 fn extract_and_validate_token(
     headers: &axum::http::HeaderMap,
     jwt_secret: &[u8],
 ) -> Result<(String, bool), &'static str> {
     // Try Authorization header first
-    if let Some(auth_header) = headers.get("authorization").and_then(|h| h.to_str().ok())
-        && let Some(token) = auth_header.strip_prefix("Bearer ")
+    if let Some(token) = bearer_token(headers)
         && let Ok(claims) = jwt::validate_token(jwt_secret, token)
     {
         return Ok((claims.sub, claims.is_admin));
@@ -256,11 +265,13 @@ fn extract_and_validate_token(
     Err("Missing or invalid authentication")
 }
 
-/// Authenticated user extracted from JWT token.
+/// Authenticated user extracted from JWT token or personal API token.
 ///
 /// Use this as a handler parameter to require authentication.
 /// The token is extracted from the `Authorization: Bearer <token>` header OR from cookie.
 /// Header takes priority if both are present.
+/// API tokens are only accepted in the header, and only for routes covered by
+/// one of the token's scopes.
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
     pub username: String,
@@ -282,6 +293,35 @@ impl FromRequestParts<AppState> for AuthenticatedUser {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
+        if let Some(token) = bearer_token(&parts.headers).filter(|token| is_api_token(token)) {
+            let auth_ctx = middleware::authenticate_api_token(token, state)
+                .await
+                .map_err(|e| {
+                    tracing::warn!("API token authentication failed: {}", e);
+                    ApiError::unauthorized("Invalid, revoked or expired API token")
+                })?;
+
+            let path = parts
+                .extensions
+                .get::<MatchedPath>()
+                .map(|path| path.as_str())
+                .unwrap_or_else(|| parts.uri.path());
+            if !auth_ctx.allows_token_scope(middleware::rest_token_scope(&parts.method, path)) {
+                tracing::warn!(
+                    "User '{}' attempted {} {} with an API token lacking the required scope",
+                    auth_ctx.username,
+                    parts.method,
+                    path
+                );
+                return Err(ApiError::forbidden(RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE));
+            }
+
+            return Ok(AuthenticatedUser {
+                username: auth_ctx.username,
+                is_admin: auth_ctx.is_admin,
+            });
+        }
+
         let (username, is_admin) = extract_and_validate_token(&parts.headers, &state.jwt_secret)
             .map_err(ApiError::unauthorized)?;
         Ok(AuthenticatedUser { username, is_admin })
@@ -12,23 +12,27 @@ use std::str::FromStr;
 use strum::IntoEnumIterator;
 
 use crate::api::sse::{destroy_progress_stream, json_progress_stream, up_progress_stream};
-use crate::auth::{cookies, jwt};
+use crate::auth::context::AuthContext;
+use crate::auth::{cookies, jwt, login, oidc};
 use crate::daemon::state::AppState;
 use crate::daemon::state::{Job, JobType};
+use crate::services::mirror::MirrorAction;
 use crate::services::progress::ProgressSender;
 use crate::services::{
-    clean, container_pull, delete, destroy, down, impairment, import, inspect, list_labs, redeploy,
-    resume, up,
+    api_token, cabling, clean, commit, container_pull, custom_model, delete, destroy, down,
+    image_usage, impairment, import, inspect, leases, link_filter, link_stats, list_labs, mirror,
+    redeploy, registry, resume, share, up, upload,
 };
 use crate::templates::{
     AdminImageUploadTemplate, AdminNotificationErrorTemplate, AdminPasswordErrorTemplate,
     AdminPasswordSuccessTemplate, AdminSshKeysListTemplate, AdminToolsTemplate,
-    AdminUserEditTemplate, AdminUsersTemplate, DashboardTemplate, EmptyStateTemplate,
-    Error403Template, Error404Template, ErrorTemplate, JobPageTemplate, LabCreateTemplate,
-    LabDestroyButtonFragment, LabDestroyConfirmFragment, LabDetailTemplate, LabsGridTemplate,
-    LabsListTemplate, LoginErrorTemplate, LoginPageTemplate, NodeDetailTemplate,
-    NodesTableFragment, PasswordErrorTemplate, PasswordSuccessTemplate, ProfileTemplate,
-    SignupErrorTemplate, SignupPageTemplate, SshKeyErrorTemplate, SshKeysListTemplate,
+    AdminUserEditTemplate, AdminUsersTemplate, ApiTokensListTemplate, DashboardTemplate,
+    EmptyStateTemplate, Error403Template, Error404Template, ErrorTemplate, JobPageTemplate,
+    LabCreateTemplate, LabDestroyButtonFragment, LabDestroyConfirmFragment, LabDetailTemplate,
+    LabLeasesFragment, LabSharesFragment, LabTopologyFragment, LabsGridTemplate, LabsListTemplate,
+    LoginErrorTemplate, LoginPageTemplate, NodeDetailTemplate, NodesTableFragment,
+    PasswordErrorTemplate, PasswordSuccessTemplate, ProfileTemplate, SignupErrorTemplate,
+    SignupPageTemplate, SshKeyErrorTemplate, SshKeysListTemplate,
 };
 
 use super::errors::ApiError;
@@ -40,17 +44,29 @@ use super::extractors::{
 use shared::api_spec;
 use shared::auth::{password, ssh};
 use shared::data::{
-    BiosTypes, ChangePasswordRequest, ChangePasswordResponse, ContainerPullRequest,
-    CpuArchitecture, CpuModels, CreateUserRequest, CreateUserResponse, DeleteImageRequest,
-    DestroyRequest, DiskBuses, DownloadImageRequest, GetUserInfoResponse, ImportRequest,
-    InspectRequest, InspectResponse, InterfaceType, LabNodeActionResponse, ListImagesRequest,
-    ListLabsResponse, ListUsersResponse, LoginRequest, LoginResponse, MachineType, NodeConfig,
-    NodeModel, OsVariant, RedeployRequest, ScanImagesRequest, SetDefaultImageRequest,
-    ShowImageRequest, UpRequest, UpdateImpairmentRequest, UpdateImpairmentResponse, UserInfo,
-    ZtpMethod,
+    AddCustomModelRequest, ApiTokenInfo, AuthProvider, AuthProvidersResponse, BiosTypes,
+    ChangePasswordRequest, ChangePasswordResponse, ContainerPullRequest, CpuArchitecture,
+    CpuModels, CreateApiTokenResponse, CreateUserRequest, CreateUserResponse,
+    DeleteCustomModelRequest, DeleteImageRequest, DeleteTeamResponse, DestroyRequest,
+    DeviceLoginPollRequest, DeviceLoginPollResponse, DeviceLoginStartResponse, DiskBuses,
+    DownloadImageRequest, GetUserInfoResponse, ImageVersionUsage, ImportRequest, InspectRequest,
+    InspectResponse, InterfaceType, LabLease, LabLeasesResponse, LabNodeActionResponse, LabRole,
+    LinkFilter, LinkFilterRequest, LinkFilterResponse, LinkMirrorRequest, LinkMirrorResponse,
+    LinkStatsResponse, ListApiTokensResponse, ListImagesRequest, ListLabSharesResponse,
+    ListLabsResponse, ListTeamsResponse, ListUsersResponse, LoginRequest, LoginResponse,
+    MachineType, MirrorDirection, NodeCommitRequest, NodeConfig, NodeModel, NodeState, OsVariant,
+    PruneImagesRequest, RedeployRequest, RegistryLoginRequest, RegistryLogoutRequest,
+    RevokeApiTokenResponse, ScanImagesRequest, SetDefaultImageRequest, ShareLabRequest,
+    ShowImageRequest, StartUploadRequest, TeamInfo, TokenScope, UnshareLabRequest, UpRequest,
+    UpdateImpairmentRequest, UpdateImpairmentResponse, UpdateTeamMembersRequest, UserInfo,
+    VerifyCablingRequest, VerifyCablingResponse, VerifyImageRequest, ZtpMethod, split_grantee,
 };
-use shared::konst::{JWT_TOKEN_EXPIRY_SECONDS, SHERPA_SERVER_CERT_PATH};
-use shared::util::{generate_lab_name, get_id_for_user};
+use shared::konst::{
+    API_TOKEN_DEFAULT_EXPIRY_DAYS, IMAGE_UPLOAD_CHUNK_SHA256_HEADER, JWT_TOKEN_EXPIRY_SECONDS,
+    SHERPA_SERVER_CERT_PATH,
+};
+use shared::util::{format_bytes, generate_lab_name, get_id_for_user};
+use topology::{Diagram, DiagramBridge, DiagramLink, DiagramNode};
 
 /// Authenticate user and issue JWT token
 ///
@@ -63,27 +79,19 @@ pub async fn login(
     State(state): State<AppState>,
     Json(request): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, ApiError> {
-    // Get user from database (includes password_hash)
-    let user = db::get_user_for_auth(&state.db, &request.username)
+    // Verify credentials against the local store or the LDAP directory
+    let user = login::authenticate_password(&state, &request.username, &request.password)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Authentication error: {:?}", e);
+            ApiError::internal("Authentication error")
+        })?
+        .ok_or_else(|| {
             // Don't reveal whether user exists
-            tracing::debug!("Login attempt for non-existent user: {}", request.username);
+            tracing::debug!("Invalid credentials for user: {}", request.username);
             ApiError::unauthorized("Invalid username or password")
         })?;
 
-    // Verify password against stored hash
-    let is_valid =
-        password::verify_password(&request.password, &user.password_hash).map_err(|e| {
-            tracing::error!("Password verification error: {:?}", e);
-            ApiError::internal("Authentication error")
-        })?;
-
-    if !is_valid {
-        tracing::debug!("Invalid password for user: {}", request.username);
-        return Err(ApiError::unauthorized("Invalid username or password"));
-    }
-
     // Create JWT token
     let token = jwt::create_token(
         &state.jwt_secret,
@@ -176,19 +184,31 @@ pub async fn login_page_handler(
             };
             axum::response::Redirect::to(redirect_path).into_response()
         }
-        Err(CookieSessionError::Missing) => LoginPageTemplate { error, message }.into_response(),
+        Err(CookieSessionError::Missing) => login_page(&state, error, message).into_response(),
         Err(session_error) => {
             trace_rejected_session("/login", &session_error, "clear_and_render_login");
             error = "session_expired".to_string();
             (
                 [(header::SET_COOKIE, cookies::create_clear_cookie())],
-                LoginPageTemplate { error, message },
+                login_page(&state, error, message),
             )
                 .into_response()
         }
     }
 }
 
+/// Build the login page for the configured authentication providers
+fn login_page(state: &AppState, error: String, message: String) -> LoginPageTemplate {
+    let auth = &state.config.auth;
+    LoginPageTemplate {
+        error,
+        message,
+        password_enabled: auth.local || auth.ldap.is_some(),
+        sso_enabled: auth.oidc.is_some(),
+        signup_enabled: auth.local,
+    }
+}
+
 /// Process login form submission
 ///
 /// POST /login
@@ -207,23 +227,18 @@ pub async fn login_form_handler(
     State(state): State<AppState>,
     axum::Form(form): axum::Form<LoginForm>,
 ) -> impl IntoResponse {
-    // Get user from database
-    let user = match db::get_user_for_auth(&state.db, &form.username).await {
-        Ok(user) => user,
-        Err(_) => {
-            tracing::debug!("Login attempt for non-existent user: {}", form.username);
+    // Verify credentials against the local store or the LDAP directory
+    let user = match login::authenticate_password(&state, &form.username, &form.password).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            tracing::debug!("Invalid credentials for user: {}", form.username);
             return LoginErrorTemplate {
                 message: "Invalid username or password".to_string(),
             }
             .into_response();
         }
-    };
-
-    // Verify password
-    let is_valid = match password::verify_password(&form.password, &user.password_hash) {
-        Ok(valid) => valid,
         Err(e) => {
-            tracing::error!("Password verification error: {:?}", e);
+            tracing::error!("Authentication error: {:?}", e);
             return LoginErrorTemplate {
                 message: "An error occurred during authentication".to_string(),
             }
@@ -231,14 +246,6 @@ pub async fn login_form_handler(
         }
     };
 
-    if !is_valid {
-        tracing::debug!("Invalid password for user: {}", form.username);
-        return LoginErrorTemplate {
-            message: "Invalid username or password".to_string(),
-        }
-        .into_response();
-    }
-
     // Determine cookie expiry based on remember_me
     let expiry_seconds = if form.remember_me {
         cookies::COOKIE_MAX_AGE_REMEMBER
@@ -301,9 +308,13 @@ pub async fn login_form_handler(
 ///
 /// GET /signup
 ///
-/// Shows the signup form with password requirements.
-pub async fn signup_page_handler() -> impl IntoResponse {
-    SignupPageTemplate {}
+/// Shows the signup form with password requirements. Redirects to the login
+/// page when local accounts are disabled.
+pub async fn signup_page_handler(State(state): State<AppState>) -> Response {
+    if !state.config.auth.local {
+        return axum::response::Redirect::to("/login").into_response();
+    }
+    SignupPageTemplate {}.into_response()
 }
 
 /// Process signup form submission
@@ -332,6 +343,15 @@ pub async fn signup_form_handler(
 ) -> impl IntoResponse {
     tracing::info!("Signup attempt for username: {}", form.username);
 
+    // Self-registration creates a local password, which may be disabled
+    if !state.config.auth.local {
+        tracing::debug!("Signup rejected, local accounts are disabled");
+        return SignupErrorTemplate {
+            message: "Self-registration is disabled on this server".to_string(),
+        }
+        .into_response();
+    }
+
     // 1. Validate passwords match
     if form.password != form.confirm_password {
         tracing::debug!(
@@ -494,6 +514,212 @@ pub async fn logout_handler() -> impl IntoResponse {
     )
 }
 
+// ============================================================================
+// External Identity Provider Handlers
+// ============================================================================
+
+/// List the login methods offered by the server
+///
+/// GET /api/v1/auth/providers
+pub async fn auth_providers_json(State(state): State<AppState>) -> Json<AuthProvidersResponse> {
+    Json(login::providers(&state.config.auth))
+}
+
+/// Start an OIDC device login for the CLI
+///
+/// POST /api/v1/auth/device
+///
+/// # Errors
+/// - `400 Bad Request` - Single sign-on is not configured
+/// - `500 Internal Server Error` - The identity provider rejected the request
+pub async fn device_login_start_json(
+    State(state): State<AppState>,
+) -> Result<Json<DeviceLoginStartResponse>, ApiError> {
+    login::oidc_config(&state.config.auth).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let response = login::start_device_login(&state).await.map_err(|e| {
+        tracing::error!("Failed to start OIDC device login: {:?}", e);
+        ApiError::internal("Failed to start single sign-on device login")
+    })?;
+    Ok(Json(response))
+}
+
+/// Poll an OIDC device login
+///
+/// POST /api/v1/auth/device/token
+///
+/// # Errors
+/// - `400 Bad Request` - Single sign-on is not configured
+/// - `401 Unauthorized` - The login was denied, expired or cannot be provisioned
+pub async fn device_login_poll_json(
+    State(state): State<AppState>,
+    Json(request): Json<DeviceLoginPollRequest>,
+) -> Result<Json<DeviceLoginPollResponse>, ApiError> {
+    login::oidc_config(&state.config.auth).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let response = login::poll_device_login(&state, &request.device_code)
+        .await
+        .map_err(|e| {
+            tracing::warn!("OIDC device login failed: {:?}", e);
+            ApiError::unauthorized("Single sign-on device login failed")
+        })?;
+    Ok(Json(response))
+}
+
+/// Query parameters of the OIDC callback
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// Redirect to the login page after a failed single sign-on
+fn sso_failed() -> Response {
+    (
+        StatusCode::SEE_OTHER,
+        [
+            (
+                header::SET_COOKIE,
+                cookies::create_clear_oidc_state_cookie(),
+            ),
+            (header::LOCATION, "/login?error=sso_failed".to_string()),
+        ],
+    )
+        .into_response()
+}
+
+/// Start a single sign-on web login
+///
+/// GET /auth/oidc/login
+///
+/// Stores the signed login state in a short-lived cookie and redirects to
+/// the identity provider.
+pub async fn oidc_login_handler(State(state): State<AppState>) -> Response {
+    let Some(config) = &state.config.auth.oidc else {
+        return axum::response::Redirect::to("/login").into_response();
+    };
+
+    let client = match oidc::OidcClient::discover(config).await {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("OIDC discovery failed: {:?}", e);
+            return sso_failed();
+        }
+    };
+
+    let login_state = oidc::LoginState::new();
+    let redirect = login_state.encode(&state.jwt_secret).and_then(|encoded| {
+        client
+            .authorization_url(&login_state)
+            .map(|url| (encoded, url))
+    });
+    match redirect {
+        Ok((encoded, url)) => (
+            StatusCode::SEE_OTHER,
+            [
+                (
+                    header::SET_COOKIE,
+                    cookies::create_oidc_state_cookie(&encoded),
+                ),
+                (header::LOCATION, url),
+            ],
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to start OIDC login: {:?}", e);
+            sso_failed()
+        }
+    }
+}
+
+/// Complete a single sign-on web login
+///
+/// GET /auth/oidc/callback
+///
+/// Verifies the login state, exchanges the authorization code, provisions
+/// the user and sets the authentication cookie. The redirect is done with a
+/// page refresh rather than a 303, because browsers don't send SameSite=Strict
+/// cookies on a redirect chain that started at the identity provider.
+pub async fn oidc_callback_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> Response {
+    let Some(config) = &state.config.auth.oidc else {
+        return axum::response::Redirect::to("/login").into_response();
+    };
+
+    if let Some(error) = &query.error {
+        tracing::warn!("OIDC provider returned an error: {}", error);
+        return sso_failed();
+    }
+    let (Some(code), Some(returned_state)) = (&query.code, &query.state) else {
+        return sso_failed();
+    };
+
+    let login_state = match headers
+        .get(header::COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(cookies::extract_oidc_state_from_cookie)
+        .map(|encoded| oidc::LoginState::decode(&encoded, &state.jwt_secret))
+    {
+        Some(Ok(login_state)) if login_state.state == *returned_state => login_state,
+        _ => {
+            tracing::warn!("OIDC callback with a missing or mismatched login state");
+            return sso_failed();
+        }
+    };
+
+    let oidc_user = match oidc::OidcClient::discover(config).await {
+        Ok(client) => client.exchange_code(code, &login_state).await,
+        Err(e) => Err(e),
+    };
+    let user = match oidc_user {
+        Ok(oidc_user) => login::provision_oidc_user(&state, &oidc_user).await,
+        Err(e) => Err(e),
+    };
+    let user = match user {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::warn!("OIDC login failed: {:?}", e);
+            return sso_failed();
+        }
+    };
+
+    let token = match jwt::create_token(
+        &state.jwt_secret,
+        &user.username,
+        user.is_admin,
+        cookies::COOKIE_MAX_AGE_NORMAL,
+    ) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("Failed to create JWT token: {:?}", e);
+            return sso_failed();
+        }
+    };
+
+    tracing::info!(
+        event = "auth.login_succeeded",
+        channel = "oidc",
+        username = %user.username,
+        is_admin = user.is_admin,
+        "User logged in successfully"
+    );
+
+    let redirect_path = if user.is_admin { "/admin/users" } else { "/" };
+    (
+        axum::response::AppendHeaders([
+            (header::SET_COOKIE, cookies::create_auth_cookie(&token, false)),
+            (header::SET_COOKIE, cookies::create_clear_oidc_state_cookie()),
+        ]),
+        Html(format!(
+            r#"<!DOCTYPE html><html><head><meta http-equiv="refresh" content="0;url={0}"></head><body><a href="{0}">Continue</a></body></html>"#,
+            redirect_path
+        )),
+    )
+        .into_response()
+}
+
 // ============================================================================
 // Profile Management Handlers
 // ============================================================================
@@ -512,6 +738,27 @@ pub struct AddSshKeyForm {
     ssh_key: String,
 }
 
+/// Form data for creating an API token
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenForm {
+    name: String,
+    scope_read_only: Option<String>,   // checkbox
+    scope_lab_operate: Option<String>, // checkbox
+    scope_image_admin: Option<String>, // checkbox
+    expires_in_days: u32,
+}
+
+/// Helper struct for displaying an API token on the profile page
+#[derive(Debug, Clone)]
+pub struct ApiTokenSummary {
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expired: bool,
+    pub expires_at_formatted: String,
+    pub last_used_at_formatted: String,
+}
+
 /// Display user profile page
 ///
 /// GET /profile
@@ -544,6 +791,10 @@ pub async fn profile_handler(
         }
         .render()
         .unwrap_or_else(|_| String::from("Error rendering SSH keys")),
+        api_tokens_html: api_tokens_list(&state, &auth.username, None, None)
+            .await
+            .render()
+            .unwrap_or_else(|_| String::from("Error rendering API tokens")),
     }
     .into_response()
 }
@@ -569,7 +820,14 @@ pub async fn update_password_handler(
 
     // 2. Get user from database (with password_hash)
     let user = match db::get_user_for_auth(&state.db, &auth.username).await {
-        Ok(user) => user,
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            tracing::error!("User '{}' not found for password update", auth.username);
+            return PasswordErrorTemplate {
+                message: "Failed to verify current password".to_string(),
+            }
+            .into_response();
+        }
         Err(e) => {
             tracing::error!(
                 "Failed to load user '{}' for password update: {:?}",
@@ -583,6 +841,17 @@ pub async fn update_password_handler(
         }
     };
 
+    // Passwords of LDAP and OIDC users are managed by the identity provider
+    if user.auth_provider != AuthProvider::Local {
+        return PasswordErrorTemplate {
+            message: format!(
+                "Your password is managed by your {} identity provider",
+                user.auth_provider
+            ),
+        }
+        .into_response();
+    }
+
     // 3. Verify current password
     let is_valid = match password::verify_password(&form.current_password, &user.password_hash) {
         Ok(valid) => valid,
@@ -804,6 +1073,112 @@ async fn fetch_and_render_ssh_keys(state: &AppState, username: &str) -> Response
     .into_response()
 }
 
+/// Create an API token from the profile page
+///
+/// POST /profile/api-tokens
+///
+/// Returns the updated API tokens list HTML fragment for HTMX swap, with the
+/// new token shown once at the top.
+pub async fn create_api_token_handler(
+    State(state): State<AppState>,
+    auth: AuthenticatedUserFromCookie,
+    axum::Form(form): axum::Form<CreateApiTokenForm>,
+) -> impl IntoResponse {
+    let scopes: Vec<TokenScope> = [
+        (form.scope_read_only.is_some(), TokenScope::ReadOnly),
+        (form.scope_lab_operate.is_some(), TokenScope::LabOperate),
+        (form.scope_image_admin.is_some(), TokenScope::ImageAdmin),
+    ]
+    .into_iter()
+    .filter_map(|(checked, scope)| checked.then_some(scope))
+    .collect();
+
+    match api_token::create_api_token(
+        form.name.trim(),
+        &scopes,
+        form.expires_in_days,
+        &auth.username,
+        &state,
+    )
+    .await
+    {
+        Ok(response) => {
+            api_tokens_list(&state, &auth.username, Some(response.api_token), None).await
+        }
+        Err(e) => api_tokens_list(&state, &auth.username, None, Some(e.to_string())).await,
+    }
+    .into_response()
+}
+
+/// Revoke an API token from the profile page
+///
+/// DELETE /profile/api-tokens/{name}
+///
+/// Returns the updated API tokens list HTML fragment for HTMX swap.
+pub async fn revoke_api_token_handler(
+    State(state): State<AppState>,
+    auth: AuthenticatedUserFromCookie,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let token_error = api_token::revoke_api_token(&name, &auth.username, &state)
+        .await
+        .err()
+        .map(|e| e.to_string());
+
+    api_tokens_list(&state, &auth.username, None, token_error)
+        .await
+        .into_response()
+}
+
+/// Build the API tokens list partial for a user
+async fn api_tokens_list(
+    state: &AppState,
+    username: &str,
+    new_token: Option<String>,
+    token_error: Option<String>,
+) -> ApiTokensListTemplate {
+    let (tokens, token_error) = match api_token::list_api_tokens(username, state).await {
+        Ok(response) => (
+            response.tokens.into_iter().map(api_token_summary).collect(),
+            token_error,
+        ),
+        Err(e) => {
+            tracing::error!("Failed to list API tokens for user '{}': {:?}", username, e);
+            (
+                vec![],
+                token_error.or_else(|| Some("Failed to load API tokens".to_string())),
+            )
+        }
+    };
+
+    ApiTokensListTemplate {
+        tokens,
+        new_token,
+        token_error,
+    }
+}
+
+/// Convert token info to its profile page display form
+fn api_token_summary(info: ApiTokenInfo) -> ApiTokenSummary {
+    let format = |seconds: i64| {
+        Timestamp::from_second(seconds)
+            .map(format_date_simple)
+            .unwrap_or_else(|_| "Unknown".to_string())
+    };
+
+    ApiTokenSummary {
+        expired: info.expires_at <= Timestamp::now().as_second(),
+        expires_at_formatted: format(info.expires_at),
+        last_used_at_formatted: info
+            .last_used_at
+            .map(format)
+            .unwrap_or_else(|| "never".to_string()),
+        name: info.name,
+        prefix: info.prefix,
+        scopes: info.scopes,
+    }
+}
+
 // ============================================================================
 // Lab Management Handlers
 // ============================================================================
@@ -1129,6 +1504,105 @@ pub async fn get_labs_html(
     }
 }
 
+/// Form payload for sharing a lab from the lab detail page
+#[derive(Deserialize)]
+pub struct ShareLabForm {
+    /// Username, or `team:<name>` for a team
+    pub grantee: String,
+    pub role: LabRole,
+}
+
+/// Render the lab shares partial, with an optional error message
+async fn render_lab_shares(
+    state: &AppState,
+    lab_id: &str,
+    share_error: Option<String>,
+) -> Response {
+    match db::list_lab_shares(&state.db, lab_id).await {
+        Ok(shares) => LabSharesFragment {
+            lab_id: lab_id.to_string(),
+            shares,
+            share_error,
+        }
+        .into_response(),
+        Err(e) => {
+            tracing::error!("Failed to list shares for lab '{}': {:?}", lab_id, e);
+            ErrorTemplate {
+                message: "Failed to load lab shares".to_string(),
+            }
+            .into_response()
+        }
+    }
+}
+
+/// Share a lab with a user or team (web UI handler, cookie auth)
+///
+/// POST /labs/{lab_id}/shares
+#[tracing::instrument(skip(state, form), fields(%lab_id))]
+pub async fn lab_share_add_handler(
+    Path(lab_id): Path<String>,
+    auth: AuthenticatedUserFromCookie,
+    State(state): State<AppState>,
+    Form(form): Form<ShareLabForm>,
+) -> Result<Response, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
+
+    let (username, team) = split_grantee(form.grantee.trim());
+    let request = ShareLabRequest {
+        lab_id: lab_id.clone(),
+        username,
+        team,
+        role: form.role,
+        token: String::new(),
+    };
+    let share_error = share::share_lab(&request, &state)
+        .await
+        .err()
+        .map(|e| format!("Failed to share lab: {e}"));
+
+    Ok(render_lab_shares(&state, &lab_id, share_error).await)
+}
+
+/// Remove a lab share (web UI handler, cookie auth)
+///
+/// DELETE /labs/{lab_id}/shares/{grantee}
+#[tracing::instrument(skip(state), fields(%lab_id, %grantee))]
+pub async fn lab_share_remove_handler(
+    Path((lab_id, grantee)): Path<(String, String)>,
+    auth: AuthenticatedUserFromCookie,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
+
+    let (username, team) = split_grantee(&grantee);
+    let request = UnshareLabRequest {
+        lab_id: lab_id.clone(),
+        username,
+        team,
+        token: String::new(),
+    };
+    let share_error = share::unshare_lab(&request, &state)
+        .await
+        .err()
+        .map(|e| format!("Failed to remove share: {e}"));
+
+    Ok(render_lab_shares(&state, &lab_id, share_error).await)
+}
+
 /// Stop all nodes in a lab (web UI handler, cookie auth)
 ///
 /// POST /labs/{lab_id}/stop
@@ -1138,12 +1612,14 @@ pub async fn lab_stop_handler(
     State(state): State<AppState>,
     Path(lab_id): Path<String>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = down::shutdown_lab_nodes(&lab_id, None, &state)
         .await
@@ -1161,12 +1637,14 @@ pub async fn lab_start_handler(
     State(state): State<AppState>,
     Path(lab_id): Path<String>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = resume::start_lab_nodes(&lab_id, None, &state)
         .await
@@ -1184,12 +1662,14 @@ pub async fn node_stop_handler(
     State(state): State<AppState>,
     Path((lab_id, node_name)): Path<(String, String)>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = down::shutdown_lab_nodes(&lab_id, Some(&node_name), &state)
         .await
@@ -1207,12 +1687,14 @@ pub async fn node_start_handler(
     State(state): State<AppState>,
     Path((lab_id, node_name)): Path<(String, String)>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = resume::start_lab_nodes(&lab_id, Some(&node_name), &state)
         .await
@@ -1230,12 +1712,14 @@ pub async fn node_redeploy_handler(
     State(state): State<AppState>,
     Path((lab_id, node_name)): Path<(String, String)>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     // Read the saved manifest from the lab directory
     let manifest_path = format!(
@@ -1323,6 +1807,19 @@ pub async fn lab_detail_handler(
             let inactive_device_count = response.inactive_devices.len();
             let link_count = response.links.len();
             let bridge_count = response.bridges.len();
+            let topology_svg = lab_topology_svg(&lab_id, &response);
+            let role = db::get_lab_role(&state.db, &lab_id, &auth.username)
+                .await
+                .unwrap_or(None);
+            let auth_ctx = AuthContext::new(auth.username.clone(), auth.is_admin);
+            let can_manage = auth_ctx.has_lab_role(role, LabRole::Owner);
+            let shares = if can_manage {
+                db::list_lab_shares(&state.db, &lab_id)
+                    .await
+                    .unwrap_or_default()
+            } else {
+                vec![]
+            };
             LabDetailTemplate {
                 username: auth.username.clone(),
                 is_admin: auth.is_admin,
@@ -1338,6 +1835,11 @@ pub async fn lab_detail_handler(
                 link_count,
                 bridges: response.bridges,
                 bridge_count,
+                topology_svg,
+                can_operate: auth_ctx.has_lab_role(role, LabRole::Operator),
+                can_manage,
+                shares,
+                share_error: None,
             }
             .into_response()
         }
@@ -1403,9 +1905,9 @@ pub async fn node_detail_handler(
     auth: AuthenticatedUserFromCookie,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // Validate ownership
-    let owner = match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(o) => o,
+    // Validate access: any role on the lab, or admin
+    let role = match db::get_lab_role(&state.db, &lab_id, &auth.username).await {
+        Ok(role) => role,
         Err(_) => {
             return Error404Template {
                 username: auth.username,
@@ -1416,7 +1918,7 @@ pub async fn node_detail_handler(
             .into_response();
         }
     };
-    if !auth.is_admin && auth.username != owner {
+    if !auth.is_admin && role.is_none() {
         return Error403Template {
             username: auth.username,
             is_admin: auth.is_admin,
@@ -2121,6 +2623,47 @@ pub struct ImageSummary {
     pub default: bool,
 }
 
+/// Helper struct for displaying the storage usage of an image version
+#[derive(Debug, Clone)]
+pub struct ImageUsageSummary {
+    pub model: String,
+    pub kind: String,
+    pub version: String,
+    pub default: bool,
+    pub size: String,
+    pub node_count: usize,
+    pub labs: String,
+    pub last_used: String,
+}
+
+/// Helper struct for displaying a storage pool file no node owns
+#[derive(Debug, Clone)]
+pub struct OrphanedDiskSummary {
+    pub path: String,
+    pub size: String,
+}
+
+/// Convert image version usage to its admin page display form
+fn image_usage_summary(usage: ImageVersionUsage) -> ImageUsageSummary {
+    ImageUsageSummary {
+        model: usage.model.to_string(),
+        kind: usage.kind.to_string(),
+        version: usage.version,
+        default: usage.default,
+        size: usage
+            .size_bytes
+            .map(format_bytes)
+            .unwrap_or_else(|| "missing".to_string()),
+        node_count: usage.node_count,
+        labs: usage.labs.join(", "),
+        last_used: usage
+            .last_used_at
+            .and_then(|seconds| Timestamp::from_second(seconds).ok())
+            .map(format_date_simple)
+            .unwrap_or_else(|| "-".to_string()),
+    }
+}
+
 /// Admin handler to list all image configurations
 pub async fn admin_images_list_handler(
     State(state): State<AppState>,
@@ -2164,11 +2707,46 @@ pub async fn admin_images_list_handler(
     let mut summaries: Vec<ImageSummary> = best_by_model.into_values().collect();
     summaries.sort_by(|a, b| a.model.cmp(&b.model));
 
+    // Storage usage is informational, so a failure shouldn't hide the list
+    let (usage, orphaned_disks, total_size, usage_error) =
+        match image_usage::image_usage(&state).await {
+            Ok(response) => (
+                response
+                    .images
+                    .into_iter()
+                    .map(image_usage_summary)
+                    .collect(),
+                response
+                    .orphaned_disks
+                    .into_iter()
+                    .map(|disk| OrphanedDiskSummary {
+                        path: disk.path,
+                        size: format_bytes(disk.size_bytes),
+                    })
+                    .collect(),
+                format_bytes(response.total_size_bytes),
+                None,
+            ),
+            Err(e) => {
+                tracing::error!("Failed to load image usage: {:?}", e);
+                (
+                    vec![],
+                    vec![],
+                    "-".to_string(),
+                    Some("Failed to load image storage usage".to_string()),
+                )
+            }
+        };
+
     let template = crate::templates::AdminImagesListTemplate {
         username: _admin.username.clone(),
         is_admin: true,
         active_page: "admin_images".to_string(),
         configs: summaries,
+        usage,
+        orphaned_disks,
+        total_size,
+        usage_error,
     };
 
     Ok(template)
@@ -2579,8 +3157,9 @@ pub async fn admin_image_update_handler(
         dedicated_management_interface,
         management_interface: config.management_interface, // Keep original (read-only)
         reserved_interface_count: form.reserved_interface_count,
-        default: form_default,       // Use value from form checkbox
-        boot_mode: config.boot_mode, // Keep original
+        default: form_default,             // Use value from form checkbox
+        boot_mode: config.boot_mode,       // Keep original
+        image_sha256: config.image_sha256, // Keep original
     };
 
     // Update in database
@@ -2665,6 +3244,7 @@ pub struct UploadFields {
     pub version: String,
     pub file_data: Vec<u8>,
     pub default: bool,
+    pub checksum: Option<String>,
 }
 
 /// Raw string fields extracted from multipart before validation.
@@ -2673,6 +3253,7 @@ pub struct RawUploadFields {
     pub version: Option<String>,
     pub file_data: Option<Vec<u8>>,
     pub default: Option<String>,
+    pub checksum: Option<String>,
 }
 
 /// Validate raw upload fields and convert to typed UploadFields.
@@ -2692,23 +3273,35 @@ pub fn validate_upload_fields(raw: RawUploadFields) -> Result<UploadFields, Stri
 
     let default = raw.default.as_deref() == Some("on");
 
+    let checksum = raw
+        .checksum
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if let Some(checksum) = &checksum {
+        checksum
+            .parse::<shared::util::Checksum>()
+            .map_err(|e| format!("Invalid checksum: {}", e))?;
+    }
+
     Ok(UploadFields {
         model: parsed_model,
         version: version_str,
         file_data: data,
         default,
+        checksum,
     })
 }
 
 /// Parse multipart form fields for image upload.
 ///
-/// Extracts model, version, file, and default fields from the multipart stream.
+/// Extracts model, version, file, default and checksum fields from the multipart stream.
 /// Returns an error if any required field is missing or invalid.
 pub async fn parse_upload_fields(mut multipart: Multipart) -> Result<UploadFields, String> {
     let mut model: Option<String> = None;
     let mut version: Option<String> = None;
     let mut file_data: Option<Vec<u8>> = None;
     let mut default: Option<String> = None;
+    let mut checksum: Option<String> = None;
 
     while let Some(field) = multipart
         .next_field()
@@ -2747,6 +3340,13 @@ pub async fn parse_upload_fields(mut multipart: Multipart) -> Result<UploadField
                     .map_err(|e| format!("Failed to read default field: {}", e))?;
                 default = Some(text);
             }
+            "checksum" => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| format!("Failed to read checksum field: {}", e))?;
+                checksum = Some(text);
+            }
             _ => {
                 tracing::debug!("Ignoring unknown upload field: {}", name);
             }
@@ -2758,6 +3358,7 @@ pub async fn parse_upload_fields(mut multipart: Multipart) -> Result<UploadField
         version,
         file_data,
         default,
+        checksum,
     })
 }
 
@@ -2819,6 +3420,9 @@ pub async fn admin_image_upload_handler(
         version: fields.version,
         src: temp_path.clone(),
         default: fields.default,
+        checksum: fields.checksum,
+        compress: false,
+        sparsify: false,
     };
 
     let (progress_tx, _progress_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -2868,17 +3472,25 @@ pub struct NodeActionPayload {
     pub node_name: Option<String>,
 }
 
-/// Helper to verify lab ownership. Returns Ok(()) or an ApiError.
-async fn require_lab_access(
-    auth: &AuthenticatedUser,
+/// Helper to verify the user has at least the required role on a lab.
+/// Admins pass for every lab. Returns Ok(()) or an ApiError.
+async fn require_lab_role(
+    username: &str,
+    is_admin: bool,
     lab_id: &str,
+    required: LabRole,
     state: &AppState,
 ) -> Result<(), ApiError> {
-    let owner = db::get_lab_owner_username(&state.db, lab_id)
+    let role = db::get_lab_role(&state.db, lab_id, username)
         .await
         .map_err(|_| ApiError::not_found("Lab", format!("Lab not found: {lab_id}")))?;
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden("You do not have access to this lab"));
+    if !AuthContext::new(username.to_string(), is_admin).has_lab_role(role, required) {
+        return Err(match role {
+            Some(role) => ApiError::forbidden(format!(
+                "Your {role} role on this lab does not allow this action"
+            )),
+            None => ApiError::forbidden("You do not have access to this lab"),
+        });
     }
     Ok(())
 }
@@ -2924,7 +3536,14 @@ pub async fn delete_lab_json(
     State(state): State<AppState>,
     Path(lab_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    require_lab_access(&auth, &lab_id, &state).await?;
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
 
     let request = DestroyRequest {
         lab_id,
@@ -2952,7 +3571,14 @@ pub async fn down_lab_json(
     Path(lab_id): Path<String>,
     Json(payload): Json<NodeActionPayload>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    require_lab_access(&auth, &lab_id, &state).await?;
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = down::shutdown_lab_nodes(&lab_id, payload.node_name.as_deref(), &state)
         .await
@@ -2970,7 +3596,14 @@ pub async fn resume_lab_json(
     Path(lab_id): Path<String>,
     Json(payload): Json<NodeActionPayload>,
 ) -> Result<Json<LabNodeActionResponse>, ApiError> {
-    require_lab_access(&auth, &lab_id, &state).await?;
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let response = resume::start_lab_nodes(&lab_id, payload.node_name.as_deref(), &state)
         .await
@@ -2988,7 +3621,14 @@ pub async fn redeploy_node_json(
     Path((lab_id, node_name)): Path<(String, String)>,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<impl IntoResponse, ApiError> {
-    require_lab_access(&auth, &lab_id, &state).await?;
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let manifest = payload
         .get("manifest")
@@ -3014,6 +3654,44 @@ pub async fn redeploy_node_json(
     Ok(sse::Sse::new(json_progress_stream(progress_rx, result_rx)))
 }
 
+/// Request body of a node commit
+#[derive(Deserialize)]
+pub struct CommitNodeBody {
+    pub version: String,
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// Capture a lab node as a new image version (admin only, SSE streaming)
+///
+/// POST /api/v1/labs/{lab_id}/nodes/{node_name}/commit
+pub async fn commit_node_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((lab_id, node_name)): Path<(String, String)>,
+    Json(payload): Json<CommitNodeBody>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let request = NodeCommitRequest {
+        lab_id,
+        node_name,
+        version: payload.version,
+        default: payload.default,
+    };
+
+    let (progress_tx, progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (result_tx, result_rx) = tokio::sync::oneshot::channel();
+    let progress = ProgressSender::new(progress_tx);
+
+    tokio::spawn(async move {
+        let result = commit::commit_node(request, &state, progress).await;
+        let _ = result_tx.send(result);
+    });
+
+    Ok(sse::Sse::new(json_progress_stream(progress_rx, result_rx)))
+}
+
 /// Force-clean a lab (admin only)
 ///
 /// POST /api/v1/admin/tools/labs/clean/{lab_id}
@@ -3134,6 +3812,9 @@ pub async fn upload_image_multipart(
         version: fields.version,
         src: temp_path.clone(),
         default: fields.default,
+        checksum: fields.checksum,
+        compress: false,
+        sparsify: false,
     };
 
     let (progress_tx, _progress_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -3153,6 +3834,224 @@ pub async fn upload_image_multipart(
     })?))
 }
 
+/// Query parameters of a chunk upload
+#[derive(Deserialize)]
+pub struct UploadChunkQuery {
+    pub offset: u64,
+}
+
+/// Start or resume a chunked image upload
+///
+/// POST /api/v1/images/uploads
+pub async fn start_upload_json(
+    auth: AuthenticatedUser,
+    Json(request): Json<StartUploadRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = upload::start_upload(request)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Upload a chunk of an image, importing it after the final chunk
+///
+/// The body is the raw chunk data, with its SHA-256 in the
+/// `X-Chunk-Sha256` header.
+///
+/// PUT /api/v1/images/uploads/{upload_id}?offset=<bytes>
+pub async fn upload_chunk_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(upload_id): Path<String>,
+    Query(query): Query<UploadChunkQuery>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let sha256 = headers
+        .get(IMAGE_UPLOAD_CHUNK_SHA256_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            ApiError::bad_request(format!("Missing {IMAGE_UPLOAD_CHUNK_SHA256_HEADER} header"))
+        })?;
+
+    let (progress_tx, _progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let progress = ProgressSender::new(progress_tx);
+
+    let response = upload::upload_chunk(&upload_id, query.offset, sha256, &body, &state, progress)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Cancel a chunked image upload
+///
+/// DELETE /api/v1/images/uploads/{upload_id}
+pub async fn cancel_upload_json(
+    auth: AuthenticatedUser,
+    Path(upload_id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = upload::cancel_upload(&upload_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// List custom node models
+///
+/// GET /api/v1/images/models
+pub async fn list_custom_models_json(
+    _auth: AuthenticatedUser,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let response = custom_model::list_models().map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Add, or replace, a custom node model
+///
+/// POST /api/v1/images/models
+pub async fn add_custom_model_json(
+    auth: AuthenticatedUser,
+    Json(request): Json<AddCustomModelRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    // Failures here are problems with the submitted definition
+    let response =
+        custom_model::add_model(request).map_err(|e| ApiError::bad_request(format!("{e:#}")))?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Delete a custom node model
+///
+/// DELETE /api/v1/images/models/{name}
+pub async fn delete_custom_model_json(
+    auth: AuthenticatedUser,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response =
+        custom_model::delete_model(DeleteCustomModelRequest { name }).map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Report image storage usage
+///
+/// GET /api/v1/images/usage
+pub async fn image_usage_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = image_usage::image_usage(&state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Remove image versions that no node uses
+///
+/// POST /api/v1/images/prune
+pub async fn prune_images_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<PruneImagesRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = image_usage::prune_images(request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Verify and store the credentials for a container registry
+///
+/// POST /api/v1/admin/registries
+pub async fn registry_login_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(request): Json<RegistryLoginRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = registry::registry_login(request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// List the registries with stored credentials
+///
+/// GET /api/v1/admin/registries
+pub async fn list_registries_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = registry::list_registries(&state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
+/// Remove the stored credentials of a container registry
+///
+/// DELETE /api/v1/admin/registries/{registry}
+pub async fn registry_logout_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(registry): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let response = registry::registry_logout(RegistryLogoutRequest { registry }, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
 /// Delete an image
 ///
 /// DELETE /api/v1/images/{model}/{version}
@@ -3203,6 +4102,31 @@ pub async fn set_default_image_json(
     })?))
 }
 
+/// Verify an image against the digest recorded on import
+///
+/// POST /api/v1/images/{model}/{version}/verify
+pub async fn verify_image_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((model, version)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin_auth(&auth)?;
+
+    let model: NodeModel = model
+        .parse()
+        .map_err(|_| ApiError::bad_request(format!("Invalid model: {model}")))?;
+
+    let request = VerifyImageRequest { model, version };
+
+    let response = import::verify_image(request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(serde_json::to_value(&response).map_err(|e| {
+        ApiError::internal(format!("Failed to serialize response: {e}"))
+    })?))
+}
+
 /// Scan for images
 ///
 /// POST /api/v1/admin/tools/images/scan
@@ -3414,22 +4338,477 @@ pub async fn get_user_info_json(
     }))
 }
 
-/// Handler to return the nodes table fragment for HTMX polling
-///
-/// GET /labs/{lab_id}/nodes
-pub async fn lab_nodes_handler(
-    Path(lab_id): Path<String>,
-    auth: AuthenticatedUserFromCookie,
-    State(state): State<AppState>,
-) -> impl IntoResponse {
-    let request = InspectRequest {
-        lab_id: lab_id.clone(),
-        username: auth.username.clone(),
-    };
+/// Payload for sharing a lab over the REST API
+#[derive(Deserialize)]
+pub struct ShareLabPayload {
+    pub username: Option<String>,
+    pub team: Option<String>,
+    pub role: LabRole,
+}
 
-    match inspect::inspect_lab(request, &state).await {
-        Ok(response) => {
-            let device_count = response.devices.len();
+/// Payload for starting or stopping a port mirror over the REST API
+#[derive(Deserialize)]
+pub struct LinkMirrorPayload {
+    pub source: String,
+    pub destination: String,
+    #[serde(default)]
+    pub direction: MirrorDirection,
+}
+
+/// Payload for replacing a link filter over the REST API
+#[derive(Deserialize)]
+pub struct LinkFilterPayload {
+    pub link: String,
+    #[serde(default)]
+    pub filter: LinkFilter,
+}
+
+/// Query parameters for verifying the cabling of a lab
+#[derive(Deserialize)]
+pub struct VerifyCablingQuery {
+    pub wait_secs: Option<u64>,
+}
+
+/// Payload for creating a team over the REST API
+#[derive(Deserialize)]
+pub struct CreateTeamPayload {
+    pub name: String,
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Payload for updating team members over the REST API
+#[derive(Deserialize)]
+pub struct TeamMembersPayload {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Helper to verify the user owns a team or is an admin
+async fn require_team_owner(
+    auth: &AuthenticatedUser,
+    name: &str,
+    state: &AppState,
+) -> Result<(), ApiError> {
+    let owner = db::get_team_owner_username(&state.db, name)
+        .await
+        .map_err(|_| ApiError::not_found("Team", format!("Team not found: {name}")))?;
+    if !auth.is_admin && auth.username != owner {
+        return Err(ApiError::forbidden(
+            "Only the team owner or an administrator can modify this team",
+        ));
+    }
+    Ok(())
+}
+
+/// List the shares of a lab
+///
+/// GET /api/v1/labs/{lab_id}/shares
+pub async fn list_lab_shares_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+) -> Result<Json<ListLabSharesResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
+
+    let response = share::list_lab_shares(&lab_id, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// List the DHCP leases of a lab
+///
+/// GET /api/v1/labs/{lab_id}/leases
+pub async fn lab_leases_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+) -> Result<Json<LabLeasesResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
+
+    let response = leases::lab_leases(&lab_id, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Read the traffic counters of a lab's links
+///
+/// GET /api/v1/labs/{lab_id}/links/stats
+pub async fn link_stats_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+) -> Result<Json<LinkStatsResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
+
+    let response = link_stats::link_stats(&lab_id, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Start copying the traffic of a link interface to a mirror port
+///
+/// POST /api/v1/labs/{lab_id}/links/mirrors
+pub async fn link_mirror_add_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+    Json(payload): Json<LinkMirrorPayload>,
+) -> Result<Json<LinkMirrorResponse>, ApiError> {
+    link_mirror_json(auth, state, lab_id, payload, MirrorAction::Add).await
+}
+
+/// Stop copying the traffic of a link interface to a mirror port
+///
+/// DELETE /api/v1/labs/{lab_id}/links/mirrors
+pub async fn link_mirror_remove_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+    Json(payload): Json<LinkMirrorPayload>,
+) -> Result<Json<LinkMirrorResponse>, ApiError> {
+    link_mirror_json(auth, state, lab_id, payload, MirrorAction::Remove).await
+}
+
+async fn link_mirror_json(
+    auth: AuthenticatedUser,
+    state: AppState,
+    lab_id: String,
+    payload: LinkMirrorPayload,
+    action: MirrorAction,
+) -> Result<Json<LinkMirrorResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
+
+    let request = LinkMirrorRequest {
+        lab_id,
+        source: payload.source,
+        destination: payload.destination,
+        direction: payload.direction,
+        token: String::new(),
+    };
+    let response = match action {
+        MirrorAction::Add => mirror::mirror_add(&request, &state).await,
+        MirrorAction::Remove => mirror::mirror_remove(&request, &state).await,
+    }
+    .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Replace the layer 2 filter of a running link
+///
+/// POST /api/v1/labs/{lab_id}/links/filter
+pub async fn link_filter_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+    Json(payload): Json<LinkFilterPayload>,
+) -> Result<Json<LinkFilterResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
+
+    let request = LinkFilterRequest {
+        lab_id,
+        link: payload.link,
+        filter: payload.filter,
+        token: String::new(),
+    };
+    let response = link_filter::update_link_filter(&request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Compare the LLDP/CDP neighbours a lab's nodes advertise with the manifest
+///
+/// POST /api/v1/labs/{lab_id}/cabling/verify
+pub async fn verify_cabling_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+    Query(query): Query<VerifyCablingQuery>,
+) -> Result<Json<VerifyCablingResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
+
+    let request = VerifyCablingRequest {
+        lab_id,
+        wait_secs: query.wait_secs,
+        token: String::new(),
+    };
+    let response = cabling::verify_cabling(&request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Share a lab with a user or a team (lab owner or admin)
+///
+/// POST /api/v1/labs/{lab_id}/shares
+pub async fn share_lab_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(lab_id): Path<String>,
+    Json(payload): Json<ShareLabPayload>,
+) -> Result<Json<ListLabSharesResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
+
+    let request = ShareLabRequest {
+        lab_id,
+        username: payload.username,
+        team: payload.team,
+        role: payload.role,
+        token: String::new(),
+    };
+    let response = share::share_lab(&request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Remove a lab share (lab owner or admin)
+///
+/// DELETE /api/v1/labs/{lab_id}/shares/{grantee}
+///
+/// `grantee` is a username, or `team:<name>` for a team.
+pub async fn unshare_lab_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path((lab_id, grantee)): Path<(String, String)>,
+) -> Result<Json<ListLabSharesResponse>, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
+
+    let (username, team) = split_grantee(&grantee);
+    let request = UnshareLabRequest {
+        lab_id,
+        username,
+        team,
+        token: String::new(),
+    };
+    let response = share::unshare_lab(&request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Create a team owned by the caller
+///
+/// POST /api/v1/teams
+pub async fn create_team_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTeamPayload>,
+) -> Result<Json<TeamInfo>, ApiError> {
+    let team = share::create_team(&payload.name, &payload.members, &auth.username, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(team))
+}
+
+/// List teams
+///
+/// GET /api/v1/teams
+pub async fn list_teams_json(
+    _auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<ListTeamsResponse>, ApiError> {
+    let teams = db::list_teams(&state.db).await.map_err(ApiError::from)?;
+
+    Ok(Json(ListTeamsResponse { teams }))
+}
+
+/// Add and remove team members (team owner or admin)
+///
+/// POST /api/v1/teams/{name}/members
+pub async fn update_team_members_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<TeamMembersPayload>,
+) -> Result<Json<TeamInfo>, ApiError> {
+    require_team_owner(&auth, &name, &state).await?;
+
+    let request = UpdateTeamMembersRequest {
+        name,
+        add: payload.add,
+        remove: payload.remove,
+        token: String::new(),
+    };
+    let team = share::update_team_members(&request, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(team))
+}
+
+/// Delete a team (team owner or admin)
+///
+/// DELETE /api/v1/teams/{name}
+pub async fn delete_team_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<DeleteTeamResponse>, ApiError> {
+    require_team_owner(&auth, &name, &state).await?;
+
+    share::delete_team(&name, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(DeleteTeamResponse {
+        success: true,
+        name,
+    }))
+}
+
+/// Payload for creating an API token over the REST API
+#[derive(Deserialize)]
+pub struct CreateApiTokenPayload {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    #[serde(default = "default_api_token_expiry_days")]
+    pub expires_in_days: u32,
+}
+
+fn default_api_token_expiry_days() -> u32 {
+    API_TOKEN_DEFAULT_EXPIRY_DAYS
+}
+
+/// Create a personal API token
+///
+/// POST /api/v1/tokens
+///
+/// The response holds the token itself, it cannot be retrieved again.
+pub async fn create_api_token_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateApiTokenPayload>,
+) -> Result<Json<CreateApiTokenResponse>, ApiError> {
+    let response = api_token::create_api_token(
+        &payload.name,
+        &payload.scopes,
+        payload.expires_in_days,
+        &auth.username,
+        &state,
+    )
+    .await
+    .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok(Json(response))
+}
+
+/// List the authenticated user's API tokens
+///
+/// GET /api/v1/tokens
+pub async fn list_api_tokens_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> Result<Json<ListApiTokensResponse>, ApiError> {
+    let response = api_token::list_api_tokens(&auth.username, &state)
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(Json(response))
+}
+
+/// Revoke one of the authenticated user's API tokens
+///
+/// DELETE /api/v1/tokens/{name}
+pub async fn revoke_api_token_json(
+    auth: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<RevokeApiTokenResponse>, ApiError> {
+    let response = api_token::revoke_api_token(&name, &auth.username, &state)
+        .await
+        .map_err(|_| ApiError::not_found("API token", format!("API token '{}' not found", name)))?;
+
+    Ok(Json(response))
+}
+
+/// Handler to return the nodes table fragment for HTMX polling
+///
+/// GET /labs/{lab_id}/nodes
+pub async fn lab_nodes_handler(
+    Path(lab_id): Path<String>,
+    auth: AuthenticatedUserFromCookie,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let request = InspectRequest {
+        lab_id: lab_id.clone(),
+        username: auth.username.clone(),
+    };
+
+    match inspect::inspect_lab(request, &state).await {
+        Ok(response) => {
+            let device_count = response.devices.len();
             NodesTableFragment {
                 lab_id,
                 devices: response.devices,
@@ -3444,6 +4823,147 @@ pub async fn lab_nodes_handler(
     }
 }
 
+/// Handler to return the topology diagram fragment for HTMX polling
+///
+/// GET /labs/{lab_id}/topology
+pub async fn lab_topology_handler(
+    Path(lab_id): Path<String>,
+    auth: AuthenticatedUserFromCookie,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let request = InspectRequest {
+        lab_id: lab_id.clone(),
+        username: auth.username.clone(),
+    };
+
+    match inspect::inspect_lab(request, &state).await {
+        Ok(response) => LabTopologyFragment {
+            topology_svg: lab_topology_svg(&lab_id, &response),
+        }
+        .into_response(),
+        Err(e) => ErrorTemplate {
+            message: format!("Failed to load topology: {}", e),
+        }
+        .into_response(),
+    }
+}
+
+/// Helper struct for displaying a DHCP lease on the lab detail page
+#[derive(Debug, Clone)]
+pub struct LeaseSummary {
+    pub mac_address: String,
+    pub ipv4_address: String,
+    pub hostname: String,
+    pub node_name: Option<String>,
+    pub expires_formatted: String,
+}
+
+/// Convert a lease to its lab detail page display form
+fn lease_summary(lease: LabLease) -> LeaseSummary {
+    let expires_formatted = match lease.expiry {
+        0 => "never".to_string(),
+        expiry => i64::try_from(expiry)
+            .ok()
+            .and_then(|seconds| Timestamp::from_second(seconds).ok())
+            .map(|ts| ts.strftime("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "Unknown".to_string()),
+    };
+
+    LeaseSummary {
+        mac_address: lease.mac_address,
+        ipv4_address: lease.ipv4_address,
+        hostname: lease.hostname.unwrap_or_else(|| "-".to_string()),
+        node_name: lease.node_name,
+        expires_formatted,
+    }
+}
+
+/// Handler to return the DHCP leases fragment for HTMX polling
+///
+/// GET /labs/{lab_id}/leases
+pub async fn lab_leases_handler(
+    Path(lab_id): Path<String>,
+    auth: AuthenticatedUserFromCookie,
+    State(state): State<AppState>,
+) -> Result<Response, ApiError> {
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
+
+    let (leases, lease_error) = match leases::lab_leases(&lab_id, &state).await {
+        Ok(response) => (
+            response.leases.into_iter().map(lease_summary).collect(),
+            None,
+        ),
+        Err(e) => (vec![], Some(format!("Failed to load leases: {e}"))),
+    };
+
+    Ok(LabLeasesFragment {
+        lab_id,
+        leases,
+        lease_error,
+    }
+    .into_response())
+}
+
+/// Render the lab topology as an SVG coloured by node state.
+///
+/// Nodes link to their detail page. Links are drawn dashed unless both ends
+/// are running.
+fn lab_topology_svg(lab_id: &str, response: &InspectResponse) -> String {
+    let running = |name: &str| {
+        response
+            .devices
+            .iter()
+            .any(|device| device.name == name && device.state == NodeState::Running)
+    };
+
+    let diagram = Diagram {
+        name: response.lab_info.name.clone(),
+        nodes: response
+            .devices
+            .iter()
+            .map(|device| DiagramNode {
+                name: device.name.clone(),
+                model: device.model,
+                state: Some(device.state),
+                href: Some(format!("/labs/{}/nodes/{}", lab_id, device.name)),
+            })
+            .collect(),
+        links: response
+            .links
+            .iter()
+            .map(|link| DiagramLink {
+                node_a: link.node_a_name.clone(),
+                int_a: link.int_a.clone(),
+                node_b: link.node_b_name.clone(),
+                int_b: link.int_b.clone(),
+                impairment: link.impairment.clone(),
+                up: Some(running(&link.node_a_name) && running(&link.node_b_name)),
+            })
+            .collect(),
+        bridges: response
+            .bridges
+            .iter()
+            .map(|bridge| DiagramBridge {
+                name: bridge.bridge_name.clone(),
+                members: bridge
+                    .connected_nodes
+                    .iter()
+                    .map(|node| (node.clone(), None))
+                    .collect(),
+            })
+            .collect(),
+    };
+
+    diagram.to_svg()
+}
+
 /// Download lab files as a zip archive
 ///
 /// GET /labs/{lab_id}/download
@@ -3460,15 +4980,14 @@ pub async fn lab_download_handler(
         .await
         .map_err(|_| ApiError::not_found("lab", format!("Lab not found: {lab_id}")))?;
 
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to check ownership: {e}")))?;
-
-    if !auth.is_admin && auth.username != owner {
-        return Err(ApiError::forbidden(
-            "Permission denied: you do not own this lab",
-        ));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Viewer,
+        &state,
+    )
+    .await?;
 
     let lab_dir = format!("{}/{}", shared::konst::SHERPA_LABS_PATH, lab_id);
     let lab_info_path = format!("{}/{}", lab_dir, shared::konst::LAB_FILE_NAME);
@@ -3588,15 +5107,14 @@ pub async fn lab_destroy_post_handler(
         .await
         .map_err(|e| ApiError::not_found("lab", format!("Lab not found: {}", e)))?;
 
-    let owner = db::get_lab_owner_username(&state.db, &lab_id)
-        .await
-        .map_err(|e| ApiError::internal(format!("Failed to check ownership: {}", e)))?;
-
-    if owner != auth.username && !auth.is_admin {
-        return Err(ApiError::forbidden(
-            "Permission denied: you do not own this lab",
-        ));
-    }
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Owner,
+        &state,
+    )
+    .await?;
 
     let job_id = uuid::Uuid::new_v4().to_string();
     let request = DestroyRequest {
@@ -3853,7 +5371,14 @@ pub async fn update_impairment_json(
     Path((lab_id, link_index)): Path<(String, u16)>,
     Json(payload): Json<UpdateImpairmentRequest>,
 ) -> Result<Json<UpdateImpairmentResponse>, ApiError> {
-    require_lab_access(&auth, &lab_id, &state).await?;
+    require_lab_role(
+        &auth.username,
+        auth.is_admin,
+        &lab_id,
+        LabRole::Operator,
+        &state,
+    )
+    .await?;
 
     let request = UpdateImpairmentRequest {
         lab_id,
@@ -3903,6 +5428,7 @@ mod tests {
             version: version.map(|s| s.to_string()),
             file_data,
             default: default.map(|s| s.to_string()),
+            checksum: None,
         }
     }
 
@@ -4008,4 +5534,29 @@ mod tests {
         let fields = result.expect("should succeed");
         assert!(fields.default);
     }
+
+    #[test]
+    fn test_validate_upload_fields_checksum() {
+        let mut raw = raw_fields(Some("ubuntu_linux"), Some("24.04"), Some(vec![0x01]), None);
+        raw.checksum = Some("  ".to_string());
+        let fields = validate_upload_fields(raw).expect("should succeed");
+        assert!(fields.checksum.is_none());
+
+        let mut raw = raw_fields(Some("ubuntu_linux"), Some("24.04"), Some(vec![0x01]), None);
+        raw.checksum = Some(" md5:5d41402abc4b2a76b9719d911017c592 ".to_string());
+        let fields = validate_upload_fields(raw).expect("should succeed");
+        assert_eq!(
+            fields.checksum.as_deref(),
+            Some("md5:5d41402abc4b2a76b9719d911017c592")
+        );
+
+        let mut raw = raw_fields(Some("ubuntu_linux"), Some("24.04"), Some(vec![0x01]), None);
+        raw.checksum = Some("sha1:abc".to_string());
+        let err = validate_upload_fields(raw).unwrap_err();
+        assert!(
+            err.contains("Invalid checksum"),
+            "expected checksum error, got: {}",
+            err
+        );
+    }
 }
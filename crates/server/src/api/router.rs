@@ -1,33 +1,46 @@
+use axum::extract::DefaultBodyLimit;
 use axum::http::{StatusCode, header};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, post, put};
 use axum::{Router, extract::Path};
 use rust_embed::Embed;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::trace::TraceLayer;
 
+use shared::konst::IMAGE_UPLOAD_MAX_CHUNK_SIZE;
+
 use crate::daemon::state::AppState;
 
 use super::handlers::{
-    add_ssh_key_handler, admin_add_ssh_key_handler, admin_dashboard_handler,
+    add_custom_model_json, add_ssh_key_handler, admin_add_ssh_key_handler, admin_dashboard_handler,
     admin_delete_ssh_key_handler, admin_delete_user_handler, admin_image_detail_handler,
     admin_image_edit_page_handler, admin_image_update_handler, admin_image_upload_handler,
     admin_image_upload_page_handler, admin_image_versions_handler, admin_images_list_handler,
     admin_labs_list_handler, admin_tools_clean_handler, admin_tools_handler,
     admin_tools_scan_handler, admin_update_user_password_handler, admin_user_edit_handler,
-    api_spec_handler, change_password_json, clean_lab_json, create_lab_json, create_user_json,
-    dashboard_handler, delete_image_json, delete_lab_json, delete_ssh_key_handler,
-    delete_user_json, down_lab_json, download_image_json, get_certificate_handler, get_lab,
-    get_labs_html, get_labs_json, get_user_info_json, health_check, import_image_json,
+    api_spec_handler, auth_providers_json, cancel_upload_json, change_password_json,
+    clean_lab_json, commit_node_json, create_api_token_handler, create_api_token_json,
+    create_lab_json, create_team_json, create_user_json, dashboard_handler,
+    delete_custom_model_json, delete_image_json, delete_lab_json, delete_ssh_key_handler,
+    delete_team_json, delete_user_json, device_login_poll_json, device_login_start_json,
+    down_lab_json, download_image_json, get_certificate_handler, get_lab, get_labs_html,
+    get_labs_json, get_user_info_json, health_check, image_usage_json, import_image_json,
     job_page_handler, job_stream_handler, lab_create_page_handler, lab_create_post_handler,
     lab_destroy_button_handler, lab_destroy_confirm_handler, lab_destroy_post_handler,
-    lab_detail_handler, lab_download_handler, lab_nodes_handler, lab_start_handler,
-    lab_stop_handler, labs_list_page_handler, list_images_json, list_users_json, login,
-    login_form_handler, login_page_handler, logout_handler, node_detail_handler,
-    node_redeploy_handler, node_start_handler, node_stop_handler, openapi_handler, profile_handler,
-    pull_image_json, redeploy_node_json, resume_lab_json, scan_images_json, set_default_image_json,
-    show_image_json, signup_form_handler, signup_page_handler, update_impairment_json,
-    update_password_handler, upload_image_multipart,
+    lab_detail_handler, lab_download_handler, lab_leases_handler, lab_leases_json,
+    lab_nodes_handler, lab_share_add_handler, lab_share_remove_handler, lab_start_handler,
+    lab_stop_handler, lab_topology_handler, labs_list_page_handler, link_filter_json,
+    link_mirror_add_json, link_mirror_remove_json, link_stats_json, list_api_tokens_json,
+    list_custom_models_json, list_images_json, list_lab_shares_json, list_registries_json,
+    list_teams_json, list_users_json, login, login_form_handler, login_page_handler,
+    logout_handler, node_detail_handler, node_redeploy_handler, node_start_handler,
+    node_stop_handler, oidc_callback_handler, oidc_login_handler, openapi_handler, profile_handler,
+    prune_images_json, pull_image_json, redeploy_node_json, registry_login_json,
+    registry_logout_json, resume_lab_json, revoke_api_token_handler, revoke_api_token_json,
+    scan_images_json, set_default_image_json, share_lab_json, show_image_json, signup_form_handler,
+    signup_page_handler, start_upload_json, unshare_lab_json, update_impairment_json,
+    update_password_handler, update_team_members_json, upload_chunk_json, upload_image_multipart,
+    verify_cabling_json, verify_image_json,
 };
 
 #[derive(Embed)]
@@ -99,6 +112,8 @@ pub fn build_router() -> Router<AppState> {
         .route("/signup", get(signup_page_handler))
         .route("/signup", post(signup_form_handler))
         .route("/logout", post(logout_handler))
+        .route("/auth/oidc/login", get(oidc_login_handler))
+        .route("/auth/oidc/callback", get(oidc_callback_handler))
         // Protected HTML routes (require cookie authentication)
         .route("/", get(dashboard_handler))
         .route("/labs", get(labs_list_page_handler))
@@ -111,6 +126,13 @@ pub fn build_router() -> Router<AppState> {
         .route("/jobs/{job_id}/stream", get(job_stream_handler))
         .route("/labs/{lab_id}", get(lab_detail_handler))
         .route("/labs/{lab_id}/nodes", get(lab_nodes_handler))
+        .route("/labs/{lab_id}/topology", get(lab_topology_handler))
+        .route("/labs/{lab_id}/leases", get(lab_leases_handler))
+        .route("/labs/{lab_id}/shares", post(lab_share_add_handler))
+        .route(
+            "/labs/{lab_id}/shares/{grantee}",
+            delete(lab_share_remove_handler),
+        )
         .route("/labs/{lab_id}/nodes/{node_name}", get(node_detail_handler))
         .route(
             "/labs/{lab_id}/destroy/confirm",
@@ -140,6 +162,11 @@ pub fn build_router() -> Router<AppState> {
         .route("/profile/password", post(update_password_handler))
         .route("/profile/ssh-keys", post(add_ssh_key_handler))
         .route("/profile/ssh-keys/{index}", delete(delete_ssh_key_handler))
+        .route("/profile/api-tokens", post(create_api_token_handler))
+        .route(
+            "/profile/api-tokens/{name}",
+            delete(revoke_api_token_handler),
+        )
         // Admin routes (require admin privileges)
         .route("/admin/users", get(admin_dashboard_handler))
         .route("/admin/labs", get(admin_labs_list_handler))
@@ -196,6 +223,9 @@ pub fn build_router() -> Router<AppState> {
         .route("/api/v1/openapi.json", get(openapi_handler))
         .route("/api/docs", get(api_docs_handler))
         .route("/api/v1/auth/login", post(login)) // JSON login for CLI
+        .route("/api/v1/auth/providers", get(auth_providers_json))
+        .route("/api/v1/auth/device", post(device_login_start_json))
+        .route("/api/v1/auth/device/token", post(device_login_poll_json))
         .route("/api/v1/labs", get(get_labs_json))
         // Protected API endpoints (authentication required)
         .route("/api/v1/labs", post(create_lab_json))
@@ -207,15 +237,68 @@ pub fn build_router() -> Router<AppState> {
             "/api/v1/labs/{id}/nodes/{node_name}/redeploy",
             post(redeploy_node_json),
         )
+        .route(
+            "/api/v1/labs/{id}/nodes/{node_name}/commit",
+            post(commit_node_json),
+        )
+        .route(
+            "/api/v1/labs/{id}/shares",
+            get(list_lab_shares_json).post(share_lab_json),
+        )
+        .route(
+            "/api/v1/labs/{id}/shares/{grantee}",
+            delete(unshare_lab_json),
+        )
+        .route("/api/v1/labs/{id}/leases", get(lab_leases_json))
+        // Team API endpoints
+        .route("/api/v1/teams", post(create_team_json).get(list_teams_json))
+        .route("/api/v1/teams/{name}", delete(delete_team_json))
+        .route(
+            "/api/v1/teams/{name}/members",
+            post(update_team_members_json),
+        )
+        // API token endpoints
+        .route(
+            "/api/v1/tokens",
+            post(create_api_token_json).get(list_api_tokens_json),
+        )
+        .route("/api/v1/tokens/{name}", delete(revoke_api_token_json))
         // Link API endpoints
         .route(
             "/api/v1/labs/{lab_id}/links/{link_index}/impairment",
             post(update_impairment_json),
         )
+        .route("/api/v1/labs/{lab_id}/links/stats", get(link_stats_json))
+        .route(
+            "/api/v1/labs/{lab_id}/links/mirrors",
+            post(link_mirror_add_json).delete(link_mirror_remove_json),
+        )
+        .route("/api/v1/labs/{lab_id}/links/filter", post(link_filter_json))
+        .route(
+            "/api/v1/labs/{lab_id}/cabling/verify",
+            post(verify_cabling_json),
+        )
         // Image API endpoints
         .route("/api/v1/images", get(list_images_json))
         .route("/api/v1/images/import", post(import_image_json))
         .route("/api/v1/images/upload", post(upload_image_multipart))
+        .route("/api/v1/images/uploads", post(start_upload_json))
+        .route(
+            "/api/v1/images/uploads/{upload_id}",
+            put(upload_chunk_json)
+                .delete(cancel_upload_json)
+                .layer(DefaultBodyLimit::max(IMAGE_UPLOAD_MAX_CHUNK_SIZE as usize)),
+        )
+        .route(
+            "/api/v1/images/models",
+            get(list_custom_models_json).post(add_custom_model_json),
+        )
+        .route(
+            "/api/v1/images/models/{name}",
+            delete(delete_custom_model_json),
+        )
+        .route("/api/v1/images/usage", get(image_usage_json))
+        .route("/api/v1/images/prune", post(prune_images_json))
         .route("/api/v1/images/pull", post(pull_image_json))
         .route("/api/v1/images/download", post(download_image_json))
         .route("/api/v1/images/{model}", get(show_image_json))
@@ -227,6 +310,10 @@ pub fn build_router() -> Router<AppState> {
             "/api/v1/images/{model}/{version}/default",
             post(set_default_image_json),
         )
+        .route(
+            "/api/v1/images/{model}/{version}/verify",
+            post(verify_image_json),
+        )
         // Admin API — Tools
         .route("/api/v1/admin/tools/labs/clean/{id}", post(clean_lab_json))
         .route("/api/v1/admin/tools/images/scan", post(scan_images_json))
@@ -243,6 +330,15 @@ pub fn build_router() -> Router<AppState> {
             "/api/v1/admin/users/{username}/password",
             post(change_password_json),
         )
+        // Admin API — Registries
+        .route(
+            "/api/v1/admin/registries",
+            post(registry_login_json).get(list_registries_json),
+        )
+        .route(
+            "/api/v1/admin/registries/{registry}",
+            delete(registry_logout_json),
+        )
         // Apply middleware layers (outermost = first to process request)
         .layer(cors)
         .layer(TraceLayer::new_for_http())
@@ -176,7 +176,9 @@ async fn handle_client_message(
             if method == "up"
                 || method == "destroy"
                 || method == "redeploy"
+                || method == "node.commit"
                 || method == "image.import"
+                || method == "image.upload_chunk"
                 || method == "image.pull"
                 || method == "image.download"
             {
@@ -1,4 +1,5 @@
 use axum::extract::ws::Message;
+use base64::Engine;
 use opentelemetry::KeyValue;
 use serde_json;
 use std::sync::Arc;
@@ -10,51 +11,77 @@ use crate::api::websocket::messages::{RpcError, ServerMessage};
 use crate::auth::context::AuthContext;
 use crate::auth::middleware;
 use crate::daemon::state::AppState;
+use crate::services::mirror::MirrorAction;
 use crate::services::{
-    clean, container_pull, delete, destroy, down, download, impairment, import, inspect, list_labs,
-    progress, redeploy, resume, up,
+    api_token, cabling, clean, commit, container_pull, custom_model, delete, destroy, down,
+    download, image_usage, impairment, import, inspect, leases, link_filter, link_stats, list_labs,
+    mirror, progress, redeploy, registry, resume, share, up, upload,
 };
+use shared::auth::api_token::is_api_token;
 use shared::auth::password;
-use shared::data;
+use shared::data::{self, LabRole};
 use shared::error::RpcErrorCode;
 use shared::konst::{
-    JWT_TOKEN_EXPIRY_SECONDS, RPC_MSG_ACCESS_DENIED_LAB, RPC_MSG_ACCESS_DENIED_LAST_ADMIN,
-    RPC_MSG_ACCESS_DENIED_OWN_INFO, RPC_MSG_ACCESS_DENIED_OWN_PASSWORD,
-    RPC_MSG_ACCESS_DENIED_SELF_DELETE, RPC_MSG_ADMIN_ONLY_CLEAN, RPC_MSG_ADMIN_ONLY_CONTAINER_PULL,
-    RPC_MSG_ADMIN_ONLY_IMAGE_DELETE, RPC_MSG_ADMIN_ONLY_IMAGE_DOWNLOAD,
-    RPC_MSG_ADMIN_ONLY_IMAGE_IMPORT, RPC_MSG_ADMIN_ONLY_IMAGE_SCAN,
-    RPC_MSG_ADMIN_ONLY_IMAGE_SET_DEFAULT, RPC_MSG_AUTH_ERROR, RPC_MSG_AUTH_INVALID,
-    RPC_MSG_AUTH_REQUIRED, RPC_MSG_CONTAINER_PULL_FAILED, RPC_MSG_IMAGE_DELETE_FAILED,
-    RPC_MSG_IMAGE_DOWNLOAD_FAILED, RPC_MSG_IMAGE_IMPORT_FAILED, RPC_MSG_IMAGE_LIST_FAILED,
+    JWT_TOKEN_EXPIRY_SECONDS, RPC_MSG_ACCESS_DENIED_LAB, RPC_MSG_ACCESS_DENIED_LAB_SHARE,
+    RPC_MSG_ACCESS_DENIED_LAST_ADMIN, RPC_MSG_ACCESS_DENIED_OWN_INFO,
+    RPC_MSG_ACCESS_DENIED_OWN_PASSWORD, RPC_MSG_ACCESS_DENIED_SELF_DELETE,
+    RPC_MSG_ACCESS_DENIED_TEAM, RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE, RPC_MSG_ADMIN_ONLY_CLEAN,
+    RPC_MSG_ADMIN_ONLY_CONTAINER_PULL, RPC_MSG_ADMIN_ONLY_IMAGE_DELETE,
+    RPC_MSG_ADMIN_ONLY_IMAGE_DOWNLOAD, RPC_MSG_ADMIN_ONLY_IMAGE_IMPORT,
+    RPC_MSG_ADMIN_ONLY_IMAGE_MODEL, RPC_MSG_ADMIN_ONLY_IMAGE_PRUNE, RPC_MSG_ADMIN_ONLY_IMAGE_SCAN,
+    RPC_MSG_ADMIN_ONLY_IMAGE_SET_DEFAULT, RPC_MSG_ADMIN_ONLY_IMAGE_UPLOAD,
+    RPC_MSG_ADMIN_ONLY_IMAGE_USAGE, RPC_MSG_ADMIN_ONLY_IMAGE_VERIFY,
+    RPC_MSG_ADMIN_ONLY_NODE_COMMIT, RPC_MSG_ADMIN_ONLY_REGISTRY, RPC_MSG_API_TOKEN_CREATE_FAILED,
+    RPC_MSG_API_TOKEN_LIST_FAILED, RPC_MSG_API_TOKEN_REVOKE_FAILED, RPC_MSG_AUTH_ERROR,
+    RPC_MSG_AUTH_INVALID, RPC_MSG_AUTH_REQUIRED, RPC_MSG_CONTAINER_PULL_FAILED,
+    RPC_MSG_IMAGE_DELETE_FAILED, RPC_MSG_IMAGE_DOWNLOAD_FAILED, RPC_MSG_IMAGE_IMPORT_FAILED,
+    RPC_MSG_IMAGE_LIST_FAILED, RPC_MSG_IMAGE_MODEL_FAILED, RPC_MSG_IMAGE_PRUNE_FAILED,
     RPC_MSG_IMAGE_SCAN_FAILED, RPC_MSG_IMAGE_SET_DEFAULT_FAILED, RPC_MSG_IMAGE_SHOW_FAILED,
-    RPC_MSG_IMPAIRMENT_UPDATE_FAILED, RPC_MSG_INVALID_PARAMS_CHANGE_PASSWORD,
-    RPC_MSG_INVALID_PARAMS_CONTAINER_PULL, RPC_MSG_INVALID_PARAMS_CREATE_USER,
-    RPC_MSG_INVALID_PARAMS_DELETE_USER, RPC_MSG_INVALID_PARAMS_GET_USER_INFO,
-    RPC_MSG_INVALID_PARAMS_IMAGE_DELETE, RPC_MSG_INVALID_PARAMS_IMAGE_DOWNLOAD,
-    RPC_MSG_INVALID_PARAMS_IMAGE_LIST, RPC_MSG_INVALID_PARAMS_IMAGE_SET_DEFAULT,
-    RPC_MSG_INVALID_PARAMS_IMAGE_SHOW, RPC_MSG_INVALID_PARAMS_IMPAIRMENT,
-    RPC_MSG_INVALID_PARAMS_IMPORT, RPC_MSG_INVALID_PARAMS_LAB_ID, RPC_MSG_INVALID_PARAMS_LOGIN,
-    RPC_MSG_INVALID_PARAMS_MANIFEST, RPC_MSG_INVALID_PARAMS_REDEPLOY, RPC_MSG_INVALID_PARAMS_TOKEN,
+    RPC_MSG_IMAGE_UPLOAD_FAILED, RPC_MSG_IMAGE_USAGE_FAILED, RPC_MSG_IMAGE_VERIFY_FAILED,
+    RPC_MSG_IMPAIRMENT_UPDATE_FAILED, RPC_MSG_INTERNAL_ERROR,
+    RPC_MSG_INVALID_PARAMS_CHANGE_PASSWORD, RPC_MSG_INVALID_PARAMS_CONTAINER_PULL,
+    RPC_MSG_INVALID_PARAMS_CREATE_API_TOKEN, RPC_MSG_INVALID_PARAMS_CREATE_TEAM,
+    RPC_MSG_INVALID_PARAMS_CREATE_USER, RPC_MSG_INVALID_PARAMS_DELETE_TEAM,
+    RPC_MSG_INVALID_PARAMS_DELETE_USER, RPC_MSG_INVALID_PARAMS_DEVICE_POLL,
+    RPC_MSG_INVALID_PARAMS_GET_USER_INFO, RPC_MSG_INVALID_PARAMS_IMAGE_DELETE,
+    RPC_MSG_INVALID_PARAMS_IMAGE_DOWNLOAD, RPC_MSG_INVALID_PARAMS_IMAGE_LIST,
+    RPC_MSG_INVALID_PARAMS_IMAGE_PRUNE, RPC_MSG_INVALID_PARAMS_IMAGE_SET_DEFAULT,
+    RPC_MSG_INVALID_PARAMS_IMAGE_SHOW, RPC_MSG_INVALID_PARAMS_IMAGE_VERIFY,
+    RPC_MSG_INVALID_PARAMS_IMPAIRMENT, RPC_MSG_INVALID_PARAMS_IMPORT,
+    RPC_MSG_INVALID_PARAMS_LAB_ID, RPC_MSG_INVALID_PARAMS_LINK_FILTER,
+    RPC_MSG_INVALID_PARAMS_LINK_MIRROR, RPC_MSG_INVALID_PARAMS_LOGIN,
+    RPC_MSG_INVALID_PARAMS_MANIFEST, RPC_MSG_INVALID_PARAMS_MODEL_ADD,
+    RPC_MSG_INVALID_PARAMS_MODEL_DELETE, RPC_MSG_INVALID_PARAMS_NODE_COMMIT,
+    RPC_MSG_INVALID_PARAMS_REDEPLOY, RPC_MSG_INVALID_PARAMS_REGISTRY_LOGIN,
+    RPC_MSG_INVALID_PARAMS_REGISTRY_LOGOUT, RPC_MSG_INVALID_PARAMS_REVOKE_API_TOKEN,
+    RPC_MSG_INVALID_PARAMS_SHARE_LAB, RPC_MSG_INVALID_PARAMS_TOKEN,
+    RPC_MSG_INVALID_PARAMS_UNSHARE_LAB, RPC_MSG_INVALID_PARAMS_UPDATE_TEAM,
+    RPC_MSG_INVALID_PARAMS_UPLOAD_CANCEL, RPC_MSG_INVALID_PARAMS_UPLOAD_CHUNK,
+    RPC_MSG_INVALID_PARAMS_UPLOAD_START, RPC_MSG_INVALID_PARAMS_VERIFY_CABLING,
     RPC_MSG_LAB_CLEAN_FAILED, RPC_MSG_LAB_DESTROY_FAILED, RPC_MSG_LAB_DOWN_FAILED,
-    RPC_MSG_LAB_INSPECT_FAILED, RPC_MSG_LAB_RESUME_FAILED, RPC_MSG_LAB_UP_FAILED,
-    RPC_MSG_PASSWORD_VALIDATION_FAILED, RPC_MSG_REDEPLOY_FAILED, RPC_MSG_SERIALIZE_FAILED,
-    RPC_MSG_TOKEN_CREATE_FAILED, RPC_MSG_USER_ADMIN_ONLY_CREATE, RPC_MSG_USER_ADMIN_ONLY_DELETE,
-    RPC_MSG_USER_ADMIN_ONLY_LIST, RPC_MSG_USER_CREATE_FAILED, RPC_MSG_USER_DELETE_FAILED,
-    RPC_MSG_USER_DELETE_SAFETY_CHECK_FAILED, RPC_MSG_USER_LIST_FAILED,
-    RPC_MSG_USER_PASSWORD_UPDATE_FAILED,
+    RPC_MSG_LAB_INSPECT_FAILED, RPC_MSG_LAB_LEASES_FAILED, RPC_MSG_LAB_RESUME_FAILED,
+    RPC_MSG_LAB_SHARE_FAILED, RPC_MSG_LAB_UP_FAILED, RPC_MSG_LINK_FILTER_FAILED,
+    RPC_MSG_LINK_MIRROR_FAILED, RPC_MSG_LINK_STATS_FAILED, RPC_MSG_NODE_COMMIT_FAILED,
+    RPC_MSG_OIDC_DEVICE_POLL_FAILED, RPC_MSG_OIDC_DEVICE_START_FAILED, RPC_MSG_OIDC_NOT_CONFIGURED,
+    RPC_MSG_PASSWORD_VALIDATION_FAILED, RPC_MSG_REDEPLOY_FAILED, RPC_MSG_REGISTRY_LIST_FAILED,
+    RPC_MSG_REGISTRY_LOGIN_FAILED, RPC_MSG_REGISTRY_LOGOUT_FAILED, RPC_MSG_SERIALIZE_FAILED,
+    RPC_MSG_TEAM_CREATE_FAILED, RPC_MSG_TEAM_DELETE_FAILED, RPC_MSG_TEAM_LIST_FAILED,
+    RPC_MSG_TEAM_UPDATE_FAILED, RPC_MSG_TOKEN_CREATE_FAILED, RPC_MSG_USER_ADMIN_ONLY_CREATE,
+    RPC_MSG_USER_ADMIN_ONLY_DELETE, RPC_MSG_USER_ADMIN_ONLY_LIST, RPC_MSG_USER_CREATE_FAILED,
+    RPC_MSG_USER_DELETE_FAILED, RPC_MSG_USER_DELETE_SAFETY_CHECK_FAILED, RPC_MSG_USER_LIST_FAILED,
+    RPC_MSG_USER_PASSWORD_UPDATE_FAILED, RPC_MSG_VERIFY_CABLING_FAILED,
 };
 
 /// Authenticate request and verify admin privileges.
 ///
 /// Returns `Ok(AuthContext)` on success, or `Err(ServerMessage)` with the appropriate
 /// auth-required or access-denied error response.
-async fn require_admin(
+fn require_admin(
     id: &str,
-    params: &serde_json::Value,
-    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
     deny_msg: &str,
 ) -> Result<AuthContext, ServerMessage> {
-    let auth_ctx = match middleware::authenticate_request(params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed: {}", e);
@@ -94,12 +121,11 @@ async fn require_admin(
 /// Returns `Ok(AuthContext)` on success, or sends the error via WebSocket and returns `Err(())`.
 async fn require_admin_streaming(
     id: &str,
-    params: &serde_json::Value,
-    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
     connection: &Arc<Connection>,
     deny_msg: &str,
 ) -> Result<AuthContext, ()> {
-    let auth_ctx = match middleware::authenticate_request(params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed: {}", e);
@@ -142,6 +168,44 @@ async fn require_admin_streaming(
     Ok(auth_ctx)
 }
 
+/// Verify the authenticated user has at least the required role on a lab.
+///
+/// Returns `Err(RpcError)` with a not-found error if the lab does not exist,
+/// or an access-denied error if the user's role is insufficient.
+async fn require_lab_role(
+    auth_ctx: &AuthContext,
+    lab_id: &str,
+    required: LabRole,
+    action: &str,
+    state: &AppState,
+    deny_msg: &str,
+) -> Result<(), RpcError> {
+    let role = db::get_lab_role(&state.db, lab_id, &auth_ctx.username)
+        .await
+        .map_err(|e| RpcError {
+            code: RpcErrorCode::NotFound,
+            message: format!("Lab not found: {}", lab_id),
+            context: Some(format!("{:?}", e)),
+        })?;
+
+    if !auth_ctx.has_lab_role(role, required) {
+        tracing::warn!(
+            "User '{}' attempted to {} lab '{}' without the {} role",
+            auth_ctx.username,
+            action,
+            lab_id,
+            required
+        );
+        return Err(RpcError {
+            code: RpcErrorCode::AccessDenied,
+            message: deny_msg.to_string(),
+            context: None,
+        });
+    }
+
+    Ok(())
+}
+
 /// Send an RPC error response over the WebSocket connection.
 async fn send_rpc_error(
     connection: &Arc<Connection>,
@@ -179,62 +243,167 @@ pub async fn handle_rpc_request(
     let start = Instant::now();
     let method_attr = KeyValue::new("rpc.method", method.clone());
 
+    // The auth methods obtain or check credentials themselves
     let response = match method.as_str() {
         "auth.login" => handle_auth_login(id, params, state).await,
         "auth.validate" => handle_auth_validate(id, params, state).await,
-        "inspect" => handle_inspect(id, params, state).await,
-        "download" => handle_download(id, params, state).await,
-        "labs.list" => handle_labs_list(id, params, state).await,
-        "down" => handle_down(id, params, state).await,
-        "link.update_impairment" => handle_link_update_impairment(id, params, state).await,
-        "resume" => handle_resume(id, params, state).await,
+        "auth.providers" => handle_auth_providers(id, state),
+        "auth.device_start" => handle_auth_device_start(id, state).await,
+        "auth.device_poll" => handle_auth_device_poll(id, params, state).await,
+        _ => handle_authenticated_rpc_request(id, &method, params, state).await,
+    };
+
+    state
+        .metrics
+        .rpc_duration
+        .record(start.elapsed().as_secs_f64(), &[method_attr]);
+
+    response
+}
+
+/// Authenticate the credentials in RPC request params once for the whole call
+///
+/// Authentication failures are returned for the method handler to report.
+/// Returns `Err` with the error message if the request authenticated with an
+/// API token whose scopes do not cover the method.
+async fn authenticate_rpc_request(
+    method: &str,
+    params: &serde_json::Value,
+    state: &AppState,
+) -> Result<anyhow::Result<AuthContext>, String> {
+    let auth = middleware::authenticate_request(params, state).await;
+    if let Ok(auth_ctx) = &auth {
+        middleware::authorize_token_scope(method, auth_ctx).map_err(|e| e.to_string())?;
+    }
+    Ok(auth)
+}
+
+/// Route an RPC request that requires authentication
+async fn handle_authenticated_rpc_request(
+    id: String,
+    method: &str,
+    params: serde_json::Value,
+    state: &AppState,
+) -> ServerMessage {
+    let auth = match authenticate_rpc_request(method, &params, state).await {
+        Ok(auth) => auth,
+        Err(e) => {
+            return ServerMessage::RpcResponse {
+                id,
+                result: None,
+                error: Some(RpcError {
+                    code: RpcErrorCode::AccessDenied,
+                    message: RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE.to_string(),
+                    context: Some(e),
+                }),
+            };
+        }
+    };
+
+    match method {
+        "inspect" => handle_inspect(id, params, state, auth).await,
+        "download" => handle_download(id, params, state, auth).await,
+        "labs.list" => handle_labs_list(id, state, auth).await,
+        "down" => handle_down(id, params, state, auth).await,
+        "link.update_impairment" => handle_link_update_impairment(id, params, state, auth).await,
+        "link.stats" => handle_link_stats(id, params, state, auth).await,
+        "link.mirror_add" => handle_link_mirror(id, params, state, MirrorAction::Add, auth).await,
+        "link.mirror_remove" => {
+            handle_link_mirror(id, params, state, MirrorAction::Remove, auth).await
+        }
+        "link.update_filter" => handle_link_update_filter(id, params, state, auth).await,
+        "resume" => handle_resume(id, params, state, auth).await,
         // Note: "destroy" is handled separately via handle_streaming_rpc_request
-        "clean" => match require_admin(&id, &params, state, RPC_MSG_ADMIN_ONLY_CLEAN).await {
+        "clean" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_CLEAN) {
             Ok(auth_ctx) => handle_clean(id, params, state, auth_ctx).await,
             Err(e) => e,
         },
         // Note: "image.import" is handled separately via handle_streaming_rpc_request
-        "image.delete" => {
-            match require_admin(&id, &params, state, RPC_MSG_ADMIN_ONLY_IMAGE_DELETE).await {
-                Ok(auth_ctx) => handle_image_delete(id, params, state, auth_ctx).await,
-                Err(e) => e,
-            }
-        }
+        "image.delete" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_DELETE) {
+            Ok(auth_ctx) => handle_image_delete(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
         "image.set_default" => {
-            match require_admin(&id, &params, state, RPC_MSG_ADMIN_ONLY_IMAGE_SET_DEFAULT).await {
+            match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_SET_DEFAULT) {
                 Ok(auth_ctx) => handle_image_set_default(id, params, state, auth_ctx).await,
                 Err(e) => e,
             }
         }
-        "image.list" => handle_image_list(id, params, state).await,
-        "image.show" => handle_image_show(id, params, state).await,
-        "image.scan" => {
-            match require_admin(&id, &params, state, RPC_MSG_ADMIN_ONLY_IMAGE_SCAN).await {
-                Ok(auth_ctx) => handle_image_scan(id, params, state, auth_ctx).await,
-                Err(e) => e,
-            }
-        }
+        "image.upload_start" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_UPLOAD) {
+            Ok(_) => handle_image_upload_start(id, params).await,
+            Err(e) => e,
+        },
+        "image.upload_cancel" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_UPLOAD) {
+            Ok(_) => handle_image_upload_cancel(id, params).await,
+            Err(e) => e,
+        },
+        "image.verify" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_VERIFY) {
+            Ok(_) => handle_image_verify(id, params, state).await,
+            Err(e) => e,
+        },
+        "image.list" => handle_image_list(id, params, state, auth).await,
+        "image.show" => handle_image_show(id, params, state, auth).await,
+        "image.scan" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_SCAN) {
+            Ok(auth_ctx) => handle_image_scan(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
+        "image.model_add" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_MODEL) {
+            Ok(_) => handle_image_model_add(id, params),
+            Err(e) => e,
+        },
+        "image.model_list" => handle_image_model_list(id, auth),
+        "image.model_delete" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_MODEL) {
+            Ok(_) => handle_image_model_delete(id, params),
+            Err(e) => e,
+        },
+        "image.usage" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_USAGE) {
+            Ok(_) => handle_image_usage(id, state).await,
+            Err(e) => e,
+        },
+        "image.prune" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_IMAGE_PRUNE) {
+            Ok(auth_ctx) => handle_image_prune(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
+        "registry.login" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_REGISTRY) {
+            Ok(auth_ctx) => handle_registry_login(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
+        "registry.list" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_REGISTRY) {
+            Ok(_) => handle_registry_list(id, state).await,
+            Err(e) => e,
+        },
+        "registry.logout" => match require_admin(&id, auth, RPC_MSG_ADMIN_ONLY_REGISTRY) {
+            Ok(auth_ctx) => handle_registry_logout(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
         // Note: "image.pull" is handled separately via handle_streaming_rpc_request
         // Note: "image.download" is handled separately via handle_streaming_rpc_request
-        "user.create" => {
-            match require_admin(&id, &params, state, RPC_MSG_USER_ADMIN_ONLY_CREATE).await {
-                Ok(auth_ctx) => handle_user_create(id, params, state, auth_ctx).await,
-                Err(e) => e,
-            }
-        }
-        "user.list" => match require_admin(&id, &params, state, RPC_MSG_USER_ADMIN_ONLY_LIST).await
-        {
+        "user.create" => match require_admin(&id, auth, RPC_MSG_USER_ADMIN_ONLY_CREATE) {
+            Ok(auth_ctx) => handle_user_create(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
+        "user.list" => match require_admin(&id, auth, RPC_MSG_USER_ADMIN_ONLY_LIST) {
             Ok(auth_ctx) => handle_user_list(id, params, state, auth_ctx).await,
             Err(e) => e,
         },
-        "user.delete" => {
-            match require_admin(&id, &params, state, RPC_MSG_USER_ADMIN_ONLY_DELETE).await {
-                Ok(auth_ctx) => handle_user_delete(id, params, state, auth_ctx).await,
-                Err(e) => e,
-            }
-        }
-        "user.passwd" => handle_user_passwd(id, params, state).await,
-        "user.info" => handle_user_info(id, params, state).await,
+        "user.delete" => match require_admin(&id, auth, RPC_MSG_USER_ADMIN_ONLY_DELETE) {
+            Ok(auth_ctx) => handle_user_delete(id, params, state, auth_ctx).await,
+            Err(e) => e,
+        },
+        "user.passwd" => handle_user_passwd(id, params, state, auth).await,
+        "user.info" => handle_user_info(id, params, state, auth).await,
+        "lab.share" => handle_lab_share(id, params, state, auth).await,
+        "lab.unshare" => handle_lab_unshare(id, params, state, auth).await,
+        "lab.shares" => handle_lab_shares(id, params, state, auth).await,
+        "lab.leases" => handle_lab_leases(id, params, state, auth).await,
+        "lab.verify_cabling" => handle_lab_verify_cabling(id, params, state, auth).await,
+        "team.create" => handle_team_create(id, params, state, auth).await,
+        "team.list" => handle_team_list(id, state, auth).await,
+        "team.update" => handle_team_update(id, params, state, auth).await,
+        "team.delete" => handle_team_delete(id, params, state, auth).await,
+        "token.create" => handle_token_create(id, params, state, auth).await,
+        "token.list" => handle_token_list(id, state, auth).await,
+        "token.revoke" => handle_token_revoke(id, params, state, auth).await,
         // Note: "up" is handled separately via handle_streaming_rpc_request
         _ => {
             // Unknown method
@@ -248,14 +417,7 @@ pub async fn handle_rpc_request(
                 }),
             }
         }
-    };
-
-    state
-        .metrics
-        .rpc_duration
-        .record(start.elapsed().as_secs_f64(), &[method_attr]);
-
-    response
+    }
 }
 
 /// Handle streaming RPC request (sends multiple messages during execution)
@@ -270,45 +432,61 @@ pub async fn handle_streaming_rpc_request(
     let start = Instant::now();
     let method_attr = KeyValue::new("rpc.method", method.clone());
 
-    match method.as_str() {
-        "up" => handle_up(id, params, state, connection).await,
-        "destroy" => handle_destroy_streaming(id, params, state, connection).await,
-        "redeploy" => handle_redeploy_streaming(id, params, state, connection).await,
-        "image.import" => {
-            if let Ok(auth_ctx) = require_admin_streaming(
-                &id,
-                &params,
-                state,
+    let auth = match authenticate_rpc_request(&method, &params, state).await {
+        Ok(auth) => auth,
+        Err(e) => {
+            send_rpc_error(
                 connection,
-                RPC_MSG_ADMIN_ONLY_IMAGE_IMPORT,
+                id,
+                RpcErrorCode::AccessDenied,
+                RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE.to_string(),
+                Some(e),
             )
-            .await
+            .await;
+            return;
+        }
+    };
+
+    match method.as_str() {
+        "up" => handle_up(id, params, state, connection, auth).await,
+        "destroy" => handle_destroy_streaming(id, params, state, connection, auth).await,
+        "redeploy" => handle_redeploy_streaming(id, params, state, connection, auth).await,
+        "node.commit" => {
+            if require_admin_streaming(&id, auth, connection, RPC_MSG_ADMIN_ONLY_NODE_COMMIT)
+                .await
+                .is_ok()
+            {
+                handle_node_commit_streaming(id, params, state, connection).await;
+            }
+        }
+        "image.import" => {
+            if let Ok(auth_ctx) =
+                require_admin_streaming(&id, auth, connection, RPC_MSG_ADMIN_ONLY_IMAGE_IMPORT)
+                    .await
             {
                 handle_image_import_streaming(id, params, state, connection, auth_ctx).await;
             }
         }
+        "image.upload_chunk" => {
+            if require_admin_streaming(&id, auth, connection, RPC_MSG_ADMIN_ONLY_IMAGE_UPLOAD)
+                .await
+                .is_ok()
+            {
+                handle_image_upload_chunk_streaming(id, params, state, connection).await;
+            }
+        }
         "image.pull" => {
-            if let Ok(auth_ctx) = require_admin_streaming(
-                &id,
-                &params,
-                state,
-                connection,
-                RPC_MSG_ADMIN_ONLY_CONTAINER_PULL,
-            )
-            .await
+            if let Ok(auth_ctx) =
+                require_admin_streaming(&id, auth, connection, RPC_MSG_ADMIN_ONLY_CONTAINER_PULL)
+                    .await
             {
                 handle_image_pull_streaming(id, params, state, connection, auth_ctx).await;
             }
         }
         "image.download" => {
-            if let Ok(auth_ctx) = require_admin_streaming(
-                &id,
-                &params,
-                state,
-                connection,
-                RPC_MSG_ADMIN_ONLY_IMAGE_DOWNLOAD,
-            )
-            .await
+            if let Ok(auth_ctx) =
+                require_admin_streaming(&id, auth, connection, RPC_MSG_ADMIN_ONLY_IMAGE_DOWNLOAD)
+                    .await
             {
                 handle_image_download_streaming(id, params, state, connection, auth_ctx).await;
             }
@@ -341,10 +519,10 @@ pub async fn handle_streaming_rpc_request(
 /// Expected params: {"token": "string"}
 async fn handle_labs_list(
     id: String,
-    params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for labs.list: {}", e);
@@ -392,9 +570,14 @@ async fn handle_labs_list(
 /// Handle "inspect" RPC call
 ///
 /// Expected params: {"lab_id": "string", "token": "string"}
-async fn handle_inspect(id: String, params: serde_json::Value, state: &AppState) -> ServerMessage {
+async fn handle_inspect(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for inspect: {}", e);
@@ -426,39 +609,22 @@ async fn handle_inspect(id: String, params: serde_json::Value, state: &AppState)
         }
     };
 
-    // Check authorization: user must own the lab or be an admin
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to inspect lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    lab_id,
-                    owner_username
-                );
-                return ServerMessage::RpcResponse {
-                    id,
-                    result: None,
-                    error: Some(RpcError {
-                        code: RpcErrorCode::AccessDenied,
-                        message: RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                        context: None,
-                    }),
-                };
-            }
-        }
-        Err(e) => {
-            // Lab not found
-            return ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::NotFound,
-                    message: format!("Lab not found: {}", lab_id),
-                    context: Some(format!("{:?}", e)),
-                }),
-            };
-        }
+    // Check authorization: user must have access to the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Viewer,
+        "inspect",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
     }
 
     // User is authenticated and authorized - use their username from the token
@@ -510,8 +676,13 @@ async fn handle_inspect(id: String, params: serde_json::Value, state: &AppState)
 /// Handle "download" RPC call — return lab files for CLI use
 ///
 /// Expected params: {"lab_id": "string", "token": "string"}
-async fn handle_download(id: String, params: serde_json::Value, state: &AppState) -> ServerMessage {
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+async fn handle_download(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for download: {}", e);
@@ -542,37 +713,22 @@ async fn handle_download(id: String, params: serde_json::Value, state: &AppState
         }
     };
 
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to download lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    lab_id,
-                    owner_username
-                );
-                return ServerMessage::RpcResponse {
-                    id,
-                    result: None,
-                    error: Some(RpcError {
-                        code: RpcErrorCode::AccessDenied,
-                        message: RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                        context: None,
-                    }),
-                };
-            }
-        }
-        Err(e) => {
-            return ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::NotFound,
-                    message: format!("Lab not found: {}", lab_id),
-                    context: Some(format!("{:?}", e)),
-                }),
-            };
-        }
+    // Check authorization: user must have access to the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Viewer,
+        "download",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
     }
 
     match download::download_lab_files(&lab_id, &auth_ctx.username, state).await {
@@ -617,9 +773,14 @@ async fn handle_download(id: String, params: serde_json::Value, state: &AppState
 /// Handle "down" RPC call — shutdown all (or a specific) node(s) for a lab
 ///
 /// Expected params: {"lab_id": "string", "token": "string", "node_name": "string" (optional)}
-async fn handle_down(id: String, params: serde_json::Value, state: &AppState) -> ServerMessage {
+async fn handle_down(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for down: {}", e);
@@ -657,38 +818,22 @@ async fn handle_down(id: String, params: serde_json::Value, state: &AppState) ->
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    // Check authorization: user must own the lab or be an admin
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to down lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    lab_id,
-                    owner_username
-                );
-                return ServerMessage::RpcResponse {
-                    id,
-                    result: None,
-                    error: Some(RpcError {
-                        code: RpcErrorCode::AccessDenied,
-                        message: RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                        context: None,
-                    }),
-                };
-            }
-        }
-        Err(e) => {
-            return ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::NotFound,
-                    message: format!("Lab not found: {}", lab_id),
-                    context: Some(format!("{:?}", e)),
-                }),
-            };
-        }
+    // Check authorization: user must have the operator role on the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Operator,
+        "down",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
     }
 
     // Call service
@@ -735,9 +880,14 @@ async fn handle_down(id: String, params: serde_json::Value, state: &AppState) ->
 /// Handle "resume" RPC call — start/poweron all (or a specific) node(s) for a lab
 ///
 /// Expected params: {"lab_id": "string", "token": "string", "node_name": "string" (optional)}
-async fn handle_resume(id: String, params: serde_json::Value, state: &AppState) -> ServerMessage {
+async fn handle_resume(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for resume: {}", e);
@@ -775,51 +925,35 @@ async fn handle_resume(id: String, params: serde_json::Value, state: &AppState)
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
-    // Check authorization: user must own the lab or be an admin
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to resume lab '{}' owned by '{}'",
+    // Check authorization: user must have the operator role on the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Operator,
+        "resume",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    // Call service
+    match resume::start_lab_nodes(&lab_id, node_name.as_deref(), state).await {
+        Ok(response) => match serde_json::to_value(&response) {
+            Ok(result) => {
+                tracing::info!(
+                    "User '{}' started lab '{}' nodes (node_name: {:?})",
                     auth_ctx.username,
                     lab_id,
-                    owner_username
+                    node_name
                 );
-                return ServerMessage::RpcResponse {
-                    id,
-                    result: None,
-                    error: Some(RpcError {
-                        code: RpcErrorCode::AccessDenied,
-                        message: RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                        context: None,
-                    }),
-                };
-            }
-        }
-        Err(e) => {
-            return ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::NotFound,
-                    message: format!("Lab not found: {}", lab_id),
-                    context: Some(format!("{:?}", e)),
-                }),
-            };
-        }
-    }
-
-    // Call service
-    match resume::start_lab_nodes(&lab_id, node_name.as_deref(), state).await {
-        Ok(response) => match serde_json::to_value(&response) {
-            Ok(result) => {
-                tracing::info!(
-                    "User '{}' started lab '{}' nodes (node_name: {:?})",
-                    auth_ctx.username,
-                    lab_id,
-                    node_name
-                );
-                ServerMessage::RpcResponse {
+                ServerMessage::RpcResponse {
                     id,
                     result: Some(result),
                     error: None,
@@ -859,8 +993,9 @@ async fn handle_link_update_impairment(
     id: String,
     params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for link.update_impairment: {}", e);
@@ -906,37 +1041,22 @@ async fn handle_link_update_impairment(
         }
     };
 
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to update impairment on lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    lab_id,
-                    owner_username
-                );
-                return ServerMessage::RpcResponse {
-                    id,
-                    result: None,
-                    error: Some(RpcError {
-                        code: RpcErrorCode::AccessDenied,
-                        message: RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                        context: None,
-                    }),
-                };
-            }
-        }
-        Err(e) => {
-            return ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::NotFound,
-                    message: format!("Lab not found: {}", lab_id),
-                    context: Some(format!("{:?}", e)),
-                }),
-            };
-        }
+    // Check authorization: user must have the operator role on the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Operator,
+        "update impairment on",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
     }
 
     let request = data::UpdateImpairmentRequest {
@@ -1006,9 +1126,10 @@ async fn handle_destroy_streaming(
     params: serde_json::Value,
     state: &AppState,
     connection: &Arc<Connection>,
+    auth: anyhow::Result<AuthContext>,
 ) {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for destroy: {}", e);
@@ -1041,37 +1162,18 @@ async fn handle_destroy_streaming(
     };
 
     // Check authorization: user must own the lab or be an admin
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to destroy lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    lab_id,
-                    owner_username
-                );
-                send_rpc_error(
-                    connection,
-                    id,
-                    RpcErrorCode::AccessDenied,
-                    RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                    None,
-                )
-                .await;
-                return;
-            }
-        }
-        Err(e) => {
-            send_rpc_error(
-                connection,
-                id,
-                RpcErrorCode::NotFound,
-                format!("Lab not found: {}", lab_id),
-                Some(format!("{:?}", e)),
-            )
-            .await;
-            return;
-        }
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Owner,
+        "destroy",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        send_rpc_error(connection, id, error.code, error.message, error.context).await;
+        return;
     }
 
     // Create progress channel
@@ -1149,9 +1251,10 @@ async fn handle_redeploy_streaming(
     params: serde_json::Value,
     state: &AppState,
     connection: &Arc<Connection>,
+    auth: anyhow::Result<AuthContext>,
 ) {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for redeploy: {}", e);
@@ -1213,39 +1316,19 @@ async fn handle_redeploy_streaming(
         }
     };
 
-    // Check authorization: user must own the lab or be an admin
-    match db::get_lab_owner_username(&state.db, &lab_id).await {
-        Ok(owner_username) => {
-            if !auth_ctx.can_access(&owner_username) {
-                tracing::warn!(
-                    "User '{}' attempted to redeploy node '{}' in lab '{}' owned by '{}'",
-                    auth_ctx.username,
-                    node_name,
-                    lab_id,
-                    owner_username
-                );
-                send_rpc_error(
-                    connection,
-                    id,
-                    RpcErrorCode::AccessDenied,
-                    RPC_MSG_ACCESS_DENIED_LAB.to_string(),
-                    None,
-                )
-                .await;
-                return;
-            }
-        }
-        Err(e) => {
-            send_rpc_error(
-                connection,
-                id,
-                RpcErrorCode::NotFound,
-                format!("Lab not found: {}", lab_id),
-                Some(format!("{:?}", e)),
-            )
-            .await;
-            return;
-        }
+    // Check authorization: user must have the operator role on the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &lab_id,
+        LabRole::Operator,
+        "redeploy nodes in",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        send_rpc_error(connection, id, error.code, error.message, error.context).await;
+        return;
     }
 
     // Create progress channel
@@ -1385,6 +1468,46 @@ async fn handle_clean(
     }
 }
 
+/// Handle "node.commit" RPC call with streaming progress (admin-only)
+///
+/// Expected params: NodeCommitRequest {"lab_id": "string", "node_name": "string",
+/// "version": "string", "default": bool, "token": "string"}
+async fn handle_node_commit_streaming(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    connection: &Arc<Connection>,
+) {
+    let request: data::NodeCommitRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_NODE_COMMIT) {
+            Ok(req) => req,
+            Err(response) => {
+                if let Ok(json) = serde_json::to_string(&response) {
+                    let _ = connection.send(Message::Text(json.into())).await;
+                }
+                return;
+            }
+        };
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let conn_clone = Arc::clone(connection);
+    let forward_task = tokio::spawn(async move {
+        while let Some(msg) = progress_rx.recv().await {
+            let _ = conn_clone.send(msg).await;
+        }
+    });
+    let progress = progress::ProgressSender::new(progress_tx);
+
+    let result = commit::commit_node(request, state, progress).await;
+
+    let _ = forward_task.await;
+
+    let response = service_response(id, result, RPC_MSG_NODE_COMMIT_FAILED);
+    if let Ok(json) = serde_json::to_string(&response) {
+        let _ = connection.send(Message::Text(json.into())).await;
+    }
+}
+
 /// Handle "image.import" RPC call with streaming progress (admin-only)
 ///
 /// Expected params: ImportRequest {"model": "string", "version": "string", "src": "string", "token": "string"}
@@ -1480,6 +1603,101 @@ async fn handle_image_import_streaming(
     }
 }
 
+/// Handle "image.upload_start" RPC call — start or resume a chunked upload
+///
+/// Expected params: StartUploadRequest {"model": "string", "version": "string",
+/// "filename": "string", "size": u64, "sha256": "string", "token": "string"}
+async fn handle_image_upload_start(id: String, params: serde_json::Value) -> ServerMessage {
+    let request: data::StartUploadRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_UPLOAD_START) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = upload::start_upload(request).await;
+    service_response(id, result, RPC_MSG_IMAGE_UPLOAD_FAILED)
+}
+
+/// Handle "image.upload_cancel" RPC call — discard a chunked upload
+///
+/// Expected params: CancelUploadRequest {"upload_id": "string", "token": "string"}
+async fn handle_image_upload_cancel(id: String, params: serde_json::Value) -> ServerMessage {
+    let request: data::CancelUploadRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_UPLOAD_CANCEL) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = upload::cancel_upload(&request.upload_id).await;
+    service_response(id, result, RPC_MSG_IMAGE_UPLOAD_FAILED)
+}
+
+/// Handle "image.upload_chunk" RPC call with streaming progress (admin-only)
+///
+/// Only the final chunk streams status messages, while the image is verified
+/// and imported.
+///
+/// Expected params: UploadChunkRequest {"upload_id": "string", "offset": u64,
+/// "sha256": "string", "data": "base64", "token": "string"}
+async fn handle_image_upload_chunk_streaming(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    connection: &Arc<Connection>,
+) {
+    let request: data::UploadChunkRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_UPLOAD_CHUNK) {
+            Ok(req) => req,
+            Err(response) => {
+                if let Ok(json) = serde_json::to_string(&response) {
+                    let _ = connection.send(Message::Text(json.into())).await;
+                }
+                return;
+            }
+        };
+
+    let chunk = match base64::engine::general_purpose::STANDARD.decode(&request.data) {
+        Ok(chunk) => chunk,
+        Err(e) => {
+            send_rpc_error(
+                connection,
+                id,
+                RpcErrorCode::InvalidParams,
+                RPC_MSG_INVALID_PARAMS_UPLOAD_CHUNK.to_string(),
+                Some(format!("Chunk data is not valid base64: {}", e)),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let conn_clone = Arc::clone(connection);
+    let forward_task = tokio::spawn(async move {
+        while let Some(msg) = progress_rx.recv().await {
+            let _ = conn_clone.send(msg).await;
+        }
+    });
+    let progress = progress::ProgressSender::new(progress_tx);
+
+    let result = upload::upload_chunk(
+        &request.upload_id,
+        request.offset,
+        &request.sha256,
+        &chunk,
+        state,
+        progress,
+    )
+    .await;
+
+    let _ = forward_task.await;
+
+    let response = service_response(id, result, RPC_MSG_IMAGE_UPLOAD_FAILED);
+    if let Ok(json) = serde_json::to_string(&response) {
+        let _ = connection.send(Message::Text(json.into())).await;
+    }
+}
+
 /// Handle "image.delete" RPC call
 ///
 /// Expected params: DeleteImageRequest {"model": "string", "version": "string", "token": "string"}
@@ -1546,6 +1764,162 @@ async fn handle_image_delete(
     }
 }
 
+/// Handle "image.verify" RPC call
+///
+/// Expected params: VerifyImageRequest {"model": "string", "version": "string", "token": "string"}
+async fn handle_image_verify(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+) -> ServerMessage {
+    let request: data::VerifyImageRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_IMAGE_VERIFY) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = import::verify_image(request, state).await;
+    service_response(id, result, RPC_MSG_IMAGE_VERIFY_FAILED)
+}
+
+/// Handle "image.model_add" RPC call
+///
+/// Expected params: AddCustomModelRequest {"definition": "string", "replace": bool, "token": "string"}
+fn handle_image_model_add(id: String, params: serde_json::Value) -> ServerMessage {
+    let request: data::AddCustomModelRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_MODEL_ADD) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = custom_model::add_model(request);
+    service_response(id, result, RPC_MSG_IMAGE_MODEL_FAILED)
+}
+
+/// Handle "image.model_list" RPC call
+///
+/// Expected params: {"token": "string"}
+fn handle_image_model_list(id: String, auth: anyhow::Result<AuthContext>) -> ServerMessage {
+    if let Err(e) = authenticate(&id, "image.model_list", auth) {
+        return e;
+    }
+
+    let result = custom_model::list_models();
+    service_response(id, result, RPC_MSG_IMAGE_MODEL_FAILED)
+}
+
+/// Handle "image.model_delete" RPC call
+///
+/// Expected params: DeleteCustomModelRequest {"name": "string", "token": "string"}
+fn handle_image_model_delete(id: String, params: serde_json::Value) -> ServerMessage {
+    let request: data::DeleteCustomModelRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_MODEL_DELETE) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = custom_model::delete_model(request);
+    service_response(id, result, RPC_MSG_IMAGE_MODEL_FAILED)
+}
+
+/// Handle "image.usage" RPC call
+///
+/// Expected params: {"token": "string"}
+async fn handle_image_usage(id: String, state: &AppState) -> ServerMessage {
+    let result = image_usage::image_usage(state).await;
+    service_response(id, result, RPC_MSG_IMAGE_USAGE_FAILED)
+}
+
+/// Handle "image.prune" RPC call
+///
+/// Expected params: PruneImagesRequest {"unused_for_secs": number | null, "dry_run": bool, "token": "string"}
+async fn handle_image_prune(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth_ctx: AuthContext,
+) -> ServerMessage {
+    let request: data::PruneImagesRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_IMAGE_PRUNE) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = image_usage::prune_images(request, state).await;
+    if let Ok(response) = &result
+        && !response.dry_run
+    {
+        tracing::info!(
+            "Admin '{}' pruned {} image versions",
+            auth_ctx.username,
+            response.pruned.len()
+        );
+    }
+    service_response(id, result, RPC_MSG_IMAGE_PRUNE_FAILED)
+}
+
+/// Handle "registry.login" RPC call
+///
+/// Expected params: RegistryLoginRequest {"registry": "string", "username": "string", "password": "string", "token": "string"}
+async fn handle_registry_login(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth_ctx: AuthContext,
+) -> ServerMessage {
+    let request: data::RegistryLoginRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_REGISTRY_LOGIN) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = registry::registry_login(request, state).await;
+    if let Ok(response) = &result {
+        tracing::info!(
+            "Admin '{}' stored credentials for registry {}",
+            auth_ctx.username,
+            response.registry
+        );
+    }
+    service_response(id, result, RPC_MSG_REGISTRY_LOGIN_FAILED)
+}
+
+/// Handle "registry.list" RPC call
+///
+/// Expected params: {"token": "string"}
+async fn handle_registry_list(id: String, state: &AppState) -> ServerMessage {
+    let result = registry::list_registries(state).await;
+    service_response(id, result, RPC_MSG_REGISTRY_LIST_FAILED)
+}
+
+/// Handle "registry.logout" RPC call
+///
+/// Expected params: RegistryLogoutRequest {"registry": "string", "token": "string"}
+async fn handle_registry_logout(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth_ctx: AuthContext,
+) -> ServerMessage {
+    let request: data::RegistryLogoutRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_REGISTRY_LOGOUT) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = registry::registry_logout(request, state).await;
+    if let Ok(response) = &result
+        && response.removed
+    {
+        tracing::info!(
+            "Admin '{}' removed credentials for registry {}",
+            auth_ctx.username,
+            response.registry
+        );
+    }
+    service_response(id, result, RPC_MSG_REGISTRY_LOGOUT_FAILED)
+}
+
 /// Handle "image.set_default" RPC call
 ///
 /// Expected params: SetDefaultImageRequest {"model": "string", "version": "string", "token": "string"}
@@ -1619,9 +1993,10 @@ async fn handle_image_list(
     id: String,
     params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for image.list: {}", e);
@@ -1700,9 +2075,10 @@ async fn handle_image_show(
     id: String,
     params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for image.show: {}", e);
@@ -1933,7 +2309,7 @@ async fn handle_image_pull_streaming(
 
 /// Handle "image.download" RPC call
 ///
-/// Expected params: DownloadImageRequest {"model": "string", "version": "string", "url": "string?", "default": bool, "token": "string"}
+/// Expected params: DownloadImageRequest {"model": "string", "version": "string", "url": "string?", "default": bool, "registry_auth": {"username": "string", "password": "string"}?, "token": "string"}
 async fn handle_image_download_streaming(
     id: String,
     params: serde_json::Value,
@@ -2030,9 +2406,10 @@ async fn handle_up(
     params: serde_json::Value,
     state: &AppState,
     connection: &Arc<Connection>,
+    auth: anyhow::Result<AuthContext>,
 ) {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for up: {}", e);
@@ -2196,13 +2573,23 @@ async fn handle_auth_login(
         }
     };
 
-    // Get user from database (for authentication)
-    let user = match db::get_user_for_auth(&state.db, &login_request.username).await {
-        Ok(user) => user,
-        Err(_) => {
+    // Verify credentials against the local store or the LDAP directory
+    let user = match crate::auth::login::authenticate_password(
+        state,
+        &login_request.username,
+        &login_request.password,
+    )
+    .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => {
             // Don't reveal whether user exists or not
-            return ServerMessage::RpcResponse {
-                id,
+            tracing::warn!(
+                username = %login_request.username,
+                "Login failed: invalid credentials"
+            );
+            return ServerMessage::RpcResponse {
+                id,
                 result: None,
                 error: Some(RpcError {
                     code: RpcErrorCode::AuthInvalid,
@@ -2211,89 +2598,73 @@ async fn handle_auth_login(
                 }),
             };
         }
+        Err(e) => {
+            tracing::error!(error = %e, "Authentication error");
+            return ServerMessage::RpcResponse {
+                id,
+                result: None,
+                error: Some(RpcError {
+                    code: RpcErrorCode::InternalError,
+                    message: RPC_MSG_AUTH_ERROR.to_string(),
+                    context: Some(format!("{:?}", e)),
+                }),
+            };
+        }
     };
 
-    // Verify password
-    match password::verify_password(&login_request.password, &user.password_hash) {
-        Ok(true) => {
-            // Password is correct, create JWT token
-
-            match crate::auth::jwt::create_token(
-                &state.jwt_secret,
-                &user.username,
-                user.is_admin,
-                JWT_TOKEN_EXPIRY_SECONDS,
-            ) {
-                Ok(token) => {
-                    let expires_at = jiff::Timestamp::now().as_second() + JWT_TOKEN_EXPIRY_SECONDS;
+    login_response(id, state, &user)
+}
 
-                    let response = data::LoginResponse {
-                        token,
-                        username: user.username.clone(),
-                        is_admin: user.is_admin,
-                        expires_at,
-                    };
+/// Issue a session token for an authenticated user
+fn login_response(id: String, state: &AppState, user: &data::DbUser) -> ServerMessage {
+    match crate::auth::jwt::create_token(
+        &state.jwt_secret,
+        &user.username,
+        user.is_admin,
+        JWT_TOKEN_EXPIRY_SECONDS,
+    ) {
+        Ok(token) => {
+            let expires_at = jiff::Timestamp::now().as_second() + JWT_TOKEN_EXPIRY_SECONDS;
+
+            let response = data::LoginResponse {
+                token,
+                username: user.username.clone(),
+                is_admin: user.is_admin,
+                expires_at,
+            };
 
-                    tracing::info!(
-                        username = %user.username,
-                        is_admin = user.is_admin,
-                        "User logged in successfully"
-                    );
-
-                    match serde_json::to_value(&response) {
-                        Ok(result) => ServerMessage::RpcResponse {
-                            id,
-                            result: Some(result),
-                            error: None,
-                        },
-                        Err(e) => ServerMessage::RpcResponse {
-                            id,
-                            result: None,
-                            error: Some(RpcError {
-                                code: RpcErrorCode::InternalError,
-                                message: RPC_MSG_SERIALIZE_FAILED.to_string(),
-                                context: Some(format!("{:?}", e)),
-                            }),
-                        },
-                    }
-                }
-                Err(e) => {
-                    tracing::error!(error = %e, "Failed to create JWT token");
-                    ServerMessage::RpcResponse {
-                        id,
-                        result: None,
-                        error: Some(RpcError {
-                            code: RpcErrorCode::InternalError,
-                            message: RPC_MSG_TOKEN_CREATE_FAILED.to_string(),
-                            context: Some(format!("{:?}", e)),
-                        }),
-                    }
-                }
-            }
-        }
-        Ok(false) => {
-            tracing::warn!(
-                username = %login_request.username,
-                "Login failed: invalid password"
+            tracing::info!(
+                username = %user.username,
+                is_admin = user.is_admin,
+                auth_provider = %user.auth_provider,
+                "User logged in successfully"
             );
-            ServerMessage::RpcResponse {
-                id,
-                result: None,
-                error: Some(RpcError {
-                    code: RpcErrorCode::AuthInvalid,
-                    message: RPC_MSG_AUTH_INVALID.to_string(),
-                    context: None,
-                }),
+
+            match serde_json::to_value(&response) {
+                Ok(result) => ServerMessage::RpcResponse {
+                    id,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(e) => ServerMessage::RpcResponse {
+                    id,
+                    result: None,
+                    error: Some(RpcError {
+                        code: RpcErrorCode::InternalError,
+                        message: RPC_MSG_SERIALIZE_FAILED.to_string(),
+                        context: Some(format!("{:?}", e)),
+                    }),
+                },
             }
         }
         Err(e) => {
-            tracing::error!(error = %e, "Password verification error");
+            tracing::error!(error = %e, "Failed to create JWT token");
             ServerMessage::RpcResponse {
                 id,
                 result: None,
                 error: Some(RpcError {
                     code: RpcErrorCode::InternalError,
-                    message: RPC_MSG_AUTH_ERROR.to_string(),
+                    message: RPC_MSG_TOKEN_CREATE_FAILED.to_string(),
                     context: Some(format!("{:?}", e)),
                 }),
             }
@@ -2612,9 +2983,10 @@ async fn handle_user_passwd(
     id: String,
     params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for user.passwd: {}", e);
@@ -2764,9 +3136,10 @@ async fn handle_user_info(
     id: String,
     params: serde_json::Value,
     state: &AppState,
+    auth: anyhow::Result<AuthContext>,
 ) -> ServerMessage {
     // Authenticate the request
-    let auth_ctx = match middleware::authenticate_request(&params, state).await {
+    let auth_ctx = match auth {
         Ok(ctx) => ctx,
         Err(e) => {
             tracing::warn!("Authentication failed for user.info: {}", e);
@@ -2871,6 +3244,64 @@ async fn handle_user_info(
     }
 }
 
+/// Handle "auth.providers" RPC call
+///
+/// Expected params: {}
+fn handle_auth_providers(id: String, state: &AppState) -> ServerMessage {
+    service_response(
+        id,
+        Ok(crate::auth::login::providers(&state.config.auth)),
+        RPC_MSG_INTERNAL_ERROR,
+    )
+}
+
+/// Handle "auth.device_start" RPC call
+///
+/// Expected params: {}
+async fn handle_auth_device_start(id: String, state: &AppState) -> ServerMessage {
+    if state.config.auth.oidc.is_none() {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::InvalidRequest,
+                message: RPC_MSG_OIDC_NOT_CONFIGURED.to_string(),
+                context: None,
+            }),
+        };
+    }
+    let result = crate::auth::login::start_device_login(state).await;
+    service_response(id, result, RPC_MSG_OIDC_DEVICE_START_FAILED)
+}
+
+/// Handle "auth.device_poll" RPC call
+///
+/// Expected params: {"device_code": "string"}
+async fn handle_auth_device_poll(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+) -> ServerMessage {
+    let request: data::DeviceLoginPollRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_DEVICE_POLL) {
+            Ok(request) => request,
+            Err(e) => return e,
+        };
+    if state.config.auth.oidc.is_none() {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::InvalidRequest,
+                message: RPC_MSG_OIDC_NOT_CONFIGURED.to_string(),
+                context: None,
+            }),
+        };
+    }
+    let result = crate::auth::login::poll_device_login(state, &request.device_code).await;
+    service_response(id, result, RPC_MSG_OIDC_DEVICE_POLL_FAILED)
+}
+
 /// Handle "auth.validate" RPC call
 ///
 /// Expected params: {"token": "string"}
@@ -2895,17 +3326,28 @@ async fn handle_auth_validate(
         }
     };
 
-    // Validate token
-    match crate::auth::jwt::validate_token(&state.jwt_secret, &validate_request.token) {
-        Ok(claims) => {
+    // Validate token, either a login session JWT or a personal API token
+    let validated = if is_api_token(&validate_request.token) {
+        middleware::validate_api_token(&validate_request.token, state)
+            .await
+            .map(|(auth_ctx, expires_at)| {
+                (auth_ctx.username, auth_ctx.is_admin, expires_at.as_second())
+            })
+    } else {
+        crate::auth::jwt::validate_token(&state.jwt_secret, &validate_request.token)
+            .map(|claims| (claims.sub, claims.is_admin, claims.exp))
+    };
+
+    match validated {
+        Ok((username, is_admin, expires_at)) => {
             let response = data::ValidateResponse {
                 valid: true,
-                username: Some(claims.sub.clone()),
-                is_admin: Some(claims.is_admin),
-                expires_at: Some(claims.exp),
+                username: Some(username.clone()),
+                is_admin: Some(is_admin),
+                expires_at: Some(expires_at),
             };
 
-            tracing::debug!(username = %claims.sub, "Token validated successfully");
+            tracing::debug!(username = %username, "Token validated successfully");
 
             match serde_json::to_value(&response) {
                 Ok(result) => ServerMessage::RpcResponse {
@@ -2953,3 +3395,630 @@ async fn handle_auth_validate(
         }
     }
 }
+
+/// Check the request authenticated, returning the auth-required error response on failure.
+fn authenticate(
+    id: &str,
+    method: &str,
+    auth: anyhow::Result<AuthContext>,
+) -> Result<AuthContext, ServerMessage> {
+    auth.map_err(|e| {
+        tracing::warn!("Authentication failed for {}: {}", method, e);
+        ServerMessage::RpcResponse {
+            id: id.to_string(),
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::AuthRequired,
+                message: RPC_MSG_AUTH_REQUIRED.to_string(),
+                context: Some(format!("{:?}", e)),
+            }),
+        }
+    })
+}
+
+/// Parse request params, returning the invalid-params error response on failure.
+fn parse_params<T: serde::de::DeserializeOwned>(
+    id: &str,
+    params: serde_json::Value,
+    invalid_msg: &str,
+) -> Result<T, ServerMessage> {
+    serde_json::from_value(params).map_err(|e| ServerMessage::RpcResponse {
+        id: id.to_string(),
+        result: None,
+        error: Some(RpcError {
+            code: RpcErrorCode::InvalidParams,
+            message: invalid_msg.to_string(),
+            context: Some(format!("{:?}", e)),
+        }),
+    })
+}
+
+/// Convert a service result into an RPC response.
+fn service_response<T: serde::Serialize>(
+    id: String,
+    result: anyhow::Result<T>,
+    failed_msg: &str,
+) -> ServerMessage {
+    match result.map(|response| serde_json::to_value(&response)) {
+        Ok(Ok(result)) => ServerMessage::RpcResponse {
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Ok(Err(e)) => ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::InternalError,
+                message: RPC_MSG_SERIALIZE_FAILED.to_string(),
+                context: Some(format!("{:?}", e)),
+            }),
+        },
+        Err(e) => ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::ServerError,
+                message: failed_msg.to_string(),
+                context: Some(format!("{:?}", e)),
+            }),
+        },
+    }
+}
+
+/// Handle "lab.shares" RPC call — list the shares of a lab
+///
+/// Expected params: ListLabSharesRequest {"lab_id": "string", "token": "string"}
+async fn handle_lab_shares(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "lab.shares", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::ListLabSharesRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_LAB_ID) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    // Check authorization: user must have access to the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Viewer,
+        "list shares of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = share::list_lab_shares(&request.lab_id, state).await;
+    service_response(id, result, RPC_MSG_LAB_SHARE_FAILED)
+}
+
+/// Handle "lab.leases" RPC call — list the DHCP leases of a lab
+///
+/// Expected params: LabLeasesRequest {"lab_id": "string", "token": "string"}
+async fn handle_lab_leases(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "lab.leases", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::LabLeasesRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_LAB_ID) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Viewer,
+        "list leases of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = leases::lab_leases(&request.lab_id, state).await;
+    service_response(id, result, RPC_MSG_LAB_LEASES_FAILED)
+}
+
+/// Handle "lab.verify_cabling" RPC call — compare the LLDP/CDP neighbours
+/// the lab's nodes advertise with the manifest
+///
+/// Expected params: VerifyCablingRequest {"lab_id": "string", "wait_secs": number (optional),
+/// "token": "string"}
+async fn handle_lab_verify_cabling(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "lab.verify_cabling", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::VerifyCablingRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_VERIFY_CABLING) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Viewer,
+        "verify the cabling of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = cabling::verify_cabling(&request, state).await;
+    service_response(id, result, RPC_MSG_VERIFY_CABLING_FAILED)
+}
+
+/// Handle "link.stats" RPC call — read the traffic counters of a lab's links
+///
+/// Expected params: LinkStatsRequest {"lab_id": "string", "token": "string"}
+async fn handle_link_stats(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "link.stats", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::LinkStatsRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_LAB_ID) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Viewer,
+        "read link stats of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = link_stats::link_stats(&request.lab_id, state).await;
+    service_response(id, result, RPC_MSG_LINK_STATS_FAILED)
+}
+
+/// Handle "link.mirror_add" and "link.mirror_remove" RPC calls — start or stop
+/// copying the traffic of a link interface to a mirror port
+///
+/// Expected params: LinkMirrorRequest {"lab_id": "string", "source": "node::interface",
+/// "destination": "node::interface", "direction": "ingress" | "egress" | "both",
+/// "token": "string"}
+async fn handle_link_mirror(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    action: MirrorAction,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let method = match action {
+        MirrorAction::Add => "link.mirror_add",
+        MirrorAction::Remove => "link.mirror_remove",
+    };
+    let auth_ctx = match authenticate(&id, method, auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::LinkMirrorRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_LINK_MIRROR) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Operator,
+        "mirror links of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = match action {
+        MirrorAction::Add => mirror::mirror_add(&request, state).await,
+        MirrorAction::Remove => mirror::mirror_remove(&request, state).await,
+    };
+    service_response(id, result, RPC_MSG_LINK_MIRROR_FAILED)
+}
+
+/// Handle "link.update_filter" RPC call — replace the layer 2 filter of a running link
+///
+/// Expected params: LinkFilterRequest {"lab_id": "string", "link": "node::interface",
+/// "filter": {"drop": [...], "allow": [...]}, "token": "string"}
+async fn handle_link_update_filter(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "link.update_filter", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::LinkFilterRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_LINK_FILTER) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Operator,
+        "filter links of",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = link_filter::update_link_filter(&request, state).await;
+    service_response(id, result, RPC_MSG_LINK_FILTER_FAILED)
+}
+
+/// Handle "lab.share" RPC call — share a lab with a user or a team
+///
+/// Expected params: ShareLabRequest {"lab_id": "string", "username" | "team": "string",
+/// "role": "viewer" | "operator", "token": "string"}
+async fn handle_lab_share(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "lab.share", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::ShareLabRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_SHARE_LAB) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    // Check authorization: user must own the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Owner,
+        "share",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB_SHARE,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = share::share_lab(&request, state).await;
+    service_response(id, result, RPC_MSG_LAB_SHARE_FAILED)
+}
+
+/// Handle "lab.unshare" RPC call — remove a lab share
+///
+/// Expected params: UnshareLabRequest {"lab_id": "string", "username" | "team": "string",
+/// "token": "string"}
+async fn handle_lab_unshare(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "lab.unshare", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::UnshareLabRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_UNSHARE_LAB) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    // Check authorization: user must own the lab or be an admin
+    if let Err(error) = require_lab_role(
+        &auth_ctx,
+        &request.lab_id,
+        LabRole::Owner,
+        "unshare",
+        state,
+        RPC_MSG_ACCESS_DENIED_LAB_SHARE,
+    )
+    .await
+    {
+        return ServerMessage::RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        };
+    }
+
+    let result = share::unshare_lab(&request, state).await;
+    service_response(id, result, RPC_MSG_LAB_SHARE_FAILED)
+}
+
+/// Handle "team.create" RPC call — the caller becomes the team owner
+///
+/// Expected params: CreateTeamRequest {"name": "string", "members": ["string"], "token": "string"}
+async fn handle_team_create(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "team.create", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::CreateTeamRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_CREATE_TEAM) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result =
+        share::create_team(&request.name, &request.members, &auth_ctx.username, state).await;
+    if result.is_ok() {
+        tracing::info!(owner = %auth_ctx.username, team = %request.name, "Team created");
+    }
+    service_response(id, result, RPC_MSG_TEAM_CREATE_FAILED)
+}
+
+/// Handle "team.list" RPC call
+///
+/// Expected params: ListTeamsRequest {"token": "string"}
+async fn handle_team_list(
+    id: String,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    if let Err(e) = authenticate(&id, "team.list", auth) {
+        return e;
+    }
+
+    let result = db::list_teams(&state.db)
+        .await
+        .map(|teams| data::ListTeamsResponse { teams });
+    service_response(id, result, RPC_MSG_TEAM_LIST_FAILED)
+}
+
+/// Handle "token.create" RPC call — create a personal API token for the caller
+///
+/// Expected params: CreateApiTokenRequest {"name": "string", "scopes": ["string"],
+/// "expires_in_days": number, "token": "string"}
+async fn handle_token_create(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "token.create", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::CreateApiTokenRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_CREATE_API_TOKEN) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = api_token::create_api_token(
+        &request.name,
+        &request.scopes,
+        request.expires_in_days,
+        &auth_ctx.username,
+        state,
+    )
+    .await;
+    service_response(id, result, RPC_MSG_API_TOKEN_CREATE_FAILED)
+}
+
+/// Handle "token.list" RPC call — list the caller's API tokens
+///
+/// Expected params: ListApiTokensRequest {"token": "string"}
+async fn handle_token_list(
+    id: String,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "token.list", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+
+    let result = api_token::list_api_tokens(&auth_ctx.username, state).await;
+    service_response(id, result, RPC_MSG_API_TOKEN_LIST_FAILED)
+}
+
+/// Handle "token.revoke" RPC call — revoke one of the caller's API tokens
+///
+/// Expected params: RevokeApiTokenRequest {"name": "string", "token": "string"}
+async fn handle_token_revoke(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "token.revoke", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::RevokeApiTokenRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_REVOKE_API_TOKEN) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+
+    let result = api_token::revoke_api_token(&request.name, &auth_ctx.username, state).await;
+    service_response(id, result, RPC_MSG_API_TOKEN_REVOKE_FAILED)
+}
+
+/// Check that the caller owns the team or is an admin.
+async fn require_team_owner(
+    id: &str,
+    auth_ctx: &AuthContext,
+    name: &str,
+    state: &AppState,
+) -> Result<(), ServerMessage> {
+    let owner = db::get_team_owner_username(&state.db, name)
+        .await
+        .map_err(|e| ServerMessage::RpcResponse {
+            id: id.to_string(),
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::NotFound,
+                message: format!("Team not found: {}", name),
+                context: Some(format!("{:?}", e)),
+            }),
+        })?;
+
+    if !auth_ctx.can_access(&owner) {
+        tracing::warn!(
+            "User '{}' attempted to modify team '{}' owned by '{}'",
+            auth_ctx.username,
+            name,
+            owner
+        );
+        return Err(ServerMessage::RpcResponse {
+            id: id.to_string(),
+            result: None,
+            error: Some(RpcError {
+                code: RpcErrorCode::AccessDenied,
+                message: RPC_MSG_ACCESS_DENIED_TEAM.to_string(),
+                context: None,
+            }),
+        });
+    }
+
+    Ok(())
+}
+
+/// Handle "team.update" RPC call — add and remove team members
+///
+/// Expected params: UpdateTeamMembersRequest {"name": "string", "add": ["string"],
+/// "remove": ["string"], "token": "string"}
+async fn handle_team_update(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "team.update", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::UpdateTeamMembersRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_UPDATE_TEAM) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+    if let Err(e) = require_team_owner(&id, &auth_ctx, &request.name, state).await {
+        return e;
+    }
+
+    let result = share::update_team_members(&request, state).await;
+    service_response(id, result, RPC_MSG_TEAM_UPDATE_FAILED)
+}
+
+/// Handle "team.delete" RPC call
+///
+/// Expected params: DeleteTeamRequest {"name": "string", "token": "string"}
+async fn handle_team_delete(
+    id: String,
+    params: serde_json::Value,
+    state: &AppState,
+    auth: anyhow::Result<AuthContext>,
+) -> ServerMessage {
+    let auth_ctx = match authenticate(&id, "team.delete", auth) {
+        Ok(ctx) => ctx,
+        Err(e) => return e,
+    };
+    let request: data::DeleteTeamRequest =
+        match parse_params(&id, params, RPC_MSG_INVALID_PARAMS_DELETE_TEAM) {
+            Ok(req) => req,
+            Err(e) => return e,
+        };
+    if let Err(e) = require_team_owner(&id, &auth_ctx, &request.name, state).await {
+        return e;
+    }
+
+    let result =
+        share::delete_team(&request.name, state)
+            .await
+            .map(|()| data::DeleteTeamResponse {
+                success: true,
+                name: request.name.clone(),
+            });
+    if result.is_ok() {
+        tracing::info!(user = %auth_ctx.username, team = %request.name, "Team deleted");
+    }
+    service_response(id, result, RPC_MSG_TEAM_DELETE_FAILED)
+}
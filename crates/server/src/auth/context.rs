@@ -1,18 +1,50 @@
 use serde::{Deserialize, Serialize};
+use shared::data::{LabRole, TokenScope};
 
-/// Authentication context extracted from a validated JWT token
+/// Authentication context extracted from a validated JWT or API token
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthContext {
     /// Username of the authenticated user
     pub username: String,
     /// Whether the user is an admin
     pub is_admin: bool,
+    /// Scopes of the API token used to authenticate (`None` for a login session)
+    pub scopes: Option<Vec<TokenScope>>,
 }
 
 impl AuthContext {
-    /// Create a new AuthContext
+    /// Create a new AuthContext for a login session
     pub fn new(username: String, is_admin: bool) -> Self {
-        Self { username, is_admin }
+        Self {
+            username,
+            is_admin,
+            scopes: None,
+        }
+    }
+
+    /// Create a new AuthContext for a request authenticated with an API token
+    pub fn from_api_token(username: String, is_admin: bool, scopes: Vec<TokenScope>) -> Self {
+        Self {
+            username,
+            is_admin,
+            scopes: Some(scopes),
+        }
+    }
+
+    /// Check if the credentials used for the request allow an operation
+    ///
+    /// `required` is the operation's token scope from the API spec (`None`
+    /// if the operation cannot be used with API tokens).
+    ///
+    /// Returns true if:
+    /// - The user authenticated with a login session (sessions are not scoped), OR
+    /// - The API token has a scope covering the required scope
+    pub fn allows_token_scope(&self, required: Option<TokenScope>) -> bool {
+        match (&self.scopes, required) {
+            (None, _) => true,
+            (Some(scopes), Some(required)) => TokenScope::is_granted(scopes, required),
+            (Some(_), None) => false,
+        }
     }
 
     /// Check if the user can access a resource owned by the specified username
@@ -23,6 +55,18 @@ impl AuthContext {
     pub fn can_access(&self, owner_username: &str) -> bool {
         self.is_admin || self.username == owner_username
     }
+
+    /// Check if the user has at least the required role on a lab
+    ///
+    /// `role` is the user's own role on the lab as resolved from ownership
+    /// and shares (`None` if the lab is not shared with them).
+    ///
+    /// Returns true if:
+    /// - The user is an admin (has every role on all labs), OR
+    /// - The user's role is equal to or higher than the required role
+    pub fn has_lab_role(&self, role: Option<LabRole>, required: LabRole) -> bool {
+        self.is_admin || role.is_some_and(|role| role >= required)
+    }
 }
 
 #[cfg(test)]
@@ -50,4 +94,40 @@ mod tests {
         assert!(!ctx.can_access("bob"));
         assert!(!ctx.can_access("charlie"));
     }
+
+    #[test]
+    fn test_lab_role_hierarchy() {
+        let ctx = AuthContext::new("alice".to_string(), false);
+        assert!(ctx.has_lab_role(Some(LabRole::Owner), LabRole::Owner));
+        assert!(ctx.has_lab_role(Some(LabRole::Owner), LabRole::Viewer));
+        assert!(ctx.has_lab_role(Some(LabRole::Operator), LabRole::Viewer));
+        assert!(ctx.has_lab_role(Some(LabRole::Operator), LabRole::Operator));
+        assert!(!ctx.has_lab_role(Some(LabRole::Operator), LabRole::Owner));
+        assert!(!ctx.has_lab_role(Some(LabRole::Viewer), LabRole::Operator));
+        assert!(!ctx.has_lab_role(None, LabRole::Viewer));
+    }
+
+    #[test]
+    fn test_admin_has_every_lab_role() {
+        let ctx = AuthContext::new("admin".to_string(), true);
+        assert!(ctx.has_lab_role(None, LabRole::Owner));
+        assert!(ctx.has_lab_role(Some(LabRole::Viewer), LabRole::Operator));
+    }
+
+    #[test]
+    fn test_session_allows_every_operation() {
+        let ctx = AuthContext::new("alice".to_string(), false);
+        assert!(ctx.allows_token_scope(None));
+        assert!(ctx.allows_token_scope(Some(TokenScope::ImageAdmin)));
+    }
+
+    #[test]
+    fn test_api_token_limited_to_scopes() {
+        let ctx =
+            AuthContext::from_api_token("ci".to_string(), false, vec![TokenScope::LabOperate]);
+        assert!(ctx.allows_token_scope(Some(TokenScope::ReadOnly)));
+        assert!(ctx.allows_token_scope(Some(TokenScope::LabOperate)));
+        assert!(!ctx.allows_token_scope(Some(TokenScope::ImageAdmin)));
+        assert!(!ctx.allows_token_scope(None));
+    }
 }
@@ -1,3 +1,5 @@
+use shared::konst::OIDC_LOGIN_STATE_EXPIRY_SECONDS;
+
 /// Cookie name for authentication token
 pub const AUTH_COOKIE_NAME: &str = "sherpa_auth";
 
@@ -7,6 +9,9 @@ pub const COOKIE_MAX_AGE_NORMAL: i64 = 7 * 24 * 60 * 60; // 7 days in seconds
 /// Cookie max-age for "remember me" sessions (30 days)
 pub const COOKIE_MAX_AGE_REMEMBER: i64 = 30 * 24 * 60 * 60; // 30 days in seconds
 
+/// Cookie name for a pending OIDC login
+pub const OIDC_STATE_COOKIE_NAME: &str = "sherpa_oidc";
+
 /// Creates an authentication cookie with the JWT token.
 ///
 /// The cookie is:
@@ -49,6 +54,45 @@ pub fn create_clear_cookie() -> String {
     )
 }
 
+/// Creates a cookie holding the signed state of a pending OIDC login.
+///
+/// The cookie is scoped to the OIDC routes and uses SameSite=Lax, because the
+/// identity provider redirects back to the callback with a cross-site request.
+///
+/// # Arguments
+/// * `login_state` - Signed login state from `oidc::LoginState::encode`
+///
+/// # Returns
+/// A Set-Cookie header value string
+pub fn create_oidc_state_cookie(login_state: &str) -> String {
+    format!(
+        "{}={}; Path=/auth/oidc; HttpOnly; Secure; SameSite=Lax; Max-Age={}",
+        OIDC_STATE_COOKIE_NAME, login_state, OIDC_LOGIN_STATE_EXPIRY_SECONDS
+    )
+}
+
+/// Creates a cookie that clears the OIDC login state cookie.
+///
+/// # Returns
+/// A Set-Cookie header value string that clears the OIDC state cookie
+pub fn create_clear_oidc_state_cookie() -> String {
+    format!(
+        "{}=; Path=/auth/oidc; HttpOnly; Secure; SameSite=Lax; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+        OIDC_STATE_COOKIE_NAME
+    )
+}
+
+/// Extracts the OIDC login state from a Cookie header value.
+///
+/// # Returns
+/// The signed login state if found, None otherwise
+pub fn extract_oidc_state_from_cookie(cookie_header: &str) -> Option<String> {
+    cookie_header.split(';').find_map(|cookie| {
+        let (name, value) = cookie.trim().split_once('=')?;
+        (name == OIDC_STATE_COOKIE_NAME && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
 /// Extract every authentication token from a Cookie header.
 #[tracing::instrument(level = "debug", skip(cookie_header))]
 pub(crate) fn extract_tokens_from_cookie(cookie_header: &str) -> Vec<&str> {
@@ -145,6 +189,24 @@ mod tests {
         assert_eq!(token, None);
     }
 
+    #[test]
+    fn test_oidc_state_cookie_round_trip() {
+        let cookie = create_oidc_state_cookie("signed_state");
+
+        assert!(cookie.contains("sherpa_oidc=signed_state"));
+        assert!(cookie.contains("Path=/auth/oidc"));
+        assert!(cookie.contains("SameSite=Lax"));
+        assert!(cookie.contains("HttpOnly"));
+
+        let cookie_header = "sherpa_auth=my_token; sherpa_oidc=signed_state";
+        assert_eq!(
+            extract_oidc_state_from_cookie(cookie_header),
+            Some("signed_state".to_string())
+        );
+        assert_eq!(extract_oidc_state_from_cookie("sherpa_auth=my_token"), None);
+        assert!(create_clear_oidc_state_cookie().contains("Max-Age=0"));
+    }
+
     #[test]
     fn test_extract_token_from_cookie_empty() {
         let token = extract_token_from_cookie("");
@@ -58,12 +58,14 @@ pub fn load_or_generate_secret() -> Result<Vec<u8>> {
     }
 }
 
-/// Generate a new secret and save it to the specified path.
-fn generate_and_save_secret(secret_path: &Path) -> Result<Vec<u8>> {
+/// Generate a new 32-byte secret and save it to the specified path.
+///
+/// Also used for the registry credential key, which lives next to the JWT secret.
+pub(crate) fn generate_and_save_secret(secret_path: &Path) -> Result<Vec<u8>> {
     // Create parent directory with 0700 permissions
     let parent_dir = secret_path
         .parent()
-        .context("Failed to get parent directory of JWT secret path")?;
+        .context("Failed to get parent directory of secret path")?;
 
     if !parent_dir.exists() {
         fs::create_dir_all(parent_dir).context("Failed to create .secret directory")?;
@@ -85,18 +87,23 @@ fn generate_and_save_secret(secret_path: &Path) -> Result<Vec<u8>> {
     rand::thread_rng().fill_bytes(&mut secret);
 
     // Write secret to file
-    fs::write(secret_path, &secret).context("Failed to write JWT secret file")?;
+    fs::write(secret_path, &secret).context(format!(
+        "Failed to write secret file {}",
+        secret_path.display()
+    ))?;
 
     // Set file permissions to 0600 (owner read/write only)
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let perms = fs::Permissions::from_mode(0o600);
-        fs::set_permissions(secret_path, perms)
-            .context("Failed to set JWT secret file permissions")?;
+        fs::set_permissions(secret_path, perms).context("Failed to set secret file permissions")?;
     }
 
-    info!("Successfully generated and saved new JWT secret");
+    info!(
+        "Successfully generated and saved new secret at {}",
+        secret_path.display()
+    );
     Ok(secret)
 }
 
@@ -0,0 +1,212 @@
+//! LDAP bind authentication.
+//!
+//! A user is looked up under the configured base DN, then authenticated by
+//! binding as the user's DN with the supplied password. Sherpa never stores
+//! the password, and group membership from the directory decides admin
+//! privileges on every login.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use ldap3::{LdapConnAsync, LdapConnSettings, Scope, SearchEntry, ldap_escape};
+use shared::data::LdapConfig;
+use tracing::instrument;
+
+/// LDAP result code for a failed bind (RFC 4511)
+const LDAP_INVALID_CREDENTIALS: u32 = 49;
+
+/// A user authenticated by the LDAP directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LdapUser {
+    pub username: String,
+    pub is_admin: bool,
+}
+
+/// Build the user search filter for a login name.
+///
+/// The login name is escaped so it cannot change the meaning of the filter.
+fn user_filter(config: &LdapConfig, username: &str) -> String {
+    config
+        .user_filter
+        .replace("{username}", &ldap_escape(username))
+}
+
+/// Check whether any of the user's groups is an admin group.
+///
+/// DNs are compared case-insensitively, as most directories treat them.
+fn is_admin_member(groups: &[String], admin_groups: &[String]) -> bool {
+    groups.iter().any(|group| {
+        admin_groups
+            .iter()
+            .any(|admin| admin.trim().eq_ignore_ascii_case(group.trim()))
+    })
+}
+
+/// Authenticate a user with an LDAP bind.
+///
+/// # Returns
+/// - `Ok(Some(user))` if the directory accepted the credentials
+/// - `Ok(None)` if the user doesn't exist, is ambiguous or the password is wrong
+///
+/// # Errors
+/// Returns an error if the directory cannot be reached or the service
+/// account bind or user search fails
+#[instrument(skip(config, password), fields(url = %config.url))]
+pub async fn authenticate(
+    config: &LdapConfig,
+    username: &str,
+    password: &str,
+) -> Result<Option<LdapUser>> {
+    // An empty password would be an unauthenticated bind, which succeeds
+    if password.is_empty() {
+        return Ok(None);
+    }
+
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let settings = LdapConnSettings::new()
+        .set_conn_timeout(timeout)
+        .set_starttls(config.starttls)
+        .set_no_tls_verify(config.insecure);
+
+    let (conn, mut ldap) = LdapConnAsync::with_settings(settings, &config.url)
+        .await
+        .context(format!("Failed to connect to LDAP server {}", config.url))?;
+    ldap3::drive!(conn);
+
+    if let Some(bind_dn) = &config.bind_dn {
+        ldap.with_timeout(timeout)
+            .simple_bind(bind_dn, config.bind_password.as_deref().unwrap_or_default())
+            .await
+            .context("LDAP service account bind failed")?
+            .success()
+            .context("LDAP service account bind was rejected")?;
+    }
+
+    let (entries, _) = ldap
+        .with_timeout(timeout)
+        .search(
+            &config.user_base_dn,
+            Scope::Subtree,
+            &user_filter(config, username),
+            vec![config.group_attribute.as_str()],
+        )
+        .await
+        .context("LDAP user search failed")?
+        .success()
+        .context("LDAP user search was rejected")?;
+
+    let entry = match entries.len() {
+        1 => SearchEntry::construct(entries.into_iter().next().context("LDAP entry missing")?),
+        0 => {
+            tracing::debug!(username = %username, "LDAP user not found");
+            return Ok(None);
+        }
+        count => {
+            tracing::warn!(
+                username = %username,
+                count,
+                "LDAP user filter matched more than one entry"
+            );
+            return Ok(None);
+        }
+    };
+
+    let bind = ldap
+        .with_timeout(timeout)
+        .simple_bind(&entry.dn, password)
+        .await
+        .context("LDAP user bind failed")?;
+    if bind.rc == LDAP_INVALID_CREDENTIALS {
+        tracing::debug!(username = %username, "LDAP bind rejected the password");
+        return Ok(None);
+    }
+    bind.success().context("LDAP user bind was rejected")?;
+
+    if let Err(e) = ldap.unbind().await {
+        tracing::debug!(error = %e, "LDAP unbind failed");
+    }
+
+    let groups: Vec<String> = entry
+        .attrs
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case(&config.group_attribute))
+        .flat_map(|(_, values)| values.iter().cloned())
+        .collect();
+
+    Ok(Some(LdapUser {
+        username: username.to_string(),
+        is_admin: is_admin_member(&groups, &config.admin_groups),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ldap_config() -> LdapConfig {
+        toml::from_str(
+            r#"
+            url = "ldap://localhost:389"
+            user_base_dn = "ou=people,dc=example,dc=com"
+            admin_groups = ["cn=sherpa-admins,ou=groups,dc=example,dc=com"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_user_filter_escapes_username() {
+        let config = ldap_config();
+        assert_eq!(user_filter(&config, "alice"), "(uid=alice)");
+        assert_eq!(user_filter(&config, "*)(uid=*"), r"(uid=\2a\29\28uid=\2a)");
+    }
+
+    #[test]
+    fn test_is_admin_member_ignores_dn_case() {
+        let config = ldap_config();
+        let groups = vec![
+            "cn=students,ou=groups,dc=example,dc=com".to_string(),
+            "CN=Sherpa-Admins,OU=Groups,DC=example,DC=com".to_string(),
+        ];
+        assert!(is_admin_member(&groups, &config.admin_groups));
+        assert!(!is_admin_member(&groups[..1], &config.admin_groups));
+        assert!(!is_admin_member(&groups, &[]));
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_empty_password() {
+        // Never reaches the network
+        let result = authenticate(&ldap_config(), "alice", "").await.unwrap();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires a local LDAP container, see docs/SERVER.md
+    async fn test_authenticate_against_local_directory() {
+        let config: LdapConfig = toml::from_str(
+            r#"
+            url = "ldap://localhost:1389"
+            bind_dn = "cn=admin,dc=example,dc=org"
+            bind_password = "adminpassword"
+            user_base_dn = "ou=users,dc=example,dc=org"
+            user_filter = "(cn={username})"
+            "#,
+        )
+        .unwrap();
+
+        let user = authenticate(&config, "user01", "password1").await.unwrap();
+        assert_eq!(
+            user,
+            Some(LdapUser {
+                username: "user01".to_string(),
+                is_admin: false,
+            })
+        );
+        assert!(
+            authenticate(&config, "user01", "wrong")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
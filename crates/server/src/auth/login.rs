@@ -0,0 +1,269 @@
+//! Login across the configured authentication providers.
+//!
+//! Local users are verified against their password hash, LDAP users with a
+//! bind against the directory. Users authenticated by LDAP or OIDC for the
+//! first time are provisioned into the `user` table.
+
+use anyhow::{Context, Result, anyhow};
+use jiff::Timestamp;
+use shared::auth::password;
+use shared::data::{
+    AuthConfig, AuthProvider, AuthProvidersResponse, DbUser, DeviceLoginPollResponse,
+    DeviceLoginStartResponse, DeviceLoginStatus, LoginResponse, OidcConfig,
+};
+use shared::konst::{JWT_TOKEN_EXPIRY_SECONDS, RPC_MSG_OIDC_NOT_CONFIGURED};
+use tracing::instrument;
+
+use crate::auth::oidc::{DevicePoll, OidcClient};
+use crate::auth::{jwt, ldap, oidc};
+use crate::daemon::state::AppState;
+
+/// Report which login methods the server offers.
+pub fn providers(auth: &AuthConfig) -> AuthProvidersResponse {
+    AuthProvidersResponse {
+        password: auth.local || auth.ldap.is_some(),
+        ldap: auth.ldap.is_some(),
+        oidc: auth.oidc.is_some(),
+    }
+}
+
+/// Get the OIDC configuration, or fail if single sign-on is disabled.
+pub fn oidc_config(auth: &AuthConfig) -> Result<&OidcConfig> {
+    auth.oidc
+        .as_ref()
+        .ok_or_else(|| anyhow!(RPC_MSG_OIDC_NOT_CONFIGURED))
+}
+
+/// Authenticate a username and password.
+///
+/// # Returns
+/// - `Ok(Some(user))` if the credentials are valid
+/// - `Ok(None)` if the credentials are invalid, or the user must log in
+///   through another provider
+///
+/// # Errors
+/// Returns an error if the user lookup fails, password verification fails,
+/// the LDAP directory cannot be reached, or the user cannot be provisioned
+#[instrument(skip(state, password))]
+pub async fn authenticate_password(
+    state: &AppState,
+    username: &str,
+    password: &str,
+) -> Result<Option<DbUser>> {
+    let auth = &state.config.auth;
+    // Only a missing user falls through to LDAP; a failed lookup fails the login
+    let existing = db::get_user_for_auth(&state.db, username)
+        .await
+        .context("Failed to look up user")?;
+
+    match existing {
+        Some(user) if user.auth_provider == AuthProvider::Local => {
+            if !auth.local {
+                tracing::debug!(username = %username, "Local password login is disabled");
+                return Ok(None);
+            }
+            let is_valid = password::verify_password(password, &user.password_hash)?;
+            Ok(is_valid.then_some(user))
+        }
+        // OIDC users never have a password Sherpa can check
+        Some(user) if user.auth_provider == AuthProvider::Oidc => Ok(None),
+        _ => {
+            let Some(ldap_config) = &auth.ldap else {
+                return Ok(None);
+            };
+            let Some(ldap_user) = ldap::authenticate(ldap_config, username, password).await? else {
+                return Ok(None);
+            };
+            let user = db::provision_external_user(
+                &state.db,
+                &ldap_user.username,
+                AuthProvider::Ldap,
+                ldap_user.is_admin,
+            )
+            .await
+            .context("Failed to provision LDAP user")?;
+            Ok(Some(user))
+        }
+    }
+}
+
+/// Provision a user authenticated by the OIDC provider.
+///
+/// # Errors
+/// Returns an error if the username belongs to a local or LDAP user, or the
+/// user cannot be stored
+pub async fn provision_oidc_user(state: &AppState, user: &oidc::OidcUser) -> Result<DbUser> {
+    db::provision_external_user(&state.db, &user.username, AuthProvider::Oidc, user.is_admin)
+        .await
+        .context("Failed to provision OIDC user")
+}
+
+/// Start an OIDC device login for the CLI.
+///
+/// # Errors
+/// Returns an error if OIDC is not configured or the provider rejects the
+/// request
+pub async fn start_device_login(state: &AppState) -> Result<DeviceLoginStartResponse> {
+    let config = oidc_config(&state.config.auth)?;
+    OidcClient::discover(config)
+        .await?
+        .start_device_login()
+        .await
+}
+
+/// Poll an OIDC device login, issuing a session token once it completes.
+///
+/// # Errors
+/// Returns an error if OIDC is not configured, the login was denied or
+/// expired, or the user cannot be provisioned
+pub async fn poll_device_login(
+    state: &AppState,
+    device_code: &str,
+) -> Result<DeviceLoginPollResponse> {
+    let config = oidc_config(&state.config.auth)?;
+    let poll = OidcClient::discover(config)
+        .await?
+        .poll_device_login(device_code)
+        .await?;
+
+    let oidc_user = match poll {
+        DevicePoll::Pending => {
+            return Ok(DeviceLoginPollResponse {
+                status: DeviceLoginStatus::Pending,
+                login: None,
+            });
+        }
+        DevicePoll::SlowDown => {
+            return Ok(DeviceLoginPollResponse {
+                status: DeviceLoginStatus::SlowDown,
+                login: None,
+            });
+        }
+        DevicePoll::Complete(user) => user,
+    };
+
+    let user = provision_oidc_user(state, &oidc_user).await?;
+    let token = jwt::create_token(
+        &state.jwt_secret,
+        &user.username,
+        user.is_admin,
+        JWT_TOKEN_EXPIRY_SECONDS,
+    )
+    .context("Failed to create authentication token")?;
+
+    tracing::info!(
+        event = "auth.login_succeeded",
+        channel = "oidc_device",
+        username = %user.username,
+        is_admin = user.is_admin,
+        "User logged in successfully"
+    );
+
+    Ok(DeviceLoginPollResponse {
+        status: DeviceLoginStatus::Complete,
+        login: Some(LoginResponse {
+            token,
+            username: user.username,
+            is_admin: user.is_admin,
+            expires_at: Timestamp::now().as_second() + JWT_TOKEN_EXPIRY_SECONDS,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
+    use shared::data::DatabaseEngine;
+    use shared::util;
+
+    /// State over a fresh in-memory database, with only local login.
+    async fn state() -> AppState {
+        let db = db::connect_embedded(DatabaseEngine::Memory, "", "login", "test")
+            .await
+            .expect("in-memory database opens");
+        db::apply_schema(&db).await.expect("schema applies");
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default(),
+            FakeContainerRuntime::default(),
+            FakeHostNetwork::default(),
+        );
+        AppState::for_tests(db, util::default_config(), fakes.runtime())
+    }
+
+    #[test]
+    fn test_providers_reports_configured_methods() {
+        let auth = AuthConfig::default();
+        let response = providers(&auth);
+        assert!(response.password);
+        assert!(!response.ldap);
+        assert!(!response.oidc);
+        assert!(oidc_config(&auth).is_err());
+
+        let auth: AuthConfig = toml::from_str(
+            r#"
+            local = false
+
+            [oidc]
+            issuer_url = "https://sso.example.com"
+            client_id = "sherpa"
+            redirect_url = "https://sherpa.example.com/auth/oidc/callback"
+            "#,
+        )
+        .unwrap();
+        let response = providers(&auth);
+        assert!(!response.password);
+        assert!(response.oidc);
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_password_local_user() {
+        let state = state().await;
+        db::create_user(
+            &state.db,
+            "alice".to_string(),
+            "TestPass123!",
+            false,
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let user = authenticate_password(&state, "alice", "TestPass123!")
+            .await
+            .unwrap();
+        assert_eq!(user.unwrap().username, "alice");
+        assert!(
+            authenticate_password(&state, "alice", "WrongPass123!")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        // Without LDAP, a missing user is a rejected login
+        assert!(
+            authenticate_password(&state, "nobody", "TestPass123!")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_password_fails_on_lookup_error() {
+        let state = state().await;
+        // A user record that cannot be decoded makes the lookup fail
+        state
+            .db
+            .query("REMOVE TABLE user; CREATE user SET username = 'alice';")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        assert!(
+            authenticate_password(&state, "alice", "TestPass123!")
+                .await
+                .is_err()
+        );
+    }
+}
@@ -1,21 +1,123 @@
-use anyhow::{Context as AnyhowContext, Result};
+use anyhow::{Context as AnyhowContext, Result, anyhow, bail};
+use axum::http::Method;
+use jiff::Timestamp;
 use serde_json::Value;
+use shared::api_spec::{self, HttpMethod};
+use shared::auth::api_token::{hash_api_token, is_api_token};
+use shared::data::TokenScope;
 
 use crate::auth::context::AuthContext;
 use crate::auth::jwt;
 use crate::daemon::state::AppState;
 
-/// Extract and validate JWT token from RPC request params
+/// Validate a personal API token
+///
+/// This function:
+/// 1. Looks up the token by its SHA-256 digest
+/// 2. Checks that the token has not expired
+/// 3. Loads the owning user to get the current admin status
+/// 4. Records the token as used
+///
+/// Returns an AuthContext carrying the token's scopes, and the token expiry.
+///
+/// Returns an error if:
+/// - No token matches (unknown or revoked)
+/// - The token has expired
+/// - The owning user no longer exists
+pub async fn validate_api_token(token: &str, state: &AppState) -> Result<(AuthContext, Timestamp)> {
+    let api_token = db::get_api_token_by_hash(&state.db, &hash_api_token(token))
+        .await?
+        .ok_or_else(|| anyhow!("Unknown or revoked API token"))?;
+
+    if api_token.expires_at <= Timestamp::now() {
+        bail!("API token '{}' has expired", api_token.name);
+    }
+
+    let user = db::get_user_by_id(&state.db, api_token.user.clone())
+        .await?
+        .ok_or_else(|| anyhow!("User for API token '{}' not found", api_token.name))?;
+
+    if let Some(token_id) = &api_token.id
+        && let Err(e) = db::touch_api_token(&state.db, token_id).await
+    {
+        tracing::warn!(
+            "Failed to record use of API token '{}': {:?}",
+            api_token.name,
+            e
+        );
+    }
+
+    Ok((
+        AuthContext::from_api_token(user.username, user.is_admin, api_token.scopes),
+        api_token.expires_at,
+    ))
+}
+
+/// Validate a personal API token, see [`validate_api_token`]
+pub async fn authenticate_api_token(token: &str, state: &AppState) -> Result<AuthContext> {
+    validate_api_token(token, state)
+        .await
+        .map(|(auth_ctx, _)| auth_ctx)
+}
+
+/// Token scope required to call an RPC method with an API token
+///
+/// Taken from the API spec. `labs.list` and `download` are not part of the
+/// spec and only read data.
+pub fn rpc_token_scope(method: &str) -> Option<TokenScope> {
+    match method {
+        "labs.list" | "download" => Some(TokenScope::ReadOnly),
+        _ => api_spec::find_rpc_operation(method).and_then(|op| op.token_scope),
+    }
+}
+
+/// Token scope required to call a REST route with an API token
+///
+/// `path` is the matched route pattern, looked up in the API spec. Routes
+/// outside the spec (e.g. HTML pages) cannot be used with API tokens.
+pub fn rest_token_scope(method: &Method, path: &str) -> Option<TokenScope> {
+    let method = match *method {
+        Method::GET => HttpMethod::Get,
+        Method::POST => HttpMethod::Post,
+        Method::PUT => HttpMethod::Put,
+        Method::DELETE => HttpMethod::Delete,
+        _ => return None,
+    };
+    api_spec::find_rest_operation(method, path).and_then(|op| op.token_scope)
+}
+
+/// Check that an authenticated RPC caller may call a method
+///
+/// Callers authenticated with a login session JWT are not scoped and
+/// always pass. Callers authenticated with an API token must have a scope
+/// covering the method's required scope.
+///
+/// Returns an error if an API token lacks the required scope.
+pub fn authorize_token_scope(method: &str, auth_ctx: &AuthContext) -> Result<()> {
+    if !auth_ctx.allows_token_scope(rpc_token_scope(method)) {
+        tracing::warn!(
+            "User '{}' attempted '{}' with an API token lacking the required scope",
+            auth_ctx.username,
+            method
+        );
+        bail!("API token scope does not allow '{}'", method);
+    }
+
+    Ok(())
+}
+
+/// Extract and validate the token from RPC request params
 ///
 /// This function:
 /// 1. Extracts the "token" field from params
-/// 2. Validates the token signature and expiration
+/// 2. Validates the JWT signature and expiration, or the API token
 /// 3. Returns an AuthContext with user info
 ///
 /// Returns an error if:
 /// - Token is missing
 /// - Token is invalid or expired
 /// - Token signature doesn't match
+/// - API token is unknown, revoked or expired
 pub async fn authenticate_request(params: &Value, state: &AppState) -> Result<AuthContext> {
     // Extract token from params
     let token = params
@@ -23,6 +125,12 @@ pub async fn authenticate_request(params: &Value, state: &AppState) -> Result<Au
         .and_then(|v| v.as_str())
         .context("Missing 'token' field in request params")?;
 
+    if is_api_token(token) {
+        return authenticate_api_token(token, state)
+            .await
+            .context("Invalid, revoked or expired API token");
+    }
+
     // Validate token
     let claims =
         jwt::validate_token(&state.jwt_secret, token).context("Invalid or expired token")?;
@@ -35,6 +143,8 @@ pub async fn authenticate_request(params: &Value, state: &AppState) -> Result<Au
 mod tests {
     use serde_json::json;
 
+    use super::*;
+
     // Note: authenticate_request() tests require full AppState infrastructure (DB, Docker, libvirt, etc.)
     // For simpler unit tests, see jwt.rs and context.rs
     // Integration tests should cover the full authentication flow
@@ -50,4 +160,49 @@ mod tests {
         let token = params_no_token.get("token").and_then(|v| v.as_str());
         assert_eq!(token, None);
     }
+
+    #[test]
+    fn test_rpc_token_scope() {
+        assert_eq!(rpc_token_scope("inspect"), Some(TokenScope::ReadOnly));
+        assert_eq!(rpc_token_scope("labs.list"), Some(TokenScope::ReadOnly));
+        assert_eq!(rpc_token_scope("up"), Some(TokenScope::LabOperate));
+        assert_eq!(rpc_token_scope("image.pull"), Some(TokenScope::ImageAdmin));
+        assert_eq!(rpc_token_scope("token.create"), None);
+        assert_eq!(rpc_token_scope("user.create"), None);
+    }
+
+    #[test]
+    fn test_rest_token_scope() {
+        assert_eq!(
+            rest_token_scope(&Method::GET, "/api/v1/labs/{id}"),
+            Some(TokenScope::ReadOnly)
+        );
+        assert_eq!(
+            rest_token_scope(&Method::DELETE, "/api/v1/labs/{id}"),
+            Some(TokenScope::LabOperate)
+        );
+        assert_eq!(
+            rest_token_scope(
+                &Method::POST,
+                "/api/v1/labs/{id}/nodes/{node_name}/redeploy"
+            ),
+            Some(TokenScope::LabOperate)
+        );
+        assert_eq!(rest_token_scope(&Method::POST, "/api/v1/tokens"), None);
+        assert_eq!(rest_token_scope(&Method::GET, "/labs/{lab_id}"), None);
+        assert_eq!(rest_token_scope(&Method::PUT, "/api/v1/labs/{id}"), None);
+    }
+
+    #[test]
+    fn test_authorize_token_scope() {
+        let session = AuthContext::new("alice".to_string(), false);
+        assert!(authorize_token_scope("up", &session).is_ok());
+        assert!(authorize_token_scope("token.create", &session).is_ok());
+
+        let read_only =
+            AuthContext::from_api_token("alice".to_string(), false, vec![TokenScope::ReadOnly]);
+        assert!(authorize_token_scope("inspect", &read_only).is_ok());
+        assert!(authorize_token_scope("up", &read_only).is_err());
+        assert!(authorize_token_scope("token.create", &read_only).is_err());
+    }
 }
@@ -1,4 +1,7 @@
 pub mod context;
 pub mod cookies;
 pub mod jwt;
+pub mod ldap;
+pub mod login;
 pub mod middleware;
+pub mod oidc;
@@ -0,0 +1,763 @@
+//! OpenID Connect single sign-on.
+//!
+//! The identity provider is discovered from
+//! `{issuer_url}/.well-known/openid-configuration` at the start of every login,
+//! so no provider state is kept between requests. The web UI uses the
+//! authorization code flow with PKCE, the CLI uses the device authorization
+//! flow (RFC 8628). ID tokens are verified against the provider's JWKS, or
+//! against the client secret for HMAC-signed tokens. Only the signing
+//! algorithms the provider advertises are accepted.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jiff::Timestamp;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use shared::data::{DeviceLoginStartResponse, OidcConfig};
+use shared::konst::OIDC_LOGIN_STATE_EXPIRY_SECONDS;
+use tracing::instrument;
+
+/// Grant type used to poll the token endpoint during a device login
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Timeout for requests to the identity provider
+const OIDC_HTTP_TIMEOUT_SECS: u64 = 10;
+
+/// Poll interval used when the provider doesn't send one (RFC 8628)
+const DEVICE_DEFAULT_INTERVAL_SECS: u64 = 5;
+
+/// Provider endpoints from the discovery document.
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    #[serde(default)]
+    device_authorization_endpoint: Option<String>,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+impl ProviderMetadata {
+    /// Algorithms ID tokens may be signed with. Discovery requires providers
+    /// to list them, RS256 is the default for those that don't.
+    fn id_token_algorithms(&self) -> Vec<Algorithm> {
+        if self.id_token_signing_alg_values_supported.is_empty() {
+            return vec![Algorithm::RS256];
+        }
+        // Unsupported values, such as "none", are never accepted
+        self.id_token_signing_alg_values_supported
+            .iter()
+            .filter_map(|alg| alg.parse().ok())
+            .collect()
+    }
+}
+
+/// Successful token endpoint response, only the ID token is used.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    id_token: Option<String>,
+}
+
+/// Token endpoint error response (RFC 6749 section 5.2).
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Device authorization endpoint response (RFC 8628 section 3.2).
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+/// A user authenticated by the identity provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcUser {
+    pub username: String,
+    pub is_admin: bool,
+}
+
+/// Result of polling a device login.
+#[derive(Debug)]
+pub enum DevicePoll {
+    /// The user has not approved the login yet
+    Pending,
+    /// The client is polling too often
+    SlowDown,
+    /// The user approved the login
+    Complete(OidcUser),
+}
+
+/// A pending web login.
+///
+/// Kept in a signed cookie between the redirect to the identity provider and
+/// the callback, so the server doesn't have to track pending logins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginState {
+    /// CSRF protection, echoed back by the provider
+    pub state: String,
+    /// Replay protection, embedded in the ID token
+    pub nonce: String,
+    /// PKCE code verifier
+    pub code_verifier: String,
+    /// Expiration time (Unix seconds)
+    pub exp: i64,
+}
+
+impl LoginState {
+    /// Create a login state with fresh random values.
+    pub fn new() -> Self {
+        Self {
+            state: random_token(),
+            nonce: random_token(),
+            code_verifier: random_token(),
+            exp: Timestamp::now().as_second() + OIDC_LOGIN_STATE_EXPIRY_SECONDS,
+        }
+    }
+
+    /// Sign the login state for storage in a cookie.
+    pub fn encode(&self, secret: &[u8]) -> Result<String> {
+        encode(&Header::default(), self, &EncodingKey::from_secret(secret))
+            .context("Failed to encode OIDC login state")
+    }
+
+    /// Verify and decode a login state cookie.
+    pub fn decode(token: &str, secret: &[u8]) -> Result<Self> {
+        let mut validation = Validation::default();
+        validation.set_required_spec_claims(&["exp"]);
+        decode::<Self>(token, &DecodingKey::from_secret(secret), &validation)
+            .map(|data| data.claims)
+            .context("Invalid or expired OIDC login state")
+    }
+}
+
+impl Default for LoginState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generate a random URL-safe token with 256 bits of entropy.
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Derive the PKCE `S256` code challenge from a code verifier.
+fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Map verified ID token claims to a Sherpa user.
+fn user_from_claims(config: &OidcConfig, claims: &Map<String, Value>) -> Result<OidcUser> {
+    let username = claims
+        .get(&config.username_claim)
+        .and_then(Value::as_str)
+        .filter(|username| !username.is_empty())
+        .ok_or_else(|| anyhow!("ID token has no '{}' claim", config.username_claim))?;
+
+    // Providers send groups as an array, some send a single string
+    let groups: Vec<&str> = match claims.get(&config.groups_claim) {
+        Some(Value::Array(groups)) => groups.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(group)) => vec![group.as_str()],
+        _ => vec![],
+    };
+    let is_admin = groups
+        .iter()
+        .any(|group| config.admin_groups.iter().any(|admin| admin == group));
+
+    Ok(OidcUser {
+        username: username.to_string(),
+        is_admin,
+    })
+}
+
+/// Client for the configured identity provider.
+pub struct OidcClient<'a> {
+    config: &'a OidcConfig,
+    metadata: ProviderMetadata,
+    http: reqwest::Client,
+}
+
+impl<'a> OidcClient<'a> {
+    /// Fetch the provider's discovery document.
+    ///
+    /// # Errors
+    /// Returns an error if the document cannot be fetched or its issuer
+    /// doesn't match the configured issuer URL
+    #[instrument(skip(config), fields(issuer = %config.issuer_url))]
+    pub async fn discover(config: &'a OidcConfig) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(OIDC_HTTP_TIMEOUT_SECS))
+            .build()
+            .context("Failed to build OIDC HTTP client")?;
+
+        let issuer = config.issuer_url.trim_end_matches('/');
+        let metadata: ProviderMetadata = http
+            .get(format!("{}/.well-known/openid-configuration", issuer))
+            .send()
+            .await
+            .context("Failed to fetch OIDC discovery document")?
+            .error_for_status()
+            .context("OIDC discovery request failed")?
+            .json()
+            .await
+            .context("Invalid OIDC discovery document")?;
+
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            bail!(
+                "OIDC issuer mismatch: configured '{}', provider reports '{}'",
+                config.issuer_url,
+                metadata.issuer
+            );
+        }
+
+        Ok(Self {
+            config,
+            metadata,
+            http,
+        })
+    }
+
+    /// Build the URL that starts a web login at the identity provider.
+    pub fn authorization_url(&self, login: &LoginState) -> Result<String> {
+        let scopes = self.config.scopes.join(" ");
+        let code_challenge = pkce_challenge(&login.code_verifier);
+        let url = Url::parse_with_params(
+            &self.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", scopes.as_str()),
+                ("state", login.state.as_str()),
+                ("nonce", login.nonce.as_str()),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("Invalid OIDC authorization endpoint")?;
+        Ok(url.to_string())
+    }
+
+    /// Exchange the code from a web login callback for the user's identity.
+    ///
+    /// # Errors
+    /// Returns an error if the provider rejects the code or the ID token
+    /// cannot be verified
+    #[instrument(skip_all)]
+    pub async fn exchange_code(&self, code: &str, login: &LoginState) -> Result<OidcUser> {
+        let response = self
+            .token_request(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("code_verifier", login.code_verifier.as_str()),
+            ])
+            .await?
+            .map_err(|e| anyhow!("OIDC code exchange failed: {}", describe(&e)))?;
+
+        let id_token = response
+            .id_token
+            .ok_or_else(|| anyhow!("OIDC token response has no ID token"))?;
+        self.verify_id_token(&id_token, Some(&login.nonce)).await
+    }
+
+    /// Start a device login for the CLI.
+    ///
+    /// # Errors
+    /// Returns an error if the provider doesn't support the device flow or
+    /// rejects the request
+    #[instrument(skip_all)]
+    pub async fn start_device_login(&self) -> Result<DeviceLoginStartResponse> {
+        let endpoint = self
+            .metadata
+            .device_authorization_endpoint
+            .as_deref()
+            .ok_or_else(|| anyhow!("The OIDC provider does not support device login"))?;
+
+        let scopes = self.config.scopes.join(" ");
+        let response: DeviceAuthorizationResponse = self
+            .with_client_auth(self.http.post(endpoint), &[("scope", scopes.as_str())])
+            .send()
+            .await
+            .context("Failed to reach the OIDC device authorization endpoint")?
+            .error_for_status()
+            .context("OIDC device authorization request failed")?
+            .json()
+            .await
+            .context("Invalid OIDC device authorization response")?;
+
+        Ok(DeviceLoginStartResponse {
+            device_code: response.device_code,
+            user_code: response.user_code,
+            verification_uri: response.verification_uri,
+            verification_uri_complete: response.verification_uri_complete,
+            expires_in: response.expires_in,
+            interval: response.interval.unwrap_or(DEVICE_DEFAULT_INTERVAL_SECS),
+        })
+    }
+
+    /// Poll a device login.
+    ///
+    /// # Errors
+    /// Returns an error if the login was denied, expired, or the ID token
+    /// cannot be verified
+    #[instrument(skip_all)]
+    pub async fn poll_device_login(&self, device_code: &str) -> Result<DevicePoll> {
+        let response = match self
+            .token_request(&[
+                ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                ("device_code", device_code),
+            ])
+            .await?
+        {
+            Ok(response) => response,
+            Err(e) if e.error == "authorization_pending" => return Ok(DevicePoll::Pending),
+            Err(e) if e.error == "slow_down" => return Ok(DevicePoll::SlowDown),
+            Err(e) => bail!("OIDC device login failed: {}", describe(&e)),
+        };
+
+        let id_token = response
+            .id_token
+            .ok_or_else(|| anyhow!("OIDC token response has no ID token"))?;
+        let user = self.verify_id_token(&id_token, None).await?;
+        Ok(DevicePoll::Complete(user))
+    }
+
+    /// Send a request to the token endpoint.
+    ///
+    /// Returns the provider's error response as the inner `Err`, so callers
+    /// can react to device flow states.
+    async fn token_request(
+        &self,
+        form: &[(&str, &str)],
+    ) -> Result<std::result::Result<TokenResponse, TokenErrorResponse>> {
+        let response = self
+            .with_client_auth(self.http.post(&self.metadata.token_endpoint), form)
+            .send()
+            .await
+            .context("Failed to reach the OIDC token endpoint")?;
+
+        if response.status().is_success() {
+            let tokens = response
+                .json()
+                .await
+                .context("Invalid OIDC token response")?;
+            return Ok(Ok(tokens));
+        }
+
+        let status = response.status();
+        let error = response
+            .json()
+            .await
+            .with_context(|| format!("OIDC token request failed with status {}", status))?;
+        Ok(Err(error))
+    }
+
+    /// Add client authentication to a form request.
+    ///
+    /// Confidential clients use HTTP basic auth, public clients send only
+    /// their client ID.
+    fn with_client_auth(
+        &self,
+        request: reqwest::RequestBuilder,
+        form: &[(&str, &str)],
+    ) -> reqwest::RequestBuilder {
+        match &self.config.client_secret {
+            Some(secret) => request
+                .basic_auth(&self.config.client_id, Some(secret))
+                .form(form),
+            None => {
+                let mut form = form.to_vec();
+                form.push(("client_id", self.config.client_id.as_str()));
+                request.form(&form)
+            }
+        }
+    }
+
+    /// Verify an ID token and map its claims to a Sherpa user.
+    ///
+    /// # Errors
+    /// Returns an error if the signature, issuer, audience, expiry or nonce
+    /// is invalid, or the username claim is missing
+    async fn verify_id_token(&self, id_token: &str, nonce: Option<&str>) -> Result<OidcUser> {
+        let header = decode_header(id_token).context("Malformed ID token")?;
+
+        // The header is unverified, so its algorithm must not pick the key
+        // type on its own, e.g. HMAC with the client secret
+        let algorithms = self.metadata.id_token_algorithms();
+        if !algorithms.contains(&header.alg) {
+            bail!(
+                "ID token is signed with {:?}, which the provider does not advertise",
+                header.alg
+            );
+        }
+
+        let key = match header.alg {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = self.config.client_secret.as_deref().ok_or_else(|| {
+                    anyhow!("HMAC-signed ID tokens require a configured client secret")
+                })?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            _ => {
+                let jwks: JwkSet = self
+                    .http
+                    .get(&self.metadata.jwks_uri)
+                    .send()
+                    .await
+                    .context("Failed to fetch OIDC signing keys")?
+                    .error_for_status()
+                    .context("OIDC signing key request failed")?
+                    .json()
+                    .await
+                    .context("Invalid OIDC signing keys")?;
+                let jwk = match &header.kid {
+                    Some(kid) => jwks.find(kid),
+                    None => jwks.keys.first(),
+                }
+                .ok_or_else(|| anyhow!("No OIDC signing key matches the ID token"))?;
+                DecodingKey::from_jwk(jwk).context("Unsupported OIDC signing key")?
+            }
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.algorithms = algorithms;
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&self.metadata.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<Map<String, Value>>(id_token, &key, &validation)
+            .context("ID token verification failed")?
+            .claims;
+
+        if let Some(nonce) = nonce
+            && claims.get("nonce").and_then(Value::as_str) != Some(nonce)
+        {
+            bail!("ID token nonce does not match the login request");
+        }
+
+        user_from_claims(self.config, &claims)
+    }
+}
+
+/// Format a token endpoint error for logs and error messages.
+fn describe(error: &TokenErrorResponse) -> String {
+    match &error.error_description {
+        Some(description) => format!("{} ({})", error.error, description),
+        None => error.error.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn oidc_config() -> OidcConfig {
+        toml::from_str(
+            r#"
+            issuer_url = "https://sso.example.com/realms/lab"
+            client_id = "sherpa"
+            redirect_url = "https://sherpa.example.com/auth/oidc/callback"
+            admin_groups = ["sherpa-admins"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pkce_challenge_matches_rfc7636_example() {
+        // Appendix B of RFC 7636
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_login_state_round_trip() {
+        let secret = b"test_secret_32_bytes_long_enough";
+        let login = LoginState::new();
+        let token = login.encode(secret).unwrap();
+
+        let decoded = LoginState::decode(&token, secret).unwrap();
+        assert_eq!(decoded.state, login.state);
+        assert_eq!(decoded.code_verifier, login.code_verifier);
+
+        assert!(LoginState::decode(&token, b"another_secret_32_bytes_long_xxx").is_err());
+    }
+
+    #[test]
+    fn test_login_state_is_not_a_session_token() {
+        let secret = b"test_secret_32_bytes_long_enough";
+        let token = LoginState::new().encode(secret).unwrap();
+        assert!(crate::auth::jwt::validate_token(secret, &token).is_err());
+    }
+
+    #[test]
+    fn test_user_from_claims_maps_admin_groups() {
+        let config = oidc_config();
+        let claims = json!({
+            "sub": "1234",
+            "preferred_username": "alice",
+            "groups": ["students", "sherpa-admins"],
+        });
+        let user = user_from_claims(&config, claims.as_object().unwrap()).unwrap();
+        assert_eq!(user.username, "alice");
+        assert!(user.is_admin);
+
+        let claims = json!({"preferred_username": "bob", "groups": "students"});
+        let user = user_from_claims(&config, claims.as_object().unwrap()).unwrap();
+        assert!(!user.is_admin);
+    }
+
+    #[test]
+    fn test_user_from_claims_requires_username() {
+        let config = oidc_config();
+        let claims = json!({"sub": "1234", "preferred_username": ""});
+        assert!(user_from_claims(&config, claims.as_object().unwrap()).is_err());
+    }
+
+    /// Client secret shared with the mock issuer, which signs ID tokens with HS256
+    const MOCK_CLIENT_SECRET: &str = "mock-client-secret-at-least-32-bytes";
+
+    /// Nonce the mock issuer embeds in ID tokens from the code flow
+    const MOCK_NONCE: &str = "mock-nonce";
+
+    /// Start a mock OIDC issuer on a random local port, signing ID tokens
+    /// with HS256.
+    async fn start_mock_issuer() -> String {
+        start_mock_issuer_advertising(&["HS256"]).await
+    }
+
+    /// Start a mock OIDC issuer that advertises `algorithms` for ID tokens.
+    async fn start_mock_issuer_advertising(algorithms: &[&str]) -> String {
+        use axum::extract::{Form, State};
+        use axum::http::StatusCode;
+        use axum::routing::{get, post};
+        use axum::{Json, Router};
+        use std::collections::HashMap;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let id_token = |issuer: &str, nonce: Option<&str>| {
+            let mut claims = json!({
+                "iss": issuer,
+                "aud": "sherpa",
+                "sub": "1234",
+                "exp": Timestamp::now().as_second() + 300,
+                "preferred_username": "alice",
+                "groups": ["sherpa-admins"],
+            });
+            if let Some(nonce) = nonce {
+                claims["nonce"] = json!(nonce);
+            }
+            encode(
+                &Header::new(Algorithm::HS256),
+                &claims,
+                &EncodingKey::from_secret(MOCK_CLIENT_SECRET.as_bytes()),
+            )
+            .unwrap()
+        };
+
+        let algorithms: Vec<String> = algorithms.iter().map(|alg| alg.to_string()).collect();
+        let discovery = move |State(issuer): State<String>| {
+            let algorithms = algorithms.clone();
+            async move {
+                Json(json!({
+                    "issuer": issuer,
+                    "authorization_endpoint": format!("{}/authorize", issuer),
+                    "token_endpoint": format!("{}/token", issuer),
+                    "jwks_uri": format!("{}/jwks", issuer),
+                    "device_authorization_endpoint": format!("{}/device", issuer),
+                    "id_token_signing_alg_values_supported": algorithms,
+                }))
+            }
+        };
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery.clone()))
+            // Serves the root issuer's document under another issuer URL
+            .route("/other/.well-known/openid-configuration", get(discovery))
+            .route(
+                "/device",
+                post(|| async {
+                    Json(json!({
+                        "device_code": "approved",
+                        "user_code": "ABCD-EFGH",
+                        "verification_uri": "https://sso.example.com/device",
+                        "expires_in": 600,
+                    }))
+                }),
+            )
+            .route(
+                "/token",
+                post(
+                    move |State(issuer): State<String>,
+                          Form(form): Form<HashMap<String, String>>| async move {
+                        let grant_type = form.get("grant_type").map(String::as_str);
+                        let code = form.get("code").map(String::as_str);
+                        let device_code = form.get("device_code").map(String::as_str);
+                        match (grant_type, code, device_code) {
+                            (Some("authorization_code"), Some("good-code"), _)
+                                if form.contains_key("code_verifier") =>
+                            {
+                                let token = id_token(&issuer, Some(MOCK_NONCE));
+                                (StatusCode::OK, Json(json!({"id_token": token})))
+                            }
+                            (Some(DEVICE_CODE_GRANT_TYPE), _, Some("approved")) => {
+                                let token = id_token(&issuer, None);
+                                (StatusCode::OK, Json(json!({"id_token": token})))
+                            }
+                            (Some(DEVICE_CODE_GRANT_TYPE), _, Some("pending")) => (
+                                StatusCode::BAD_REQUEST,
+                                Json(json!({"error": "authorization_pending"})),
+                            ),
+                            _ => (
+                                StatusCode::BAD_REQUEST,
+                                Json(json!({"error": "invalid_grant"})),
+                            ),
+                        }
+                    },
+                ),
+            )
+            .with_state(issuer.clone());
+
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        issuer
+    }
+
+    fn mock_config(issuer: &str) -> OidcConfig {
+        let mut config = oidc_config();
+        config.issuer_url = issuer.to_string();
+        config.client_secret = Some(MOCK_CLIENT_SECRET.to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn test_code_flow_against_mock_issuer() {
+        let issuer = start_mock_issuer().await;
+        let config = mock_config(&issuer);
+        let client = OidcClient::discover(&config).await.unwrap();
+
+        let login = LoginState {
+            nonce: MOCK_NONCE.to_string(),
+            ..LoginState::new()
+        };
+        let url = client.authorization_url(&login).unwrap();
+        assert!(url.starts_with(&format!("{}/authorize?", issuer)));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains(&format!("state={}", login.state)));
+
+        let user = client.exchange_code("good-code", &login).await.unwrap();
+        assert_eq!(
+            user,
+            OidcUser {
+                username: "alice".to_string(),
+                is_admin: true,
+            }
+        );
+
+        assert!(client.exchange_code("bad-code", &login).await.is_err());
+
+        // An ID token issued for another login request is rejected
+        let replayed = LoginState::new();
+        assert!(client.exchange_code("good-code", &replayed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_device_flow_against_mock_issuer() {
+        let issuer = start_mock_issuer().await;
+        let config = mock_config(&issuer);
+        let client = OidcClient::discover(&config).await.unwrap();
+
+        let start = client.start_device_login().await.unwrap();
+        assert_eq!(start.user_code, "ABCD-EFGH");
+        assert_eq!(start.interval, DEVICE_DEFAULT_INTERVAL_SECS);
+
+        assert!(matches!(
+            client.poll_device_login("pending").await.unwrap(),
+            DevicePoll::Pending
+        ));
+        assert!(matches!(
+            client.poll_device_login(&start.device_code).await.unwrap(),
+            DevicePoll::Complete(OidcUser { is_admin: true, .. })
+        ));
+        assert!(client.poll_device_login("denied").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_algorithm_the_provider_does_not_advertise() {
+        // An HS256 token must not be verified with the client secret when
+        // the provider only signs with RS256
+        let issuer = start_mock_issuer_advertising(&["RS256"]).await;
+        let config = mock_config(&issuer);
+        let client = OidcClient::discover(&config).await.unwrap();
+
+        let login = LoginState {
+            nonce: MOCK_NONCE.to_string(),
+            ..LoginState::new()
+        };
+        let err = client.exchange_code("good-code", &login).await.unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("does not advertise"),
+            "{:#}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_id_token_algorithms() {
+        let metadata = |algorithms: &[&str]| ProviderMetadata {
+            issuer: String::new(),
+            authorization_endpoint: String::new(),
+            token_endpoint: String::new(),
+            jwks_uri: String::new(),
+            device_authorization_endpoint: None,
+            id_token_signing_alg_values_supported: algorithms
+                .iter()
+                .map(|alg| alg.to_string())
+                .collect(),
+        };
+        assert_eq!(metadata(&[]).id_token_algorithms(), vec![Algorithm::RS256]);
+        assert_eq!(
+            metadata(&["RS256", "ES256", "none"]).id_token_algorithms(),
+            vec![Algorithm::RS256, Algorithm::ES256]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discover_rejects_issuer_mismatch() {
+        let issuer = start_mock_issuer().await;
+        let config = mock_config(&format!("{}/other", issuer));
+        assert!(OidcClient::discover(&config).await.is_err());
+    }
+}
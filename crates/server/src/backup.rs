@@ -0,0 +1,737 @@
+//! Backup and restore of the whole sherpad state
+//!
+//! A backup is a zip archive with:
+//! - `backup.json`: the manifest, with a SHA-256 of every other entry
+//! - `db/<table>.surql`: one SurrealQL `UPSERT` per record
+//! - `files/<absolute path>`: config, secrets, TLS material, the server SSH
+//!   keypair, custom models and, optionally, lab directories
+//!
+//! Node image records are only included with `--images`. Image disks and
+//! container images are never included.
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+use zip::write::SimpleFileOptions;
+
+use db::{
+    BACKUP_TABLES, Database, apply_schema, count_table, export_table, list_labs, list_record_ids,
+    list_record_links, migrate, migration_status, restore_tables,
+};
+use shared::konst::{
+    JWT_SECRET_PATH, REGISTRY_KEY_PATH, SHERPA_BASE_DIR, SHERPA_CONFIG_FILE_PATH, SHERPA_LABS_PATH,
+    SHERPA_MODELS_PATH, SHERPA_SSH_PRIVATE_KEY_PATH, SHERPA_SSH_PUBLIC_KEY_PATH,
+};
+use shared::util::{load_config, term_msg_surround, term_msg_underline};
+
+use crate::daemon::pidfile::verify_not_running;
+use crate::migrate::connect_database;
+use crate::tls::CertificateManager;
+
+const BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "backup.json";
+const DB_ENTRY_DIR: &str = "db";
+const FILES_ENTRY_DIR: &str = "files";
+const NODE_IMAGE_TABLE: &str = "node_image";
+const STAGING_SUFFIX: &str = "sherpa-restore";
+
+/// Contents of a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// sherpad version that wrote the backup
+    pub sherpa_version: String,
+    pub created_at: Timestamp,
+    /// Schema migration version of the database
+    pub schema_version: u32,
+    /// Whether the node_image table is included
+    pub include_images: bool,
+    /// Whether the lab directories are included
+    pub include_labs: bool,
+    pub tables: Vec<BackupTableEntry>,
+    pub files: Vec<BackupFileEntry>,
+    /// node_image records referenced by nodes
+    pub image_refs: Vec<String>,
+    /// Lab IDs in the lab table
+    pub labs: Vec<String>,
+}
+
+/// A table export in a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupTableEntry {
+    pub name: String,
+    pub records: usize,
+    pub sha256: String,
+}
+
+/// A server file in a backup archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFileEntry {
+    /// Absolute path the file is restored to
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Unix permission bits
+    pub mode: u32,
+}
+
+/// Write a backup archive of the server state to `file`
+///
+/// The archive is secret material: it holds the JWT secret, the registry
+/// key, the TLS private key and the server SSH private key. See
+/// [`create_archive`].
+pub async fn run_backup(
+    file: &Path,
+    include_images: bool,
+    include_labs: bool,
+    db_password: Option<&str>,
+) -> Result<()> {
+    term_msg_surround("Sherpa Server Backup");
+    let db = connect_database(db_password).await?;
+    let config = load_config(SHERPA_CONFIG_FILE_PATH)
+        .context(format!("Failed to load config: {SHERPA_CONFIG_FILE_PATH}"))?;
+
+    let out = create_archive(file)?;
+    let mut zip = zip::ZipWriter::new(out);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    // Database tables
+    term_msg_underline("Exporting Database");
+    let mut tables = vec![];
+    for table in BACKUP_TABLES {
+        if table.name == NODE_IMAGE_TABLE && !include_images {
+            continue;
+        }
+        let statements = export_table(&db, table).await?;
+        let content = statements.join("\n");
+        zip.start_file(table_entry(table.name), options)?;
+        zip.write_all(content.as_bytes())?;
+
+        println!("{:<20} {} records", table.name, statements.len());
+        tables.push(BackupTableEntry {
+            name: table.name.to_string(),
+            records: statements.len(),
+            sha256: sha256_hex(content.as_bytes()),
+        });
+    }
+
+    let labs: Vec<String> = list_labs(&db)
+        .await?
+        .into_iter()
+        .map(|l| l.lab_id)
+        .collect();
+
+    // Server files
+    term_msg_underline("Exporting Files");
+    let certificates = CertificateManager::new(&config.tls)?;
+    let mut paths: Vec<PathBuf> = [
+        SHERPA_CONFIG_FILE_PATH,
+        JWT_SECRET_PATH,
+        REGISTRY_KEY_PATH,
+        SHERPA_SSH_PRIVATE_KEY_PATH,
+        SHERPA_SSH_PUBLIC_KEY_PATH,
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .chain([
+        certificates.cert_path().to_path_buf(),
+        certificates.key_path().to_path_buf(),
+    ])
+    .collect();
+    collect_files(Path::new(SHERPA_MODELS_PATH), &mut paths)?;
+    if include_labs {
+        for lab_id in &labs {
+            let lab_dir = Path::new(SHERPA_LABS_PATH).join(lab_id);
+            if lab_dir.is_dir() {
+                collect_files(&lab_dir, &mut paths)?;
+            } else {
+                println!("Skipping missing lab directory: {}", lab_dir.display());
+            }
+        }
+    }
+
+    let mut files = vec![];
+    for path in paths {
+        if !path.is_file() {
+            println!("Skipping missing file: {}", path.display());
+            continue;
+        }
+        let content = fs::read(&path).context(format!("Failed to read {}", path.display()))?;
+        let mode = file_mode(&path)?;
+        zip.start_file(file_entry(&path.to_string_lossy()), options)?;
+        zip.write_all(&content)?;
+
+        files.push(BackupFileEntry {
+            path: path.to_string_lossy().to_string(),
+            size: content.len() as u64,
+            sha256: sha256_hex(&content),
+            mode,
+        });
+    }
+    println!("{} files", files.len());
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        sherpa_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Timestamp::now(),
+        schema_version: migration_status(&db).await?.current_version,
+        include_images,
+        include_labs,
+        tables,
+        files,
+        image_refs: list_record_links(&db, "node", "image").await?,
+        labs,
+    };
+    zip.start_file(MANIFEST_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish().context("Failed to finalize backup archive")?;
+
+    term_msg_surround(&format!("Backup written to {}", file.display()));
+    Ok(())
+}
+
+pub async fn run_restore(file: &Path, dry_run: bool, db_password: Option<&str>) -> Result<()> {
+    if !dry_run {
+        verify_not_running().context("Stop sherpad before restoring a backup")?;
+    }
+
+    let archive = File::open(file).context(format!("Failed to open {}", file.display()))?;
+    let mut zip = ZipArchive::new(archive).context("Backup is not a valid zip archive")?;
+    let manifest: BackupManifest = serde_json::from_slice(&read_entry(&mut zip, MANIFEST_ENTRY)?)
+        .context("Failed to parse backup manifest")?;
+
+    // TLS material may live outside the base directory, only at the paths
+    // this server is configured with
+    let config = load_config(SHERPA_CONFIG_FILE_PATH)
+        .context(format!("Failed to load config: {SHERPA_CONFIG_FILE_PATH}"))?;
+    let certificates = CertificateManager::new(&config.tls)?;
+    let tls_paths = [
+        certificates.cert_path().to_path_buf(),
+        certificates.key_path().to_path_buf(),
+    ];
+
+    let (tables, errors) = verify_archive(&mut zip, &manifest, &tls_paths);
+
+    let db = connect_database(db_password).await?;
+    let warnings = check_target(&db, &manifest).await?;
+
+    print_report(&db, &manifest, &errors, &warnings).await?;
+
+    if !errors.is_empty() {
+        bail!("Backup failed consistency checks, nothing was restored");
+    }
+    if dry_run {
+        println!();
+        println!("Dry run, nothing was restored");
+        return Ok(());
+    }
+
+    // Files are written next to their targets before the database is
+    // touched, and only moved into place once it is restored. A failed write
+    // leaves both the database and the files as they were, so the restored
+    // records never end up paired with the old secrets and keys.
+    term_msg_underline("Staging Files");
+    let mut staged = vec![];
+    for entry in &manifest.files {
+        let path = PathBuf::from(&entry.path);
+        let staging = staging_path(&path);
+        let result = read_entry(&mut zip, &file_entry(&entry.path))
+            .and_then(|content| write_file(&staging, &content, entry.mode));
+        if let Err(e) = result {
+            remove_staged(&staged);
+            let _ = fs::remove_file(&staging);
+            return Err(e.context("Failed to stage files, nothing was restored"));
+        }
+        staged.push((staging, path));
+    }
+    println!("{} files staged", staged.len());
+
+    term_msg_underline("Restoring Database");
+    if let Err(e) = restore_database(&db, &tables).await {
+        remove_staged(&staged);
+        return Err(e);
+    }
+
+    term_msg_underline("Restoring Files");
+    for (staging, path) in &staged {
+        fs::rename(staging, path).context(format!(
+            "Failed to move {} into place, the database is already restored",
+            path.display()
+        ))?;
+    }
+    println!("{} files restored", staged.len());
+
+    term_msg_surround("Restore complete, start sherpad to use the restored state");
+    Ok(())
+}
+
+/// Create a new backup archive readable by the owner only
+///
+/// Fails if `file` exists, so an archive is never written through a file
+/// or symlink someone else created.
+fn create_archive(file: &Path) -> Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(file)
+        .context(format!("Failed to create {}", file.display()))
+}
+
+/// Replace the database contents with the table exports of a backup
+async fn restore_database(db: &Database, tables: &[(String, Vec<String>)]) -> Result<()> {
+    apply_schema(db)
+        .await
+        .context("Failed to apply database schema")?;
+    let tables: Vec<(&str, Vec<String>)> = tables
+        .iter()
+        .map(|(name, statements)| (name.as_str(), statements.clone()))
+        .collect();
+    restore_tables(db, &tables).await?;
+    let migrated = migrate(db).await?;
+    if !migrated.is_empty() {
+        println!("Applied migrations: {migrated:?}");
+    }
+    Ok(())
+}
+
+/// Check the manifest against the archive entries
+///
+/// `tls_paths` are the TLS certificate and key paths of the server, the only
+/// files restored outside the base directory.
+///
+/// Returns the parsed table exports and the problems found.
+fn verify_archive<R: Read + std::io::Seek>(
+    zip: &mut ZipArchive<R>,
+    manifest: &BackupManifest,
+    tls_paths: &[PathBuf],
+) -> (Vec<(String, Vec<String>)>, Vec<String>) {
+    let mut errors = vec![];
+    let mut tables = vec![];
+
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        errors.push(format!(
+            "Unsupported backup format version {} (expected {})",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        ));
+    }
+    let latest = db::migration::latest_version();
+    if manifest.schema_version > latest {
+        errors.push(format!(
+            "Backup schema version {} is newer than this sherpad supports (version {})",
+            manifest.schema_version, latest
+        ));
+    }
+
+    let known_tables: HashSet<&str> = BACKUP_TABLES.iter().map(|t| t.name).collect();
+    for table in &manifest.tables {
+        if !known_tables.contains(table.name.as_str()) {
+            errors.push(format!("Unknown table in backup: {}", table.name));
+            continue;
+        }
+        match read_entry(zip, &table_entry(&table.name)) {
+            Ok(content) if sha256_hex(&content) != table.sha256 => {
+                errors.push(format!("Checksum mismatch for table {}", table.name))
+            }
+            Ok(content) => {
+                let statements: Vec<String> = String::from_utf8_lossy(&content)
+                    .lines()
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if statements.len() != table.records {
+                    errors.push(format!(
+                        "Table {} has {} records, manifest lists {}",
+                        table.name,
+                        statements.len(),
+                        table.records
+                    ));
+                }
+                tables.push((table.name.clone(), statements));
+            }
+            Err(e) => errors.push(format!("{e:#}")),
+        }
+    }
+    // Restore in dependency order, whatever the manifest order
+    tables.sort_by_key(|(name, _)| BACKUP_TABLES.iter().position(|t| t.name == name));
+
+    for entry in &manifest.files {
+        if let Err(e) = check_restore_path(&entry.path, tls_paths) {
+            errors.push(format!("{e:#}"));
+            continue;
+        }
+        match read_entry(zip, &file_entry(&entry.path)) {
+            Ok(content) if content.len() as u64 != entry.size => {
+                errors.push(format!("Size mismatch for file {}", entry.path))
+            }
+            Ok(content) if sha256_hex(&content) != entry.sha256 => {
+                errors.push(format!("Checksum mismatch for file {}", entry.path))
+            }
+            Ok(_) => {}
+            Err(e) => errors.push(format!("{e:#}")),
+        }
+    }
+
+    (tables, errors)
+}
+
+/// Check the backup against the server it is restored to
+async fn check_target(db: &Database, manifest: &BackupManifest) -> Result<Vec<String>> {
+    let mut warnings = vec![];
+
+    if !manifest.include_images {
+        let images: HashSet<String> = list_record_ids(db, NODE_IMAGE_TABLE)
+            .await?
+            .into_iter()
+            .collect();
+        let missing = manifest
+            .image_refs
+            .iter()
+            .filter(|image| !images.contains(*image))
+            .count();
+        if missing > 0 {
+            warnings.push(format!(
+                "Nodes reference {missing} node images that are not on this server, \
+                 back up with --images to include them"
+            ));
+        }
+    }
+
+    if manifest.include_labs {
+        let restored_dirs: HashSet<&str> = manifest
+            .files
+            .iter()
+            .filter_map(|f| lab_id_of(&f.path))
+            .collect();
+        for lab_id in &manifest.labs {
+            if !restored_dirs.contains(lab_id.as_str()) {
+                warnings.push(format!("Lab {lab_id} has no lab directory in the backup"));
+            }
+        }
+    }
+
+    Ok(warnings)
+}
+
+async fn print_report(
+    db: &Database,
+    manifest: &BackupManifest,
+    errors: &[String],
+    warnings: &[String],
+) -> Result<()> {
+    term_msg_underline("Backup");
+    println!("Created:         {}", manifest.created_at);
+    println!("sherpad version: {}", manifest.sherpa_version);
+    println!("Schema version:  {}", manifest.schema_version);
+    println!("Node images:     {}", yes_no(manifest.include_images));
+    println!("Lab directories: {}", yes_no(manifest.include_labs));
+
+    term_msg_underline("Database");
+    println!("{:<20} {:>8} {:>8}", "TABLE", "CURRENT", "BACKUP");
+    for table in &manifest.tables {
+        println!(
+            "{:<20} {:>8} {:>8}",
+            table.name,
+            count_table(db, &table.name).await?,
+            table.records
+        );
+    }
+
+    term_msg_underline("Files");
+    for entry in &manifest.files {
+        let action = if Path::new(&entry.path).exists() {
+            "overwrite"
+        } else {
+            "create"
+        };
+        println!("{:<10} {}", action, entry.path);
+    }
+
+    if !warnings.is_empty() {
+        term_msg_underline("Warnings");
+        for warning in warnings {
+            println!("- {warning}");
+        }
+    }
+    if !errors.is_empty() {
+        term_msg_underline("Errors");
+        for error in errors {
+            println!("- {error}");
+        }
+    }
+    Ok(())
+}
+
+fn table_entry(table: &str) -> String {
+    format!("{DB_ENTRY_DIR}/{table}.surql")
+}
+
+fn file_entry(path: &str) -> String {
+    format!("{FILES_ENTRY_DIR}/{}", path.trim_start_matches('/'))
+}
+
+fn read_entry<R: Read + std::io::Seek>(zip: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = zip
+        .by_name(name)
+        .context(format!("Backup entry missing: {name}"))?;
+    let mut content = vec![];
+    entry
+        .read_to_end(&mut content)
+        .context(format!("Failed to read backup entry: {name}"))?;
+    Ok(content)
+}
+
+/// Restored files must have an absolute path without `..` components, under
+/// the base directory or at one of the server's TLS paths
+fn check_restore_path(path: &str, tls_paths: &[PathBuf]) -> Result<()> {
+    let path = Path::new(path);
+    if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        bail!(
+            "Refusing to restore file outside the server: {}",
+            path.display()
+        );
+    }
+    if !path.starts_with(SHERPA_BASE_DIR) && !tls_paths.iter().any(|p| p == path) {
+        bail!(
+            "Refusing to restore file outside {} and the configured TLS paths: {}",
+            SHERPA_BASE_DIR,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Sibling path a restored file is written to before it is moved into place
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{STAGING_SUFFIX}"));
+    path.with_file_name(name)
+}
+
+fn remove_staged(staged: &[(PathBuf, PathBuf)]) {
+    for (staging, _) in staged {
+        if let Err(e) = fs::remove_file(staging) {
+            println!("Failed to remove staged file {}: {e}", staging.display());
+        }
+    }
+}
+
+/// Lab ID of a file under the labs directory
+fn lab_id_of(path: &str) -> Option<&str> {
+    path.strip_prefix(SHERPA_LABS_PATH)?
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir).context(format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &[u8], mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context(format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, content).context(format!("Failed to write {}", path.display()))?;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .context(format!("Failed to set permissions on {}", path.display()))?;
+    Ok(())
+}
+
+fn file_mode(path: &Path) -> Result<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    Ok(fs::metadata(path)
+        .context(format!("Failed to read metadata of {}", path.display()))?
+        .permissions()
+        .mode()
+        & 0o7777)
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "included" } else { "not included" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn manifest() -> BackupManifest {
+        BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            sherpa_version: "0.0.0".to_string(),
+            created_at: Timestamp::UNIX_EPOCH,
+            schema_version: 1,
+            include_images: false,
+            include_labs: true,
+            tables: vec![],
+            files: vec![],
+            image_refs: vec![],
+            labs: vec![],
+        }
+    }
+
+    fn archive(entries: &[(&str, &[u8])]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buf);
+            for (name, content) in entries {
+                zip.start_file(*name, SimpleFileOptions::default())
+                    .expect("starts entry");
+                zip.write_all(content).expect("writes entry");
+            }
+            zip.finish().expect("finishes archive");
+        }
+        ZipArchive::new(Cursor::new(buf.into_inner())).expect("reads archive")
+    }
+
+    #[test]
+    fn test_verify_archive_accepts_consistent_backup() {
+        let users = b"UPSERT user CONTENT {};\nUPSERT user CONTENT {};";
+        let secret = b"secret";
+        let mut manifest = manifest();
+        manifest.tables.push(BackupTableEntry {
+            name: "user".to_string(),
+            records: 2,
+            sha256: sha256_hex(users),
+        });
+        manifest.files.push(BackupFileEntry {
+            path: JWT_SECRET_PATH.to_string(),
+            size: secret.len() as u64,
+            sha256: sha256_hex(secret),
+            mode: 0o600,
+        });
+        let mut zip = archive(&[
+            ("db/user.surql", users),
+            (&file_entry(JWT_SECRET_PATH), secret),
+        ]);
+
+        let (tables, errors) = verify_archive(&mut zip, &manifest, &[]);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(tables[0].1.len(), 2);
+    }
+
+    #[test]
+    fn test_verify_archive_reports_problems() {
+        let mut manifest = manifest();
+        manifest.schema_version = db::migration::latest_version() + 1;
+        manifest.tables.push(BackupTableEntry {
+            name: "user".to_string(),
+            records: 3,
+            sha256: sha256_hex(b"UPSERT user CONTENT {};"),
+        });
+        manifest.tables.push(BackupTableEntry {
+            name: "lab".to_string(),
+            records: 0,
+            sha256: sha256_hex(b""),
+        });
+        manifest.files.push(BackupFileEntry {
+            path: "/opt/sherpa/../etc/passwd".to_string(),
+            size: 0,
+            sha256: sha256_hex(b""),
+            mode: 0o644,
+        });
+        let mut zip = archive(&[("db/user.surql", b"UPSERT user CONTENT {};")]);
+
+        let (_, errors) = verify_archive(&mut zip, &manifest, &[]);
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(errors[0].contains("schema version"));
+        assert!(errors[1].contains("has 1 records"));
+        assert!(errors[2].contains("db/lab.surql"));
+        assert!(errors[3].contains("outside the server"));
+    }
+
+    #[test]
+    fn test_verify_archive_restricts_file_paths() {
+        let content = b"content";
+        let tls_paths = [PathBuf::from("/etc/sherpa/tls/server.crt")];
+        let mut manifest = manifest();
+        let paths = [
+            "/opt/sherpa/config/sherpa.toml",
+            "/etc/sherpa/tls/server.crt",
+            "/etc/sherpa/tls/server.key",
+            "/etc/cron.d/sherpa",
+            "/opt/sherpa-other/file",
+        ];
+        let entries: Vec<(String, &[u8])> = paths
+            .iter()
+            .map(|path| (file_entry(path), content.as_slice()))
+            .collect();
+        for path in paths {
+            manifest.files.push(BackupFileEntry {
+                path: path.to_string(),
+                size: content.len() as u64,
+                sha256: sha256_hex(content),
+                mode: 0o644,
+            });
+        }
+        let entries: Vec<(&str, &[u8])> = entries
+            .iter()
+            .map(|(name, content)| (name.as_str(), *content))
+            .collect();
+        let mut zip = archive(&entries);
+
+        let (_, errors) = verify_archive(&mut zip, &manifest, &tls_paths);
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(errors[0].contains("/etc/sherpa/tls/server.key"));
+        assert!(errors[1].contains("/etc/cron.d/sherpa"));
+        assert!(errors[2].contains("/opt/sherpa-other/file"));
+    }
+
+    #[test]
+    fn test_create_archive_owner_only() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("backup.zip");
+
+        create_archive(&file).unwrap();
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        assert!(create_archive(&file).is_err());
+    }
+
+    #[test]
+    fn test_staging_path() {
+        assert_eq!(
+            staging_path(Path::new("/opt/sherpa/.certs/server.key")),
+            PathBuf::from("/opt/sherpa/.certs/server.key.sherpa-restore")
+        );
+    }
+
+    #[test]
+    fn test_lab_id_of() {
+        assert_eq!(
+            lab_id_of("/opt/sherpa/labs/abcd1234/ztp/dev01.cfg"),
+            Some("abcd1234")
+        );
+        assert_eq!(lab_id_of("/opt/sherpa/config/sherpa.toml"), None);
+    }
+}
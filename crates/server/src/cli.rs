@@ -1,4 +1,7 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use shared::data::DatabaseEngine;
 
 #[derive(Parser)]
 #[command(name = "sherpad")]
@@ -68,6 +71,52 @@ pub enum Commands {
         /// SurrealDB port (also reads from SHERPA_DB_PORT env var or /opt/sherpa/env/sherpa.env)
         #[arg(long = "db-port", env = "SHERPA_DB_PORT")]
         db_port: Option<u16>,
+
+        /// Database engine, embedded engines run without a separate SurrealDB server (default: existing config, then remote)
+        #[arg(long = "db-engine", value_enum)]
+        db_engine: Option<DatabaseEngine>,
+    },
+
+    /// Show or apply database schema migrations
+    Migrate {
+        /// SurrealDB password (also reads from SHERPA_DB_PASSWORD env var or /opt/sherpa/env/sherpa.env)
+        #[arg(long = "db-pass", env = "SHERPA_DB_PASSWORD", global = true)]
+        db_password: Option<String>,
+
+        #[command(subcommand)]
+        command: MigrateCommands,
+    },
+
+    /// Back up the database, secrets, TLS material and config to an archive
+    Backup {
+        /// Backup archive to write
+        file: PathBuf,
+
+        /// Include node image records
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        images: bool,
+
+        /// Include lab directories (ZTP configs, lab info, certificates)
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        labs: bool,
+
+        /// SurrealDB password (also reads from SHERPA_DB_PASSWORD env var or /opt/sherpa/env/sherpa.env)
+        #[arg(long = "db-pass", env = "SHERPA_DB_PASSWORD")]
+        db_password: Option<String>,
+    },
+
+    /// Restore a backup archive written by `sherpad backup`
+    Restore {
+        /// Backup archive to restore
+        file: PathBuf,
+
+        /// Check the archive and report what would change, without restoring
+        #[arg(long, action = clap::ArgAction::SetTrue)]
+        dry_run: bool,
+
+        /// SurrealDB password (also reads from SHERPA_DB_PASSWORD env var or /opt/sherpa/env/sherpa.env)
+        #[arg(long = "db-pass", env = "SHERPA_DB_PASSWORD")]
+        db_password: Option<String>,
     },
 
     /// Fix up server environment
@@ -77,3 +126,11 @@ pub enum Commands {
         boxes: bool,
     },
 }
+
+#[derive(Subcommand)]
+pub enum MigrateCommands {
+    /// Show applied and pending migrations
+    Status,
+    /// Apply pending migrations
+    Up,
+}
@@ -1,6 +1,7 @@
-use opentelemetry::metrics::{Counter, Histogram, Meter, UpDownCounter};
+use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter, UpDownCounter};
 use shared::konst::{
-    OTEL_METRIC_ERROR_COUNT, OTEL_METRIC_OPERATION_DURATION, OTEL_METRIC_RPC_DURATION,
+    OTEL_METRIC_ERROR_COUNT, OTEL_METRIC_LINK_BYTES, OTEL_METRIC_LINK_DROPS,
+    OTEL_METRIC_LINK_PACKETS, OTEL_METRIC_OPERATION_DURATION, OTEL_METRIC_RPC_DURATION,
     OTEL_METRIC_WS_CONNECTIONS,
 };
 
@@ -19,6 +20,13 @@ pub struct Metrics {
     pub operation_duration: Histogram<f64>,
     /// Error counter, keyed by `operation.type` and `error.type`.
     pub error_count: Counter<u64>,
+    /// Cumulative packets per link direction, keyed by `lab.id`, `link.index`
+    /// and `link.direction`.
+    pub link_packets: Gauge<u64>,
+    /// Cumulative bytes per link direction, same attributes as `link_packets`.
+    pub link_bytes: Gauge<u64>,
+    /// Cumulative drops per link direction, same attributes as `link_packets`.
+    pub link_drops: Gauge<u64>,
 }
 
 impl Metrics {
@@ -47,11 +55,32 @@ impl Metrics {
             .with_description("Error count by operation and error type")
             .build();
 
+        let link_packets = meter
+            .u64_gauge(OTEL_METRIC_LINK_PACKETS)
+            .with_description("Packets carried by a lab link direction")
+            .with_unit("packets")
+            .build();
+
+        let link_bytes = meter
+            .u64_gauge(OTEL_METRIC_LINK_BYTES)
+            .with_description("Bytes carried by a lab link direction")
+            .with_unit("By")
+            .build();
+
+        let link_drops = meter
+            .u64_gauge(OTEL_METRIC_LINK_DROPS)
+            .with_description("Packets dropped on a lab link direction")
+            .with_unit("packets")
+            .build();
+
         Self {
             ws_connections,
             rpc_duration,
             operation_duration,
             error_count,
+            link_packets,
+            link_bytes,
+            link_drops,
         }
     }
 
@@ -20,6 +20,8 @@ use crate::api::build_router;
 use crate::api::websocket;
 use crate::daemon::metrics::Metrics;
 use crate::daemon::state::AppState;
+use crate::services::boot;
+use crate::services::leases::run_lease_watcher;
 use crate::services::scanner::run_scanner;
 use crate::tls::CertificateManager;
 use shared::data::OtelConfig;
@@ -309,6 +311,9 @@ pub async fn run_server(foreground: bool) -> Result<()> {
         .await
         .context("Failed to initialize application state")?;
 
+    // Restart built-in boot services of labs that were running before
+    boot::restore(&state).await;
+
     // Set up shutdown cancellation token for background services
     let cancel_token = CancellationToken::new();
 
@@ -319,8 +324,15 @@ pub async fn run_server(foreground: bool) -> Result<()> {
         tokio::spawn(async move {
             run_scanner(scanner_state, scanner_token).await;
         });
+
+        // The lease watcher shares the scanner interval
+        let lease_state = state.clone();
+        let lease_token = cancel_token.child_token();
+        tokio::spawn(async move {
+            run_lease_watcher(lease_state, lease_token).await;
+        });
     } else {
-        tracing::info!("Scanner service is disabled");
+        tracing::info!("Scanner service and lease watcher are disabled");
     }
 
     // Clone state for HTTP /cert endpoints before moving into main router
@@ -2,15 +2,17 @@ use anyhow::{Context, Result};
 use bollard::Docker;
 use dashmap::DashMap;
 use libvirt::Qemu;
-use shared::data::{Config, DestroyRequest, UpRequest};
+use shared::data::{Config, DestroyRequest, UpRequest, VerifyCablingResponse};
 use shared::konst::{
     SHERPA_DB_NAME, SHERPA_DB_NAMESPACE, SHERPA_DB_PORT, SHERPA_DB_SERVER, SHERPA_ENV_FILE_PATH,
 };
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 use crate::api::websocket::connection::ConnectionRegistry;
 use crate::auth::jwt;
 use crate::daemon::metrics::Metrics;
+use crate::runtime::Runtime;
 
 /// The type of operation a job represents.
 pub enum JobType {
@@ -31,8 +33,13 @@ pub struct Job {
 /// - Database connection (SurrealDB)
 /// - libvirt client (for VMs and unikernels)
 /// - Docker client (for containers)
+/// - Runtime backends services drive VMs, containers and host networking through
 /// - Sherpa configuration
 /// - JWT secret for authentication
+/// - Key encrypting stored registry credentials
+/// - Built-in boot services of running labs
+/// - Latest cabling verification of each lab
+/// - Host interfaces claimed by labs being brought up
 #[derive(Clone)]
 pub struct AppState {
     /// Registry of active WebSocket connections
@@ -43,15 +50,28 @@ pub struct AppState {
     pub qemu: Arc<Qemu>,
     /// Docker client
     pub docker: Arc<Docker>,
+    /// Runtime backends wrapping the libvirt and Docker clients
+    pub runtime: Runtime,
     /// Sherpa configuration
     pub config: Arc<Config>,
     /// JWT secret for token creation and validation
     pub jwt_secret: Arc<Vec<u8>>,
+    /// Key encrypting stored registry passwords
+    pub registry_key: Arc<Vec<u8>>,
     /// OpenTelemetry metrics instruments
     pub metrics: Metrics,
+    /// Built-in boot services of running labs, keyed by lab ID.
+    /// Cancelling a token stops that lab's services.
+    pub boot_services: Arc<DashMap<String, CancellationToken>>,
     /// Pending jobs awaiting SSE stream pickup.
     /// Keyed by job_id, consumed once by the stream handler.
     pub pending_jobs: Arc<DashMap<String, Job>>,
+    /// Latest cabling verification of each lab, keyed by lab ID.
+    /// Shown by inspect until the lab is destroyed.
+    pub cabling: Arc<DashMap<String, VerifyCablingResponse>>,
+    /// Host interfaces claimed by a running `up`, keyed by interface name
+    /// with the claiming lab ID. Held from the host check until the attach.
+    pub host_interface_claims: Arc<DashMap<String, String>>,
 }
 
 impl AppState {
@@ -62,28 +82,11 @@ impl AppState {
             jwt::load_or_generate_secret().context("Failed to load or generate JWT secret")?;
         tracing::info!("JWT secret loaded successfully");
 
-        // Connect to SurrealDB
-        let db_password = std::env::var("SHERPA_DB_PASSWORD").context(format!(
-            "SHERPA_DB_PASSWORD environment variable is not set (check {})",
-            SHERPA_ENV_FILE_PATH
-        ))?;
-
-        let db_port = std::env::var("SHERPA_DB_PORT")
-            .ok()
-            .and_then(|v| v.parse::<u16>().ok())
-            .unwrap_or(SHERPA_DB_PORT);
-
-        let db = db::connect(
-            SHERPA_DB_SERVER,
-            db_port,
-            SHERPA_DB_NAMESPACE,
-            SHERPA_DB_NAME,
-            &db_password,
-        )
-        .await
-        .context("Failed to connect to SurrealDB")?;
+        let registry_key = crate::services::registry::load_or_generate_key()
+            .context("Failed to load or generate registry key")?;
 
-        tracing::info!("Connected to SurrealDB at {}:{}", SHERPA_DB_SERVER, db_port);
+        // Connect to SurrealDB
+        let db = connect_database(&config).await?;
 
         // Apply database schema
         db::apply_schema(&db)
@@ -91,6 +94,14 @@ impl AppState {
             .context("Failed to apply database schema")?;
         tracing::debug!("Database schema applied");
 
+        // Bring data written by older releases up to date
+        let migrated = db::migrate(&db)
+            .await
+            .context("Failed to apply database migrations")?;
+        if !migrated.is_empty() {
+            tracing::info!(versions = ?migrated, "Applied database migrations");
+        }
+
         // Seed admin user if SHERPA_ADMIN_PASSWORD is set
         if let Ok(admin_password) = std::env::var("SHERPA_ADMIN_PASSWORD") {
             match db::seed_admin_user(&db, &admin_password).await {
@@ -134,15 +145,96 @@ impl AppState {
 
         tracing::info!("Connected to Docker daemon");
 
+        let qemu = Arc::new(qemu);
+        let docker = Arc::new(docker);
+
         Ok(Self {
             connections: crate::api::websocket::connection::create_registry(),
             db,
-            qemu: Arc::new(qemu),
-            docker: Arc::new(docker),
+            runtime: Runtime::new(qemu.clone(), docker.clone()),
+            qemu,
+            docker,
             config: Arc::new(config),
             jwt_secret: Arc::new(jwt_secret),
+            registry_key: Arc::new(registry_key),
             metrics,
+            boot_services: Arc::new(DashMap::new()),
             pending_jobs: Arc::new(DashMap::new()),
+            cabling: Arc::new(DashMap::new()),
+            host_interface_claims: Arc::new(DashMap::new()),
         })
     }
 }
+
+/// Connect to the configured database: a separate SurrealDB server, or an
+/// engine embedded in sherpad.
+async fn connect_database(config: &Config) -> Result<db::Database> {
+    let engine = config.database.engine;
+    if engine.is_embedded() {
+        let db = db::connect_embedded(
+            engine,
+            &config.database.path,
+            SHERPA_DB_NAMESPACE,
+            SHERPA_DB_NAME,
+        )
+        .await
+        .context("Failed to open embedded database")?;
+
+        tracing::info!(
+            engine = ?engine,
+            path = %config.database.path,
+            "Opened embedded database"
+        );
+        return Ok(db);
+    }
+
+    let db_password = std::env::var("SHERPA_DB_PASSWORD").context(format!(
+        "SHERPA_DB_PASSWORD environment variable is not set (check {})",
+        SHERPA_ENV_FILE_PATH
+    ))?;
+
+    let db_port = std::env::var("SHERPA_DB_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(SHERPA_DB_PORT);
+
+    let db = db::connect(
+        SHERPA_DB_SERVER,
+        db_port,
+        SHERPA_DB_NAMESPACE,
+        SHERPA_DB_NAME,
+        &db_password,
+    )
+    .await
+    .context("Failed to connect to SurrealDB")?;
+
+    tracing::info!("Connected to SurrealDB at {}:{}", SHERPA_DB_SERVER, db_port);
+    Ok(db)
+}
+
+#[cfg(test)]
+impl AppState {
+    /// State for service tests: `db` and `runtime` stand in for the real
+    /// infrastructure, and the libvirt and Docker clients are never connected.
+    pub fn for_tests(db: db::Database, config: Config, runtime: Runtime) -> Self {
+        Self {
+            connections: crate::api::websocket::connection::create_registry(),
+            db,
+            qemu: Arc::new(Qemu::default()),
+            // An HTTP client builds without a Docker socket on the host
+            docker: Arc::new(
+                Docker::connect_with_http("http://127.0.0.1:2375", 4, bollard::API_DEFAULT_VERSION)
+                    .expect("Docker client should build"),
+            ),
+            runtime,
+            config: Arc::new(config),
+            jwt_secret: Arc::new(vec![0; 32]),
+            registry_key: Arc::new(vec![0; 32]),
+            metrics: Metrics::noop(),
+            boot_services: Arc::new(DashMap::new()),
+            pending_jobs: Arc::new(DashMap::new()),
+            cabling: Arc::new(DashMap::new()),
+            host_interface_claims: Arc::new(DashMap::new()),
+        }
+    }
+}
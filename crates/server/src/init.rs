@@ -4,9 +4,9 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
-use db::{apply_schema, connect, upsert_user};
+use db::{Database, apply_schema, connect, connect_embedded, migrate, upsert_user};
 use libvirt::{BridgeNetwork, Qemu, SherpaStoragePool};
-use shared::data::{NodeConfig, NodeModel};
+use shared::data::{DatabaseEngine, NodeConfig, NodeModel};
 use shared::konst::{
     SHERPA_BASE_DIR, SHERPA_BINS_PATH, SHERPA_BLANK_DISK_DIR, SHERPA_BRIDGE_NETWORK_BRIDGE,
     SHERPA_BRIDGE_NETWORK_NAME, SHERPA_CONFIG_FILE_PATH, SHERPA_CONFIG_PATH,
@@ -16,7 +16,7 @@ use shared::konst::{
     SHERPA_SSH_PUBLIC_KEY_PATH, SHERPA_STORAGE_POOL, SHERPA_STORAGE_POOL_PATH,
 };
 use shared::util::{
-    create_config, create_dir, default_config, file_exists, generate_ssh_keypair,
+    create_config, create_dir, default_config, file_exists, generate_ssh_keypair, load_config,
     read_env_file_value, term_msg_highlight, term_msg_surround, term_msg_underline,
 };
 use ssh_key::Algorithm;
@@ -28,20 +28,31 @@ pub async fn init(
     ws_port: Option<u16>,
     http_port: Option<u16>,
     db_port: Option<u16>,
+    db_engine: Option<DatabaseEngine>,
 ) -> Result<()> {
     let env_file = Path::new(SHERPA_ENV_FILE_PATH);
 
-    let db_password = match db_password {
-        Some(p) => p.to_string(),
-        None => read_env_file_value(env_file, "SHERPA_DB_PASSWORD").ok_or_else(|| {
-            anyhow::anyhow!(
-                "Database password not provided. Supply it via:\n  \
+    // Keep the engine of an existing config unless one is given
+    let existing_config = load_config(SHERPA_CONFIG_FILE_PATH).ok();
+    let db_engine = db_engine
+        .or(existing_config.as_ref().map(|c| c.database.engine))
+        .unwrap_or_default();
+
+    // Embedded engines have no users, so only a remote server needs a password
+    let db_password = match (db_password, db_engine.is_embedded()) {
+        (_, true) => None,
+        (Some(p), false) => Some(p.to_string()),
+        (None, false) => Some(
+            read_env_file_value(env_file, "SHERPA_DB_PASSWORD").ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Database password not provided. Supply it via:\n  \
                      1. --db-pass flag\n  \
                      2. SHERPA_DB_PASSWORD environment variable\n  \
                      3. SHERPA_DB_PASSWORD entry in {}",
-                env_file.display()
-            )
-        })?,
+                    env_file.display()
+                )
+            })?,
+        ),
     };
 
     let server_ipv4 = match server_ipv4 {
@@ -120,6 +131,7 @@ pub async fn init(
         config.server_ipv6 = server_ipv6_addr;
         config.ws_port = ws_port;
         config.http_port = http_port;
+        config.database.engine = db_engine;
         create_config(&config, SHERPA_CONFIG_FILE_PATH)?;
         println!("Config written to: {SHERPA_CONFIG_FILE_PATH}");
     }
@@ -158,17 +170,29 @@ pub async fn init(
 
     // Database initialization
     term_msg_highlight("Initializing Database");
-    let db = connect(
-        SHERPA_DB_SERVER,
-        db_port,
-        SHERPA_DB_NAMESPACE,
-        SHERPA_DB_NAME,
-        &db_password,
-    )
-    .await?;
+    if db_engine == DatabaseEngine::Memory {
+        println!("In-memory database is created when sherpad starts");
+        println!("Set SHERPA_ADMIN_PASSWORD to create the admin user on startup");
+        term_msg_surround("Sherpa Server Initialized");
+        return Ok(());
+    }
+    let db = match db_password {
+        Some(db_password) => {
+            connect(
+                SHERPA_DB_SERVER,
+                db_port,
+                SHERPA_DB_NAMESPACE,
+                SHERPA_DB_NAME,
+                &db_password,
+            )
+            .await?
+        }
+        None => open_embedded_database(db_engine, existing_config.as_ref()).await?,
+    };
 
     term_msg_underline("Applying Database Schema");
     apply_schema(&db).await?;
+    migrate(&db).await?;
     println!("Database schema applied");
 
     // Create initial admin user
@@ -186,6 +210,18 @@ pub async fn init(
     Ok(())
 }
 
+async fn open_embedded_database(
+    engine: DatabaseEngine,
+    existing_config: Option<&shared::data::Config>,
+) -> Result<Database> {
+    let path = existing_config
+        .map(|c| c.database.path.clone())
+        .unwrap_or_else(|| default_config().database.path);
+    create_dir(&path)?;
+    println!("Opening {engine:?} database: {path}");
+    connect_embedded(engine, &path, SHERPA_DB_NAMESPACE, SHERPA_DB_NAME).await
+}
+
 fn prompt(label: &str) -> Result<String> {
     print!("{}: ", label);
     io::stdout().flush().context("Failed to flush stdout")?;
@@ -3,10 +3,13 @@
 
 pub mod api;
 pub mod auth;
+pub mod backup;
 pub mod cli;
 pub mod daemon;
 pub mod doctor;
 pub mod init;
+pub mod migrate;
+pub mod runtime;
 pub mod services;
 pub mod templates;
 pub mod tls;
@@ -37,6 +37,7 @@ async fn main() -> Result<()> {
             ws_port,
             http_port,
             db_port,
+            db_engine,
         } => {
             sherpad::init::init(
                 force,
@@ -45,9 +46,25 @@ async fn main() -> Result<()> {
                 ws_port,
                 http_port,
                 db_port,
+                db_engine,
             )
             .await
         }
+        Commands::Migrate {
+            db_password,
+            command,
+        } => sherpad::migrate::run_migrate(command, db_password.as_deref()).await,
+        Commands::Backup {
+            file,
+            images,
+            labs,
+            db_password,
+        } => sherpad::backup::run_backup(&file, images, labs, db_password.as_deref()).await,
+        Commands::Restore {
+            file,
+            dry_run,
+            db_password,
+        } => sherpad::backup::run_restore(&file, dry_run, db_password.as_deref()).await,
         Commands::Doctor { boxes } => sherpad::doctor::doctor(boxes),
     }
 }
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use db::{Database, apply_schema, connect, connect_embedded, migrate, migration_status};
+use shared::data::DatabaseEngine;
+use shared::konst::{
+    SHERPA_CONFIG_FILE_PATH, SHERPA_DB_NAME, SHERPA_DB_NAMESPACE, SHERPA_DB_PORT, SHERPA_DB_SERVER,
+    SHERPA_ENV_FILE_PATH,
+};
+use shared::util::{load_config, read_env_file_value, term_msg_surround, term_msg_underline};
+
+use crate::cli::MigrateCommands;
+
+pub async fn run_migrate(command: MigrateCommands, db_password: Option<&str>) -> Result<()> {
+    let db = connect_database(db_password).await?;
+
+    match command {
+        MigrateCommands::Status => print_status(&db).await,
+        MigrateCommands::Up => {
+            term_msg_surround("Migrating Database");
+            apply_schema(&db)
+                .await
+                .context("Failed to apply database schema")?;
+
+            let applied = migrate(&db).await?;
+            if applied.is_empty() {
+                println!("Database is up to date");
+            } else {
+                for version in &applied {
+                    println!("Applied migration {version}");
+                }
+            }
+            print_status(&db).await
+        }
+    }
+}
+
+async fn print_status(db: &Database) -> Result<()> {
+    let status = migration_status(db).await?;
+
+    term_msg_underline("Schema Migrations");
+    println!("Database version: {}", status.current_version);
+    println!("Latest version:   {}", status.latest_version);
+    println!();
+    for step in &status.steps {
+        match step.applied_at {
+            Some(applied_at) => println!(
+                "  ✓ {:>3}  {}  (applied {})",
+                step.version,
+                step.name,
+                applied_at.strftime("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            None => println!("  - {:>3}  {}  (pending)", step.version, step.name),
+        }
+    }
+
+    if status.current_version > status.latest_version {
+        println!();
+        println!("Database is newer than this sherpad, upgrade sherpad before starting it");
+    } else if status.pending() > 0 {
+        println!();
+        println!(
+            "{} pending, run `sherpad migrate up` or start sherpad to apply",
+            status.pending()
+        );
+    }
+    Ok(())
+}
+
+/// Connect to the database configured in `sherpa.toml`, for commands run
+/// outside the daemon.
+pub(crate) async fn connect_database(db_password: Option<&str>) -> Result<Database> {
+    let config = load_config(SHERPA_CONFIG_FILE_PATH)
+        .context(format!("Failed to load config: {SHERPA_CONFIG_FILE_PATH}"))?;
+
+    match config.database.engine {
+        DatabaseEngine::Remote => {}
+        DatabaseEngine::Memory => {
+            bail!("The in-memory database only exists inside a running sherpad")
+        }
+        engine => {
+            return connect_embedded(
+                engine,
+                &config.database.path,
+                SHERPA_DB_NAMESPACE,
+                SHERPA_DB_NAME,
+            )
+            .await;
+        }
+    }
+
+    let env_file = Path::new(SHERPA_ENV_FILE_PATH);
+    let db_password = match db_password {
+        Some(p) => p.to_string(),
+        None => read_env_file_value(env_file, "SHERPA_DB_PASSWORD").ok_or_else(|| {
+            anyhow::anyhow!(
+                "Database password not provided. Supply it via --db-pass, \
+                 SHERPA_DB_PASSWORD or {}",
+                env_file.display()
+            )
+        })?,
+    };
+    let db_port = std::env::var("SHERPA_DB_PORT")
+        .ok()
+        .or_else(|| read_env_file_value(env_file, "SHERPA_DB_PORT"))
+        .and_then(|v| v.parse::<u16>().ok())
+        .unwrap_or(SHERPA_DB_PORT);
+
+    connect(
+        SHERPA_DB_SERVER,
+        db_port,
+        SHERPA_DB_NAMESPACE,
+        SHERPA_DB_NAME,
+        &db_password,
+    )
+    .await
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bollard::Docker;
+use bollard::secret::ContainerSummaryStateEnum;
+use shared::data::{ContainerNetworkAttachment, RegistryAuth};
+
+use super::ContainerRuntime;
+
+/// [`ContainerRuntime`] backed by the local Docker daemon.
+pub struct DockerRuntime {
+    docker: Arc<Docker>,
+}
+
+impl DockerRuntime {
+    pub fn new(docker: Arc<Docker>) -> Self {
+        Self { docker }
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for DockerRuntime {
+    async fn container_states(&self) -> Result<HashMap<String, ContainerSummaryStateEnum>> {
+        let containers = container::list_containers(&self.docker)
+            .await
+            .context("Failed to list Docker containers")?;
+
+        let mut states = HashMap::with_capacity(containers.len());
+        for c in &containers {
+            let name = match &c.names {
+                Some(names) if !names.is_empty() => {
+                    // Docker names have a leading '/'
+                    names[0].trim_start_matches('/').to_string()
+                }
+                _ => continue,
+            };
+            if let Some(status) = c.state {
+                states.insert(name, status);
+            }
+        }
+        Ok(states)
+    }
+
+    async fn list_containers(&self) -> Result<Vec<String>> {
+        let containers = container::list_containers(&self.docker).await?;
+        // From docs: for historical reasons, container names start with a '/'
+        Ok(containers
+            .iter()
+            .filter_map(|c| c.names.as_ref()?.first())
+            .map(|name| name.trim_start_matches('/').to_string())
+            .collect())
+    }
+
+    async fn kill_container(&self, name: &str) -> Result<()> {
+        container::kill_container(&self.docker, name).await
+    }
+
+    async fn remove_container(&self, name: &str) -> Result<()> {
+        container::remove_container(&self.docker, name).await
+    }
+
+    async fn pause_container(&self, name: &str) -> Result<()> {
+        container::pause_container(&self.docker, name).await
+    }
+
+    async fn unpause_container(&self, name: &str) -> Result<()> {
+        container::unpause_container(&self.docker, name).await
+    }
+
+    async fn list_networks(&self) -> Result<Vec<String>> {
+        let networks = container::list_networks(&self.docker).await?;
+        Ok(networks.into_iter().filter_map(|n| n.name).collect())
+    }
+
+    async fn delete_network(&self, name: &str) -> Result<()> {
+        container::delete_network(&self.docker, name).await
+    }
+
+    async fn list_images(&self) -> Result<Vec<String>> {
+        container::get_local_images(&self.docker).await
+    }
+
+    async fn pull_image(&self, repo: &str, tag: &str, auth: Option<&RegistryAuth>) -> Result<()> {
+        container::pull_image(repo, tag, auth, |msg| {
+            tracing::debug!(image = %repo, "{}", msg);
+        })
+        .await
+    }
+
+    async fn create_bridge_network(
+        &self,
+        name: &str,
+        ipv4_prefix: Option<String>,
+        ipv6_prefix: Option<String>,
+        bridge: &str,
+    ) -> Result<()> {
+        container::create_docker_bridge_network(
+            &self.docker,
+            name,
+            ipv4_prefix,
+            ipv6_prefix,
+            bridge,
+        )
+        .await
+    }
+
+    async fn create_macvlan_network(&self, parent: &str, name: &str) -> Result<()> {
+        container::create_docker_macvlan_network(&self.docker, parent, name).await
+    }
+
+    async fn create_macvlan_bridge_network(&self, parent: &str, name: &str) -> Result<()> {
+        container::create_docker_macvlan_bridge_network(&self.docker, parent, name).await
+    }
+
+    async fn run_container(
+        &self,
+        name: &str,
+        image: &str,
+        env_vars: Vec<String>,
+        volumes: Vec<String>,
+        capabilities: Vec<String>,
+        management_network: ContainerNetworkAttachment,
+        additional_networks: Vec<ContainerNetworkAttachment>,
+        commands: Vec<String>,
+        privileged: bool,
+        shm_size: Option<i64>,
+        user: Option<String>,
+    ) -> Result<bool> {
+        container::run_container(
+            &self.docker,
+            name,
+            image,
+            env_vars,
+            volumes,
+            capabilities,
+            management_network,
+            additional_networks,
+            commands,
+            privileged,
+            shm_size,
+            user,
+        )
+        .await
+    }
+
+    async fn container_pid(&self, name: &str) -> Result<u32> {
+        container::get_container_pid(&self.docker, name).await
+    }
+
+    async fn exec_detached(&self, name: &str, cmd: &[&str]) -> Result<()> {
+        container::exec_container_detached(&self.docker, name, cmd.to_vec()).await
+    }
+
+    async fn exec_with_retry(
+        &self,
+        name: &str,
+        cmd: &[&str],
+        retries: u32,
+        delay: Duration,
+    ) -> Result<()> {
+        container::exec_container_with_retry(&self.docker, name, cmd.to_vec(), retries, delay).await
+    }
+}
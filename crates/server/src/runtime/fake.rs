@@ -0,0 +1,722 @@
+//! Recording in-memory runtime backends for unit tests.
+//!
+//! Each fake keeps the resources it was seeded with, mutates them as the
+//! service under test calls into it, and records every mutating call as
+//! `"<method> <name>"`. A call listed with `fail_on` returns an error instead
+//! of taking effect, which lets tests drive partial-failure paths.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use bollard::secret::ContainerSummaryStateEnum;
+use libvirt::NatNetwork;
+use network::{InterfaceStats, LinkImpairment, P2pRedirectStats, TcHook};
+use shared::data::{
+    ContainerNetworkAttachment, DiscoveredNeighbour, LinkFilter, PortVlan, RegistryAuth,
+};
+use virt::sys::{VIR_DOMAIN_PAUSED, VIR_DOMAIN_RUNNING, VIR_DOMAIN_SHUTOFF};
+
+use super::{ContainerRuntime, HostNetwork, Runtime, VmRuntime, VmShutdown, VmStart};
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Call log and injected failures shared by all fakes.
+#[derive(Default)]
+struct Recorder {
+    calls: Mutex<Vec<String>>,
+    failures: BTreeSet<String>,
+}
+
+impl Recorder {
+    /// Fail if `call` was registered with `fail_on`.
+    fn check(&self, call: &str) -> Result<()> {
+        if self.failures.contains(call) {
+            bail!("injected failure: {call}");
+        }
+        Ok(())
+    }
+
+    /// Record a mutating call, then fail if it was registered with `fail_on`.
+    fn record(&self, call: String) -> Result<()> {
+        lock(&self.calls).push(call.clone());
+        self.check(&call)
+    }
+}
+
+/// In-memory [`VmRuntime`].
+#[derive(Default)]
+pub struct FakeVmRuntime {
+    domains: Mutex<BTreeMap<String, u32>>,
+    volumes: Mutex<BTreeSet<String>>,
+    networks: Mutex<BTreeSet<String>>,
+    recorder: Recorder,
+}
+
+impl FakeVmRuntime {
+    pub fn with_domain(self, name: &str, state: u32) -> Self {
+        lock(&self.domains).insert(name.to_string(), state);
+        self
+    }
+
+    pub fn with_volume(self, name: &str) -> Self {
+        lock(&self.volumes).insert(name.to_string());
+        self
+    }
+
+    pub fn with_network(self, name: &str) -> Self {
+        lock(&self.networks).insert(name.to_string());
+        self
+    }
+
+    pub fn fail_on(mut self, call: &str) -> Self {
+        self.recorder.failures.insert(call.to_string());
+        self
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        lock(&self.recorder.calls).clone()
+    }
+
+    pub fn domains(&self) -> BTreeMap<String, u32> {
+        lock(&self.domains).clone()
+    }
+
+    pub fn volumes(&self) -> Vec<String> {
+        lock(&self.volumes).iter().cloned().collect()
+    }
+
+    pub fn networks(&self) -> Vec<String> {
+        lock(&self.networks).iter().cloned().collect()
+    }
+}
+
+#[async_trait]
+impl VmRuntime for FakeVmRuntime {
+    async fn domain_states(&self) -> Result<HashMap<String, u32>> {
+        self.recorder.check("domain_states")?;
+        Ok(lock(&self.domains).clone().into_iter().collect())
+    }
+
+    async fn list_domains(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_domains")?;
+        Ok(lock(&self.domains).keys().cloned().collect())
+    }
+
+    async fn destroy_domain(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("destroy_domain {name}"))?;
+        match lock(&self.domains).remove(name) {
+            Some(_) => Ok(()),
+            None => bail!("Domain not found: {name}"),
+        }
+    }
+
+    async fn shutdown_domain(&self, name: &str) -> Result<VmShutdown> {
+        self.recorder.record(format!("shutdown_domain {name}"))?;
+        let mut domains = lock(&self.domains);
+        let Some(state) = domains.get_mut(name) else {
+            bail!("Domain not found: {name}");
+        };
+        if *state == VIR_DOMAIN_SHUTOFF {
+            return Ok(VmShutdown::AlreadyStopped);
+        }
+        *state = VIR_DOMAIN_SHUTOFF;
+        Ok(VmShutdown::Graceful)
+    }
+
+    async fn start_domain(&self, name: &str) -> Result<VmStart> {
+        self.recorder.record(format!("start_domain {name}"))?;
+        let mut domains = lock(&self.domains);
+        let Some(state) = domains.get_mut(name) else {
+            bail!("Domain not found: {name}");
+        };
+        let outcome = match *state {
+            VIR_DOMAIN_SHUTOFF => VmStart::Started,
+            VIR_DOMAIN_PAUSED => VmStart::Resumed,
+            _ => VmStart::AlreadyRunning,
+        };
+        *state = VIR_DOMAIN_RUNNING;
+        Ok(outcome)
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_volumes")?;
+        Ok(self.volumes())
+    }
+
+    async fn delete_volume(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("delete_volume {name}"))?;
+        if !lock(&self.volumes).remove(name) {
+            bail!("Volume not found: {name}");
+        }
+        Ok(())
+    }
+
+    async fn list_networks(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_networks")?;
+        Ok(self.networks())
+    }
+
+    async fn delete_network(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("delete_network {name}"))?;
+        if !lock(&self.networks).remove(name) {
+            bail!("Network not found: {name}");
+        }
+        Ok(())
+    }
+
+    async fn create_isolated_network(&self, name: &str, _bridge: &str) -> Result<()> {
+        self.recorder.record(format!("create_network {name}"))?;
+        lock(&self.networks).insert(name.to_string());
+        Ok(())
+    }
+
+    async fn create_reserved_network(&self, name: &str, _bridge: &str) -> Result<()> {
+        self.recorder.record(format!("create_network {name}"))?;
+        lock(&self.networks).insert(name.to_string());
+        Ok(())
+    }
+
+    async fn create_nat_network(&self, network: NatNetwork) -> Result<()> {
+        self.recorder
+            .record(format!("create_network {}", network.network_name))?;
+        lock(&self.networks).insert(network.network_name);
+        Ok(())
+    }
+
+    async fn create_domain(&self, name: &str, _xml: String) -> Result<()> {
+        self.recorder.record(format!("create_domain {name}"))?;
+        if lock(&self.domains)
+            .insert(name.to_string(), VIR_DOMAIN_RUNNING)
+            .is_some()
+        {
+            bail!("Domain already exists: {name}");
+        }
+        Ok(())
+    }
+
+    async fn clone_volume(&self, _src: &str, dst: &str, _size_gb: Option<u16>) -> Result<()> {
+        let name = dst.rsplit('/').next().unwrap_or(dst);
+        self.recorder.record(format!("clone_volume {name}"))?;
+        lock(&self.volumes).insert(name.to_string());
+        Ok(())
+    }
+}
+
+/// In-memory [`ContainerRuntime`].
+#[derive(Default)]
+pub struct FakeContainerRuntime {
+    containers: Mutex<BTreeMap<String, ContainerSummaryStateEnum>>,
+    networks: Mutex<BTreeSet<String>>,
+    images: Mutex<BTreeSet<String>>,
+    recorder: Recorder,
+}
+
+impl FakeContainerRuntime {
+    pub fn with_container(self, name: &str, state: ContainerSummaryStateEnum) -> Self {
+        lock(&self.containers).insert(name.to_string(), state);
+        self
+    }
+
+    pub fn with_network(self, name: &str) -> Self {
+        lock(&self.networks).insert(name.to_string());
+        self
+    }
+
+    /// Add a local image, as `repo:tag`.
+    pub fn with_image(self, image: &str) -> Self {
+        lock(&self.images).insert(image.to_string());
+        self
+    }
+
+    pub fn fail_on(mut self, call: &str) -> Self {
+        self.recorder.failures.insert(call.to_string());
+        self
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        lock(&self.recorder.calls).clone()
+    }
+
+    pub fn containers(&self) -> BTreeMap<String, ContainerSummaryStateEnum> {
+        lock(&self.containers).clone()
+    }
+
+    pub fn networks(&self) -> Vec<String> {
+        lock(&self.networks).iter().cloned().collect()
+    }
+
+    pub fn images(&self) -> Vec<String> {
+        lock(&self.images).iter().cloned().collect()
+    }
+
+    fn add_network(&self, name: &str) -> Result<()> {
+        if !lock(&self.networks).insert(name.to_string()) {
+            bail!("Network {name} already exists");
+        }
+        Ok(())
+    }
+
+    fn require_running(&self, name: &str) -> Result<()> {
+        match lock(&self.containers).get(name) {
+            Some(ContainerSummaryStateEnum::RUNNING) => Ok(()),
+            Some(state) => bail!("Container {name} is {state}"),
+            None => bail!("No such container: {name}"),
+        }
+    }
+
+    /// Move a container from `from` to `to`, failing from any other state.
+    fn transition(
+        &self,
+        name: &str,
+        from: ContainerSummaryStateEnum,
+        to: ContainerSummaryStateEnum,
+    ) -> Result<()> {
+        let mut containers = lock(&self.containers);
+        match containers.get_mut(name) {
+            Some(state) if *state == from => {
+                *state = to;
+                Ok(())
+            }
+            Some(state) => bail!("Container {name} is {state}"),
+            None => bail!("No such container: {name}"),
+        }
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for FakeContainerRuntime {
+    async fn container_states(&self) -> Result<HashMap<String, ContainerSummaryStateEnum>> {
+        self.recorder.check("container_states")?;
+        Ok(self.containers().into_iter().collect())
+    }
+
+    async fn list_containers(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_containers")?;
+        Ok(lock(&self.containers).keys().cloned().collect())
+    }
+
+    async fn kill_container(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("kill_container {name}"))?;
+        self.transition(
+            name,
+            ContainerSummaryStateEnum::RUNNING,
+            ContainerSummaryStateEnum::EXITED,
+        )
+    }
+
+    async fn remove_container(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("remove_container {name}"))?;
+        match lock(&self.containers).remove(name) {
+            Some(_) => Ok(()),
+            None => bail!("No such container: {name}"),
+        }
+    }
+
+    async fn pause_container(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("pause_container {name}"))?;
+        self.transition(
+            name,
+            ContainerSummaryStateEnum::RUNNING,
+            ContainerSummaryStateEnum::PAUSED,
+        )
+    }
+
+    async fn unpause_container(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("unpause_container {name}"))?;
+        self.transition(
+            name,
+            ContainerSummaryStateEnum::PAUSED,
+            ContainerSummaryStateEnum::RUNNING,
+        )
+    }
+
+    async fn list_networks(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_networks")?;
+        Ok(self.networks())
+    }
+
+    async fn delete_network(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("delete_network {name}"))?;
+        if !lock(&self.networks).remove(name) {
+            bail!("No such network: {name}");
+        }
+        Ok(())
+    }
+
+    async fn list_images(&self) -> Result<Vec<String>> {
+        self.recorder.check("list_images")?;
+        Ok(self.images())
+    }
+
+    async fn pull_image(&self, repo: &str, tag: &str, _auth: Option<&RegistryAuth>) -> Result<()> {
+        self.recorder.record(format!("pull_image {repo}:{tag}"))?;
+        lock(&self.images).insert(format!("{repo}:{tag}"));
+        Ok(())
+    }
+
+    async fn create_bridge_network(
+        &self,
+        name: &str,
+        _ipv4_prefix: Option<String>,
+        _ipv6_prefix: Option<String>,
+        _bridge: &str,
+    ) -> Result<()> {
+        self.recorder.record(format!("create_network {name}"))?;
+        self.add_network(name)
+    }
+
+    async fn create_macvlan_network(&self, _parent: &str, name: &str) -> Result<()> {
+        self.recorder.record(format!("create_network {name}"))?;
+        self.add_network(name)
+    }
+
+    async fn create_macvlan_bridge_network(&self, _parent: &str, name: &str) -> Result<()> {
+        self.recorder.record(format!("create_network {name}"))?;
+        self.add_network(name)
+    }
+
+    async fn run_container(
+        &self,
+        name: &str,
+        image: &str,
+        _env_vars: Vec<String>,
+        _volumes: Vec<String>,
+        _capabilities: Vec<String>,
+        management_network: ContainerNetworkAttachment,
+        additional_networks: Vec<ContainerNetworkAttachment>,
+        _commands: Vec<String>,
+        _privileged: bool,
+        _shm_size: Option<i64>,
+        _user: Option<String>,
+    ) -> Result<bool> {
+        self.recorder.record(format!("run_container {name}"))?;
+        if !lock(&self.images).contains(image) {
+            bail!("No such image: {image}");
+        }
+        let networks = lock(&self.networks);
+        if let Some(missing) = std::iter::once(&management_network)
+            .chain(&additional_networks)
+            .find(|attachment| !networks.contains(&attachment.name))
+        {
+            bail!("Network {} not found", missing.name);
+        }
+        let mut containers = lock(&self.containers);
+        if containers.contains_key(name) {
+            bail!("Container {name} already exists");
+        }
+        containers.insert(name.to_string(), ContainerSummaryStateEnum::RUNNING);
+        Ok(true)
+    }
+
+    async fn container_pid(&self, name: &str) -> Result<u32> {
+        self.recorder.check("container_pid")?;
+        self.require_running(name)?;
+        let index = lock(&self.containers)
+            .keys()
+            .position(|container| container == name)
+            .unwrap_or_default();
+        Ok(1000 + index as u32)
+    }
+
+    async fn exec_detached(&self, name: &str, _cmd: &[&str]) -> Result<()> {
+        self.recorder.record(format!("exec {name}"))?;
+        self.require_running(name)
+    }
+
+    async fn exec_with_retry(
+        &self,
+        name: &str,
+        _cmd: &[&str],
+        _retries: u32,
+        _delay: Duration,
+    ) -> Result<()> {
+        self.recorder.record(format!("exec {name}"))?;
+        self.require_running(name)
+    }
+}
+
+/// In-memory [`HostNetwork`].
+#[derive(Default)]
+pub struct FakeHostNetwork {
+    interfaces: Mutex<BTreeSet<String>>,
+    interface_stats: BTreeMap<String, InterfaceStats>,
+    p2p_stats: HashMap<u32, P2pRedirectStats>,
+    neighbours: Vec<(String, TcHook, DiscoveredNeighbour)>,
+    recorder: Recorder,
+}
+
+impl FakeHostNetwork {
+    pub fn with_interface(self, name: &str) -> Self {
+        lock(&self.interfaces).insert(name.to_string());
+        self
+    }
+
+    /// Add an interface that reports `stats`.
+    pub fn with_interface_stats(mut self, name: &str, stats: InterfaceStats) -> Self {
+        lock(&self.interfaces).insert(name.to_string());
+        self.interface_stats.insert(name.to_string(), stats);
+        self
+    }
+
+    /// Add an eBPF redirect program that redirects to `peer_ifindex`.
+    pub fn with_p2p_stats(mut self, peer_ifindex: u32, stats: P2pRedirectStats) -> Self {
+        self.p2p_stats.insert(peer_ifindex, stats);
+        self
+    }
+
+    /// Make a capture on `hook` of `interface` decode `neighbour`.
+    pub fn with_neighbour(
+        mut self,
+        interface: &str,
+        hook: TcHook,
+        neighbour: DiscoveredNeighbour,
+    ) -> Self {
+        self.neighbours
+            .push((interface.to_string(), hook, neighbour));
+        self
+    }
+
+    pub fn fail_on(mut self, call: &str) -> Self {
+        self.recorder.failures.insert(call.to_string());
+        self
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        lock(&self.recorder.calls).clone()
+    }
+
+    pub fn interfaces(&self) -> Vec<String> {
+        lock(&self.interfaces).iter().cloned().collect()
+    }
+
+    fn add_interfaces(&self, names: &[&str]) -> Result<()> {
+        let mut interfaces = lock(&self.interfaces);
+        if let Some(existing) = names.iter().find(|name| interfaces.contains(**name)) {
+            bail!("Interface {existing} already exists");
+        }
+        interfaces.extend(names.iter().map(|name| name.to_string()));
+        Ok(())
+    }
+
+    fn require_interfaces(&self, names: &[&str]) -> Result<()> {
+        let interfaces = lock(&self.interfaces);
+        if let Some(missing) = names.iter().find(|name| !interfaces.contains(**name)) {
+            bail!("Interface not found: {missing}");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HostNetwork for FakeHostNetwork {
+    async fn find_interfaces(&self, pattern: &str) -> Result<Vec<String>> {
+        self.recorder.check("find_interfaces")?;
+        Ok(lock(&self.interfaces)
+            .iter()
+            .filter(|name| name.contains(pattern))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_interface(&self, name: &str) -> Result<()> {
+        self.recorder.record(format!("delete_interface {name}"))?;
+        if !lock(&self.interfaces).remove(name) {
+            bail!("Interface not found: {name}");
+        }
+        Ok(())
+    }
+
+    async fn interface_stats(&self, pattern: &str) -> Result<HashMap<String, InterfaceStats>> {
+        self.recorder.check("interface_stats")?;
+        let interfaces = lock(&self.interfaces);
+        Ok(self
+            .interface_stats
+            .iter()
+            .filter(|(name, _)| name.contains(pattern) && interfaces.contains(*name))
+            .map(|(name, stats)| (name.clone(), *stats))
+            .collect())
+    }
+
+    async fn p2p_redirect_stats(&self) -> Result<HashMap<u32, P2pRedirectStats>> {
+        self.recorder.check("p2p_redirect_stats")?;
+        Ok(self.p2p_stats.clone())
+    }
+
+    async fn add_p2p_mirror(&self, peer: &str, target: &str) -> Result<()> {
+        self.require_interfaces(&[peer, target])?;
+        self.recorder
+            .record(format!("add_p2p_mirror {peer} {target}"))
+    }
+
+    async fn remove_p2p_mirror(&self, peer: &str, target: &str) -> Result<()> {
+        self.require_interfaces(&[peer, target])?;
+        self.recorder
+            .record(format!("remove_p2p_mirror {peer} {target}"))
+    }
+
+    async fn add_tc_mirror(
+        &self,
+        interface: &str,
+        hook: TcHook,
+        target: &str,
+        priority: u16,
+    ) -> Result<()> {
+        self.require_interfaces(&[interface, target])?;
+        self.recorder.record(format!(
+            "add_tc_mirror {interface} {hook:?} {target} {priority}"
+        ))
+    }
+
+    async fn remove_tc_mirror(&self, interface: &str, hook: TcHook, priority: u16) -> Result<()> {
+        self.require_interfaces(&[interface])?;
+        self.recorder
+            .record(format!("remove_tc_mirror {interface} {hook:?} {priority}"))
+    }
+
+    async fn set_p2p_filter(&self, peer: &str, filter: &LinkFilter) -> Result<()> {
+        self.require_interfaces(&[peer])?;
+        self.recorder
+            .record(format!("set_p2p_filter {peer} {filter}"))
+    }
+
+    async fn set_l2_filter(&self, interface: &str, filter: &LinkFilter) -> Result<()> {
+        self.require_interfaces(&[interface])?;
+        self.recorder
+            .record(format!("set_l2_filter {interface} {filter}"))
+    }
+
+    async fn set_bridge_port_vlans(
+        &self,
+        port: &str,
+        bridge: &str,
+        vlans: &[PortVlan],
+    ) -> Result<()> {
+        self.require_interfaces(&[port])?;
+        let vlans = vlans
+            .iter()
+            .map(PortVlan::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.recorder
+            .record(format!("set_bridge_port_vlans {port} {bridge} {vlans}"))
+    }
+
+    async fn capture_neighbours(
+        &self,
+        targets: &[(String, TcHook)],
+        _window: Duration,
+    ) -> Result<Vec<Option<DiscoveredNeighbour>>> {
+        self.recorder.check("capture_neighbours")?;
+        let names: Vec<&str> = targets.iter().map(|(name, _)| name.as_str()).collect();
+        self.require_interfaces(&names)?;
+        Ok(targets
+            .iter()
+            .map(|(name, hook)| {
+                self.neighbours
+                    .iter()
+                    .find(|(interface, on, _)| interface == name && on == hook)
+                    .map(|(_, _, neighbour)| neighbour.clone())
+            })
+            .collect())
+    }
+
+    async fn check_host_interface(&self, name: &str) -> Result<()> {
+        self.recorder.check("check_host_interface")?;
+        self.require_interfaces(&[name])
+    }
+
+    async fn create_bridge(&self, name: &str, _alias: &str, _mtu: u16) -> Result<()> {
+        self.recorder.record(format!("create_bridge {name}"))?;
+        self.add_interfaces(&[name])
+    }
+
+    async fn create_vlan_bridge(&self, name: &str, _alias: &str, _mtu: u16) -> Result<()> {
+        self.recorder.record(format!("create_vlan_bridge {name}"))?;
+        self.add_interfaces(&[name])
+    }
+
+    async fn create_veth_pair(
+        &self,
+        name_a: &str,
+        name_b: &str,
+        _alias_a: &str,
+        _alias_b: &str,
+        _mtu: u16,
+    ) -> Result<()> {
+        self.recorder
+            .record(format!("create_veth_pair {name_a} {name_b}"))?;
+        self.add_interfaces(&[name_a, name_b])
+    }
+
+    async fn enslave_to_bridge(&self, interface: &str, bridge: &str) -> Result<()> {
+        self.require_interfaces(&[interface, bridge])?;
+        self.recorder
+            .record(format!("enslave_to_bridge {interface} {bridge}"))
+    }
+
+    async fn attach_host_interface(&self, interface: &str, bridge: &str) -> Result<()> {
+        self.require_interfaces(&[interface, bridge])?;
+        self.recorder
+            .record(format!("attach_host_interface {interface} {bridge}"))
+    }
+
+    async fn set_link_down(&self, name: &str) -> Result<()> {
+        self.require_interfaces(&[name])?;
+        self.recorder.record(format!("set_link_down {name}"))
+    }
+
+    async fn move_to_netns(&self, name: &str, pid: u32) -> Result<()> {
+        self.require_interfaces(&[name])?;
+        self.recorder
+            .record(format!("move_to_netns {name} {pid}"))?;
+        // The interface leaves the host namespace
+        lock(&self.interfaces).remove(name);
+        Ok(())
+    }
+
+    async fn attach_p2p_redirect(&self, interface: &str, peer: &str) -> Result<()> {
+        self.require_interfaces(&[interface, peer])?;
+        self.recorder
+            .record(format!("attach_p2p_redirect {interface} {peer}"))
+    }
+
+    async fn apply_netem(&self, interface: &str, _impairment: &LinkImpairment) -> Result<()> {
+        self.require_interfaces(&[interface])?;
+        self.recorder.record(format!("apply_netem {interface}"))
+    }
+}
+
+/// Handles to the fakes behind a [`Runtime`], for seeding and inspection.
+pub struct FakeRuntime {
+    pub vms: Arc<FakeVmRuntime>,
+    pub containers: Arc<FakeContainerRuntime>,
+    pub network: Arc<FakeHostNetwork>,
+}
+
+impl FakeRuntime {
+    pub fn new(
+        vms: FakeVmRuntime,
+        containers: FakeContainerRuntime,
+        network: FakeHostNetwork,
+    ) -> Self {
+        Self {
+            vms: Arc::new(vms),
+            containers: Arc::new(containers),
+            network: Arc::new(network),
+        }
+    }
+
+    /// A [`Runtime`] whose backends are these fakes.
+    pub fn runtime(&self) -> Runtime {
+        Runtime {
+            vms: self.vms.clone(),
+            containers: self.containers.clone(),
+            network: self.network.clone(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use network::{InterfaceStats, LinkImpairment, P2pRedirectStats, TcHook};
+use shared::data::{DiscoveredNeighbour, LinkFilter, PortVlan};
+
+use super::HostNetwork;
+
+/// [`HostNetwork`] backed by netlink on the local host.
+pub struct LinuxHostNetwork;
+
+#[async_trait]
+impl HostNetwork for LinuxHostNetwork {
+    async fn find_interfaces(&self, pattern: &str) -> Result<Vec<String>> {
+        network::find_interfaces_fuzzy(pattern).await
+    }
+
+    async fn delete_interface(&self, name: &str) -> Result<()> {
+        network::delete_interface(name).await
+    }
+
+    async fn interface_stats(&self, pattern: &str) -> Result<HashMap<String, InterfaceStats>> {
+        network::interface_stats(pattern).await
+    }
+
+    async fn p2p_redirect_stats(&self) -> Result<HashMap<u32, P2pRedirectStats>> {
+        // Walking the loaded BPF programs is a series of blocking syscalls.
+        tokio::task::spawn_blocking(network::p2p_redirect_stats)
+            .await
+            .context("eBPF stats task panicked")?
+    }
+
+    async fn add_p2p_mirror(&self, peer: &str, target: &str) -> Result<()> {
+        let peer_ifindex = network::get_ifindex(peer).await?;
+        let target_ifindex = network::get_ifindex(target).await?;
+        tokio::task::spawn_blocking(move || network::add_p2p_mirror(peer_ifindex, target_ifindex))
+            .await
+            .context("eBPF mirror task panicked")?
+    }
+
+    async fn remove_p2p_mirror(&self, peer: &str, target: &str) -> Result<()> {
+        let peer_ifindex = network::get_ifindex(peer).await?;
+        let target_ifindex = network::get_ifindex(target).await?;
+        tokio::task::spawn_blocking(move || {
+            network::remove_p2p_mirror(peer_ifindex, target_ifindex)
+        })
+        .await
+        .context("eBPF mirror task panicked")?
+    }
+
+    async fn add_tc_mirror(
+        &self,
+        interface: &str,
+        hook: TcHook,
+        target: &str,
+        priority: u16,
+    ) -> Result<()> {
+        let ifindex = network::get_ifindex(interface).await?;
+        let target_ifindex = network::get_ifindex(target).await?;
+        network::add_mirror(ifindex as i32, hook, target_ifindex, priority).await
+    }
+
+    async fn remove_tc_mirror(&self, interface: &str, hook: TcHook, priority: u16) -> Result<()> {
+        let ifindex = network::get_ifindex(interface).await?;
+        network::remove_mirror(ifindex as i32, hook, priority).await
+    }
+
+    async fn set_p2p_filter(&self, peer: &str, filter: &LinkFilter) -> Result<()> {
+        let peer_ifindex = network::get_ifindex(peer).await?;
+        let filter = filter.clone();
+        tokio::task::spawn_blocking(move || network::set_p2p_filter(peer_ifindex, &filter))
+            .await
+            .context("eBPF filter task panicked")?
+    }
+
+    async fn set_bridge_port_vlans(
+        &self,
+        port: &str,
+        bridge: &str,
+        vlans: &[PortVlan],
+    ) -> Result<()> {
+        network::set_bridge_port_vlans(port, bridge, vlans).await
+    }
+
+    async fn set_l2_filter(&self, interface: &str, filter: &LinkFilter) -> Result<()> {
+        let interface = interface.to_string();
+        let filter = filter.clone();
+        tokio::task::spawn_blocking(move || network::set_l2_filter(&interface, &filter))
+            .await
+            .context("eBPF filter task panicked")?
+    }
+
+    async fn capture_neighbours(
+        &self,
+        targets: &[(String, TcHook)],
+        window: Duration,
+    ) -> Result<Vec<Option<DiscoveredNeighbour>>> {
+        let mut ifindexes = Vec::with_capacity(targets.len());
+        for (interface, hook) in targets {
+            ifindexes.push((network::get_ifindex(interface).await?, *hook));
+        }
+        tokio::task::spawn_blocking(move || network::capture_neighbours(&ifindexes, window))
+            .await
+            .context("Neighbour capture task panicked")?
+    }
+
+    async fn check_host_interface(&self, name: &str) -> Result<()> {
+        network::check_host_interface(name).await
+    }
+
+    async fn create_bridge(&self, name: &str, alias: &str, mtu: u16) -> Result<()> {
+        network::create_bridge(name, alias, mtu).await
+    }
+
+    async fn create_vlan_bridge(&self, name: &str, alias: &str, mtu: u16) -> Result<()> {
+        network::create_vlan_bridge(name, alias, mtu).await
+    }
+
+    async fn create_veth_pair(
+        &self,
+        name_a: &str,
+        name_b: &str,
+        alias_a: &str,
+        alias_b: &str,
+        mtu: u16,
+    ) -> Result<()> {
+        network::create_veth_pair(name_a, name_b, alias_a, alias_b, mtu).await
+    }
+
+    async fn enslave_to_bridge(&self, interface: &str, bridge: &str) -> Result<()> {
+        network::enslave_to_bridge(interface, bridge).await
+    }
+
+    async fn attach_host_interface(&self, interface: &str, bridge: &str) -> Result<()> {
+        network::attach_host_interface(interface, bridge).await
+    }
+
+    async fn set_link_down(&self, name: &str) -> Result<()> {
+        network::set_link_down(name).await
+    }
+
+    async fn move_to_netns(&self, name: &str, pid: u32) -> Result<()> {
+        network::move_to_netns(name, pid).await
+    }
+
+    async fn attach_p2p_redirect(&self, interface: &str, peer: &str) -> Result<()> {
+        let peer_ifindex = network::get_ifindex(peer)
+            .await
+            .with_context(|| format!("failed to get ifindex for {peer}"))?;
+        let interface = interface.to_string();
+        tokio::task::spawn_blocking(move || network::attach_p2p_redirect(&interface, peer_ifindex))
+            .await
+            .context("eBPF redirect task panicked")?
+    }
+
+    async fn apply_netem(&self, interface: &str, impairment: &LinkImpairment) -> Result<()> {
+        let ifindex = network::get_ifindex(interface)
+            .await
+            .with_context(|| format!("failed to get ifindex for {interface}"))?;
+        network::apply_netem(ifindex as i32, impairment).await
+    }
+}
@@ -0,0 +1,291 @@
+//! Runtime backends used by the lab services.
+//!
+//! Services talk to libvirt, Docker and the host network stack through the
+//! [`VmRuntime`], [`ContainerRuntime`] and [`HostNetwork`] traits rather than
+//! calling `virt`, `bollard` and `network` directly. The daemon wires in the
+//! real backends; unit tests swap in the recording fakes from `fake`.
+
+mod docker;
+#[cfg(test)]
+pub mod fake;
+mod host;
+mod qemu;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bollard::Docker;
+use bollard::secret::ContainerSummaryStateEnum;
+use libvirt::{NatNetwork, Qemu};
+use network::{InterfaceStats, LinkImpairment, P2pRedirectStats, TcHook};
+use shared::data::{
+    ContainerNetworkAttachment, DiscoveredNeighbour, LinkFilter, PortVlan, RegistryAuth,
+};
+
+pub use docker::DockerRuntime;
+pub use host::LinuxHostNetwork;
+pub use qemu::QemuRuntime;
+
+/// Outcome of a VM shutdown request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmShutdown {
+    /// ACPI shutdown sent through the guest agent
+    Graceful,
+    /// Powered off because no guest agent answered
+    Forced,
+    /// Domain was not running
+    AlreadyStopped,
+}
+
+/// Outcome of a VM start request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmStart {
+    /// Cold booted a shut off domain
+    Started,
+    /// Resumed a paused domain
+    Resumed,
+    /// Domain was already running
+    AlreadyRunning,
+}
+
+/// Virtual machine backend (libvirt/QEMU).
+#[async_trait]
+pub trait VmRuntime: Send + Sync {
+    /// Map of every domain name to its libvirt state code.
+    async fn domain_states(&self) -> Result<HashMap<String, u32>>;
+
+    /// Names of every defined domain.
+    async fn list_domains(&self) -> Result<Vec<String>>;
+
+    /// Undefine a domain (and its NVRAM), powering it off if it is running.
+    async fn destroy_domain(&self, name: &str) -> Result<()>;
+
+    /// Shut a domain down, gracefully when a guest agent is available.
+    async fn shutdown_domain(&self, name: &str) -> Result<VmShutdown>;
+
+    /// Boot a shut off domain or resume a paused one.
+    async fn start_domain(&self, name: &str) -> Result<VmStart>;
+
+    /// Names of every volume in the Sherpa storage pool.
+    async fn list_volumes(&self) -> Result<Vec<String>>;
+
+    /// Delete a volume from the Sherpa storage pool.
+    async fn delete_volume(&self, name: &str) -> Result<()>;
+
+    /// Names of every libvirt network.
+    async fn list_networks(&self) -> Result<Vec<String>>;
+
+    /// Stop and undefine a libvirt network.
+    async fn delete_network(&self, name: &str) -> Result<()>;
+
+    /// Define and start an isolated network, whose ports cannot reach one another.
+    async fn create_isolated_network(&self, name: &str, bridge: &str) -> Result<()>;
+
+    /// Define and start a network for the control traffic between VM components.
+    async fn create_reserved_network(&self, name: &str, bridge: &str) -> Result<()>;
+
+    /// Define and start a NAT network.
+    async fn create_nat_network(&self, network: NatNetwork) -> Result<()>;
+
+    /// Define a persistent domain from its XML and boot it.
+    async fn create_domain(&self, name: &str, xml: String) -> Result<()>;
+
+    /// Clone the disk at `src` to `dst`, growing it to `size_gb` if set.
+    async fn clone_volume(&self, src: &str, dst: &str, size_gb: Option<u16>) -> Result<()>;
+}
+
+/// Container backend (Docker).
+#[async_trait]
+pub trait ContainerRuntime: Send + Sync {
+    /// Map of every container name to its state.
+    async fn container_states(&self) -> Result<HashMap<String, ContainerSummaryStateEnum>>;
+
+    /// Names of every container, without Docker's leading `/`.
+    async fn list_containers(&self) -> Result<Vec<String>>;
+
+    /// Send SIGKILL to a container.
+    async fn kill_container(&self, name: &str) -> Result<()>;
+
+    /// Remove a container.
+    async fn remove_container(&self, name: &str) -> Result<()>;
+
+    /// Pause a running container.
+    async fn pause_container(&self, name: &str) -> Result<()>;
+
+    /// Unpause a paused container.
+    async fn unpause_container(&self, name: &str) -> Result<()>;
+
+    /// Names of every Docker network.
+    async fn list_networks(&self) -> Result<Vec<String>>;
+
+    /// Remove a Docker network.
+    async fn delete_network(&self, name: &str) -> Result<()>;
+
+    /// Every local image as `repo:tag`.
+    async fn list_images(&self) -> Result<Vec<String>>;
+
+    /// Pull an image, authenticating to its registry with `auth`.
+    async fn pull_image(&self, repo: &str, tag: &str, auth: Option<&RegistryAuth>) -> Result<()>;
+
+    /// Create a bridge network on the host bridge `bridge`.
+    async fn create_bridge_network(
+        &self,
+        name: &str,
+        ipv4_prefix: Option<String>,
+        ipv6_prefix: Option<String>,
+        bridge: &str,
+    ) -> Result<()>;
+
+    /// Create a macvlan network in passthru mode on `parent`.
+    async fn create_macvlan_network(&self, parent: &str, name: &str) -> Result<()>;
+
+    /// Create a macvlan network in bridge mode on `parent`.
+    async fn create_macvlan_bridge_network(&self, parent: &str, name: &str) -> Result<()>;
+
+    /// Create and start a container, returning whether it is running.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_container(
+        &self,
+        name: &str,
+        image: &str,
+        env_vars: Vec<String>,
+        volumes: Vec<String>,
+        capabilities: Vec<String>,
+        management_network: ContainerNetworkAttachment,
+        additional_networks: Vec<ContainerNetworkAttachment>,
+        commands: Vec<String>,
+        privileged: bool,
+        shm_size: Option<i64>,
+        user: Option<String>,
+    ) -> Result<bool>;
+
+    /// PID of a running container's init process.
+    async fn container_pid(&self, name: &str) -> Result<u32>;
+
+    /// Run a command in a container without waiting for it.
+    async fn exec_detached(&self, name: &str, cmd: &[&str]) -> Result<()>;
+
+    /// Run a command in a container, retrying `retries` times `delay` apart.
+    async fn exec_with_retry(
+        &self,
+        name: &str,
+        cmd: &[&str],
+        retries: u32,
+        delay: Duration,
+    ) -> Result<()>;
+}
+
+/// Host network stack (netlink).
+#[async_trait]
+pub trait HostNetwork: Send + Sync {
+    /// Names of host interfaces containing `pattern`.
+    async fn find_interfaces(&self, pattern: &str) -> Result<Vec<String>>;
+
+    /// Delete a host interface.
+    async fn delete_interface(&self, name: &str) -> Result<()>;
+
+    /// Kernel counters of host interfaces containing `pattern`, keyed by name.
+    async fn interface_stats(&self, pattern: &str) -> Result<HashMap<String, InterfaceStats>>;
+
+    /// Counters of the loaded eBPF redirect programs, keyed by peer ifindex.
+    async fn p2p_redirect_stats(&self) -> Result<HashMap<u32, P2pRedirectStats>>;
+
+    /// Also send the frames the eBPF redirect program forwards to `peer` to `target`.
+    async fn add_p2p_mirror(&self, peer: &str, target: &str) -> Result<()>;
+
+    /// Stop copying the frames forwarded to `peer` to `target`.
+    async fn remove_p2p_mirror(&self, peer: &str, target: &str) -> Result<()>;
+
+    /// Copy the frames on a clsact hook of `interface` to `target`.
+    async fn add_tc_mirror(
+        &self,
+        interface: &str,
+        hook: TcHook,
+        target: &str,
+        priority: u16,
+    ) -> Result<()>;
+
+    /// Remove the mirror filter with `priority` from a clsact hook of `interface`.
+    async fn remove_tc_mirror(&self, interface: &str, hook: TcHook, priority: u16) -> Result<()>;
+
+    /// Replace the filter of the eBPF redirect program forwarding to `peer`.
+    async fn set_p2p_filter(&self, peer: &str, filter: &LinkFilter) -> Result<()>;
+
+    /// Replace the eBPF filter program on the ingress of `interface`.
+    async fn set_l2_filter(&self, interface: &str, filter: &LinkFilter) -> Result<()>;
+
+    /// Join `port` to the VLAN-aware `bridge` as a member of `vlans` only.
+    async fn set_bridge_port_vlans(
+        &self,
+        port: &str,
+        bridge: &str,
+        vlans: &[PortVlan],
+    ) -> Result<()>;
+
+    /// First LLDP or CDP advertisement on a hook of each interface within
+    /// `window`, in the order of `targets`.
+    async fn capture_neighbours(
+        &self,
+        targets: &[(String, TcHook)],
+        window: Duration,
+    ) -> Result<Vec<Option<DiscoveredNeighbour>>>;
+
+    /// Fail unless host interface `name` exists and can join a lab bridge.
+    async fn check_host_interface(&self, name: &str) -> Result<()>;
+
+    /// Create a bridge described by `alias`.
+    async fn create_bridge(&self, name: &str, alias: &str, mtu: u16) -> Result<()>;
+
+    /// Create a VLAN-aware bridge described by `alias`.
+    async fn create_vlan_bridge(&self, name: &str, alias: &str, mtu: u16) -> Result<()>;
+
+    /// Create a veth pair, describing each end by its alias.
+    async fn create_veth_pair(
+        &self,
+        name_a: &str,
+        name_b: &str,
+        alias_a: &str,
+        alias_b: &str,
+        mtu: u16,
+    ) -> Result<()>;
+
+    /// Join `interface` to `bridge`.
+    async fn enslave_to_bridge(&self, interface: &str, bridge: &str) -> Result<()>;
+
+    /// Join the host interface `interface` to `bridge` for an external link.
+    async fn attach_host_interface(&self, interface: &str, bridge: &str) -> Result<()>;
+
+    /// Set an interface administratively down.
+    async fn set_link_down(&self, name: &str) -> Result<()>;
+
+    /// Move an interface into the network namespace of process `pid`.
+    async fn move_to_netns(&self, name: &str, pid: u32) -> Result<()>;
+
+    /// Attach the eBPF program redirecting every frame received on `interface` to `peer`.
+    async fn attach_p2p_redirect(&self, interface: &str, peer: &str) -> Result<()>;
+
+    /// Impair the frames sent out of `interface` with netem.
+    async fn apply_netem(&self, interface: &str, impairment: &LinkImpairment) -> Result<()>;
+}
+
+/// The set of runtime backends available to services.
+#[derive(Clone)]
+pub struct Runtime {
+    pub vms: Arc<dyn VmRuntime>,
+    pub containers: Arc<dyn ContainerRuntime>,
+    pub network: Arc<dyn HostNetwork>,
+}
+
+impl Runtime {
+    /// Build the production backends on top of existing libvirt and Docker clients.
+    pub fn new(qemu: Arc<Qemu>, docker: Arc<Docker>) -> Self {
+        Self {
+            vms: Arc::new(QemuRuntime::new(qemu)),
+            containers: Arc::new(DockerRuntime::new(docker)),
+            network: Arc::new(LinuxHostNetwork),
+        }
+    }
+}
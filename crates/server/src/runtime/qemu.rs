@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use libvirt::{IsolatedNetwork, NatNetwork, Qemu, QemuConnection, ReservedNetwork};
+use shared::konst::SHERPA_STORAGE_POOL;
+use virt::domain::Domain;
+use virt::network::Network;
+use virt::storage_pool::StoragePool;
+use virt::sys::{VIR_DOMAIN_PAUSED, VIR_DOMAIN_SHUTOFF, VIR_DOMAIN_UNDEFINE_NVRAM};
+
+use super::{VmRuntime, VmShutdown, VmStart};
+
+/// [`VmRuntime`] backed by the local libvirt/QEMU daemon.
+pub struct QemuRuntime {
+    qemu: Arc<Qemu>,
+}
+
+impl QemuRuntime {
+    pub fn new(qemu: Arc<Qemu>) -> Self {
+        Self { qemu }
+    }
+
+    /// Run a libvirt operation on a blocking thread, since libvirt calls are
+    /// blocking FFI.
+    async fn with_conn<T, F>(&self, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&QemuConnection) -> Result<T> + Send + 'static,
+    {
+        let qemu = self.qemu.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = qemu.connect().context("Failed to connect to libvirt")?;
+            op(&conn)
+        })
+        .await
+        .context("libvirt task panicked")?
+    }
+}
+
+/// Check if the QEMU guest agent is available by sending a ping command.
+/// Uses a short timeout (5s) so we don't block long on unresponsive VMs.
+fn has_guest_agent(domain: &Domain) -> bool {
+    domain
+        .qemu_agent_command(r#"{"execute":"guest-ping"}"#, 5, 0)
+        .is_ok()
+}
+
+#[async_trait]
+impl VmRuntime for QemuRuntime {
+    async fn domain_states(&self) -> Result<HashMap<String, u32>> {
+        self.with_conn(|conn| {
+            let domains = conn
+                .list_all_domains(0)
+                .context("Failed to list libvirt domains")?;
+
+            let mut states = HashMap::with_capacity(domains.len());
+            for domain in &domains {
+                let name = match domain.get_name() {
+                    Ok(name) => name,
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Failed to get domain name, skipping");
+                        continue;
+                    }
+                };
+                let state_code = match domain.get_state() {
+                    Ok((state, _reason)) => state,
+                    Err(e) => {
+                        tracing::debug!(domain = %name, error = %e, "Failed to get domain state");
+                        continue;
+                    }
+                };
+                states.insert(name, state_code);
+            }
+            Ok(states)
+        })
+        .await
+    }
+
+    async fn list_domains(&self) -> Result<Vec<String>> {
+        self.with_conn(|conn| {
+            let domains = conn.list_all_domains(0).context("Failed to list domains")?;
+            Ok(domains
+                .iter()
+                .filter_map(|domain| match domain.get_name() {
+                    Ok(name) => Some(name),
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Failed to get domain name, skipping");
+                        None
+                    }
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn destroy_domain(&self, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let domain = Domain::lookup_by_name(conn, &name).context("Domain not found")?;
+            let is_active = domain.is_active().unwrap_or(false);
+
+            // UEFI domains will have an NVRAM file that must be deleted.
+            domain
+                .undefine_flags(VIR_DOMAIN_UNDEFINE_NVRAM)
+                .context("Failed to undefine domain")?;
+            if is_active {
+                domain.destroy().context("Failed to destroy domain")?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn shutdown_domain(&self, name: &str) -> Result<VmShutdown> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let domain = Domain::lookup_by_name(conn, &name).context("Domain not found")?;
+
+            if !domain.is_active().context("Failed to check state")? {
+                return Ok(VmShutdown::AlreadyStopped);
+            }
+
+            if has_guest_agent(&domain) {
+                // Guest agent available — graceful ACPI shutdown
+                domain.shutdown().context("Failed to shutdown")?;
+                Ok(VmShutdown::Graceful)
+            } else {
+                // No guest agent — force power off
+                domain.destroy().context("Failed to power off")?;
+                Ok(VmShutdown::Forced)
+            }
+        })
+        .await
+    }
+
+    async fn start_domain(&self, name: &str) -> Result<VmStart> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let domain = Domain::lookup_by_name(conn, &name).context("Domain not found")?;
+            let (state, _reason) = domain.get_state().context("Failed to get state")?;
+
+            if state == VIR_DOMAIN_SHUTOFF {
+                // Cold boot a defined-but-inactive domain
+                domain.create().context("Failed to start")?;
+                Ok(VmStart::Started)
+            } else if state == VIR_DOMAIN_PAUSED {
+                domain.resume().context("Failed to resume")?;
+                Ok(VmStart::Resumed)
+            } else {
+                Ok(VmStart::AlreadyRunning)
+            }
+        })
+        .await
+    }
+
+    async fn list_volumes(&self) -> Result<Vec<String>> {
+        self.with_conn(|conn| {
+            let storage_pool = StoragePool::lookup_by_name(conn, SHERPA_STORAGE_POOL).context(
+                format!("Failed to find storage pool '{}'", SHERPA_STORAGE_POOL),
+            )?;
+            storage_pool
+                .list_volumes()
+                .context("Failed to list storage volumes")
+        })
+        .await
+    }
+
+    async fn delete_volume(&self, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.with_conn(move |conn| libvirt::delete_disk(conn, &name))
+            .await
+    }
+
+    async fn list_networks(&self) -> Result<Vec<String>> {
+        self.with_conn(|conn| {
+            let networks = conn
+                .list_all_networks(0)
+                .context("Failed to list networks")?;
+            Ok(networks
+                .iter()
+                .filter_map(|network| match network.get_name() {
+                    Ok(name) => Some(name),
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Failed to get network name, skipping");
+                        None
+                    }
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn delete_network(&self, name: &str) -> Result<()> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            let network = Network::lookup_by_name(conn, &name).context("Network not found")?;
+            if network.is_active().unwrap_or(false) {
+                network.destroy().context("Failed to destroy network")?;
+            }
+            network.undefine().context("Failed to undefine network")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn create_isolated_network(&self, name: &str, bridge: &str) -> Result<()> {
+        let network = IsolatedNetwork {
+            network_name: name.to_string(),
+            bridge_name: bridge.to_string(),
+        };
+        self.with_conn(move |conn| network.create(conn)).await
+    }
+
+    async fn create_reserved_network(&self, name: &str, bridge: &str) -> Result<()> {
+        let network = ReservedNetwork {
+            network_name: name.to_string(),
+            bridge_name: bridge.to_string(),
+        };
+        self.with_conn(move |conn| network.create(conn)).await
+    }
+
+    async fn create_nat_network(&self, network: NatNetwork) -> Result<()> {
+        self.with_conn(move |conn| network.create(conn)).await
+    }
+
+    async fn create_domain(&self, name: &str, xml: String) -> Result<()> {
+        let name = name.to_string();
+        self.with_conn(move |conn| {
+            libvirt::create_vm(conn, &xml)
+                .with_context(|| format!("Failed to create domain: {name}"))?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn clone_volume(&self, src: &str, dst: &str, size_gb: Option<u16>) -> Result<()> {
+        let src = src.to_string();
+        let dst = dst.to_string();
+        self.with_conn(move |conn| {
+            libvirt::clone_disk(conn, &src, &dst)
+                .with_context(|| format!("Failed to clone disk from: {src} to: {dst}"))?;
+
+            if let Some(size_gb) = size_gb {
+                libvirt::resize_disk(conn, &dst, size_gb)
+                    .with_context(|| format!("Failed to resize disk '{dst}' to {size_gb}G"))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, Result, anyhow, bail};
+use jiff::{Timestamp, ToSpan};
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use shared::auth::api_token::{api_token_display_prefix, generate_api_token, hash_api_token};
+use shared::data::{
+    ApiTokenInfo, CreateApiTokenResponse, DbApiToken, ListApiTokensResponse, RecordId,
+    RevokeApiTokenResponse, TokenScope,
+};
+use shared::konst::API_TOKEN_MAX_EXPIRY_DAYS;
+
+/// Convert a stored token to its display form.
+fn token_info(token: &DbApiToken) -> ApiTokenInfo {
+    ApiTokenInfo {
+        name: token.name.clone(),
+        prefix: token.prefix.clone(),
+        scopes: token.scopes.clone(),
+        expires_at: token.expires_at.as_second(),
+        last_used_at: token.last_used_at.map(|ts| ts.as_second()),
+        created_at: token.created_at.as_second(),
+    }
+}
+
+/// Look up the record ID of a user.
+async fn user_id(username: &str, state: &AppState) -> Result<RecordId> {
+    db::get_user(&state.db, username)
+        .await
+        .context(format!("User not found: {}", username))?
+        .id
+        .ok_or_else(|| anyhow!("User '{}' missing record ID", username))
+}
+
+/// Create a personal API token for a user
+///
+/// The returned response holds the only copy of the token, the database
+/// only stores its SHA-256 digest.
+///
+/// # Errors
+/// Returns error if:
+/// - No scope is given or `expires_in_days` is out of range (1-365)
+/// - A non-admin user requests the `image-admin` scope
+/// - The name is invalid or already used by one of the user's tokens
+/// - Database operation fails
+#[instrument(skip(state, scopes), fields(%name, %username))]
+pub async fn create_api_token(
+    name: &str,
+    scopes: &[TokenScope],
+    expires_in_days: u32,
+    username: &str,
+    state: &AppState,
+) -> Result<CreateApiTokenResponse> {
+    if scopes.is_empty() {
+        bail!("At least one token scope is required");
+    }
+    if !(1..=API_TOKEN_MAX_EXPIRY_DAYS).contains(&expires_in_days) {
+        bail!(
+            "Token expiry must be between 1 and {} days, got {}",
+            API_TOKEN_MAX_EXPIRY_DAYS,
+            expires_in_days
+        );
+    }
+
+    let user = db::get_user(&state.db, username)
+        .await
+        .context(format!("User not found: {}", username))?;
+    if scopes.contains(&TokenScope::ImageAdmin) && !user.is_admin {
+        bail!("Only administrators can create tokens with the image-admin scope");
+    }
+    let user_id = user
+        .id
+        .ok_or_else(|| anyhow!("User '{}' missing record ID", username))?;
+
+    let mut granted: Vec<TokenScope> = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        if !granted.contains(scope) {
+            granted.push(*scope);
+        }
+    }
+
+    let secret = generate_api_token();
+    let now = Timestamp::now();
+    let token = db::create_api_token(
+        &state.db,
+        DbApiToken {
+            id: None,
+            name: name.to_string(),
+            user: user_id,
+            token_hash: hash_api_token(&secret),
+            prefix: api_token_display_prefix(&secret),
+            scopes: granted,
+            expires_at: now + (i64::from(expires_in_days) * 24).hours(),
+            last_used_at: None,
+            created_at: now,
+        },
+    )
+    .await?;
+
+    tracing::info!(
+        username = %username,
+        token = %name,
+        scopes = ?token.scopes,
+        "API token created"
+    );
+
+    Ok(CreateApiTokenResponse {
+        api_token: secret,
+        info: token_info(&token),
+    })
+}
+
+/// List a user's API tokens
+///
+/// # Errors
+/// Returns error if the user doesn't exist or a database query fails
+#[instrument(skip(state), fields(%username))]
+pub async fn list_api_tokens(username: &str, state: &AppState) -> Result<ListApiTokensResponse> {
+    let user_id = user_id(username, state).await?;
+    let tokens = db::list_api_tokens_by_user(&state.db, &user_id).await?;
+
+    Ok(ListApiTokensResponse {
+        tokens: tokens.iter().map(token_info).collect(),
+    })
+}
+
+/// Revoke one of a user's API tokens
+///
+/// # Errors
+/// Returns error if the user has no token with this name or a database
+/// operation fails
+#[instrument(skip(state), fields(%name, %username))]
+pub async fn revoke_api_token(
+    name: &str,
+    username: &str,
+    state: &AppState,
+) -> Result<RevokeApiTokenResponse> {
+    let user_id = user_id(username, state).await?;
+
+    if !db::delete_api_token(&state.db, &user_id, name).await? {
+        bail!("API token not found: {}", name);
+    }
+
+    tracing::info!(username = %username, token = %name, "API token revoked");
+
+    Ok(RevokeApiTokenResponse {
+        success: true,
+        name: name.to_string(),
+    })
+}
@@ -0,0 +1,756 @@
+//! DHCPv4 server for a lab management network.
+//!
+//! Nodes with a [`ZtpRecord`] get their reserved address along with the
+//! options pointing them at their startup config, the same options the
+//! dnsmasq config template sets. Other clients get an address from the
+//! dynamic range.
+
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+
+use shared::data::{DhcpLease, ZtpMethod, ZtpRecord};
+use shared::konst::{
+    DHCP_CLIENT_PORT, DHCP_SERVER_PORT, HTTP_PORT, NODE_CONFIGS_DIR, SHERPA_DOMAIN_NAME,
+};
+
+use super::BootConfig;
+use super::leases::LeaseStore;
+
+/// Lease time handed to clients, the same as the dnsmasq range
+const LEASE_TIME: u32 = 120;
+
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+/// Length of the fixed BOOTP header, before the magic cookie
+const HEADER_LEN: usize = 236;
+/// Replies are padded to the minimum BOOTP message length
+const MIN_PACKET_LEN: usize = 300;
+const BOOTREQUEST: u8 = 1;
+const BOOTREPLY: u8 = 2;
+const HTYPE_ETHERNET: u8 = 1;
+const HLEN_ETHERNET: u8 = 6;
+
+// Message types (option 53)
+const DISCOVER: u8 = 1;
+const OFFER: u8 = 2;
+const REQUEST: u8 = 3;
+const DECLINE: u8 = 4;
+const ACK: u8 = 5;
+const NAK: u8 = 6;
+const RELEASE: u8 = 7;
+const INFORM: u8 = 8;
+
+// Options
+const OPT_PAD: u8 = 0;
+const OPT_SUBNET_MASK: u8 = 1;
+const OPT_ROUTER: u8 = 3;
+const OPT_DNS_SERVER: u8 = 6;
+const OPT_HOSTNAME: u8 = 12;
+const OPT_DOMAIN_NAME: u8 = 15;
+const OPT_VENDOR_SPECIFIC: u8 = 43;
+const OPT_REQUESTED_IP: u8 = 50;
+const OPT_LEASE_TIME: u8 = 51;
+const OPT_MESSAGE_TYPE: u8 = 53;
+const OPT_SERVER_ID: u8 = 54;
+const OPT_VENDOR_CLASS: u8 = 60;
+const OPT_CLIENT_ID: u8 = 61;
+const OPT_TFTP_SERVER_NAME: u8 = 66;
+const OPT_BOOTFILE_NAME: u8 = 67;
+const OPT_TFTP_SERVER_ADDRESS: u8 = 150;
+const OPT_CONFIG_URL: u8 = 239;
+const OPT_END: u8 = 255;
+
+// Juniper ZTP sub-options of option 43
+const JUNIPER_VENDOR_CLASS: &[u8] = b"Juniper";
+const JUNIPER_CONFIG_FILE_NAME: u8 = 1;
+const JUNIPER_TRANSFER_MODE: u8 = 3;
+
+/// A DHCPv4 message on an Ethernet network
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct DhcpPacket {
+    pub op: u8,
+    pub xid: u32,
+    pub flags: u16,
+    pub ciaddr: Ipv4Addr,
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub chaddr: [u8; 6],
+    pub options: Vec<(u8, Vec<u8>)>,
+}
+
+impl DhcpPacket {
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        if buf.len() < HEADER_LEN + MAGIC_COOKIE.len() {
+            bail!("DHCP packet too short: {} bytes", buf.len());
+        }
+        if buf[1] != HTYPE_ETHERNET || buf[2] != HLEN_ETHERNET {
+            bail!(
+                "Unsupported DHCP hardware type {} length {}",
+                buf[1],
+                buf[2]
+            );
+        }
+        if buf[HEADER_LEN..HEADER_LEN + MAGIC_COOKIE.len()] != MAGIC_COOKIE {
+            bail!("DHCP packet has no magic cookie");
+        }
+
+        let address = |at: usize| Ipv4Addr::new(buf[at], buf[at + 1], buf[at + 2], buf[at + 3]);
+        let mut chaddr = [0u8; 6];
+        chaddr.copy_from_slice(&buf[28..34]);
+
+        let mut options = vec![];
+        let mut at = HEADER_LEN + MAGIC_COOKIE.len();
+        while let Some(&code) = buf.get(at) {
+            at += 1;
+            match code {
+                OPT_PAD => continue,
+                OPT_END => break,
+                _ => {}
+            }
+            let Some(&len) = buf.get(at) else {
+                bail!("DHCP option {} is truncated", code);
+            };
+            at += 1;
+            let Some(value) = buf.get(at..at + usize::from(len)) else {
+                bail!("DHCP option {} is truncated", code);
+            };
+            options.push((code, value.to_vec()));
+            at += usize::from(len);
+        }
+
+        Ok(Self {
+            op: buf[0],
+            xid: u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]),
+            flags: u16::from_be_bytes([buf[10], buf[11]]),
+            ciaddr: address(12),
+            yiaddr: address(16),
+            siaddr: address(20),
+            giaddr: address(24),
+            chaddr,
+            options,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; HEADER_LEN];
+        buf[0] = self.op;
+        buf[1] = HTYPE_ETHERNET;
+        buf[2] = HLEN_ETHERNET;
+        buf[4..8].copy_from_slice(&self.xid.to_be_bytes());
+        buf[10..12].copy_from_slice(&self.flags.to_be_bytes());
+        buf[12..16].copy_from_slice(&self.ciaddr.octets());
+        buf[16..20].copy_from_slice(&self.yiaddr.octets());
+        buf[20..24].copy_from_slice(&self.siaddr.octets());
+        buf[24..28].copy_from_slice(&self.giaddr.octets());
+        buf[28..34].copy_from_slice(&self.chaddr);
+        buf.extend_from_slice(&MAGIC_COOKIE);
+
+        for (code, value) in &self.options {
+            buf.push(*code);
+            buf.push(value.len() as u8);
+            buf.extend_from_slice(value);
+        }
+        buf.push(OPT_END);
+        if buf.len() < MIN_PACKET_LEN {
+            buf.resize(MIN_PACKET_LEN, OPT_PAD);
+        }
+        buf
+    }
+
+    pub fn option(&self, code: u8) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    /// Add an option, truncated to the 255 bytes an option can hold.
+    fn push_option(&mut self, code: u8, mut value: Vec<u8>) {
+        value.truncate(usize::from(u8::MAX));
+        self.options.push((code, value));
+    }
+
+    fn address_option(&self, code: u8) -> Option<Ipv4Addr> {
+        let octets: [u8; 4] = self.option(code)?.try_into().ok()?;
+        Some(Ipv4Addr::from(octets))
+    }
+
+    pub fn message_type(&self) -> Option<u8> {
+        self.option(OPT_MESSAGE_TYPE)?.first().copied()
+    }
+
+    /// Client MAC address in the lowercase colon form used by ZTP records
+    pub fn mac(&self) -> String {
+        self.chaddr
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+/// The reservation of a client, matched on its MAC address.
+fn reservation<'a>(config: &'a BootConfig, mac: &str) -> Option<&'a ZtpRecord> {
+    config
+        .records
+        .iter()
+        .find(|r| !r.mac_address.is_empty() && r.mac_address.eq_ignore_ascii_case(mac))
+}
+
+/// Whether a dynamic range address is free for a client to use.
+fn is_free(
+    config: &BootConfig,
+    leases: &LeaseStore,
+    mac: &str,
+    address: Ipv4Addr,
+    now: u64,
+) -> bool {
+    let in_range = (config.dhcp_start..=config.dhcp_end).contains(&address);
+    let reserved = address == config.server_ipv4
+        || address == config.gateway_ipv4
+        || config.records.iter().any(|r| r.ipv4_address == address);
+    let leased_to_other = leases
+        .holder(address, now)
+        .is_some_and(|l| !l.mac_address.eq_ignore_ascii_case(mac));
+    in_range && !reserved && !leased_to_other
+}
+
+/// Pick the address for a client.
+///
+/// Reservations win, then the client's previous lease, then the address it
+/// asked for, then the first free address of the dynamic range.
+fn allocate(
+    config: &BootConfig,
+    leases: &LeaseStore,
+    mac: &str,
+    requested: Option<Ipv4Addr>,
+    now: u64,
+) -> Option<Ipv4Addr> {
+    if let Some(record) = reservation(config, mac) {
+        return Some(record.ipv4_address);
+    }
+
+    let previous = leases
+        .for_mac(mac)
+        .and_then(|l| l.ipv4_address.parse::<Ipv4Addr>().ok());
+    previous
+        .into_iter()
+        .chain(requested)
+        .find(|address| is_free(config, leases, mac, *address, now))
+        .or_else(|| {
+            (config.dhcp_start.to_bits()..=config.dhcp_end.to_bits())
+                .map(Ipv4Addr::from_bits)
+                .find(|address| is_free(config, leases, mac, *address, now))
+        })
+}
+
+/// Handle a client message, updating the leases.
+///
+/// # Returns
+/// The reply to send, if any
+pub(super) fn handle_packet(
+    config: &BootConfig,
+    leases: &mut LeaseStore,
+    request: &DhcpPacket,
+    now: u64,
+) -> Option<DhcpPacket> {
+    if request.op != BOOTREQUEST {
+        return None;
+    }
+    let mac = request.mac();
+    let requested = request.address_option(OPT_REQUESTED_IP);
+
+    match request.message_type()? {
+        DISCOVER => {
+            let address = allocate(config, leases, &mac, requested, now)?;
+            Some(reply(config, request, OFFER, Some(address)))
+        }
+        REQUEST => {
+            // Selecting another server's offer
+            if request
+                .address_option(OPT_SERVER_ID)
+                .is_some_and(|server| server != config.server_ipv4)
+            {
+                return None;
+            }
+            let address =
+                requested.or((!request.ciaddr.is_unspecified()).then_some(request.ciaddr))?;
+            if allocate(config, leases, &mac, Some(address), now) != Some(address) {
+                return Some(nak(config, request));
+            }
+
+            let hostname = reservation(config, &mac)
+                .map(|r| r.node_name.clone())
+                .or_else(|| {
+                    request
+                        .option(OPT_HOSTNAME)
+                        .map(|h| String::from_utf8_lossy(h).into_owned())
+                })
+                .unwrap_or_default();
+            let client_id = request
+                .option(OPT_CLIENT_ID)
+                .map(|id| {
+                    id.iter()
+                        .map(|b| format!("{b:02x}"))
+                        .collect::<Vec<_>>()
+                        .join(":")
+                })
+                .unwrap_or_default();
+            leases.upsert(DhcpLease {
+                expiry: now + u64::from(LEASE_TIME),
+                mac_address: mac,
+                ipv4_address: address.to_string(),
+                ipv6_address: None,
+                hostname,
+                client_id,
+            });
+            Some(reply(config, request, ACK, Some(address)))
+        }
+        RELEASE => {
+            leases.release(&mac, request.ciaddr);
+            None
+        }
+        DECLINE => {
+            if let Some(address) = requested {
+                tracing::warn!(lab_id = %config.lab_id, %mac, %address, "DHCP client declined address in use");
+                leases.decline(address, now + u64::from(LEASE_TIME));
+            }
+            None
+        }
+        INFORM => Some(reply(config, request, ACK, None)),
+        _ => None,
+    }
+}
+
+/// Build an OFFER or ACK.
+///
+/// `address` is `None` when acknowledging an INFORM, which gets the network
+/// options but no lease.
+fn reply(
+    config: &BootConfig,
+    request: &DhcpPacket,
+    message_type: u8,
+    address: Option<Ipv4Addr>,
+) -> DhcpPacket {
+    let mut packet = DhcpPacket {
+        op: BOOTREPLY,
+        xid: request.xid,
+        flags: request.flags,
+        ciaddr: request.ciaddr,
+        yiaddr: address.unwrap_or(Ipv4Addr::UNSPECIFIED),
+        siaddr: config.server_ipv4,
+        giaddr: request.giaddr,
+        chaddr: request.chaddr,
+        options: vec![],
+    };
+    packet.push_option(OPT_MESSAGE_TYPE, vec![message_type]);
+    packet.push_option(OPT_SERVER_ID, config.server_ipv4.octets().to_vec());
+    if address.is_some() {
+        packet.push_option(OPT_LEASE_TIME, LEASE_TIME.to_be_bytes().to_vec());
+    }
+    packet.push_option(OPT_SUBNET_MASK, config.netmask().octets().to_vec());
+    packet.push_option(OPT_ROUTER, config.gateway_ipv4.octets().to_vec());
+    packet.push_option(OPT_DNS_SERVER, config.gateway_ipv4.octets().to_vec());
+    packet.push_option(OPT_DOMAIN_NAME, SHERPA_DOMAIN_NAME.as_bytes().to_vec());
+    packet.push_option(
+        OPT_TFTP_SERVER_NAME,
+        config.server_ipv4.to_string().into_bytes(),
+    );
+    packet.push_option(
+        OPT_TFTP_SERVER_ADDRESS,
+        config.server_ipv4.octets().to_vec(),
+    );
+
+    let Some(record) = reservation(config, &request.mac()) else {
+        return packet;
+    };
+    packet.push_option(OPT_HOSTNAME, record.node_name.as_bytes().to_vec());
+    match record.ztp_method {
+        ZtpMethod::Tftp => {
+            packet.push_option(OPT_BOOTFILE_NAME, record.config_file.as_bytes().to_vec());
+            let juniper = request
+                .option(OPT_VENDOR_CLASS)
+                .is_some_and(|class| class.starts_with(JUNIPER_VENDOR_CLASS));
+            if juniper {
+                packet.push_option(OPT_VENDOR_SPECIFIC, juniper_options(&record.config_file));
+            }
+        }
+        ZtpMethod::Http => {
+            let url = format!(
+                "http://{}:{}/{}/{}",
+                config.server_ipv4, HTTP_PORT, NODE_CONFIGS_DIR, record.config_file
+            );
+            packet.push_option(OPT_BOOTFILE_NAME, url.clone().into_bytes());
+            packet.push_option(OPT_CONFIG_URL, url.into_bytes());
+        }
+        _ => {}
+    }
+    packet
+}
+
+fn nak(config: &BootConfig, request: &DhcpPacket) -> DhcpPacket {
+    let mut packet = DhcpPacket {
+        op: BOOTREPLY,
+        xid: request.xid,
+        flags: request.flags,
+        ciaddr: Ipv4Addr::UNSPECIFIED,
+        yiaddr: Ipv4Addr::UNSPECIFIED,
+        siaddr: Ipv4Addr::UNSPECIFIED,
+        giaddr: request.giaddr,
+        chaddr: request.chaddr,
+        options: vec![],
+    };
+    packet.push_option(OPT_MESSAGE_TYPE, vec![NAK]);
+    packet.push_option(OPT_SERVER_ID, config.server_ipv4.octets().to_vec());
+    packet
+}
+
+/// Juniper ZTP vendor options: the config file and its transfer mode.
+fn juniper_options(config_file: &str) -> Vec<u8> {
+    let mut options = vec![];
+    for (code, value) in [
+        (JUNIPER_CONFIG_FILE_NAME, config_file.as_bytes()),
+        (JUNIPER_TRANSFER_MODE, b"tftp".as_slice()),
+    ] {
+        options.push(code);
+        options.push(value.len() as u8);
+        options.extend_from_slice(value);
+    }
+    options
+}
+
+/// Where to send a reply.
+///
+/// Renewing clients have an address and get a unicast reply, everything else
+/// is broadcast as the client cannot receive unicast yet.
+fn reply_destination(request: &DhcpPacket, reply: &DhcpPacket) -> SocketAddrV4 {
+    if reply.message_type() != Some(NAK) && !request.ciaddr.is_unspecified() {
+        SocketAddrV4::new(request.ciaddr, DHCP_CLIENT_PORT)
+    } else {
+        SocketAddrV4::new(Ipv4Addr::BROADCAST, DHCP_CLIENT_PORT)
+    }
+}
+
+fn message_name(message_type: Option<u8>) -> &'static str {
+    match message_type {
+        Some(OFFER) => "OFFER",
+        Some(ACK) => "ACK",
+        Some(NAK) => "NAK",
+        _ => "unknown",
+    }
+}
+
+/// Bind the DHCP server port on the management bridge.
+pub(super) fn bind(bridge: &str) -> Result<UdpSocket> {
+    super::bind_udp(
+        SocketAddr::from((Ipv4Addr::UNSPECIFIED, DHCP_SERVER_PORT)),
+        Some(bridge),
+    )
+}
+
+/// Answer DHCP clients until the lab's boot services are stopped.
+pub(super) async fn serve(socket: UdpSocket, config: Arc<BootConfig>, token: CancellationToken) {
+    let leases_path = config.leases_path();
+    let mut leases = LeaseStore::load(leases_path.clone()).unwrap_or_else(|e| {
+        tracing::warn!(lab_id = %config.lab_id, error = %e, "Starting with empty DHCP leases");
+        LeaseStore::new(leases_path)
+    });
+
+    let mut buf = [0u8; 1500];
+    loop {
+        let len = tokio::select! {
+            _ = token.cancelled() => break,
+            received = socket.recv_from(&mut buf) => match received {
+                Ok((len, _)) => len,
+                Err(e) => {
+                    tracing::warn!(lab_id = %config.lab_id, error = %e, "DHCP receive failed");
+                    continue;
+                }
+            },
+        };
+        let request = match DhcpPacket::parse(&buf[..len]) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::debug!(lab_id = %config.lab_id, error = %e, "Ignoring malformed DHCP packet");
+                continue;
+            }
+        };
+
+        let reply = handle_packet(&config, &mut leases, &request, super::now());
+        if let Err(e) = leases.save_if_dirty() {
+            tracing::warn!(lab_id = %config.lab_id, error = %e, "Failed to save DHCP leases");
+        }
+        let Some(reply) = reply else {
+            continue;
+        };
+
+        tracing::debug!(
+            lab_id = %config.lab_id,
+            mac = %request.mac(),
+            address = %reply.yiaddr,
+            message = message_name(reply.message_type()),
+            "Sending DHCP reply"
+        );
+        let destination = reply_destination(&request, &reply);
+        if let Err(e) = socket.send_to(&reply.to_bytes(), destination).await {
+            tracing::warn!(lab_id = %config.lab_id, error = %e, "DHCP send failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::boot::tests::test_config;
+
+    const DEV01_MAC: [u8; 6] = [0x52, 0x54, 0x00, 0xaa, 0xbb, 0x01];
+    const DEV02_MAC: [u8; 6] = [0x52, 0x54, 0x00, 0xaa, 0xbb, 0x02];
+    const OTHER_MAC: [u8; 6] = [0x52, 0x54, 0x00, 0xcc, 0xdd, 0x01];
+
+    fn request(mac: [u8; 6], message_type: u8, options: Vec<(u8, Vec<u8>)>) -> DhcpPacket {
+        let mut packet = DhcpPacket {
+            op: BOOTREQUEST,
+            xid: 0x1234_5678,
+            flags: 0x8000,
+            ciaddr: Ipv4Addr::UNSPECIFIED,
+            yiaddr: Ipv4Addr::UNSPECIFIED,
+            siaddr: Ipv4Addr::UNSPECIFIED,
+            giaddr: Ipv4Addr::UNSPECIFIED,
+            chaddr: mac,
+            options: vec![(OPT_MESSAGE_TYPE, vec![message_type])],
+        };
+        packet.options.extend(options);
+        packet
+    }
+
+    fn store(dir: &std::path::Path) -> LeaseStore {
+        LeaseStore::load(dir.join("leases.json")).unwrap()
+    }
+
+    #[test]
+    fn test_packet_round_trip() {
+        let packet = request(
+            DEV01_MAC,
+            DISCOVER,
+            vec![(OPT_VENDOR_CLASS, b"Juniper-vmx".to_vec())],
+        );
+        let bytes = packet.to_bytes();
+        assert_eq!(bytes.len(), MIN_PACKET_LEN);
+        assert_eq!(DhcpPacket::parse(&bytes).unwrap(), packet);
+        assert_eq!(packet.mac(), "52:54:00:aa:bb:01");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(DhcpPacket::parse(&[0u8; 100]).is_err());
+        assert!(DhcpPacket::parse(&[0u8; 300]).is_err());
+
+        let mut truncated = request(DEV01_MAC, DISCOVER, vec![]).to_bytes();
+        truncated.truncate(HEADER_LEN + MAGIC_COOKIE.len() + 2);
+        truncated.push(OPT_HOSTNAME);
+        truncated.push(10);
+        assert!(DhcpPacket::parse(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_reserved_client_gets_tftp_boot_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let offer = handle_packet(
+            &config,
+            &mut leases,
+            &request(DEV01_MAC, DISCOVER, vec![]),
+            0,
+        )
+        .unwrap();
+        assert_eq!(offer.message_type(), Some(OFFER));
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(172, 31, 0, 11));
+        assert_eq!(offer.option(OPT_BOOTFILE_NAME), Some(&b"dev01.conf"[..]));
+        assert_eq!(offer.option(OPT_HOSTNAME), Some(&b"dev01"[..]));
+        assert_eq!(offer.option(OPT_TFTP_SERVER_NAME), Some(&b"172.31.0.2"[..]));
+        assert_eq!(offer.option(OPT_SUBNET_MASK), Some(&[255, 255, 255, 0][..]));
+        assert_eq!(offer.option(OPT_VENDOR_SPECIFIC), None);
+        // Offers do not create leases
+        assert!(leases.leases().is_empty());
+    }
+
+    #[test]
+    fn test_reserved_client_gets_http_boot_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let offer = handle_packet(
+            &config,
+            &mut leases,
+            &request(DEV02_MAC, DISCOVER, vec![]),
+            0,
+        )
+        .unwrap();
+        let url = b"http://172.31.0.2:8080/configs/dev02.conf";
+        assert_eq!(offer.option(OPT_BOOTFILE_NAME), Some(&url[..]));
+        assert_eq!(offer.option(OPT_CONFIG_URL), Some(&url[..]));
+    }
+
+    #[test]
+    fn test_juniper_vendor_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let discover = request(
+            DEV01_MAC,
+            DISCOVER,
+            vec![(OPT_VENDOR_CLASS, b"Juniper-vmx-VM1234".to_vec())],
+        );
+        let offer = handle_packet(&config, &mut leases, &discover, 0).unwrap();
+        let mut expected = vec![JUNIPER_CONFIG_FILE_NAME, 10];
+        expected.extend_from_slice(b"dev01.conf");
+        expected.extend_from_slice(&[JUNIPER_TRANSFER_MODE, 4]);
+        expected.extend_from_slice(b"tftp");
+        assert_eq!(offer.option(OPT_VENDOR_SPECIFIC), Some(expected.as_slice()));
+    }
+
+    #[test]
+    fn test_dynamic_allocation_skips_reservations_and_leases() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let ack = handle_packet(
+            &config,
+            &mut leases,
+            &request(
+                OTHER_MAC,
+                REQUEST,
+                vec![(OPT_REQUESTED_IP, vec![172, 31, 0, 10])],
+            ),
+            0,
+        )
+        .unwrap();
+        assert_eq!(ack.message_type(), Some(ACK));
+        assert_eq!(ack.yiaddr, Ipv4Addr::new(172, 31, 0, 10));
+        assert_eq!(ack.option(OPT_BOOTFILE_NAME), None);
+        assert_eq!(leases.leases().len(), 1);
+
+        // .10 is leased and .11 and .12 are reserved
+        let offer = handle_packet(
+            &config,
+            &mut leases,
+            &request([0x52, 0x54, 0x00, 0xcc, 0xdd, 0x02], DISCOVER, vec![]),
+            0,
+        )
+        .unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(172, 31, 0, 13));
+
+        // The first client keeps its address
+        let offer = handle_packet(
+            &config,
+            &mut leases,
+            &request(OTHER_MAC, DISCOVER, vec![]),
+            0,
+        )
+        .unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(172, 31, 0, 10));
+    }
+
+    #[test]
+    fn test_request_for_wrong_address_is_naked() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let nak = handle_packet(
+            &config,
+            &mut leases,
+            &request(
+                DEV01_MAC,
+                REQUEST,
+                vec![(OPT_REQUESTED_IP, vec![172, 31, 0, 50])],
+            ),
+            0,
+        )
+        .unwrap();
+        assert_eq!(nak.message_type(), Some(NAK));
+        assert!(leases.leases().is_empty());
+    }
+
+    #[test]
+    fn test_request_for_other_server_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let reply = handle_packet(
+            &config,
+            &mut leases,
+            &request(
+                DEV01_MAC,
+                REQUEST,
+                vec![
+                    (OPT_REQUESTED_IP, vec![172, 31, 0, 11]),
+                    (OPT_SERVER_ID, vec![172, 31, 0, 99]),
+                ],
+            ),
+            0,
+        );
+        assert!(reply.is_none());
+    }
+
+    #[test]
+    fn test_release_and_inform() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let mut renew = request(DEV01_MAC, REQUEST, vec![]);
+        renew.ciaddr = Ipv4Addr::new(172, 31, 0, 11);
+        let ack = handle_packet(&config, &mut leases, &renew, 0).unwrap();
+        assert_eq!(ack.message_type(), Some(ACK));
+        assert_eq!(
+            reply_destination(&renew, &ack),
+            SocketAddrV4::new(renew.ciaddr, DHCP_CLIENT_PORT)
+        );
+        assert_eq!(leases.leases()[0].hostname, "dev01");
+
+        let mut inform = request(DEV01_MAC, INFORM, vec![]);
+        inform.ciaddr = renew.ciaddr;
+        let ack = handle_packet(&config, &mut leases, &inform, 0).unwrap();
+        assert_eq!(ack.yiaddr, Ipv4Addr::UNSPECIFIED);
+        assert_eq!(ack.option(OPT_LEASE_TIME), None);
+
+        let mut release = request(DEV01_MAC, RELEASE, vec![]);
+        release.ciaddr = renew.ciaddr;
+        assert!(handle_packet(&config, &mut leases, &release, 0).is_none());
+        assert!(leases.leases().is_empty());
+    }
+
+    #[test]
+    fn test_declined_address_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let mut leases = store(dir.path());
+
+        let decline = request(
+            OTHER_MAC,
+            DECLINE,
+            vec![(OPT_REQUESTED_IP, vec![172, 31, 0, 10])],
+        );
+        assert!(handle_packet(&config, &mut leases, &decline, 0).is_none());
+
+        let offer = handle_packet(
+            &config,
+            &mut leases,
+            &request(OTHER_MAC, DISCOVER, vec![]),
+            0,
+        )
+        .unwrap();
+        assert_eq!(offer.yiaddr, Ipv4Addr::new(172, 31, 0, 13));
+    }
+}
@@ -0,0 +1,217 @@
+//! Stateless DHCPv6 for a lab management network.
+//!
+//! Nodes take their IPv6 address from router advertisements, so this only
+//! answers Information-request messages with the DNS server and search domain,
+//! like the `ra-stateless` mode of the dnsmasq container.
+
+use std::net::{Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+
+use shared::konst::{DHCPV6_SERVER_PORT, SHERPA_DOMAIN_NAME};
+
+use super::BootConfig;
+
+/// All_DHCP_Relay_Agents_and_Servers (RFC 8415)
+const ALL_DHCP_SERVERS: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 1, 2);
+
+// Message types
+const REPLY: u8 = 7;
+const INFORMATION_REQUEST: u8 = 11;
+
+// Options
+const OPT_CLIENTID: u16 = 1;
+const OPT_SERVERID: u16 = 2;
+const OPT_DNS_SERVERS: u16 = 23;
+const OPT_DOMAIN_LIST: u16 = 24;
+
+/// DUID based on a UUID (RFC 6355)
+const DUID_UUID: u16 = 4;
+
+/// Server DUID of a lab, stable across restarts.
+pub(super) fn server_duid(lab_id: &str) -> Vec<u8> {
+    let hash = Sha256::digest(lab_id.as_bytes());
+    let mut duid = DUID_UUID.to_be_bytes().to_vec();
+    duid.extend_from_slice(&hash[..16]);
+    duid
+}
+
+/// Split DHCPv6 options into code and value.
+fn parse_options(mut buf: &[u8]) -> Result<Vec<(u16, &[u8])>> {
+    let mut options = vec![];
+    while !buf.is_empty() {
+        if buf.len() < 4 {
+            bail!("DHCPv6 option header is truncated");
+        }
+        let code = u16::from_be_bytes([buf[0], buf[1]]);
+        let len = usize::from(u16::from_be_bytes([buf[2], buf[3]]));
+        let Some(value) = buf.get(4..4 + len) else {
+            bail!("DHCPv6 option {} is truncated", code);
+        };
+        options.push((code, value));
+        buf = &buf[4 + len..];
+    }
+    Ok(options)
+}
+
+fn push_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_be_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+/// Encode a domain name in DNS wire format.
+fn encode_domain(name: &str) -> Vec<u8> {
+    let mut encoded = vec![];
+    for label in name.split('.').filter(|l| !l.is_empty()) {
+        encoded.push(label.len() as u8);
+        encoded.extend_from_slice(label.as_bytes());
+    }
+    encoded.push(0);
+    encoded
+}
+
+/// Handle a client message.
+///
+/// # Returns
+/// The reply to send, if any
+pub(super) fn handle_packet(config: &BootConfig, duid: &[u8], buf: &[u8]) -> Option<Vec<u8>> {
+    if buf.len() < 4 || buf[0] != INFORMATION_REQUEST {
+        return None;
+    }
+    let options = parse_options(&buf[4..]).ok()?;
+    // Addressed to another server
+    if options
+        .iter()
+        .any(|(code, value)| *code == OPT_SERVERID && *value != duid)
+    {
+        return None;
+    }
+
+    let mut reply = vec![REPLY, buf[1], buf[2], buf[3]];
+    if let Some((_, client_id)) = options.iter().find(|(code, _)| *code == OPT_CLIENTID) {
+        push_option(&mut reply, OPT_CLIENTID, client_id);
+    }
+    push_option(&mut reply, OPT_SERVERID, duid);
+    if let Some(dns) = config.gateway_ipv6 {
+        push_option(&mut reply, OPT_DNS_SERVERS, &dns.octets());
+    }
+    push_option(
+        &mut reply,
+        OPT_DOMAIN_LIST,
+        &encode_domain(SHERPA_DOMAIN_NAME),
+    );
+    Some(reply)
+}
+
+/// Bind the DHCPv6 server port on the management bridge.
+pub(super) async fn bind(bridge: &str) -> Result<UdpSocket> {
+    let socket = super::bind_udp(
+        SocketAddr::from((Ipv6Addr::UNSPECIFIED, DHCPV6_SERVER_PORT)),
+        Some(bridge),
+    )?;
+    let index = network::get_ifindex(bridge).await?;
+    socket
+        .join_multicast_v6(&ALL_DHCP_SERVERS, index)
+        .context(format!("Failed to join DHCPv6 multicast group on {bridge}"))?;
+    Ok(socket)
+}
+
+/// Answer DHCPv6 clients until the lab's boot services are stopped.
+pub(super) async fn serve(socket: UdpSocket, config: Arc<BootConfig>, token: CancellationToken) {
+    let duid = server_duid(&config.lab_id);
+    let mut buf = [0u8; 1500];
+    loop {
+        let (len, peer) = tokio::select! {
+            _ = token.cancelled() => break,
+            received = socket.recv_from(&mut buf) => match received {
+                Ok(received) => received,
+                Err(e) => {
+                    tracing::warn!(lab_id = %config.lab_id, error = %e, "DHCPv6 receive failed");
+                    continue;
+                }
+            },
+        };
+        let Some(reply) = handle_packet(&config, &duid, &buf[..len]) else {
+            continue;
+        };
+        tracing::debug!(lab_id = %config.lab_id, client = %peer, "Sending DHCPv6 reply");
+        if let Err(e) = socket.send_to(&reply, peer).await {
+            tracing::warn!(lab_id = %config.lab_id, error = %e, "DHCPv6 send failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::boot::tests::test_config;
+
+    fn information_request(options: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut buf = vec![INFORMATION_REQUEST, 0xab, 0xcd, 0xef];
+        for (code, value) in options {
+            push_option(&mut buf, *code, value);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_server_duid_is_stable() {
+        let duid = server_duid("abcd1234");
+        assert_eq!(duid.len(), 18);
+        assert_eq!(&duid[..2], &[0, 4]);
+        assert_eq!(duid, server_duid("abcd1234"));
+        assert_ne!(duid, server_duid("efgh5678"));
+    }
+
+    #[test]
+    fn test_encode_domain() {
+        assert_eq!(
+            encode_domain("sherpa.lab.local"),
+            b"\x06sherpa\x03lab\x05local\x00".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_information_request_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let duid = server_duid(&config.lab_id);
+        let client_id = [0, 3, 0, 1, 0x52, 0x54, 0, 0xaa, 0xbb, 0x01];
+
+        let reply = handle_packet(
+            &config,
+            &duid,
+            &information_request(&[(OPT_CLIENTID, &client_id)]),
+        )
+        .unwrap();
+        assert_eq!(&reply[..4], &[REPLY, 0xab, 0xcd, 0xef]);
+
+        let options = parse_options(&reply[4..]).unwrap();
+        assert_eq!(options[0], (OPT_CLIENTID, &client_id[..]));
+        assert_eq!(options[1], (OPT_SERVERID, duid.as_slice()));
+        let dns: Ipv6Addr = "fd00:b00b::1".parse().unwrap();
+        assert_eq!(options[2], (OPT_DNS_SERVERS, &dns.octets()[..]));
+        assert_eq!(options[3].0, OPT_DOMAIN_LIST);
+    }
+
+    #[test]
+    fn test_ignores_other_messages_and_servers() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let duid = server_duid(&config.lab_id);
+
+        let mut solicit = information_request(&[]);
+        solicit[0] = 1;
+        assert!(handle_packet(&config, &duid, &solicit).is_none());
+
+        let other_server = information_request(&[(OPT_SERVERID, &server_duid("other"))]);
+        assert!(handle_packet(&config, &duid, &other_server).is_none());
+
+        assert!(handle_packet(&config, &duid, &[INFORMATION_REQUEST, 0, 0, 0, 0, 1]).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+//! HTTP server for node startup configs and the DHCP lease file.
+//!
+//! Serves the same paths as the boot container: configs under `/configs/`
+//! and leases at `/dnsmasq/dnsmasq.leases`.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::{Path, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use tokio::net::{TcpListener, TcpSocket};
+use tokio_util::sync::CancellationToken;
+
+use shared::konst::{DHCP_LEASES_FILE, DHCP_URI_DIR, HTTP_PORT, NODE_CONFIGS_DIR};
+
+use super::BootConfig;
+use super::leases::{LeaseStore, render_dnsmasq};
+
+/// Maximum pending connections on the listener
+const BACKLOG: u32 = 128;
+
+fn router(config: Arc<BootConfig>) -> Router {
+    Router::new()
+        .route(&format!("/{NODE_CONFIGS_DIR}/{{*path}}"), get(config_file))
+        .route(
+            &format!("/{DHCP_URI_DIR}/{DHCP_LEASES_FILE}"),
+            get(lease_file),
+        )
+        .with_state(config)
+}
+
+async fn config_file(State(config): State<Arc<BootConfig>>, Path(path): Path<String>) -> Response {
+    let Some(file) = super::resolve_file(&config.configs_dir(), &path) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match tokio::fs::read(&file).await {
+        Ok(body) => ([(header::CONTENT_TYPE, "application/octet-stream")], body).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn lease_file(State(config): State<Arc<BootConfig>>) -> Response {
+    match LeaseStore::load(config.leases_path()) {
+        Ok(store) => render_dnsmasq(store.leases()).into_response(),
+        Err(e) => {
+            tracing::warn!(lab_id = %config.lab_id, error = %e, "Failed to load DHCP leases");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Bind the HTTP port on the boot server address.
+pub(super) fn bind(address: Ipv4Addr) -> Result<TcpListener> {
+    let address = SocketAddr::from((address, HTTP_PORT));
+    let socket = TcpSocket::new_v4().context("Failed to create HTTP socket")?;
+    socket.set_reuseaddr(true)?;
+    socket
+        .bind(address)
+        .context(format!("Failed to bind HTTP server to {address}"))?;
+    socket.listen(BACKLOG).context("Failed to listen for HTTP")
+}
+
+/// Serve HTTP until the lab's boot services are stopped.
+pub(super) async fn serve(
+    listener: TcpListener,
+    config: Arc<BootConfig>,
+    token: CancellationToken,
+) {
+    let lab_id = config.lab_id.clone();
+    if let Err(e) = axum::serve(listener, router(config))
+        .with_graceful_shutdown(token.cancelled_owned())
+        .await
+    {
+        tracing::warn!(lab_id = %lab_id, error = %e, "Boot HTTP server failed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::boot::tests::test_config;
+
+    async fn get_body(config: BootConfig, path: &str) -> (StatusCode, String) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let token = CancellationToken::new();
+        tokio::spawn(serve(listener, Arc::new(config), token.clone()));
+
+        let response = reqwest::get(format!("http://{address}{path}"))
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = response.text().await.unwrap();
+        token.cancel();
+        (StatusCode::from_u16(status.as_u16()).unwrap(), body)
+    }
+
+    #[tokio::test]
+    async fn test_serves_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        std::fs::create_dir_all(config.configs_dir()).unwrap();
+        std::fs::write(config.configs_dir().join("dev02.conf"), "hostname dev02").unwrap();
+
+        let (status, body) = get_body(config.clone(), "/configs/dev02.conf").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "hostname dev02");
+
+        let (status, _) = get_body(config, "/configs/missing.conf").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_serves_leases() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        std::fs::create_dir_all(config.boot_dir()).unwrap();
+
+        let (status, body) = get_body(config, "/dnsmasq/dnsmasq.leases").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "");
+    }
+}
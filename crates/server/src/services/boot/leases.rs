@@ -0,0 +1,224 @@
+//! Native store of the leases handed out by the built-in DHCP server.
+//!
+//! Leases are kept as JSON next to the lab boot config. The HTTP service also
+//! renders them in the dnsmasq lease file format, which is what the boot
+//! container used to publish.
+
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use shared::data::DhcpLease;
+
+/// Placeholder dnsmasq writes for an unknown hostname or client ID
+const UNKNOWN: &str = "*";
+
+/// Leases of one lab, persisted to disk
+#[derive(Debug)]
+pub(super) struct LeaseStore {
+    path: PathBuf,
+    leases: Vec<DhcpLease>,
+    dirty: bool,
+}
+
+impl LeaseStore {
+    /// An empty store saving to `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            leases: vec![],
+            dirty: false,
+        }
+    }
+
+    /// Load the leases at `path`, starting empty if the file does not exist.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(path));
+        }
+        let contents = fs::read_to_string(&path)
+            .context(format!("Failed to read lease file: {}", path.display()))?;
+        let leases = serde_json::from_str(&contents)
+            .context(format!("Failed to parse lease file: {}", path.display()))?;
+        Ok(Self {
+            path,
+            leases,
+            dirty: false,
+        })
+    }
+
+    /// Write the leases to disk if they changed since the last save.
+    pub fn save_if_dirty(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let contents =
+            serde_json::to_string_pretty(&self.leases).context("Failed to serialize leases")?;
+        fs::write(&self.path, contents).context(format!(
+            "Failed to write lease file: {}",
+            self.path.display()
+        ))?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn leases(&self) -> &[DhcpLease] {
+        &self.leases
+    }
+
+    /// The last lease of a client, expired or not.
+    pub fn for_mac(&self, mac: &str) -> Option<&DhcpLease> {
+        self.leases
+            .iter()
+            .find(|l| l.mac_address.eq_ignore_ascii_case(mac))
+    }
+
+    /// The unexpired lease holding an address.
+    pub fn holder(&self, address: Ipv4Addr, now: u64) -> Option<&DhcpLease> {
+        let address = address.to_string();
+        self.leases
+            .iter()
+            .find(|l| l.ipv4_address == address && l.expiry > now)
+    }
+
+    /// Store a lease, replacing any other lease of its client or address.
+    pub fn upsert(&mut self, lease: DhcpLease) {
+        self.leases.retain(|l| {
+            !l.mac_address.eq_ignore_ascii_case(&lease.mac_address)
+                && l.ipv4_address != lease.ipv4_address
+        });
+        self.leases.push(lease);
+        self.dirty = true;
+    }
+
+    /// Drop the lease of a client on an address.
+    ///
+    /// # Returns
+    /// Whether a lease was released
+    pub fn release(&mut self, mac: &str, address: Ipv4Addr) -> bool {
+        let address = address.to_string();
+        let before = self.leases.len();
+        self.leases
+            .retain(|l| !(l.mac_address.eq_ignore_ascii_case(mac) && l.ipv4_address == address));
+        let released = self.leases.len() != before;
+        self.dirty |= released;
+        released
+    }
+
+    /// Hold back an address a client found in use until `expiry`.
+    pub fn decline(&mut self, address: Ipv4Addr, expiry: u64) {
+        self.upsert(DhcpLease {
+            expiry,
+            mac_address: String::new(),
+            ipv4_address: address.to_string(),
+            ipv6_address: None,
+            hostname: UNKNOWN.to_string(),
+            client_id: UNKNOWN.to_string(),
+        });
+    }
+}
+
+/// Render leases in the dnsmasq lease file format.
+///
+/// Declined addresses have no client and are left out.
+pub(super) fn render_dnsmasq(leases: &[DhcpLease]) -> String {
+    leases
+        .iter()
+        .filter(|l| !l.mac_address.is_empty())
+        .map(|l| {
+            let field = |value: &str| {
+                if value.is_empty() {
+                    UNKNOWN.to_string()
+                } else {
+                    value.to_string()
+                }
+            };
+            format!(
+                "{} {} {} {} {}\n",
+                l.expiry,
+                l.mac_address,
+                l.ipv4_address,
+                field(&l.hostname),
+                field(&l.client_id)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lease(mac: &str, address: &str, expiry: u64) -> DhcpLease {
+        DhcpLease {
+            expiry,
+            mac_address: mac.to_string(),
+            ipv4_address: address.to_string(),
+            ipv6_address: None,
+            hostname: "dev01".to_string(),
+            client_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_upsert_replaces_client_and_address() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = LeaseStore::load(dir.path().join("leases.json")).unwrap();
+        store.upsert(lease("52:54:00:aa:bb:01", "172.31.0.10", 100));
+        store.upsert(lease("52:54:00:AA:BB:01", "172.31.0.11", 100));
+        store.upsert(lease("52:54:00:aa:bb:02", "172.31.0.11", 100));
+
+        assert_eq!(store.leases().len(), 1);
+        assert_eq!(store.leases()[0].mac_address, "52:54:00:aa:bb:02");
+    }
+
+    #[test]
+    fn test_holder_ignores_expired_leases() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = LeaseStore::load(dir.path().join("leases.json")).unwrap();
+        store.upsert(lease("52:54:00:aa:bb:01", "172.31.0.10", 100));
+
+        let address = Ipv4Addr::new(172, 31, 0, 10);
+        assert!(store.holder(address, 99).is_some());
+        assert!(store.holder(address, 100).is_none());
+        assert!(store.for_mac("52:54:00:aa:bb:01").is_some());
+    }
+
+    #[test]
+    fn test_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = LeaseStore::load(dir.path().join("leases.json")).unwrap();
+        store.upsert(lease("52:54:00:aa:bb:01", "172.31.0.10", 100));
+
+        assert!(!store.release("52:54:00:aa:bb:01", Ipv4Addr::new(172, 31, 0, 11)));
+        assert!(store.release("52:54:00:aa:bb:01", Ipv4Addr::new(172, 31, 0, 10)));
+        assert!(store.leases().is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leases.json");
+        let mut store = LeaseStore::load(path.clone()).unwrap();
+        store.upsert(lease("52:54:00:aa:bb:01", "172.31.0.10", 100));
+        store.save_if_dirty().unwrap();
+
+        let loaded = LeaseStore::load(path).unwrap();
+        assert_eq!(loaded.leases(), store.leases());
+    }
+
+    #[test]
+    fn test_render_dnsmasq() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = LeaseStore::load(dir.path().join("leases.json")).unwrap();
+        store.upsert(lease("52:54:00:aa:bb:01", "172.31.0.10", 100));
+        store.decline(Ipv4Addr::new(172, 31, 0, 20), 100);
+
+        assert_eq!(
+            render_dnsmasq(store.leases()),
+            "100 52:54:00:aa:bb:01 172.31.0.10 dev01 *\n"
+        );
+    }
+}
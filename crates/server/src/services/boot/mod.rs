@@ -0,0 +1,343 @@
+//! Built-in boot services for lab management networks.
+//!
+//! An alternative to the `sherpa-router` dnsmasq container, selected with
+//! `boot_services = "builtin"` in the `[ztp_server]` config. DHCPv4, stateless
+//! DHCPv6, TFTP and HTTP are served by sherpad itself on the management
+//! bridge of each lab, so ZTP works on hosts that cannot pull the container.
+//!
+//! The boot server address is added to the bridge and the services bind to it,
+//! or to the bridge itself for DHCP. Each lab's settings are saved next to its
+//! ZTP files, so the services are restarted when sherpad restarts.
+
+mod dhcp;
+mod dhcpv6;
+mod http;
+mod leases;
+mod tftp;
+
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+use tracing::instrument;
+
+use shared::data::ZtpRecord;
+use shared::konst::{
+    BOOT_SERVICES_CONFIG_FILE, BOOT_SERVICES_DIR, BOOT_SERVICES_LEASES_FILE, NODE_CONFIGS_DIR,
+    SHERPA_LABS_PATH, TFTP_DIR, ZTP_DIR,
+};
+
+use crate::daemon::state::AppState;
+
+/// Settings of the boot services for one lab
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BootConfig {
+    pub lab_id: String,
+    /// Management bridge the services listen on
+    pub bridge: String,
+    /// Address of the boot server, added to the bridge
+    pub server_ipv4: Ipv4Addr,
+    /// Default gateway and DNS server handed to clients
+    pub gateway_ipv4: Ipv4Addr,
+    pub prefix_length: u8,
+    /// First address of the dynamic DHCP range
+    pub dhcp_start: Ipv4Addr,
+    /// Last address of the dynamic DHCP range
+    pub dhcp_end: Ipv4Addr,
+    pub server_ipv6: Option<Ipv6Addr>,
+    /// DNS server handed to DHCPv6 clients
+    pub gateway_ipv6: Option<Ipv6Addr>,
+    pub ipv6_prefix_length: Option<u8>,
+    /// Lab ZTP directory holding the TFTP root and node configs
+    pub ztp_dir: PathBuf,
+    /// DHCP reservations and boot options of the lab nodes
+    pub records: Vec<ZtpRecord>,
+}
+
+impl BootConfig {
+    fn boot_dir(&self) -> PathBuf {
+        self.ztp_dir.join(BOOT_SERVICES_DIR)
+    }
+
+    fn tftp_dir(&self) -> PathBuf {
+        self.ztp_dir.join(TFTP_DIR)
+    }
+
+    fn configs_dir(&self) -> PathBuf {
+        self.ztp_dir.join(NODE_CONFIGS_DIR)
+    }
+
+    fn leases_path(&self) -> PathBuf {
+        self.boot_dir().join(BOOT_SERVICES_LEASES_FILE)
+    }
+
+    fn netmask(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(
+            u32::MAX
+                .checked_shl(32 - u32::from(self.prefix_length))
+                .unwrap_or(0),
+        )
+    }
+}
+
+/// Start the boot services of a lab, replacing any already running.
+///
+/// # Errors
+/// Returns an error if the boot server address cannot be added to the bridge,
+/// the settings cannot be saved or a service cannot bind its socket.
+#[instrument(skip(state, config), fields(lab_id = %config.lab_id, bridge = %config.bridge))]
+pub async fn start(state: &AppState, config: BootConfig) -> Result<()> {
+    stop(state, &config.lab_id);
+
+    network::add_address(
+        &config.bridge,
+        config.server_ipv4.into(),
+        config.prefix_length,
+    )
+    .await?;
+    if let (Some(address), Some(prefix_length)) = (config.server_ipv6, config.ipv6_prefix_length) {
+        network::add_address(&config.bridge, address.into(), prefix_length).await?;
+    }
+
+    let boot_dir = config.boot_dir();
+    fs::create_dir_all(&boot_dir).context(format!(
+        "Failed to create boot services directory: {}",
+        boot_dir.display()
+    ))?;
+    let config_json =
+        serde_json::to_string_pretty(&config).context("Failed to serialize boot config")?;
+    fs::write(boot_dir.join(BOOT_SERVICES_CONFIG_FILE), config_json)
+        .context("Failed to save boot config")?;
+
+    // Bind everything before spawning, so a failure leaves nothing running
+    let dhcp_socket = dhcp::bind(&config.bridge)?;
+    let dhcpv6_socket = match config.server_ipv6 {
+        Some(_) => Some(dhcpv6::bind(&config.bridge).await?),
+        None => None,
+    };
+    let tftp_socket = tftp::bind(config.server_ipv4)?;
+    let http_listener = http::bind(config.server_ipv4)?;
+
+    let config = Arc::new(config);
+    let token = CancellationToken::new();
+    tokio::spawn(dhcp::serve(dhcp_socket, config.clone(), token.clone()));
+    if let Some(socket) = dhcpv6_socket {
+        tokio::spawn(dhcpv6::serve(socket, config.clone(), token.clone()));
+    }
+    tokio::spawn(tftp::serve(tftp_socket, config.clone(), token.clone()));
+    tokio::spawn(http::serve(http_listener, config.clone(), token.clone()));
+
+    state.boot_services.insert(config.lab_id.clone(), token);
+    tracing::info!(
+        lab_id = %config.lab_id,
+        server_ipv4 = %config.server_ipv4,
+        reservations = config.records.len(),
+        "Started built-in boot services"
+    );
+    Ok(())
+}
+
+/// Stop the boot services of a lab.
+///
+/// # Returns
+/// Whether the services were running
+pub fn stop(state: &AppState, lab_id: &str) -> bool {
+    let Some((_, token)) = state.boot_services.remove(lab_id) else {
+        return false;
+    };
+    token.cancel();
+    tracing::info!(lab_id = %lab_id, "Stopped built-in boot services");
+    true
+}
+
+/// Restart the boot services of labs that used them before sherpad restarted.
+///
+/// Failures are logged rather than returned, so one broken lab does not stop
+/// the daemon from starting.
+pub async fn restore(state: &AppState) {
+    let Ok(entries) = fs::read_dir(SHERPA_LABS_PATH) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry
+            .path()
+            .join(ZTP_DIR)
+            .join(BOOT_SERVICES_DIR)
+            .join(BOOT_SERVICES_CONFIG_FILE);
+        if !path.exists() {
+            continue;
+        }
+
+        let config = match load_config(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Skipping unreadable boot config");
+                continue;
+            }
+        };
+        let lab_id = config.lab_id.clone();
+        if let Err(e) = start(state, config).await {
+            tracing::warn!(lab_id = %lab_id, error = %e, "Failed to restore built-in boot services");
+        }
+    }
+}
+
+fn load_config(path: &Path) -> Result<BootConfig> {
+    let contents = fs::read_to_string(path).context("Failed to read boot config")?;
+    serde_json::from_str(&contents).context("Failed to parse boot config")
+}
+
+/// Create a UDP socket for a boot service.
+///
+/// Address reuse lets the DHCP servers of several labs share their port, each
+/// bound to its own bridge.
+fn bind_udp(address: SocketAddr, bridge: Option<&str>) -> Result<UdpSocket> {
+    let domain = match address {
+        SocketAddr::V4(_) => Domain::IPV4,
+        SocketAddr::V6(_) => Domain::IPV6,
+    };
+    let socket = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
+        .context("Failed to create UDP socket")?;
+    if domain == Domain::IPV6 {
+        socket.set_only_v6(true)?;
+    } else {
+        socket.set_broadcast(true)?;
+    }
+    socket.set_reuse_address(true)?;
+    if let Some(bridge) = bridge {
+        socket
+            .bind_device(Some(bridge.as_bytes()))
+            .context(format!("Failed to bind UDP socket to {bridge}"))?;
+    }
+    socket.set_nonblocking(true)?;
+    socket
+        .bind(&address.into())
+        .context(format!("Failed to bind UDP socket to {address}"))?;
+    UdpSocket::from_std(socket.into()).context("Failed to register UDP socket")
+}
+
+/// Resolve a path requested by a client to a file inside `root`.
+///
+/// Returns `None` for absolute paths, `..` components, symlinks leading out of
+/// `root` and files that do not exist.
+fn resolve_file(root: &Path, requested: &str) -> Option<PathBuf> {
+    let relative = Path::new(requested.trim_start_matches('/'));
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+
+    let root = root.canonicalize().ok()?;
+    let path = root.join(relative).canonicalize().ok()?;
+    (path.starts_with(&root) && path.is_file()).then_some(path)
+}
+
+/// Current time as a Unix timestamp
+fn now() -> u64 {
+    u64::try_from(jiff::Timestamp::now().as_second()).unwrap_or(0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use shared::data::ZtpMethod;
+
+    pub(crate) fn test_config(ztp_dir: &Path) -> BootConfig {
+        BootConfig {
+            lab_id: "abcd1234".to_string(),
+            bridge: "smb-abcd1234".to_string(),
+            server_ipv4: Ipv4Addr::new(172, 31, 0, 2),
+            gateway_ipv4: Ipv4Addr::new(172, 31, 0, 1),
+            prefix_length: 24,
+            dhcp_start: Ipv4Addr::new(172, 31, 0, 10),
+            dhcp_end: Ipv4Addr::new(172, 31, 0, 254),
+            server_ipv6: Some("fd00:b00b::2".parse().unwrap()),
+            gateway_ipv6: Some("fd00:b00b::1".parse().unwrap()),
+            ipv6_prefix_length: Some(64),
+            ztp_dir: ztp_dir.to_path_buf(),
+            records: vec![
+                ZtpRecord {
+                    node_name: "dev01".to_string(),
+                    config_file: "dev01.conf".to_string(),
+                    ipv4_address: Ipv4Addr::new(172, 31, 0, 11),
+                    ipv6_address: None,
+                    mac_address: "52:54:00:aa:bb:01".to_string(),
+                    ztp_method: ZtpMethod::Tftp,
+                    ssh_port: 22,
+                },
+                ZtpRecord {
+                    node_name: "dev02".to_string(),
+                    config_file: "dev02.conf".to_string(),
+                    ipv4_address: Ipv4Addr::new(172, 31, 0, 12),
+                    ipv6_address: None,
+                    mac_address: "52:54:00:aa:bb:02".to_string(),
+                    ztp_method: ZtpMethod::Http,
+                    ssh_port: 22,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_netmask() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(dir.path());
+        assert_eq!(config.netmask(), Ipv4Addr::new(255, 255, 255, 0));
+        config.prefix_length = 0;
+        assert_eq!(config.netmask(), Ipv4Addr::UNSPECIFIED);
+    }
+
+    #[test]
+    fn test_boot_config_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(dir.path());
+        let path = dir.path().join(BOOT_SERVICES_CONFIG_FILE);
+        fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let loaded = load_config(&path).unwrap();
+        assert_eq!(loaded.lab_id, config.lab_id);
+        assert_eq!(loaded.records.len(), 2);
+        assert_eq!(loaded.records[1].ztp_method, ZtpMethod::Http);
+    }
+
+    #[test]
+    fn test_resolve_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tftp");
+        fs::create_dir_all(root.join("juniper")).unwrap();
+        fs::write(root.join("juniper/juniper.conf"), "config").unwrap();
+        fs::write(dir.path().join("secret"), "secret").unwrap();
+
+        let expected = root.join("juniper/juniper.conf").canonicalize().unwrap();
+        assert_eq!(
+            resolve_file(&root, "juniper/juniper.conf"),
+            Some(expected.clone())
+        );
+        assert_eq!(resolve_file(&root, "/juniper/juniper.conf"), Some(expected));
+        assert_eq!(resolve_file(&root, "../secret"), None);
+        assert_eq!(resolve_file(&root, "juniper/../../secret"), None);
+        assert_eq!(resolve_file(&root, "missing.conf"), None);
+        assert_eq!(resolve_file(&root, "juniper"), None);
+    }
+
+    #[test]
+    fn test_resolve_file_rejects_escaping_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("tftp");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(dir.path().join("secret"), "secret").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("secret"), root.join("link")).unwrap();
+
+        assert_eq!(resolve_file(&root, "link"), None);
+    }
+}
@@ -0,0 +1,402 @@
+//! Read-only TFTP server for the lab TFTP directory.
+//!
+//! Supports the `blksize` and `tsize` options (RFC 2348/2349). Each transfer
+//! runs on its own socket, as the protocol expects.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use tokio::net::UdpSocket;
+use tokio::time::{Instant, timeout_at};
+use tokio_util::sync::CancellationToken;
+
+use shared::konst::TFTP_PORT;
+
+use super::BootConfig;
+
+// Opcodes
+const OP_RRQ: u16 = 1;
+const OP_DATA: u16 = 3;
+const OP_ACK: u16 = 4;
+const OP_ERROR: u16 = 5;
+const OP_OACK: u16 = 6;
+
+// Error codes
+const ERR_NOT_FOUND: u16 = 1;
+const ERR_ILLEGAL_OPERATION: u16 = 4;
+
+const DEFAULT_BLOCK_SIZE: usize = 512;
+const MIN_BLOCK_SIZE: usize = 8;
+const MAX_BLOCK_SIZE: usize = 65464;
+/// How long to wait for an ACK before sending again
+const ACK_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_ATTEMPTS: u32 = 5;
+
+/// A read request and the options it negotiates
+#[derive(Debug, PartialEq)]
+pub(super) struct ReadRequest {
+    pub filename: String,
+    pub block_size: Option<usize>,
+    /// Whether the client asked for the transfer size
+    pub tsize: bool,
+}
+
+/// Parse a read request; write requests and unknown opcodes are errors.
+pub(super) fn parse_request(buf: &[u8]) -> Result<ReadRequest> {
+    if buf.len() < 2 {
+        bail!("TFTP packet too short");
+    }
+    let opcode = u16::from_be_bytes([buf[0], buf[1]]);
+    if opcode != OP_RRQ {
+        bail!(
+            "Only TFTP read requests are supported, got opcode {}",
+            opcode
+        );
+    }
+
+    let Some(fields) = buf[2..].strip_suffix(&[0]) else {
+        bail!("TFTP request is not NUL terminated");
+    };
+    let fields = fields
+        .split(|b| *b == 0)
+        .map(std::str::from_utf8)
+        .collect::<Result<Vec<_>, _>>()
+        .context("TFTP request is not valid UTF-8")?;
+    let [filename, mode, options @ ..] = fields.as_slice() else {
+        bail!("TFTP request has no transfer mode");
+    };
+    if !mode.eq_ignore_ascii_case("octet") && !mode.eq_ignore_ascii_case("netascii") {
+        bail!("Unsupported TFTP transfer mode: {}", mode);
+    }
+
+    let mut request = ReadRequest {
+        filename: filename.to_string(),
+        block_size: None,
+        tsize: false,
+    };
+    for option in options.chunks_exact(2) {
+        match option[0].to_ascii_lowercase().as_str() {
+            "blksize" => {
+                request.block_size = option[1]
+                    .parse::<usize>()
+                    .ok()
+                    .map(|size| size.clamp(MIN_BLOCK_SIZE, MAX_BLOCK_SIZE));
+            }
+            "tsize" => request.tsize = true,
+            _ => {}
+        }
+    }
+    Ok(request)
+}
+
+fn data_packet(block: u16, data: &[u8]) -> Vec<u8> {
+    let mut packet = OP_DATA.to_be_bytes().to_vec();
+    packet.extend_from_slice(&block.to_be_bytes());
+    packet.extend_from_slice(data);
+    packet
+}
+
+fn error_packet(code: u16, message: &str) -> Vec<u8> {
+    let mut packet = OP_ERROR.to_be_bytes().to_vec();
+    packet.extend_from_slice(&code.to_be_bytes());
+    packet.extend_from_slice(message.as_bytes());
+    packet.push(0);
+    packet
+}
+
+fn oack_packet(options: &[(&str, String)]) -> Vec<u8> {
+    let mut packet = OP_OACK.to_be_bytes().to_vec();
+    for (name, value) in options {
+        packet.extend_from_slice(name.as_bytes());
+        packet.push(0);
+        packet.extend_from_slice(value.as_bytes());
+        packet.push(0);
+    }
+    packet
+}
+
+/// The block number a client acknowledged, or an error if it aborted.
+fn parse_ack(buf: &[u8]) -> Result<Option<u16>> {
+    if buf.len() < 4 {
+        return Ok(None);
+    }
+    match u16::from_be_bytes([buf[0], buf[1]]) {
+        OP_ACK => Ok(Some(u16::from_be_bytes([buf[2], buf[3]]))),
+        OP_ERROR => bail!(
+            "TFTP client aborted the transfer: {}",
+            String::from_utf8_lossy(&buf[4..]).trim_end_matches('\0')
+        ),
+        _ => Ok(None),
+    }
+}
+
+/// Send a packet until the client acknowledges `block`.
+async fn send_until_acked(
+    socket: &UdpSocket,
+    packet: &[u8],
+    block: u16,
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut buf = [0u8; 516];
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(packet).await?;
+        let deadline = Instant::now() + ACK_TIMEOUT;
+        loop {
+            let received = tokio::select! {
+                _ = token.cancelled() => bail!("TFTP transfer cancelled"),
+                received = timeout_at(deadline, socket.recv(&mut buf)) => received,
+            };
+            let Ok(received) = received else {
+                // Timed out, send again
+                break;
+            };
+            // Duplicate ACKs of earlier blocks are ignored
+            if parse_ack(&buf[..received?])? == Some(block) {
+                return Ok(());
+            }
+        }
+    }
+    bail!("Timed out waiting for TFTP ACK of block {}", block)
+}
+
+/// Send a file to a client from a new socket.
+async fn send_file(
+    root: PathBuf,
+    request: ReadRequest,
+    local: Ipv4Addr,
+    peer: SocketAddr,
+    token: CancellationToken,
+) -> Result<()> {
+    let socket = UdpSocket::bind((local, 0))
+        .await
+        .context("Failed to bind TFTP transfer socket")?;
+    socket.connect(peer).await?;
+
+    let data = match super::resolve_file(&root, &request.filename) {
+        Some(path) => tokio::fs::read(&path).await.ok(),
+        None => None,
+    };
+    let Some(data) = data else {
+        socket
+            .send(&error_packet(ERR_NOT_FOUND, "File not found"))
+            .await?;
+        bail!("TFTP file not found: {}", request.filename);
+    };
+
+    let mut options = vec![];
+    if let Some(size) = request.block_size {
+        options.push(("blksize", size.to_string()));
+    }
+    if request.tsize {
+        options.push(("tsize", data.len().to_string()));
+    }
+    if !options.is_empty() {
+        send_until_acked(&socket, &oack_packet(&options), 0, &token).await?;
+    }
+
+    // A final short (possibly empty) block ends the transfer
+    let block_size = request.block_size.unwrap_or(DEFAULT_BLOCK_SIZE);
+    for index in 0..=data.len() / block_size {
+        let start = index * block_size;
+        let chunk = &data[start..data.len().min(start + block_size)];
+        // Block numbers wrap around for files over 65535 blocks
+        let block = (index + 1) as u16;
+        send_until_acked(&socket, &data_packet(block, chunk), block, &token).await?;
+    }
+
+    tracing::debug!(file = %request.filename, client = %peer, bytes = data.len(), "TFTP transfer complete");
+    Ok(())
+}
+
+/// Bind the TFTP port on the boot server address.
+pub(super) fn bind(address: Ipv4Addr) -> Result<UdpSocket> {
+    super::bind_udp(SocketAddr::from((address, TFTP_PORT)), None)
+}
+
+/// Serve read requests until the lab's boot services are stopped.
+pub(super) async fn serve(socket: UdpSocket, config: Arc<BootConfig>, token: CancellationToken) {
+    let mut buf = [0u8; 1500];
+    loop {
+        let (len, peer) = tokio::select! {
+            _ = token.cancelled() => break,
+            received = socket.recv_from(&mut buf) => match received {
+                Ok(received) => received,
+                Err(e) => {
+                    tracing::warn!(lab_id = %config.lab_id, error = %e, "TFTP receive failed");
+                    continue;
+                }
+            },
+        };
+        let request = match parse_request(&buf[..len]) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::debug!(lab_id = %config.lab_id, client = %peer, error = %e, "Rejecting TFTP request");
+                let _ = socket
+                    .send_to(&error_packet(ERR_ILLEGAL_OPERATION, &e.to_string()), peer)
+                    .await;
+                continue;
+            }
+        };
+
+        tracing::info!(lab_id = %config.lab_id, client = %peer, file = %request.filename, "TFTP read request");
+        let lab_id = config.lab_id.clone();
+        let transfer = send_file(
+            config.tftp_dir(),
+            request,
+            config.server_ipv4,
+            peer,
+            token.child_token(),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = transfer.await {
+                tracing::warn!(lab_id = %lab_id, client = %peer, error = %e, "TFTP transfer failed");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rrq(fields: &[&str]) -> Vec<u8> {
+        let mut buf = OP_RRQ.to_be_bytes().to_vec();
+        for field in fields {
+            buf.extend_from_slice(field.as_bytes());
+            buf.push(0);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(&rrq(&["dev01.conf", "octet"])).unwrap(),
+            ReadRequest {
+                filename: "dev01.conf".to_string(),
+                block_size: None,
+                tsize: false,
+            }
+        );
+        assert_eq!(
+            parse_request(&rrq(&[
+                "dev01.conf",
+                "NETASCII",
+                "blksize",
+                "1468",
+                "tsize",
+                "0"
+            ]))
+            .unwrap(),
+            ReadRequest {
+                filename: "dev01.conf".to_string(),
+                block_size: Some(1468),
+                tsize: true,
+            }
+        );
+        assert_eq!(
+            parse_request(&rrq(&["dev01.conf", "octet", "blksize", "100000"]))
+                .unwrap()
+                .block_size,
+            Some(MAX_BLOCK_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_parse_request_rejects_invalid() {
+        let mut wrq = rrq(&["dev01.conf", "octet"]);
+        wrq[1] = 2;
+        assert!(parse_request(&wrq).is_err());
+        assert!(parse_request(&rrq(&["dev01.conf", "mail"])).is_err());
+        assert!(parse_request(&rrq(&["dev01.conf"])).is_err());
+        assert!(parse_request(b"\x00\x01dev01.conf\x00octet").is_err());
+        assert!(parse_request(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_packets() {
+        assert_eq!(data_packet(1, b"abc"), b"\x00\x03\x00\x01abc".to_vec());
+        assert_eq!(
+            error_packet(ERR_NOT_FOUND, "File not found"),
+            b"\x00\x05\x00\x01File not found\x00".to_vec()
+        );
+        assert_eq!(
+            oack_packet(&[("tsize", "42".to_string())]),
+            b"\x00\x06tsize\x0042\x00".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_ack() {
+        assert_eq!(parse_ack(b"\x00\x04\x00\x07").unwrap(), Some(7));
+        assert_eq!(parse_ack(b"\x00\x03\x00\x07").unwrap(), None);
+        assert!(parse_ack(b"\x00\x05\x00\x00Disk full\x00").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transfer() {
+        let dir = tempfile::tempdir().unwrap();
+        let data: Vec<u8> = (0..1200).map(|i| (i % 251) as u8).collect();
+        std::fs::write(dir.path().join("dev01.conf"), &data).unwrap();
+
+        let client = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let request = ReadRequest {
+            filename: "dev01.conf".to_string(),
+            block_size: None,
+            tsize: true,
+        };
+        let transfer = tokio::spawn(send_file(
+            dir.path().to_path_buf(),
+            request,
+            Ipv4Addr::LOCALHOST,
+            client.local_addr().unwrap(),
+            CancellationToken::new(),
+        ));
+
+        let mut buf = [0u8; 1024];
+        let (len, server) = client.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..len], b"\x00\x06tsize\x001200\x00");
+        client.send_to(b"\x00\x04\x00\x00", server).await.unwrap();
+
+        let mut received = vec![];
+        for block in 1..=3u16 {
+            let (len, _) = client.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..4], &data_packet(block, &[])[..]);
+            received.extend_from_slice(&buf[4..len]);
+            let mut ack = OP_ACK.to_be_bytes().to_vec();
+            ack.extend_from_slice(&block.to_be_bytes());
+            client.send_to(&ack, server).await.unwrap();
+        }
+
+        transfer.await.unwrap().unwrap();
+        assert_eq!(received, data);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let request = ReadRequest {
+            filename: "../missing.conf".to_string(),
+            block_size: None,
+            tsize: false,
+        };
+        let result = send_file(
+            dir.path().to_path_buf(),
+            request,
+            Ipv4Addr::LOCALHOST,
+            client.local_addr().unwrap(),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(result.is_err());
+
+        let mut buf = [0u8; 64];
+        let len = client.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..4], b"\x00\x05\x00\x01");
+        assert!(len > 4);
+    }
+}
@@ -0,0 +1,137 @@
+//! VLAN-aware shared bridges.
+//!
+//! A manifest bridge with `ports` is created with VLAN filtering. Its VM
+//! members are connected through named taps (`tpv{n}-{lab_id}`) rather than
+//! libvirt bridge interfaces, so libvirt leaves them out of the bridge. Once
+//! a VM is running its taps are joined to the bridge and given the access or
+//! trunk VLANs of the member. Taps are recreated when a VM boots, so the
+//! VLANs are set again from the saved manifest after resume and redeploy.
+
+use anyhow::{Context, Result};
+use shared::data::PortVlan;
+use topology::BridgePortTap;
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use crate::runtime::HostNetwork;
+use crate::services::mirror::load_lab_manifest;
+
+/// Join the taps of `nodes` to their VLAN-aware bridges.
+pub async fn apply_bridge_port_vlans(
+    taps: &[BridgePortTap],
+    nodes: &[String],
+    network: &dyn HostNetwork,
+) -> Result<usize> {
+    let mut applied = 0;
+    for tap in taps.iter().filter(|tap| nodes.contains(&tap.node)) {
+        network
+            .set_bridge_port_vlans(&tap.tap_name, &tap.bridge_name, &tap.vlans)
+            .await
+            .context(format!(
+                "Failed to set VLANs {} of {}::{} on bridge {}",
+                vlan_summary(&tap.vlans),
+                tap.node,
+                tap.interface,
+                tap.bridge_name
+            ))?;
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+/// Join the taps of `nodes` to their bridges, from the manifest the lab was
+/// created from.
+#[instrument(skip(state), fields(%lab_id))]
+pub async fn start_manifest_bridge_vlans(
+    lab_id: &str,
+    nodes: &[String],
+    state: &AppState,
+) -> Result<usize> {
+    let manifest = load_lab_manifest(lab_id)?;
+    let taps = topology::bridge_port_taps(&manifest.bridges, lab_id);
+    let applied = apply_bridge_port_vlans(&taps, nodes, state.runtime.network.as_ref()).await?;
+    if applied > 0 {
+        tracing::info!(ports = applied, "Set VLANs of shared bridge ports");
+    }
+    Ok(applied)
+}
+
+fn vlan_summary(vlans: &[PortVlan]) -> String {
+    vlans
+        .iter()
+        .map(PortVlan::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeHostNetwork;
+    use shared::data::VlanRange;
+
+    fn tap(node: &str, index: usize, vlans: Vec<PortVlan>) -> BridgePortTap {
+        BridgePortTap {
+            bridge_name: "brs0-abcd1234".to_string(),
+            node: node.to_string(),
+            interface: "eth1".to_string(),
+            tap_name: format!("tpv{index}-abcd1234"),
+            vlans,
+        }
+    }
+
+    fn native(vlan: u16) -> PortVlan {
+        PortVlan {
+            vlans: VlanRange::single(vlan),
+            native: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_bridge_port_vlans_for_nodes() {
+        let network = FakeHostNetwork::default()
+            .with_interface("tpv0-abcd1234")
+            .with_interface("tpv1-abcd1234");
+        let trunk = vec![
+            PortVlan {
+                vlans: VlanRange {
+                    first: 100,
+                    last: 199,
+                },
+                native: false,
+            },
+            native(1),
+        ];
+        let taps = [
+            tap("agg01", 0, trunk),
+            tap("ce01", 1, vec![native(10)]),
+            tap("ce02", 2, vec![native(20)]),
+        ];
+
+        let applied =
+            apply_bridge_port_vlans(&taps, &["agg01".to_string(), "ce01".to_string()], &network)
+                .await
+                .unwrap();
+
+        assert_eq!(applied, 2);
+        assert_eq!(
+            network.calls(),
+            vec![
+                "set_bridge_port_vlans tpv0-abcd1234 brs0-abcd1234 100-199, 1 native",
+                "set_bridge_port_vlans tpv1-abcd1234 brs0-abcd1234 10 native",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_bridge_port_vlans_missing_tap() {
+        let network = FakeHostNetwork::default();
+        let taps = [tap("ce01", 1, vec![native(10)])];
+
+        let err = apply_bridge_port_vlans(&taps, &["ce01".to_string()], &network)
+            .await
+            .unwrap_err();
+
+        assert!(format!("{err:#}").contains("ce01::eth1"));
+    }
+}
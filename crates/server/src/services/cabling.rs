@@ -0,0 +1,340 @@
+//! Cabling verification from the LLDP and CDP advertisements of lab nodes.
+//!
+//! Each node's advertisements are captured on the host side of its end of a
+//! link and compared to the peer end the manifest describes: what node A
+//! sends is what node B discovers. P2p links capture the frames arriving on
+//! `tap_a` and `tap_b` from the nodes. Bridged links capture the frames
+//! leaving `veth_a` and `veth_b` towards the other bridge. The capture is
+//! taken before any link filter, so it checks the cabling even when the
+//! filter drops the advertisements.
+//!
+//! A wrong advertised interface usually means the vendor's interface mapping
+//! (`first_interface_index`, `reserved_interface_count`) is off. The latest
+//! result of each lab is kept in memory and shown by `inspect`.
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use network::TcHook;
+use shared::data::{
+    BridgeKind, CablingCheck, CablingStatus, DbLink, DiscoveredNeighbour, RecordId,
+    VerifyCablingRequest, VerifyCablingResponse,
+};
+use shared::konst::{CABLING_CAPTURE_MAX_SECS, CABLING_CAPTURE_SECS};
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use crate::runtime::HostNetwork;
+
+/// Listen for the advertisements of a lab's nodes and compare them to the
+/// manifest.
+#[instrument(skip(state), fields(lab_id = %request.lab_id))]
+pub async fn verify_cabling(
+    request: &VerifyCablingRequest,
+    state: &AppState,
+) -> Result<VerifyCablingResponse> {
+    let lab_id = &request.lab_id;
+    let wait_secs = request.wait_secs.unwrap_or(CABLING_CAPTURE_SECS);
+    if !(1..=CABLING_CAPTURE_MAX_SECS).contains(&wait_secs) {
+        bail!("wait_secs must be between 1 and {CABLING_CAPTURE_MAX_SECS}");
+    }
+
+    let lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found", lab_id))?;
+
+    let lab_record_id = lab
+        .id
+        .ok_or_else(|| anyhow!("Lab '{}' missing record ID", lab_id))?;
+
+    let nodes = db::list_nodes_by_lab(&state.db, lab_record_id.clone()).await?;
+    let links = db::list_links_by_lab(&state.db, lab_record_id).await?;
+
+    let node_names = nodes
+        .into_iter()
+        .filter_map(|node| node.id.map(|id| (id, node.name)))
+        .collect();
+
+    let response = collect_cabling(
+        lab_id,
+        &links,
+        &node_names,
+        state.runtime.network.as_ref(),
+        wait_secs,
+    )
+    .await?;
+
+    tracing::info!(
+        verified = response.count(CablingStatus::Verified),
+        mismatch = response.count(CablingStatus::Mismatch),
+        not_seen = response.count(CablingStatus::NotSeen),
+        "Verified lab cabling"
+    );
+    state.cabling.insert(lab_id.clone(), response.clone());
+    Ok(response)
+}
+
+/// Capture the advertisements on every link and check both ends of each.
+pub(crate) async fn collect_cabling(
+    lab_id: &str,
+    links: &[DbLink],
+    node_names: &HashMap<RecordId, String>,
+    network: &dyn HostNetwork,
+    wait_secs: u64,
+) -> Result<VerifyCablingResponse> {
+    let mut links: Vec<&DbLink> = links.iter().collect();
+    links.sort_by_key(|link| link.index);
+
+    let targets: Vec<(String, TcHook)> = links
+        .iter()
+        .filter_map(|link| capture_points(link))
+        .flatten()
+        .collect();
+
+    let mut captured = network
+        .capture_neighbours(&targets, Duration::from_secs(wait_secs))
+        .await
+        .context("Failed to capture LLDP/CDP advertisements")?
+        .into_iter();
+
+    let node_name = |id: &RecordId| {
+        node_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.key.to_string())
+    };
+
+    let mut checks = Vec::with_capacity(links.len() * 2);
+    for link in links {
+        let (node_a, node_b) = (node_name(&link.node_a), node_name(&link.node_b));
+        // What A sends is what B discovers, and the other way around.
+        let (sent_by_a, sent_by_b) = match capture_points(link) {
+            Some(_) => (
+                Some(captured.next().flatten()),
+                Some(captured.next().flatten()),
+            ),
+            None => (None, None),
+        };
+
+        checks.push(check(
+            link.index,
+            (&node_a, &link.int_a),
+            (&node_b, &link.int_b),
+            sent_by_b,
+        ));
+        checks.push(check(
+            link.index,
+            (&node_b, &link.int_b),
+            (&node_a, &link.int_a),
+            sent_by_a,
+        ));
+    }
+
+    let checked_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+
+    Ok(VerifyCablingResponse {
+        lab_id: lab_id.to_string(),
+        checked_at_ms,
+        wait_secs,
+        checks,
+    })
+}
+
+/// Host interfaces carrying what node A and node B send, or `None` when
+/// the link has no host interface per node.
+fn capture_points(link: &DbLink) -> Option<[(String, TcHook); 2]> {
+    match link.kind {
+        BridgeKind::P2p => Some([
+            (link.tap_a.clone(), TcHook::Ingress),
+            (link.tap_b.clone(), TcHook::Ingress),
+        ]),
+        BridgeKind::P2pBridge => Some([
+            (link.veth_a.clone(), TcHook::Egress),
+            (link.veth_b.clone(), TcHook::Egress),
+        ]),
+        _ => None,
+    }
+}
+
+/// Check one end of a link against what its peer advertised. `discovered`
+/// is `None` when the link could not be captured on.
+fn check(
+    link_index: u16,
+    (node, interface): (&str, &str),
+    (expected_node, expected_interface): (&str, &str),
+    discovered: Option<Option<DiscoveredNeighbour>>,
+) -> CablingCheck {
+    let status = match &discovered {
+        None => CablingStatus::Unsupported,
+        Some(None) => CablingStatus::NotSeen,
+        Some(Some(neighbour)) if neighbour.is_from(expected_node, expected_interface) => {
+            CablingStatus::Verified
+        }
+        Some(Some(_)) => CablingStatus::Mismatch,
+    };
+
+    CablingCheck {
+        link_index,
+        node: node.to_string(),
+        interface: interface.to_string(),
+        expected_node: expected_node.to_string(),
+        expected_interface: expected_interface.to_string(),
+        discovered: discovered.flatten(),
+        status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeHostNetwork;
+    use shared::data::{DiscoveryProtocol, LinkFilter};
+
+    const LAB_ID: &str = "abcd1234";
+
+    fn link(index: u16, kind: BridgeKind) -> DbLink {
+        DbLink {
+            id: None,
+            index,
+            kind,
+            node_a: RecordId::new("node", "dev01"),
+            node_b: RecordId::new("node", "dev02"),
+            int_a: "Ethernet1".to_string(),
+            int_b: "Ethernet2".to_string(),
+            lab: RecordId::new("lab", LAB_ID),
+            bridge_a: format!("bra{index}-{LAB_ID}"),
+            bridge_b: format!("brb{index}-{LAB_ID}"),
+            veth_a: format!("vea{index}-{LAB_ID}"),
+            veth_b: format!("veb{index}-{LAB_ID}"),
+            tap_a: format!("tpa{index}-{LAB_ID}"),
+            tap_b: format!("tpb{index}-{LAB_ID}"),
+            delay_us: 0,
+            jitter_us: 0,
+            loss_percent: 0.0,
+            reorder_percent: 0.0,
+            corrupt_percent: 0.0,
+            filter: LinkFilter::default(),
+        }
+    }
+
+    fn lldp(system_name: &str, port_id: &str) -> DiscoveredNeighbour {
+        DiscoveredNeighbour {
+            protocol: DiscoveryProtocol::Lldp,
+            chassis_id: "52:54:00:12:34:56".to_string(),
+            system_name: Some(system_name.to_string()),
+            port_id: port_id.to_string(),
+            port_description: None,
+        }
+    }
+
+    fn node_names() -> HashMap<RecordId, String> {
+        HashMap::from([
+            (RecordId::new("node", "dev01"), "dev01".to_string()),
+            (RecordId::new("node", "dev02"), "dev02".to_string()),
+        ])
+    }
+
+    fn statuses(response: &VerifyCablingResponse) -> Vec<(&str, CablingStatus)> {
+        response
+            .checks
+            .iter()
+            .map(|check| (check.node.as_str(), check.status))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_p2p_cabling_verified_and_mismatch() {
+        // dev02 advertises Ethernet3 where the manifest cables Ethernet2.
+        let network = FakeHostNetwork::default()
+            .with_interface("tpa0-abcd1234")
+            .with_interface("tpb0-abcd1234")
+            .with_neighbour("tpa0-abcd1234", TcHook::Ingress, lldp("dev01", "Et1"))
+            .with_neighbour("tpb0-abcd1234", TcHook::Ingress, lldp("dev02", "Ethernet3"));
+
+        let response = collect_cabling(
+            LAB_ID,
+            &[link(0, BridgeKind::P2p)],
+            &node_names(),
+            &network,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            statuses(&response),
+            vec![
+                ("dev01", CablingStatus::Mismatch),
+                ("dev02", CablingStatus::Verified),
+            ]
+        );
+        assert_eq!(response.checks[0].expected_interface, "Ethernet2");
+        assert_eq!(
+            response.checks[0].discovered.as_ref().unwrap().port_id,
+            "Ethernet3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bridged_cabling_captures_veth_egress() {
+        let network = FakeHostNetwork::default()
+            .with_interface("vea0-abcd1234")
+            .with_interface("veb0-abcd1234")
+            .with_neighbour("vea0-abcd1234", TcHook::Egress, lldp("dev01", "Ethernet1"))
+            .with_neighbour("veb0-abcd1234", TcHook::Ingress, lldp("dev02", "Ethernet2"));
+
+        let response = collect_cabling(
+            LAB_ID,
+            &[link(0, BridgeKind::P2pBridge)],
+            &node_names(),
+            &network,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            statuses(&response),
+            vec![
+                ("dev01", CablingStatus::NotSeen),
+                ("dev02", CablingStatus::Verified),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cabling_unsupported_link_kind() {
+        let network = FakeHostNetwork::default()
+            .with_interface("tpa1-abcd1234")
+            .with_interface("tpb1-abcd1234");
+
+        let response = collect_cabling(
+            LAB_ID,
+            &[link(1, BridgeKind::P2p), link(0, BridgeKind::P2pUdp)],
+            &node_names(),
+            &network,
+            1,
+        )
+        .await
+        .unwrap();
+
+        let checks: Vec<(u16, CablingStatus)> = response
+            .checks
+            .iter()
+            .map(|check| (check.link_index, check.status))
+            .collect();
+        assert_eq!(
+            checks,
+            vec![
+                (0, CablingStatus::Unsupported),
+                (0, CablingStatus::Unsupported),
+                (1, CablingStatus::NotSeen),
+                (1, CablingStatus::NotSeen),
+            ]
+        );
+    }
+}
@@ -10,6 +10,8 @@ use shared::util::{dir_exists, load_file};
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::runtime::Runtime;
+use crate::services::boot;
 use crate::services::destroy::{
     cleanup_database, destroy_containers, destroy_docker_networks, destroy_interfaces,
     destroy_libvirt_networks, destroy_vms_and_disks,
@@ -22,8 +24,17 @@ use crate::services::destroy::{
 /// - Does not validate user ownership (admin-only, verified at RPC layer)
 /// - Tolerates missing lab info files
 /// - Always attempts all resource types regardless of partial failures
-#[instrument(skip(state), fields(%lab_id))]
 pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse> {
+    clean_lab_in(lab_id, state, SHERPA_LABS_PATH).await
+}
+
+/// Clean all resources for a lab whose files live under `labs_dir`.
+#[instrument(skip(state), fields(%lab_id))]
+pub(crate) async fn clean_lab_in(
+    lab_id: &str,
+    state: &AppState,
+    labs_dir: &str,
+) -> Result<DestroyResponse> {
     let start_time = std::time::Instant::now();
 
     tracing::info!(lab_id = %lab_id, "Starting admin clean operation");
@@ -32,7 +43,7 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
     let mut errors = Vec::new();
 
     // Try to load lab name from filesystem, fall back to "unknown"
-    let lab_dir = format!("{SHERPA_LABS_PATH}/{lab_id}");
+    let lab_dir = format!("{labs_dir}/{lab_id}");
     let lab_name = load_file(&format!("{lab_dir}/{LAB_FILE_NAME}"))
         .ok()
         .and_then(|content| content.parse::<LabInfo>().ok().map(|info| info.name))
@@ -45,10 +56,93 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
         "Clean operation context"
     );
 
+    // Stop built-in boot services before their bridge is removed
+    boot::stop(state, lab_id);
+
+    // 1-5. Tear down runtime resources
+    clean_runtime(lab_id, &state.runtime, &mut summary, &mut errors).await;
+
+    // 6. Clean up database (tolerate missing records)
+    tracing::info!(lab_id = %lab_id, "Cleaning database records");
+    match cleanup_database(lab_id, &state.db).await {
+        Ok(_) => {
+            summary.database_records_deleted = true;
+            tracing::info!(lab_id = %lab_id, "Database cleanup successful");
+        }
+        Err(e) => {
+            summary.database_records_deleted = false;
+            errors.push(DestroyError::new("database", lab_id, format!("{:?}", e)));
+            tracing::warn!(lab_id = %lab_id, error = ?e, "Database cleanup failed (may not exist)");
+        }
+    }
+
+    // 7. Delete lab directory
+    tracing::info!(lab_id = %lab_id, lab_dir = %lab_dir, "Deleting lab directory");
+    if dir_exists(&lab_dir) {
+        match fs::remove_dir_all(&lab_dir) {
+            Ok(_) => {
+                summary.lab_directory_deleted = true;
+                tracing::info!(lab_id = %lab_id, lab_dir = %lab_dir, "Lab directory deleted");
+            }
+            Err(e) => {
+                summary.lab_directory_deleted = false;
+                errors.push(DestroyError::new(
+                    "filesystem",
+                    &lab_dir,
+                    format!("{:?}", e),
+                ));
+                tracing::error!(lab_id = %lab_id, lab_dir = %lab_dir, error = ?e, "Failed to delete lab directory");
+            }
+        }
+    } else {
+        summary.lab_directory_deleted = true;
+        tracing::debug!(lab_id = %lab_id, lab_dir = %lab_dir, "Lab directory already removed");
+    }
+
+    let success = errors.is_empty();
+    let total_duration = start_time.elapsed().as_secs();
+
+    let op_attrs = &[KeyValue::new("operation.type", "clean")];
+    state
+        .metrics
+        .operation_duration
+        .record(start_time.elapsed().as_secs_f64(), op_attrs);
+    if !success {
+        state.metrics.error_count.add(1, op_attrs);
+    }
+
+    tracing::info!(
+        lab_id = %lab_id,
+        lab_name = %lab_name,
+        success = success,
+        total_duration_secs = total_duration,
+        total_errors = errors.len(),
+        "Admin clean operation completed"
+    );
+
+    Ok(DestroyResponse {
+        success,
+        lab_id: lab_id.to_string(),
+        lab_name,
+        summary,
+        errors,
+    })
+}
+
+/// Tear down every runtime resource belonging to a lab.
+///
+/// Each resource type is attempted even if an earlier one failed; failures
+/// are recorded in `errors` rather than returned.
+pub(crate) async fn clean_runtime(
+    lab_id: &str,
+    runtime: &Runtime,
+    summary: &mut DestroySummary,
+    errors: &mut Vec<DestroyError>,
+) {
     // 1. Destroy containers
     let containers_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Cleaning containers");
-    destroy_containers(lab_id, &state.docker, &mut summary, &mut errors).await;
+    destroy_containers(lab_id, &*runtime.containers, summary, errors).await;
     tracing::info!(
         lab_id = %lab_id,
         destroyed = summary.containers_destroyed.len(),
@@ -60,7 +154,7 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
     // 2. Destroy VMs and disks
     let vms_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Cleaning VMs and disks");
-    if let Err(e) = destroy_vms_and_disks(lab_id, &state.qemu, &mut summary, &mut errors) {
+    if let Err(e) = destroy_vms_and_disks(lab_id, &*runtime.vms, summary, errors).await {
         errors.push(DestroyError::new(
             "vm",
             lab_id,
@@ -81,7 +175,7 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
     // 3. Destroy Docker networks
     let docker_net_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Cleaning Docker networks");
-    destroy_docker_networks(lab_id, &state.docker, &mut summary, &mut errors).await;
+    destroy_docker_networks(lab_id, &*runtime.containers, summary, errors).await;
     tracing::info!(
         lab_id = %lab_id,
         destroyed = summary.docker_networks_destroyed.len(),
@@ -93,7 +187,7 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
     // 4. Destroy libvirt networks
     let libvirt_net_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Cleaning libvirt networks");
-    if let Err(e) = destroy_libvirt_networks(lab_id, &state.qemu, &mut summary, &mut errors) {
+    if let Err(e) = destroy_libvirt_networks(lab_id, &*runtime.vms, summary, errors).await {
         errors.push(DestroyError::new(
             "libvirt_network",
             lab_id,
@@ -112,7 +206,7 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
     // 5. Delete network interfaces
     let interfaces_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Cleaning network interfaces");
-    destroy_interfaces(lab_id, &mut summary, &mut errors).await;
+    destroy_interfaces(lab_id, &*runtime.network, summary, errors).await;
     tracing::info!(
         lab_id = %lab_id,
         deleted = summary.interfaces_deleted.len(),
@@ -120,70 +214,73 @@ pub async fn clean_lab(lab_id: &str, state: &AppState) -> Result<DestroyResponse
         duration_secs = interfaces_timer.elapsed().as_secs(),
         "Network interface cleanup completed"
     );
+}
 
-    // 6. Clean up database (tolerate missing records)
-    tracing::info!(lab_id = %lab_id, "Cleaning database records");
-    match cleanup_database(lab_id, &state.db).await {
-        Ok(_) => {
-            summary.database_records_deleted = true;
-            tracing::info!(lab_id = %lab_id, "Database cleanup successful");
-        }
-        Err(e) => {
-            summary.database_records_deleted = false;
-            errors.push(DestroyError::new("database", lab_id, format!("{:?}", e)));
-            tracing::warn!(lab_id = %lab_id, error = ?e, "Database cleanup failed (may not exist)");
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
+    use bollard::secret::ContainerSummaryStateEnum;
+    use virt::sys::VIR_DOMAIN_RUNNING;
 
-    // 7. Delete lab directory
-    tracing::info!(lab_id = %lab_id, lab_dir = %lab_dir, "Deleting lab directory");
-    if dir_exists(&lab_dir) {
-        match fs::remove_dir_all(&lab_dir) {
-            Ok(_) => {
-                summary.lab_directory_deleted = true;
-                tracing::info!(lab_id = %lab_id, lab_dir = %lab_dir, "Lab directory deleted");
-            }
-            Err(e) => {
-                summary.lab_directory_deleted = false;
-                errors.push(DestroyError::new(
-                    "filesystem",
-                    &lab_dir,
-                    format!("{:?}", e),
-                ));
-                tracing::error!(lab_id = %lab_id, lab_dir = %lab_dir, error = ?e, "Failed to delete lab directory");
-            }
-        }
-    } else {
-        summary.lab_directory_deleted = true;
-        tracing::debug!(lab_id = %lab_id, lab_dir = %lab_dir, "Lab directory already removed");
+    /// Resources left behind by an `up` that failed partway through.
+    fn partial_lab(vms: FakeVmRuntime, containers: FakeContainerRuntime) -> FakeRuntime {
+        FakeRuntime::new(
+            vms.with_domain("r1-abc12345", VIR_DOMAIN_RUNNING)
+                .with_volume("r1-abc12345.qcow2")
+                .with_network("sherpa-isolated-r1-abc12345")
+                .with_domain("r1-zzz99999", VIR_DOMAIN_RUNNING),
+            containers
+                .with_container("srl1-abc12345", ContainerSummaryStateEnum::RUNNING)
+                .with_network("srl1-eth1-abc12345"),
+            FakeHostNetwork::default()
+                .with_interface("bra1-abc12345")
+                .with_interface("vea1-abc12345"),
+        )
     }
 
-    let success = errors.is_empty();
-    let total_duration = start_time.elapsed().as_secs();
+    #[tokio::test]
+    async fn test_clean_runtime_removes_lab_resources() {
+        let fakes = partial_lab(FakeVmRuntime::default(), FakeContainerRuntime::default());
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
 
-    let op_attrs = &[KeyValue::new("operation.type", "clean")];
-    state
-        .metrics
-        .operation_duration
-        .record(start_time.elapsed().as_secs_f64(), op_attrs);
-    if !success {
-        state.metrics.error_count.add(1, op_attrs);
+        clean_runtime("abc12345", &fakes.runtime(), &mut summary, &mut errors).await;
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            fakes.vms.domains().keys().collect::<Vec<_>>(),
+            vec!["r1-zzz99999"]
+        );
+        assert!(fakes.vms.volumes().is_empty());
+        assert!(fakes.vms.networks().is_empty());
+        assert!(fakes.containers.containers().is_empty());
+        assert!(fakes.containers.networks().is_empty());
+        assert!(fakes.network.interfaces().is_empty());
     }
 
-    tracing::info!(
-        lab_id = %lab_id,
-        lab_name = %lab_name,
-        success = success,
-        total_duration_secs = total_duration,
-        total_errors = errors.len(),
-        "Admin clean operation completed"
-    );
+    #[tokio::test]
+    async fn test_clean_runtime_continues_after_failures() {
+        let fakes = partial_lab(
+            FakeVmRuntime::default().fail_on("list_domains"),
+            FakeContainerRuntime::default().fail_on("remove_container srl1-abc12345"),
+        );
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
 
-    Ok(DestroyResponse {
-        success,
-        lab_id: lab_id.to_string(),
-        lab_name,
-        summary,
-        errors,
-    })
+        clean_runtime("abc12345", &fakes.runtime(), &mut summary, &mut errors).await;
+
+        assert_eq!(summary.containers_failed, vec!["srl1-abc12345"]);
+        assert_eq!(errors.len(), 2);
+        // Later phases still ran
+        assert_eq!(
+            summary.libvirt_networks_destroyed,
+            vec!["sherpa-isolated-r1-abc12345"]
+        );
+        assert_eq!(
+            summary.docker_networks_destroyed,
+            vec!["srl1-eth1-abc12345"]
+        );
+        assert_eq!(summary.interfaces_deleted.len(), 2);
+    }
 }
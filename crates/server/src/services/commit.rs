@@ -0,0 +1,332 @@
+// Server-side implementation of capturing a node as a new image version
+
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow, bail};
+use opentelemetry::KeyValue;
+use tracing::instrument;
+use uuid::Uuid;
+
+use shared::data::{NodeCommitRequest, NodeCommitResponse, NodeConfig, NodeKind, StatusKind};
+use shared::konst::{SHERPA_IMAGES_IMPORT_PATH, SHERPA_IMAGES_PATH, SHERPA_STORAGE_POOL_PATH};
+use shared::util::{
+    convert_disk_image, create_dir, delete_dirs, file_exists, file_sha256, image_filename,
+};
+
+use crate::daemon::state::AppState;
+use crate::services::progress::ProgressSender;
+
+/// Capture a node as a new version of its model's image.
+///
+/// VMs must be stopped. Their boot disk is flattened into
+/// `images_dir/<model>/<version>`. Containers are committed to
+/// `<repo>:<version>` while running. The new image is registered as a
+/// `node_image` record copied from the image the node was deployed from.
+#[instrument(skip(state, progress), fields(lab_id = %request.lab_id, node_name = %request.node_name, version = %request.version))]
+pub async fn commit_node(
+    request: NodeCommitRequest,
+    state: &AppState,
+    progress: ProgressSender,
+) -> Result<NodeCommitResponse> {
+    let start = Instant::now();
+    let result = commit(request, state, &progress, start).await;
+
+    let op_attrs = &[KeyValue::new("operation.type", "commit")];
+    state
+        .metrics
+        .operation_duration
+        .record(start.elapsed().as_secs_f64(), op_attrs);
+    if result.is_err() {
+        state.metrics.error_count.add(1, op_attrs);
+    }
+
+    result
+}
+
+async fn commit(
+    request: NodeCommitRequest,
+    state: &AppState,
+    progress: &ProgressSender,
+    start: Instant,
+) -> Result<NodeCommitResponse> {
+    let lab_id = &request.lab_id;
+    let node_name = &request.node_name;
+    validate_version(&request.version)?;
+
+    let _ = progress.send_status(
+        format!("Loading context for node: {}", node_name),
+        StatusKind::Progress,
+    );
+
+    let db_lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found in database", lab_id))?;
+    let lab_record_id = db::get_lab_id(&db_lab).context("Failed to get lab record ID")?;
+    let db_node = db::get_node_by_name_and_lab(&state.db, node_name, lab_record_id)
+        .await
+        .context(format!(
+            "Node '{}' not found in database for lab '{}'",
+            node_name, lab_id
+        ))?;
+    let source_image = db::get_node_image_by_id(&state.db, db_node.image.clone())
+        .await
+        .context(format!("Failed to get image of node '{}'", node_name))?
+        .ok_or_else(|| anyhow!("Image of node '{}' not found in database", node_name))?;
+
+    let model = source_image.model;
+    let kind = source_image.kind.clone();
+    if db::get_node_image_by_model_kind_version(&state.db, &model, &kind, &request.version)
+        .await
+        .context("Failed to query existing node_image")?
+        .is_some()
+    {
+        bail!(
+            "Image version '{}' already exists for model '{}'",
+            request.version,
+            model
+        );
+    }
+
+    let device_name = format!("{}-{}", node_name, lab_id);
+    let mut new_image = NodeConfig {
+        id: None,
+        version: request.version.clone(),
+        default: false,
+        image_sha256: None,
+        ..source_image
+    };
+
+    let image = match kind {
+        NodeKind::VirtualMachine => {
+            ensure_vm_stopped(state, &device_name, node_name).await?;
+
+            let filename = image_filename(&kind, new_image.boot_mode.as_ref());
+            let version_dir = format!("{SHERPA_IMAGES_PATH}/{model}/{}", request.version);
+            let version_disk = format!("{version_dir}/{filename}");
+            if file_exists(&version_disk) {
+                bail!("Image file already exists: {}", version_disk);
+            }
+
+            let src_disk = format!("{SHERPA_STORAGE_POOL_PATH}/{device_name}-hdd.qcow2");
+            let digest =
+                flatten_disk(&src_disk, filename, &version_dir, &version_disk, progress).await?;
+            new_image.image_sha256 = Some(digest);
+            version_disk
+        }
+        NodeKind::Container => {
+            let current = container::get_container_image(&state.docker, &device_name)
+                .await
+                .context(format!(
+                    "Failed to inspect container of node '{}'",
+                    node_name
+                ))?;
+            let repo = image_repo(&current);
+            let _ = progress.send_status(
+                format!(
+                    "Committing container {} to {}:{}",
+                    device_name, repo, request.version
+                ),
+                StatusKind::Progress,
+            );
+            container::commit_container(&state.docker, &device_name, repo, &request.version)
+                .await?;
+            let _ = progress.send_status("Container committed".to_string(), StatusKind::Done);
+            format!("{repo}:{}", request.version)
+        }
+        NodeKind::Unikernel => bail!("Commit is not supported for unikernel nodes"),
+    };
+
+    let _ = progress.send_status("Updating database...".to_string(), StatusKind::Progress);
+    let mut created = db::create_node_image(&state.db, new_image)
+        .await
+        .context(format!("Failed to register model '{}' in database", model))?;
+    if request.default {
+        // update_node_image unsets the default on the other versions
+        created.default = true;
+        db::update_node_image(&state.db, created.clone())
+            .await
+            .context("Failed to set image as default")?;
+    }
+    let _ = progress.send_status("Image tracked in database".to_string(), StatusKind::Done);
+
+    tracing::info!(
+        model = %model,
+        version = %request.version,
+        image = %image,
+        "Committed node to image"
+    );
+
+    Ok(NodeCommitResponse {
+        node_name: request.node_name,
+        model,
+        kind,
+        version: request.version,
+        image,
+        image_sha256: created.image_sha256,
+        default: created.default,
+        total_time_secs: start.elapsed().as_secs(),
+    })
+}
+
+/// Check a version can be used as both a directory name and a Docker tag.
+fn validate_version(version: &str) -> Result<()> {
+    let mut chars = version.chars();
+    let valid_first = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+    let valid_rest = chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid_first || !valid_rest || version.len() > 128 {
+        bail!(
+            "Invalid version '{}': use up to 128 letters, digits, '_', '.' or '-', not starting with '.' or '-'",
+            version
+        );
+    }
+    Ok(())
+}
+
+/// Strip the tag, if any, from an image reference.
+fn image_repo(reference: &str) -> &str {
+    match reference.rsplit_once(':') {
+        // A colon before the last slash is a registry port, not a tag
+        Some((repo, tag)) if !tag.contains('/') => repo,
+        _ => reference,
+    }
+}
+
+async fn ensure_vm_stopped(state: &AppState, device_name: &str, node_name: &str) -> Result<()> {
+    let qemu = state.qemu.clone();
+    let device_name = device_name.to_string();
+    let active = tokio::task::spawn_blocking(move || -> Result<bool> {
+        let conn = qemu.connect().context("Failed to connect to libvirt")?;
+        let domain = virt::domain::Domain::lookup_by_name(&conn, &device_name)
+            .map_err(|e| anyhow!("Domain not found: {}", e))?;
+        domain.is_active().context("Failed to check domain state")
+    })
+    .await
+    .context("Domain state task failed")??;
+
+    if active {
+        bail!(
+            "Node '{}' is running, stop it first with `sherpa down --node {}`",
+            node_name,
+            node_name
+        );
+    }
+    Ok(())
+}
+
+/// Flatten a node disk into a standalone qcow2 at `version_disk`.
+///
+/// `version_dir` is only created once the flattened disk is ready to be
+/// moved into it, and removed again if the move fails, so a failed commit
+/// leaves no empty version directory behind for `scan_images` to find.
+///
+/// # Returns
+/// The SHA-256 digest of the stored image
+async fn flatten_disk(
+    src_disk: &str,
+    filename: &'static str,
+    version_dir: &str,
+    version_disk: &str,
+    progress: &ProgressSender,
+) -> Result<String> {
+    if !file_exists(src_disk) {
+        bail!("Node disk not found: {}", src_disk);
+    }
+
+    let work_dir = format!("{SHERPA_IMAGES_IMPORT_PATH}/{}", Uuid::now_v7());
+    create_dir(&work_dir).context("Failed to create commit work directory")?;
+
+    let src_disk = src_disk.to_string();
+    let work_disk = format!("{work_dir}/{filename}");
+    let version_dir = version_dir.to_string();
+    let version_disk = version_disk.to_string();
+    let task_progress = progress.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<String> {
+        let _ = task_progress.send_status(
+            format!("Flattening disk {}...", src_disk),
+            StatusKind::Progress,
+        );
+        convert_disk_image(&src_disk, "qcow2", &work_disk, false)?;
+
+        let _ = task_progress.send_status(
+            "Calculating image digest...".to_string(),
+            StatusKind::Progress,
+        );
+        let digest = file_sha256(&work_disk)?;
+
+        create_dir(&version_dir).context("Failed to create version directory")?;
+        if let Err(e) = std::fs::rename(&work_disk, &version_disk) {
+            // remove_dir only succeeds on the empty directory created above
+            let _ = std::fs::remove_dir(&version_dir);
+            return Err(e).with_context(|| format!("Failed to move image to {}", version_disk));
+        }
+        let _ = task_progress.send_status(
+            format!("Image stored at {}", version_disk),
+            StatusKind::Done,
+        );
+        Ok(digest)
+    })
+    .await
+    .context("Disk flatten task failed")
+    .and_then(|result| result);
+
+    if let Err(e) = delete_dirs(&work_dir) {
+        tracing::warn!(
+            "Failed to clean up commit work directory {}: {:?}",
+            work_dir,
+            e
+        );
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_version() {
+        assert!(validate_version("17.9-golden").is_ok());
+        assert!(validate_version("v1_2").is_ok());
+        assert!(validate_version("").is_err());
+        assert!(validate_version(".hidden").is_err());
+        assert!(validate_version("-flag").is_err());
+        assert!(validate_version("../escape").is_err());
+        assert!(validate_version("a/b").is_err());
+        assert!(validate_version(&"a".repeat(129)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_flatten_disk_failure_leaves_no_version_dir() {
+        let version_dir = std::env::temp_dir()
+            .join(format!("sherpa-commit-{}", Uuid::now_v7()))
+            .to_string_lossy()
+            .to_string();
+        let version_disk = format!("{version_dir}/virtioa.qcow2");
+        let progress = ProgressSender::new(tokio::sync::mpsc::unbounded_channel().0);
+
+        let result = flatten_disk(
+            "/nonexistent/node-hdd.qcow2",
+            "virtioa.qcow2",
+            &version_dir,
+            &version_disk,
+            &progress,
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(!std::path::Path::new(&version_dir).exists());
+    }
+
+    #[test]
+    fn test_image_repo() {
+        assert_eq!(
+            image_repo("ghcr.io/nokia/srlinux:24.10.1"),
+            "ghcr.io/nokia/srlinux"
+        );
+        assert_eq!(image_repo("localhost:5000/frr:9.1"), "localhost:5000/frr");
+        assert_eq!(image_repo("localhost:5000/frr"), "localhost:5000/frr");
+        assert_eq!(image_repo("alpine"), "alpine");
+    }
+}
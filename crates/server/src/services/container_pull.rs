@@ -2,12 +2,15 @@ use anyhow::{Context, Result};
 use opentelemetry::KeyValue;
 use std::time::Instant;
 
-use shared::data::{ContainerPullRequest, ContainerPullResponse, NodeConfig, NodeKind, StatusKind};
+use shared::data::{
+    ContainerPullRequest, ContainerPullResponse, NodeConfig, NodeKind, StatusKind, image_registry,
+};
 
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
 use crate::services::progress::ProgressSender;
+use crate::services::registry;
 
 /// Pull a container image from an OCI registry via Docker and import to DB
 #[instrument(skip(state, progress), fields(model = %request.model, repo = %request.repo, tag = %request.tag))]
@@ -25,7 +28,16 @@ pub async fn pull_container_image(
         "Pulling container image"
     );
 
-    container::pull_image(&request.repo, &request.tag, |msg| {
+    let registry = image_registry(&request.repo);
+    let auth = registry::credentials_for(state, &registry).await?;
+    if auth.is_some() {
+        let _ = progress.send_status(
+            format!("Using stored credentials for {}", registry),
+            StatusKind::Info,
+        );
+    }
+
+    container::pull_image(&request.repo, &request.tag, auth.as_ref(), |msg| {
         let _ = progress.send_status(msg.to_string(), StatusKind::Progress);
     })
     .await
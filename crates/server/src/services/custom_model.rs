@@ -0,0 +1,301 @@
+//! User-defined node models.
+//!
+//! Each custom model is a TOML definition stored as
+//! `SHERPA_MODELS_PATH/<name>.toml`. Manifests refer to them as
+//! `model = "custom:<name>"`; before validation the server swaps each custom
+//! node to its generic base model and attaches the definition, which then
+//! overlays the base image settings, interface naming and ZTP.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use tracing::instrument;
+
+use shared::data::{
+    AddCustomModelRequest, AddCustomModelResponse, CustomModel, DeleteCustomModelRequest,
+    DeleteCustomModelResponse, ListCustomModelsResponse, NodeModel, parse_custom_model_ref,
+    validate_custom_model_name,
+};
+use shared::konst::SHERPA_MODELS_PATH;
+
+/// Add, or replace, a custom model definition
+#[instrument(skip(request), fields(replace = request.replace))]
+pub fn add_model(request: AddCustomModelRequest) -> Result<AddCustomModelResponse> {
+    add_model_in(Path::new(SHERPA_MODELS_PATH), request)
+}
+
+/// List all custom model definitions
+pub fn list_models() -> Result<ListCustomModelsResponse> {
+    list_models_in(Path::new(SHERPA_MODELS_PATH))
+}
+
+/// Delete a custom model definition
+#[instrument(skip(request), fields(name = %request.name))]
+pub fn delete_model(request: DeleteCustomModelRequest) -> Result<DeleteCustomModelResponse> {
+    delete_model_in(Path::new(SHERPA_MODELS_PATH), request)
+}
+
+/// Resolve the custom model nodes of a manifest.
+///
+/// Custom nodes are switched to their base model, and take the definition's
+/// base image version when the manifest doesn't set one. The returned map
+/// holds the definitions by name, for attaching to the expanded nodes.
+pub fn resolve_manifest_models(
+    nodes: &mut [topology::Node],
+) -> Result<HashMap<String, CustomModel>> {
+    resolve_manifest_models_in(Path::new(SHERPA_MODELS_PATH), nodes)
+}
+
+fn model_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    validate_custom_model_name(name)?;
+    Ok(dir.join(format!("{name}.toml")))
+}
+
+fn load_model(dir: &Path, name: &str) -> Result<CustomModel> {
+    let path = model_path(dir, name)?;
+    let definition = match fs::read_to_string(&path) {
+        Ok(definition) => definition,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            bail!("Custom model not found: {name} (add it with `sherpa server image model add`)")
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    let model = CustomModel::from_toml(&definition)
+        .with_context(|| format!("Failed to load custom model from {}", path.display()))?;
+    if model.name != name {
+        bail!(
+            "Custom model file {} defines model '{}'",
+            path.display(),
+            model.name
+        );
+    }
+    Ok(model)
+}
+
+fn add_model_in(dir: &Path, request: AddCustomModelRequest) -> Result<AddCustomModelResponse> {
+    let model = CustomModel::from_toml(&request.definition)?;
+    if let Some(template) = &model.ztp.template {
+        template::CustomZtpTemplate::check(template)
+            .with_context(|| format!("Custom model '{}': invalid ZTP template", model.name))?;
+    }
+
+    let path = model_path(dir, &model.name)?;
+    let replaced = path.exists();
+    if replaced && !request.replace {
+        bail!(
+            "Custom model '{}' already exists (use --replace to overwrite it)",
+            model.name
+        );
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    // Write to a temporary file first so a failed write never leaves a
+    // truncated definition behind
+    let tmp_path = path.with_extension("toml.tmp");
+    fs::write(&tmp_path, &request.definition)
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    tracing::info!(name = %model.name, replaced, "Stored custom model");
+    Ok(AddCustomModelResponse { model, replaced })
+}
+
+fn list_models_in(dir: &Path) -> Result<ListCustomModelsResponse> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(ListCustomModelsResponse {
+                models: vec![],
+                total: 0,
+            });
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+    };
+
+    let mut models = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        match load_model(dir, name) {
+            Ok(model) => models.push(model),
+            // A broken file shouldn't hide every other model
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Skipping invalid custom model")
+            }
+        }
+    }
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let total = models.len();
+    Ok(ListCustomModelsResponse { models, total })
+}
+
+fn delete_model_in(
+    dir: &Path,
+    request: DeleteCustomModelRequest,
+) -> Result<DeleteCustomModelResponse> {
+    let name = parse_custom_model_ref(&request.name).unwrap_or(&request.name);
+    let path = model_path(dir, name)?;
+    match fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => bail!("Custom model not found: {name}"),
+        Err(e) => return Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+    }
+
+    tracing::info!(name = %name, "Deleted custom model");
+    Ok(DeleteCustomModelResponse {
+        name: name.to_string(),
+    })
+}
+
+fn resolve_manifest_models_in(
+    dir: &Path,
+    nodes: &mut [topology::Node],
+) -> Result<HashMap<String, CustomModel>> {
+    let mut models: HashMap<String, CustomModel> = HashMap::new();
+    for node in nodes.iter_mut() {
+        if node.model != NodeModel::Custom {
+            continue;
+        }
+        let Some(name) = node.custom_model.clone() else {
+            bail!("Node '{}': custom model name is missing", node.name);
+        };
+        if !models.contains_key(&name) {
+            let model = load_model(dir, &name).with_context(|| format!("Node '{}'", node.name))?;
+            models.insert(name.clone(), model);
+        }
+        let model = &models[&name];
+        node.model = model.base_model();
+        if node.version.is_none() {
+            node.version = model.version.clone();
+        }
+    }
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VYOS: &str = r#"
+name = "vyos"
+version = "1.5"
+
+[interfaces]
+management = "eth0"
+
+[ztp]
+method = "cdrom"
+filename = "config.boot"
+template = "set system host-name {{ hostname }}"
+"#;
+
+    fn add(dir: &Path, definition: &str, replace: bool) -> Result<AddCustomModelResponse> {
+        add_model_in(
+            dir,
+            AddCustomModelRequest {
+                definition: definition.to_string(),
+                replace,
+            },
+        )
+    }
+
+    #[test]
+    fn test_add_list_delete_model() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(list_models_in(dir.path()).unwrap().total, 0);
+
+        let added = add(dir.path(), VYOS, false).unwrap();
+        assert_eq!(added.model.name, "vyos");
+        assert!(!added.replaced);
+
+        let listed = list_models_in(dir.path()).unwrap();
+        assert_eq!(listed.total, 1);
+        assert_eq!(listed.models[0].name, "vyos");
+
+        assert!(add(dir.path(), VYOS, false).is_err());
+        assert!(add(dir.path(), VYOS, true).unwrap().replaced);
+
+        let deleted = delete_model_in(
+            dir.path(),
+            DeleteCustomModelRequest {
+                name: "custom:vyos".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(deleted.name, "vyos");
+        assert_eq!(list_models_in(dir.path()).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_add_model_rejects_bad_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let definition = VYOS.replace("{{ hostname }}", "{{ host }}");
+        let err = add(dir.path(), &definition, false).unwrap_err();
+        assert!(format!("{err:#}").contains("host"));
+        assert_eq!(list_models_in(dir.path()).unwrap().total, 0);
+    }
+
+    #[test]
+    fn test_list_models_skips_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        add(dir.path(), VYOS, false).unwrap();
+        fs::write(dir.path().join("broken.toml"), "name = ").unwrap();
+        fs::write(dir.path().join("other.toml"), "name = \"vyos\"").unwrap();
+        let listed = list_models_in(dir.path()).unwrap();
+        assert_eq!(listed.total, 1);
+    }
+
+    #[test]
+    fn test_resolve_manifest_models() {
+        let dir = tempfile::tempdir().unwrap();
+        add(dir.path(), VYOS, false).unwrap();
+
+        let mut nodes = vec![
+            topology::Node {
+                name: "r1".to_string(),
+                model: NodeModel::Custom,
+                custom_model: Some("vyos".to_string()),
+                ..Default::default()
+            },
+            topology::Node {
+                name: "r2".to_string(),
+                model: NodeModel::Custom,
+                custom_model: Some("vyos".to_string()),
+                version: Some("1.4".to_string()),
+                ..Default::default()
+            },
+            topology::Node {
+                name: "r3".to_string(),
+                model: NodeModel::CiscoIosv,
+                ..Default::default()
+            },
+        ];
+        let models = resolve_manifest_models_in(dir.path(), &mut nodes).unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(nodes[0].model, NodeModel::GenericVm);
+        assert_eq!(nodes[0].version.as_deref(), Some("1.5"));
+        assert_eq!(nodes[0].custom_model.as_deref(), Some("vyos"));
+        assert_eq!(nodes[1].version.as_deref(), Some("1.4"));
+        assert_eq!(nodes[2].model, NodeModel::CiscoIosv);
+
+        let mut missing = vec![topology::Node {
+            name: "r4".to_string(),
+            model: NodeModel::Custom,
+            custom_model: Some("sonic".to_string()),
+            ..Default::default()
+        }];
+        let err = resolve_manifest_models_in(dir.path(), &mut missing).unwrap_err();
+        assert!(format!("{err:#}").contains("Custom model not found: sonic"));
+    }
+}
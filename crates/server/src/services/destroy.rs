@@ -2,18 +2,12 @@ use std::fs;
 
 use anyhow::{Context, Result, anyhow};
 use opentelemetry::KeyValue;
-use virt::storage_pool::StoragePool;
-use virt::sys::VIR_DOMAIN_UNDEFINE_NVRAM;
 
-use container::{delete_network, kill_container, list_containers, list_networks, remove_container};
-use libvirt::delete_disk;
-use network::{delete_interface, find_interfaces_fuzzy};
 use shared::data::{
     DestroyError, DestroyRequest, DestroyResponse, DestroySummary, LabInfo, StatusKind,
 };
 use shared::konst::{
-    BRIDGE_PREFIX, CONTAINER_VETH_PREFIX, LAB_FILE_NAME, SHERPA_LABS_PATH, SHERPA_STORAGE_POOL,
-    TAP_PREFIX, VETH_PREFIX,
+    BRIDGE_PREFIX, CONTAINER_VETH_PREFIX, LAB_FILE_NAME, SHERPA_LABS_PATH, TAP_PREFIX, VETH_PREFIX,
 };
 use shared::util::{dir_exists, load_file};
 use std::str::FromStr;
@@ -21,6 +15,8 @@ use std::str::FromStr;
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::runtime::{ContainerRuntime, HostNetwork, VmRuntime};
+use crate::services::boot;
 use crate::services::progress::ProgressSender;
 
 /// Destroy a lab and all its resources
@@ -103,11 +99,23 @@ pub async fn destroy_lab(
         "Loaded lab information"
     );
 
+    // Stop built-in boot services before their bridge is removed
+    if boot::stop(state, lab_id) {
+        let _ = progress.send_status("Stopped boot services".to_string(), StatusKind::Done);
+    }
+    state.cabling.remove(lab_id);
+
     // 1. Destroy containers
     let containers_timer = std::time::Instant::now();
     tracing::info!(lab_id = %lab_id, "Destroying containers");
     let _ = progress.send_status("Destroying containers...".to_string(), StatusKind::Progress);
-    destroy_containers(lab_id, &state.docker, &mut summary, &mut errors).await;
+    destroy_containers(
+        lab_id,
+        &*state.runtime.containers,
+        &mut summary,
+        &mut errors,
+    )
+    .await;
     let containers_duration = containers_timer.elapsed().as_secs();
     if summary.containers_destroyed.is_empty() && summary.containers_failed.is_empty() {
         let _ = progress.send_status("No containers to destroy".to_string(), StatusKind::Info);
@@ -132,7 +140,7 @@ pub async fn destroy_lab(
         "Destroying VMs and disks...".to_string(),
         StatusKind::Progress,
     );
-    destroy_vms_and_disks(lab_id, &state.qemu, &mut summary, &mut errors)?;
+    destroy_vms_and_disks(lab_id, &*state.runtime.vms, &mut summary, &mut errors).await?;
     if summary.vms_destroyed.is_empty() && summary.vms_failed.is_empty() {
         let _ = progress.send_status("No VMs to destroy".to_string(), StatusKind::Info);
     } else {
@@ -161,7 +169,13 @@ pub async fn destroy_lab(
         "Destroying Docker networks...".to_string(),
         StatusKind::Progress,
     );
-    destroy_docker_networks(lab_id, &state.docker, &mut summary, &mut errors).await;
+    destroy_docker_networks(
+        lab_id,
+        &*state.runtime.containers,
+        &mut summary,
+        &mut errors,
+    )
+    .await;
     if summary.docker_networks_destroyed.is_empty() && summary.docker_networks_failed.is_empty() {
         let _ = progress.send_status(
             "No Docker networks to destroy".to_string(),
@@ -191,7 +205,7 @@ pub async fn destroy_lab(
         "Destroying libvirt networks...".to_string(),
         StatusKind::Progress,
     );
-    destroy_libvirt_networks(lab_id, &state.qemu, &mut summary, &mut errors)?;
+    destroy_libvirt_networks(lab_id, &*state.runtime.vms, &mut summary, &mut errors).await?;
     if summary.libvirt_networks_destroyed.is_empty() && summary.libvirt_networks_failed.is_empty() {
         let _ = progress.send_status(
             "No libvirt networks to destroy".to_string(),
@@ -221,7 +235,7 @@ pub async fn destroy_lab(
         "Deleting network interfaces...".to_string(),
         StatusKind::Progress,
     );
-    destroy_interfaces(lab_id, &mut summary, &mut errors).await;
+    destroy_interfaces(lab_id, &*state.runtime.network, &mut summary, &mut errors).await;
     if summary.interfaces_deleted.is_empty() && summary.interfaces_failed.is_empty() {
         let _ = progress.send_status(
             "No network interfaces to delete".to_string(),
@@ -330,19 +344,15 @@ pub async fn destroy_lab(
 /// Destroy all containers for a lab
 pub(crate) async fn destroy_containers(
     lab_id: &str,
-    docker: &bollard::Docker,
+    containers: &dyn ContainerRuntime,
     summary: &mut DestroySummary,
     errors: &mut Vec<DestroyError>,
 ) {
-    match list_containers(docker).await {
-        Ok(containers) => {
-            let lab_containers: Vec<_> = containers
-                .iter()
-                .filter(|c| {
-                    c.names
-                        .as_ref()
-                        .is_some_and(|names| names.iter().any(|name| name.contains(lab_id)))
-                })
+    match containers.list_containers().await {
+        Ok(names) => {
+            let lab_containers: Vec<String> = names
+                .into_iter()
+                .filter(|name| name.contains(lab_id))
                 .collect();
 
             tracing::debug!(
@@ -351,55 +361,41 @@ pub(crate) async fn destroy_containers(
                 "Found containers to destroy"
             );
 
-            for container in containers {
-                if let Some(names) = &container.names {
-                    // Check if any container name contains the lab_id
-                    if names.iter().any(|name| name.contains(lab_id)) {
-                        // From docs: for historical reasons, container names start with a '/'
-                        // Extract the actual container name (remove leading /)
-                        if let Some(container_name) = names.first() {
-                            let name = container_name.trim_start_matches('/');
-                            tracing::debug!(
-                                lab_id = %lab_id,
-                                container_name = %name,
-                                "Destroying container"
-                            );
-                            // Best-effort kill — container may not be running
-                            // (e.g. created but never started during a partial failure).
-                            if let Err(e) = kill_container(docker, name).await {
-                                tracing::debug!(
-                                    lab_id = %lab_id,
-                                    container_name = %name,
-                                    error = ?e,
-                                    "Kill container failed (may not be running), proceeding to remove"
-                                );
-                            }
-
-                            match remove_container(docker, name).await {
-                                Ok(_) => {
-                                    summary.containers_destroyed.push(name.to_string());
-                                    tracing::info!(
-                                        lab_id = %lab_id,
-                                        container_name = %name,
-                                        "Container destroyed"
-                                    );
-                                }
-                                Err(e) => {
-                                    summary.containers_failed.push(name.to_string());
-                                    errors.push(DestroyError::new(
-                                        "container",
-                                        name,
-                                        format!("{:?}", e),
-                                    ));
-                                    tracing::error!(
-                                        lab_id = %lab_id,
-                                        container_name = %name,
-                                        error = ?e,
-                                        "Failed to remove container"
-                                    );
-                                }
-                            }
-                        }
+            for name in &lab_containers {
+                tracing::debug!(
+                    lab_id = %lab_id,
+                    container_name = %name,
+                    "Destroying container"
+                );
+                // Best-effort kill — container may not be running
+                // (e.g. created but never started during a partial failure).
+                if let Err(e) = containers.kill_container(name).await {
+                    tracing::debug!(
+                        lab_id = %lab_id,
+                        container_name = %name,
+                        error = ?e,
+                        "Kill container failed (may not be running), proceeding to remove"
+                    );
+                }
+
+                match containers.remove_container(name).await {
+                    Ok(_) => {
+                        summary.containers_destroyed.push(name.to_string());
+                        tracing::info!(
+                            lab_id = %lab_id,
+                            container_name = %name,
+                            "Container destroyed"
+                        );
+                    }
+                    Err(e) => {
+                        summary.containers_failed.push(name.to_string());
+                        errors.push(DestroyError::new("container", name, format!("{:?}", e)));
+                        tracing::error!(
+                            lab_id = %lab_id,
+                            container_name = %name,
+                            error = ?e,
+                            "Failed to remove container"
+                        );
                     }
                 }
             }
@@ -416,75 +412,34 @@ pub(crate) async fn destroy_containers(
 }
 
 /// Destroy all VMs and their disks for a lab
-pub(crate) fn destroy_vms_and_disks(
+pub(crate) async fn destroy_vms_and_disks(
     lab_id: &str,
-    qemu: &libvirt::Qemu,
+    vms: &dyn VmRuntime,
     summary: &mut DestroySummary,
     errors: &mut Vec<DestroyError>,
 ) -> Result<()> {
-    let qemu_conn = qemu.connect().context("Failed to connect to libvirt")?;
-
     // Destroy VMs
-    let domains = qemu_conn
-        .list_all_domains(0)
-        .context("Failed to list domains")?;
-
-    for domain in domains {
-        let vm_name = match domain.get_name() {
-            Ok(name) => name,
-            Err(e) => {
-                errors.push(DestroyError::new(
-                    "vm",
-                    "unknown",
-                    format!("Failed to get domain name: {:?}", e),
-                ));
-                continue;
-            }
-        };
-
-        if !vm_name.contains(lab_id) {
-            continue;
-        }
+    let domains = vms.list_domains().await?;
 
-        let is_active = domain.is_active().unwrap_or(false);
-
-        // UEFI domains will have an NVRAM file that must be deleted.
-        let result = domain
-            .undefine_flags(VIR_DOMAIN_UNDEFINE_NVRAM)
-            .context("Failed to undefine domain")
-            .and_then(|_| {
-                if is_active {
-                    domain.destroy().context("Failed to destroy domain")?;
-                }
-                Ok(())
-            });
-
-        match result {
+    for vm_name in domains.iter().filter(|name| name.contains(lab_id)) {
+        match vms.destroy_domain(vm_name).await {
             Ok(_) => {
                 summary.vms_destroyed.push(vm_name.clone());
                 tracing::info!("Destroyed VM: {}", vm_name);
             }
             Err(e) => {
                 summary.vms_failed.push(vm_name.clone());
-                errors.push(DestroyError::new("vm", &vm_name, format!("{:?}", e)));
+                errors.push(DestroyError::new("vm", vm_name, format!("{:?}", e)));
                 tracing::error!("Failed to destroy VM {}: {:?}", vm_name, e);
             }
         }
     }
 
     // Delete all disks belonging to this lab
+    let pool_disks = vms.list_volumes().await?;
 
-    let storage_pool = StoragePool::lookup_by_name(&qemu_conn, SHERPA_STORAGE_POOL).context(
-        format!("Failed to find storage pool '{}'", SHERPA_STORAGE_POOL),
-    )?;
-    let pool_disks = storage_pool
-        .list_volumes()
-        .context("Failed to list storage volumes")?;
-
-    let lab_disks: Vec<&String> = pool_disks.iter().filter(|d| d.contains(lab_id)).collect();
-
-    for disk in lab_disks {
-        match delete_disk(&qemu_conn, disk) {
+    for disk in pool_disks.iter().filter(|d| d.contains(lab_id)) {
+        match vms.delete_volume(disk).await {
             Ok(_) => {
                 summary.disks_deleted.push(disk.to_string());
                 tracing::info!("Deleted disk: {}", disk);
@@ -503,34 +458,30 @@ pub(crate) fn destroy_vms_and_disks(
 /// Destroy all Docker networks for a lab
 pub(crate) async fn destroy_docker_networks(
     lab_id: &str,
-    docker: &bollard::Docker,
+    containers: &dyn ContainerRuntime,
     summary: &mut DestroySummary,
     errors: &mut Vec<DestroyError>,
 ) {
-    match list_networks(docker).await {
-        Ok(container_networks) => {
-            for network in container_networks {
-                if let Some(network_name) = network.name
-                    && network_name.contains(lab_id)
-                {
-                    match delete_network(docker, &network_name).await {
-                        Ok(_) => {
-                            summary.docker_networks_destroyed.push(network_name.clone());
-                            tracing::info!("Destroyed Docker network: {}", network_name);
-                        }
-                        Err(e) => {
-                            summary.docker_networks_failed.push(network_name.clone());
-                            errors.push(DestroyError::new(
-                                "docker_network",
-                                &network_name,
-                                format!("{:?}", e),
-                            ));
-                            tracing::error!(
-                                "Failed to destroy Docker network {}: {:?}",
-                                network_name,
-                                e
-                            );
-                        }
+    match containers.list_networks().await {
+        Ok(network_names) => {
+            for network_name in network_names.iter().filter(|n| n.contains(lab_id)) {
+                match containers.delete_network(network_name).await {
+                    Ok(_) => {
+                        summary.docker_networks_destroyed.push(network_name.clone());
+                        tracing::info!("Destroyed Docker network: {}", network_name);
+                    }
+                    Err(e) => {
+                        summary.docker_networks_failed.push(network_name.clone());
+                        errors.push(DestroyError::new(
+                            "docker_network",
+                            network_name,
+                            format!("{:?}", e),
+                        ));
+                        tracing::error!(
+                            "Failed to destroy Docker network {}: {:?}",
+                            network_name,
+                            e
+                        );
                     }
                 }
             }
@@ -547,58 +498,34 @@ pub(crate) async fn destroy_docker_networks(
 }
 
 /// Destroy all libvirt networks for a lab
-pub(crate) fn destroy_libvirt_networks(
+pub(crate) async fn destroy_libvirt_networks(
     lab_id: &str,
-    qemu: &libvirt::Qemu,
+    vms: &dyn VmRuntime,
     summary: &mut DestroySummary,
     errors: &mut Vec<DestroyError>,
 ) -> Result<()> {
-    let qemu_conn = qemu.connect().context("Failed to connect to libvirt")?;
-    let networks = qemu_conn
-        .list_all_networks(0)
-        .context("Failed to list networks")?;
-
-    for network in networks {
-        let network_name = match network.get_name() {
-            Ok(name) => name,
+    let networks = vms.list_networks().await?;
+
+    for network_name in networks.iter().filter(|n| n.contains(lab_id)) {
+        match vms.delete_network(network_name).await {
+            Ok(_) => {
+                summary
+                    .libvirt_networks_destroyed
+                    .push(network_name.clone());
+                tracing::info!("Destroyed libvirt network: {}", network_name);
+            }
             Err(e) => {
+                summary.libvirt_networks_failed.push(network_name.clone());
                 errors.push(DestroyError::new(
                     "libvirt_network",
-                    "unknown",
-                    format!("Failed to get network name: {:?}", e),
+                    network_name,
+                    format!("{:?}", e),
                 ));
-                continue;
-            }
-        };
-
-        if network_name.contains(lab_id) {
-            match (|| -> Result<()> {
-                let is_active = network.is_active().unwrap_or(false);
-                if is_active {
-                    network.destroy().context("Failed to destroy network")?;
-                }
-                network.undefine().context("Failed to undefine network")?;
-                Ok(())
-            })() {
-                Ok(_) => {
-                    summary
-                        .libvirt_networks_destroyed
-                        .push(network_name.clone());
-                    tracing::info!("Destroyed libvirt network: {}", network_name);
-                }
-                Err(e) => {
-                    summary.libvirt_networks_failed.push(network_name.clone());
-                    errors.push(DestroyError::new(
-                        "libvirt_network",
-                        &network_name,
-                        format!("{:?}", e),
-                    ));
-                    tracing::error!(
-                        "Failed to destroy libvirt network {}: {:?}",
-                        network_name,
-                        e
-                    );
-                }
+                tracing::error!(
+                    "Failed to destroy libvirt network {}: {:?}",
+                    network_name,
+                    e
+                );
             }
         }
     }
@@ -606,42 +533,44 @@ pub(crate) fn destroy_libvirt_networks(
     Ok(())
 }
 
+/// Whether a host interface was created by Sherpa outside of libvirt/Docker.
+/// Only one side of a veth pair needs to be deleted.
+fn is_sherpa_interface(interface: &str) -> bool {
+    interface.starts_with(&format!("{}a", BRIDGE_PREFIX))
+        || interface.starts_with(&format!("{}b", BRIDGE_PREFIX))
+        || interface.starts_with(&format!("{}i", BRIDGE_PREFIX))
+        || interface.starts_with(&format!("{}s", BRIDGE_PREFIX))
+        || interface.starts_with(&format!("{}a", VETH_PREFIX))
+        || interface.starts_with(&format!("{}a", TAP_PREFIX))
+        || interface.starts_with(&format!("{}b", TAP_PREFIX))
+        || interface.starts_with(CONTAINER_VETH_PREFIX)
+        || interface.starts_with("cd")
+        || interface.starts_with("ce")
+}
+
 /// Delete network interfaces for a lab
 pub(crate) async fn destroy_interfaces(
     lab_id: &str,
+    network: &dyn HostNetwork,
     summary: &mut DestroySummary,
     errors: &mut Vec<DestroyError>,
 ) {
-    match find_interfaces_fuzzy(lab_id).await {
+    match network.find_interfaces(lab_id).await {
         Ok(lab_interfaces) => {
-            for interface in lab_interfaces {
-                // Only delete interfaces created outside of Libvirt/Docker
-                // Only 1 side of the veth interface needs to be deleted
-                if interface.starts_with(&format!("{}a", BRIDGE_PREFIX))
-                    || interface.starts_with(&format!("{}b", BRIDGE_PREFIX))
-                    || interface.starts_with(&format!("{}i", BRIDGE_PREFIX))
-                    || interface.starts_with(&format!("{}s", BRIDGE_PREFIX))
-                    || interface.starts_with(&format!("{}a", VETH_PREFIX))
-                    || interface.starts_with(&format!("{}a", TAP_PREFIX))
-                    || interface.starts_with(&format!("{}b", TAP_PREFIX))
-                    || interface.starts_with(CONTAINER_VETH_PREFIX)
-                    || interface.starts_with("cd")
-                    || interface.starts_with("ce")
-                {
-                    match delete_interface(&interface).await {
-                        Ok(_) => {
-                            summary.interfaces_deleted.push(interface.clone());
-                            tracing::info!("Deleted interface: {}", interface);
-                        }
-                        Err(e) => {
-                            summary.interfaces_failed.push(interface.clone());
-                            errors.push(DestroyError::new(
-                                "interface",
-                                &interface,
-                                format!("{:?}", e),
-                            ));
-                            tracing::error!("Failed to delete interface {}: {:?}", interface, e);
-                        }
+            for interface in lab_interfaces.iter().filter(|i| is_sherpa_interface(i)) {
+                match network.delete_interface(interface).await {
+                    Ok(_) => {
+                        summary.interfaces_deleted.push(interface.clone());
+                        tracing::info!("Deleted interface: {}", interface);
+                    }
+                    Err(e) => {
+                        summary.interfaces_failed.push(interface.clone());
+                        errors.push(DestroyError::new(
+                            "interface",
+                            interface,
+                            format!("{:?}", e),
+                        ));
+                        tracing::error!("Failed to delete interface {}: {:?}", interface, e);
                     }
                 }
             }
@@ -670,3 +599,103 @@ pub(crate) async fn cleanup_database(lab_id: &str, db: &db::Database) -> Result<
         .context("Failed to delete lab")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeVmRuntime};
+    use bollard::secret::ContainerSummaryStateEnum;
+    use virt::sys::{VIR_DOMAIN_RUNNING, VIR_DOMAIN_SHUTOFF};
+
+    #[tokio::test]
+    async fn test_destroy_containers_only_touches_lab() {
+        let containers = FakeContainerRuntime::default()
+            .with_container("srl1-abc12345", ContainerSummaryStateEnum::RUNNING)
+            .with_container("srl2-abc12345", ContainerSummaryStateEnum::CREATED)
+            .with_container("srl1-zzz99999", ContainerSummaryStateEnum::RUNNING);
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
+
+        destroy_containers("abc12345", &containers, &mut summary, &mut errors).await;
+
+        assert_eq!(
+            summary.containers_destroyed,
+            vec!["srl1-abc12345", "srl2-abc12345"]
+        );
+        assert!(errors.is_empty());
+        assert_eq!(
+            containers.containers().keys().collect::<Vec<_>>(),
+            vec!["srl1-zzz99999"]
+        );
+        // A container that was never started is still removed after the kill fails
+        assert!(
+            containers
+                .calls()
+                .contains(&"remove_container srl2-abc12345".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_destroy_vms_and_disks_continues_after_failure() {
+        let vms = FakeVmRuntime::default()
+            .with_domain("r1-abc12345", VIR_DOMAIN_RUNNING)
+            .with_domain("r2-abc12345", VIR_DOMAIN_SHUTOFF)
+            .with_volume("r1-abc12345.qcow2")
+            .with_volume("r2-abc12345.qcow2")
+            .with_volume("r1-zzz99999.qcow2")
+            .fail_on("destroy_domain r1-abc12345");
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
+
+        destroy_vms_and_disks("abc12345", &vms, &mut summary, &mut errors)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.vms_failed, vec!["r1-abc12345"]);
+        assert_eq!(summary.vms_destroyed, vec!["r2-abc12345"]);
+        assert_eq!(
+            summary.disks_deleted,
+            vec!["r1-abc12345.qcow2", "r2-abc12345.qcow2"]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(vms.volumes(), vec!["r1-zzz99999.qcow2"]);
+    }
+
+    #[tokio::test]
+    async fn test_destroy_vms_and_disks_propagates_list_failure() {
+        let vms = FakeVmRuntime::default().fail_on("list_domains");
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
+
+        let result = destroy_vms_and_disks("abc12345", &vms, &mut summary, &mut errors).await;
+
+        assert!(result.is_err());
+        assert!(vms.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_destroy_interfaces_skips_foreign_interfaces() {
+        let network = FakeHostNetwork::default()
+            .with_interface("bra1-abc12345")
+            .with_interface("tpa1-abc12345")
+            .with_interface("vnet-abc12345");
+        let mut summary = DestroySummary::default();
+        let mut errors = Vec::new();
+
+        destroy_interfaces("abc12345", &network, &mut summary, &mut errors).await;
+
+        assert_eq!(
+            summary.interfaces_deleted,
+            vec!["bra1-abc12345", "tpa1-abc12345"]
+        );
+        assert_eq!(network.interfaces(), vec!["vnet-abc12345"]);
+    }
+
+    #[test]
+    fn test_is_sherpa_interface() {
+        assert!(is_sherpa_interface("brs0-abc12345"));
+        assert!(is_sherpa_interface("cv1-abc12345"));
+        assert!(!is_sherpa_interface("brm-abc12345"));
+        assert!(!is_sherpa_interface("eth0"));
+    }
+}
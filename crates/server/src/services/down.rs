@@ -7,6 +7,7 @@ use std::time::Instant;
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::runtime::{Runtime, VmShutdown};
 
 /// Shutdown all (or a specific) node(s) for a lab.
 ///
@@ -86,9 +87,11 @@ pub async fn shutdown_lab_nodes(
         futures.push(async move {
             let result = match kind {
                 NodeKind::VirtualMachine | NodeKind::Unikernel => {
-                    shutdown_vm(&device_name, &node_name, &state).await
+                    shutdown_vm(&device_name, &node_name, &state.runtime).await
+                }
+                NodeKind::Container => {
+                    shutdown_container(&device_name, &node_name, &state.runtime).await
                 }
-                NodeKind::Container => shutdown_container(&device_name, &node_name, &state).await,
             };
 
             (result, node_name, node_id)
@@ -118,99 +121,26 @@ pub async fn shutdown_lab_nodes(
     Ok(LabNodeActionResponse { results })
 }
 
-/// Check if the QEMU guest agent is available by sending a ping command.
-/// Uses a short timeout (5s) so we don't block long on unresponsive VMs.
-fn has_guest_agent(domain: &virt::domain::Domain) -> bool {
-    domain
-        .qemu_agent_command(r#"{"execute":"guest-ping"}"#, 5, 0)
-        .is_ok()
-}
-
-async fn shutdown_vm(device_name: &str, node_name: &str, state: &AppState) -> NodeActionResult {
-    let qemu = state.qemu.clone();
-    let device_name = device_name.to_string();
-    let node_name_owned = node_name.to_string();
-
-    match tokio::task::spawn_blocking(move || -> Result<NodeActionResult> {
-        let node_name = node_name_owned;
-        let conn = qemu.connect().context("Failed to connect to libvirt")?;
-        let domain = match virt::domain::Domain::lookup_by_name(&conn, &device_name) {
-            Ok(d) => d,
-            Err(e) => {
-                return Ok(NodeActionResult {
-                    name: node_name,
-                    success: false,
-                    message: format!("Domain not found: {}", e),
-                });
-            }
-        };
-
-        match domain.is_active() {
-            Ok(true) => {
-                if has_guest_agent(&domain) {
-                    // Guest agent available — graceful ACPI shutdown
-                    match domain.shutdown() {
-                        Ok(_) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: true,
-                            message: "Shutdown initiated (graceful)".to_string(),
-                        }),
-                        Err(e) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: false,
-                            message: format!("Failed to shutdown: {}", e),
-                        }),
-                    }
-                } else {
-                    // No guest agent — force power off
-                    match domain.destroy() {
-                        Ok(_) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: true,
-                            message: "Powered off (forced, no guest agent)".to_string(),
-                        }),
-                        Err(e) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: false,
-                            message: format!("Failed to power off: {}", e),
-                        }),
-                    }
-                }
-            }
-            Ok(false) => Ok(NodeActionResult {
-                name: node_name,
-                success: true,
-                message: "Already stopped".to_string(),
-            }),
-            Err(e) => Ok(NodeActionResult {
-                name: node_name,
-                success: false,
-                message: format!("Failed to check state: {}", e),
-            }),
-        }
-    })
-    .await
-    {
-        Ok(Ok(result)) => result,
-        Ok(Err(e)) => NodeActionResult {
-            name: node_name.to_string(),
-            success: false,
-            message: format!("Libvirt error: {}", e),
-        },
-        Err(e) => NodeActionResult {
-            name: node_name.to_string(),
-            success: false,
-            message: format!("Task panicked: {}", e),
-        },
+async fn shutdown_vm(device_name: &str, node_name: &str, runtime: &Runtime) -> NodeActionResult {
+    let (success, message) = match runtime.vms.shutdown_domain(device_name).await {
+        Ok(VmShutdown::Graceful) => (true, "Shutdown initiated (graceful)".to_string()),
+        Ok(VmShutdown::Forced) => (true, "Powered off (forced, no guest agent)".to_string()),
+        Ok(VmShutdown::AlreadyStopped) => (true, "Already stopped".to_string()),
+        Err(e) => (false, format!("{:#}", e)),
+    };
+    NodeActionResult {
+        name: node_name.to_string(),
+        success,
+        message,
     }
 }
 
 async fn shutdown_container(
     device_name: &str,
     node_name: &str,
-    state: &AppState,
+    runtime: &Runtime,
 ) -> NodeActionResult {
-    match container::pause_container(&state.docker, device_name).await {
+    match runtime.containers.pause_container(device_name).await {
         Ok(()) => NodeActionResult {
             name: node_name.to_string(),
             success: true,
@@ -223,3 +153,52 @@ async fn shutdown_container(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
+    use bollard::secret::ContainerSummaryStateEnum;
+    use virt::sys::{VIR_DOMAIN_RUNNING, VIR_DOMAIN_SHUTOFF};
+
+    fn fakes() -> FakeRuntime {
+        FakeRuntime::new(
+            FakeVmRuntime::default()
+                .with_domain("r1-abc12345", VIR_DOMAIN_RUNNING)
+                .with_domain("r2-abc12345", VIR_DOMAIN_SHUTOFF),
+            FakeContainerRuntime::default()
+                .with_container("srl1-abc12345", ContainerSummaryStateEnum::RUNNING),
+            FakeHostNetwork::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_vm() {
+        let fakes = fakes();
+        let runtime = fakes.runtime();
+
+        let running = shutdown_vm("r1-abc12345", "r1", &runtime).await;
+        let stopped = shutdown_vm("r2-abc12345", "r2", &runtime).await;
+        let missing = shutdown_vm("r3-abc12345", "r3", &runtime).await;
+
+        assert!(running.success);
+        assert_eq!(running.message, "Shutdown initiated (graceful)");
+        assert!(stopped.success);
+        assert_eq!(stopped.message, "Already stopped");
+        assert!(!missing.success);
+        assert_eq!(fakes.vms.domains()["r1-abc12345"], VIR_DOMAIN_SHUTOFF);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_container_pauses() {
+        let fakes = fakes();
+
+        let result = shutdown_container("srl1-abc12345", "srl1", &fakes.runtime()).await;
+
+        assert!(result.success);
+        assert_eq!(
+            fakes.containers.containers()["srl1-abc12345"],
+            ContainerSummaryStateEnum::PAUSED
+        );
+    }
+}
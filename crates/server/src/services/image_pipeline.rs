@@ -0,0 +1,352 @@
+//! Image import pipeline.
+//!
+//! Vendors ship images as OVA appliances, tarballs and disks in formats other
+//! than qcow2. The pipeline verifies the source checksum, unpacks archives,
+//! converts the disk to qcow2 and leaves a file in a work directory ready to
+//! be moved into the images directory.
+//!
+//! Every function here blocks on file I/O or external tools, so callers run
+//! them with `spawn_blocking`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use shared::data::{ImportRequest, StatusKind};
+use shared::konst::UNIKERNEL_KERNEL_FILENAME;
+use shared::util::{
+    Checksum, convert_disk_image, copy_file, create_dir, disk_image_format, extract_archive,
+    is_tar_archive, parse_checksum_listing, path_to_string, sparsify_disk_image,
+    verify_file_checksum,
+};
+
+use crate::services::progress::ProgressSender;
+
+/// Disk file extensions looked for inside archives
+const DISK_EXTENSIONS: &[&str] = &["qcow2", "vmdk", "vhdx", "vhd", "vdi", "img", "raw"];
+
+/// Checksum file extensions vendors publish next to an image
+const CHECKSUM_EXTENSIONS: &[&str] = &["sha256", "sha256sum", "md5", "md5sum"];
+
+/// Checksum listings covering every file in a directory
+const CHECKSUM_LISTINGS: &[&str] = &["SHA256SUMS", "MD5SUMS"];
+
+/// Find the expected checksum for a source file.
+///
+/// A user-supplied checksum takes precedence. Otherwise a vendor checksum
+/// file next to the source is used, either `<src>.sha256` style or a
+/// `SHA256SUMS` listing in the same directory.
+///
+/// # Errors
+/// Returns an error if the user-supplied checksum cannot be parsed
+pub fn expected_checksum(src: &str, checksum: Option<&str>) -> Result<Option<Checksum>> {
+    if let Some(checksum) = checksum {
+        return checksum.parse().map(Some);
+    }
+
+    let path = Path::new(src);
+    let Some(filename) = path.file_name().map(|f| f.to_string_lossy().to_string()) else {
+        return Ok(None);
+    };
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    let sidecars = CHECKSUM_EXTENSIONS
+        .iter()
+        .map(|ext| PathBuf::from(format!("{src}.{ext}")))
+        .chain(CHECKSUM_LISTINGS.iter().map(|name| dir.join(name)));
+
+    for sidecar in sidecars {
+        let Ok(contents) = fs::read_to_string(&sidecar) else {
+            continue;
+        };
+        if let Some(checksum) = parse_checksum_listing(&contents, &filename) {
+            tracing::debug!(source = %src, sidecar = %sidecar.display(), "Using vendor checksum");
+            return Ok(Some(checksum));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Verify the source file of an import against its expected checksum.
+///
+/// # Returns
+/// The checksum that was verified, or `None` if no checksum was supplied
+/// and the vendor didn't publish one
+///
+/// # Errors
+/// Returns an error if the checksum is invalid or doesn't match
+pub fn verify_source(
+    request: &ImportRequest,
+    progress: &ProgressSender,
+) -> Result<Option<Checksum>> {
+    let Some(expected) = expected_checksum(&request.src, request.checksum.as_deref())? else {
+        let _ = progress.send_status(
+            "No checksum supplied, skipping source verification".to_string(),
+            StatusKind::Info,
+        );
+        return Ok(None);
+    };
+
+    let _ = progress.send_status(
+        format!(
+            "Verifying {} checksum of {}...",
+            expected.algorithm, request.src
+        ),
+        StatusKind::Progress,
+    );
+    verify_file_checksum(&request.src, &expected)?;
+    let _ = progress.send_status("Source checksum verified".to_string(), StatusKind::Done);
+
+    Ok(Some(expected))
+}
+
+/// Prepare an image for the images directory.
+///
+/// Unpacks OVA/tar archives, converts the disk to qcow2 and optionally
+/// compresses and sparsifies it. Unikernel kernels are copied as-is.
+///
+/// # Returns
+/// The path of the prepared image inside `work_dir`, named `filename`
+///
+/// # Errors
+/// Returns an error if the archive has no disk, a checksum in the archive
+/// doesn't match, or an external tool fails
+pub fn prepare_image(
+    request: &ImportRequest,
+    filename: &str,
+    work_dir: &str,
+    progress: &ProgressSender,
+) -> Result<String> {
+    let output = format!("{work_dir}/{filename}");
+
+    if filename == UNIKERNEL_KERNEL_FILENAME {
+        copy_file(&request.src, &output).context("Failed to copy kernel image")?;
+        return Ok(output);
+    }
+
+    let disk = if is_tar_archive(&request.src)? {
+        let extract_dir = format!("{work_dir}/extract");
+        create_dir(&extract_dir)?;
+        let _ = progress.send_status(
+            format!("Extracting archive {}...", request.src),
+            StatusKind::Progress,
+        );
+        extract_archive(&request.src, &extract_dir)?;
+
+        let disk = find_disk(Path::new(&extract_dir))?;
+        verify_archive_checksums(&disk)?;
+        let _ = progress.send_status(
+            format!("Found disk image {}", path_to_string(&disk)),
+            StatusKind::Done,
+        );
+        path_to_string(&disk)
+    } else {
+        request.src.clone()
+    };
+
+    let format = disk_image_format(&disk)?;
+    if format == "qcow2" && !request.compress {
+        let _ = progress.send_status(
+            format!("Copying qcow2 image {}...", disk),
+            StatusKind::Progress,
+        );
+        copy_file(&disk, &output).context("Failed to copy image file")?;
+    } else {
+        let _ = progress.send_status(
+            format!("Converting {} image to qcow2...", format),
+            StatusKind::Progress,
+        );
+        convert_disk_image(&disk, &format, &output, request.compress)?;
+    }
+    let _ = progress.send_status("Image conversion complete".to_string(), StatusKind::Done);
+
+    if request.sparsify {
+        let _ = progress.send_status("Sparsifying image...".to_string(), StatusKind::Progress);
+        sparsify_disk_image(&output)?;
+        let _ = progress.send_status("Image sparsified".to_string(), StatusKind::Done);
+    }
+
+    Ok(output)
+}
+
+/// Find the disk in an extracted archive, picking the largest when there
+/// are several. Symlinks are skipped.
+fn find_disk(dir: &Path) -> Result<PathBuf> {
+    let mut disks = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            let metadata = fs::symlink_metadata(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            if metadata.is_symlink() {
+                // An archive must not point the import at files outside it
+                tracing::warn!(path = %path.display(), "Skipping symlink in archive");
+                continue;
+            }
+            if metadata.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let is_disk = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .is_some_and(|ext| DISK_EXTENSIONS.contains(&ext.as_str()));
+            if is_disk {
+                disks.push((metadata.len(), path));
+            }
+        }
+    }
+
+    disks
+        .into_iter()
+        .max_by_key(|(size, _)| *size)
+        .map(|(_, path)| path)
+        .with_context(|| {
+            format!(
+                "Archive contains no disk image (looked for: {})",
+                DISK_EXTENSIONS.join(", ")
+            )
+        })
+}
+
+/// Verify a disk extracted from an archive against any manifest or checksum
+/// file shipped alongside it.
+fn verify_archive_checksums(disk: &Path) -> Result<()> {
+    let Some(dir) = disk.parent() else {
+        return Ok(());
+    };
+    let filename = disk
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let is_listing = path.extension().is_some_and(|ext| ext == "mf")
+            || CHECKSUM_LISTINGS.contains(&name.as_str())
+            || CHECKSUM_EXTENSIONS
+                .iter()
+                .any(|ext| name == format!("{filename}.{ext}"));
+        if !is_listing {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if let Some(expected) = parse_checksum_listing(&contents, &filename) {
+            let disk = path_to_string(disk);
+            verify_file_checksum(&disk, &expected)?;
+            tracing::info!(disk = %disk, manifest = %name, "Verified disk against archive manifest");
+            return Ok(());
+        }
+    }
+
+    tracing::debug!(disk = %disk.display(), "Archive has no checksum for disk");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_expected_checksum_prefers_user_value() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("disk.vmdk");
+        fs::write(&src, "hello").unwrap();
+        fs::write(dir.path().join("disk.vmdk.md5"), "0".repeat(32)).unwrap();
+        let src = path_to_string(&src);
+
+        let checksum = expected_checksum(&src, Some(HELLO_SHA256))
+            .unwrap()
+            .unwrap();
+        assert_eq!(checksum.digest, HELLO_SHA256);
+
+        let sidecar = expected_checksum(&src, None).unwrap().unwrap();
+        assert_eq!(sidecar.digest, "0".repeat(32));
+    }
+
+    #[test]
+    fn test_expected_checksum_from_listing() {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("disk.qcow2");
+        fs::write(&src, "hello").unwrap();
+        fs::write(
+            dir.path().join("SHA256SUMS"),
+            format!("{}  disk.qcow2\n", HELLO_SHA256),
+        )
+        .unwrap();
+
+        let checksum = expected_checksum(&path_to_string(&src), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(checksum.digest, HELLO_SHA256);
+        assert!(
+            expected_checksum("/nonexistent/disk.qcow2", None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_disk_picks_largest() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("appliance.ovf"), "<Envelope/>").unwrap();
+        fs::write(dir.path().join("small.vmdk"), "a").unwrap();
+        fs::create_dir(dir.path().join("disks")).unwrap();
+        fs::write(dir.path().join("disks/large.vhdx"), "aaaa").unwrap();
+
+        let disk = find_disk(dir.path()).unwrap();
+        assert!(disk.ends_with("disks/large.vhdx"));
+
+        let empty = TempDir::new().unwrap();
+        assert!(find_disk(empty.path()).is_err());
+    }
+
+    #[test]
+    fn test_find_disk_skips_symlinks() {
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("host.img"), "aaaaaaaa").unwrap();
+        fs::create_dir(outside.path().join("images")).unwrap();
+        fs::write(outside.path().join("images/host.qcow2"), "aaaaaaaa").unwrap();
+
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("disk.vmdk"), "a").unwrap();
+        std::os::unix::fs::symlink(outside.path().join("host.img"), dir.path().join("big.img"))
+            .unwrap();
+        std::os::unix::fs::symlink(outside.path().join("images"), dir.path().join("images"))
+            .unwrap();
+
+        let disk = find_disk(dir.path()).unwrap();
+        assert!(disk.ends_with("disk.vmdk"));
+    }
+
+    #[test]
+    fn test_verify_archive_checksums_uses_manifest() {
+        let dir = TempDir::new().unwrap();
+        let disk = dir.path().join("disk1.vmdk");
+        fs::write(&disk, "hello").unwrap();
+        fs::write(
+            dir.path().join("appliance.mf"),
+            format!("SHA256(disk1.vmdk)= {}\n", HELLO_SHA256),
+        )
+        .unwrap();
+        assert!(verify_archive_checksums(&disk).is_ok());
+
+        fs::write(&disk, "tampered").unwrap();
+        let err = verify_archive_checksums(&disk).unwrap_err().to_string();
+        assert!(err.contains("Checksum mismatch"));
+    }
+}
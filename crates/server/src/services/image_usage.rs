@@ -0,0 +1,337 @@
+//! Image storage reporting and garbage collection.
+//!
+//! Usage joins each `node_image` record with the labs whose nodes use it and
+//! its size on disk (or in Docker for containers). Prune removes versions no
+//! node uses, never touching a model's default version.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+
+use anyhow::{Context, Result};
+use jiff::Timestamp;
+use tracing::instrument;
+
+use shared::data::{
+    DbNodeImageUsage, DeleteImageRequest, ImageUsageResponse, ImageVersionUsage, NodeConfig,
+    NodeKind, OrphanedDisk, PruneImagesRequest, PruneImagesResponse, PrunedImage,
+};
+use shared::konst::{SHERPA_IMAGES_PATH, SHERPA_STORAGE_POOL_PATH};
+use shared::util::{dir_exists, dir_size};
+
+use crate::daemon::state::AppState;
+use crate::services::delete;
+
+/// Report the size and users of every image version, and lab disks no node owns
+#[instrument(skip(state))]
+pub async fn image_usage(state: &AppState) -> Result<ImageUsageResponse> {
+    let images = version_usage(state).await?;
+    let orphaned_disks = orphaned_disks(state).await?;
+    let total_size_bytes = images.iter().filter_map(|image| image.size_bytes).sum();
+
+    Ok(ImageUsageResponse {
+        images,
+        orphaned_disks,
+        total_size_bytes,
+    })
+}
+
+/// Remove image versions that no node uses.
+///
+/// Default versions are always kept. With `unused_for_secs`, only versions
+/// whose last recorded use is at least that old are removed.
+#[instrument(skip(state), fields(unused_for_secs = ?request.unused_for_secs, dry_run = request.dry_run))]
+pub async fn prune_images(
+    request: PruneImagesRequest,
+    state: &AppState,
+) -> Result<PruneImagesResponse> {
+    let images = version_usage(state).await?;
+    let candidates = prune_candidates(
+        &images,
+        request.unused_for_secs,
+        Timestamp::now().as_second(),
+    );
+
+    let mut pruned = vec![];
+    let mut errors = vec![];
+    for image in candidates {
+        if !request.dry_run {
+            // delete_image removes the database record first, which the
+            // database rejects if a node started using the image meanwhile
+            let delete_request = DeleteImageRequest {
+                model: image.model,
+                version: image.version.clone(),
+            };
+            if let Err(e) = delete::delete_image(delete_request, state).await {
+                errors.push(format!("{} {}: {:#}", image.model, image.version, e));
+                continue;
+            }
+            if let Some(reference) = &image.container_image
+                && let Err(e) = container::remove_image(&state.docker, reference).await
+            {
+                errors.push(format!("{} {}: {:#}", image.model, image.version, e));
+            }
+        }
+        pruned.push(PrunedImage {
+            model: image.model,
+            kind: image.kind.clone(),
+            version: image.version.clone(),
+            size_bytes: image.size_bytes,
+        });
+    }
+
+    let freed_bytes = pruned.iter().filter_map(|image| image.size_bytes).sum();
+    tracing::info!(
+        pruned = pruned.len(),
+        freed_bytes,
+        errors = errors.len(),
+        dry_run = request.dry_run,
+        "Pruned images"
+    );
+
+    Ok(PruneImagesResponse {
+        pruned,
+        freed_bytes,
+        dry_run: request.dry_run,
+        errors,
+    })
+}
+
+async fn version_usage(state: &AppState) -> Result<Vec<ImageVersionUsage>> {
+    let records = db::list_node_image_usage(&state.db)
+        .await
+        .context("Failed to load image usage")?;
+
+    let docker_sizes = if records
+        .iter()
+        .any(|record| record.kind == NodeKind::Container)
+    {
+        container::get_local_image_sizes(&state.docker)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Failed to list Docker images, container sizes unknown");
+                HashMap::new()
+            })
+    } else {
+        HashMap::new()
+    };
+
+    let images = records
+        .into_iter()
+        .map(|record| {
+            let container_image = (record.kind == NodeKind::Container)
+                .then(|| container_repo(&record))
+                .flatten()
+                .map(|repo| format!("{repo}:{}", record.version));
+            let (size_bytes, imported_at) = match record.kind {
+                NodeKind::Container => (
+                    container_image
+                        .as_ref()
+                        .and_then(|reference| docker_sizes.get(reference))
+                        .copied(),
+                    None,
+                ),
+                _ => {
+                    let version_dir =
+                        format!("{SHERPA_IMAGES_PATH}/{}/{}", record.model, record.version);
+                    if dir_exists(&version_dir) {
+                        (Some(dir_size(&version_dir)?), modified_at(&version_dir))
+                    } else {
+                        (None, None)
+                    }
+                }
+            };
+            Ok(ImageVersionUsage {
+                model: record.model,
+                kind: record.kind,
+                version: record.version,
+                default: record.default,
+                size_bytes,
+                container_image,
+                labs: record.labs,
+                node_count: record.node_count,
+                last_used_at: record.last_used_at.map(|ts| ts.as_second()),
+                imported_at,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(images)
+}
+
+/// Files in the storage pool that do not belong to a node in the database
+async fn orphaned_disks(state: &AppState) -> Result<Vec<OrphanedDisk>> {
+    let labs = db::list_labs(&state.db)
+        .await
+        .context("Failed to list labs")?;
+    let lab_ids: HashMap<_, _> = labs
+        .into_iter()
+        .filter_map(|lab| lab.id.map(|id| (id, lab.lab_id)))
+        .collect();
+    let devices: HashSet<String> = db::list_nodes(&state.db)
+        .await
+        .context("Failed to list nodes")?
+        .into_iter()
+        .filter_map(|node| {
+            lab_ids
+                .get(&node.lab)
+                .map(|lab_id| format!("{}-{}", node.name, lab_id))
+        })
+        .collect();
+
+    let entries = match fs::read_dir(SHERPA_STORAGE_POOL_PATH) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {SHERPA_STORAGE_POOL_PATH}"));
+        }
+    };
+
+    let mut disks = vec![];
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if is_orphaned(&file_name, &devices) {
+            disks.push(OrphanedDisk {
+                path: format!("{SHERPA_STORAGE_POOL_PATH}/{file_name}"),
+                size_bytes: entry.metadata()?.len(),
+            });
+        }
+    }
+    disks.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(disks)
+}
+
+/// Whether a storage pool file name belongs to none of the `<node>-<lab_id>` devices.
+///
+/// Node files are named `<device>-hdd.qcow2`, `<device>-cfg.img`,
+/// `<device>.iso` and so on.
+fn is_orphaned(file_name: &str, devices: &HashSet<String>) -> bool {
+    !file_name
+        .char_indices()
+        .any(|(i, c)| matches!(c, '-' | '.') && i > 0 && devices.contains(&file_name[..i]))
+}
+
+/// Unix timestamp of the last modification of a file or directory
+fn modified_at(path: &str) -> Option<i64> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    Timestamp::try_from(modified).ok().map(|ts| ts.as_second())
+}
+
+/// Unreferenced, non-default versions last used at least `unused_for_secs` ago.
+///
+/// Versions added before usage was recorded have no `last_used_at`, so their
+/// import time is used instead. Versions where neither is known, container
+/// images from before usage was recorded, are only pruned without
+/// `unused_for_secs`.
+fn prune_candidates(
+    images: &[ImageVersionUsage],
+    unused_for_secs: Option<u64>,
+    now: i64,
+) -> Vec<&ImageVersionUsage> {
+    images
+        .iter()
+        .filter(|image| image.node_count == 0 && !image.default)
+        .filter(|image| match unused_for_secs {
+            None => true,
+            Some(secs) => image
+                .last_used_at
+                .or(image.imported_at)
+                .is_some_and(|last_used| {
+                    now.saturating_sub(last_used) >= i64::try_from(secs).unwrap_or(i64::MAX)
+                }),
+        })
+        .collect()
+}
+
+fn container_repo(record: &DbNodeImageUsage) -> Option<String> {
+    record
+        .repo
+        .clone()
+        .or_else(|| NodeConfig::get_model(record.model).repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::NodeModel;
+
+    fn usage(version: &str, node_count: usize, last_used_at: Option<i64>) -> ImageVersionUsage {
+        ImageVersionUsage {
+            model: NodeModel::CiscoIosv,
+            kind: NodeKind::VirtualMachine,
+            version: version.to_string(),
+            default: false,
+            size_bytes: Some(1024),
+            container_image: None,
+            labs: vec![],
+            node_count,
+            last_used_at,
+            imported_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_orphaned() {
+        let devices = HashSet::from(["r1-abcd1234".to_string(), "dev-r2-abcd1234".to_string()]);
+        assert!(!is_orphaned("r1-abcd1234-hdd.qcow2", &devices));
+        assert!(!is_orphaned("r1-abcd1234.iso", &devices));
+        assert!(!is_orphaned("dev-r2-abcd1234-cfg.img", &devices));
+        assert!(is_orphaned("r1-ffff0000-hdd.qcow2", &devices));
+        assert!(is_orphaned("r1.iso", &devices));
+        assert!(is_orphaned("r1-abcd12345-hdd.qcow2", &devices));
+    }
+
+    #[test]
+    fn test_prune_candidates() {
+        let day = 24 * 60 * 60;
+        let now = 100 * day;
+        let mut default = usage("default", 0, Some(0));
+        default.default = true;
+        // Never used since usage was recorded, falls back to the import time
+        let mut imported_stale = usage("imported-stale", 0, None);
+        imported_stale.imported_at = Some(now - 40 * day);
+        let mut imported_recent = usage("imported-recent", 0, None);
+        imported_recent.imported_at = Some(now - day);
+        // A recorded use wins over the import time
+        let mut used_recent = usage("used-recent", 0, Some(now - day));
+        used_recent.imported_at = Some(now - 40 * day);
+        let images = vec![
+            usage("in-use", 1, Some(0)),
+            usage("stale", 0, Some(now - 40 * day)),
+            usage("recent", 0, Some(now - day)),
+            imported_stale,
+            imported_recent,
+            used_recent,
+            usage("unknown", 0, None),
+            default,
+        ];
+
+        let versions = |secs| {
+            prune_candidates(&images, secs, now)
+                .into_iter()
+                .map(|image| image.version.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            versions(None),
+            vec![
+                "stale",
+                "recent",
+                "imported-stale",
+                "imported-recent",
+                "used-recent",
+                "unknown"
+            ]
+        );
+        // Versions with neither a recorded use nor an import time are kept
+        assert_eq!(
+            versions(Some(30 * day as u64)),
+            vec!["stale", "imported-stale"]
+        );
+    }
+}
@@ -2,24 +2,30 @@ use anyhow::{Context, Result};
 use opentelemetry::KeyValue;
 use std::time::Instant;
 use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
 
 use shared::data::{
-    DownloadImageRequest, ImageSummary, ImportRequest, ImportResponse, ListImagesRequest,
-    ListImagesResponse, NodeConfig, NodeKind, NodeModel, ScanImagesRequest, ScanImagesResponse,
-    ScannedImage, SetDefaultImageRequest, SetDefaultImageResponse, ShowImageRequest,
-    ShowImageResponse, StatusKind,
+    DownloadImageRequest, ImageIntegrity, ImageSummary, ImportRequest, ImportResponse,
+    ListImagesRequest, ListImagesResponse, NodeConfig, NodeKind, NodeModel, ScanImagesRequest,
+    ScanImagesResponse, ScannedImage, SetDefaultImageRequest, SetDefaultImageResponse,
+    ShowImageRequest, ShowImageResponse, StatusKind, VerifyImageRequest, VerifyImageResponse,
 };
-use shared::konst::SHERPA_IMAGES_PATH;
-use shared::util::{copy_file, create_dir, file_exists, image_filename};
+use shared::konst::{SHERPA_IMAGES_IMPORT_PATH, SHERPA_IMAGES_PATH};
+use shared::util::{create_dir, delete_dirs, file_exists, file_sha256, image_filename};
 
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::services::image_pipeline;
+use crate::services::oci;
 use crate::services::progress::ProgressSender;
 
 /// Import an image to the server and track it in the database.
 ///
-/// For VMs and unikernels: copies the disk image (qcow2) to the images directory.
+/// The source is first verified against the supplied or vendor checksum.
+///
+/// For VMs and unikernels: unpacks OVA/tar archives, converts the disk to qcow2
+/// and stores it in the images directory, recording its SHA-256 digest.
 /// For containers: loads a tar archive into the Docker daemon via `docker load`.
 #[instrument(skip(state, progress), fields(model = %request.model, version = %request.version))]
 pub async fn import_image(
@@ -27,11 +33,6 @@ pub async fn import_image(
     state: &AppState,
     progress: ProgressSender,
 ) -> Result<ImportResponse> {
-    let start = Instant::now();
-
-    let config = NodeConfig::get_model(request.model);
-    let kind = config.kind.clone();
-
     let _ = progress.send_status(
         format!("Validating source file: {}", request.src),
         StatusKind::Info,
@@ -42,6 +43,30 @@ pub async fn import_image(
         anyhow::bail!("Source file does not exist: {}", request.src);
     }
 
+    let verify_request = request.clone();
+    let verify_progress = progress.clone();
+    tokio::task::spawn_blocking(move || {
+        image_pipeline::verify_source(&verify_request, &verify_progress)
+    })
+    .await
+    .context("Checksum verification task failed")?
+    .with_context(|| format!("Failed to verify source file '{}'", request.src))?;
+
+    import_verified_image(request, state, progress).await
+}
+
+/// Import an image whose source has already been verified, e.g. a chunked
+/// upload checked against its digest as it completed.
+pub async fn import_verified_image(
+    request: ImportRequest,
+    state: &AppState,
+    progress: ProgressSender,
+) -> Result<ImportResponse> {
+    let start = Instant::now();
+
+    let config = NodeConfig::get_model(request.model);
+    let kind = config.kind.clone();
+
     // If this is the first image for this model+kind, mark it as default
     let existing_versions = db::get_node_image_versions(&state.db, &request.model, &kind).await?;
     let make_default = if existing_versions.is_empty() {
@@ -111,12 +136,11 @@ pub async fn import_image(
                 version: request.version,
                 image_path: format!("loaded from {}", request.src),
                 db_tracked: true,
+                image_sha256: None,
             })
         }
         NodeKind::VirtualMachine | NodeKind::Unikernel => {
-            // VM/Unikernel import: copy disk image to images directory
-            let _ = progress.send_status("Updating database...".to_string(), StatusKind::Progress);
-
+            // VM/Unikernel import: convert and store the disk image, then track it
             let mut db_config = config;
             db_config.version = request.version.clone();
             db_config.default = make_default;
@@ -124,22 +148,6 @@ pub async fn import_image(
 
             let filename = image_filename(&kind, db_config.boot_mode.as_ref());
 
-            db::upsert_node_image(&state.db, db_config)
-                .await
-                .context(format!(
-                    "Failed to register node model '{}' in database. Ensure the server is up to date.",
-                    request.model
-                ))?;
-
-            tracing::info!(
-                "Upserted node_image for model={} version={}",
-                request.model,
-                request.version
-            );
-
-            let _ = progress.send_status("Image tracked in database".to_string(), StatusKind::Done);
-
-            // Copy image file
             let images_dir = SHERPA_IMAGES_PATH.to_owned();
             let model_dir = format!("{images_dir}/{}", request.model);
             let version_dir = format!("{model_dir}/{}", request.version);
@@ -147,21 +155,43 @@ pub async fn import_image(
 
             create_dir(&version_dir).context("Failed to create version directory")?;
 
-            if !file_exists(&version_disk) {
-                let _ = progress.send_status(
-                    format!("Copying image to {}...", version_disk),
-                    StatusKind::Progress,
-                );
-                tracing::info!("Copying image from {} to {}", request.src, version_disk);
-                copy_file(&request.src, &version_disk).context("Failed to copy image file")?;
-                let _ = progress.send_status("Image copy complete".to_string(), StatusKind::Done);
+            let image_sha256 = if !file_exists(&version_disk) {
+                store_image(&request, filename, &version_disk, &progress).await?
             } else {
                 let _ = progress.send_status(
                     "Image already exists on disk, skipping copy".to_string(),
                     StatusKind::Info,
                 );
                 tracing::info!("Image already exists at {}, skipping copy", version_disk);
-            }
+                existing_image_sha256(
+                    state,
+                    &request.model,
+                    &kind,
+                    &request.version,
+                    &version_disk,
+                )
+                .await?
+            };
+            db_config.image_sha256 = Some(image_sha256.clone());
+
+            // Record in database once the image is in place
+            let _ = progress.send_status("Updating database...".to_string(), StatusKind::Progress);
+
+            db::upsert_node_image(&state.db, db_config)
+                .await
+                .context(format!(
+                    "Failed to register node model '{}' in database. Ensure the server is up to date.",
+                    request.model
+                ))?;
+
+            tracing::info!(
+                "Upserted node_image for model={} version={} sha256={}",
+                request.model,
+                request.version,
+                image_sha256
+            );
+
+            let _ = progress.send_status("Image tracked in database".to_string(), StatusKind::Done);
 
             Ok(ImportResponse {
                 success: true,
@@ -170,6 +200,7 @@ pub async fn import_image(
                 version: request.version,
                 image_path: version_disk,
                 db_tracked: true,
+                image_sha256: Some(image_sha256),
             })
         }
     };
@@ -186,6 +217,154 @@ pub async fn import_image(
     result
 }
 
+/// Run the import pipeline in a scratch directory and move the prepared image
+/// to `version_disk`.
+///
+/// # Returns
+/// The SHA-256 digest of the stored image
+async fn store_image(
+    request: &ImportRequest,
+    filename: &'static str,
+    version_disk: &str,
+    progress: &ProgressSender,
+) -> Result<String> {
+    let work_dir = format!("{SHERPA_IMAGES_IMPORT_PATH}/{}", Uuid::now_v7());
+    create_dir(&work_dir).context("Failed to create import work directory")?;
+
+    let pipeline_request = request.clone();
+    let pipeline_work_dir = work_dir.clone();
+    let pipeline_disk = version_disk.to_string();
+    let pipeline_progress = progress.clone();
+    let result = tokio::task::spawn_blocking(move || -> Result<String> {
+        let prepared = image_pipeline::prepare_image(
+            &pipeline_request,
+            filename,
+            &pipeline_work_dir,
+            &pipeline_progress,
+        )?;
+
+        let _ = pipeline_progress.send_status(
+            "Calculating image digest...".to_string(),
+            StatusKind::Progress,
+        );
+        let digest = file_sha256(&prepared)?;
+
+        tracing::info!("Moving image from {} to {}", prepared, pipeline_disk);
+        std::fs::rename(&prepared, &pipeline_disk)
+            .with_context(|| format!("Failed to move image to {}", pipeline_disk))?;
+        let _ = pipeline_progress.send_status(
+            format!("Image stored at {}", pipeline_disk),
+            StatusKind::Done,
+        );
+        Ok(digest)
+    })
+    .await
+    .context("Image import task failed")
+    .and_then(|result| result);
+
+    if let Err(e) = delete_dirs(&work_dir) {
+        tracing::warn!(
+            "Failed to clean up import work directory {}: {:?}",
+            work_dir,
+            e
+        );
+    }
+
+    result
+}
+
+/// Get the recorded digest of an image already on disk, calculating it if
+/// the image was imported before digests were tracked.
+async fn existing_image_sha256(
+    state: &AppState,
+    model: &NodeModel,
+    kind: &NodeKind,
+    version: &str,
+    image_path: &str,
+) -> Result<String> {
+    let existing = db::get_node_image_by_model_kind_version(&state.db, model, kind, version)
+        .await
+        .context("Failed to query existing node_image")?;
+
+    match existing.and_then(|config| config.image_sha256) {
+        Some(digest) => Ok(digest),
+        None => image_sha256(image_path.to_string()).await,
+    }
+}
+
+/// Calculate the SHA-256 digest of an image without blocking the runtime.
+async fn image_sha256(image_path: String) -> Result<String> {
+    tokio::task::spawn_blocking(move || file_sha256(&image_path))
+        .await
+        .context("Image digest task failed")?
+}
+
+/// Verify an imported image against the digest recorded when it was imported
+#[instrument(skip(state), fields(model = %request.model, version = %request.version))]
+pub async fn verify_image(
+    request: VerifyImageRequest,
+    state: &AppState,
+) -> Result<VerifyImageResponse> {
+    let config = NodeConfig::get_model(request.model);
+    let kind = config.kind.clone();
+
+    if kind == NodeKind::Container {
+        anyhow::bail!(
+            "Image verification is only supported for disk images, '{}' is a container model",
+            request.model
+        );
+    }
+
+    let image = db::get_node_image_by_model_kind_version(
+        &state.db,
+        &request.model,
+        &kind,
+        &request.version,
+    )
+    .await
+    .context("Failed to query node image by model, kind and version")?
+    .ok_or_else(|| {
+        anyhow::anyhow!(
+            "No image found for model '{}' version '{}'",
+            request.model,
+            request.version
+        )
+    })?;
+
+    let filename = image_filename(&kind, image.boot_mode.as_ref());
+    let image_path = format!(
+        "{SHERPA_IMAGES_PATH}/{}/{}/{filename}",
+        request.model, request.version
+    );
+    if !file_exists(&image_path) {
+        anyhow::bail!("Image file does not exist: {}", image_path);
+    }
+
+    let actual_sha256 = image_sha256(image_path.clone()).await?;
+    let status = match &image.image_sha256 {
+        None => ImageIntegrity::Untracked,
+        Some(expected) if *expected == actual_sha256 => ImageIntegrity::Ok,
+        Some(expected) => {
+            tracing::warn!(
+                image = %image_path,
+                expected = %expected,
+                actual = %actual_sha256,
+                "Image digest mismatch, the image may be corrupt or tampered with"
+            );
+            ImageIntegrity::Mismatch
+        }
+    };
+
+    Ok(VerifyImageResponse {
+        model: request.model,
+        version: request.version,
+        image_path,
+        expected_sha256: image.image_sha256,
+        actual_sha256,
+        status,
+    })
+}
+
 /// List images from the database with optional filtering by model and/or kind
 #[instrument(skip(state), level = "debug")]
 pub async fn list_images(
@@ -636,19 +815,26 @@ fn resolve_download_url(model: &NodeModel, version: &str, url: Option<&str>) ->
     }
 }
 
-/// Download a VM image from a URL and track it in the database
+/// Download a VM image from a URL and track it in the database.
+///
+/// `oci://` URLs are pulled from an OCI registry, which also supports
+/// unikernel images.
 #[instrument(skip(state, progress), fields(model = %request.model, version = %request.version))]
 pub async fn download_image(
     request: DownloadImageRequest,
     state: &AppState,
     progress: ProgressSender,
 ) -> Result<ImportResponse> {
+    if request.url.as_deref().is_some_and(oci::is_oci_url) {
+        return oci::pull_oci_image(request, state, progress).await;
+    }
+
     let config = NodeConfig::get_model(request.model);
     let kind = config.kind.clone();
 
     if kind != NodeKind::VirtualMachine {
         anyhow::bail!(
-            "Image download is only supported for virtual machine models, got '{}'",
+            "Image download is only supported for virtual machine models, got '{}'. Use an oci:// URL for unikernel images.",
             kind
         );
     }
@@ -668,7 +854,8 @@ pub async fn download_image(
     create_dir(&version_dir).context("Failed to create version directory")?;
 
     // Skip download if file already exists
-    if file_exists(&version_disk) {
+    let already_downloaded = file_exists(&version_disk);
+    if already_downloaded {
         tracing::info!(
             "Image already exists at {}, skipping download",
             version_disk
@@ -760,6 +947,19 @@ pub async fn download_image(
         let _ = progress.send_status(final_msg, StatusKind::Done);
     }
 
+    let image_sha256 = if already_downloaded {
+        existing_image_sha256(
+            state,
+            &request.model,
+            &kind,
+            &request.version,
+            &version_disk,
+        )
+        .await?
+    } else {
+        image_sha256(version_disk.clone()).await?
+    };
+
     let _ = progress.send_status("Updating database...".to_string(), StatusKind::Progress);
 
     // Upsert node_image record in the database
@@ -767,6 +967,7 @@ pub async fn download_image(
     db_config.version = request.version.clone();
     db_config.default = request.default;
     db_config.id = None;
+    db_config.image_sha256 = Some(image_sha256.clone());
 
     let db_tracked = match db::upsert_node_image(&state.db, db_config).await {
         Ok(_) => {
@@ -797,6 +998,7 @@ pub async fn download_image(
         version: request.version,
         image_path: version_disk,
         db_tracked,
+        image_sha256: Some(image_sha256),
     })
 }
 
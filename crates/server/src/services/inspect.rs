@@ -42,17 +42,16 @@ async fn inspect_lab_inner(request: InspectRequest, state: &AppState) -> Result<
         .await
         .context(format!("User '{}' not found in database", username))?;
 
-    let user_id = db_user
-        .id
-        .ok_or_else(|| anyhow!("User '{}' missing record ID", username))?;
-
     // Get lab from database
     let db_lab = db::get_lab(&state.db, lab_id)
         .await
         .context(format!("Lab '{}' not found in database", lab_id))?;
 
-    // Validate ownership
-    if db_lab.user != user_id {
+    // Validate access: admins, the owner and any user the lab is shared with
+    let role = db::get_lab_role(&state.db, lab_id, username)
+        .await
+        .context(format!("Failed to resolve role on lab '{}'", lab_id))?;
+    if !db_user.is_admin && role.is_none() {
         return Err(anyhow!(
             "Permission denied: Lab '{}' is owned by another user",
             lab_id
@@ -145,6 +144,13 @@ async fn inspect_lab_inner(request: InspectRequest, state: &AppState) -> Result<
             node_b_name: node_name_from_id(&db_nodes, &link.node_b),
             int_b: link.int_b,
             kind: link.kind.to_string(),
+            impairment: topology::impairment_label(
+                link.delay_us,
+                link.jitter_us,
+                link.loss_percent,
+                link.reorder_percent,
+                link.corrupt_percent,
+            ),
         })
         .collect();
 
@@ -214,6 +220,11 @@ async fn inspect_lab_inner(request: InspectRequest, state: &AppState) -> Result<
         inactive_devices: Vec::new(), // Keep field for API compatibility
         links,
         bridges,
+        cabling: state
+            .cabling
+            .get(lab_id)
+            .map(|verified| verified.checks.clone())
+            .unwrap_or_default(),
     })
 }
 
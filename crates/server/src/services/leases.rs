@@ -0,0 +1,356 @@
+//! DHCP lease tracking for lab management networks.
+//!
+//! The lease watcher fetches the leases of each lab from its boot server,
+//! matches them to nodes by management MAC and keeps the node management
+//! addresses in the database up to date. Nodes that ignore their address
+//! reservation (or have none) become visible in `inspect` this way. Leases
+//! of MACs no node owns are flagged as unknown.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use shared::data::{
+    DbLab, DbNode, DhcpLease, LabInfo, LabLease, LabLeasesResponse, LabState, LeaseStatus, RecordId,
+};
+use shared::konst::{LAB_FILE_NAME, SHERPA_LABS_PATH};
+use shared::util::{get_dhcp_leases, load_file};
+use tokio_util::sync::CancellationToken;
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+
+/// Placeholder dnsmasq writes for an unknown hostname
+const UNKNOWN_HOSTNAME: &str = "*";
+
+/// Management address changes of a node learned from its lease
+#[derive(Debug, PartialEq)]
+struct MgmtUpdate {
+    node_id: RecordId,
+    node_name: String,
+    ipv4: Option<String>,
+    ipv6: Option<String>,
+}
+
+/// Leases of a lab matched to its nodes
+#[derive(Debug, Default)]
+struct Reconciled {
+    leases: Vec<LabLease>,
+    updates: Vec<MgmtUpdate>,
+}
+
+/// Run the background lease watcher.
+///
+/// Runs on the scanner interval. Exits cleanly when the cancellation token
+/// is triggered.
+#[instrument(skip_all)]
+pub async fn run_lease_watcher(state: AppState, cancel: CancellationToken) {
+    let interval_secs = state.config.scanner.interval_secs;
+    tracing::info!(interval_secs = interval_secs, "Lease watcher started");
+
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    interval.tick().await;
+
+    // Unknown MACs already reported, as `<lab_id>/<mac>`
+    let mut unknown = HashSet::new();
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match watch_cycle(&state, &unknown).await {
+                    Ok(seen) => unknown = seen,
+                    Err(e) => tracing::error!(error = %e, "Lease watcher cycle failed"),
+                }
+            }
+            _ = cancel.cancelled() => {
+                tracing::info!("Lease watcher shutting down");
+                return;
+            }
+        }
+    }
+}
+
+/// Sync the leases of every active lab.
+///
+/// # Returns
+/// The unknown MACs seen in this cycle
+#[instrument(skip_all, level = "debug")]
+async fn watch_cycle(state: &AppState, reported: &HashSet<String>) -> Result<HashSet<String>> {
+    let labs = db::list_labs(&state.db)
+        .await
+        .context("Failed to list labs")?;
+
+    let mut seen = HashSet::new();
+    for lab in labs
+        .iter()
+        .filter(|lab| !matches!(lab.status, LabState::Stopped | LabState::Empty))
+    {
+        let leases = match sync_lab(state, lab).await {
+            Ok(leases) => leases,
+            Err(e) => {
+                tracing::debug!(lab_id = %lab.lab_id, error = %e, "Failed to sync lab leases");
+                continue;
+            }
+        };
+        for lease in leases.iter().filter(|l| l.status == LeaseStatus::Unknown) {
+            let key = format!("{}/{}", lab.lab_id, lease.mac_address);
+            if !reported.contains(&key) {
+                tracing::warn!(
+                    lab_id = %lab.lab_id,
+                    mac = %lease.mac_address,
+                    ipv4 = %lease.ipv4_address,
+                    "DHCP lease held by an unknown MAC"
+                );
+            }
+            seen.insert(key);
+        }
+    }
+    Ok(seen)
+}
+
+/// List the DHCP leases of a lab, syncing node management addresses.
+///
+/// Authorization is handled by the caller.
+#[instrument(skip(state))]
+pub async fn lab_leases(lab_id: &str, state: &AppState) -> Result<LabLeasesResponse> {
+    let lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found in database", lab_id))?;
+    let leases = sync_lab(state, &lab).await?;
+    Ok(LabLeasesResponse {
+        lab_id: lab_id.to_string(),
+        leases,
+    })
+}
+
+/// Fetch the leases of a lab and write management address changes to the DB.
+async fn sync_lab(state: &AppState, lab: &DbLab) -> Result<Vec<LabLease>> {
+    let lab_record_id = lab.id.clone().context("Lab missing record ID")?;
+
+    let lab_file = load_file(&format!(
+        "{SHERPA_LABS_PATH}/{}/{LAB_FILE_NAME}",
+        lab.lab_id
+    ))
+    .context("Unable to load lab file. Is the lab running?")?;
+    let lab_info = LabInfo::from_str(&lab_file).context("Failed to parse lab info file")?;
+
+    let (nodes, leases) = tokio::try_join!(
+        async {
+            db::list_nodes_by_lab(&state.db, lab_record_id)
+                .await
+                .context("Failed to list nodes for lab")
+        },
+        get_dhcp_leases(lab_info.ipv4_router),
+    )?;
+
+    let now = u64::try_from(jiff::Timestamp::now().as_second()).unwrap_or(0);
+    let reconciled = reconcile(&nodes, &leases, now);
+
+    for update in reconciled.updates {
+        tracing::info!(
+            lab_id = %lab.lab_id,
+            node = %update.node_name,
+            ipv4 = ?update.ipv4,
+            ipv6 = ?update.ipv6,
+            "Node management address changed"
+        );
+        if let Some(ipv4) = &update.ipv4 {
+            db::update_node_mgmt_ipv4(&state.db, update.node_id.clone(), ipv4)
+                .await
+                .context(format!(
+                    "Failed to update IPv4 of node '{}'",
+                    update.node_name
+                ))?;
+        }
+        if let Some(ipv6) = &update.ipv6 {
+            db::update_node_mgmt_ipv6(&state.db, update.node_id.clone(), ipv6)
+                .await
+                .context(format!(
+                    "Failed to update IPv6 of node '{}'",
+                    update.node_name
+                ))?;
+        }
+    }
+
+    Ok(reconciled.leases)
+}
+
+/// Match active leases to nodes by management MAC.
+///
+/// Expired and declined leases are dropped. A node's addresses are updated
+/// when its lease differs from the database.
+fn reconcile(nodes: &[DbNode], leases: &[DhcpLease], now: u64) -> Reconciled {
+    let mut reconciled = Reconciled::default();
+
+    for lease in leases {
+        let expired = lease.expiry != 0 && lease.expiry <= now;
+        if expired || lease.mac_address.is_empty() {
+            continue;
+        }
+
+        let node = nodes.iter().find(|node| {
+            node.mgmt_mac
+                .as_deref()
+                .is_some_and(|mac| mac.eq_ignore_ascii_case(&lease.mac_address))
+        });
+
+        if let Some(node) = node
+            && let Some(node_id) = &node.id
+            && !reconciled.updates.iter().any(|u| &u.node_id == node_id)
+        {
+            let ipv4 = (node.mgmt_ipv4.as_deref() != Some(lease.ipv4_address.as_str()))
+                .then(|| lease.ipv4_address.clone());
+            let ipv6 = lease
+                .ipv6_address
+                .clone()
+                .filter(|ipv6| node.mgmt_ipv6.as_ref() != Some(ipv6));
+            if ipv4.is_some() || ipv6.is_some() {
+                reconciled.updates.push(MgmtUpdate {
+                    node_id: node_id.clone(),
+                    node_name: node.name.clone(),
+                    ipv4,
+                    ipv6,
+                });
+            }
+        }
+
+        reconciled.leases.push(LabLease {
+            mac_address: lease.mac_address.to_lowercase(),
+            ipv4_address: lease.ipv4_address.clone(),
+            ipv6_address: lease.ipv6_address.clone(),
+            hostname: Some(lease.hostname.clone())
+                .filter(|h| !h.is_empty() && h != UNKNOWN_HOSTNAME),
+            expiry: lease.expiry,
+            node_name: node.map(|node| node.name.clone()),
+            status: if node.is_some() {
+                LeaseStatus::Known
+            } else {
+                LeaseStatus::Unknown
+            },
+        });
+    }
+
+    reconciled
+        .leases
+        .sort_by_key(|l| l.ipv4_address.parse::<std::net::Ipv4Addr>().ok());
+    reconciled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::NodeState;
+
+    fn node(name: &str, mac: &str, ipv4: Option<&str>) -> DbNode {
+        DbNode {
+            id: Some(RecordId::new("node", name)),
+            name: name.to_string(),
+            image: RecordId::new("node_image", "arista_veos"),
+            index: 1,
+            lab: RecordId::new("lab", "abcd1234"),
+            mgmt_ipv4: ipv4.map(str::to_string),
+            mgmt_ipv6: None,
+            mgmt_mac: Some(mac.to_string()),
+            state: NodeState::Running,
+        }
+    }
+
+    fn lease(mac: &str, ipv4: &str, expiry: u64) -> DhcpLease {
+        DhcpLease {
+            expiry,
+            mac_address: mac.to_string(),
+            ipv4_address: ipv4.to_string(),
+            ipv6_address: None,
+            hostname: "*".to_string(),
+            client_id: "*".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_updates_changed_address() {
+        let nodes = vec![
+            node("dev01", "52:54:00:aa:bb:01", Some("172.31.0.11")),
+            node("dev02", "52:54:00:aa:bb:02", Some("172.31.0.12")),
+        ];
+        let leases = vec![
+            lease("52:54:00:AA:BB:01", "172.31.0.11", 200),
+            lease("52:54:00:aa:bb:02", "172.31.0.50", 200),
+        ];
+
+        let reconciled = reconcile(&nodes, &leases, 100);
+        assert_eq!(
+            reconciled.updates,
+            vec![MgmtUpdate {
+                node_id: RecordId::new("node", "dev02"),
+                node_name: "dev02".to_string(),
+                ipv4: Some("172.31.0.50".to_string()),
+                ipv6: None,
+            }]
+        );
+        assert_eq!(reconciled.leases.len(), 2);
+        assert_eq!(reconciled.leases[0].node_name.as_deref(), Some("dev01"));
+        assert_eq!(reconciled.leases[0].mac_address, "52:54:00:aa:bb:01");
+        assert_eq!(reconciled.leases[0].hostname, None);
+        assert!(
+            reconciled
+                .leases
+                .iter()
+                .all(|l| l.status == LeaseStatus::Known)
+        );
+    }
+
+    #[test]
+    fn test_reconcile_sets_missing_address() {
+        let nodes = vec![node("pxe01", "52:54:00:aa:bb:03", None)];
+        let mut pxe = lease("52:54:00:aa:bb:03", "172.31.0.10", 0);
+        pxe.ipv6_address = Some("fd00:b00b::10".to_string());
+
+        let reconciled = reconcile(&nodes, &[pxe], 100);
+        assert_eq!(reconciled.updates.len(), 1);
+        assert_eq!(reconciled.updates[0].ipv4.as_deref(), Some("172.31.0.10"));
+        assert_eq!(reconciled.updates[0].ipv6.as_deref(), Some("fd00:b00b::10"));
+    }
+
+    #[test]
+    fn test_reconcile_flags_unknown_macs() {
+        let nodes = vec![node("dev01", "52:54:00:aa:bb:01", Some("172.31.0.11"))];
+        let mut stranger = lease("52:54:00:ff:ff:ff", "172.31.0.100", 200);
+        stranger.hostname = "stranger".to_string();
+
+        let reconciled = reconcile(&nodes, &[stranger], 100);
+        assert!(reconciled.updates.is_empty());
+        assert_eq!(reconciled.leases[0].status, LeaseStatus::Unknown);
+        assert_eq!(reconciled.leases[0].node_name, None);
+        assert_eq!(reconciled.leases[0].hostname.as_deref(), Some("stranger"));
+    }
+
+    #[test]
+    fn test_reconcile_skips_expired_and_declined() {
+        let nodes = vec![node("dev01", "52:54:00:aa:bb:01", None)];
+        let leases = vec![
+            lease("52:54:00:aa:bb:01", "172.31.0.11", 100),
+            lease("", "172.31.0.20", 200),
+        ];
+
+        let reconciled = reconcile(&nodes, &leases, 100);
+        assert!(reconciled.leases.is_empty());
+        assert!(reconciled.updates.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_sorts_by_address() {
+        let leases = vec![
+            lease("52:54:00:ff:ff:01", "172.31.0.100", 0),
+            lease("52:54:00:ff:ff:02", "172.31.0.20", 0),
+        ];
+
+        let reconciled = reconcile(&[], &leases, 100);
+        let addresses: Vec<&str> = reconciled
+            .leases
+            .iter()
+            .map(|l| l.ipv4_address.as_str())
+            .collect();
+        assert_eq!(addresses, vec!["172.31.0.20", "172.31.0.100"]);
+    }
+}
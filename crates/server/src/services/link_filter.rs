@@ -0,0 +1,253 @@
+//! Layer 2 filter policies of lab links.
+//!
+//! P2p links filter in the eBPF redirect programs. The program on a node's
+//! tap forwards what the node sends and is keyed by the peer tap, so the
+//! filter is set on the programs keyed by `tap_a` and `tap_b` to cover both
+//! directions. Bridged links attach the eBPF `l2_filter` program to the
+//! ingress of `veth_a` and `veth_b`, the veth pair joining the two link
+//! bridges, so frames are filtered as they cross from one bridge to the
+//! other. The kernel bridges still consume the link-local frames they never
+//! forward (`group_fwd_mask`), a filter can only drop more.
+//!
+//! The filter is stored on the link record and re-applied whenever the
+//! redirect programs are re-attached.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use shared::data::{BridgeKind, DbLink, LinkFilterRequest, LinkFilterResponse, RecordId};
+use shared::util::split_node_int;
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use crate::runtime::HostNetwork;
+
+/// Replace the filter of a running link and store it on the link record.
+#[instrument(skip(state), fields(lab_id = %request.lab_id, link = %request.link))]
+pub async fn update_link_filter(
+    request: &LinkFilterRequest,
+    state: &AppState,
+) -> Result<LinkFilterResponse> {
+    let lab_id = &request.lab_id;
+    request.filter.validate()?;
+
+    let lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found", lab_id))?;
+
+    let lab_record_id = lab
+        .id
+        .ok_or_else(|| anyhow!("Lab '{}' missing record ID", lab_id))?;
+
+    let nodes = db::list_nodes_by_lab(&state.db, lab_record_id.clone()).await?;
+    let links = db::list_links_by_lab(&state.db, lab_record_id).await?;
+
+    let node_names = nodes
+        .into_iter()
+        .filter_map(|node| node.id.map(|id| (id, node.name)))
+        .collect();
+
+    let mut db_link = find_link(&links, &node_names, &request.link)?
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' is not an interface of a link in lab '{}'",
+                request.link,
+                lab_id
+            )
+        })?
+        .clone();
+    db_link.filter = request.filter.clone();
+
+    apply_link_filter(&db_link, state.runtime.network.as_ref()).await?;
+
+    let link_index = db_link.index;
+    db::update_link(&state.db, db_link)
+        .await
+        .context("Failed to update link filter in database")?;
+
+    Ok(LinkFilterResponse {
+        lab_id: lab_id.clone(),
+        link_index,
+        filter: request.filter.clone(),
+        message: format!(
+            "Link {} ({}) in lab '{}' filter: {}",
+            link_index, request.link, lab_id, request.filter
+        ),
+    })
+}
+
+/// Find the link with `endpoint` ("node::interface") on either side.
+fn find_link<'a>(
+    links: &'a [DbLink],
+    node_names: &HashMap<RecordId, String>,
+    endpoint: &str,
+) -> Result<Option<&'a DbLink>> {
+    let (node, interface) = split_node_int(endpoint)?;
+    let is_node = |id: &RecordId| node_names.get(id).is_some_and(|name| *name == node);
+
+    Ok(links.iter().find(|link| {
+        (is_node(&link.node_a) && link.int_a == interface)
+            || (is_node(&link.node_b) && link.int_b == interface)
+    }))
+}
+
+/// Apply the filter stored on a link to its host interfaces.
+///
+/// P2p links must have their redirect programs attached already. An empty
+/// filter clears the one in place.
+pub(crate) async fn apply_link_filter(link: &DbLink, network: &dyn HostNetwork) -> Result<()> {
+    match link.kind {
+        BridgeKind::P2p => {
+            for peer in [&link.tap_a, &link.tap_b] {
+                network
+                    .set_p2p_filter(peer, &link.filter)
+                    .await
+                    .context(format!(
+                        "Failed to set filter of the program forwarding to {peer}"
+                    ))?;
+            }
+        }
+        BridgeKind::P2pBridge => {
+            for veth in [&link.veth_a, &link.veth_b] {
+                network
+                    .set_l2_filter(veth, &link.filter)
+                    .await
+                    .context(format!("Failed to set filter on {veth}"))?;
+            }
+        }
+        ref kind if link.filter.is_empty() => {
+            tracing::debug!(link_index = link.index, kind = %kind, "No filter to apply");
+        }
+        ref kind => bail!("Link filters are not supported on {} links", kind),
+    }
+
+    tracing::info!(
+        link_index = link.index,
+        filter = %link.filter,
+        "Applied link filter"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeHostNetwork;
+    use shared::data::{L2Match, L2Protocol, LinkFilter};
+
+    const LAB_ID: &str = "abcd1234";
+
+    fn link(index: u16, kind: BridgeKind, filter: LinkFilter) -> DbLink {
+        DbLink {
+            id: None,
+            index,
+            kind,
+            node_a: RecordId::new("node", "dev01"),
+            node_b: RecordId::new("node", "dev02"),
+            int_a: "eth1".to_string(),
+            int_b: "eth2".to_string(),
+            lab: RecordId::new("lab", LAB_ID),
+            bridge_a: format!("bra{index}-{LAB_ID}"),
+            bridge_b: format!("brb{index}-{LAB_ID}"),
+            veth_a: format!("vea{index}-{LAB_ID}"),
+            veth_b: format!("veb{index}-{LAB_ID}"),
+            tap_a: format!("tpa{index}-{LAB_ID}"),
+            tap_b: format!("tpb{index}-{LAB_ID}"),
+            delay_us: 0,
+            jitter_us: 0,
+            loss_percent: 0.0,
+            reorder_percent: 0.0,
+            corrupt_percent: 0.0,
+            filter,
+        }
+    }
+
+    fn drop_lacp() -> LinkFilter {
+        LinkFilter {
+            drop: vec![L2Match::Protocol(L2Protocol::Lacp)],
+            allow: vec![],
+        }
+    }
+
+    fn network() -> FakeHostNetwork {
+        FakeHostNetwork::default()
+            .with_interface("tpa0-abcd1234")
+            .with_interface("tpb0-abcd1234")
+            .with_interface("vea0-abcd1234")
+            .with_interface("veb0-abcd1234")
+    }
+
+    #[tokio::test]
+    async fn test_p2p_filter_covers_both_directions() {
+        let network = network();
+
+        apply_link_filter(&link(0, BridgeKind::P2p, drop_lacp()), &network)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            network.calls(),
+            vec![
+                "set_p2p_filter tpa0-abcd1234 drop lacp",
+                "set_p2p_filter tpb0-abcd1234 drop lacp",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bridged_filter_uses_veths() {
+        let network = network();
+
+        apply_link_filter(
+            &link(0, BridgeKind::P2pBridge, LinkFilter::default()),
+            &network,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            network.calls(),
+            vec![
+                "set_l2_filter vea0-abcd1234 pass all",
+                "set_l2_filter veb0-abcd1234 pass all",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filter_unsupported_link_kind() {
+        let network = network();
+
+        let err = apply_link_filter(&link(0, BridgeKind::P2pUdp, drop_lacp()), &network)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported on p2p_udp links"));
+
+        apply_link_filter(
+            &link(0, BridgeKind::P2pUdp, LinkFilter::default()),
+            &network,
+        )
+        .await
+        .unwrap();
+        assert!(network.calls().is_empty());
+    }
+
+    #[test]
+    fn test_find_link_either_side() {
+        let links = [link(0, BridgeKind::P2p, LinkFilter::default())];
+        let node_names = HashMap::from([
+            (RecordId::new("node", "dev01"), "dev01".to_string()),
+            (RecordId::new("node", "dev02"), "dev02".to_string()),
+        ]);
+
+        for endpoint in ["dev01::eth1", "dev02::eth2"] {
+            let found = find_link(&links, &node_names, endpoint).unwrap();
+            assert_eq!(found.map(|l| l.index), Some(0));
+        }
+        assert!(
+            find_link(&links, &node_names, "dev01::eth2")
+                .unwrap()
+                .is_none()
+        );
+    }
+}
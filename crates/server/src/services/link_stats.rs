@@ -0,0 +1,370 @@
+//! Live traffic counters for lab links.
+//!
+//! P2p links are read from the counter map of the eBPF redirect program on
+//! each tap. The program on `tap_a` redirects to `tap_b`, so its counters are
+//! the A→B direction, keyed by the ifindex of `tap_b`. When no program
+//! reports counters (an older ELF without the counter map), the kernel stats
+//! of `tap_a` are used instead. Bridged links are read from the kernel stats
+//! of `veth_a`, the host side of the veth pair joining the two bridges.
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow};
+use network::{InterfaceStats, P2pRedirectStats};
+use opentelemetry::KeyValue;
+use shared::data::{
+    BridgeKind, DbLink, LinkStats, LinkStatsResponse, LinkStatsSource, RecordId, TrafficCounters,
+};
+use tracing::instrument;
+
+use crate::daemon::metrics::Metrics;
+use crate::daemon::state::AppState;
+use crate::runtime::HostNetwork;
+
+/// Read the traffic counters of every link in a lab.
+#[instrument(skip(state), fields(lab_id = %lab_id))]
+pub async fn link_stats(lab_id: &str, state: &AppState) -> Result<LinkStatsResponse> {
+    let lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found", lab_id))?;
+
+    let lab_record_id = lab
+        .id
+        .ok_or_else(|| anyhow!("Lab '{}' missing record ID", lab_id))?;
+
+    let nodes = db::list_nodes_by_lab(&state.db, lab_record_id.clone()).await?;
+    let links = db::list_links_by_lab(&state.db, lab_record_id).await?;
+
+    let node_names = nodes
+        .into_iter()
+        .filter_map(|node| node.id.map(|id| (id, node.name)))
+        .collect();
+
+    collect_link_stats(lab_id, &links, &node_names, state.runtime.network.as_ref()).await
+}
+
+/// Export the counters of a lab's links as OTel gauges.
+///
+/// Links whose interfaces are gone are skipped rather than reported as zero.
+pub fn record_link_metrics(metrics: &Metrics, stats: &LinkStatsResponse) {
+    for link in &stats.links {
+        if link.source == LinkStatsSource::Unavailable {
+            continue;
+        }
+        for (direction, counters) in [("a_to_b", &link.a_to_b), ("b_to_a", &link.b_to_a)] {
+            let attrs = &[
+                KeyValue::new("lab.id", stats.lab_id.clone()),
+                KeyValue::new("link.index", i64::from(link.index)),
+                KeyValue::new("link.direction", direction),
+            ];
+            metrics.link_packets.record(counters.packets, attrs);
+            metrics.link_bytes.record(counters.bytes, attrs);
+            metrics.link_drops.record(counters.drops, attrs);
+        }
+    }
+}
+
+/// Build the link counters of a lab from the host's interface and eBPF stats.
+pub(crate) async fn collect_link_stats(
+    lab_id: &str,
+    links: &[DbLink],
+    node_names: &HashMap<RecordId, String>,
+    network: &dyn HostNetwork,
+) -> Result<LinkStatsResponse> {
+    let interfaces = network
+        .interface_stats(lab_id)
+        .await
+        .context("Failed to read interface stats")?;
+
+    let has_p2p = links.iter().any(|link| link.kind == BridgeKind::P2p);
+    let redirects = if has_p2p {
+        network.p2p_redirect_stats().await.unwrap_or_else(|e| {
+            tracing::debug!(error = %e, "Failed to read eBPF redirect stats");
+            HashMap::new()
+        })
+    } else {
+        HashMap::new()
+    };
+
+    let mut stats: Vec<LinkStats> = links
+        .iter()
+        .map(|link| link_counters(link, node_names, &interfaces, &redirects))
+        .collect();
+    stats.sort_by_key(|link| link.index);
+
+    let sampled_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+
+    Ok(LinkStatsResponse {
+        lab_id: lab_id.to_string(),
+        sampled_at_ms,
+        links: stats,
+    })
+}
+
+fn link_counters(
+    link: &DbLink,
+    node_names: &HashMap<RecordId, String>,
+    interfaces: &HashMap<String, InterfaceStats>,
+    redirects: &HashMap<u32, P2pRedirectStats>,
+) -> LinkStats {
+    let node_name = |id: &RecordId| {
+        node_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.key.to_string())
+    };
+
+    let (source, a_to_b, b_to_a) = match link.kind {
+        BridgeKind::P2p => p2p_counters(link, interfaces, redirects),
+        _ => match interfaces.get(&link.veth_a) {
+            // Frames from A leave bridge A through veth_a towards bridge B.
+            Some(veth_a) => (
+                LinkStatsSource::Netlink,
+                transmitted(veth_a),
+                received(veth_a),
+            ),
+            None => unavailable(),
+        },
+    };
+
+    LinkStats {
+        index: link.index,
+        kind: link.kind.clone(),
+        node_a: node_name(&link.node_a),
+        int_a: link.int_a.clone(),
+        node_b: node_name(&link.node_b),
+        int_b: link.int_b.clone(),
+        source,
+        a_to_b,
+        b_to_a,
+    }
+}
+
+fn p2p_counters(
+    link: &DbLink,
+    interfaces: &HashMap<String, InterfaceStats>,
+    redirects: &HashMap<u32, P2pRedirectStats>,
+) -> (LinkStatsSource, TrafficCounters, TrafficCounters) {
+    let (Some(tap_a), Some(tap_b)) = (interfaces.get(&link.tap_a), interfaces.get(&link.tap_b))
+    else {
+        return unavailable();
+    };
+
+    // Each program is keyed by the peer it redirects to.
+    match (redirects.get(&tap_b.ifindex), redirects.get(&tap_a.ifindex)) {
+        (Some(from_a), Some(from_b)) => (
+            LinkStatsSource::Ebpf,
+            redirected(from_a),
+            redirected(from_b),
+        ),
+        // A tap receives what its node sends.
+        _ => (
+            LinkStatsSource::Netlink,
+            received(tap_a),
+            transmitted(tap_a),
+        ),
+    }
+}
+
+fn unavailable() -> (LinkStatsSource, TrafficCounters, TrafficCounters) {
+    (
+        LinkStatsSource::Unavailable,
+        TrafficCounters::default(),
+        TrafficCounters::default(),
+    )
+}
+
+fn redirected(stats: &P2pRedirectStats) -> TrafficCounters {
+    TrafficCounters {
+        packets: stats.packets,
+        bytes: stats.bytes,
+        drops: stats.redirect_failures,
+    }
+}
+
+fn received(stats: &InterfaceStats) -> TrafficCounters {
+    TrafficCounters {
+        packets: stats.rx_packets,
+        bytes: stats.rx_bytes,
+        drops: stats.rx_dropped,
+    }
+}
+
+fn transmitted(stats: &InterfaceStats) -> TrafficCounters {
+    TrafficCounters {
+        packets: stats.tx_packets,
+        bytes: stats.tx_bytes,
+        drops: stats.tx_dropped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeHostNetwork;
+    use shared::data::LinkFilter;
+
+    const LAB_ID: &str = "abcd1234";
+
+    fn link(index: u16, kind: BridgeKind) -> DbLink {
+        DbLink {
+            id: None,
+            index,
+            kind,
+            node_a: RecordId::new("node", "dev01"),
+            node_b: RecordId::new("node", "dev02"),
+            int_a: format!("eth{}", index + 1),
+            int_b: format!("eth{}", index + 1),
+            lab: RecordId::new("lab", LAB_ID),
+            bridge_a: format!("bra{index}-{LAB_ID}"),
+            bridge_b: format!("brb{index}-{LAB_ID}"),
+            veth_a: format!("vea{index}-{LAB_ID}"),
+            veth_b: format!("veb{index}-{LAB_ID}"),
+            tap_a: format!("tpa{index}-{LAB_ID}"),
+            tap_b: format!("tpb{index}-{LAB_ID}"),
+            delay_us: 0,
+            jitter_us: 0,
+            loss_percent: 0.0,
+            reorder_percent: 0.0,
+            corrupt_percent: 0.0,
+            filter: LinkFilter::default(),
+        }
+    }
+
+    fn node_names() -> HashMap<RecordId, String> {
+        HashMap::from([
+            (RecordId::new("node", "dev01"), "dev01".to_string()),
+            (RecordId::new("node", "dev02"), "dev02".to_string()),
+        ])
+    }
+
+    fn iface(ifindex: u32, rx: (u64, u64, u64), tx: (u64, u64, u64)) -> InterfaceStats {
+        InterfaceStats {
+            ifindex,
+            rx_packets: rx.0,
+            rx_bytes: rx.1,
+            rx_dropped: rx.2,
+            tx_packets: tx.0,
+            tx_bytes: tx.1,
+            tx_dropped: tx.2,
+        }
+    }
+
+    fn counters(packets: u64, bytes: u64, drops: u64) -> TrafficCounters {
+        TrafficCounters {
+            packets,
+            bytes,
+            drops,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_p2p_link_uses_ebpf_counters() {
+        let network = FakeHostNetwork::default()
+            .with_interface_stats("tpa0-abcd1234", iface(10, (1, 1, 0), (1, 1, 0)))
+            .with_interface_stats("tpb0-abcd1234", iface(11, (1, 1, 0), (1, 1, 0)))
+            .with_p2p_stats(
+                11,
+                P2pRedirectStats {
+                    packets: 100,
+                    bytes: 6_400,
+                    redirect_failures: 2,
+                },
+            )
+            .with_p2p_stats(
+                10,
+                P2pRedirectStats {
+                    packets: 40,
+                    bytes: 2_560,
+                    redirect_failures: 0,
+                },
+            );
+
+        let response =
+            collect_link_stats(LAB_ID, &[link(0, BridgeKind::P2p)], &node_names(), &network)
+                .await
+                .unwrap();
+
+        let stats = &response.links[0];
+        assert_eq!(stats.source, LinkStatsSource::Ebpf);
+        assert_eq!(stats.a_to_b, counters(100, 6_400, 2));
+        assert_eq!(stats.b_to_a, counters(40, 2_560, 0));
+        assert_eq!(stats.node_a, "dev01");
+        assert_eq!(stats.node_b, "dev02");
+    }
+
+    #[tokio::test]
+    async fn test_p2p_link_falls_back_to_tap_counters() {
+        let network = FakeHostNetwork::default()
+            .with_interface_stats("tpa0-abcd1234", iface(10, (5, 500, 1), (7, 700, 0)))
+            .with_interface_stats("tpb0-abcd1234", iface(11, (7, 700, 0), (5, 500, 0)))
+            .fail_on("p2p_redirect_stats");
+
+        let response =
+            collect_link_stats(LAB_ID, &[link(0, BridgeKind::P2p)], &node_names(), &network)
+                .await
+                .unwrap();
+
+        let stats = &response.links[0];
+        assert_eq!(stats.source, LinkStatsSource::Netlink);
+        assert_eq!(stats.a_to_b, counters(5, 500, 1));
+        assert_eq!(stats.b_to_a, counters(7, 700, 0));
+    }
+
+    #[tokio::test]
+    async fn test_bridged_link_uses_veth_counters() {
+        let network = FakeHostNetwork::default()
+            .with_interface_stats("vea1-abcd1234", iface(20, (3, 300, 0), (9, 900, 4)));
+
+        let response = collect_link_stats(
+            LAB_ID,
+            &[link(1, BridgeKind::P2pBridge)],
+            &node_names(),
+            &network,
+        )
+        .await
+        .unwrap();
+
+        let stats = &response.links[0];
+        assert_eq!(stats.source, LinkStatsSource::Netlink);
+        assert_eq!(stats.a_to_b, counters(9, 900, 4));
+        assert_eq!(stats.b_to_a, counters(3, 300, 0));
+    }
+
+    #[tokio::test]
+    async fn test_missing_interfaces_are_unavailable() {
+        let network = FakeHostNetwork::default();
+
+        let response = collect_link_stats(
+            LAB_ID,
+            &[link(2, BridgeKind::P2pBridge), link(0, BridgeKind::P2p)],
+            &node_names(),
+            &network,
+        )
+        .await
+        .unwrap();
+
+        let indexes: Vec<u16> = response.links.iter().map(|l| l.index).collect();
+        assert_eq!(indexes, vec![0, 2]);
+        assert!(
+            response
+                .links
+                .iter()
+                .all(|l| l.source == LinkStatsSource::Unavailable)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_interface_stats_failure_is_an_error() {
+        let network = FakeHostNetwork::default().fail_on("interface_stats");
+
+        let result =
+            collect_link_stats(LAB_ID, &[link(0, BridgeKind::P2p)], &node_names(), &network).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -4,7 +4,7 @@ use tracing::instrument;
 use crate::daemon::state::AppState;
 use shared::data::{LabSummary, ListLabsResponse};
 
-/// List all labs for a specific user
+/// List all labs for a specific user, including labs shared with them
 ///
 /// # Arguments
 /// * `username` - The username to list labs for
@@ -50,6 +50,37 @@ pub async fn list_labs(username: &str, state: &AppState) -> Result<ListLabsRespo
             name: lab.name.clone(),
             node_count,
             status: lab.status,
+            shared_role: None,
+        });
+    }
+
+    // Add labs shared with the user, directly or through a team
+    let shared_labs = db::list_labs_shared_with_user(&state.db, &user_id)
+        .await
+        .context("Failed to list labs shared with user")?;
+
+    for lab in shared_labs {
+        if lab.user == user_id {
+            continue;
+        }
+        let lab_record_id = lab
+            .id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Lab has no ID"))?;
+
+        let node_count = db::count_nodes_by_lab(&state.db, lab_record_id)
+            .await
+            .unwrap_or(0);
+        let shared_role = db::get_lab_role(&state.db, &lab.lab_id, username)
+            .await
+            .context("Failed to resolve role on shared lab")?;
+
+        lab_summaries.push(LabSummary {
+            id: lab.lab_id.clone(),
+            name: lab.name.clone(),
+            node_count,
+            status: lab.status,
+            shared_role,
         });
     }
 
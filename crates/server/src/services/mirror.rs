@@ -0,0 +1,437 @@
+//! Port mirroring from lab links to mirror ports.
+//!
+//! A mirror port is a node interface declared as a destination in the
+//! manifest `mirrors`. It is the only member of its own lab bridge, and
+//! mirrored frames are sent to that bridge.
+//!
+//! P2p links copy frames from the eBPF redirect programs. The program on a
+//! node's tap carries what the node sends and is keyed by the peer tap, so
+//! frames sent by node A are the program keyed by `tap_b`, and frames
+//! received by node A are the program keyed by `tap_a`. Bridged links copy
+//! frames with tc mirred filters on `veth_a`/`veth_b`, the host side of the
+//! veth pair joining the two link bridges: frames sent by a node leave its
+//! bridge through the egress of its veth, and frames it receives arrive on
+//! the ingress.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, anyhow, bail};
+use network::TcHook;
+use shared::data::{
+    BridgeKind, DbLink, LinkMirrorRequest, LinkMirrorResponse, MirrorDirection, RecordId,
+};
+use shared::konst::{BRIDGE_PREFIX, SHERPA_LAB_MANIFEST_FILE, SHERPA_LABS_PATH};
+use shared::util::split_node_int;
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use crate::runtime::HostNetwork;
+
+/// tc priority of the mirror filter for mirror port bridge 0.
+/// Each mirror port gets its own priority so sessions on the same hook
+/// can be added and removed independently.
+const MIRROR_TC_PRIORITY_BASE: u16 = 10;
+
+/// Whether a mirror session is being started or stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorAction {
+    Add,
+    Remove,
+}
+
+/// Start copying the traffic of a link interface to a mirror port.
+#[instrument(skip(state), fields(lab_id = %request.lab_id))]
+pub async fn mirror_add(
+    request: &LinkMirrorRequest,
+    state: &AppState,
+) -> Result<LinkMirrorResponse> {
+    apply_request(request, MirrorAction::Add, state).await
+}
+
+/// Stop copying the traffic of a link interface to a mirror port.
+/// Stopping a session that is not running succeeds.
+#[instrument(skip(state), fields(lab_id = %request.lab_id))]
+pub async fn mirror_remove(
+    request: &LinkMirrorRequest,
+    state: &AppState,
+) -> Result<LinkMirrorResponse> {
+    apply_request(request, MirrorAction::Remove, state).await
+}
+
+async fn apply_request(
+    request: &LinkMirrorRequest,
+    action: MirrorAction,
+    state: &AppState,
+) -> Result<LinkMirrorResponse> {
+    let lab_id = &request.lab_id;
+    let lab = db::get_lab(&state.db, lab_id)
+        .await
+        .context(format!("Lab '{}' not found", lab_id))?;
+
+    let lab_record_id = lab
+        .id
+        .ok_or_else(|| anyhow!("Lab '{}' missing record ID", lab_id))?;
+
+    let nodes = db::list_nodes_by_lab(&state.db, lab_record_id.clone()).await?;
+    let links = db::list_links_by_lab(&state.db, lab_record_id).await?;
+
+    let node_names = nodes
+        .into_iter()
+        .filter_map(|node| node.id.map(|id| (id, node.name)))
+        .collect();
+
+    let manifest = load_lab_manifest(lab_id)?;
+    let port_index = manifest
+        .mirror_port_index(&request.destination)
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' is not a mirror port of lab '{}', declare it as a destination in the manifest mirrors",
+                request.destination,
+                lab_id
+            )
+        })?;
+
+    apply_mirror(
+        lab_id,
+        &links,
+        &node_names,
+        &request.source,
+        port_index,
+        request.direction,
+        action,
+        state.runtime.network.as_ref(),
+    )
+    .await?;
+
+    let verb = match action {
+        MirrorAction::Add => "Mirroring",
+        MirrorAction::Remove => "Stopped mirroring",
+    };
+    Ok(LinkMirrorResponse {
+        lab_id: lab_id.clone(),
+        source: request.source.clone(),
+        destination: request.destination.clone(),
+        direction: request.direction,
+        message: format!(
+            "{} {} traffic of {} to {}",
+            verb, request.direction, request.source, request.destination
+        ),
+    })
+}
+
+/// Start every mirror declared in a lab's manifest.
+///
+/// Mirrors only copy traffic, so a mirror that cannot be started does not
+/// fail the caller. Returns a description of each one that failed.
+pub async fn start_manifest_mirrors(
+    lab_id: &str,
+    lab_record_id: &RecordId,
+    state: &AppState,
+) -> Result<Vec<String>> {
+    let manifest = load_lab_manifest(lab_id)?;
+    let Some(mirrors) = &manifest.mirrors else {
+        return Ok(vec![]);
+    };
+
+    let nodes = db::list_nodes_by_lab(&state.db, lab_record_id.clone()).await?;
+    let links = db::list_links_by_lab(&state.db, lab_record_id.clone()).await?;
+    let node_names = nodes
+        .into_iter()
+        .filter_map(|node| node.id.map(|id| (id, node.name)))
+        .collect();
+
+    let mut failures = vec![];
+    for mirror in mirrors {
+        let result = match manifest.mirror_port_index(&mirror.destination) {
+            Some(port_index) => {
+                apply_mirror(
+                    lab_id,
+                    &links,
+                    &node_names,
+                    &mirror.source,
+                    port_index,
+                    mirror.direction,
+                    MirrorAction::Add,
+                    state.runtime.network.as_ref(),
+                )
+                .await
+            }
+            None => Err(anyhow!("'{}' is not a mirror port", mirror.destination)),
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                lab_id = %lab_id,
+                source = %mirror.source,
+                destination = %mirror.destination,
+                error = %e,
+                "Failed to start port mirror"
+            );
+            failures.push(format!(
+                "{} -> {}: {:#}",
+                mirror.source, mirror.destination, e
+            ));
+        }
+    }
+    Ok(failures)
+}
+
+/// Read the manifest a lab was created from.
+pub fn load_lab_manifest(lab_id: &str) -> Result<topology::Manifest> {
+    let manifest_path = format!("{SHERPA_LABS_PATH}/{lab_id}/{SHERPA_LAB_MANIFEST_FILE}");
+    let manifest_str = std::fs::read_to_string(&manifest_path)
+        .context(format!("Lab manifest not found: {manifest_path}"))?;
+    let manifest: serde_json::Value =
+        serde_json::from_str(&manifest_str).context("Failed to parse lab manifest")?;
+    topology::Manifest::from_json(manifest)
+}
+
+/// Add or remove the host mirrors of one session.
+///
+/// `source` is a "node::interface" endpoint of a lab link and `port_index`
+/// the lab bridge index of the mirror port.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn apply_mirror(
+    lab_id: &str,
+    links: &[DbLink],
+    node_names: &HashMap<RecordId, String>,
+    source: &str,
+    port_index: u16,
+    direction: MirrorDirection,
+    action: MirrorAction,
+    network: &dyn HostNetwork,
+) -> Result<()> {
+    let (node, interface) = split_node_int(source)?;
+    let is_node = |id: &RecordId| node_names.get(id).is_some_and(|name| *name == node);
+
+    let (link, is_a) = links
+        .iter()
+        .find_map(|link| {
+            if is_node(&link.node_a) && link.int_a == interface {
+                Some((link, true))
+            } else if is_node(&link.node_b) && link.int_b == interface {
+                Some((link, false))
+            } else {
+                None
+            }
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' is not an interface of a link in lab '{}'",
+                source,
+                lab_id
+            )
+        })?;
+
+    let target = format!("{}s{}-{}", BRIDGE_PREFIX, port_index, lab_id);
+
+    match link.kind {
+        BridgeKind::P2p => {
+            let (own_tap, peer_tap) = if is_a {
+                (&link.tap_a, &link.tap_b)
+            } else {
+                (&link.tap_b, &link.tap_a)
+            };
+            let mut programs = vec![];
+            if direction.includes_ingress() {
+                programs.push(own_tap);
+            }
+            if direction.includes_egress() {
+                programs.push(peer_tap);
+            }
+            for peer in programs {
+                match action {
+                    MirrorAction::Add => network.add_p2p_mirror(peer, &target).await?,
+                    MirrorAction::Remove => network.remove_p2p_mirror(peer, &target).await?,
+                }
+            }
+        }
+        BridgeKind::P2pBridge => {
+            let veth = if is_a { &link.veth_a } else { &link.veth_b };
+            let priority = MIRROR_TC_PRIORITY_BASE + port_index;
+            let mut hooks = vec![];
+            if direction.includes_ingress() {
+                hooks.push(TcHook::Ingress);
+            }
+            if direction.includes_egress() {
+                hooks.push(TcHook::Egress);
+            }
+            for hook in hooks {
+                match action {
+                    MirrorAction::Add => {
+                        network.add_tc_mirror(veth, hook, &target, priority).await?
+                    }
+                    MirrorAction::Remove => network.remove_tc_mirror(veth, hook, priority).await?,
+                }
+            }
+        }
+        ref kind => bail!("Port mirroring is not supported on {} links", kind),
+    }
+
+    tracing::info!(
+        lab_id = %lab_id,
+        source = %source,
+        target = %target,
+        direction = %direction,
+        action = ?action,
+        "Updated port mirror"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::FakeHostNetwork;
+    use shared::data::LinkFilter;
+
+    const LAB_ID: &str = "abcd1234";
+
+    fn link(index: u16, kind: BridgeKind) -> DbLink {
+        DbLink {
+            id: None,
+            index,
+            kind,
+            node_a: RecordId::new("node", "dev01"),
+            node_b: RecordId::new("node", "dev02"),
+            int_a: "eth1".to_string(),
+            int_b: "eth2".to_string(),
+            lab: RecordId::new("lab", LAB_ID),
+            bridge_a: format!("bra{index}-{LAB_ID}"),
+            bridge_b: format!("brb{index}-{LAB_ID}"),
+            veth_a: format!("vea{index}-{LAB_ID}"),
+            veth_b: format!("veb{index}-{LAB_ID}"),
+            tap_a: format!("tpa{index}-{LAB_ID}"),
+            tap_b: format!("tpb{index}-{LAB_ID}"),
+            delay_us: 0,
+            jitter_us: 0,
+            loss_percent: 0.0,
+            reorder_percent: 0.0,
+            corrupt_percent: 0.0,
+            filter: LinkFilter::default(),
+        }
+    }
+
+    fn node_names() -> HashMap<RecordId, String> {
+        HashMap::from([
+            (RecordId::new("node", "dev01"), "dev01".to_string()),
+            (RecordId::new("node", "dev02"), "dev02".to_string()),
+        ])
+    }
+
+    fn network() -> FakeHostNetwork {
+        FakeHostNetwork::default()
+            .with_interface("tpa0-abcd1234")
+            .with_interface("tpb0-abcd1234")
+            .with_interface("vea0-abcd1234")
+            .with_interface("veb0-abcd1234")
+            .with_interface("brs2-abcd1234")
+    }
+
+    #[tokio::test]
+    async fn test_p2p_mirror_uses_redirect_programs() {
+        let network = network();
+        let links = [link(0, BridgeKind::P2p)];
+
+        apply_mirror(
+            LAB_ID,
+            &links,
+            &node_names(),
+            "dev01::eth1",
+            2,
+            MirrorDirection::Egress,
+            MirrorAction::Add,
+            &network,
+        )
+        .await
+        .unwrap();
+        apply_mirror(
+            LAB_ID,
+            &links,
+            &node_names(),
+            "dev02::eth2",
+            2,
+            MirrorDirection::Ingress,
+            MirrorAction::Remove,
+            &network,
+        )
+        .await
+        .unwrap();
+
+        // Frames sent by dev01 and received by dev02 are both redirected to tap_b
+        assert_eq!(
+            network.calls(),
+            vec![
+                "add_p2p_mirror tpb0-abcd1234 brs2-abcd1234",
+                "remove_p2p_mirror tpb0-abcd1234 brs2-abcd1234",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bridged_mirror_uses_tc_hooks() {
+        let network = network();
+
+        apply_mirror(
+            LAB_ID,
+            &[link(0, BridgeKind::P2pBridge)],
+            &node_names(),
+            "dev02::eth2",
+            2,
+            MirrorDirection::Both,
+            MirrorAction::Add,
+            &network,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            network.calls(),
+            vec![
+                "add_tc_mirror veb0-abcd1234 Ingress brs2-abcd1234 12",
+                "add_tc_mirror veb0-abcd1234 Egress brs2-abcd1234 12",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mirror_source_must_be_a_link() {
+        let network = network();
+
+        let err = apply_mirror(
+            LAB_ID,
+            &[link(0, BridgeKind::P2p)],
+            &node_names(),
+            "dev01::eth9",
+            2,
+            MirrorDirection::Both,
+            MirrorAction::Add,
+            &network,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("not an interface of a link"));
+        assert!(network.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mirror_port_must_exist() {
+        let network = FakeHostNetwork::default()
+            .with_interface("tpa0-abcd1234")
+            .with_interface("tpb0-abcd1234");
+
+        let result = apply_mirror(
+            LAB_ID,
+            &[link(0, BridgeKind::P2p)],
+            &node_names(),
+            "dev01::eth1",
+            2,
+            MirrorDirection::Both,
+            MirrorAction::Add,
+            &network,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
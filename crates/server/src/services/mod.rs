@@ -1,16 +1,32 @@
+pub mod api_token;
+pub mod boot;
+pub mod bridge_vlan;
+pub mod cabling;
 pub mod clean;
+pub mod commit;
 pub mod container_pull;
+pub mod custom_model;
 pub mod delete;
 pub mod destroy;
 pub mod down;
 pub mod download;
+pub mod image_pipeline;
+pub mod image_usage;
 pub mod impairment;
 pub mod import;
 pub mod inspect;
+pub mod leases;
+pub mod link_filter;
+pub mod link_stats;
 pub mod list_labs;
+pub mod mirror;
 pub mod node_ops;
+pub mod oci;
 pub mod progress;
 pub mod redeploy;
+pub mod registry;
 pub mod resume;
 pub mod scanner;
+pub mod share;
 pub mod up;
+pub mod upload;
@@ -7,6 +7,7 @@ use anyhow::{Context, Result, anyhow, bail};
 use askama::Template;
 use tracing::instrument;
 
+use crate::runtime::{ContainerRuntime, VmRuntime};
 use crate::services::progress::ProgressSender;
 
 use shared::data;
@@ -36,7 +37,7 @@ use shared::konst::{
     SHERPA_USERNAME, SSH_PORT, TELNET_PORT, VAULT_ZTP_CONFIG_MOUNT, ZTP_DIR, ZTP_ISO, ZTP_JSON,
 };
 use shared::util;
-use virt::sys::VIR_DOMAIN_UNDEFINE_NVRAM;
+use virt::sys::VIR_DOMAIN_RUNNING;
 
 // ============================================================================
 // Result structs
@@ -201,6 +202,7 @@ pub fn generate_container_ztp(
                         mgmt_ipv4: mgmt_net.v4.clone(),
                         mgmt_ipv6_address: node.ipv6_address,
                         mgmt_ipv6: mgmt_net.v6.clone(),
+                        interface_mtus: node.interface_mtus.clone(),
                     };
                     arista_template.render()?
                 }
@@ -553,7 +555,30 @@ pub fn generate_vm_ztp(
     let (mut src_ignition_disk, mut dst_ignition_disk): (Option<String>, Option<String>) =
         (None, None);
 
-    if node_image.ztp_enable {
+    if node_image.ztp_enable
+        && let Some(custom_model) = node.custom_model.clone()
+    {
+        let custom_ztp = take_custom_ztp_config(node, progress)?;
+        let ztp_iso = generate_custom_model_ztp(
+            node,
+            &custom_model,
+            node_image,
+            lab_dir,
+            tftp_dir,
+            sherpa_user,
+            dns,
+            mgmt_net,
+            node_ipv4_address,
+            &custom_ztp,
+            progress,
+        )?;
+        if ztp_iso {
+            src_cdrom_iso = Some(format!("{lab_dir}/{}/{ZTP_ISO}", node.name));
+            dst_cdrom_iso = Some(format!(
+                "{SHERPA_STORAGE_POOL_PATH}/{node_name_with_lab}.iso"
+            ));
+        }
+    } else if node_image.ztp_enable {
         // Validate custom ZTP config support
         if node.ztp_config.is_some() {
             match node_image.ztp_method {
@@ -1488,6 +1513,7 @@ fn generate_tftp_ztp(
                     mgmt_ipv4: mgmt_net.v4.clone(),
                     mgmt_ipv6_address: node.ipv6_address,
                     mgmt_ipv6: mgmt_net.v6.clone(),
+                    interface_mtus: node.interface_mtus.clone(),
                 };
                 let rendered_template = arista_template.render()?;
                 let ztp_config = format!("{tftp_dir}/{}.conf", node.name);
@@ -1516,6 +1542,7 @@ fn generate_tftp_ztp(
                     mgmt_ipv4: mgmt_net.v4.clone(),
                     mgmt_ipv6_address: node.ipv6_address,
                     mgmt_ipv6: mgmt_net.v6.clone(),
+                    interface_mtus: node.interface_mtus.clone(),
                 };
                 let juniper_rendered_template = juniper_template.render()?;
                 let ztp_config = format!("{tftp_dir}/{}.conf", node.name);
@@ -1530,6 +1557,131 @@ fn generate_tftp_ztp(
     Ok(())
 }
 
+/// Render and write the ZTP config of a custom model node.
+///
+/// A per-node `ztp_config` from the manifest takes precedence over the
+/// model's template. Returns whether a ZTP ISO was built for the node.
+#[allow(clippy::too_many_arguments)]
+fn generate_custom_model_ztp(
+    node: &topology::NodeExpanded,
+    custom_model: &data::CustomModel,
+    node_image: &data::NodeConfig,
+    lab_dir: &str,
+    tftp_dir: &str,
+    sherpa_user: &data::User,
+    dns: &data::Dns,
+    mgmt_net: &data::SherpaNetwork,
+    node_ipv4_address: Ipv4Addr,
+    custom_ztp: &Option<String>,
+    progress: &ProgressSender,
+) -> Result<bool> {
+    let _ = progress.send_status(
+        format!(
+            "Creating {} ZTP config for custom model VM: {} ({})",
+            custom_model.ztp.method,
+            node.name,
+            custom_model.reference()
+        ),
+        StatusKind::Progress,
+    );
+
+    let config = match custom_ztp {
+        Some(config) => config.clone(),
+        None => {
+            let source = custom_model.ztp.template.as_deref().ok_or_else(|| {
+                anyhow!(
+                    "Custom model '{}' has no ZTP template",
+                    custom_model.reference()
+                )
+            })?;
+
+            let mut user = sherpa_user.clone();
+            if let Some(username) = &node_image.ztp_username {
+                user.username = username.clone();
+            }
+            if let Some(password) = &node_image.ztp_password {
+                user.password = Some(password.clone());
+            }
+
+            let data_interface_count = node
+                .data_interface_count
+                .unwrap_or(node_image.data_interface_count);
+            let first_data_idx = node_image.reserved_interface_count.saturating_add(1);
+            let interfaces = (first_data_idx
+                ..=node_image
+                    .reserved_interface_count
+                    .saturating_add(data_interface_count))
+                .map(|idx| {
+                    let name = node.interface_from_idx(idx)?;
+                    let mtu = node
+                        .interface_mtu(&name)
+                        .unwrap_or(node_image.interface_mtu);
+                    Ok(data::InterfaceMtu { name, mtu })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let t = template::CustomZtpTemplate {
+                hostname: node.name.clone(),
+                user,
+                dns: dns.clone(),
+                mgmt_interface: node.interface_from_idx(0)?,
+                mgmt_ipv4: mgmt_net.v4.clone(),
+                mgmt_ipv4_address: node_ipv4_address,
+                mgmt_ipv6_address: node.ipv6_address,
+                mgmt_ipv6: mgmt_net.v6.clone(),
+                interfaces,
+            };
+            t.render(source).with_context(|| {
+                format!(
+                    "Failed to render ZTP template of custom model '{}' for node '{}'",
+                    custom_model.reference(),
+                    node.name
+                )
+            })?
+        }
+    };
+
+    let dir = format!("{lab_dir}/{}", node.name);
+    match custom_model.ztp.method {
+        data::ZtpMethod::Tftp => {
+            util::create_file(&format!("{tftp_dir}/{}.conf", node.name), config)?;
+            Ok(false)
+        }
+        data::ZtpMethod::Cdrom => {
+            let filename = custom_model.ztp.filename.as_deref().ok_or_else(|| {
+                anyhow!(
+                    "Custom model '{}' has no ZTP config filename",
+                    custom_model.reference()
+                )
+            })?;
+            util::create_dir(&dir)?;
+            util::create_file(&format!("{dir}/{filename}"), config)?;
+            util::create_ztp_iso(&format!("{dir}/{ZTP_ISO}"), dir)?;
+            Ok(true)
+        }
+        data::ZtpMethod::CloudInit => {
+            let meta_data = template::MetaDataConfig {
+                instance_id: format!("iid-{}", node.name),
+                local_hostname: format!("{}.{}", node.name, SHERPA_DOMAIN_NAME),
+                ..Default::default()
+            };
+            util::create_dir(&dir)?;
+            util::create_file(&format!("{dir}/{CLOUD_INIT_USER_DATA}"), config)?;
+            util::create_file(
+                &format!("{dir}/{CLOUD_INIT_META_DATA}"),
+                meta_data.to_string()?,
+            )?;
+            util::create_ztp_iso(&format!("{dir}/{ZTP_ISO}"), dir)?;
+            Ok(true)
+        }
+        ref method => bail!(
+            "ZTP method '{}' is not supported for custom model '{}'",
+            method,
+            custom_model.reference()
+        ),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn generate_cdrom_ztp(
     node: &topology::NodeExpanded,
@@ -1617,6 +1769,7 @@ fn generate_cdrom_ztp(
                     mgmt_ipv4: mgmt_net.v4.clone(),
                     mgmt_ipv6_address: node.ipv6_address,
                     mgmt_ipv6: mgmt_net.v6.clone(),
+                    interface_mtus: node.interface_mtus.clone(),
                 };
                 let rendered_template = t.render()?;
                 let ztp_config = format!("{dir}/{CISCO_NXOS_ZTP_CONFIG}");
@@ -1670,6 +1823,7 @@ fn generate_cdrom_ztp(
                     mgmt_ipv4: mgmt_net.v4.clone(),
                     mgmt_ipv6_address: node.ipv6_address,
                     mgmt_ipv6: mgmt_net.v6.clone(),
+                    interface_mtus: node.interface_mtus.clone(),
                 };
                 let rendered_template = t.render()?;
                 let ztp_config = format!("{dir}/{JUNIPER_ZTP_CONFIG}");
@@ -1938,6 +2092,7 @@ fn generate_usb_ztp(
                         mgmt_ipv4: mgmt_net.v4.clone(),
                         mgmt_ipv6_address: node.ipv6_address,
                         mgmt_ipv6: mgmt_net.v6.clone(),
+                        interface_mtus: node.interface_mtus.clone(),
                     };
                     t.render()?
                 }
@@ -1970,6 +2125,7 @@ fn generate_usb_ztp(
                         mgmt_ipv4: mgmt_net.v4.clone(),
                         mgmt_ipv6_address: node.ipv6_address,
                         mgmt_ipv6: mgmt_net.v6.clone(),
+                        interface_mtus: node.interface_mtus.clone(),
                     };
                     t.render()?
                 }
@@ -2368,10 +2524,10 @@ fn cpu_features_for_model(model: &data::NodeModel) -> Vec<data::CpuFeature> {
 // Disk Cloning
 // ============================================================================
 
-/// Clone a list of disks in parallel using libvirt.
-#[instrument(skip(qemu_conn, clone_disks, progress), fields(%lab_id), level = "debug")]
+/// Clone a list of disks in parallel.
+#[instrument(skip(vms, clone_disks, progress), fields(%lab_id), level = "debug")]
 pub async fn clone_node_disks(
-    qemu_conn: Arc<libvirt::QemuConnection>,
+    vms: Arc<dyn VmRuntime>,
     clone_disks: Vec<data::CloneDisk>,
     lab_id: &str,
     progress: &ProgressSender,
@@ -2391,7 +2547,7 @@ pub async fn clone_node_disks(
     let tasks: Vec<_> = clone_disks
         .into_iter()
         .map(|disk| {
-            let conn: Arc<libvirt::QemuConnection> = Arc::clone(&qemu_conn);
+            let vms = Arc::clone(&vms);
             let progress_clone = progress.clone();
             let src = disk.src.clone();
             let dst = disk.dst.clone();
@@ -2415,33 +2571,7 @@ pub async fn clone_node_disks(
                 let _ = progress_clone
                     .send_status(format!("Cloning disk from: {}", src), StatusKind::Progress);
 
-                let conn_for_blocking = conn.clone();
-                let src_for_blocking = src.clone();
-                let dst_for_blocking = dst.clone();
-
-                tokio::task::spawn_blocking(move || -> Result<()> {
-                    libvirt::clone_disk(&conn_for_blocking, &src_for_blocking, &dst_for_blocking)
-                        .with_context(|| {
-                        format!(
-                            "Failed to clone disk from: {} to: {}",
-                            src_for_blocking, dst_for_blocking
-                        )
-                    })?;
-
-                    if let Some(size_gb) = disk_size {
-                        libvirt::resize_disk(&conn_for_blocking, &dst_for_blocking, size_gb)
-                            .with_context(|| {
-                                format!(
-                                    "Failed to resize disk '{}' to {}G",
-                                    dst_for_blocking, size_gb
-                                )
-                            })?;
-                    }
-
-                    Ok(())
-                })
-                .await
-                .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+                vms.clone_volume(&src, &dst, disk_size).await?;
 
                 progress_clone.send_status(format!("Cloned disk to: {}", dst), StatusKind::Done)?;
                 Ok::<(), anyhow::Error>(())
@@ -2465,10 +2595,10 @@ pub async fn clone_node_disks(
 // VM Creation
 // ============================================================================
 
-/// Create a VM from a domain template using libvirt.
-#[instrument(skip(qemu_conn, domain, progress), fields(vm_name = %domain.name), level = "debug")]
+/// Create a VM from a domain template.
+#[instrument(skip(vms, domain, progress), fields(vm_name = %domain.name), level = "debug")]
 pub async fn create_vm(
-    qemu_conn: Arc<libvirt::QemuConnection>,
+    vms: Arc<dyn VmRuntime>,
     domain: template::DomainTemplate,
     progress: &ProgressSender,
 ) -> Result<()> {
@@ -2480,16 +2610,9 @@ pub async fn create_vm(
         .render()
         .with_context(|| format!("Failed to render XML for VM: {}", vm_name))?;
 
-    let conn_for_blocking = qemu_conn.clone();
-    let vm_name_for_blocking = vm_name.clone();
-
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        libvirt::create_vm(&conn_for_blocking, &rendered_xml)
-            .with_context(|| format!("Failed to create VM: {}", vm_name_for_blocking))?;
-        Ok(())
-    })
-    .await
-    .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+    vms.create_domain(&vm_name, rendered_xml)
+        .await
+        .with_context(|| format!("Failed to create VM: {}", vm_name))?;
 
     let _ = progress.send_status(format!("Created VM: {}", vm_name), StatusKind::Done);
 
@@ -2502,9 +2625,9 @@ pub async fn create_vm(
 
 /// Start a container node with all its network attachments.
 #[allow(clippy::too_many_arguments)]
-#[instrument(skip(docker, env_vars, volumes, capabilities, progress), fields(%container_name, %image), level = "debug")]
+#[instrument(skip(containers, env_vars, volumes, capabilities, progress), fields(%container_name, %image), level = "debug")]
 pub async fn start_container_node(
-    docker: &Arc<bollard::Docker>,
+    containers: &dyn ContainerRuntime,
     container_name: &str,
     image: &str,
     env_vars: Vec<String>,
@@ -2519,21 +2642,21 @@ pub async fn start_container_node(
     model: data::NodeModel,
     progress: &ProgressSender,
 ) -> Result<bool> {
-    let is_running = container::run_container(
-        docker,
-        container_name,
-        image,
-        env_vars,
-        volumes,
-        capabilities,
-        management_network_attachment,
-        additional_networks,
-        commands,
-        privileged,
-        shm_size,
-        user,
-    )
-    .await?;
+    let is_running = containers
+        .run_container(
+            container_name,
+            image,
+            env_vars,
+            volumes,
+            capabilities,
+            management_network_attachment,
+            additional_networks,
+            commands,
+            privileged,
+            shm_size,
+            user,
+        )
+        .await?;
 
     if !is_running {
         return Ok(false);
@@ -2542,7 +2665,8 @@ pub async fn start_container_node(
     // SR Linux: flush default namespace IP to avoid DUP pings
     if model == data::NodeModel::NokiaSrlinux {
         let flush_cmd = "for i in $(seq 1 30); do ip netns list 2>/dev/null | grep -q srbase-mgmt && break; sleep 1; done; ip addr flush dev mgmt0";
-        container::exec_container_detached(docker, container_name, vec!["sh", "-c", flush_cmd])
+        containers
+            .exec_detached(container_name, &["sh", "-c", flush_cmd])
             .await
             .with_context(|| {
                 format!(
@@ -2733,10 +2857,10 @@ fn generate_unikernel_cmdline(
     }
 }
 
-/// Create a unikernel from a domain template using libvirt.
-#[instrument(skip(qemu_conn, domain, progress), fields(uk_name = %domain.name), level = "debug")]
+/// Create a unikernel from a domain template.
+#[instrument(skip(vms, domain, progress), fields(uk_name = %domain.name), level = "debug")]
 pub async fn create_unikernel(
-    qemu_conn: Arc<libvirt::QemuConnection>,
+    vms: Arc<dyn VmRuntime>,
     domain: template::UnikernelDomainTemplate,
     progress: &ProgressSender,
 ) -> Result<()> {
@@ -2751,38 +2875,26 @@ pub async fn create_unikernel(
         .render()
         .with_context(|| format!("Failed to render XML for unikernel: {}", uk_name))?;
 
-    let conn_for_blocking = qemu_conn.clone();
-    let uk_name_for_blocking = uk_name.clone();
-
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        libvirt::create_vm(&conn_for_blocking, &rendered_xml)
-            .with_context(|| format!("Failed to create unikernel: {}", uk_name_for_blocking))?;
-        Ok(())
-    })
-    .await
-    .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+    vms.create_domain(&uk_name, rendered_xml)
+        .await
+        .with_context(|| format!("Failed to create unikernel: {}", uk_name))?;
 
     let _ = progress.send_status(format!("Created unikernel: {}", uk_name), StatusKind::Done);
 
     Ok(())
 }
 
-/// Check if a unikernel is ready by verifying libvirt domain state, with optional TCP port probe.
-#[instrument(skip(qemu_conn), level = "debug")]
-pub fn check_unikernel_ready(
-    qemu_conn: &libvirt::QemuConnection,
+/// Check if a unikernel is ready by verifying its domain state, with optional TCP port probe.
+#[instrument(skip(vms), level = "debug")]
+pub async fn check_unikernel_ready(
+    vms: &dyn VmRuntime,
     domain_name: &str,
     ready_port: Option<u16>,
     mgmt_ip: Option<&str>,
 ) -> Result<bool> {
-    // Check if domain is running via libvirt
-    let domain = match virt::domain::Domain::lookup_by_name(qemu_conn, domain_name) {
-        Ok(d) => d,
-        Err(_) => return Ok(false),
-    };
-
-    let (state, _) = domain.get_state()?;
-    if state != virt::sys::VIR_DOMAIN_RUNNING {
+    // Check if domain is running
+    let states = vms.domain_states().await?;
+    if states.get(domain_name) != Some(&VIR_DOMAIN_RUNNING) {
         return Ok(false);
     }
 
@@ -2801,9 +2913,9 @@ pub fn check_unikernel_ready(
 // ============================================================================
 
 /// Destroy a single VM node: destroy domain, undefine, delete disks, and destroy networks.
-#[instrument(skip(qemu_conn), fields(%node_name, %lab_id), level = "debug")]
+#[instrument(skip(vms), fields(%node_name, %lab_id), level = "debug")]
 pub async fn destroy_vm_node(
-    qemu_conn: Arc<libvirt::QemuConnection>,
+    vms: &dyn VmRuntime,
     node_name: &str,
     lab_id: &str,
     node_idx: u16,
@@ -2812,100 +2924,56 @@ pub async fn destroy_vm_node(
     let node_name_with_lab = format!("{}-{}", node_name, lab_id);
 
     // Destroy and undefine VM domain
-    let conn = qemu_conn.clone();
-    let vm_name = node_name_with_lab.clone();
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        if let Ok(domain) = virt::domain::Domain::lookup_by_name(&conn, &vm_name) {
-            if domain.is_active()? {
-                domain.destroy()?;
-            }
-            domain.undefine_flags(VIR_DOMAIN_UNDEFINE_NVRAM)?;
-            tracing::info!(vm_name = %vm_name, "VM destroyed and undefined");
-        }
-        Ok(())
-    })
-    .await
-    .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+    if vms.list_domains().await?.contains(&node_name_with_lab) {
+        vms.destroy_domain(&node_name_with_lab).await?;
+        tracing::info!(vm_name = %node_name_with_lab, "VM destroyed and undefined");
+    }
 
     // Delete VM disks from storage pool
-    let conn = qemu_conn.clone();
-    let disk_prefix = node_name_with_lab;
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        if let Ok(pool) = virt::storage_pool::StoragePool::lookup_by_name(&conn, "sherpa-pool")
-            && let Ok(volumes) = pool.list_all_volumes(0)
-        {
-            for vol in volumes {
-                if let Ok(vol_name) = vol.get_name()
-                    && vol_name.starts_with(&disk_prefix)
-                {
-                    tracing::info!(volume = %vol_name, "Deleting disk");
-                    let _ = vol.delete(0);
-                }
-            }
-        }
-        Ok(())
-    })
-    .await
-    .map_err(|e| anyhow!("Task join error: {:?}", e))??;
-
-    // Destroy per-node isolated network
-    let isolated = node_isolated_network_data(node_name, node_idx, lab_id);
-    let conn = qemu_conn.clone();
-    let iso_net_name = isolated.network_name.clone();
-    tokio::task::spawn_blocking(move || -> Result<()> {
-        if let Ok(net) = virt::network::Network::lookup_by_name(&conn, &iso_net_name) {
-            if net.is_active()? {
-                net.destroy()?;
+    if let Ok(volumes) = vms.list_volumes().await {
+        for vol_name in volumes {
+            if vol_name.starts_with(&node_name_with_lab) {
+                tracing::info!(volume = %vol_name, "Deleting disk");
+                let _ = vms.delete_volume(&vol_name).await;
             }
-            net.undefine()?;
-            tracing::info!(network = %iso_net_name, "Destroyed isolated network");
         }
-        Ok(())
-    })
-    .await
-    .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+    }
 
-    // Destroy per-node reserved network if applicable
+    // Destroy per-node isolated and reserved networks
+    let mut node_networks =
+        vec![node_isolated_network_data(node_name, node_idx, lab_id).network_name];
     if reserved_interface_count > 0 {
-        let reserved = node_reserved_network_data(node_name, node_idx, lab_id);
-        let conn = qemu_conn.clone();
-        let res_net_name = reserved.network_name.clone();
-        tokio::task::spawn_blocking(move || -> Result<()> {
-            if let Ok(net) = virt::network::Network::lookup_by_name(&conn, &res_net_name) {
-                if net.is_active()? {
-                    net.destroy()?;
-                }
-                net.undefine()?;
-                tracing::info!(network = %res_net_name, "Destroyed reserved network");
-            }
-            Ok(())
-        })
-        .await
-        .map_err(|e| anyhow!("Task join error: {:?}", e))??;
+        node_networks.push(node_reserved_network_data(node_name, node_idx, lab_id).network_name);
+    }
+    let networks = vms.list_networks().await?;
+    for network in node_networks {
+        if networks.contains(&network) {
+            vms.delete_network(&network).await?;
+            tracing::info!(network = %network, "Destroyed node network");
+        }
     }
 
     Ok(())
 }
 
 /// Destroy a single container node: kill, remove, and delete associated Docker networks.
-#[instrument(skip(docker), fields(%node_name, %lab_id), level = "debug")]
+#[instrument(skip(containers), fields(%node_name, %lab_id), level = "debug")]
 pub async fn destroy_container_node(
-    docker: &bollard::Docker,
+    containers: &dyn ContainerRuntime,
     node_name: &str,
     lab_id: &str,
 ) -> Result<()> {
     let container_name = format!("{}-{}", node_name, lab_id);
-    let _ = container::kill_container(docker, &container_name).await;
-    let _ = container::remove_container(docker, &container_name).await;
+    let _ = containers.kill_container(&container_name).await;
+    let _ = containers.remove_container(&container_name).await;
 
     // Destroy Docker networks belonging to this node
-    if let Ok(networks) = container::list_networks(docker).await {
-        for net in networks {
-            if let Some(net_name) = net.name
-                && net_name.starts_with(&format!("{}-", node_name))
+    if let Ok(networks) = containers.list_networks().await {
+        for net_name in networks {
+            if net_name.starts_with(&format!("{}-", node_name))
                 && net_name.ends_with(&format!("-{}", lab_id))
             {
-                let _ = container::delete_network(docker, &net_name).await;
+                let _ = containers.delete_network(&net_name).await;
                 tracing::info!(network = %net_name, "Destroyed Docker network for node");
             }
         }
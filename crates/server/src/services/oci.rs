@@ -0,0 +1,817 @@
+//! Pull VM and unikernel images published as OCI artifacts.
+//!
+//! An image is stored in a registry either as a single raw layer (e.g. pushed
+//! with `oras push`) or as a containerdisk, a container image whose layer is a
+//! tar holding the disk under `/disk`. The pull resolves the manifest,
+//! downloads the largest layer while checking its digest, and hands it to the
+//! import pipeline, which unpacks containerdisk layers and converts the disk
+//! to qcow2.
+
+use anyhow::{Context, Result, anyhow, bail};
+use futures_util::StreamExt;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, WWW_AUTHENTICATE};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tracing::instrument;
+use uuid::Uuid;
+
+use shared::data::{
+    DownloadImageRequest, ImportRequest, ImportResponse, NodeConfig, NodeKind, RegistryAuth,
+    StatusKind,
+};
+use shared::konst::{SHERPA_IMAGES_IMPORT_PATH, SHERPA_IMAGES_PATH};
+use shared::util::{create_dir, delete_dirs, file_exists, image_filename, is_tar_archive};
+
+use crate::daemon::state::AppState;
+use crate::services::progress::ProgressSender;
+use crate::services::{import, registry};
+
+/// URL scheme marking a download as an OCI registry reference
+pub const OCI_SCHEME: &str = "oci://";
+
+/// Manifest media types accepted from the registry, single-platform first
+const MANIFEST_MEDIA_TYPES: &str = "application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json, \
+    application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json";
+
+/// Bytes downloaded between progress messages
+const PROGRESS_INTERVAL: u64 = 5 * 1024 * 1024;
+
+/// Whether a download URL is an `oci://` registry reference
+pub fn is_oci_url(url: &str) -> bool {
+    url.starts_with(OCI_SCHEME)
+}
+
+/// An image reference of the form `<registry>/<repository>[:<tag>][@sha256:<digest>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OciReference {
+    pub registry: String,
+    pub repository: String,
+    pub tag: Option<String>,
+    /// Pinned manifest digest, checked against the manifest the registry returns
+    pub digest: Option<String>,
+}
+
+impl OciReference {
+    /// Parse an `oci://` URL.
+    pub fn parse(url: &str) -> Result<Self> {
+        let usage = "expected oci://<registry>/<repository>[:<tag>][@sha256:<digest>]";
+        let rest = url
+            .strip_prefix(OCI_SCHEME)
+            .ok_or_else(|| anyhow!("Invalid OCI reference '{}': {}", url, usage))?;
+        let (registry, rest) = rest
+            .split_once('/')
+            .filter(|(registry, _)| !registry.is_empty())
+            .ok_or_else(|| anyhow!("Invalid OCI reference '{}': {}", url, usage))?;
+
+        let (name, digest) = match rest.split_once('@') {
+            Some((name, digest)) => (name, Some(digest.to_string())),
+            None => (rest, None),
+        };
+        let (repository, tag) = match name.rsplit_once(':') {
+            Some((repository, tag)) => (repository, Some(tag.to_string())),
+            None => (name, None),
+        };
+
+        let valid_repository = !repository.is_empty()
+            && repository.split('/').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c))
+            });
+        if !valid_repository {
+            bail!(
+                "Invalid repository '{}' in OCI reference '{}'",
+                repository,
+                url
+            );
+        }
+        if tag.as_deref().is_some_and(|tag| {
+            tag.is_empty()
+                || tag.len() > 128
+                || !tag
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c))
+        }) {
+            bail!("Invalid tag in OCI reference '{}'", url);
+        }
+        if let Some(digest) = &digest {
+            let valid = digest
+                .strip_prefix("sha256:")
+                .is_some_and(|hex| hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+            if !valid {
+                bail!(
+                    "Invalid digest '{}' in OCI reference '{}': only sha256:<hex> is supported",
+                    digest,
+                    url
+                );
+            }
+        }
+
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            tag,
+            digest,
+        })
+    }
+
+    /// The manifest reference to request: the digest if pinned, else the tag
+    fn manifest_reference(&self) -> &str {
+        self.digest
+            .as_deref()
+            .or(self.tag.as_deref())
+            .unwrap_or("latest")
+    }
+
+    /// Base URL of the repository in the registry API
+    fn base_url(&self) -> String {
+        format!("{}/{}", registry_api_url(&self.registry), self.repository)
+    }
+}
+
+/// Base URL of the registry API. Loopback registries are assumed to
+/// serve plain HTTP, as a local `registry:2` container does.
+fn registry_api_url(registry: &str) -> String {
+    let host = match registry {
+        "docker.io" => "registry-1.docker.io",
+        registry => registry,
+    };
+    let hostname = host
+        .rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+        .map_or(host, |(hostname, _)| hostname);
+    let scheme = if matches!(hostname, "localhost" | "127.0.0.1" | "[::1]") {
+        "http"
+    } else {
+        "https"
+    };
+    format!("{scheme}://{host}/v2")
+}
+
+impl std::fmt::Display for OciReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+        if let Some(tag) = &self.tag {
+            write!(f, ":{tag}")?;
+        }
+        if let Some(digest) = &self.digest {
+            write!(f, "@{digest}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Pull a VM or unikernel image from an OCI registry and import it.
+///
+/// `request.url` must be an `oci://` reference. The layer is downloaded to a
+/// scratch directory and imported like a local file.
+#[instrument(skip(request, state, progress), fields(model = %request.model, version = %request.version))]
+pub async fn pull_oci_image(
+    request: DownloadImageRequest,
+    state: &AppState,
+    progress: ProgressSender,
+) -> Result<ImportResponse> {
+    let config = NodeConfig::get_model(request.model);
+    let kind = config.kind.clone();
+    if kind == NodeKind::Container {
+        bail!(
+            "Model '{}' is a container, use `sherpa server image pull --repo` for container images",
+            request.model
+        );
+    }
+
+    let url = request.url.as_deref().unwrap_or_default();
+    let reference = OciReference::parse(url)?;
+
+    let filename = image_filename(&kind, config.boot_mode.as_ref());
+    let version_disk = format!(
+        "{SHERPA_IMAGES_PATH}/{}/{}/{filename}",
+        request.model, request.version
+    );
+    let mut import_request = ImportRequest {
+        model: request.model,
+        version: request.version.clone(),
+        src: version_disk.clone(),
+        default: request.default,
+        checksum: None,
+        compress: false,
+        sparsify: false,
+    };
+    if file_exists(&version_disk) {
+        // The import reuses the stored image without reading the source
+        let _ = progress.send_status(
+            "Image already exists, skipping pull".to_string(),
+            StatusKind::Info,
+        );
+        return import::import_verified_image(import_request, state, progress).await;
+    }
+
+    let work_dir = format!("{SHERPA_IMAGES_IMPORT_PATH}/{}", Uuid::now_v7());
+    create_dir(&work_dir).context("Failed to create pull work directory")?;
+    let layer_path = format!("{work_dir}/layer");
+
+    let result = async move {
+        let registry_auth = match request.registry_auth {
+            Some(auth) => Some(auth),
+            None => registry::credentials_for(state, &reference.registry).await?,
+        };
+        let manifest_digest =
+            pull_layer(&reference, registry_auth.as_ref(), &layer_path, &progress).await?;
+        tracing::info!(reference = %reference, digest = %manifest_digest, "Pulled OCI image layer");
+
+        if kind == NodeKind::Unikernel && is_tar_archive(&layer_path)? {
+            bail!(
+                "Unikernel images must be pushed as a single raw kernel layer, '{}' is an archive",
+                reference
+            );
+        }
+
+        import_request.src = layer_path;
+        import::import_verified_image(import_request, state, progress).await
+    }
+    .await;
+
+    if let Err(e) = delete_dirs(&work_dir) {
+        tracing::warn!(
+            "Failed to clean up pull work directory {}: {:?}",
+            work_dir,
+            e
+        );
+    }
+
+    result
+}
+
+/// Check that `auth` is accepted by `registry`.
+///
+/// Requests the registry API root, answering its auth challenge with the
+/// credentials, as `docker login` does.
+pub async fn verify_registry_login(registry: &str, auth: &RegistryAuth) -> Result<()> {
+    let reference = OciReference {
+        registry: registry.to_string(),
+        repository: String::new(),
+        tag: None,
+        digest: None,
+    };
+    let mut client = RegistryClient::new(&reference, Some(auth))?;
+    client
+        .get(&format!("{}/", registry_api_url(registry)), None)
+        .await
+        .with_context(|| format!("Login to registry {} failed", registry))?;
+    Ok(())
+}
+
+/// Download the image layer of `reference` to `dest`.
+///
+/// # Returns
+/// The digest of the manifest the layer was taken from
+async fn pull_layer(
+    reference: &OciReference,
+    auth: Option<&RegistryAuth>,
+    dest: &str,
+    progress: &ProgressSender,
+) -> Result<String> {
+    let mut registry = RegistryClient::new(reference, auth)?;
+
+    let _ = progress.send_status(
+        format!("Resolving OCI image {}...", reference),
+        StatusKind::Progress,
+    );
+    let (mut manifest, manifest_digest) = registry
+        .manifest(reference.manifest_reference(), reference.digest.as_deref())
+        .await?;
+    if manifest.is_index() {
+        let platform = select_platform_manifest(&manifest.manifests, host_architecture())?;
+        manifest = registry
+            .manifest(&platform.digest, Some(&platform.digest))
+            .await?
+            .0;
+    }
+    let layer = select_layer(&manifest.layers)?;
+    let _ = progress.send_status(
+        format!(
+            "Resolved {} to {} (layer {}, {:.1} MB)",
+            reference,
+            manifest_digest,
+            layer.digest,
+            layer.size as f64 / 1_048_576.0
+        ),
+        StatusKind::Done,
+    );
+
+    registry.blob(layer, dest, progress).await?;
+    Ok(manifest_digest)
+}
+
+/// How requests to the registry are authenticated
+enum Authorization {
+    None,
+    Basic,
+    Bearer(String),
+}
+
+/// Minimal client for the OCI distribution API of one repository
+struct RegistryClient<'a> {
+    http: Client,
+    base_url: String,
+    repository: &'a str,
+    auth: Option<&'a RegistryAuth>,
+    authorization: Authorization,
+}
+
+impl<'a> RegistryClient<'a> {
+    fn new(reference: &'a OciReference, auth: Option<&'a RegistryAuth>) -> Result<Self> {
+        let http = Client::builder()
+            .user_agent(concat!("sherpa/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("Failed to build registry HTTP client")?;
+        Ok(Self {
+            http,
+            base_url: reference.base_url(),
+            repository: &reference.repository,
+            auth,
+            authorization: Authorization::None,
+        })
+    }
+
+    /// Fetch a manifest, verifying it against `expected_digest` when given.
+    ///
+    /// # Returns
+    /// The manifest and its digest
+    async fn manifest(
+        &mut self,
+        reference: &str,
+        expected_digest: Option<&str>,
+    ) -> Result<(Manifest, String)> {
+        let url = format!("{}/manifests/{reference}", self.base_url);
+        let response = self.get(&url, Some(MANIFEST_MEDIA_TYPES)).await?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let body = response.bytes().await.context("Failed to read manifest")?;
+
+        let digest = format!("sha256:{:x}", Sha256::digest(&body));
+        if let Some(expected) = expected_digest
+            && digest != expected
+        {
+            bail!(
+                "Manifest digest mismatch for {}: expected {}, got {}",
+                self.repository,
+                expected,
+                digest
+            );
+        }
+
+        let mut manifest: Manifest =
+            serde_json::from_slice(&body).context("Failed to parse manifest")?;
+        if manifest.media_type.is_none() {
+            manifest.media_type = content_type;
+        }
+        Ok((manifest, digest))
+    }
+
+    /// Stream a blob to `dest`, verifying its size and digest.
+    async fn blob(
+        &mut self,
+        descriptor: &Descriptor,
+        dest: &str,
+        progress: &ProgressSender,
+    ) -> Result<()> {
+        let expected = descriptor
+            .digest
+            .strip_prefix("sha256:")
+            .ok_or_else(|| anyhow!("Unsupported layer digest '{}'", descriptor.digest))?;
+
+        let url = format!("{}/blobs/{}", self.base_url, descriptor.digest);
+        let response = self.get(&url, None).await?;
+
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .with_context(|| format!("Failed to create file {}", dest))?;
+        let mut hasher = Sha256::new();
+        let mut downloaded: u64 = 0;
+        let mut last_reported: u64 = 0;
+
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Failed to read layer chunk")?;
+            hasher.update(&chunk);
+            file.write_all(&chunk)
+                .await
+                .context("Failed to write chunk to disk")?;
+            downloaded += chunk.len() as u64;
+
+            if downloaded - last_reported >= PROGRESS_INTERVAL {
+                last_reported = downloaded;
+                let percent = (downloaded as f64 / descriptor.size.max(1) as f64 * 100.0) as u64;
+                let _ = progress.send_status(
+                    format!(
+                        "Downloaded {:.1} MB / {:.1} MB ({}%)",
+                        downloaded as f64 / 1_048_576.0,
+                        descriptor.size as f64 / 1_048_576.0,
+                        percent
+                    ),
+                    StatusKind::Progress,
+                );
+            }
+        }
+        file.flush().await.context("Failed to flush layer file")?;
+
+        if downloaded != descriptor.size {
+            bail!(
+                "Layer size mismatch: expected {} bytes, got {}",
+                descriptor.size,
+                downloaded
+            );
+        }
+        let actual = format!("{:x}", hasher.finalize());
+        if actual != expected {
+            bail!(
+                "Layer digest mismatch: expected {}, got sha256:{}",
+                descriptor.digest,
+                actual
+            );
+        }
+
+        let _ = progress.send_status(
+            format!(
+                "Download complete: {:.1} MB, digest verified",
+                downloaded as f64 / 1_048_576.0
+            ),
+            StatusKind::Done,
+        );
+        Ok(())
+    }
+
+    /// GET a registry URL, authenticating on the first 401 challenge.
+    async fn get(&mut self, url: &str, accept: Option<&str>) -> Result<Response> {
+        let response = self.request(url, accept).send().await?;
+        let response = if response.status() == StatusCode::UNAUTHORIZED
+            && matches!(self.authorization, Authorization::None)
+        {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| anyhow!("Registry returned 401 without an auth challenge"))?
+                .to_string();
+            self.authorization = self.authenticate(&challenge).await?;
+            self.request(url, accept).send().await?
+        } else {
+            response
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let hint = match status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if self.auth.is_none() => {
+                    ", the registry may need credentials (--username or `sherpa server registry login`)"
+                }
+                StatusCode::NOT_FOUND => ", check the repository and tag",
+                _ => "",
+            };
+            bail!(
+                "Registry request failed: HTTP {} from {}{}",
+                status,
+                url,
+                hint
+            );
+        }
+        Ok(response)
+    }
+
+    fn request(&self, url: &str, accept: Option<&str>) -> RequestBuilder {
+        let mut request = self.http.get(url);
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        match &self.authorization {
+            Authorization::None => request,
+            Authorization::Basic => match self.auth {
+                Some(auth) => request.basic_auth(&auth.username, Some(&auth.password)),
+                None => request,
+            },
+            Authorization::Bearer(token) => request.bearer_auth(token),
+        }
+    }
+
+    /// Answer a `WWW-Authenticate` challenge.
+    async fn authenticate(&self, challenge: &str) -> Result<Authorization> {
+        let (scheme, params) = parse_challenge(challenge)?;
+        if scheme.eq_ignore_ascii_case("basic") {
+            if self.auth.is_none() {
+                bail!(
+                    "Registry requires credentials, pass --username or store them with `sherpa server registry login`"
+                );
+            }
+            return Ok(Authorization::Basic);
+        }
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            bail!("Unsupported registry auth scheme '{}'", scheme);
+        }
+
+        let realm = params
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .map(|(_, value)| value.as_str())
+            .ok_or_else(|| anyhow!("Registry auth challenge has no realm"))?;
+        let default_scope = format!("repository:{}:pull", self.repository);
+        let mut query: Vec<(&str, &str)> = params
+            .iter()
+            .filter(|(key, _)| key == "service" || key == "scope")
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        // A login check requests the API root, which needs no repository scope
+        if !self.repository.is_empty() && !query.iter().any(|(key, _)| *key == "scope") {
+            query.push(("scope", &default_scope));
+        }
+
+        let url = reqwest::Url::parse_with_params(realm, &query)
+            .with_context(|| format!("Invalid registry auth realm '{}'", realm))?;
+        let mut request = self.http.get(url);
+        if let Some(auth) = self.auth {
+            request = request.basic_auth(&auth.username, Some(&auth.password));
+        }
+        let response = request
+            .send()
+            .await
+            .context("Failed to request registry token")?;
+        if !response.status().is_success() {
+            bail!(
+                "Registry token request failed: HTTP {} from {}",
+                response.status(),
+                realm
+            );
+        }
+        let token: TokenResponse = response
+            .json()
+            .await
+            .context("Failed to parse registry token")?;
+        token
+            .token
+            .or(token.access_token)
+            .map(Authorization::Bearer)
+            .ok_or_else(|| anyhow!("Registry token response has no token"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// An image manifest or, with `manifests` set, an image index
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    media_type: Option<String>,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+impl Manifest {
+    fn is_index(&self) -> bool {
+        self.media_type
+            .as_deref()
+            .is_some_and(|media_type| media_type.contains("index") || media_type.contains("list"))
+            || (!self.manifests.is_empty() && self.layers.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    digest: String,
+    size: u64,
+    platform: Option<Platform>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Platform {
+    architecture: String,
+    os: String,
+}
+
+/// The OCI architecture name of the server
+fn host_architecture() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => arch,
+    }
+}
+
+/// Pick the linux manifest for `architecture` from an image index.
+fn select_platform_manifest<'m>(
+    manifests: &'m [Descriptor],
+    architecture: &str,
+) -> Result<&'m Descriptor> {
+    if let Some(manifest) = manifests.iter().find(|manifest| {
+        manifest
+            .platform
+            .as_ref()
+            .is_some_and(|platform| platform.os == "linux" && platform.architecture == architecture)
+    }) {
+        return Ok(manifest);
+    }
+    match manifests {
+        [only] => Ok(only),
+        _ => bail!(
+            "Image index has no linux/{} manifest (found: {})",
+            architecture,
+            manifests
+                .iter()
+                .filter_map(|manifest| manifest.platform.as_ref())
+                .map(|platform| format!("{}/{}", platform.os, platform.architecture))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// The layer holding the image: the only layer, or the largest if there are
+/// several, e.g. a containerdisk built on a base image.
+fn select_layer(layers: &[Descriptor]) -> Result<&Descriptor> {
+    layers
+        .iter()
+        .max_by_key(|layer| layer.size)
+        .ok_or_else(|| anyhow!("Image manifest has no layers"))
+}
+
+/// Split a `WWW-Authenticate` header into its scheme and parameters.
+fn parse_challenge(header: &str) -> Result<(String, Vec<(String, String)>)> {
+    let header = header.trim();
+    let (scheme, rest) = header.split_once(' ').unwrap_or((header, ""));
+    if scheme.is_empty() {
+        bail!("Empty registry auth challenge");
+    }
+
+    let mut params = Vec::new();
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ',' || c.is_whitespace()).is_some() {}
+        let key: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        if key.is_empty() {
+            break;
+        }
+        chars.next();
+        let value: String = if chars.next_if_eq(&'"').is_some() {
+            let value = std::iter::from_fn(|| chars.next_if(|c| *c != '"')).collect();
+            chars.next();
+            value
+        } else {
+            std::iter::from_fn(|| chars.next_if(|c| *c != ',')).collect()
+        };
+        params.push((key.trim().to_ascii_lowercase(), value));
+    }
+
+    Ok((scheme.to_string(), params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn descriptor(digest: &str, size: u64, platform: Option<(&str, &str)>) -> Descriptor {
+        Descriptor {
+            digest: digest.to_string(),
+            size,
+            platform: platform.map(|(os, architecture)| Platform {
+                architecture: architecture.to_string(),
+                os: os.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_parse_reference_with_tag() {
+        let reference = OciReference::parse("oci://registry.lab:5000/nos/veos:4.32").unwrap();
+        assert_eq!(reference.registry, "registry.lab:5000");
+        assert_eq!(reference.repository, "nos/veos");
+        assert_eq!(reference.tag.as_deref(), Some("4.32"));
+        assert_eq!(reference.digest, None);
+        assert_eq!(reference.manifest_reference(), "4.32");
+        assert_eq!(
+            reference.base_url(),
+            "https://registry.lab:5000/v2/nos/veos"
+        );
+    }
+
+    #[test]
+    fn test_parse_reference_with_digest() {
+        let url = format!("oci://localhost:5000/nos:1.2@{DIGEST}");
+        let reference = OciReference::parse(&url).unwrap();
+        assert_eq!(reference.tag.as_deref(), Some("1.2"));
+        assert_eq!(reference.digest.as_deref(), Some(DIGEST));
+        assert_eq!(reference.manifest_reference(), DIGEST);
+        assert_eq!(reference.base_url(), "http://localhost:5000/v2/nos");
+        assert_eq!(
+            reference.to_string(),
+            format!("localhost:5000/nos:1.2@{DIGEST}")
+        );
+    }
+
+    #[test]
+    fn test_parse_reference_defaults_to_latest() {
+        let reference = OciReference::parse("oci://docker.io/library/cirros").unwrap();
+        assert_eq!(reference.manifest_reference(), "latest");
+        assert_eq!(
+            reference.base_url(),
+            "https://registry-1.docker.io/v2/library/cirros"
+        );
+    }
+
+    #[test]
+    fn test_parse_reference_rejects_invalid() {
+        assert!(OciReference::parse("https://registry/nos:1.2").is_err());
+        assert!(OciReference::parse("oci://registry").is_err());
+        assert!(OciReference::parse("oci:///nos:1.2").is_err());
+        assert!(OciReference::parse("oci://registry/NOS:1.2").is_err());
+        assert!(OciReference::parse("oci://registry/nos:").is_err());
+        assert!(OciReference::parse("oci://registry/nos@sha256:abc").is_err());
+        assert!(OciReference::parse("oci://registry/nos@md5:abc").is_err());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let (scheme, params) = parse_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:nos:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(scheme, "Bearer");
+        assert_eq!(
+            params,
+            vec![
+                (
+                    "realm".to_string(),
+                    "https://auth.docker.io/token".to_string()
+                ),
+                ("service".to_string(), "registry.docker.io".to_string()),
+                ("scope".to_string(), "repository:nos:pull,push".to_string()),
+            ]
+        );
+
+        let (scheme, params) = parse_challenge(r#"Basic realm="Registry""#).unwrap();
+        assert_eq!(scheme, "Basic");
+        assert_eq!(params, vec![("realm".to_string(), "Registry".to_string())]);
+    }
+
+    #[test]
+    fn test_select_platform_manifest() {
+        let manifests = vec![
+            descriptor("sha256:arm", 1, Some(("linux", "arm64"))),
+            descriptor("sha256:amd", 1, Some(("linux", "amd64"))),
+        ];
+        assert_eq!(
+            select_platform_manifest(&manifests, "amd64")
+                .unwrap()
+                .digest,
+            "sha256:amd"
+        );
+        assert!(select_platform_manifest(&manifests, "s390x").is_err());
+
+        let single = vec![descriptor("sha256:only", 1, None)];
+        assert_eq!(
+            select_platform_manifest(&single, "amd64").unwrap().digest,
+            "sha256:only"
+        );
+    }
+
+    #[test]
+    fn test_select_layer_picks_largest() {
+        let layers = vec![
+            descriptor("sha256:base", 10, None),
+            descriptor("sha256:disk", 1000, None),
+        ];
+        assert_eq!(select_layer(&layers).unwrap().digest, "sha256:disk");
+        assert!(select_layer(&[]).is_err());
+    }
+
+    #[test]
+    fn test_manifest_is_index() {
+        let index: Manifest = serde_json::from_str(
+            r#"{"mediaType":"application/vnd.oci.image.index.v1+json","manifests":[]}"#,
+        )
+        .unwrap();
+        assert!(index.is_index());
+
+        let manifest: Manifest = serde_json::from_str(&format!(
+            r#"{{"schemaVersion":2,"config":{{"digest":"{DIGEST}","size":2}},"layers":[{{"mediaType":"application/vnd.oci.image.layer.v1.tar","digest":"{DIGEST}","size":5}}]}}"#
+        ))
+        .unwrap();
+        assert!(!manifest.is_index());
+        assert_eq!(manifest.layers[0].size, 5);
+    }
+}
@@ -1,6 +1,5 @@
 // Server-side implementation of the redeploy operation for a single node
 
-use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow, bail};
@@ -9,13 +8,17 @@ use opentelemetry::KeyValue;
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::services::bridge_vlan;
+use crate::services::custom_model;
+use crate::services::link_filter;
+use crate::services::mirror;
 use crate::services::node_ops;
 use crate::services::progress::ProgressSender;
 
 use shared::data;
 use shared::data::{NodeState, RedeployRequest, RedeployResponse, StatusKind};
 use shared::konst::{
-    BRIDGE_PREFIX, CONTAINER_VETH_PREFIX, KVM_OUI, LAB_FILE_NAME, READINESS_SLEEP,
+    BRIDGE_PREFIX, CONTAINER_VETH_PREFIX, KVM_OUI, LAB_FILE_NAME, MTU_JUMBO_NET, READINESS_SLEEP,
     READINESS_TIMEOUT, SHERPA_LABS_PATH, SHERPA_MANAGEMENT_NETWORK_NAME, SSH_PORT, TAP_PREFIX,
     TFTP_DIR, ZTP_DIR,
 };
@@ -56,8 +59,11 @@ pub async fn redeploy_node(
     );
 
     // Parse manifest
-    let manifest: topology::Manifest =
-        serde_json::from_value(request.manifest).context("Failed to deserialize manifest")?;
+    let mut manifest = topology::Manifest::from_json(request.manifest)
+        .context("Failed to deserialize manifest")?;
+    let custom_models = custom_model::resolve_manifest_models(&mut manifest.nodes)
+        .context("Failed to resolve custom models")?;
+    let bridge_taps = topology::bridge_port_taps(&manifest.bridges, lab_id);
 
     // Find the target node in the manifest
     let manifest_nodes: Vec<topology::NodeExpanded> = manifest
@@ -67,6 +73,11 @@ pub async fn redeploy_node(
         .map(|(idx, node)| topology::NodeExpanded {
             name: node.name.clone(),
             model: node.model,
+            custom_model: node
+                .custom_model
+                .as_ref()
+                .and_then(|name| custom_models.get(name))
+                .cloned(),
             index: idx as u16 + 1,
             version: node.version.clone(),
             memory: node.memory,
@@ -93,6 +104,8 @@ pub async fn redeploy_node(
             user_scripts: node.user_scripts_data.clone(),
             kernel_cmdline: node.kernel_cmdline.clone(),
             ready_port: node.ready_port,
+            mtu: node.mtu,
+            interface_mtus: vec![],
         })
         .collect();
 
@@ -136,8 +149,11 @@ pub async fn redeploy_node(
 
     let node_image = node_images
         .first()
-        .ok_or_else(|| anyhow!("Node image config not found for node '{}'", node_name))?
-        .clone();
+        .ok_or_else(|| anyhow!("Node image config not found for node '{}'", node_name))?;
+    let node_image = match &target_node.custom_model {
+        Some(custom) => custom.apply(node_image),
+        None => node_image.clone(),
+    };
     let data_interface_count = validate::effective_data_interface_count(
         node_name,
         target_node.data_interface_count,
@@ -147,6 +163,12 @@ pub async fn redeploy_node(
         "Data interface count validation failed for node: {}",
         node_name
     ))?;
+    let first_data_idx = node_image.reserved_interface_count.saturating_add(1);
+    let last_data_idx = node_image
+        .reserved_interface_count
+        .saturating_add(data_interface_count);
+    target_node.interface_mtus = target_node
+        .data_interface_mtus(first_data_idx..=last_data_idx, &manifest.expanded_links()?)?;
 
     // Load lab info from filesystem
     let lab_dir = format!("{SHERPA_LABS_PATH}/{lab_id}");
@@ -206,14 +228,8 @@ pub async fn redeploy_node(
         StatusKind::Done,
     );
 
-    // Get connections
-    let docker_conn = state.docker.clone();
-    let qemu_conn = Arc::new(
-        state
-            .qemu
-            .connect()
-            .context("Failed to connect to libvirt")?,
-    );
+    // VM, container and host network backends
+    let runtime = &state.runtime;
 
     // ========================================================================
     // Stage 2: Destroy existing node
@@ -226,7 +242,7 @@ pub async fn redeploy_node(
     match node_image.kind {
         data::NodeKind::VirtualMachine => {
             node_ops::destroy_vm_node(
-                qemu_conn.clone(),
+                runtime.vms.as_ref(),
                 node_name,
                 lab_id,
                 node_idx,
@@ -235,7 +251,8 @@ pub async fn redeploy_node(
             .await?;
         }
         data::NodeKind::Container => {
-            node_ops::destroy_container_node(&docker_conn, node_name, lab_id).await?;
+            node_ops::destroy_container_node(runtime.containers.as_ref(), node_name, lab_id)
+                .await?;
         }
         data::NodeKind::Unikernel => {
             bail!("Redeploy not supported for unikernel nodes");
@@ -264,19 +281,17 @@ pub async fn redeploy_node(
         );
 
         let isolated = node_ops::node_isolated_network_data(node_name, node_idx, lab_id);
-        let iso_network = libvirt::IsolatedNetwork {
-            network_name: isolated.network_name,
-            bridge_name: isolated.bridge_name,
-        };
-        iso_network.create(&qemu_conn)?;
+        runtime
+            .vms
+            .create_isolated_network(&isolated.network_name, &isolated.bridge_name)
+            .await?;
 
         if node_image.reserved_interface_count > 0 {
             let reserved = node_ops::node_reserved_network_data(node_name, node_idx, lab_id);
-            let res_network = libvirt::ReservedNetwork {
-                network_name: reserved.network_name,
-                bridge_name: reserved.bridge_name,
-            };
-            res_network.create(&qemu_conn)?;
+            runtime
+                .vms
+                .create_reserved_network(&reserved.network_name, &reserved.bridge_name)
+                .await?;
         }
 
         let _ = progress.send_status(
@@ -366,14 +381,19 @@ pub async fn redeploy_node(
                     if link.node_a == node_record_id {
                         let container_veth =
                             format!("{}a{}-{}", CONTAINER_VETH_PREFIX, link.index, lab_id);
-                        network::create_veth_pair(
-                            &link.tap_a,
-                            &container_veth,
-                            &format!("{}-p2p-host-{}::{}", lab_id, node_name, link.int_a),
-                            &format!("{}-p2p-ctr-{}::{}", lab_id, node_name, link.int_a),
-                        )
-                        .await?;
-                        let iface_idx = util::interface_to_idx(&target_node.model, &link.int_a)?;
+                        runtime
+                            .network
+                            .create_veth_pair(
+                                &link.tap_a,
+                                &container_veth,
+                                &format!("{}-p2p-host-{}::{}", lab_id, node_name, link.int_a),
+                                &format!("{}-p2p-ctr-{}::{}", lab_id, node_name, link.int_a),
+                                target_node
+                                    .interface_mtu(&link.int_a)
+                                    .unwrap_or(MTU_JUMBO_NET),
+                            )
+                            .await?;
+                        let iface_idx = target_node.interface_to_idx(&link.int_a)?;
                         p2p_container_veths.push(P2pContainerVeth {
                             host_veth: link.tap_a.clone(),
                             container_veth,
@@ -385,14 +405,19 @@ pub async fn redeploy_node(
                     if link.node_b == node_record_id {
                         let container_veth =
                             format!("{}b{}-{}", CONTAINER_VETH_PREFIX, link.index, lab_id);
-                        network::create_veth_pair(
-                            &link.tap_b,
-                            &container_veth,
-                            &format!("{}-p2p-host-{}::{}", lab_id, node_name, link.int_b),
-                            &format!("{}-p2p-ctr-{}::{}", lab_id, node_name, link.int_b),
-                        )
-                        .await?;
-                        let iface_idx = util::interface_to_idx(&target_node.model, &link.int_b)?;
+                        runtime
+                            .network
+                            .create_veth_pair(
+                                &link.tap_b,
+                                &container_veth,
+                                &format!("{}-p2p-host-{}::{}", lab_id, node_name, link.int_b),
+                                &format!("{}-p2p-ctr-{}::{}", lab_id, node_name, link.int_b),
+                                target_node
+                                    .interface_mtu(&link.int_b)
+                                    .unwrap_or(MTU_JUMBO_NET),
+                            )
+                            .await?;
+                        let iface_idx = target_node.interface_to_idx(&link.int_b)?;
                         p2p_container_veths.push(P2pContainerVeth {
                             host_veth: link.tap_b.clone(),
                             container_veth,
@@ -414,13 +439,18 @@ pub async fn redeploy_node(
                     }
                     let host_veth = format!("cd{}i{}-{}", node_idx, idx, lab_id);
                     let container_veth = format!("ce{}i{}-{}", node_idx, idx, lab_id);
-                    network::create_veth_pair(
-                        &host_veth,
-                        &container_veth,
-                        &format!("{}-p2p-disabled-host-{}::{}", lab_id, node_name, idx),
-                        &format!("{}-p2p-disabled-ctr-{}::{}", lab_id, node_name, idx),
-                    )
-                    .await?;
+                    runtime
+                        .network
+                        .create_veth_pair(
+                            &host_veth,
+                            &container_veth,
+                            &format!("{}-p2p-disabled-host-{}::{}", lab_id, node_name, idx),
+                            &format!("{}-p2p-disabled-ctr-{}::{}", lab_id, node_name, idx),
+                            target_node
+                                .interface_mtu(&target_node.interface_from_idx(idx)?)
+                                .unwrap_or(MTU_JUMBO_NET),
+                        )
+                        .await?;
                     p2p_container_veths.push(P2pContainerVeth {
                         host_veth,
                         container_veth,
@@ -452,16 +482,14 @@ pub async fn redeploy_node(
                 let isolated = node_ops::node_isolated_network_data(node_name, node_idx, lab_id);
 
                 for idx in first_data_idx..=max_iface_idx {
-                    let iface_name = util::interface_from_idx(&target_node.model, idx)?;
+                    let iface_name = target_node.interface_from_idx(idx)?;
 
                     if let Some((docker_net_name, bridge_name)) = iface_net_lookup.get(&iface_name)
                     {
-                        container::create_docker_macvlan_network(
-                            &docker_conn,
-                            bridge_name,
-                            docker_net_name,
-                        )
-                        .await?;
+                        runtime
+                            .containers
+                            .create_macvlan_network(bridge_name, docker_net_name)
+                            .await?;
 
                         let linux_interface_name =
                             if target_node.model == data::NodeModel::NokiaSrlinux {
@@ -478,12 +506,10 @@ pub async fn redeploy_node(
                         });
                     } else {
                         let docker_net_name = format!("{}-iso{}-{}", node_name, idx, lab_id);
-                        container::create_docker_macvlan_bridge_network(
-                            &docker_conn,
-                            &isolated.bridge_name,
-                            &docker_net_name,
-                        )
-                        .await?;
+                        runtime
+                            .containers
+                            .create_macvlan_bridge_network(&isolated.bridge_name, &docker_net_name)
+                            .await?;
 
                         let linux_interface_name =
                             util::interface_from_idx(&target_node.model, idx)
@@ -515,7 +541,7 @@ pub async fn redeploy_node(
             };
 
             let is_running = node_ops::start_container_node(
-                &docker_conn,
+                runtime.containers.as_ref(),
                 &container_name,
                 &container_image,
                 ztp_result.env_vars,
@@ -541,10 +567,13 @@ pub async fn redeploy_node(
 
             // P2p post-start: move veths into container netns and attach eBPF
             if is_p2p_container && !p2p_container_veths.is_empty() {
-                let pid = container::get_container_pid(&docker_conn, &container_name).await?;
+                let pid = runtime.containers.container_pid(&container_name).await?;
 
                 for veth_info in &p2p_container_veths {
-                    network::move_to_netns(&veth_info.container_veth, pid).await?;
+                    runtime
+                        .network
+                        .move_to_netns(&veth_info.container_veth, pid)
+                        .await?;
 
                     let target_name = if veth_info.node_model == data::NodeModel::NokiaSrlinux {
                         let iface_name = util::interface_from_idx(
@@ -567,23 +596,24 @@ pub async fn redeploy_node(
                             veth_info.container_veth, target_name, target_name, target_name
                         )
                     };
-                    container::exec_container_with_retry(
-                        &docker_conn,
-                        &container_name,
-                        vec!["sh", "-c", &setup_cmd],
-                        3,
-                        Duration::from_secs(2),
-                    )
-                    .await
-                    .with_context(|| {
-                        format!(
-                            "Failed to configure P2p interface {} in container {}",
-                            target_name, container_name
+                    runtime
+                        .containers
+                        .exec_with_retry(
+                            &container_name,
+                            &["sh", "-c", &setup_cmd],
+                            3,
+                            Duration::from_secs(2),
                         )
-                    })?;
+                        .await
+                        .with_context(|| {
+                            format!(
+                                "Failed to configure P2p interface {} in container {}",
+                                target_name, container_name
+                            )
+                        })?;
 
                     if veth_info.admin_down {
-                        network::set_link_down(&veth_info.host_veth).await?;
+                        runtime.network.set_link_down(&veth_info.host_veth).await?;
                     }
 
                     tracing::info!(
@@ -605,16 +635,15 @@ pub async fn redeploy_node(
                         continue;
                     }
 
-                    let ifindex_a = network::get_ifindex(&link.tap_a)
+                    runtime
+                        .network
+                        .attach_p2p_redirect(&link.tap_a, &link.tap_b)
                         .await
-                        .context(format!("failed to get ifindex for {}", link.tap_a))?;
-                    let ifindex_b = network::get_ifindex(&link.tap_b)
-                        .await
-                        .context(format!("failed to get ifindex for {}", link.tap_b))?;
-
-                    network::attach_p2p_redirect(&link.tap_a, ifindex_b)
                         .context(format!("failed to attach eBPF redirect on {}", link.tap_a))?;
-                    network::attach_p2p_redirect(&link.tap_b, ifindex_a)
+                    runtime
+                        .network
+                        .attach_p2p_redirect(&link.tap_b, &link.tap_a)
+                        .await
                         .context(format!("failed to attach eBPF redirect on {}", link.tap_b))?;
 
                     // Re-apply link impairment if configured
@@ -626,8 +655,13 @@ pub async fn redeploy_node(
                             reorder_percent: 0.0,
                             corrupt_percent: 0.0,
                         };
-                        network::apply_netem(ifindex_a as i32, &netem).await?;
-                        network::apply_netem(ifindex_b as i32, &netem).await?;
+                        runtime.network.apply_netem(&link.tap_a, &netem).await?;
+                        runtime.network.apply_netem(&link.tap_b, &netem).await?;
+                    }
+
+                    // The new programs start with an empty filter
+                    if !link.filter.is_empty() {
+                        link_filter::apply_link_filter(link, runtime.network.as_ref()).await?;
                     }
 
                     tracing::info!(
@@ -677,7 +711,7 @@ pub async fn redeploy_node(
                 StatusKind::Progress,
             );
 
-            node_ops::clone_node_disks(qemu_conn.clone(), vm_ztp.clone_disks, lab_id, &progress)
+            node_ops::clone_node_disks(runtime.vms.clone(), vm_ztp.clone_disks, lab_id, &progress)
                 .await?;
 
             // Build interfaces from DB links
@@ -723,7 +757,10 @@ pub async fn redeploy_node(
                 .checked_add(data_interface_count)
                 .ok_or_else(|| anyhow!("Data interface count overflow for node {}", node_name))?;
             for idx in first_data_interface_idx..=max_interface_idx {
-                let interface_name = util::interface_from_idx(&target_node.model, idx)?;
+                let interface_name = target_node.interface_from_idx(idx)?;
+                let mtu = target_node
+                    .interface_mtu(&interface_name)
+                    .unwrap_or(node_image.interface_mtu);
 
                 // Check if this interface has a link
                 let mut found_link = false;
@@ -757,7 +794,7 @@ pub async fn redeploy_node(
                         interfaces.push(data::Interface {
                             name: iface_name,
                             num: idx,
-                            mtu: node_image.interface_mtu,
+                            mtu,
                             mac_address: util::random_mac(KVM_OUI),
                             connection_type: conn_type,
                             interface_connection: Some(interface_connection),
@@ -794,7 +831,7 @@ pub async fn redeploy_node(
                         interfaces.push(data::Interface {
                             name: iface_name,
                             num: idx,
-                            mtu: node_image.interface_mtu,
+                            mtu,
                             mac_address: util::random_mac(KVM_OUI),
                             connection_type: conn_type,
                             interface_connection: Some(interface_connection),
@@ -808,14 +845,28 @@ pub async fn redeploy_node(
                 if !found_link {
                     let db_bridges = db::list_bridges(&db, &lab_record_id).await?;
                     let mut found_bridge = false;
+                    if let Some(tap) = bridge_taps
+                        .iter()
+                        .find(|tap| tap.node == *node_name && tap.interface == interface_name)
+                    {
+                        interfaces.push(data::Interface {
+                            name: tap.tap_name.clone(),
+                            num: idx,
+                            mtu,
+                            mac_address: util::random_mac(KVM_OUI),
+                            connection_type: data::ConnectionTypes::VlanBridge,
+                            interface_connection: None,
+                        });
+                        found_bridge = true;
+                    }
                     for bridge in &db_bridges {
-                        if bridge.nodes.contains(&node_record_id) {
+                        if !found_bridge && bridge.nodes.contains(&node_record_id) {
                             // Check if this node's interface connects to this bridge
                             // For now, treat as bridge interface
                             interfaces.push(data::Interface {
                                 name: bridge.bridge_name.clone(),
                                 num: idx,
-                                mtu: node_image.interface_mtu,
+                                mtu,
                                 mac_address: util::random_mac(KVM_OUI),
                                 connection_type: data::ConnectionTypes::PrivateBridge,
                                 interface_connection: None,
@@ -828,9 +879,9 @@ pub async fn redeploy_node(
                     if !found_bridge {
                         // Disabled interface - connected to isolated network
                         interfaces.push(data::Interface {
-                            name: util::dasher(&util::interface_from_idx(&target_node.model, idx)?),
+                            name: util::dasher(&target_node.interface_from_idx(idx)?),
                             num: idx,
-                            mtu: node_image.interface_mtu,
+                            mtu,
                             mac_address: util::random_mac(KVM_OUI),
                             connection_type: data::ConnectionTypes::Disabled,
                             interface_connection: None,
@@ -868,7 +919,7 @@ pub async fn redeploy_node(
             let _ =
                 progress.send_status(format!("Creating VM: {}", node_name), StatusKind::Progress);
 
-            node_ops::create_vm(qemu_conn.clone(), domain, &progress).await?;
+            node_ops::create_vm(runtime.vms.clone(), domain, &progress).await?;
 
             // Re-attach eBPF redirect on P2p links involving this VM
             let p2p_vm_links: Vec<_> = db_links
@@ -889,16 +940,15 @@ pub async fn redeploy_node(
                 );
 
                 for link in &p2p_vm_links {
-                    let ifindex_a = network::get_ifindex(&link.tap_a)
-                        .await
-                        .context(format!("failed to get ifindex for {}", link.tap_a))?;
-                    let ifindex_b = network::get_ifindex(&link.tap_b)
+                    runtime
+                        .network
+                        .attach_p2p_redirect(&link.tap_a, &link.tap_b)
                         .await
-                        .context(format!("failed to get ifindex for {}", link.tap_b))?;
-
-                    network::attach_p2p_redirect(&link.tap_a, ifindex_b)
                         .context(format!("failed to attach eBPF redirect on {}", link.tap_a))?;
-                    network::attach_p2p_redirect(&link.tap_b, ifindex_a)
+                    runtime
+                        .network
+                        .attach_p2p_redirect(&link.tap_b, &link.tap_a)
+                        .await
                         .context(format!("failed to attach eBPF redirect on {}", link.tap_b))?;
 
                     if link.delay_us > 0 || link.loss_percent > 0.0 {
@@ -909,8 +959,13 @@ pub async fn redeploy_node(
                             reorder_percent: 0.0,
                             corrupt_percent: 0.0,
                         };
-                        network::apply_netem(ifindex_a as i32, &netem).await?;
-                        network::apply_netem(ifindex_b as i32, &netem).await?;
+                        runtime.network.apply_netem(&link.tap_a, &netem).await?;
+                        runtime.network.apply_netem(&link.tap_b, &netem).await?;
+                    }
+
+                    // The new programs start with an empty filter
+                    if !link.filter.is_empty() {
+                        link_filter::apply_link_filter(link, runtime.network.as_ref()).await?;
                     }
 
                     tracing::info!(
@@ -922,9 +977,20 @@ pub async fn redeploy_node(
                 }
             }
 
+            // The new taps of VLAN-aware bridge ports are not attached yet
+            bridge_vlan::apply_bridge_port_vlans(
+                &bridge_taps,
+                std::slice::from_ref(node_name),
+                runtime.network.as_ref(),
+            )
+            .await?;
+
             // Set isolated bridge DOWN to remove carrier from disabled VM interfaces
             if has_disabled {
-                network::set_link_down(&isolated_net.bridge_name).await?;
+                runtime
+                    .network
+                    .set_link_down(&isolated_net.bridge_name)
+                    .await?;
             }
 
             // Stage 6: Readiness check
@@ -981,6 +1047,23 @@ pub async fn redeploy_node(
         }
     }
 
+    // Re-attached redirect programs start without the manifest's mirrors
+    match mirror::start_manifest_mirrors(lab_id, &lab_record_id, state).await {
+        Ok(failures) => {
+            for failure in failures {
+                let _ = progress.send_status(
+                    format!("Failed to restart port mirror {failure}"),
+                    StatusKind::Info,
+                );
+            }
+        }
+        Err(e) => tracing::warn!(
+            lab_id = %lab_id,
+            error = ?e,
+            "Failed to restart port mirrors after redeploy"
+        ),
+    }
+
     let total_time = start_time.elapsed().as_secs();
 
     state.metrics.operation_duration.record(
@@ -0,0 +1,240 @@
+//! Credentials for private container registries.
+//!
+//! Admins store one set of credentials per registry host. Passwords are
+//! encrypted with AES-256-GCM under a server key kept next to the JWT secret,
+//! so a database dump alone does not reveal them. Image pulls look up the
+//! credentials of the registry the image is hosted on.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use aws_lc_rs::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::RngCore;
+use tracing::instrument;
+
+use shared::data::{
+    DbRegistryCredential, ListRegistriesResponse, RegistryAuth, RegistryCredentialInfo,
+    RegistryLoginRequest, RegistryLoginResponse, RegistryLogoutRequest, RegistryLogoutResponse,
+    normalize_registry,
+};
+use shared::konst::REGISTRY_KEY_PATH;
+
+use crate::auth::jwt;
+use crate::daemon::state::AppState;
+use crate::services::oci;
+
+/// Length of the registry credential key (AES-256)
+const KEY_LEN: usize = 32;
+
+/// Load or generate the key that encrypts registry passwords.
+///
+/// Unlike the JWT secret, an unreadable key is not replaced, as that would
+/// make every stored password undecryptable.
+///
+/// # Errors
+/// Returns an error if the key file cannot be read or written, or has the
+/// wrong length.
+pub fn load_or_generate_key() -> Result<Vec<u8>> {
+    let key_path = PathBuf::from(REGISTRY_KEY_PATH);
+    if !key_path.exists() {
+        tracing::info!(
+            "Registry key not found. Generating new key at {}",
+            REGISTRY_KEY_PATH
+        );
+        return jwt::generate_and_save_secret(&key_path);
+    }
+
+    let key = fs::read(&key_path).context("Failed to read registry key file")?;
+    if key.len() != KEY_LEN {
+        bail!(
+            "Registry key file {} has unexpected length: {} bytes (expected {})",
+            REGISTRY_KEY_PATH,
+            key.len(),
+            KEY_LEN
+        );
+    }
+    Ok(key)
+}
+
+fn cipher(key: &[u8]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&AES_256_GCM, key)
+        .map_err(|_| anyhow!("Invalid registry key length: {} bytes", key.len()))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypt a password, binding it to its registry.
+///
+/// # Returns
+/// Base64 of a random nonce followed by the ciphertext and tag
+fn encrypt_password(key: &[u8], registry: &str, password: &str) -> Result<String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut sealed = password.as_bytes().to_vec();
+    cipher(key)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(registry.as_bytes()),
+            &mut sealed,
+        )
+        .map_err(|_| anyhow!("Failed to encrypt password for registry {}", registry))?;
+
+    let mut encrypted = nonce.to_vec();
+    encrypted.extend_from_slice(&sealed);
+    Ok(BASE64.encode(encrypted))
+}
+
+/// Decrypt a password stored by [`encrypt_password`].
+fn decrypt_password(key: &[u8], registry: &str, encrypted: &str) -> Result<String> {
+    let decoded = BASE64
+        .decode(encrypted)
+        .context(format!("Corrupt stored password for registry {}", registry))?;
+    if decoded.len() < NONCE_LEN {
+        bail!("Corrupt stored password for registry {}", registry);
+    }
+    let (nonce, sealed) = decoded.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow!("Corrupt stored password for registry {}", registry))?;
+
+    let mut sealed = sealed.to_vec();
+    let password = cipher(key)?
+        .open_in_place(nonce, Aad::from(registry.as_bytes()), &mut sealed)
+        .map_err(|_| {
+            anyhow!(
+                "Failed to decrypt password for registry {}, was the registry key replaced?",
+                registry
+            )
+        })?;
+    String::from_utf8(password.to_vec())
+        .context(format!("Corrupt stored password for registry {}", registry))
+}
+
+/// Convert stored credentials to their display form.
+fn credential_info(credential: &DbRegistryCredential) -> RegistryCredentialInfo {
+    RegistryCredentialInfo {
+        registry: credential.registry.clone(),
+        username: credential.username.clone(),
+        created_at: credential.created_at.as_second(),
+        updated_at: credential.updated_at.as_second(),
+    }
+}
+
+/// Verify and store the credentials for a registry
+///
+/// The credentials are checked against the registry before they are saved,
+/// replacing any credentials already stored for it.
+///
+/// # Errors
+/// Returns error if:
+/// - The registry host or username is invalid
+/// - The registry rejects the credentials or cannot be reached
+/// - Encryption or the database operation fails
+#[instrument(skip(request, state), fields(registry = %request.registry, username = %request.username))]
+pub async fn registry_login(
+    request: RegistryLoginRequest,
+    state: &AppState,
+) -> Result<RegistryLoginResponse> {
+    let registry = normalize_registry(&request.registry)?;
+    let username = request.username.trim();
+    if username.is_empty() || request.password.is_empty() {
+        bail!("Username and password are required");
+    }
+
+    let auth = RegistryAuth {
+        username: username.to_string(),
+        password: request.password,
+    };
+    oci::verify_registry_login(&registry, &auth).await?;
+
+    let encrypted = encrypt_password(&state.registry_key, &registry, &auth.password)?;
+    let replaced = db::get_registry_credential(&state.db, &registry)
+        .await?
+        .is_some();
+    db::upsert_registry_credential(&state.db, &registry, username, &encrypted).await?;
+
+    tracing::info!(%registry, %username, replaced, "Stored registry credentials");
+    Ok(RegistryLoginResponse {
+        registry,
+        username: auth.username,
+        replaced,
+    })
+}
+
+/// List the stored registry credentials, without passwords
+#[instrument(skip(state))]
+pub async fn list_registries(state: &AppState) -> Result<ListRegistriesResponse> {
+    let credentials = db::list_registry_credentials(&state.db).await?;
+    Ok(ListRegistriesResponse {
+        registries: credentials.iter().map(credential_info).collect(),
+    })
+}
+
+/// Remove the stored credentials of a registry
+#[instrument(skip(request, state), fields(registry = %request.registry))]
+pub async fn registry_logout(
+    request: RegistryLogoutRequest,
+    state: &AppState,
+) -> Result<RegistryLogoutResponse> {
+    let registry = normalize_registry(&request.registry)?;
+    let removed = db::delete_registry_credential(&state.db, &registry).await?;
+    if removed {
+        tracing::info!(%registry, "Removed registry credentials");
+    }
+    Ok(RegistryLogoutResponse { registry, removed })
+}
+
+/// The stored credentials for `registry`, if any.
+///
+/// Used to authenticate image pulls, which pass the registry host of the image.
+pub async fn credentials_for(state: &AppState, registry: &str) -> Result<Option<RegistryAuth>> {
+    let registry = normalize_registry(registry)?;
+    let Some(credential) = db::get_registry_credential(&state.db, &registry).await? else {
+        return Ok(None);
+    };
+
+    let password = decrypt_password(
+        &state.registry_key,
+        &registry,
+        &credential.password_encrypted,
+    )?;
+    tracing::debug!(%registry, username = %credential.username, "Using stored registry credentials");
+    Ok(Some(RegistryAuth {
+        username: credential.username,
+        password,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; KEY_LEN] = [7; KEY_LEN];
+
+    #[test]
+    fn test_password_round_trip() {
+        let encrypted = encrypt_password(&KEY, "harbor.example.com", "s3cret!").unwrap();
+        assert!(!encrypted.contains("s3cret"));
+        assert_eq!(
+            decrypt_password(&KEY, "harbor.example.com", &encrypted).unwrap(),
+            "s3cret!"
+        );
+    }
+
+    #[test]
+    fn test_encryption_uses_fresh_nonce() {
+        let first = encrypt_password(&KEY, "ghcr.io", "s3cret!").unwrap();
+        let second = encrypt_password(&KEY, "ghcr.io", "s3cret!").unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_other_registry_or_key() {
+        let encrypted = encrypt_password(&KEY, "harbor.example.com", "s3cret!").unwrap();
+        assert!(decrypt_password(&KEY, "ghcr.io", &encrypted).is_err());
+        assert!(decrypt_password(&[8; KEY_LEN], "harbor.example.com", &encrypted).is_err());
+        assert!(decrypt_password(&KEY, "harbor.example.com", "bm9wZQ==").is_err());
+    }
+}
@@ -3,11 +3,14 @@ use futures::future::join_all;
 use opentelemetry::KeyValue;
 use shared::data::{self, LabNodeActionResponse, NodeActionResult, NodeKind, NodeState, RecordId};
 use std::time::Instant;
-use virt::sys::{VIR_DOMAIN_PAUSED, VIR_DOMAIN_SHUTOFF};
 
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::runtime::{Runtime, VmStart};
+use crate::services::bridge_vlan;
+use crate::services::link_filter;
+use crate::services::mirror;
 
 /// Start/poweron all (or a specific) node(s) for a lab.
 ///
@@ -87,9 +90,11 @@ pub async fn start_lab_nodes(
         futures.push(async move {
             let result = match kind {
                 NodeKind::VirtualMachine | NodeKind::Unikernel => {
-                    start_vm(&device_name, &node_name, &state).await
+                    start_vm(&device_name, &node_name, &state.runtime).await
+                }
+                NodeKind::Container => {
+                    start_container_node(&device_name, &node_name, &state.runtime).await
                 }
-                NodeKind::Container => start_container_node(&device_name, &node_name, &state).await,
             };
 
             (result, node_name, node_id, kind_clone)
@@ -131,6 +136,29 @@ pub async fn start_lab_nodes(
         );
     }
 
+    // Cold boot also recreates the taps of VLAN-aware bridge ports
+    if !cold_booted_vms.is_empty()
+        && let Err(e) =
+            bridge_vlan::start_manifest_bridge_vlans(lab_id, &cold_booted_vms, state).await
+    {
+        tracing::warn!(
+            lab_id = %lab_id,
+            error = ?e,
+            "Failed to join VLAN-aware bridge ports after VM cold boot"
+        );
+    }
+
+    // Re-attached redirect programs start without the manifest's mirrors
+    if !cold_booted_vms.is_empty()
+        && let Err(e) = mirror::start_manifest_mirrors(lab_id, &lab_record_id, state).await
+    {
+        tracing::warn!(
+            lab_id = %lab_id,
+            error = ?e,
+            "Failed to restart port mirrors after VM cold boot"
+        );
+    }
+
     state.metrics.operation_duration.record(
         start.elapsed().as_secs_f64(),
         &[KeyValue::new("operation.type", "resume")],
@@ -139,93 +167,26 @@ pub async fn start_lab_nodes(
     Ok(LabNodeActionResponse { results })
 }
 
-async fn start_vm(device_name: &str, node_name: &str, state: &AppState) -> NodeActionResult {
-    let qemu = state.qemu.clone();
-    let device_name = device_name.to_string();
-    let node_name_owned = node_name.to_string();
-
-    match tokio::task::spawn_blocking(move || -> Result<NodeActionResult> {
-        let node_name = node_name_owned;
-        let conn = qemu.connect().context("Failed to connect to libvirt")?;
-        let domain = match virt::domain::Domain::lookup_by_name(&conn, &device_name) {
-            Ok(d) => d,
-            Err(e) => {
-                return Ok(NodeActionResult {
-                    name: node_name,
-                    success: false,
-                    message: format!("Domain not found: {}", e),
-                });
-            }
-        };
-
-        match domain.get_state() {
-            Ok((state, _reason)) => {
-                if state == VIR_DOMAIN_SHUTOFF {
-                    // Cold boot a defined-but-inactive domain
-                    match domain.create() {
-                        Ok(_) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: true,
-                            message: "Started".to_string(),
-                        }),
-                        Err(e) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: false,
-                            message: format!("Failed to start: {}", e),
-                        }),
-                    }
-                } else if state == VIR_DOMAIN_PAUSED {
-                    // Resume a paused domain
-                    match domain.resume() {
-                        Ok(_) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: true,
-                            message: "Resumed".to_string(),
-                        }),
-                        Err(e) => Ok(NodeActionResult {
-                            name: node_name,
-                            success: false,
-                            message: format!("Failed to resume: {}", e),
-                        }),
-                    }
-                } else {
-                    // Already running or other state
-                    Ok(NodeActionResult {
-                        name: node_name,
-                        success: true,
-                        message: "Already running".to_string(),
-                    })
-                }
-            }
-            Err(e) => Ok(NodeActionResult {
-                name: node_name,
-                success: false,
-                message: format!("Failed to get state: {}", e),
-            }),
-        }
-    })
-    .await
-    {
-        Ok(Ok(result)) => result,
-        Ok(Err(e)) => NodeActionResult {
-            name: node_name.to_string(),
-            success: false,
-            message: format!("Libvirt error: {}", e),
-        },
-        Err(e) => NodeActionResult {
-            name: node_name.to_string(),
-            success: false,
-            message: format!("Task panicked: {}", e),
-        },
+async fn start_vm(device_name: &str, node_name: &str, runtime: &Runtime) -> NodeActionResult {
+    let (success, message) = match runtime.vms.start_domain(device_name).await {
+        Ok(VmStart::Started) => (true, "Started".to_string()),
+        Ok(VmStart::Resumed) => (true, "Resumed".to_string()),
+        Ok(VmStart::AlreadyRunning) => (true, "Already running".to_string()),
+        Err(e) => (false, format!("{:#}", e)),
+    };
+    NodeActionResult {
+        name: node_name.to_string(),
+        success,
+        message,
     }
 }
 
 async fn start_container_node(
     device_name: &str,
     node_name: &str,
-    state: &AppState,
+    runtime: &Runtime,
 ) -> NodeActionResult {
-    match container::unpause_container(&state.docker, device_name).await {
+    match runtime.containers.unpause_container(device_name).await {
         Ok(()) => NodeActionResult {
             name: node_name.to_string(),
             success: true,
@@ -277,16 +238,16 @@ async fn reattach_p2p_ebpf_for_nodes(
             continue;
         }
 
-        let ifindex_a = network::get_ifindex(&link.tap_a)
+        let runtime = &state.runtime;
+        runtime
+            .network
+            .attach_p2p_redirect(&link.tap_a, &link.tap_b)
             .await
-            .context(format!("failed to get ifindex for {}", link.tap_a))?;
-        let ifindex_b = network::get_ifindex(&link.tap_b)
-            .await
-            .context(format!("failed to get ifindex for {}", link.tap_b))?;
-
-        network::attach_p2p_redirect(&link.tap_a, ifindex_b)
             .context(format!("failed to attach eBPF redirect on {}", link.tap_a))?;
-        network::attach_p2p_redirect(&link.tap_b, ifindex_a)
+        runtime
+            .network
+            .attach_p2p_redirect(&link.tap_b, &link.tap_a)
+            .await
             .context(format!("failed to attach eBPF redirect on {}", link.tap_b))?;
 
         // Re-apply link impairment if configured
@@ -298,8 +259,13 @@ async fn reattach_p2p_ebpf_for_nodes(
                 reorder_percent: 0.0,
                 corrupt_percent: 0.0,
             };
-            network::apply_netem(ifindex_a as i32, &netem).await?;
-            network::apply_netem(ifindex_b as i32, &netem).await?;
+            runtime.network.apply_netem(&link.tap_a, &netem).await?;
+            runtime.network.apply_netem(&link.tap_b, &netem).await?;
+        }
+
+        // The new programs start with an empty filter
+        if !link.filter.is_empty() {
+            link_filter::apply_link_filter(link, runtime.network.as_ref()).await?;
         }
 
         tracing::info!(
@@ -312,3 +278,147 @@ async fn reattach_p2p_ebpf_for_nodes(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
+    use bollard::secret::ContainerSummaryStateEnum;
+    use shared::data::{DatabaseEngine, NodeConfig, NodeModel};
+    use shared::util;
+    use virt::sys::{VIR_DOMAIN_PAUSED, VIR_DOMAIN_RUNNING, VIR_DOMAIN_SHUTOFF};
+
+    #[tokio::test]
+    async fn test_start_vm() {
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default()
+                .with_domain("r1-abc12345", VIR_DOMAIN_SHUTOFF)
+                .with_domain("r2-abc12345", VIR_DOMAIN_PAUSED)
+                .with_domain("r3-abc12345", VIR_DOMAIN_RUNNING),
+            FakeContainerRuntime::default(),
+            FakeHostNetwork::default(),
+        );
+        let runtime = fakes.runtime();
+
+        assert_eq!(
+            start_vm("r1-abc12345", "r1", &runtime).await.message,
+            "Started"
+        );
+        assert_eq!(
+            start_vm("r2-abc12345", "r2", &runtime).await.message,
+            "Resumed"
+        );
+        assert_eq!(
+            start_vm("r3-abc12345", "r3", &runtime).await.message,
+            "Already running"
+        );
+        assert!(
+            fakes
+                .vms
+                .domains()
+                .values()
+                .all(|state| *state == VIR_DOMAIN_RUNNING)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_container_node_requires_paused() {
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default(),
+            FakeContainerRuntime::default()
+                .with_container("srl1-abc12345", ContainerSummaryStateEnum::PAUSED)
+                .with_container("srl2-abc12345", ContainerSummaryStateEnum::EXITED),
+            FakeHostNetwork::default(),
+        );
+        let runtime = fakes.runtime();
+
+        let paused = start_container_node("srl1-abc12345", "srl1", &runtime).await;
+        let exited = start_container_node("srl2-abc12345", "srl2", &runtime).await;
+
+        assert!(paused.success);
+        assert!(!exited.success);
+        assert!(exited.message.starts_with("Failed to unpause"));
+    }
+
+    #[tokio::test]
+    async fn test_cold_boot_reattaches_p2p_redirect() {
+        let db = db::connect_embedded(DatabaseEngine::Memory, "", "resume", "test")
+            .await
+            .expect("in-memory database opens");
+        db::apply_schema(&db).await.expect("schema applies");
+        let user = db::create_user(&db, "alice".to_string(), "TestPass123!", false, vec![])
+            .await
+            .expect("user creates");
+        let image = db::create_node_image(&db, NodeConfig::get_model(NodeModel::UbuntuLinux))
+            .await
+            .expect("node image creates");
+        let lab = db::create_lab(
+            &db,
+            "Resume Lab",
+            "abc12345",
+            &user,
+            "127.127.1.0/24",
+            "172.31.1.0/24",
+            "172.31.1.1",
+            "172.31.1.2",
+        )
+        .await
+        .expect("lab creates");
+        let lab_record_id = lab.id.expect("lab has id");
+        let image_id = image.id.expect("image has id");
+        let r1 = db::create_node(&db, "r1", 1, image_id.clone(), lab_record_id.clone())
+            .await
+            .expect("r1 creates");
+        let r2 = db::create_node(&db, "r2", 2, image_id, lab_record_id.clone())
+            .await
+            .expect("r2 creates");
+        let mut link = db::create_link(
+            &db,
+            0,
+            data::BridgeKind::P2p,
+            r1.id.expect("r1 has id"),
+            r2.id.expect("r2 has id"),
+            "eth1".to_string(),
+            "eth1".to_string(),
+            String::new(),
+            String::new(),
+            "veth-a".to_string(),
+            "veth-b".to_string(),
+            "tpa0-abc12345".to_string(),
+            "tpb0-abc12345".to_string(),
+            lab_record_id,
+        )
+        .await
+        .expect("link creates");
+        link.delay_us = 1000;
+        db::update_link(&db, link).await.expect("link updates");
+
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default()
+                .with_domain("r1-abc12345", VIR_DOMAIN_SHUTOFF)
+                .with_domain("r2-abc12345", VIR_DOMAIN_PAUSED),
+            FakeContainerRuntime::default(),
+            FakeHostNetwork::default()
+                .with_interface("tpa0-abc12345")
+                .with_interface("tpb0-abc12345"),
+        );
+        let state = AppState::for_tests(db, util::default_config(), fakes.runtime());
+
+        let response = start_lab_nodes("abc12345", None, &state).await.unwrap();
+        assert!(response.results.iter().all(|result| result.success));
+
+        // r1 was cold booted, so both sides of its link are re-attached
+        let calls = fakes.network.calls();
+        for call in [
+            "attach_p2p_redirect tpa0-abc12345 tpb0-abc12345",
+            "attach_p2p_redirect tpb0-abc12345 tpa0-abc12345",
+            "apply_netem tpa0-abc12345",
+            "apply_netem tpb0-abc12345",
+        ] {
+            assert!(
+                calls.contains(&call.to_string()),
+                "missing {call} in {calls:?}"
+            );
+        }
+    }
+}
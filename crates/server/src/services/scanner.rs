@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
@@ -10,11 +9,14 @@ use tracing::instrument;
 use virt::sys::{VIR_DOMAIN_PAUSED, VIR_DOMAIN_RUNNING, VIR_DOMAIN_SHUTOFF};
 
 use crate::daemon::state::AppState;
+use crate::runtime::Runtime;
+use crate::services::link_stats;
 
 /// Run the background scanner service.
 ///
 /// Periodically queries libvirt and Docker for actual runtime state of all
-/// nodes, then reconciles the database to match. Exits cleanly when the
+/// nodes, then reconciles the database to match. With OTel enabled it also
+/// exports the traffic counters of every lab link. Exits cleanly when the
 /// cancellation token is triggered.
 #[instrument(skip_all)]
 pub async fn run_scanner(state: AppState, cancel: CancellationToken) {
@@ -44,28 +46,7 @@ pub async fn run_scanner(state: AppState, cancel: CancellationToken) {
 /// Execute a single scan cycle: query runtimes, reconcile with DB.
 #[instrument(skip_all, level = "debug")]
 async fn scan_cycle(state: &AppState) -> Result<()> {
-    // Query both runtimes concurrently
-    let qemu = state.qemu.clone();
-    let docker = state.docker.clone();
-
-    let (vm_states, container_states) =
-        tokio::join!(query_libvirt_states(qemu), query_docker_states(docker),);
-
-    let vm_states = match vm_states {
-        Ok(states) => states,
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to query libvirt states");
-            HashMap::new()
-        }
-    };
-
-    let container_states = match container_states {
-        Ok(states) => states,
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to query Docker states");
-            HashMap::new()
-        }
-    };
+    let (vm_states, container_states) = query_runtime_states(&state.runtime).await;
 
     // Fetch all labs from DB
     let labs = db::list_labs(&state.db)
@@ -85,11 +66,57 @@ async fn scan_cycle(state: &AppState) -> Result<()> {
                 "Failed to scan lab"
             );
         }
+
+        // Link counters are only read when something will export them.
+        if state.config.otel.enabled {
+            match link_stats::link_stats(&lab.lab_id, state).await {
+                Ok(stats) => link_stats::record_link_metrics(&state.metrics, &stats),
+                Err(e) => tracing::debug!(
+                    lab_id = %lab.lab_id,
+                    error = %e,
+                    "Failed to read link stats"
+                ),
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Query both runtimes concurrently for the state of every domain and
+/// container. A runtime that cannot be queried contributes an empty map so
+/// the other runtime's nodes are still reconciled.
+async fn query_runtime_states(
+    runtime: &Runtime,
+) -> (
+    HashMap<String, u32>,
+    HashMap<String, ContainerSummaryStateEnum>,
+) {
+    // Query both runtimes concurrently
+    let (vm_states, container_states) = tokio::join!(
+        runtime.vms.domain_states(),
+        runtime.containers.container_states(),
+    );
+
+    let vm_states = match vm_states {
+        Ok(states) => states,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to query libvirt states");
+            HashMap::new()
+        }
+    };
+
+    let container_states = match container_states {
+        Ok(states) => states,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to query Docker states");
+            HashMap::new()
+        }
+    };
+
+    (vm_states, container_states)
+}
+
 /// Scan a single lab: fetch its nodes, determine actual state, update DB if changed.
 #[instrument(skip_all, fields(lab_id = %lab.lab_id), level = "debug")]
 async fn scan_lab(
@@ -214,66 +241,10 @@ fn detect_node_state(
     }
 }
 
-/// Query libvirt for all domain states. Must run in spawn_blocking since
-/// libvirt calls are blocking FFI.
-async fn query_libvirt_states(qemu: Arc<libvirt::Qemu>) -> Result<HashMap<String, u32>> {
-    tokio::task::spawn_blocking(move || -> Result<HashMap<String, u32>> {
-        let conn = qemu.connect().context("Failed to connect to libvirt")?;
-        let domains = conn
-            .list_all_domains(0)
-            .context("Failed to list libvirt domains")?;
-
-        let mut states = HashMap::with_capacity(domains.len());
-        for domain in &domains {
-            let name = match domain.get_name() {
-                Ok(name) => name,
-                Err(e) => {
-                    tracing::debug!(error = %e, "Failed to get domain name, skipping");
-                    continue;
-                }
-            };
-            let state_code = match domain.get_state() {
-                Ok((state, _reason)) => state,
-                Err(e) => {
-                    tracing::debug!(domain = %name, error = %e, "Failed to get domain state");
-                    continue;
-                }
-            };
-            states.insert(name, state_code);
-        }
-        Ok(states)
-    })
-    .await
-    .context("libvirt query task panicked")?
-}
-
-/// Query Docker for all container states.
-async fn query_docker_states(
-    docker: Arc<bollard::Docker>,
-) -> Result<HashMap<String, ContainerSummaryStateEnum>> {
-    let containers = container::list_containers(&docker)
-        .await
-        .context("Failed to list Docker containers")?;
-
-    let mut states = HashMap::with_capacity(containers.len());
-    for c in &containers {
-        let name = match &c.names {
-            Some(names) if !names.is_empty() => {
-                // Docker names have a leading '/'
-                names[0].trim_start_matches('/').to_string()
-            }
-            _ => continue,
-        };
-        if let Some(status) = c.state {
-            states.insert(name, status);
-        }
-    }
-    Ok(states)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
 
     #[test]
     fn test_detect_vm_running() {
@@ -488,4 +459,51 @@ mod tests {
         );
         assert_eq!(state, NodeState::Unknown);
     }
+
+    #[tokio::test]
+    async fn test_query_runtime_states_from_fakes() {
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default().with_domain("router1-abc12345", VIR_DOMAIN_RUNNING),
+            FakeContainerRuntime::default()
+                .with_container("switch1-abc12345", ContainerSummaryStateEnum::PAUSED),
+            FakeHostNetwork::default(),
+        );
+
+        let (vm_states, container_states) = query_runtime_states(&fakes.runtime()).await;
+
+        assert_eq!(
+            detect_node_state(
+                "router1-abc12345",
+                &NodeKind::VirtualMachine,
+                &vm_states,
+                &container_states,
+            ),
+            NodeState::Running
+        );
+        assert_eq!(
+            detect_node_state(
+                "switch1-abc12345",
+                &NodeKind::Container,
+                &vm_states,
+                &container_states,
+            ),
+            NodeState::Stopped
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_runtime_states_tolerates_failed_runtime() {
+        let fakes = FakeRuntime::new(
+            FakeVmRuntime::default().with_domain("router1-abc12345", VIR_DOMAIN_RUNNING),
+            FakeContainerRuntime::default()
+                .with_container("switch1-abc12345", ContainerSummaryStateEnum::RUNNING)
+                .fail_on("container_states"),
+            FakeHostNetwork::default(),
+        );
+
+        let (vm_states, container_states) = query_runtime_states(&fakes.runtime()).await;
+
+        assert_eq!(vm_states.len(), 1);
+        assert!(container_states.is_empty());
+    }
 }
@@ -0,0 +1,222 @@
+use anyhow::{Context, Result, anyhow, bail};
+use tracing::instrument;
+
+use crate::daemon::state::AppState;
+use shared::data::{
+    DbTeam, ListLabSharesResponse, RecordId, ShareLabRequest, TeamInfo, UnshareLabRequest,
+    UpdateTeamMembersRequest,
+};
+
+/// Resolve the grantee of a share request to a `user` or `team` record.
+///
+/// Exactly one of `username` or `team` must be set.
+async fn resolve_grantee(
+    username: Option<&str>,
+    team: Option<&str>,
+    state: &AppState,
+) -> Result<RecordId> {
+    match (username, team) {
+        (Some(username), None) => db::get_user(&state.db, username)
+            .await
+            .context(format!("User not found: {}", username))?
+            .id
+            .ok_or_else(|| anyhow!("User '{}' missing record ID", username)),
+        (None, Some(team)) => db::get_team_by_name(&state.db, team)
+            .await?
+            .id
+            .ok_or_else(|| anyhow!("Team '{}' missing record ID", team)),
+        _ => bail!("Exactly one of a username or a team must be given"),
+    }
+}
+
+/// Resolve usernames to user record IDs, dropping duplicates.
+async fn resolve_users(usernames: &[String], state: &AppState) -> Result<Vec<RecordId>> {
+    let mut ids: Vec<RecordId> = Vec::with_capacity(usernames.len());
+    for username in usernames {
+        let user = db::get_user(&state.db, username)
+            .await
+            .context(format!("User not found: {}", username))?;
+        let id = user
+            .id
+            .ok_or_else(|| anyhow!("User '{}' missing record ID", username))?;
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    Ok(ids)
+}
+
+/// Look up a team by name in the list of teams with resolved usernames.
+async fn team_info(name: &str, state: &AppState) -> Result<TeamInfo> {
+    db::list_teams(&state.db)
+        .await?
+        .into_iter()
+        .find(|team| team.name == name)
+        .ok_or_else(|| anyhow!("Team not found: {}", name))
+}
+
+/// List the shares of a lab
+///
+/// Authorization is handled by the caller.
+///
+/// # Errors
+/// Returns error if the lab doesn't exist or a database query fails
+#[instrument(skip(state), fields(%lab_id))]
+pub async fn list_lab_shares(lab_id: &str, state: &AppState) -> Result<ListLabSharesResponse> {
+    let owner = db::get_lab_owner_username(&state.db, lab_id).await?;
+    let shares = db::list_lab_shares(&state.db, lab_id).await?;
+
+    Ok(ListLabSharesResponse {
+        lab_id: lab_id.to_string(),
+        owner,
+        shares,
+    })
+}
+
+/// Share a lab with a user or a team
+///
+/// Sharing with a grantee that already has a share replaces its role.
+/// Authorization (lab owner or admin) is handled by the caller.
+///
+/// # Returns
+/// The shares of the lab after the change
+///
+/// # Errors
+/// Returns error if:
+/// - Neither or both of `username` and `team` are set
+/// - The lab, user or team doesn't exist
+/// - The lab would be shared with its owner
+/// - Database operation fails
+#[instrument(skip(state, request), fields(lab_id = %request.lab_id, role = %request.role))]
+pub async fn share_lab(
+    request: &ShareLabRequest,
+    state: &AppState,
+) -> Result<ListLabSharesResponse> {
+    let lab = db::get_lab(&state.db, &request.lab_id)
+        .await
+        .context(format!("Lab not found: {}", request.lab_id))?;
+    let lab_record_id = lab.id.ok_or_else(|| anyhow!("Lab missing record ID"))?;
+
+    let grantee =
+        resolve_grantee(request.username.as_deref(), request.team.as_deref(), state).await?;
+    if grantee == lab.user {
+        bail!("Lab '{}' is already owned by this user", request.lab_id);
+    }
+
+    db::upsert_lab_share(&state.db, &lab_record_id, &grantee, request.role).await?;
+
+    tracing::info!(
+        lab_id = %request.lab_id,
+        grantee = ?grantee,
+        role = %request.role,
+        "Lab shared"
+    );
+
+    list_lab_shares(&request.lab_id, state).await
+}
+
+/// Remove the share of a lab for a user or a team
+///
+/// Authorization (lab owner or admin) is handled by the caller.
+///
+/// # Returns
+/// The shares of the lab after the change
+///
+/// # Errors
+/// Returns error if:
+/// - Neither or both of `username` and `team` are set
+/// - The lab, user or team doesn't exist
+/// - The grantee has no share on the lab
+/// - Database operation fails
+#[instrument(skip(state, request), fields(lab_id = %request.lab_id))]
+pub async fn unshare_lab(
+    request: &UnshareLabRequest,
+    state: &AppState,
+) -> Result<ListLabSharesResponse> {
+    let lab = db::get_lab(&state.db, &request.lab_id)
+        .await
+        .context(format!("Lab not found: {}", request.lab_id))?;
+    let lab_record_id = lab.id.ok_or_else(|| anyhow!("Lab missing record ID"))?;
+
+    let grantee =
+        resolve_grantee(request.username.as_deref(), request.team.as_deref(), state).await?;
+
+    if !db::delete_lab_share(&state.db, &lab_record_id, &grantee).await? {
+        bail!("Lab '{}' is not shared with {:?}", request.lab_id, grantee);
+    }
+
+    tracing::info!(lab_id = %request.lab_id, grantee = ?grantee, "Lab share removed");
+
+    list_lab_shares(&request.lab_id, state).await
+}
+
+/// Create a team owned by `owner`
+///
+/// # Errors
+/// Returns error if the name is invalid or taken, a member doesn't exist,
+/// or a database operation fails
+#[instrument(skip(state, members), fields(%name, %owner))]
+pub async fn create_team(
+    name: &str,
+    members: &[String],
+    owner: &str,
+    state: &AppState,
+) -> Result<TeamInfo> {
+    let owner_id = db::get_user(&state.db, owner)
+        .await
+        .context(format!("User not found: {}", owner))?
+        .id
+        .ok_or_else(|| anyhow!("User '{}' missing record ID", owner))?;
+    let member_ids = resolve_users(members, state).await?;
+
+    db::create_team(&state.db, name, owner_id, member_ids).await?;
+    team_info(name, state).await
+}
+
+/// Add and remove members of a team
+///
+/// Adding an existing member or removing a non-member is a no-op.
+/// Authorization (team owner or admin) is handled by the caller.
+///
+/// # Errors
+/// Returns error if the team or a user doesn't exist, or a database
+/// operation fails
+#[instrument(skip(state, request), fields(name = %request.name))]
+pub async fn update_team_members(
+    request: &UpdateTeamMembersRequest,
+    state: &AppState,
+) -> Result<TeamInfo> {
+    let team = db::get_team_by_name(&state.db, &request.name).await?;
+    let add = resolve_users(&request.add, state).await?;
+    let remove = resolve_users(&request.remove, state).await?;
+
+    let mut members: Vec<RecordId> = team
+        .members
+        .iter()
+        .filter(|member| !remove.contains(member))
+        .cloned()
+        .collect();
+    for member in add {
+        if !members.contains(&member) {
+            members.push(member);
+        }
+    }
+
+    db::update_team(&state.db, DbTeam { members, ..team }).await?;
+    team_info(&request.name, state).await
+}
+
+/// Delete a team, removing every lab share granted to it
+///
+/// Authorization (team owner or admin) is handled by the caller.
+///
+/// # Errors
+/// Returns error if the team doesn't exist or a database operation fails
+#[instrument(skip(state), fields(%name))]
+pub async fn delete_team(name: &str, state: &AppState) -> Result<()> {
+    let team = db::get_team_by_name(&state.db, name).await?;
+    let team_id = team
+        .id
+        .ok_or_else(|| anyhow!("Team '{}' missing record ID", name))?;
+    db::delete_team(&state.db, &team_id).await
+}
@@ -8,6 +8,8 @@ use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow, bail};
 use askama::Template;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use serde_json;
 
 use opentelemetry::KeyValue;
@@ -15,22 +17,29 @@ use opentelemetry::KeyValue;
 use tracing::instrument;
 
 use crate::daemon::state::AppState;
+use crate::services::boot;
+use crate::services::bridge_vlan;
 use crate::services::clean;
+use crate::services::custom_model;
+use crate::services::link_filter;
+use crate::services::mirror;
 use crate::services::node_ops;
 use crate::services::progress::ProgressSender;
+use crate::services::registry;
 use crate::tls;
 
 use shared::data;
-use shared::data::{NodeState, StatusKind};
+use shared::data::{NodeState, StatusKind, image_registry};
 use shared::konst::{
     BRIDGE_PREFIX, CONTAINER_DNSMASQ_CAPABILITIES, CONTAINER_DNSMASQ_NAME, CONTAINER_DNSMASQ_REPO,
-    CONTAINER_VETH_PREFIX, DNSMASQ_CONFIG_FILE, DNSMASQ_DIR, DNSMASQ_LEASES_FILE, KVM_OUI,
-    LAB_CA_CERT_FILE, LAB_CA_KEY_FILE, LAB_CERT_VALIDITY_DAYS, LAB_CERTS_DIR, LAB_FILE_NAME,
-    NODE_CONFIGS_DIR, READINESS_SLEEP, READINESS_TIMEOUT, SHERPA_CONFIG_FILE_PATH,
-    SHERPA_LAB_MANIFEST_FILE, SHERPA_LABS_PATH, SHERPA_LOOPBACK_PREFIX,
-    SHERPA_LOOPBACK_PREFIX_IPV6, SHERPA_MANAGEMENT_NETWORK_BRIDGE_PREFIX,
+    CONTAINER_VETH_PREFIX, DNSMASQ_CONFIG_FILE, DNSMASQ_DIR, DNSMASQ_LEASES_FILE,
+    EXTERNAL_HOST_NODE, KVM_OUI, LAB_CA_CERT_FILE, LAB_CA_KEY_FILE, LAB_CERT_VALIDITY_DAYS,
+    LAB_CERTS_DIR, LAB_FILE_NAME, MIRROR_PORT_PREFIX, MTU_JUMBO_NET, NODE_CONFIGS_DIR,
+    READINESS_SLEEP, READINESS_TIMEOUT, SHERPA_LAB_MANIFEST_FILE, SHERPA_LABS_PATH,
+    SHERPA_LOOPBACK_PREFIX, SHERPA_LOOPBACK_PREFIX_IPV6, SHERPA_MANAGEMENT_NETWORK_BRIDGE_PREFIX,
     SHERPA_MANAGEMENT_NETWORK_IPV6, SHERPA_MANAGEMENT_NETWORK_NAME, SHERPA_SSH_CONFIG_FILE,
-    SHERPA_SSH_PRIVATE_KEY_PATH, SSH_PORT, TAP_PREFIX, TFTP_DIR, VETH_PREFIX, ZTP_DIR,
+    SHERPA_SSH_PRIVATE_KEY_PATH, SHERPA_SSH_PUBLIC_KEY_PATH, SSH_PORT, TAP_PREFIX, TFTP_DIR,
+    VETH_PREFIX, ZTP_DIR,
 };
 use shared::util;
 
@@ -87,13 +96,23 @@ fn find_bridge_interface(
     node_name: &str,
     interface_name: &str,
     bridge_connections: &[topology::BridgeDetailed],
+    bridge_taps: &[topology::BridgePortTap],
 ) -> Option<data::NodeInterface> {
     let mut interface_data = None;
     for bridge in bridge_connections.iter() {
         for link in bridge.links.iter() {
             if link.node_name == node_name && link.interface_name == *interface_name {
+                let tap = bridge_taps
+                    .iter()
+                    .find(|tap| {
+                        tap.bridge_name == bridge.bridge_name
+                            && tap.node == node_name
+                            && tap.interface == interface_name
+                    })
+                    .map(|tap| tap.tap_name.clone());
                 interface_data = Some(data::NodeInterface::Bridge(data::BridgeInterface {
                     name: bridge.bridge_name.clone(),
+                    tap,
                 }))
             }
         }
@@ -118,7 +137,7 @@ fn process_manifest_bridges(
 
         for link in bridge.links.iter() {
             if let Some(node) = manifest_nodes.iter().find(|n| n.name == link.node) {
-                let interface_idx = util::interface_to_idx(&node.model, &link.interface)?;
+                let interface_idx = node.interface_to_idx(&link.interface)?;
                 bridge_links.push(topology::BridgeLinkDetailed {
                     node_name: link.node.clone(),
                     node_model: node.model,
@@ -134,19 +153,163 @@ fn process_manifest_bridges(
             bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_idx, lab_id),
             index: bridge_idx as u16,
             links: bridge_links,
+            external_interface: None,
+        });
+    }
+    Ok(bridges_detailed)
+}
+
+/// Process manifest links to host interfaces into single-node bridges
+///
+/// Each external link gets its own lab bridge with the host interface
+/// enslaved, numbered after the manifest bridges.
+fn process_external_links(
+    manifest_links: &Option<Vec<topology::Link2>>,
+    manifest_nodes: &[topology::NodeExpanded],
+    lab_id: &str,
+    first_index: usize,
+) -> Result<Vec<topology::BridgeDetailed>> {
+    let mut bridges_detailed = vec![];
+    for (offset, link) in manifest_links
+        .iter()
+        .flatten()
+        .filter(|x| x.is_external())
+        .enumerate()
+    {
+        let external = link.expand_external()?;
+        let node = manifest_nodes
+            .iter()
+            .find(|n| n.name == external.node)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Manifest link - '{}' defined in links, not defined in devices",
+                    external.node
+                )
+            })?;
+        let bridge_index = first_index + offset;
+        let name = format!("{}-{}", EXTERNAL_HOST_NODE, external.host_interface);
+
+        bridges_detailed.push(topology::BridgeDetailed {
+            libvirt_name: format!("sherpa-bridge{}-{}-{}", bridge_index, name, lab_id),
+            bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_index, lab_id),
+            manifest_name: name,
+            index: bridge_index as u16,
+            links: vec![topology::BridgeLinkDetailed {
+                node_name: node.name.clone(),
+                node_model: node.model,
+                interface_index: node.interface_to_idx(&external.interface)?,
+                interface_name: external.interface,
+            }],
+            external_interface: Some(external.host_interface),
+        });
+    }
+    Ok(bridges_detailed)
+}
+
+/// Host interfaces claimed for one `up`, released when dropped
+///
+/// The claim is taken before the host interface check and held until the
+/// interface is attached, so a concurrent `up` cannot pass the same check
+/// in between.
+struct HostInterfaceClaim {
+    claims: Arc<DashMap<String, String>>,
+    interfaces: Vec<String>,
+}
+
+impl HostInterfaceClaim {
+    /// Claim every interface for `lab_id`, or none if any is already claimed
+    fn acquire(
+        claims: &Arc<DashMap<String, String>>,
+        lab_id: &str,
+        interfaces: &[&String],
+    ) -> Result<Self> {
+        let mut claim = Self {
+            claims: claims.clone(),
+            interfaces: vec![],
+        };
+        for interface in interfaces {
+            match claims.entry((*interface).clone()) {
+                Entry::Occupied(entry) => bail!(
+                    "Host interface {} is being attached by lab {}",
+                    interface,
+                    entry.get()
+                ),
+                Entry::Vacant(entry) => {
+                    entry.insert(lab_id.to_string());
+                    claim.interfaces.push((*interface).clone());
+                }
+            }
+        }
+        Ok(claim)
+    }
+}
+
+impl Drop for HostInterfaceClaim {
+    fn drop(&mut self) {
+        for interface in &self.interfaces {
+            self.claims.remove(interface);
+        }
+    }
+}
+
+/// Process manifest mirror destinations into single-node bridges
+///
+/// Each distinct destination is a mirror port with its own lab bridge,
+/// numbered after the manifest bridges and external links.
+fn process_mirror_ports(
+    manifest_mirrors: &Option<Vec<topology::Mirror>>,
+    manifest_nodes: &[topology::NodeExpanded],
+    lab_id: &str,
+    first_index: usize,
+) -> Result<Vec<topology::BridgeDetailed>> {
+    let mut bridges_detailed = vec![];
+    for (offset, port) in topology::mirror_ports(manifest_mirrors).iter().enumerate() {
+        let (node_name, interface) = util::split_node_int(port)?;
+        let node = manifest_nodes
+            .iter()
+            .find(|n| n.name == node_name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Manifest mirror - '{}' defined in mirrors, not defined in devices",
+                    node_name
+                )
+            })?;
+        let bridge_index = first_index + offset;
+        let name = format!("{}-{}-{}", MIRROR_PORT_PREFIX, node.name, interface);
+
+        bridges_detailed.push(topology::BridgeDetailed {
+            libvirt_name: format!("sherpa-bridge{}-{}-{}", bridge_index, name, lab_id),
+            bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_index, lab_id),
+            manifest_name: name,
+            index: bridge_index as u16,
+            links: vec![topology::BridgeLinkDetailed {
+                node_name: node.name.clone(),
+                node_model: node.model,
+                interface_index: node.interface_to_idx(&interface)?,
+                interface_name: interface,
+            }],
+            external_interface: None,
         });
     }
     Ok(bridges_detailed)
 }
 
 /// Process manifest nodes into expanded format with indices assigned
-fn process_manifest_nodes(manifest_nodes: &[topology::Node]) -> Vec<topology::NodeExpanded> {
+fn process_manifest_nodes(
+    manifest_nodes: &[topology::Node],
+    custom_models: &HashMap<String, data::CustomModel>,
+) -> Vec<topology::NodeExpanded> {
     manifest_nodes
         .iter()
         .enumerate()
         .map(|(idx, node)| topology::NodeExpanded {
             name: node.name.clone(),
             model: node.model,
+            custom_model: node
+                .custom_model
+                .as_ref()
+                .and_then(|name| custom_models.get(name))
+                .cloned(),
             index: idx as u16 + 1,
             version: node.version.clone(),
             memory: node.memory,
@@ -173,6 +336,8 @@ fn process_manifest_nodes(manifest_nodes: &[topology::Node]) -> Vec<topology::No
             user_scripts: node.user_scripts_data.clone(),
             kernel_cmdline: node.kernel_cmdline.clone(),
             ready_port: node.ready_port,
+            mtu: node.mtu,
+            interface_mtus: vec![],
         })
         .collect()
 }
@@ -185,6 +350,7 @@ fn process_manifest_links(
     let manifest_links = manifest_links.clone().unwrap_or_default();
     let links = manifest_links
         .iter()
+        .filter(|x| !x.is_external())
         .map(|x: &topology::Link2| x.expand())
         .collect::<Result<Vec<topology::LinkExpanded>>>()?;
 
@@ -193,12 +359,14 @@ fn process_manifest_links(
         let mut this_link = topology::LinkDetailed {
             p2p: link.p2p,
             impairment: link.impairment.clone(),
+            filter: link.filter.clone(),
+            mtu: link.mtu,
             ..Default::default()
         };
         for device in manifest_nodes.iter() {
             let device_model = device.model;
             if link.node_a == device.name {
-                let int_idx = util::interface_to_idx(&device_model, &link.int_a)?;
+                let int_idx = device.interface_to_idx(&link.int_a)?;
                 let peer_node = manifest_nodes
                     .iter()
                     .find(|n| n.name == link.node_b)
@@ -215,7 +383,7 @@ fn process_manifest_links(
                     .iter()
                     .find(|n| n.name == link.node_a)
                     .ok_or_else(|| anyhow!("Peer node not found: {}", link.node_a))?;
-                let int_idx = util::interface_to_idx(&device_model, &link.int_b)?;
+                let int_idx = device.interface_to_idx(&link.int_b)?;
                 this_link.node_b = device.name.clone();
                 this_link.node_b_idx = device.index;
                 this_link.node_b_model = device_model;
@@ -256,16 +424,55 @@ fn get_node_image(
     }
 }
 
+/// Get the node image for an expanded node, with any custom model overlaid.
+fn get_expanded_node_image(
+    node: &topology::NodeExpanded,
+    data: &[data::NodeConfig],
+) -> Result<data::NodeConfig> {
+    let node_image = get_node_image(&node.model, node.version.as_deref(), data)?;
+    Ok(match &node.custom_model {
+        Some(custom) => custom.apply(&node_image),
+        None => node_image,
+    })
+}
+
 // ============================================================================
 // Main Up Service Function
 // ============================================================================
 
+/// Host paths a lab is built from.
+pub(crate) struct LabPaths {
+    pub labs_dir: String,
+    pub ssh_public_key: String,
+    pub ssh_private_key: String,
+}
+
+impl Default for LabPaths {
+    fn default() -> Self {
+        Self {
+            labs_dir: SHERPA_LABS_PATH.to_string(),
+            ssh_public_key: SHERPA_SSH_PUBLIC_KEY_PATH.to_string(),
+            ssh_private_key: SHERPA_SSH_PRIVATE_KEY_PATH.to_string(),
+        }
+    }
+}
+
 /// Start a lab with streaming progress updates
-#[instrument(skip(request, state, progress), fields(lab_id = %request.lab_id))]
 pub async fn up_lab(
     request: data::UpRequest,
     state: &AppState,
     progress: ProgressSender,
+) -> Result<data::UpResponse> {
+    up_lab_in(request, state, progress, &LabPaths::default()).await
+}
+
+/// Start a lab, keeping its files under `paths`.
+#[instrument(skip(request, state, progress, paths), fields(lab_id = %request.lab_id))]
+pub(crate) async fn up_lab_in(
+    request: data::UpRequest,
+    state: &AppState,
+    progress: ProgressSender,
+    paths: &LabPaths,
 ) -> Result<data::UpResponse> {
     // TODO: Currently accepts username without authentication. This assumes a trusted
     // environment where the client can be trusted to send correct username. In production,
@@ -279,8 +486,8 @@ pub async fn up_lab(
     let lab_id = &request.lab_id;
 
     // Deserialize manifest from JSON Value
-    let manifest: topology::Manifest =
-        serde_json::from_value(request.manifest).context("Failed to deserialize manifest")?;
+    let mut manifest = topology::Manifest::from_json(request.manifest)
+        .context("Failed to deserialize manifest")?;
 
     tracing::info!(
         "Starting lab creation: lab_id={}, name={}",
@@ -295,22 +502,14 @@ pub async fn up_lab(
 
     tracing::info!(lab_id = %lab_id, lab_name = %manifest.name, "Connecting to lab infrastructure services");
 
-    let sherpa_user = util::sherpa_user().context("Failed to get sherpa user")?;
-    let lab_dir = format!("{SHERPA_LABS_PATH}/{lab_id}");
+    let sherpa_user =
+        util::sherpa_user_from(&paths.ssh_public_key).context("Failed to get sherpa user")?;
+    let lab_dir = format!("{}/{lab_id}", paths.labs_dir);
     let current_user = &request.username;
     let management_network = format!("{}-{}", SHERPA_MANAGEMENT_NETWORK_NAME, lab_id);
 
-    // Get connections from AppState
-    let docker_conn = state.docker.clone();
-    tracing::info!(lab_id = %lab_id, "Connected to Docker daemon");
-
-    let qemu_conn = Arc::new(
-        state
-            .qemu
-            .connect()
-            .context("Failed to connect to libvirt")?,
-    );
-    tracing::info!(lab_id = %lab_id, "Connected to libvirt/QEMU");
+    // VM, container and host network backends from AppState
+    let runtime = &state.runtime;
 
     // Use the shared database connection from AppState
     let db = state.db.clone();
@@ -371,10 +570,39 @@ pub async fn up_lab(
 
     // Version & Image Validators (CRITICAL ERROR - fail fast on validation failure)
     // Fetch local Docker images for validation
-    let docker_images = container::get_local_images(&docker_conn)
+    let mut docker_images = runtime
+        .containers
+        .list_images()
         .await
         .context("Failed to list local Docker images")?;
 
+    // Swap custom model nodes to their generic base model
+    let custom_models = custom_model::resolve_manifest_models(&mut manifest.nodes)
+        .context("Manifest validation failed: custom models")?;
+
+    // Pull missing container images, with stored credentials for private registries
+    let missing_images =
+        validate::missing_container_images(&manifest.nodes, &node_images, &docker_images);
+    if !missing_images.is_empty() {
+        for (repo, tag) in &missing_images {
+            let _ = progress.send_status(
+                format!("Pulling missing container image {}:{}", repo, tag),
+                StatusKind::Progress,
+            );
+            let auth = registry::credentials_for(state, &image_registry(repo)).await?;
+            runtime
+                .containers
+                .pull_image(repo, tag, auth.as_ref())
+                .await
+                .context(format!("Failed to pull container image {}:{}", repo, tag))?;
+        }
+        docker_images = runtime
+            .containers
+            .list_images()
+            .await
+            .context("Failed to list local Docker images")?;
+    }
+
     let validated_nodes = validate::validate_and_resolve_node_versions(
         &manifest.nodes,
         &node_images,
@@ -383,11 +611,28 @@ pub async fn up_lab(
     )
     .context("Manifest validation failed: version/image validation")?;
 
-    let nodes_expanded = process_manifest_nodes(&validated_nodes);
+    let mut nodes_expanded = process_manifest_nodes(&validated_nodes, &custom_models);
     let links_detailed = process_manifest_links(&manifest.links, &nodes_expanded)
         .context("Failed to process manifest links")?;
-    let bridges_detailed = process_manifest_bridges(&manifest.bridges, &nodes_expanded, lab_id)
+    let mut bridges_detailed = process_manifest_bridges(&manifest.bridges, &nodes_expanded, lab_id)
         .context("Failed to process manifest bridges")?;
+    let external_bridges = process_external_links(
+        &manifest.links,
+        &nodes_expanded,
+        lab_id,
+        bridges_detailed.len(),
+    )
+    .context("Failed to process manifest external links")?;
+    bridges_detailed.extend(external_bridges);
+    let mirror_bridges = process_mirror_ports(
+        &manifest.mirrors,
+        &nodes_expanded,
+        lab_id,
+        bridges_detailed.len(),
+    )
+    .context("Failed to process manifest mirror ports")?;
+    bridges_detailed.extend(mirror_bridges);
+    let bridge_taps = topology::bridge_port_taps(&manifest.bridges, lab_id);
 
     tracing::info!(
         lab_id = %lab_id,
@@ -398,9 +643,11 @@ pub async fn up_lab(
     );
 
     let mut ztp_records = vec![];
+    let expanded_links = manifest.expanded_links()?;
+    let mut image_mtus = HashMap::new();
 
-    for node in &nodes_expanded {
-        let node_image = get_node_image(&node.model, node.version.as_deref(), &node_images)
+    for node in nodes_expanded.iter_mut() {
+        let node_image = get_expanded_node_image(node, &node_images)
             .context(format!("Node config not found for model: {}", node.model))?;
 
         if !node_image.dedicated_management_interface {
@@ -435,8 +682,19 @@ pub async fn up_lab(
             "Interface bounds validation failed for node: {}",
             node.name
         ))?;
+
+        let first_data_idx = node_image.reserved_interface_count.saturating_add(1);
+        let last_data_idx = node_image
+            .reserved_interface_count
+            .saturating_add(data_interface_count);
+        node.interface_mtus =
+            node.data_interface_mtus(first_data_idx..=last_data_idx, &expanded_links)?;
+        image_mtus.insert(node.name.clone(), node_image.interface_mtu);
     }
 
+    validate::check_mtus(&manifest.nodes, &links_detailed, &image_mtus)
+        .context("MTU validation failed")?;
+
     // Connection Validators
     if !links_detailed.is_empty() {
         validate::check_duplicate_interface_link(&links_detailed, &bridges_detailed)
@@ -450,6 +708,46 @@ pub async fn up_lab(
         validate::check_bridge_device(&manifest.nodes, &bridges_detailed)
             .context("Bridge device validation failed")?;
     }
+    if let Some(bridges) = &manifest.bridges {
+        validate::check_bridge_ports(bridges).context("Bridge VLAN port validation failed")?;
+    }
+
+    // External Link Validators
+    validate::check_external_links(&manifest.nodes, &bridges_detailed)
+        .context("External link validation failed")?;
+    let host_interfaces: Vec<&String> = bridges_detailed
+        .iter()
+        .filter_map(|b| b.external_interface.as_ref())
+        .collect();
+    if !db_user.is_admin
+        && let Some(host_interface) = host_interfaces
+            .iter()
+            .find(|i| !config.host_interfaces.contains(i))
+    {
+        bail!(
+            "External link validation failed: host interface {} is not in the server's host_interfaces and only admins can use other host interfaces",
+            host_interface
+        );
+    }
+    let _host_interface_claim =
+        HostInterfaceClaim::acquire(&state.host_interface_claims, lab_id, &host_interfaces)
+            .context("External link validation failed")?;
+    for host_interface in host_interfaces {
+        runtime
+            .network
+            .check_host_interface(host_interface)
+            .await
+            .context(format!(
+                "External link validation failed for host interface: {}",
+                host_interface
+            ))?;
+    }
+
+    // Mirror Validators
+    if let Some(mirrors) = &manifest.mirrors {
+        validate::check_mirrors(mirrors, &links_detailed)
+            .context("Port mirror validation failed")?;
+    }
 
     let _ = progress.send_status("Manifest validation complete".to_string(), StatusKind::Done);
     tracing::info!(lab_id = %lab_id, "Manifest validation completed successfully");
@@ -576,7 +874,7 @@ pub async fn up_lab(
         let mut node_setup_data = vec![];
 
         for node in nodes_expanded.iter() {
-            let node_image = get_node_image(&node.model, node.version.as_deref(), &node_images)?;
+            let node_image = get_expanded_node_image(node, &node_images)?;
 
             tracing::info!(
                 lab_id = %lab_id,
@@ -605,7 +903,7 @@ pub async fn up_lab(
                 .ok_or_else(|| anyhow!("Data interface count overflow for node {}", node.name))?;
 
             for idx in 0..=max_interface_idx {
-                let interface_name = util::interface_from_idx(&node.model, idx)?;
+                let interface_name = node.interface_from_idx(idx)?;
                 let interface_idx = idx;
                 let mut interface_state = data::InterfaceState::Enabled;
                 let mut interface_data = data::NodeInterface::Disabled;
@@ -620,9 +918,12 @@ pub async fn up_lab(
                     {
                         interface_data = data
                     }
-                    if let Some(data) =
-                        find_bridge_interface(&node.name, &interface_name, &bridges_detailed)
-                    {
+                    if let Some(data) = find_bridge_interface(
+                        &node.name,
+                        &interface_name,
+                        &bridges_detailed,
+                        &bridge_taps,
+                    ) {
                         interface_data = data
                     }
                     interface_state = data::InterfaceState::Disabled;
@@ -704,14 +1005,20 @@ pub async fn up_lab(
                 );
                 match node_image.kind {
                     data::NodeKind::VirtualMachine => {
-                        let node_isolated_network = libvirt::IsolatedNetwork {
-                            network_name: network.network_name,
-                            bridge_name: network.bridge_name,
-                        };
-                        node_isolated_network.create(&qemu_conn)?;
+                        runtime
+                            .vms
+                            .create_isolated_network(&network.network_name, &network.bridge_name)
+                            .await?;
                     }
                     data::NodeKind::Container => {
-                        network::create_bridge(&network.bridge_name, &network.network_name).await?;
+                        runtime
+                            .network
+                            .create_bridge(
+                                &network.bridge_name,
+                                &network.network_name,
+                                MTU_JUMBO_NET,
+                            )
+                            .await?;
                     }
                     data::NodeKind::Unikernel => {
                         tracing::warn!(
@@ -733,11 +1040,10 @@ pub async fn up_lab(
                     network_type = "reserved",
                     "Creating node reserved network"
                 );
-                let node_reserved_network = libvirt::ReservedNetwork {
-                    network_name: network.network_name,
-                    bridge_name: network.bridge_name,
-                };
-                node_reserved_network.create(&qemu_conn)?;
+                runtime
+                    .vms
+                    .create_reserved_network(&network.network_name, &network.bridge_name)
+                    .await?;
             }
 
             node_setup_data.push(data::NodeSetupData {
@@ -844,7 +1150,10 @@ pub async fn up_lab(
             ipv6_address: Some(gateway_ipv6),
             ipv6_prefix_length: Some(ipv6_management_subnet.prefix_len()),
         };
-        management_network_obj.create(&qemu_conn)?;
+        runtime
+            .vms
+            .create_nat_network(management_network_obj)
+            .await?;
 
         tracing::info!(
             lab_id = %lab_id,
@@ -854,14 +1163,15 @@ pub async fn up_lab(
         );
 
         // Docker management network
-        container::create_docker_bridge_network(
-            &docker_conn,
-            &format!("{SHERPA_MANAGEMENT_NETWORK_NAME}-{lab_id}"),
-            Some(lab_net.to_string()),
-            Some(ipv6_management_subnet.to_string()),
-            &format!("{SHERPA_MANAGEMENT_NETWORK_BRIDGE_PREFIX}-{lab_id}"),
-        )
-        .await?;
+        runtime
+            .containers
+            .create_bridge_network(
+                &format!("{SHERPA_MANAGEMENT_NETWORK_NAME}-{lab_id}"),
+                Some(lab_net.to_string()),
+                Some(ipv6_management_subnet.to_string()),
+                &format!("{SHERPA_MANAGEMENT_NETWORK_BRIDGE_PREFIX}-{lab_id}"),
+            )
+            .await?;
 
         tracing::info!(
             lab_id = %lab_id,
@@ -901,6 +1211,15 @@ pub async fn up_lab(
         // These containers handle ALL data interfaces via veth+netns, not Docker networks.
         let mut p2p_container_names: std::collections::HashSet<String> =
             std::collections::HashSet::new();
+        // P2p links with a manifest filter, applied once the redirect programs are attached.
+        let mut filtered_p2p_links: Vec<data::DbLink> = vec![];
+        // Host devices of a link stay jumbo unless the manifest sets an MTU.
+        let host_mtu = |node: &str, interface: &str| {
+            nodes_expanded
+                .iter()
+                .find(|n| n.name == node)
+                .and_then(|n| n.interface_mtu(interface))
+        };
 
         for (idx, link) in links_detailed.iter().enumerate() {
             let node_a = lab_node_data
@@ -927,7 +1246,7 @@ pub async fn up_lab(
             let tap_b = format!("{}b{}-{}", TAP_PREFIX, link.link_idx, lab_id);
 
             // Create the link in the database
-            let _db_link = db::create_link(
+            let mut db_link = db::create_link(
                 &db,
                 link.link_idx,
                 link_kind.clone(),
@@ -945,6 +1264,12 @@ pub async fn up_lab(
             )
             .await?;
 
+            // Store the manifest filter so it is re-applied with the redirect programs
+            if let Some(filter) = &link.filter {
+                db_link.filter = filter.clone();
+                db_link = db::update_link(&db, db_link).await?;
+            }
+
             lab_link_data.push(data::LabLinkData {
                 index: link.link_idx,
                 kind: link_kind.clone(),
@@ -987,13 +1312,16 @@ pub async fn up_lab(
                 if node_a.kind == data::NodeKind::Container {
                     let container_veth_a =
                         format!("{}a{}-{}", CONTAINER_VETH_PREFIX, link.link_idx, lab_id);
-                    network::create_veth_pair(
-                        &tap_a,
-                        &container_veth_a,
-                        &format!("{}-p2p-host-{}::{}", lab_id, link.node_a, link.int_a),
-                        &format!("{}-p2p-ctr-{}::{}", lab_id, link.node_a, link.int_a),
-                    )
-                    .await?;
+                    runtime
+                        .network
+                        .create_veth_pair(
+                            &tap_a,
+                            &container_veth_a,
+                            &format!("{}-p2p-host-{}::{}", lab_id, link.node_a, link.int_a),
+                            &format!("{}-p2p-ctr-{}::{}", lab_id, link.node_a, link.int_a),
+                            host_mtu(&link.node_a, &link.int_a).unwrap_or(MTU_JUMBO_NET),
+                        )
+                        .await?;
                     p2p_container_veths.push(P2pContainerVeth {
                         node_name: link.node_a.clone(),
                         host_veth: tap_a.clone(),
@@ -1015,13 +1343,16 @@ pub async fn up_lab(
                 if node_b.kind == data::NodeKind::Container {
                     let container_veth_b =
                         format!("{}b{}-{}", CONTAINER_VETH_PREFIX, link.link_idx, lab_id);
-                    network::create_veth_pair(
-                        &tap_b,
-                        &container_veth_b,
-                        &format!("{}-p2p-host-{}::{}", lab_id, link.node_b, link.int_b),
-                        &format!("{}-p2p-ctr-{}::{}", lab_id, link.node_b, link.int_b),
-                    )
-                    .await?;
+                    runtime
+                        .network
+                        .create_veth_pair(
+                            &tap_b,
+                            &container_veth_b,
+                            &format!("{}-p2p-host-{}::{}", lab_id, link.node_b, link.int_b),
+                            &format!("{}-p2p-ctr-{}::{}", lab_id, link.node_b, link.int_b),
+                            host_mtu(&link.node_b, &link.int_b).unwrap_or(MTU_JUMBO_NET),
+                        )
+                        .await?;
                     p2p_container_veths.push(P2pContainerVeth {
                         node_name: link.node_b.clone(),
                         host_veth: tap_b.clone(),
@@ -1040,6 +1371,10 @@ pub async fn up_lab(
                     );
                 }
 
+                if link.filter.is_some() {
+                    filtered_p2p_links.push(db_link);
+                }
+
                 tracing::info!(
                     lab_id = %lab_id,
                     tap_a = %tap_a,
@@ -1048,28 +1383,50 @@ pub async fn up_lab(
                 );
             } else {
                 // PeerBridge link: create bridges + veth pair (existing behavior)
-                network::create_bridge(
-                    &bridge_a,
-                    &format!("{}-bridge-{}::{}", lab_id, link.node_a, link.int_a),
-                )
-                .await?;
+                let link_mtu = host_mtu(&link.node_a, &link.int_a)
+                    .max(host_mtu(&link.node_b, &link.int_b))
+                    .unwrap_or(MTU_JUMBO_NET);
+                runtime
+                    .network
+                    .create_bridge(
+                        &bridge_a,
+                        &format!("{}-bridge-{}::{}", lab_id, link.node_a, link.int_a),
+                        link_mtu,
+                    )
+                    .await?;
 
-                network::create_bridge(
-                    &bridge_b,
-                    &format!("{}-bridge-{}::{}", lab_id, link.node_b, link.int_b),
-                )
-                .await?;
+                runtime
+                    .network
+                    .create_bridge(
+                        &bridge_b,
+                        &format!("{}-bridge-{}::{}", lab_id, link.node_b, link.int_b),
+                        link_mtu,
+                    )
+                    .await?;
 
-                network::create_veth_pair(
-                    &veth_a,
-                    &veth_b,
-                    &format!("{}-veth-{}::{}", lab_id, link.node_a, link.int_a),
-                    &format!("{}-veth-{}::{}", lab_id, link.node_b, link.int_b),
-                )
-                .await?;
+                runtime
+                    .network
+                    .create_veth_pair(
+                        &veth_a,
+                        &veth_b,
+                        &format!("{}-veth-{}::{}", lab_id, link.node_a, link.int_a),
+                        &format!("{}-veth-{}::{}", lab_id, link.node_b, link.int_b),
+                        link_mtu,
+                    )
+                    .await?;
 
-                network::enslave_to_bridge(&veth_a, &bridge_a).await?;
-                network::enslave_to_bridge(&veth_b, &bridge_b).await?;
+                runtime
+                    .network
+                    .enslave_to_bridge(&veth_a, &bridge_a)
+                    .await?;
+                runtime
+                    .network
+                    .enslave_to_bridge(&veth_b, &bridge_b)
+                    .await?;
+
+                if link.filter.is_some() {
+                    link_filter::apply_link_filter(&db_link, runtime.network.as_ref()).await?;
+                }
 
                 tracing::debug!(
                     lab_id = %lab_id,
@@ -1110,13 +1467,16 @@ pub async fn up_lab(
                 // Compact to stay within Linux's 15-char interface name limit.
                 let host_veth = format!("cd{}i{}-{}", nsd.index, iface.index, lab_id);
                 let container_veth = format!("ce{}i{}-{}", nsd.index, iface.index, lab_id);
-                network::create_veth_pair(
-                    &host_veth,
-                    &container_veth,
-                    &format!("{}-p2p-disabled-host-{}::{}", lab_id, nsd.name, iface.name),
-                    &format!("{}-p2p-disabled-ctr-{}::{}", lab_id, nsd.name, iface.name),
-                )
-                .await?;
+                runtime
+                    .network
+                    .create_veth_pair(
+                        &host_veth,
+                        &container_veth,
+                        &format!("{}-p2p-disabled-host-{}::{}", lab_id, nsd.name, iface.name),
+                        &format!("{}-p2p-disabled-ctr-{}::{}", lab_id, nsd.name, iface.name),
+                        host_mtu(&nsd.name, &iface.name).unwrap_or(MTU_JUMBO_NET),
+                    )
+                    .await?;
                 p2p_container_veths.push(P2pContainerVeth {
                     node_name: nsd.name.clone(),
                     host_veth: host_veth.clone(),
@@ -1175,12 +1535,10 @@ pub async fn up_lab(
                     bridge = %link_data.bridge_a,
                     "Creating Docker macvlan network"
                 );
-                container::create_docker_macvlan_network(
-                    &docker_conn,
-                    &link_data.bridge_a,
-                    &docker_net_name,
-                )
-                .await?;
+                runtime
+                    .containers
+                    .create_macvlan_network(&link_data.bridge_a, &docker_net_name)
+                    .await?;
                 docker_net_count += 1;
             }
 
@@ -1194,12 +1552,10 @@ pub async fn up_lab(
                     bridge = %link_data.bridge_b,
                     "Creating Docker macvlan network"
                 );
-                container::create_docker_macvlan_network(
-                    &docker_conn,
-                    &link_data.bridge_b,
-                    &docker_net_name,
-                )
-                .await?;
+                runtime
+                    .containers
+                    .create_macvlan_network(&link_data.bridge_b, &docker_net_name)
+                    .await?;
                 docker_net_count += 1;
             }
         }
@@ -1233,12 +1589,10 @@ pub async fn up_lab(
                     bridge = %iso_network.bridge_name,
                     "Creating isolated Docker macvlan bridge-mode network"
                 );
-                container::create_docker_macvlan_bridge_network(
-                    &docker_conn,
-                    &iso_network.bridge_name,
-                    &docker_net_name,
-                )
-                .await?;
+                runtime
+                    .containers
+                    .create_macvlan_bridge_network(&iso_network.bridge_name, &docker_net_name)
+                    .await?;
                 docker_net_count += 1;
             }
         }
@@ -1286,7 +1640,35 @@ pub async fn up_lab(
                 "Creating shared bridge"
             );
 
-            network::create_bridge(&bridge.bridge_name, &bridge.libvirt_name).await?;
+            if bridge_taps
+                .iter()
+                .any(|tap| tap.bridge_name == bridge.bridge_name)
+            {
+                runtime
+                    .network
+                    .create_vlan_bridge(&bridge.bridge_name, &bridge.libvirt_name, MTU_JUMBO_NET)
+                    .await?;
+            } else {
+                runtime
+                    .network
+                    .create_bridge(&bridge.bridge_name, &bridge.libvirt_name, MTU_JUMBO_NET)
+                    .await?;
+            }
+
+            if let Some(host_interface) = &bridge.external_interface {
+                // Still claimed, so only a change outside Sherpa can fail this
+                runtime.network.check_host_interface(host_interface).await?;
+                runtime
+                    .network
+                    .attach_host_interface(host_interface, &bridge.bridge_name)
+                    .await?;
+                tracing::info!(
+                    lab_id = %lab_id,
+                    bridge = %bridge.bridge_name,
+                    host_interface = %host_interface,
+                    "Attached host interface to bridge"
+                );
+            }
 
             tracing::debug!(
                 lab_id = %lab_id,
@@ -1374,7 +1756,7 @@ pub async fn up_lab(
             let node_idx = node_data.index;
             let node_ip_idx = 10 + node_idx as u32;
 
-            let node_image = get_node_image(&node.model, node.version.as_deref(), &node_images)?;
+            let node_image = get_expanded_node_image(node, &node_images)?;
             let node_ipv4_address = util::get_ipv4_addr(&mgmt_net.v4.prefix, node_ip_idx)?;
             node.ipv4_address = Some(node_ipv4_address);
 
@@ -1453,7 +1835,7 @@ pub async fn up_lab(
             let node_idx = node_data.index;
             let node_ip_idx = 10 + node_idx as u32;
 
-            let node_image = get_node_image(&node.model, node.version.as_deref(), &node_images)?;
+            let node_image = get_expanded_node_image(node, &node_images)?;
             let node_ipv4_address = util::get_ipv4_addr(&mgmt_net.v4.prefix, node_ip_idx)?;
             node.ipv4_address = Some(node_ipv4_address);
 
@@ -1526,6 +1908,9 @@ pub async fn up_lab(
             // Build interfaces list
             let mut interfaces: Vec<data::Interface> = vec![];
             for interface in node_data.interfaces.iter() {
+                let mtu = node
+                    .interface_mtu(&interface.name)
+                    .unwrap_or(node_image.interface_mtu);
                 match &interface.data {
                     data::NodeInterface::Management => {
                         interfaces.push(data::Interface {
@@ -1548,12 +1933,16 @@ pub async fn up_lab(
                         });
                     }
                     data::NodeInterface::Bridge(bridge) => {
+                        let (name, connection_type) = match &bridge.tap {
+                            Some(tap) => (tap.clone(), data::ConnectionTypes::VlanBridge),
+                            None => (bridge.name.clone(), data::ConnectionTypes::PrivateBridge),
+                        };
                         interfaces.push(data::Interface {
-                            name: bridge.name.clone(),
+                            name,
                             num: interface.index,
-                            mtu: node_image.interface_mtu,
+                            mtu,
                             mac_address: util::random_mac(KVM_OUI),
-                            connection_type: data::ConnectionTypes::PrivateBridge,
+                            connection_type,
                             interface_connection: None,
                         });
                     }
@@ -1580,7 +1969,7 @@ pub async fn up_lab(
                             interfaces.push(data::Interface {
                                 name: tap_name,
                                 num: interface.index,
-                                mtu: node_image.interface_mtu,
+                                mtu,
                                 mac_address: util::random_mac(KVM_OUI),
                                 connection_type: data::ConnectionTypes::P2p,
                                 interface_connection: Some(interface_connection),
@@ -1597,7 +1986,7 @@ pub async fn up_lab(
                             interfaces.push(data::Interface {
                                 name: bridge_name,
                                 num: interface.index,
-                                mtu: node_image.interface_mtu,
+                                mtu,
                                 mac_address: util::random_mac(KVM_OUI),
                                 connection_type: data::ConnectionTypes::PeerBridge,
                                 interface_connection: Some(interface_connection),
@@ -1606,12 +1995,9 @@ pub async fn up_lab(
                     }
                     data::NodeInterface::Disabled => {
                         interfaces.push(data::Interface {
-                            name: util::dasher(&util::interface_from_idx(
-                                &node.model,
-                                interface.index,
-                            )?),
+                            name: util::dasher(&node.interface_from_idx(interface.index)?),
                             num: interface.index,
-                            mtu: node_image.interface_mtu,
+                            mtu,
                             mac_address: util::random_mac(KVM_OUI),
                             connection_type: data::ConnectionTypes::Disabled,
                             interface_connection: None,
@@ -1663,8 +2049,7 @@ pub async fn up_lab(
                 let node_idx = node_data.index;
                 let node_ip_idx = 10 + node_idx as u32;
 
-                let node_image =
-                    get_node_image(&node.model, node.version.as_deref(), &node_images)?;
+                let node_image = get_expanded_node_image(node, &node_images)?;
                 let node_ipv4_address = util::get_ipv4_addr(&mgmt_net.v4.prefix, node_ip_idx)?;
                 node.ipv4_address = Some(node_ipv4_address);
 
@@ -1737,6 +2122,9 @@ pub async fn up_lab(
                 // Build interfaces list (same pattern as VMs)
                 let mut interfaces: Vec<data::Interface> = vec![];
                 for interface in node_data.interfaces.iter() {
+                    let mtu = node
+                        .interface_mtu(&interface.name)
+                        .unwrap_or(node_image.interface_mtu);
                     match &interface.data {
                         data::NodeInterface::Management => {
                             interfaces.push(data::Interface {
@@ -1759,12 +2147,16 @@ pub async fn up_lab(
                             });
                         }
                         data::NodeInterface::Bridge(bridge) => {
+                            let (name, connection_type) = match &bridge.tap {
+                                Some(tap) => (tap.clone(), data::ConnectionTypes::VlanBridge),
+                                None => (bridge.name.clone(), data::ConnectionTypes::PrivateBridge),
+                            };
                             interfaces.push(data::Interface {
-                                name: bridge.name.clone(),
+                                name,
                                 num: interface.index,
-                                mtu: node_image.interface_mtu,
+                                mtu,
                                 mac_address: util::random_mac(KVM_OUI),
-                                connection_type: data::ConnectionTypes::PrivateBridge,
+                                connection_type,
                                 interface_connection: None,
                             });
                         }
@@ -1793,7 +2185,7 @@ pub async fn up_lab(
                                 interfaces.push(data::Interface {
                                     name: tap_name,
                                     num: interface.index,
-                                    mtu: node_image.interface_mtu,
+                                    mtu,
                                     mac_address: util::random_mac(KVM_OUI),
                                     connection_type: data::ConnectionTypes::P2p,
                                     interface_connection: Some(interface_connection),
@@ -1810,7 +2202,7 @@ pub async fn up_lab(
                                 interfaces.push(data::Interface {
                                     name: bridge_name,
                                     num: interface.index,
-                                    mtu: node_image.interface_mtu,
+                                    mtu,
                                     mac_address: util::random_mac(KVM_OUI),
                                     connection_type: data::ConnectionTypes::PeerBridge,
                                     interface_connection: Some(interface_connection),
@@ -1819,12 +2211,9 @@ pub async fn up_lab(
                         }
                         data::NodeInterface::Disabled => {
                             interfaces.push(data::Interface {
-                                name: util::dasher(&util::interface_from_idx(
-                                    &node.model,
-                                    interface.index,
-                                )?),
+                                name: util::dasher(&node.interface_from_idx(interface.index)?),
                                 num: interface.index,
-                                mtu: node_image.interface_mtu,
+                                mtu,
                                 mac_address: util::random_mac(KVM_OUI),
                                 connection_type: data::ConnectionTypes::Disabled,
                                 interface_connection: None,
@@ -1884,105 +2273,133 @@ pub async fn up_lab(
 
         // Create remaining ZTP directories (ztp_dir and tftp_dir already created in Phase 8)
         let ztp_configs_dir = format!("{ztp_dir}/{NODE_CONFIGS_DIR}");
-        let dnsmasq_dir = format!("{ztp_dir}/{DNSMASQ_DIR}");
         util::create_dir(&ztp_configs_dir)?;
-        util::create_dir(&dnsmasq_dir)?;
 
-        tracing::debug!(
-            lab_id = %lab_id,
-            ztp_dir = %ztp_dir,
-            tftp_dir = %tftp_dir,
-            dnsmasq_dir = %dnsmasq_dir,
-            "Created ZTP directories"
-        );
+        if config.ztp_server.boot_services == data::BootServices::Builtin {
+            let boot_config = boot::BootConfig {
+                lab_id: lab_id.to_string(),
+                bridge: format!("{SHERPA_MANAGEMENT_NETWORK_BRIDGE_PREFIX}-{lab_id}"),
+                server_ipv4: mgmt_net.v4.boot_server,
+                gateway_ipv4: mgmt_net.v4.first,
+                prefix_length: mgmt_net.v4.prefix_length,
+                dhcp_start: util::get_ipv4_addr(&mgmt_net.v4.prefix, 10)?,
+                dhcp_end: util::get_ipv4_addr(&mgmt_net.v4.prefix, 254)?,
+                server_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.boot_server),
+                gateway_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.first),
+                ipv6_prefix_length: mgmt_net.v6.as_ref().map(|v6| v6.prefix_length),
+                ztp_dir: ztp_dir.clone().into(),
+                records: ztp_records.clone(),
+            };
 
-        // Create dnsmasq config
-        let dnsmaq_template = template::DnsmasqTemplate {
-            tftp_server_ipv4: mgmt_net.v4.boot_server.to_string(),
-            gateway_ipv4: mgmt_net.v4.first.to_string(),
-            dhcp_start: util::get_ipv4_addr(&mgmt_net.v4.prefix, 10)?.to_string(),
-            dhcp_end: util::get_ipv4_addr(&mgmt_net.v4.prefix, 254)?.to_string(),
-            gateway_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.first.to_string()),
-            dhcp6_start: mgmt_net
-                .v6
-                .as_ref()
-                .map(|v6| util::get_ipv6_addr(&v6.prefix, 10).map(|a| a.to_string()))
-                .transpose()?,
-            dhcp6_end: mgmt_net
-                .v6
-                .as_ref()
-                .map(|v6| util::get_ipv6_addr(&v6.prefix, 254).map(|a| a.to_string()))
-                .transpose()?,
-            dns_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.boot_server.to_string()),
-            ztp_records: ztp_records.clone(),
-        };
-        let dnsmasq_rendered_template = dnsmaq_template.render()?;
-        util::create_file(
-            &format!("{dnsmasq_dir}/{DNSMASQ_CONFIG_FILE}"),
-            dnsmasq_rendered_template,
-        )?;
-        util::create_file(
-            &format!("{dnsmasq_dir}/{DNSMASQ_LEASES_FILE}"),
-            "".to_string(),
-        )?;
-
-        // Create boot container (tftp_dir already created in Phase 8)
-        let configs_dir = format!("{ztp_dir}/{NODE_CONFIGS_DIR}");
-
-        let dnsmasq_env_dns1 = format!("DNS1={}", mgmt_net.v4.first);
-        let dnsmasq_env_dns2 = "DNS2=".to_string();
-        let boot_server_ipv4 = mgmt_net.v4.boot_server.to_string();
-
-        let webdir_config_volume = format!("{configs_dir}:/opt/{ZTP_DIR}/{NODE_CONFIGS_DIR}");
-        let dnsmasq_env_vars = vec![dnsmasq_env_dns1, dnsmasq_env_dns2];
-        let dnsmasq_config_volume =
-            format!("{dnsmasq_dir}/{DNSMASQ_CONFIG_FILE}:/etc/{DNSMASQ_CONFIG_FILE}");
-        let dnsmasq_tftp_volume = format!("{tftp_dir}:/opt/{ZTP_DIR}/{TFTP_DIR}");
-        let dnsmasq_volumes = vec![
-            dnsmasq_config_volume,
-            dnsmasq_tftp_volume,
-            webdir_config_volume,
-        ];
-        let dnsmasq_capabilities: Vec<String> = CONTAINER_DNSMASQ_CAPABILITIES
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
+            tracing::info!(
+                lab_id = %lab_id,
+                boot_server_ip = %boot_config.server_ipv4,
+                "Starting built-in boot services"
+            );
+            boot::start(state, boot_config)
+                .await
+                .context("Failed to start built-in boot services")?;
+        } else {
+            let dnsmasq_dir = format!("{ztp_dir}/{DNSMASQ_DIR}");
+            util::create_dir(&dnsmasq_dir)?;
 
-        let management_network_attachment = data::ContainerNetworkAttachment {
-            name: format!("{SHERPA_MANAGEMENT_NETWORK_NAME}-{lab_id}"),
-            ipv4_address: Some(boot_server_ipv4.clone()),
-            ipv6_address: None,
-            linux_interface_name: None,
-            admin_down: false,
-        };
+            tracing::debug!(
+                lab_id = %lab_id,
+                ztp_dir = %ztp_dir,
+                tftp_dir = %tftp_dir,
+                dnsmasq_dir = %dnsmasq_dir,
+                "Created ZTP directories"
+            );
 
-        tracing::info!(
-            lab_id = %lab_id,
-            container = %format!("{CONTAINER_DNSMASQ_NAME}-{lab_id}"),
-            boot_server_ip = %boot_server_ipv4,
-            "Starting dnsmasq boot container"
-        );
+            // Create dnsmasq config
+            let dnsmaq_template = template::DnsmasqTemplate {
+                tftp_server_ipv4: mgmt_net.v4.boot_server.to_string(),
+                gateway_ipv4: mgmt_net.v4.first.to_string(),
+                dhcp_start: util::get_ipv4_addr(&mgmt_net.v4.prefix, 10)?.to_string(),
+                dhcp_end: util::get_ipv4_addr(&mgmt_net.v4.prefix, 254)?.to_string(),
+                gateway_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.first.to_string()),
+                dhcp6_start: mgmt_net
+                    .v6
+                    .as_ref()
+                    .map(|v6| util::get_ipv6_addr(&v6.prefix, 10).map(|a| a.to_string()))
+                    .transpose()?,
+                dhcp6_end: mgmt_net
+                    .v6
+                    .as_ref()
+                    .map(|v6| util::get_ipv6_addr(&v6.prefix, 254).map(|a| a.to_string()))
+                    .transpose()?,
+                dns_ipv6: mgmt_net.v6.as_ref().map(|v6| v6.boot_server.to_string()),
+                ztp_records: ztp_records.clone(),
+            };
+            let dnsmasq_rendered_template = dnsmaq_template.render()?;
+            util::create_file(
+                &format!("{dnsmasq_dir}/{DNSMASQ_CONFIG_FILE}"),
+                dnsmasq_rendered_template,
+            )?;
+            util::create_file(
+                &format!("{dnsmasq_dir}/{DNSMASQ_LEASES_FILE}"),
+                "".to_string(),
+            )?;
 
-        let is_running = container::run_container(
-            &docker_conn,
-            &format!("{CONTAINER_DNSMASQ_NAME}-{lab_id}"),
-            CONTAINER_DNSMASQ_REPO,
-            dnsmasq_env_vars,
-            dnsmasq_volumes,
-            dnsmasq_capabilities,
-            management_network_attachment,
-            vec![],
-            vec![],
-            false,
-            None,
-            None,
-        )
-        .await?;
+            // Create boot container (tftp_dir already created in Phase 8)
+            let configs_dir = format!("{ztp_dir}/{NODE_CONFIGS_DIR}");
+
+            let dnsmasq_env_dns1 = format!("DNS1={}", mgmt_net.v4.first);
+            let dnsmasq_env_dns2 = "DNS2=".to_string();
+            let boot_server_ipv4 = mgmt_net.v4.boot_server.to_string();
+
+            let webdir_config_volume = format!("{configs_dir}:/opt/{ZTP_DIR}/{NODE_CONFIGS_DIR}");
+            let dnsmasq_env_vars = vec![dnsmasq_env_dns1, dnsmasq_env_dns2];
+            let dnsmasq_config_volume =
+                format!("{dnsmasq_dir}/{DNSMASQ_CONFIG_FILE}:/etc/{DNSMASQ_CONFIG_FILE}");
+            let dnsmasq_tftp_volume = format!("{tftp_dir}:/opt/{ZTP_DIR}/{TFTP_DIR}");
+            let dnsmasq_volumes = vec![
+                dnsmasq_config_volume,
+                dnsmasq_tftp_volume,
+                webdir_config_volume,
+            ];
+            let dnsmasq_capabilities: Vec<String> = CONTAINER_DNSMASQ_CAPABILITIES
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+
+            let management_network_attachment = data::ContainerNetworkAttachment {
+                name: format!("{SHERPA_MANAGEMENT_NETWORK_NAME}-{lab_id}"),
+                ipv4_address: Some(boot_server_ipv4.clone()),
+                ipv6_address: None,
+                linux_interface_name: None,
+                admin_down: false,
+            };
 
-        if !is_running {
-            anyhow::bail!(
-                "dnsmasq boot container {CONTAINER_DNSMASQ_NAME}-{lab_id} is not in running state after start"
+            tracing::info!(
+                lab_id = %lab_id,
+                container = %format!("{CONTAINER_DNSMASQ_NAME}-{lab_id}"),
+                boot_server_ip = %boot_server_ipv4,
+                "Starting dnsmasq boot container"
             );
+
+            let is_running = runtime
+                .containers
+                .run_container(
+                    &format!("{CONTAINER_DNSMASQ_NAME}-{lab_id}"),
+                    CONTAINER_DNSMASQ_REPO,
+                    dnsmasq_env_vars,
+                    dnsmasq_volumes,
+                    dnsmasq_capabilities,
+                    management_network_attachment,
+                    vec![],
+                    vec![],
+                    false,
+                    None,
+                    None,
+                )
+                .await?;
+
+            if !is_running {
+                anyhow::bail!(
+                    "dnsmasq boot container {CONTAINER_DNSMASQ_NAME}-{lab_id} is not in running state after start"
+                );
+            }
         }
 
         phases_completed.push("Sherpa Router".to_string());
@@ -1992,7 +2409,7 @@ pub async fn up_lab(
         // ========================================================================
         let _ = progress.send_phase(data::UpPhase::DiskCloning, "Cloning disks".to_string());
 
-        node_ops::clone_node_disks(qemu_conn.clone(), clone_disks, lab_id, &progress).await?;
+        node_ops::clone_node_disks(runtime.vms.clone(), clone_disks, lab_id, &progress).await?;
 
         phases_completed.push("DiskCloning".to_string());
 
@@ -2011,10 +2428,10 @@ pub async fn up_lab(
             let tasks: Vec<_> = domains
                 .into_iter()
                 .map(|domain| {
-                    let conn = Arc::clone(&qemu_conn);
+                    let vms = runtime.vms.clone();
                     let progress_clone = progress.clone();
                     tokio::task::spawn(async move {
-                        node_ops::create_vm(conn, domain, &progress_clone).await
+                        node_ops::create_vm(vms, domain, &progress_clone).await
                     })
                 })
                 .collect();
@@ -2042,10 +2459,10 @@ pub async fn up_lab(
             let tasks: Vec<_> = unikernel_domains
                 .into_iter()
                 .map(|domain| {
-                    let conn = Arc::clone(&qemu_conn);
+                    let vms = runtime.vms.clone();
                     let progress_clone = progress.clone();
                     tokio::task::spawn(async move {
-                        node_ops::create_unikernel(conn, domain, &progress_clone).await
+                        node_ops::create_unikernel(vms, domain, &progress_clone).await
                     })
                 })
                 .collect();
@@ -2080,16 +2497,15 @@ pub async fn up_lab(
                 let tap_a = &link_data.tap_a;
                 let tap_b = &link_data.tap_b;
 
-                let ifindex_a = network::get_ifindex(tap_a)
+                runtime
+                    .network
+                    .attach_p2p_redirect(tap_a, tap_b)
                     .await
-                    .context(format!("failed to get ifindex for {tap_a}"))?;
-                let ifindex_b = network::get_ifindex(tap_b)
-                    .await
-                    .context(format!("failed to get ifindex for {tap_b}"))?;
-
-                network::attach_p2p_redirect(tap_a, ifindex_b)
                     .context(format!("failed to attach eBPF redirect on {tap_a}"))?;
-                network::attach_p2p_redirect(tap_b, ifindex_a)
+                runtime
+                    .network
+                    .attach_p2p_redirect(tap_b, tap_a)
+                    .await
                     .context(format!("failed to attach eBPF redirect on {tap_b}"))?;
 
                 // Apply link impairment if configured
@@ -2106,20 +2522,24 @@ pub async fn up_lab(
                         reorder_percent: impairment_cfg.reorder_percent.unwrap_or(0.0),
                         corrupt_percent: impairment_cfg.corrupt_percent.unwrap_or(0.0),
                     };
-                    network::apply_netem(ifindex_a as i32, &netem).await?;
-                    network::apply_netem(ifindex_b as i32, &netem).await?;
+                    runtime.network.apply_netem(tap_a, &netem).await?;
+                    runtime.network.apply_netem(tap_b, &netem).await?;
                 }
 
                 tracing::info!(
                     lab_id = %lab_id,
                     tap_a = %tap_a,
                     tap_b = %tap_b,
-                    ifindex_a = ifindex_a,
-                    ifindex_b = ifindex_b,
                     "Attached eBPF P2p redirect"
                 );
             }
 
+            for link in &filtered_p2p_links {
+                link_filter::apply_link_filter(link, runtime.network.as_ref())
+                    .await
+                    .context(format!("Failed to apply filter of link {}", link.index))?;
+            }
+
             let _ = progress.send_status(
                 "All P2p eBPF redirects attached".to_string(),
                 StatusKind::Done,
@@ -2128,6 +2548,48 @@ pub async fn up_lab(
 
         phases_completed.push("P2pEbpfAttach".to_string());
 
+        // ========================================================================
+        // PHASE 11b1: Join VLAN-aware bridge ports
+        // ========================================================================
+        // libvirt created the named taps of VLAN-aware bridge members without
+        // attaching them, so join them to their bridges with the port VLANs.
+        if !bridge_taps.is_empty() {
+            let vm_names: Vec<String> = vm_nodes
+                .iter()
+                .chain(unikernel_nodes.iter())
+                .map(|node| node.name.clone())
+                .collect();
+            let applied = bridge_vlan::apply_bridge_port_vlans(
+                &bridge_taps,
+                &vm_names,
+                runtime.network.as_ref(),
+            )
+            .await?;
+            let _ = progress.send_status(
+                format!("Joined {applied} ports to VLAN-aware bridges"),
+                StatusKind::Done,
+            );
+        }
+
+        // ========================================================================
+        // PHASE 11b2: Start manifest port mirrors
+        // ========================================================================
+        // Mirrors only copy traffic, so failures are reported without failing
+        // the lab. They can be retried with `sherpa link mirror add`.
+        if manifest.mirrors.is_some() {
+            let failures = mirror::start_manifest_mirrors(lab_id, &lab_record_id, state).await?;
+            for failure in &failures {
+                let _ = progress.send_status(
+                    format!("Failed to start port mirror {failure}"),
+                    StatusKind::Info,
+                );
+            }
+            if failures.is_empty() {
+                let _ =
+                    progress.send_status("All port mirrors started".to_string(), StatusKind::Done);
+            }
+        }
+
         // ========================================================================
         // PHASE 11c: Set VM isolated bridges DOWN
         // ========================================================================
@@ -2145,7 +2607,7 @@ pub async fn up_lab(
                     bridge_name = %iso_net.bridge_name,
                     "Setting isolated bridge DOWN to remove carrier from disabled interfaces"
                 );
-                network::set_link_down(&iso_net.bridge_name).await?;
+                runtime.network.set_link_down(&iso_net.bridge_name).await?;
             }
         }
 
@@ -2159,12 +2621,6 @@ pub async fn up_lab(
 
         tracing::info!(lab_id = %lab_id, "Generating SSH configuration");
 
-        // Load server config to get server_ipv4
-        let config_contents = util::load_file(SHERPA_CONFIG_FILE_PATH)
-            .context("Failed to load sherpa.toml config")?;
-        let config: data::Config =
-            toml::from_str(&config_contents).context("Failed to parse sherpa.toml config")?;
-
         // Use client's username for ProxyJump (same user that initiated the lab creation)
         let proxy_user = current_user;
 
@@ -2193,11 +2649,11 @@ pub async fn up_lab(
         let _ = progress.send_status("SSH config file created".to_string(), StatusKind::Done);
 
         // Read SSH private key for transfer to client
-        let ssh_private_key = util::load_file(SHERPA_SSH_PRIVATE_KEY_PATH)
-            .context("Failed to read SSH private key")?;
+        let ssh_private_key =
+            util::load_file(&paths.ssh_private_key).context("Failed to read SSH private key")?;
         tracing::debug!(
             lab_id = %lab_id,
-            key_path = %SHERPA_SSH_PRIVATE_KEY_PATH,
+            key_path = %paths.ssh_private_key,
             "Loaded SSH private key"
         );
         let _ = progress.send_status("SSH private key loaded".to_string(), StatusKind::Done);
@@ -2465,7 +2921,7 @@ pub async fn up_lab(
 
                 let capabilities = node_ops::model_capabilities(&container.model);
                 let is_running = node_ops::start_container_node(
-                    &docker_conn,
+                    runtime.containers.as_ref(),
                     &container_name,
                     &container_image,
                     env_vars,
@@ -2493,11 +2949,14 @@ pub async fn up_lab(
                     .collect();
 
                 if !container_p2p_veths.is_empty() {
-                    let pid = container::get_container_pid(&docker_conn, &container_name).await?;
+                    let pid = runtime.containers.container_pid(&container_name).await?;
 
                     for veth_info in &container_p2p_veths {
                         // Move the container-side veth into the container's netns
-                        network::move_to_netns(&veth_info.container_veth, pid).await?;
+                        runtime
+                            .network
+                            .move_to_netns(&veth_info.container_veth, pid)
+                            .await?;
 
                         // Compute the target interface name inside the container
                         let target_name = if veth_info.node_model == data::NodeModel::NokiaSrlinux {
@@ -2522,25 +2981,26 @@ pub async fn up_lab(
                                 veth_info.container_veth, target_name, target_name, target_name
                             )
                         };
-                        container::exec_container_with_retry(
-                            &docker_conn,
-                            &container_name,
-                            vec!["sh", "-c", &setup_cmd],
-                            3,
-                            std::time::Duration::from_secs(2),
-                        )
-                        .await
-                        .with_context(|| {
-                            format!(
-                                "Failed to configure P2p interface {} in container {}",
-                                target_name, container_name
+                        runtime
+                            .containers
+                            .exec_with_retry(
+                                &container_name,
+                                &["sh", "-c", &setup_cmd],
+                                3,
+                                std::time::Duration::from_secs(2),
                             )
-                        })?;
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "Failed to configure P2p interface {} in container {}",
+                                    target_name, container_name
+                                )
+                            })?;
 
                         // For disabled interfaces, bring the host-side veth DOWN
                         // so the container side loses carrier (shows as "not connected")
                         if veth_info.admin_down {
-                            network::set_link_down(&veth_info.host_veth).await?;
+                            runtime.network.set_link_down(&veth_info.host_veth).await?;
                         }
 
                         tracing::info!(
@@ -2641,11 +3101,13 @@ pub async fn up_lab(
                 let mgmt_ip = uk.ipv4_address.map(|a| a.to_string());
 
                 match node_ops::check_unikernel_ready(
-                    &qemu_conn,
+                    runtime.vms.as_ref(),
                     &domain_name,
                     uk.ready_port,
                     mgmt_ip.as_deref(),
-                )? {
+                )
+                .await?
+                {
                     true => {
                         tracing::info!(
                             lab_id = %lab_id,
@@ -2816,7 +3278,7 @@ pub async fn up_lab(
                 StatusKind::Info,
             );
 
-            match clean::clean_lab(lab_id, state).await {
+            match clean::clean_lab_in(lab_id, state, &paths.labs_dir).await {
                 Ok(clean_response) => {
                     if clean_response.success {
                         tracing::info!(lab_id = %lab_id, "Auto-cleanup completed successfully");
@@ -2851,3 +3313,263 @@ pub async fn up_lab(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::fake::{FakeContainerRuntime, FakeHostNetwork, FakeRuntime, FakeVmRuntime};
+    use bollard::secret::ContainerSummaryStateEnum;
+    use shared::data::{DatabaseEngine, NodeConfig, NodeModel};
+    use shared::konst::CONTAINER_FRR_REPO;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc;
+
+    const LAB_ID: &str = "abc12345";
+
+    /// Two FRR containers wired back to back with a P2p link.
+    fn up_request() -> data::UpRequest {
+        data::UpRequest {
+            lab_id: LAB_ID.to_string(),
+            manifest: serde_json::json!({
+                "name": "up-test",
+                "nodes": [
+                    { "name": "r1", "model": "frr_linux" },
+                    { "name": "r2", "model": "frr_linux" },
+                ],
+                "links": [{ "src": "r1::eth1", "dst": "r2::eth1", "p2p": true }],
+            }),
+            username: "admin".to_string(),
+        }
+    }
+
+    /// An FRR container linked to the `eth9` host interface.
+    fn external_request(username: &str) -> data::UpRequest {
+        data::UpRequest {
+            lab_id: LAB_ID.to_string(),
+            manifest: serde_json::json!({
+                "name": "up-test",
+                "nodes": [{ "name": "r1", "model": "frr_linux" }],
+                "links": [{ "src": "r1::eth1", "dst": "host::eth9" }],
+            }),
+            username: username.to_string(),
+        }
+    }
+
+    /// Fakes with only the boot container image present locally.
+    fn empty_host(containers: FakeContainerRuntime, network: FakeHostNetwork) -> FakeRuntime {
+        FakeRuntime::new(
+            FakeVmRuntime::default(),
+            containers.with_image(CONTAINER_DNSMASQ_REPO),
+            network,
+        )
+    }
+
+    /// State over a fresh in-memory database holding the admin user and
+    /// the FRR node image, with lab files kept under `dir`.
+    async fn setup(fakes: &FakeRuntime, dir: &TempDir) -> (AppState, LabPaths) {
+        let db = db::connect_embedded(DatabaseEngine::Memory, "", "up", "test")
+            .await
+            .expect("in-memory database opens");
+        db::apply_schema(&db).await.expect("schema applies");
+        db::seed_admin_user(&db, "Test-Password-123!")
+            .await
+            .expect("admin user seeds");
+        db::create_node_image(&db, NodeConfig::get_model(NodeModel::FrrLinux))
+            .await
+            .expect("node image creates");
+
+        let root = dir.path().to_string_lossy();
+        let paths = LabPaths {
+            labs_dir: format!("{root}/labs"),
+            ssh_public_key: format!("{root}/sherpa_ssh_key.pub"),
+            ssh_private_key: format!("{root}/sherpa_ssh_key"),
+        };
+        util::create_dir(&paths.labs_dir).expect("labs dir creates");
+        util::create_file(
+            &paths.ssh_public_key,
+            "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIE sherpa".to_string(),
+        )
+        .expect("public key writes");
+        util::create_file(&paths.ssh_private_key, "private key".to_string())
+            .expect("private key writes");
+
+        let state = AppState::for_tests(db, util::default_config(), fakes.runtime());
+        (state, paths)
+    }
+
+    fn progress() -> ProgressSender {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        ProgressSender::new(tx)
+    }
+
+    #[tokio::test]
+    async fn test_up_lab_creates_lab_on_runtime() {
+        let fakes = empty_host(FakeContainerRuntime::default(), FakeHostNetwork::default());
+        let dir = TempDir::new().expect("tempdir creates");
+        let (state, paths) = setup(&fakes, &dir).await;
+
+        let response = up_lab_in(up_request(), &state, progress(), &paths)
+            .await
+            .expect("lab comes up");
+
+        assert!(response.success);
+        let image = format!(
+            "{CONTAINER_FRR_REPO}:{}",
+            NodeConfig::get_model(NodeModel::FrrLinux).version
+        );
+        assert!(
+            fakes
+                .containers
+                .calls()
+                .contains(&format!("pull_image {image}"))
+        );
+        assert_eq!(
+            fakes.containers.containers(),
+            [
+                format!("{CONTAINER_DNSMASQ_NAME}-{LAB_ID}"),
+                format!("r1-{LAB_ID}"),
+                format!("r2-{LAB_ID}"),
+            ]
+            .into_iter()
+            .map(|name| (name, ContainerSummaryStateEnum::RUNNING))
+            .collect()
+        );
+        assert!(
+            fakes
+                .vms
+                .networks()
+                .contains(&format!("{SHERPA_MANAGEMENT_NETWORK_NAME}-{LAB_ID}"))
+        );
+        let host_calls = fakes.network.calls();
+        for call in [
+            "create_veth_pair ",
+            "move_to_netns ",
+            "attach_p2p_redirect ",
+        ] {
+            assert!(
+                host_calls.iter().any(|c| c.starts_with(call)),
+                "missing {call}in {host_calls:?}"
+            );
+        }
+        assert_eq!(
+            db::get_lab(&state.db, LAB_ID)
+                .await
+                .expect("lab is recorded")
+                .name,
+            "up-test"
+        );
+        assert!(Path::new(&format!("{}/{LAB_ID}", paths.labs_dir)).exists());
+    }
+
+    #[tokio::test]
+    async fn test_up_lab_rolls_back_after_failure() {
+        let fakes = empty_host(
+            FakeContainerRuntime::default().fail_on(&format!("run_container r2-{LAB_ID}")),
+            FakeHostNetwork::default(),
+        );
+        let dir = TempDir::new().expect("tempdir creates");
+        let (state, paths) = setup(&fakes, &dir).await;
+
+        let result = up_lab_in(up_request(), &state, progress(), &paths).await;
+
+        assert!(result.is_err());
+        // r1 was running before r2 failed to start
+        assert!(
+            fakes
+                .containers
+                .calls()
+                .contains(&format!("run_container r1-{LAB_ID}"))
+        );
+        assert!(fakes.containers.containers().is_empty());
+        assert!(fakes.containers.networks().is_empty());
+        assert!(fakes.vms.networks().is_empty());
+        assert!(fakes.network.interfaces().is_empty());
+        assert!(db::get_lab(&state.db, LAB_ID).await.is_err());
+        assert!(!Path::new(&format!("{}/{LAB_ID}", paths.labs_dir)).exists());
+    }
+
+    #[tokio::test]
+    async fn test_up_lab_limits_host_interfaces_to_admins() {
+        let fakes = empty_host(
+            FakeContainerRuntime::default(),
+            FakeHostNetwork::default().with_interface("eth9"),
+        );
+        let dir = TempDir::new().expect("tempdir creates");
+        let (mut state, paths) = setup(&fakes, &dir).await;
+        db::create_user(
+            &state.db,
+            "alice".to_string(),
+            "Test-Password-123!",
+            false,
+            vec![],
+        )
+        .await
+        .expect("user creates");
+
+        let error = up_lab_in(external_request("alice"), &state, progress(), &paths)
+            .await
+            .expect_err("non-admin cannot use an unlisted host interface");
+        assert!(format!("{error:#}").contains("host_interfaces"));
+        assert!(db::get_lab(&state.db, LAB_ID).await.is_err());
+
+        let mut config = util::default_config();
+        config.host_interfaces = vec!["eth9".to_string()];
+        state.config = Arc::new(config);
+        up_lab_in(external_request("alice"), &state, progress(), &paths)
+            .await
+            .expect("non-admin can use a listed host interface");
+        assert!(
+            fakes
+                .network
+                .calls()
+                .iter()
+                .any(|c| c.starts_with("attach_host_interface eth9 "))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_up_lab_rejects_claimed_host_interface() {
+        let fakes = empty_host(
+            FakeContainerRuntime::default(),
+            FakeHostNetwork::default().with_interface("eth9"),
+        );
+        let dir = TempDir::new().expect("tempdir creates");
+        let (state, paths) = setup(&fakes, &dir).await;
+        state
+            .host_interface_claims
+            .insert("eth9".to_string(), "other123".to_string());
+
+        let error = up_lab_in(external_request("admin"), &state, progress(), &paths)
+            .await
+            .expect_err("a claimed host interface is refused");
+        assert!(format!("{error:#}").contains("being attached by lab other123"));
+        assert_eq!(
+            state
+                .host_interface_claims
+                .get("eth9")
+                .map(|lab| lab.clone()),
+            Some("other123".to_string())
+        );
+        assert!(db::get_lab(&state.db, LAB_ID).await.is_err());
+
+        state.host_interface_claims.remove("eth9");
+        up_lab_in(external_request("admin"), &state, progress(), &paths)
+            .await
+            .expect("lab comes up once the claim is released");
+        assert!(state.host_interface_claims.is_empty());
+    }
+
+    #[test]
+    fn test_host_interface_claim_is_all_or_nothing() {
+        let claims = Arc::new(DashMap::new());
+        let (eth8, eth9) = ("eth8".to_string(), "eth9".to_string());
+
+        let claim = HostInterfaceClaim::acquire(&claims, "lab00001", &[&eth9])
+            .expect("free interface is claimed");
+        assert!(HostInterfaceClaim::acquire(&claims, "lab00002", &[&eth8, &eth9]).is_err());
+        assert_eq!(claims.len(), 1);
+
+        drop(claim);
+        assert!(claims.is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! Resumable chunked image uploads.
+//!
+//! Each upload lives in `SHERPA_IMAGES_UPLOAD_PATH/<upload_id>` as the partial
+//! file plus the request that started it. The upload ID is derived from the
+//! model, version and file digest, so a client restarting an interrupted
+//! transfer gets the same upload back and continues from the received offset.
+//! The image is verified and imported when the final chunk arrives.
+
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tracing::instrument;
+
+use shared::data::{
+    CancelUploadResponse, ImportRequest, ImportResponse, StartUploadRequest, StatusKind,
+    UploadChunkResponse, UploadStatus,
+};
+use shared::konst::{IMAGE_UPLOAD_MAX_CHUNK_SIZE, SHERPA_IMAGES_UPLOAD_PATH};
+use shared::util::{Checksum, ChecksumAlgorithm, create_dir, delete_dirs, file_sha256};
+
+use crate::daemon::state::AppState;
+use crate::services::import;
+use crate::services::progress::ProgressSender;
+
+/// Partial image data of an upload
+const UPLOAD_DATA_FILE: &str = "data";
+/// Start request of an upload, kept so the upload survives a server restart
+const UPLOAD_META_FILE: &str = "upload.json";
+
+/// Uploads with a chunk currently being written
+static ACTIVE_UPLOADS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Marks an upload as busy until dropped, so concurrent chunks for the same
+/// upload can't interleave.
+struct UploadGuard(String);
+
+impl UploadGuard {
+    fn acquire(upload_id: &str) -> Result<Self> {
+        let mut active = ACTIVE_UPLOADS
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Upload lock poisoned"))?;
+        if !active.insert(upload_id.to_string()) {
+            bail!("A chunk for upload '{}' is already in progress", upload_id);
+        }
+        Ok(Self(upload_id.to_string()))
+    }
+}
+
+impl Drop for UploadGuard {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE_UPLOADS.lock() {
+            active.remove(&self.0);
+        }
+    }
+}
+
+/// Derive the upload ID from the model, version and file digest.
+fn upload_id(request: &StartUploadRequest, sha256: &str) -> String {
+    let key = format!("{}:{}:{}", request.model, request.version, sha256);
+    format!("{:x}", Sha256::digest(key.as_bytes()))[..32].to_string()
+}
+
+/// Get the directory of an upload, rejecting IDs that aren't ours.
+fn upload_dir(upload_id: &str) -> Result<String> {
+    if upload_id.len() != 32 || !upload_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid upload ID: '{}'", upload_id);
+    }
+    Ok(format!("{SHERPA_IMAGES_UPLOAD_PATH}/{upload_id}"))
+}
+
+/// Bytes received so far for an upload.
+async fn received_bytes(data_path: &str) -> u64 {
+    tokio::fs::metadata(data_path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
+/// Load the start request of an upload.
+async fn load_upload(upload_id: &str, dir: &str) -> Result<StartUploadRequest> {
+    let contents = tokio::fs::read_to_string(format!("{dir}/{UPLOAD_META_FILE}"))
+        .await
+        .with_context(|| format!("Upload '{}' not found, start the upload again", upload_id))?;
+    serde_json::from_str(&contents).context("Failed to parse upload metadata")
+}
+
+/// Start a chunked upload, or resume an existing one for the same file.
+///
+/// # Returns
+/// The upload state, including the offset the next chunk must start at
+///
+/// # Errors
+/// Returns an error if the request is invalid or the upload directory
+/// cannot be written
+#[instrument(skip_all, fields(model = %request.model, version = %request.version))]
+pub async fn start_upload(request: StartUploadRequest) -> Result<UploadStatus> {
+    if request.version.is_empty() {
+        bail!("Version cannot be empty");
+    }
+    if request.size == 0 {
+        bail!("Cannot upload an empty file");
+    }
+    let checksum: Checksum = request.sha256.parse().context("Invalid upload sha256")?;
+    if checksum.algorithm != ChecksumAlgorithm::Sha256 {
+        bail!("Invalid upload sha256: expected a SHA-256 digest");
+    }
+
+    let upload_id = upload_id(&request, &checksum.digest);
+    let dir = upload_dir(&upload_id)?;
+    create_dir(&dir).context("Failed to create upload directory")?;
+
+    // Keep the latest import options when an upload is resumed
+    let mut meta = request;
+    meta.sha256 = checksum.digest;
+    tokio::fs::write(
+        format!("{dir}/{UPLOAD_META_FILE}"),
+        serde_json::to_string(&meta)?,
+    )
+    .await
+    .context("Failed to write upload metadata")?;
+
+    let received = received_bytes(&format!("{dir}/{UPLOAD_DATA_FILE}")).await;
+    tracing::info!(
+        upload_id = %upload_id,
+        filename = %meta.filename,
+        size = meta.size,
+        received,
+        "Image upload started"
+    );
+
+    Ok(UploadStatus {
+        upload_id,
+        size: meta.size,
+        received,
+        max_chunk_size: IMAGE_UPLOAD_MAX_CHUNK_SIZE,
+    })
+}
+
+/// Append a chunk to an upload, importing the image once it is complete.
+///
+/// Chunks must arrive in order: `offset` must equal the bytes already
+/// received. Sending an empty chunk at the end of a complete upload retries
+/// an import that failed.
+///
+/// # Errors
+/// Returns an error if the upload is unknown, the offset or chunk digest
+/// doesn't match, or the import fails
+#[instrument(skip(data, state, progress))]
+pub async fn upload_chunk(
+    upload_id: &str,
+    offset: u64,
+    sha256: &str,
+    data: &[u8],
+    state: &AppState,
+    progress: ProgressSender,
+) -> Result<UploadChunkResponse> {
+    let dir = upload_dir(upload_id)?;
+    let meta = load_upload(upload_id, &dir).await?;
+    let _guard = UploadGuard::acquire(upload_id)?;
+
+    let data_path = format!("{dir}/{UPLOAD_DATA_FILE}");
+    let mut received = received_bytes(&data_path).await;
+    let len = data.len() as u64;
+
+    if len > IMAGE_UPLOAD_MAX_CHUNK_SIZE {
+        bail!(
+            "Invalid chunk: {} bytes exceeds the maximum of {} bytes",
+            len,
+            IMAGE_UPLOAD_MAX_CHUNK_SIZE
+        );
+    }
+    if offset != received {
+        bail!(
+            "Invalid chunk offset {}: the server has received {} bytes",
+            offset,
+            received
+        );
+    }
+    if received + len > meta.size {
+        bail!(
+            "Invalid chunk: upload would exceed the file size of {} bytes",
+            meta.size
+        );
+    }
+
+    let digest = format!("{:x}", Sha256::digest(data));
+    if digest != sha256.to_ascii_lowercase() {
+        bail!("Invalid chunk checksum at offset {}", offset);
+    }
+
+    if len > 0 {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&data_path)
+            .await
+            .context("Failed to open upload file")?;
+        file.write_all(data)
+            .await
+            .context("Failed to write upload chunk")?;
+        file.sync_data()
+            .await
+            .context("Failed to flush upload chunk")?;
+        received += len;
+    }
+
+    let import = if received == meta.size {
+        Some(complete_upload(upload_id, &dir, meta.clone(), state, progress).await?)
+    } else {
+        None
+    };
+
+    Ok(UploadChunkResponse {
+        upload_id: upload_id.to_string(),
+        size: meta.size,
+        received,
+        import,
+    })
+}
+
+/// Verify a complete upload against its digest and import it.
+///
+/// An upload that doesn't match its digest is discarded. One whose import
+/// fails is kept, so the import can be retried without uploading again.
+async fn complete_upload(
+    upload_id: &str,
+    dir: &str,
+    meta: StartUploadRequest,
+    state: &AppState,
+    progress: ProgressSender,
+) -> Result<ImportResponse> {
+    let data_path = format!("{dir}/{UPLOAD_DATA_FILE}");
+
+    let _ = progress.send_status(
+        format!("Upload complete, verifying SHA-256 of {}...", meta.filename),
+        StatusKind::Progress,
+    );
+    let digest_path = data_path.clone();
+    let digest = tokio::task::spawn_blocking(move || file_sha256(&digest_path))
+        .await
+        .context("Upload digest task failed")??;
+    if digest != meta.sha256 {
+        if let Err(e) = delete_dirs(dir) {
+            tracing::warn!("Failed to discard upload {}: {:?}", upload_id, e);
+        }
+        bail!(
+            "Checksum mismatch for upload '{}': expected sha256:{}, got sha256:{}. The upload was discarded",
+            meta.filename,
+            meta.sha256,
+            digest
+        );
+    }
+    let _ = progress.send_status("Upload verified".to_string(), StatusKind::Done);
+
+    let request = ImportRequest {
+        model: meta.model,
+        version: meta.version,
+        src: data_path,
+        default: meta.default,
+        checksum: Some(format!("sha256:{}", meta.sha256)),
+        compress: meta.compress,
+        sparsify: meta.sparsify,
+    };
+    let response = import::import_verified_image(request, state, progress).await?;
+
+    if let Err(e) = delete_dirs(dir) {
+        tracing::warn!("Failed to clean up upload {}: {:?}", upload_id, e);
+    }
+    tracing::info!(upload_id = %upload_id, "Image upload imported");
+
+    Ok(response)
+}
+
+/// Cancel an upload and delete its partial data.
+///
+/// # Errors
+/// Returns an error if the upload ID is invalid or the data cannot be deleted
+#[instrument]
+pub async fn cancel_upload(upload_id: &str) -> Result<CancelUploadResponse> {
+    let dir = upload_dir(upload_id)?;
+    let _guard = UploadGuard::acquire(upload_id)?;
+
+    let deleted = tokio::fs::try_exists(&dir).await.unwrap_or(false);
+    if deleted {
+        delete_dirs(&dir).context("Failed to delete upload data")?;
+        tracing::info!(upload_id = %upload_id, "Image upload cancelled");
+    }
+
+    Ok(CancelUploadResponse {
+        upload_id: upload_id.to_string(),
+        deleted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::NodeModel;
+
+    fn start_request(version: &str, sha256: &str) -> StartUploadRequest {
+        StartUploadRequest {
+            model: NodeModel::UbuntuLinux,
+            version: version.to_string(),
+            filename: "ubuntu.qcow2".to_string(),
+            size: 5,
+            sha256: sha256.to_string(),
+            default: false,
+            compress: false,
+            sparsify: false,
+        }
+    }
+
+    #[test]
+    fn test_upload_id_is_stable_per_file() {
+        let digest = "a".repeat(64);
+        let first = upload_id(&start_request("24.04", &digest), &digest);
+        let again = upload_id(&start_request("24.04", &digest), &digest);
+        let other = upload_id(&start_request("24.10", &digest), &digest);
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+        assert_eq!(first.len(), 32);
+        assert!(upload_dir(&first).is_ok());
+    }
+
+    #[test]
+    fn test_upload_dir_rejects_foreign_ids() {
+        assert!(upload_dir("../../etc").is_err());
+        assert!(upload_dir(&"z".repeat(32)).is_err());
+        assert!(upload_dir("abc").is_err());
+    }
+
+    #[test]
+    fn test_upload_guard_blocks_concurrent_chunks() {
+        let id = "0123456789abcdef0123456789abcdef";
+        let guard = UploadGuard::acquire(id).unwrap();
+        assert!(UploadGuard::acquire(id).is_err());
+        drop(guard);
+        assert!(UploadGuard::acquire(id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_start_upload_rejects_invalid_requests() {
+        let err = start_upload(start_request("", &"a".repeat(64)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Version"));
+
+        let err = start_upload(start_request("24.04", &"a".repeat(32)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("SHA-256"));
+    }
+}
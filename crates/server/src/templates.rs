@@ -2,11 +2,14 @@ use askama::Template;
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Response};
 use shared::data::{
-    BridgeInfo, DbUser, DestroyError, DeviceInfo, LabInfo, LabState, LabSummary, LinkInfo,
-    NodeConfig,
+    BridgeInfo, DbUser, DestroyError, DeviceInfo, LabInfo, LabShareInfo, LabState, LabSummary,
+    LinkInfo, NodeConfig,
 };
 
-use crate::api::handlers::{ImageSummary, UserSummary};
+use crate::api::handlers::{
+    ApiTokenSummary, ImageSummary, ImageUsageSummary, LeaseSummary, OrphanedDiskSummary,
+    UserSummary,
+};
 
 mod filters {
     pub fn initial(s: &str, _: &dyn askama::Values) -> askama::Result<String> {
@@ -134,6 +137,12 @@ impl IntoResponse for ErrorTemplate {
 pub struct LoginPageTemplate {
     pub error: String,
     pub message: String,
+    /// Show the username/password form (local or LDAP login)
+    pub password_enabled: bool,
+    /// Show the OIDC single sign-on button
+    pub sso_enabled: bool,
+    /// Show the signup link (local accounts only)
+    pub signup_enabled: bool,
 }
 
 impl IntoResponse for LoginPageTemplate {
@@ -303,6 +312,13 @@ pub struct LabDetailTemplate {
     pub link_count: usize,
     pub bridges: Vec<BridgeInfo>,
     pub bridge_count: usize,
+    pub topology_svg: String,
+    /// Whether the user can start, stop and redeploy nodes (operator or higher)
+    pub can_operate: bool,
+    /// Whether the user can destroy the lab and manage its shares (owner or admin)
+    pub can_manage: bool,
+    pub shares: Vec<LabShareInfo>,
+    pub share_error: Option<String>,
 }
 
 impl IntoResponse for LabDetailTemplate {
@@ -361,6 +377,7 @@ pub struct ProfileTemplate {
     pub is_admin: bool,
     pub active_page: String,
     pub ssh_keys_html: String,
+    pub api_tokens_html: String,
 }
 
 impl IntoResponse for ProfileTemplate {
@@ -456,6 +473,30 @@ impl IntoResponse for SshKeyErrorTemplate {
     }
 }
 
+/// API tokens list partial, swapped in after creating or revoking a token
+///
+/// `new_token` holds a freshly created token, which is only ever shown once.
+#[derive(Template)]
+#[template(path = "user/partials/api-tokens-list.html.jinja")]
+pub struct ApiTokensListTemplate {
+    pub tokens: Vec<ApiTokenSummary>,
+    pub new_token: Option<String>,
+    pub token_error: Option<String>,
+}
+
+impl IntoResponse for ApiTokensListTemplate {
+    fn into_response(self) -> Response {
+        match self.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to render template: {}", err),
+            )
+                .into_response(),
+        }
+    }
+}
+
 // ============================================================================
 // Admin User Management Templates
 // ============================================================================
@@ -615,6 +656,10 @@ pub struct AdminImagesListTemplate {
     pub is_admin: bool,
     pub active_page: String,
     pub configs: Vec<ImageSummary>,
+    pub usage: Vec<ImageUsageSummary>,
+    pub orphaned_disks: Vec<OrphanedDiskSummary>,
+    pub total_size: String,
+    pub usage_error: Option<String>,
 }
 
 impl IntoResponse for AdminImagesListTemplate {
@@ -858,6 +903,70 @@ impl IntoResponse for NodesTableFragment {
     }
 }
 
+/// Topology diagram partial for HTMX polling
+#[derive(Template)]
+#[template(path = "user/partials/lab-topology.html.jinja")]
+pub struct LabTopologyFragment {
+    pub topology_svg: String,
+}
+
+impl IntoResponse for LabTopologyFragment {
+    fn into_response(self) -> Response {
+        match self.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to render template: {}", err),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// DHCP leases table partial, loaded and polled by HTMX
+#[derive(Template)]
+#[template(path = "user/partials/lab-leases.html.jinja")]
+pub struct LabLeasesFragment {
+    pub lab_id: String,
+    pub leases: Vec<LeaseSummary>,
+    pub lease_error: Option<String>,
+}
+
+impl IntoResponse for LabLeasesFragment {
+    fn into_response(self) -> Response {
+        match self.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to render template: {}", err),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Lab shares list partial, swapped in after adding or removing a share
+#[derive(Template)]
+#[template(path = "user/partials/lab-shares.html.jinja")]
+pub struct LabSharesFragment {
+    pub lab_id: String,
+    pub shares: Vec<LabShareInfo>,
+    pub share_error: Option<String>,
+}
+
+impl IntoResponse for LabSharesFragment {
+    fn into_response(self) -> Response {
+        match self.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to render template: {}", err),
+            )
+                .into_response(),
+        }
+    }
+}
+
 // ============================================================================
 // Lab Destroy Fragment Templates
 // ============================================================================
@@ -978,6 +1087,7 @@ mod tests {
 
     use super::filters::initial;
     use super::*;
+    use shared::data::{LabRole, TokenScope};
 
     // ========================================================================
     // Filter tests
@@ -1045,11 +1155,123 @@ mod tests {
                 name: "test-lab".to_string(),
                 status: LabState::Unknown,
                 node_count: 2,
+                shared_role: None,
             }],
         };
         let html = tpl.render().expect("template should render");
         assert!(html.contains("test-lab"));
         assert!(html.contains("a10736e8"));
+        assert!(html.contains("labStart"));
+    }
+
+    #[test]
+    fn test_lab_shares_fragment_renders_grantees() {
+        let tpl = LabSharesFragment {
+            lab_id: "a10736e8".to_string(),
+            shares: vec![
+                LabShareInfo {
+                    username: Some("alice".to_string()),
+                    team: None,
+                    role: LabRole::Operator,
+                },
+                LabShareInfo {
+                    username: None,
+                    team: Some("students".to_string()),
+                    role: LabRole::Viewer,
+                },
+            ],
+            share_error: Some("User not found: bob".to_string()),
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("/labs/a10736e8/shares/alice"));
+        assert!(html.contains("/labs/a10736e8/shares/team:students"));
+        assert!(html.contains("operator"));
+        assert!(html.contains("User not found: bob"));
+    }
+
+    #[test]
+    fn test_lab_leases_fragment_flags_unknown_macs() {
+        let tpl = LabLeasesFragment {
+            lab_id: "a10736e8".to_string(),
+            leases: vec![
+                LeaseSummary {
+                    mac_address: "52:54:00:aa:bb:01".to_string(),
+                    ipv4_address: "172.31.0.11".to_string(),
+                    hostname: "dev01".to_string(),
+                    node_name: Some("dev01".to_string()),
+                    expires_formatted: "2026-10-18 12:00:00".to_string(),
+                },
+                LeaseSummary {
+                    mac_address: "52:54:00:ff:ff:ff".to_string(),
+                    ipv4_address: "172.31.0.100".to_string(),
+                    hostname: "-".to_string(),
+                    node_name: None,
+                    expires_formatted: "never".to_string(),
+                },
+            ],
+            lease_error: None,
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("/labs/a10736e8/nodes/dev01"));
+        assert!(html.contains("172.31.0.100"));
+        assert!(html.contains("Unknown"));
+        assert!(html.contains("(2 active)"));
+
+        let tpl = LabLeasesFragment {
+            lab_id: "a10736e8".to_string(),
+            leases: vec![],
+            lease_error: Some("Failed to load leases: lab is down".to_string()),
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("Failed to load leases: lab is down"));
+    }
+
+    #[test]
+    fn test_api_tokens_list_template_shows_new_token_once() {
+        let tpl = ApiTokensListTemplate {
+            tokens: vec![ApiTokenSummary {
+                name: "ci".to_string(),
+                prefix: "sherpa_pat_1a2b3c4d".to_string(),
+                scopes: vec![TokenScope::ReadOnly, TokenScope::LabOperate],
+                expired: false,
+                expires_at_formatted: "Jan 16, 2027".to_string(),
+                last_used_at_formatted: "never".to_string(),
+            }],
+            new_token: Some("sherpa_pat_1a2b3c4d5e6f".to_string()),
+            token_error: None,
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("/profile/api-tokens/ci"));
+        assert!(html.contains("lab-operate"));
+        assert!(html.contains("sherpa_pat_1a2b3c4d5e6f"));
+        assert!(html.contains("will not be shown again"));
+
+        let tpl = ApiTokensListTemplate {
+            tokens: vec![],
+            new_token: None,
+            token_error: Some("At least one token scope is required".to_string()),
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("No API tokens created yet"));
+        assert!(html.contains("At least one token scope is required"));
+    }
+
+    #[test]
+    fn test_labs_grid_template_marks_shared_labs() {
+        let tpl = LabsGridTemplate {
+            labs: vec![LabSummary {
+                id: "b20846f9".to_string(),
+                name: "class-lab".to_string(),
+                status: LabState::Unknown,
+                node_count: 2,
+                shared_role: Some(LabRole::Viewer),
+            }],
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("class-lab"));
+        assert!(html.contains("viewer"));
+        // Viewers cannot start or stop the lab
+        assert!(!html.contains("labStart"));
     }
 
     #[test]
@@ -1152,9 +1374,29 @@ mod tests {
         let tpl = LoginPageTemplate {
             error: String::new(),
             message: String::new(),
+            password_enabled: true,
+            sso_enabled: false,
+            signup_enabled: true,
         };
         let html = tpl.render().expect("template should render");
         assert!(html.contains("Login") || html.contains("login"));
+        assert!(html.contains("/signup"));
+        assert!(!html.contains("/auth/oidc/login"));
+    }
+
+    #[test]
+    fn test_login_page_template_sso_only() {
+        let tpl = LoginPageTemplate {
+            error: String::new(),
+            message: String::new(),
+            password_enabled: false,
+            sso_enabled: true,
+            signup_enabled: false,
+        };
+        let html = tpl.render().expect("template should render");
+        assert!(html.contains("/auth/oidc/login"));
+        assert!(!html.contains("hx-post=\"/login\""));
+        assert!(!html.contains("/signup"));
     }
 
     #[test]
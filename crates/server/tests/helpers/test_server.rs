@@ -4,8 +4,8 @@ use bollard::Docker;
 use dashmap::DashMap;
 use libvirt::Qemu;
 use shared::data::{
-    Config, ConfigurationManagement, OtelConfig, ScannerConfig, ServerConnection, TlsConfig,
-    VmProviders, ZtpServer,
+    AuthConfig, Config, ConfigurationManagement, DatabaseConfig, OtelConfig, ScannerConfig,
+    ServerConnection, TlsConfig, VmProviders, ZtpServer,
 };
 use shared::konst::SHERPA_PASSWORD;
 use std::net::SocketAddr;
@@ -17,6 +17,7 @@ use sherpad::api::build_router;
 use sherpad::api::websocket;
 use sherpad::daemon::metrics::Metrics;
 use sherpad::daemon::state::AppState;
+use sherpad::runtime::Runtime;
 
 /// Default admin password used for test servers
 pub const TEST_ADMIN_PASSWORD: &str = "TestPass123!";
@@ -74,19 +75,31 @@ impl TestServer {
             tls: TlsConfig::default(),
             otel: OtelConfig::default(),
             scanner: ScannerConfig::default(),
+            auth: AuthConfig::default(),
+            database: DatabaseConfig::default(),
+            host_interfaces: vec![],
         };
 
         let jwt_secret: Vec<u8> = (0..32).map(|_| rand::random::<u8>()).collect();
+        let registry_key: Vec<u8> = (0..32).map(|_| rand::random::<u8>()).collect();
+
+        let qemu = Arc::new(qemu);
+        let docker = Arc::new(docker);
 
         let state = AppState {
             connections: sherpad::api::websocket::connection::create_registry(),
             db: db.clone(),
-            qemu: Arc::new(qemu),
-            docker: Arc::new(docker),
+            runtime: Runtime::new(qemu.clone(), docker.clone()),
+            qemu,
+            docker,
             config: Arc::new(config),
             jwt_secret: Arc::new(jwt_secret),
+            registry_key: Arc::new(registry_key),
             metrics: Metrics::noop(),
+            boot_services: Arc::new(DashMap::new()),
             pending_jobs: Arc::new(DashMap::new()),
+            cabling: Arc::new(DashMap::new()),
+            host_interface_claims: Arc::new(DashMap::new()),
         };
 
         let app = build_router()
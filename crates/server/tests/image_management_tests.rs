@@ -128,31 +128,30 @@ async fn test_image_set_default() -> Result<()> {
     // List images to get a model and version
     let list_resp = ws.rpc_call("image.list", json!({ "token": token })).await?;
 
-    if let Some(result) = list_resp.get("result") {
-        if let Some(images) = result.as_array() {
-            if let Some(first) = images.first() {
-                let model = first.get("model").and_then(|v| v.as_str()).unwrap_or("");
-                let version = first.get("version").and_then(|v| v.as_str()).unwrap_or("");
-
-                if !model.is_empty() && !version.is_empty() {
-                    let response = ws
-                        .rpc_call(
-                            "image.set_default",
-                            json!({
-                                "token": token,
-                                "model": model,
-                                "version": version,
-                            }),
-                        )
-                        .await?;
-
-                    assert!(
-                        response.get("result").is_some(),
-                        "set_default should succeed: {:?}",
-                        response
-                    );
-                }
-            }
+    if let Some(result) = list_resp.get("result")
+        && let Some(images) = result.as_array()
+        && let Some(first) = images.first()
+    {
+        let model = first.get("model").and_then(|v| v.as_str()).unwrap_or("");
+        let version = first.get("version").and_then(|v| v.as_str()).unwrap_or("");
+
+        if !model.is_empty() && !version.is_empty() {
+            let response = ws
+                .rpc_call(
+                    "image.set_default",
+                    json!({
+                        "token": token,
+                        "model": model,
+                        "version": version,
+                    }),
+                )
+                .await?;
+
+            assert!(
+                response.get("result").is_some(),
+                "set_default should succeed: {:?}",
+                response
+            );
         }
     }
 
@@ -222,6 +221,43 @@ async fn test_image_pull_container() -> Result<()> {
     Ok(())
 }
 
+// ── Image Download (OCI) ──
+
+/// Needs a local registry holding an Ubuntu cloud image as an OCI artifact:
+///
+/// ```text
+/// docker run -d -p 5000:5000 registry:2
+/// oras push --plain-http localhost:5000/sherpa/ubuntu:24.04 ubuntu-24.04-server-cloudimg-amd64.img
+/// ```
+#[tokio::test]
+#[ignore]
+async fn test_image_download_oci_artifact() -> Result<()> {
+    let server = TestServer::start().await?;
+    let mut ws = TestWsClient::connect(&server).await?;
+    let token = ws.login_admin().await?;
+
+    let (_statuses, response) = ws
+        .rpc_call_streaming(
+            "image.download",
+            json!({
+                "token": token,
+                "model": "ubuntu_linux",
+                "version": "24.04-oci",
+                "url": "oci://localhost:5000/sherpa/ubuntu:24.04",
+            }),
+            Duration::from_secs(300),
+        )
+        .await?;
+
+    let result = response
+        .get("result")
+        .unwrap_or_else(|| panic!("image.download should succeed: {:?}", response));
+    assert_eq!(result["db_tracked"], true);
+    assert!(result["image_sha256"].is_string());
+
+    Ok(())
+}
+
 // ── Non-Admin Access Denied ──
 
 #[tokio::test]
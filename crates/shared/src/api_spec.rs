@@ -1,19 +1,34 @@
 use std::collections::BTreeMap;
+use std::sync::LazyLock;
 
 use schemars::schema_for;
 use serde::Serialize;
 use serde_json::json;
 
 use crate::data::{
-    ChangePasswordRequest, ChangePasswordResponse, ContainerPullRequest, ContainerPullResponse,
-    CreateUserRequest, CreateUserResponse, DeleteImageRequest, DeleteImageResponse,
-    DeleteUserRequest, DeleteUserResponse, DestroyRequest, DestroyResponse, DownloadImageRequest,
-    GetUserInfoRequest, GetUserInfoResponse, ImportRequest, ImportResponse, InspectRequest,
-    InspectResponse, LabNodeActionResponse, ListImagesRequest, ListImagesResponse,
-    ListUsersRequest, ListUsersResponse, LoginRequest, LoginResponse, RedeployRequest,
-    RedeployResponse, ScanImagesRequest, ScanImagesResponse, SetDefaultImageRequest,
-    SetDefaultImageResponse, ShowImageRequest, ShowImageResponse, UpRequest, UpResponse,
-    UpdateImpairmentRequest, UpdateImpairmentResponse, ValidateRequest, ValidateResponse,
+    AddCustomModelRequest, AddCustomModelResponse, ApiTokenInfo, AuthProvidersResponse,
+    CancelUploadRequest, CancelUploadResponse, ChangePasswordRequest, ChangePasswordResponse,
+    ContainerPullRequest, ContainerPullResponse, CreateApiTokenRequest, CreateApiTokenResponse,
+    CreateTeamRequest, CreateUserRequest, CreateUserResponse, DeleteCustomModelRequest,
+    DeleteCustomModelResponse, DeleteImageRequest, DeleteImageResponse, DeleteTeamRequest,
+    DeleteTeamResponse, DeleteUserRequest, DeleteUserResponse, DestroyRequest, DestroyResponse,
+    DeviceLoginPollRequest, DeviceLoginPollResponse, DeviceLoginStartResponse,
+    DownloadImageRequest, GetUserInfoRequest, GetUserInfoResponse, ImageUsageResponse,
+    ImportRequest, ImportResponse, InspectRequest, InspectResponse, LabLeasesRequest,
+    LabLeasesResponse, LabNodeActionResponse, LinkFilterRequest, LinkFilterResponse,
+    LinkMirrorRequest, LinkMirrorResponse, LinkStatsRequest, LinkStatsResponse,
+    ListApiTokensRequest, ListApiTokensResponse, ListCustomModelsResponse, ListImagesRequest,
+    ListImagesResponse, ListLabSharesRequest, ListLabSharesResponse, ListRegistriesResponse,
+    ListTeamsRequest, ListTeamsResponse, ListUsersRequest, ListUsersResponse, LoginRequest,
+    LoginResponse, NodeCommitRequest, NodeCommitResponse, PruneImagesRequest, PruneImagesResponse,
+    RedeployRequest, RedeployResponse, RegistryLoginRequest, RegistryLoginResponse,
+    RegistryLogoutRequest, RegistryLogoutResponse, RevokeApiTokenRequest, RevokeApiTokenResponse,
+    ScanImagesRequest, ScanImagesResponse, SetDefaultImageRequest, SetDefaultImageResponse,
+    ShareLabRequest, ShowImageRequest, ShowImageResponse, StartUploadRequest, TeamInfo, TokenScope,
+    UnshareLabRequest, UpRequest, UpResponse, UpdateImpairmentRequest, UpdateImpairmentResponse,
+    UpdateTeamMembersRequest, UploadChunkRequest, UploadChunkResponse, UploadStatus,
+    ValidateRequest, ValidateResponse, VerifyCablingRequest, VerifyCablingResponse,
+    VerifyImageRequest, VerifyImageResponse,
 };
 
 /// Top-level unified API specification
@@ -38,6 +53,10 @@ pub struct OperationDef {
     pub category: Category,
     /// Authentication requirement
     pub auth: AuthRequirement,
+    /// Scope an API token needs for this operation (`None` if API tokens
+    /// cannot be used, e.g. user and token management)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_scope: Option<TokenScope>,
     /// Whether this operation streams progress
     pub streaming: bool,
     /// JSON Schema reference for request type (key into schemas map)
@@ -94,11 +113,12 @@ pub struct RestBinding {
 }
 
 /// HTTP methods
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum HttpMethod {
     Get,
     Post,
+    Put,
     Delete,
 }
 
@@ -128,6 +148,38 @@ pub fn build_spec() -> ApiSpec {
     }
 }
 
+/// Operations built once for lookups at request time
+static OPERATIONS: LazyLock<Vec<OperationDef>> = LazyLock::new(build_operations);
+
+/// Find the operation bound to a JSON-RPC method
+pub fn find_rpc_operation(method: &str) -> Option<&'static OperationDef> {
+    OPERATIONS
+        .iter()
+        .find(|op| op.transports.rpc.method == method)
+}
+
+/// Find the operation bound to a REST method and route
+///
+/// `path` is a route pattern (e.g. axum's `MatchedPath`). Path parameters
+/// match regardless of their names, so `/api/v1/labs/{lab_id}` matches
+/// `/api/v1/labs/{id}`.
+pub fn find_rest_operation(method: HttpMethod, path: &str) -> Option<&'static OperationDef> {
+    OPERATIONS.iter().find(|op| {
+        op.transports.rest.method == method && rest_paths_match(&op.transports.rest.path, path)
+    })
+}
+
+fn rest_paths_match(pattern: &str, path: &str) -> bool {
+    let is_param = |segment: &str| segment.starts_with('{') && segment.ends_with('}');
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(&path)
+            .all(|(a, b)| a == b || (is_param(a) && is_param(b)))
+}
+
 fn build_operations() -> Vec<OperationDef> {
     vec![
         // Auth operations
@@ -136,6 +188,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Authenticate a user and receive a JWT token".to_string(),
             category: Category::Auth,
             auth: AuthRequirement::None,
+            token_scope: None,
             streaming: false,
             request_schema: Some("LoginRequest".to_string()),
             response_schema: Some("LoginResponse".to_string()),
@@ -154,11 +207,86 @@ fn build_operations() -> Vec<OperationDef> {
                 },
             },
         },
+        OperationDef {
+            name: "auth.providers".to_string(),
+            description: "List the login methods enabled on the server".to_string(),
+            category: Category::Auth,
+            auth: AuthRequirement::None,
+            token_scope: None,
+            streaming: false,
+            request_schema: None,
+            response_schema: Some("AuthProvidersResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/auth/providers".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "auth.providers".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa login".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "auth.device_start".to_string(),
+            description: "Start an OpenID Connect device login".to_string(),
+            category: Category::Auth,
+            auth: AuthRequirement::None,
+            token_scope: None,
+            streaming: false,
+            request_schema: None,
+            response_schema: Some("DeviceLoginStartResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/auth/device".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "auth.device_start".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa login --sso".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "auth.device_poll".to_string(),
+            description:
+                "Poll an OpenID Connect device login and receive a JWT token once approved"
+                    .to_string(),
+            category: Category::Auth,
+            auth: AuthRequirement::None,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("DeviceLoginPollRequest".to_string()),
+            response_schema: Some("DeviceLoginPollResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/auth/device/token".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "auth.device_poll".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa login --sso".to_string(),
+                },
+            },
+        },
         OperationDef {
             name: "auth.validate".to_string(),
             description: "Validate a JWT token and return user info".to_string(),
             category: Category::Auth,
             auth: AuthRequirement::None,
+            token_scope: Some(TokenScope::ReadOnly),
             streaming: false,
             request_schema: Some("ValidateRequest".to_string()),
             response_schema: Some("ValidateResponse".to_string()),
@@ -183,6 +311,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Create and start a new lab from a manifest".to_string(),
             category: Category::Lab,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: true,
             request_schema: Some("UpRequest".to_string()),
             response_schema: Some("UpResponse".to_string()),
@@ -206,6 +335,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Destroy a lab and all its resources".to_string(),
             category: Category::Lab,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: true,
             request_schema: Some("DestroyRequest".to_string()),
             response_schema: Some("DestroyResponse".to_string()),
@@ -229,6 +359,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Get detailed information about a lab".to_string(),
             category: Category::Lab,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
             streaming: false,
             request_schema: Some("InspectRequest".to_string()),
             response_schema: Some("InspectResponse".to_string()),
@@ -252,6 +383,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Stop all or specific nodes in a lab".to_string(),
             category: Category::Lab,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: false,
             request_schema: None,
             response_schema: Some("LabNodeActionResponse".to_string()),
@@ -275,6 +407,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Resume all or specific stopped nodes in a lab".to_string(),
             category: Category::Lab,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: false,
             request_schema: None,
             response_schema: Some("LabNodeActionResponse".to_string()),
@@ -298,6 +431,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Force-clean all resources for a lab without ownership check".to_string(),
             category: Category::Admin,
             auth: AuthRequirement::Admin,
+            token_scope: None,
             streaming: false,
             request_schema: None,
             response_schema: Some("DestroyResponse".to_string()),
@@ -322,6 +456,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Destroy and recreate a node with fresh configuration".to_string(),
             category: Category::Node,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: true,
             request_schema: Some("RedeployRequest".to_string()),
             response_schema: Some("RedeployResponse".to_string()),
@@ -340,12 +475,37 @@ fn build_operations() -> Vec<OperationDef> {
                 },
             },
         },
+        OperationDef {
+            name: "node.commit".to_string(),
+            description: "Capture a node as a new image version".to_string(),
+            category: Category::Node,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: true,
+            request_schema: Some("NodeCommitRequest".to_string()),
+            response_schema: Some("NodeCommitResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/labs/{id}/nodes/{node}/commit".to_string(),
+                    path_params: vec!["id".to_string(), "node".to_string()],
+                    stream_type: Some("sse".to_string()),
+                },
+                rpc: RpcBinding {
+                    method: "node.commit".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa node commit".to_string(),
+                },
+            },
+        },
         // Image operations
         OperationDef {
             name: "image.list".to_string(),
             description: "List available images with optional filters".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
             streaming: false,
             request_schema: Some("ListImagesRequest".to_string()),
             response_schema: Some("ListImagesResponse".to_string()),
@@ -369,6 +529,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Show detailed information about an image".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
             streaming: false,
             request_schema: Some("ShowImageRequest".to_string()),
             response_schema: Some("ShowImageResponse".to_string()),
@@ -392,6 +553,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Import a disk image or container tar archive".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
             request_schema: Some("ImportRequest".to_string()),
             response_schema: Some("ImportResponse".to_string()),
@@ -415,6 +577,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Delete an imported image".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
             request_schema: Some("DeleteImageRequest".to_string()),
             response_schema: Some("DeleteImageResponse".to_string()),
@@ -438,6 +601,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Set the default version for an image model".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
             request_schema: Some("SetDefaultImageRequest".to_string()),
             response_schema: Some("SetDefaultImageResponse".to_string()),
@@ -456,11 +620,228 @@ fn build_operations() -> Vec<OperationDef> {
                 },
             },
         },
+        OperationDef {
+            name: "image.usage".to_string(),
+            description: "Report image storage usage and orphaned lab disks".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: None,
+            response_schema: Some("ImageUsageResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/images/usage".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.usage".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image usage".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.prune".to_string(),
+            description: "Remove image versions no node uses".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("PruneImagesRequest".to_string()),
+            response_schema: Some("PruneImagesResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/images/prune".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.prune".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image prune".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.upload_start".to_string(),
+            description: "Start or resume a chunked image upload".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("StartUploadRequest".to_string()),
+            response_schema: Some("UploadStatus".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/images/uploads".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.upload_start".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image upload".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.upload_chunk".to_string(),
+            description: "Upload a chunk of an image, importing it after the final chunk. Over REST the body is the raw chunk, with the offset as a query parameter and its SHA-256 in the X-Chunk-Sha256 header".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: None,
+            response_schema: Some("UploadChunkResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Put,
+                    path: "/api/v1/images/uploads/{upload_id}".to_string(),
+                    path_params: vec!["upload_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.upload_chunk".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image upload".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.upload_cancel".to_string(),
+            description: "Cancel a chunked image upload".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("CancelUploadRequest".to_string()),
+            response_schema: Some("CancelUploadResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/images/uploads/{upload_id}".to_string(),
+                    path_params: vec!["upload_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.upload_cancel".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image upload --cancel".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.verify".to_string(),
+            description: "Verify an image against the digest recorded on import".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("VerifyImageRequest".to_string()),
+            response_schema: Some("VerifyImageResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/images/{model}/{version}/verify".to_string(),
+                    path_params: vec!["model".to_string(), "version".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.verify".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image verify".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.model_add".to_string(),
+            description: "Add or replace a custom node model".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("AddCustomModelRequest".to_string()),
+            response_schema: Some("AddCustomModelResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/images/models".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.model_add".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image model add".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.model_list".to_string(),
+            description: "List custom node models".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: None,
+            response_schema: Some("ListCustomModelsResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/images/models".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.model_list".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image model list".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "image.model_delete".to_string(),
+            description: "Delete a custom node model".to_string(),
+            category: Category::Image,
+            auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
+            streaming: false,
+            request_schema: Some("DeleteCustomModelRequest".to_string()),
+            response_schema: Some("DeleteCustomModelResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/images/models/{name}".to_string(),
+                    path_params: vec!["name".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "image.model_delete".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server image model delete".to_string(),
+                },
+            },
+        },
         OperationDef {
             name: "image.scan".to_string(),
             description: "Scan filesystem and Docker for discoverable images".to_string(),
             category: Category::Admin,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
             request_schema: Some("ScanImagesRequest".to_string()),
             response_schema: Some("ScanImagesResponse".to_string()),
@@ -484,6 +865,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Pull a container image from an OCI registry".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: true,
             request_schema: Some("ContainerPullRequest".to_string()),
             response_schema: Some("ContainerPullResponse".to_string()),
@@ -504,9 +886,10 @@ fn build_operations() -> Vec<OperationDef> {
         },
         OperationDef {
             name: "image.download".to_string(),
-            description: "Download a VM image from a URL".to_string(),
+            description: "Download a VM image from a URL, or a VM or unikernel image from an OCI registry".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: true,
             request_schema: Some("DownloadImageRequest".to_string()),
             response_schema: None,
@@ -530,6 +913,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Upload an image file via multipart form data".to_string(),
             category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
             request_schema: None,
             response_schema: Some("ImportResponse".to_string()),
@@ -550,79 +934,155 @@ fn build_operations() -> Vec<OperationDef> {
         },
         // User operations
         OperationDef {
-            name: "user.create".to_string(),
-            description: "Create a new user account".to_string(),
-            category: Category::Admin,
+            name: "registry.login".to_string(),
+            description: "Verify and store credentials for a private container registry".to_string(),
+            category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
-            request_schema: Some("CreateUserRequest".to_string()),
-            response_schema: Some("CreateUserResponse".to_string()),
+            request_schema: Some("RegistryLoginRequest".to_string()),
+            response_schema: Some("RegistryLoginResponse".to_string()),
             transports: Transports {
                 rest: RestBinding {
                     method: HttpMethod::Post,
-                    path: "/api/v1/admin/users".to_string(),
+                    path: "/api/v1/admin/registries".to_string(),
                     path_params: vec![],
                     stream_type: None,
                 },
                 rpc: RpcBinding {
-                    method: "user.create".to_string(),
+                    method: "registry.login".to_string(),
                 },
                 cli: CliBinding {
-                    command: "sherpa server user create".to_string(),
+                    command: "sherpa server registry login".to_string(),
                 },
             },
         },
         OperationDef {
-            name: "user.list".to_string(),
-            description: "List all user accounts".to_string(),
-            category: Category::Admin,
+            name: "registry.list".to_string(),
+            description: "List registries with stored credentials".to_string(),
+            category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
-            request_schema: Some("ListUsersRequest".to_string()),
-            response_schema: Some("ListUsersResponse".to_string()),
+            request_schema: None,
+            response_schema: Some("ListRegistriesResponse".to_string()),
             transports: Transports {
                 rest: RestBinding {
                     method: HttpMethod::Get,
-                    path: "/api/v1/admin/users".to_string(),
+                    path: "/api/v1/admin/registries".to_string(),
                     path_params: vec![],
                     stream_type: None,
                 },
                 rpc: RpcBinding {
-                    method: "user.list".to_string(),
+                    method: "registry.list".to_string(),
                 },
                 cli: CliBinding {
-                    command: "sherpa server user list".to_string(),
+                    command: "sherpa server registry list".to_string(),
                 },
             },
         },
         OperationDef {
-            name: "user.delete".to_string(),
-            description: "Delete a user account".to_string(),
-            category: Category::Admin,
+            name: "registry.logout".to_string(),
+            description: "Remove the stored credentials of a container registry".to_string(),
+            category: Category::Image,
             auth: AuthRequirement::Admin,
+            token_scope: Some(TokenScope::ImageAdmin),
             streaming: false,
-            request_schema: Some("DeleteUserRequest".to_string()),
-            response_schema: Some("DeleteUserResponse".to_string()),
+            request_schema: Some("RegistryLogoutRequest".to_string()),
+            response_schema: Some("RegistryLogoutResponse".to_string()),
             transports: Transports {
                 rest: RestBinding {
                     method: HttpMethod::Delete,
-                    path: "/api/v1/admin/users/{username}".to_string(),
-                    path_params: vec!["username".to_string()],
+                    path: "/api/v1/admin/registries/{registry}".to_string(),
+                    path_params: vec!["registry".to_string()],
                     stream_type: None,
                 },
                 rpc: RpcBinding {
-                    method: "user.delete".to_string(),
+                    method: "registry.logout".to_string(),
                 },
                 cli: CliBinding {
-                    command: "sherpa server user delete".to_string(),
+                    command: "sherpa server registry logout".to_string(),
                 },
             },
         },
         OperationDef {
-            name: "user.passwd".to_string(),
-            description: "Change a user's password".to_string(),
+            name: "user.create".to_string(),
+            description: "Create a new user account".to_string(),
+            category: Category::Admin,
+            auth: AuthRequirement::Admin,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("CreateUserRequest".to_string()),
+            response_schema: Some("CreateUserResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/admin/users".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "user.create".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server user create".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "user.list".to_string(),
+            description: "List all user accounts".to_string(),
+            category: Category::Admin,
+            auth: AuthRequirement::Admin,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("ListUsersRequest".to_string()),
+            response_schema: Some("ListUsersResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/admin/users".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "user.list".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server user list".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "user.delete".to_string(),
+            description: "Delete a user account".to_string(),
+            category: Category::Admin,
+            auth: AuthRequirement::Admin,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("DeleteUserRequest".to_string()),
+            response_schema: Some("DeleteUserResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/admin/users/{username}".to_string(),
+                    path_params: vec!["username".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "user.delete".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server user delete".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "user.passwd".to_string(),
+            description: "Change a user's password".to_string(),
             category: Category::Admin,
             auth: AuthRequirement::Authenticated,
+            token_scope: None,
             streaming: false,
             request_schema: Some("ChangePasswordRequest".to_string()),
             response_schema: Some("ChangePasswordResponse".to_string()),
@@ -646,6 +1106,7 @@ fn build_operations() -> Vec<OperationDef> {
             description: "Get detailed information about a user".to_string(),
             category: Category::Admin,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
             streaming: false,
             request_schema: Some("GetUserInfoRequest".to_string()),
             response_schema: Some("GetUserInfoResponse".to_string()),
@@ -664,6 +1125,299 @@ fn build_operations() -> Vec<OperationDef> {
                 },
             },
         },
+        // Lab sharing operations
+        OperationDef {
+            name: "lab.share".to_string(),
+            description: "Share a lab with a user or a team as viewer or operator".to_string(),
+            category: Category::Lab,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("ShareLabRequest".to_string()),
+            response_schema: Some("ListLabSharesResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/labs/{id}/shares".to_string(),
+                    path_params: vec!["id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "lab.share".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa share add".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "lab.unshare".to_string(),
+            description: "Remove a lab share from a user or a team".to_string(),
+            category: Category::Lab,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("UnshareLabRequest".to_string()),
+            response_schema: Some("ListLabSharesResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/labs/{id}/shares/{grantee}".to_string(),
+                    path_params: vec!["id".to_string(), "grantee".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "lab.unshare".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa share remove".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "lab.shares".to_string(),
+            description: "List the users and teams a lab is shared with".to_string(),
+            category: Category::Lab,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: Some("ListLabSharesRequest".to_string()),
+            response_schema: Some("ListLabSharesResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/labs/{id}/shares".to_string(),
+                    path_params: vec!["id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "lab.shares".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa share list".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "lab.leases".to_string(),
+            description: "List the DHCP leases of a lab and the nodes holding them".to_string(),
+            category: Category::Lab,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: Some("LabLeasesRequest".to_string()),
+            response_schema: Some("LabLeasesResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/labs/{id}/leases".to_string(),
+                    path_params: vec!["id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "lab.leases".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa leases".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "lab.verify_cabling".to_string(),
+            description:
+                "Compare the LLDP/CDP neighbours the nodes of a lab advertise with the manifest"
+                    .to_string(),
+            category: Category::Lab,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: Some("VerifyCablingRequest".to_string()),
+            response_schema: Some("VerifyCablingResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/labs/{lab_id}/cabling/verify".to_string(),
+                    path_params: vec!["lab_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "lab.verify_cabling".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa verify cabling".to_string(),
+                },
+            },
+        },
+        // Team operations
+        OperationDef {
+            name: "team.create".to_string(),
+            description: "Create a team owned by the caller".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("CreateTeamRequest".to_string()),
+            response_schema: Some("TeamInfo".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/teams".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "team.create".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server team create".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "team.list".to_string(),
+            description: "List all teams and their members".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: Some("ListTeamsRequest".to_string()),
+            response_schema: Some("ListTeamsResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/teams".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "team.list".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server team list".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "team.update".to_string(),
+            description: "Add or remove team members".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("UpdateTeamMembersRequest".to_string()),
+            response_schema: Some("TeamInfo".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/teams/{name}/members".to_string(),
+                    path_params: vec!["name".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "team.update".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server team add-member".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "team.delete".to_string(),
+            description: "Delete a team and the lab shares granted to it".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("DeleteTeamRequest".to_string()),
+            response_schema: Some("DeleteTeamResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/teams/{name}".to_string(),
+                    path_params: vec!["name".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "team.delete".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa server team delete".to_string(),
+                },
+            },
+        },
+        // Personal API token operations
+        OperationDef {
+            name: "token.create".to_string(),
+            description: "Create a scoped personal API token for the caller".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("CreateApiTokenRequest".to_string()),
+            response_schema: Some("CreateApiTokenResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/tokens".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "token.create".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa token create".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "token.list".to_string(),
+            description: "List the caller's personal API tokens".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("ListApiTokensRequest".to_string()),
+            response_schema: Some("ListApiTokensResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/tokens".to_string(),
+                    path_params: vec![],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "token.list".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa token list".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "token.revoke".to_string(),
+            description: "Revoke one of the caller's personal API tokens".to_string(),
+            category: Category::User,
+            auth: AuthRequirement::Authenticated,
+            token_scope: None,
+            streaming: false,
+            request_schema: Some("RevokeApiTokenRequest".to_string()),
+            response_schema: Some("RevokeApiTokenResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/tokens/{name}".to_string(),
+                    path_params: vec!["name".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "token.revoke".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa token revoke".to_string(),
+                },
+            },
+        },
         // Link operations
         OperationDef {
             name: "link.update_impairment".to_string(),
@@ -671,6 +1425,7 @@ fn build_operations() -> Vec<OperationDef> {
                 .to_string(),
             category: Category::Link,
             auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
             streaming: false,
             request_schema: Some("UpdateImpairmentRequest".to_string()),
             response_schema: Some("UpdateImpairmentResponse".to_string()),
@@ -689,6 +1444,104 @@ fn build_operations() -> Vec<OperationDef> {
                 },
             },
         },
+        OperationDef {
+            name: "link.stats".to_string(),
+            description: "Read the packet, byte and drop counters of every link in a lab"
+                .to_string(),
+            category: Category::Link,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::ReadOnly),
+            streaming: false,
+            request_schema: Some("LinkStatsRequest".to_string()),
+            response_schema: Some("LinkStatsResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Get,
+                    path: "/api/v1/labs/{lab_id}/links/stats".to_string(),
+                    path_params: vec!["lab_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "link.stats".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa link stats".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "link.mirror_add".to_string(),
+            description: "Copy the traffic of a link interface to a mirror port".to_string(),
+            category: Category::Link,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
+            streaming: false,
+            request_schema: Some("LinkMirrorRequest".to_string()),
+            response_schema: Some("LinkMirrorResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/labs/{lab_id}/links/mirrors".to_string(),
+                    path_params: vec!["lab_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "link.mirror_add".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa link mirror add".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "link.mirror_remove".to_string(),
+            description: "Stop copying the traffic of a link interface to a mirror port".to_string(),
+            category: Category::Link,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
+            streaming: false,
+            request_schema: Some("LinkMirrorRequest".to_string()),
+            response_schema: Some("LinkMirrorResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Delete,
+                    path: "/api/v1/labs/{lab_id}/links/mirrors".to_string(),
+                    path_params: vec!["lab_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "link.mirror_remove".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa link mirror remove".to_string(),
+                },
+            },
+        },
+        OperationDef {
+            name: "link.update_filter".to_string(),
+            description: "Replace the layer 2 protocol, ethertype and VLAN filter of a running link"
+                .to_string(),
+            category: Category::Link,
+            auth: AuthRequirement::Authenticated,
+            token_scope: Some(TokenScope::LabOperate),
+            streaming: false,
+            request_schema: Some("LinkFilterRequest".to_string()),
+            response_schema: Some("LinkFilterResponse".to_string()),
+            transports: Transports {
+                rest: RestBinding {
+                    method: HttpMethod::Post,
+                    path: "/api/v1/labs/{lab_id}/links/filter".to_string(),
+                    path_params: vec!["lab_id".to_string()],
+                    stream_type: None,
+                },
+                rpc: RpcBinding {
+                    method: "link.update_filter".to_string(),
+                },
+                cli: CliBinding {
+                    command: "sherpa link filter set".to_string(),
+                },
+            },
+        },
     ]
 }
 
@@ -708,6 +1561,10 @@ fn build_schemas() -> BTreeMap<String, serde_json::Value> {
     add_schema::<LoginResponse>(&mut schemas);
     add_schema::<ValidateRequest>(&mut schemas);
     add_schema::<ValidateResponse>(&mut schemas);
+    add_schema::<AuthProvidersResponse>(&mut schemas);
+    add_schema::<DeviceLoginStartResponse>(&mut schemas);
+    add_schema::<DeviceLoginPollRequest>(&mut schemas);
+    add_schema::<DeviceLoginPollResponse>(&mut schemas);
 
     // Lab lifecycle
     add_schema::<UpRequest>(&mut schemas);
@@ -718,6 +1575,8 @@ fn build_schemas() -> BTreeMap<String, serde_json::Value> {
     add_schema::<InspectResponse>(&mut schemas);
     add_schema::<RedeployRequest>(&mut schemas);
     add_schema::<RedeployResponse>(&mut schemas);
+    add_schema::<NodeCommitRequest>(&mut schemas);
+    add_schema::<NodeCommitResponse>(&mut schemas);
     add_schema::<LabNodeActionResponse>(&mut schemas);
 
     // Image management
@@ -731,6 +1590,27 @@ fn build_schemas() -> BTreeMap<String, serde_json::Value> {
     add_schema::<DeleteImageResponse>(&mut schemas);
     add_schema::<SetDefaultImageRequest>(&mut schemas);
     add_schema::<SetDefaultImageResponse>(&mut schemas);
+    add_schema::<ImageUsageResponse>(&mut schemas);
+    add_schema::<PruneImagesRequest>(&mut schemas);
+    add_schema::<PruneImagesResponse>(&mut schemas);
+    add_schema::<RegistryLoginRequest>(&mut schemas);
+    add_schema::<RegistryLoginResponse>(&mut schemas);
+    add_schema::<ListRegistriesResponse>(&mut schemas);
+    add_schema::<RegistryLogoutRequest>(&mut schemas);
+    add_schema::<RegistryLogoutResponse>(&mut schemas);
+    add_schema::<VerifyImageRequest>(&mut schemas);
+    add_schema::<VerifyImageResponse>(&mut schemas);
+    add_schema::<StartUploadRequest>(&mut schemas);
+    add_schema::<UploadStatus>(&mut schemas);
+    add_schema::<UploadChunkRequest>(&mut schemas);
+    add_schema::<UploadChunkResponse>(&mut schemas);
+    add_schema::<CancelUploadRequest>(&mut schemas);
+    add_schema::<CancelUploadResponse>(&mut schemas);
+    add_schema::<AddCustomModelRequest>(&mut schemas);
+    add_schema::<AddCustomModelResponse>(&mut schemas);
+    add_schema::<ListCustomModelsResponse>(&mut schemas);
+    add_schema::<DeleteCustomModelRequest>(&mut schemas);
+    add_schema::<DeleteCustomModelResponse>(&mut schemas);
     add_schema::<ScanImagesRequest>(&mut schemas);
     add_schema::<ScanImagesResponse>(&mut schemas);
     add_schema::<ContainerPullRequest>(&mut schemas);
@@ -740,6 +1620,12 @@ fn build_schemas() -> BTreeMap<String, serde_json::Value> {
     // Link operations
     add_schema::<UpdateImpairmentRequest>(&mut schemas);
     add_schema::<UpdateImpairmentResponse>(&mut schemas);
+    add_schema::<LinkStatsRequest>(&mut schemas);
+    add_schema::<LinkStatsResponse>(&mut schemas);
+    add_schema::<LinkMirrorRequest>(&mut schemas);
+    add_schema::<LinkMirrorResponse>(&mut schemas);
+    add_schema::<LinkFilterRequest>(&mut schemas);
+    add_schema::<LinkFilterResponse>(&mut schemas);
 
     // User management
     add_schema::<CreateUserRequest>(&mut schemas);
@@ -753,6 +1639,32 @@ fn build_schemas() -> BTreeMap<String, serde_json::Value> {
     add_schema::<GetUserInfoRequest>(&mut schemas);
     add_schema::<GetUserInfoResponse>(&mut schemas);
 
+    // Lab sharing and teams
+    add_schema::<ShareLabRequest>(&mut schemas);
+    add_schema::<UnshareLabRequest>(&mut schemas);
+    add_schema::<ListLabSharesRequest>(&mut schemas);
+    add_schema::<ListLabSharesResponse>(&mut schemas);
+    add_schema::<LabLeasesRequest>(&mut schemas);
+    add_schema::<LabLeasesResponse>(&mut schemas);
+    add_schema::<VerifyCablingRequest>(&mut schemas);
+    add_schema::<VerifyCablingResponse>(&mut schemas);
+    add_schema::<CreateTeamRequest>(&mut schemas);
+    add_schema::<TeamInfo>(&mut schemas);
+    add_schema::<ListTeamsRequest>(&mut schemas);
+    add_schema::<ListTeamsResponse>(&mut schemas);
+    add_schema::<UpdateTeamMembersRequest>(&mut schemas);
+    add_schema::<DeleteTeamRequest>(&mut schemas);
+    add_schema::<DeleteTeamResponse>(&mut schemas);
+
+    // Personal API tokens
+    add_schema::<CreateApiTokenRequest>(&mut schemas);
+    add_schema::<CreateApiTokenResponse>(&mut schemas);
+    add_schema::<ApiTokenInfo>(&mut schemas);
+    add_schema::<ListApiTokensRequest>(&mut schemas);
+    add_schema::<ListApiTokensResponse>(&mut schemas);
+    add_schema::<RevokeApiTokenRequest>(&mut schemas);
+    add_schema::<RevokeApiTokenResponse>(&mut schemas);
+
     schemas
 }
 
@@ -819,6 +1731,7 @@ pub fn build_openapi() -> serde_json::Value {
         let method_key = match rest.method {
             HttpMethod::Get => "get",
             HttpMethod::Post => "post",
+            HttpMethod::Put => "put",
             HttpMethod::Delete => "delete",
         };
 
@@ -921,6 +1834,10 @@ pub fn build_openapi() -> serde_json::Value {
             operation.insert("x-admin-required".to_string(), json!(true));
         }
 
+        if let Some(scope) = op.token_scope {
+            operation.insert("x-token-scope".to_string(), json!(scope));
+        }
+
         // Path parameters at path item level (shared across methods)
         let path_entry = paths.entry(rest.path.clone()).or_default();
 
@@ -984,9 +1901,47 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_build_spec_has_24_operations() {
+    fn test_build_spec_has_37_operations() {
+        let spec = build_spec();
+        assert_eq!(spec.operations.len(), 56);
+    }
+
+    #[test]
+    fn test_find_rpc_operation() {
+        let op = find_rpc_operation("up").unwrap();
+        assert_eq!(op.name, "lab.create");
+        assert!(find_rpc_operation("no.such.method").is_none());
+    }
+
+    #[test]
+    fn test_find_rest_operation_ignores_param_names() {
+        let op = find_rest_operation(
+            HttpMethod::Post,
+            "/api/v1/labs/{id}/nodes/{node_name}/redeploy",
+        )
+        .unwrap();
+        assert_eq!(op.name, "node.redeploy");
+
+        let op = find_rest_operation(HttpMethod::Get, "/api/v1/labs/{lab_id}").unwrap();
+        assert_eq!(op.name, "lab.inspect");
+
+        assert!(find_rest_operation(HttpMethod::Get, "/api/v1/labs/{id}/down").is_none());
+    }
+
+    #[test]
+    fn test_token_management_not_allowed_with_api_tokens() {
         let spec = build_spec();
-        assert_eq!(spec.operations.len(), 24);
+        for op in &spec.operations {
+            if op.name.starts_with("token.")
+                || (op.name.starts_with("user.") && op.name != "user.info")
+            {
+                assert!(
+                    op.token_scope.is_none(),
+                    "{} must require a session",
+                    op.name
+                );
+            }
+        }
     }
 
     #[test]
@@ -1032,9 +1987,12 @@ mod tests {
             .map(|op| op.transports.rpc.method.as_str())
             .collect();
 
-        // These are the 23 RPC methods from the WebSocket handler
+        // These are the RPC methods from the WebSocket handler
         let expected = vec![
             "auth.login",
+            "auth.providers",
+            "auth.device_start",
+            "auth.device_poll",
             "auth.validate",
             "up",
             "destroy",
@@ -1043,21 +2001,50 @@ mod tests {
             "resume",
             "clean",
             "redeploy",
+            "node.commit",
             "link.update_impairment",
+            "link.stats",
+            "link.mirror_add",
+            "link.mirror_remove",
+            "link.update_filter",
             "image.list",
             "image.show",
             "image.import",
             "image.delete",
             "image.set_default",
+            "image.usage",
+            "image.prune",
+            "image.verify",
+            "image.upload_start",
+            "image.upload_chunk",
+            "image.upload_cancel",
+            "image.model_add",
+            "image.model_list",
+            "image.model_delete",
             "image.scan",
             "image.pull",
             "image.download",
             "image.upload",
+            "registry.login",
+            "registry.list",
+            "registry.logout",
             "user.create",
             "user.list",
             "user.delete",
             "user.passwd",
             "user.info",
+            "lab.share",
+            "lab.unshare",
+            "lab.shares",
+            "lab.leases",
+            "lab.verify_cabling",
+            "team.create",
+            "team.list",
+            "team.update",
+            "team.delete",
+            "token.create",
+            "token.list",
+            "token.revoke",
         ];
 
         for method in &expected {
@@ -1076,7 +2063,7 @@ mod tests {
         let streaming_ops: Vec<&OperationDef> =
             spec.operations.iter().filter(|op| op.streaming).collect();
 
-        assert_eq!(streaming_ops.len(), 5, "Expected 5 streaming operations");
+        assert_eq!(streaming_ops.len(), 6, "Expected 6 streaming operations");
 
         for op in &streaming_ops {
             assert!(
@@ -0,0 +1,81 @@
+//! Personal API token generation and hashing.
+//!
+//! API tokens are long-lived, revocable alternatives to the JWT returned by
+//! `auth.login`, intended for automation such as CI pipelines. A token is an
+//! opaque random string with a fixed prefix so it can be told apart from a
+//! JWT. Only the SHA-256 digest of a token is stored by the server.
+
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+
+use crate::konst::API_TOKEN_PREFIX;
+
+/// Number of random bytes in a generated token.
+const API_TOKEN_RANDOM_BYTES: usize = 32;
+
+/// Number of characters of a token kept for display, including the prefix.
+const API_TOKEN_DISPLAY_LEN: usize = API_TOKEN_PREFIX.len() + 8;
+
+/// Generate a new random API token.
+///
+/// The token is `sherpa_pat_` followed by 64 hex characters.
+pub fn generate_api_token() -> String {
+    let mut bytes = [0u8; API_TOKEN_RANDOM_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{}{}", API_TOKEN_PREFIX, hex::encode(bytes))
+}
+
+/// Hash an API token for storage and lookup.
+///
+/// Tokens carry 256 bits of entropy, so an unsalted SHA-256 digest is
+/// sufficient and allows the token to be looked up by its hash.
+pub fn hash_api_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Check whether a bearer token is an API token rather than a JWT.
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// Shortened form of a token that identifies it without revealing it.
+pub fn api_token_display_prefix(token: &str) -> String {
+    token.chars().take(API_TOKEN_DISPLAY_LEN).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_api_token_format() {
+        let token = generate_api_token();
+        assert!(is_api_token(&token));
+        assert_eq!(
+            token.len(),
+            API_TOKEN_PREFIX.len() + API_TOKEN_RANDOM_BYTES * 2
+        );
+        assert_ne!(token, generate_api_token());
+    }
+
+    #[test]
+    fn test_hash_api_token_is_stable() {
+        let token = generate_api_token();
+        assert_eq!(hash_api_token(&token), hash_api_token(&token));
+        assert_ne!(hash_api_token(&token), hash_api_token("sherpa_pat_other"));
+        assert_eq!(hash_api_token(&token).len(), 64);
+    }
+
+    #[test]
+    fn test_is_api_token_rejects_jwt() {
+        assert!(!is_api_token("eyJhbGciOiJIUzI1NiJ9.e30.sig"));
+        assert!(is_api_token("sherpa_pat_abc"));
+    }
+
+    #[test]
+    fn test_api_token_display_prefix() {
+        let token = "sherpa_pat_0123456789abcdef";
+        assert_eq!(api_token_display_prefix(token), "sherpa_pat_01234567");
+    }
+}
@@ -0,0 +1,169 @@
+//! Personal API token request and response data structures.
+//!
+//! API tokens authenticate automation (e.g. CI pipelines) without a user's
+//! password. Each token carries one or more [`TokenScope`]s that limit the
+//! operations it can be used for.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Set of operations an API token can be used for.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, EnumIter, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenScope {
+    /// List, inspect and download labs and images
+    ReadOnly,
+    /// Create, destroy, stop, start and redeploy labs
+    LabOperate,
+    /// Import, pull and delete images (the token owner must be an admin)
+    ImageAdmin,
+}
+
+impl TokenScope {
+    /// All token scopes (used by DB schema generation).
+    pub fn all() -> Vec<TokenScope> {
+        TokenScope::iter().collect()
+    }
+
+    /// Check whether a set of granted scopes covers the required scope.
+    ///
+    /// Every scope includes read-only access.
+    pub fn is_granted(granted: &[TokenScope], required: TokenScope) -> bool {
+        (required == TokenScope::ReadOnly && !granted.is_empty()) || granted.contains(&required)
+    }
+}
+
+impl fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenScope::ReadOnly => write!(f, "read-only"),
+            TokenScope::LabOperate => write!(f, "lab-operate"),
+            TokenScope::ImageAdmin => write!(f, "image-admin"),
+        }
+    }
+}
+
+impl FromStr for TokenScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "read-only" => Ok(TokenScope::ReadOnly),
+            "lab-operate" => Ok(TokenScope::LabOperate),
+            "image-admin" => Ok(TokenScope::ImageAdmin),
+            _ => Err(anyhow!("Unknown token scope: {}", s)),
+        }
+    }
+}
+
+/// API token information (safe for display, never includes the token itself)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ApiTokenInfo {
+    /// Token name, unique per user
+    pub name: String,
+    /// First characters of the token, to help identify it
+    pub prefix: String,
+    /// Scopes granted to the token
+    pub scopes: Vec<TokenScope>,
+    /// When the token expires (Unix timestamp)
+    pub expires_at: i64,
+    /// When the token was last used (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    /// When the token was created (Unix timestamp)
+    pub created_at: i64,
+}
+
+/// Request to create a personal API token for the caller
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateApiTokenRequest {
+    /// Token name (min 3 chars, alphanumeric + ._-)
+    pub name: String,
+    /// Scopes to grant (at least one)
+    pub scopes: Vec<TokenScope>,
+    /// Days until the token expires (1-365)
+    pub expires_in_days: u32,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response after creating an API token
+///
+/// The token is only ever returned here, it cannot be retrieved later.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateApiTokenResponse {
+    /// The new API token
+    pub api_token: String,
+    /// Information about the new token
+    pub info: ApiTokenInfo,
+}
+
+/// Request to list the caller's API tokens
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListApiTokensRequest {
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response with the caller's API tokens
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListApiTokensResponse {
+    /// API tokens, ordered by name
+    pub tokens: Vec<ApiTokenInfo>,
+}
+
+/// Request to revoke one of the caller's API tokens
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevokeApiTokenRequest {
+    /// Name of the token to revoke
+    pub name: String,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response after revoking an API token
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevokeApiTokenResponse {
+    /// Whether the revocation was successful
+    pub success: bool,
+    /// Name of the revoked token
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_scope_display_roundtrip() {
+        for scope in TokenScope::iter() {
+            assert_eq!(scope.to_string().parse::<TokenScope>().unwrap(), scope);
+            let json = serde_json::to_string(&scope).unwrap();
+            assert_eq!(json, format!("\"{}\"", scope));
+        }
+        assert!("admin".parse::<TokenScope>().is_err());
+    }
+
+    #[test]
+    fn test_token_scope_is_granted() {
+        let lab = [TokenScope::LabOperate];
+        assert!(TokenScope::is_granted(&lab, TokenScope::ReadOnly));
+        assert!(TokenScope::is_granted(&lab, TokenScope::LabOperate));
+        assert!(!TokenScope::is_granted(&lab, TokenScope::ImageAdmin));
+
+        let read = [TokenScope::ReadOnly];
+        assert!(TokenScope::is_granted(&read, TokenScope::ReadOnly));
+        assert!(!TokenScope::is_granted(&read, TokenScope::LabOperate));
+
+        assert!(!TokenScope::is_granted(&[], TokenScope::ReadOnly));
+    }
+}
@@ -2,8 +2,44 @@
 //!
 //! These types define the JSON-RPC request/response formats for authentication operations.
 
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Where a user's credentials are verified.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProvider {
+    /// Password hash stored in the local `user` table
+    #[default]
+    Local,
+    /// LDAP bind against the configured directory
+    Ldap,
+    /// OpenID Connect single sign-on
+    Oidc,
+}
+
+impl AuthProvider {
+    /// All auth providers (used by DB schema generation).
+    pub fn all() -> Vec<AuthProvider> {
+        AuthProvider::iter().collect()
+    }
+}
+
+impl fmt::Display for AuthProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthProvider::Local => write!(f, "local"),
+            AuthProvider::Ldap => write!(f, "ldap"),
+            AuthProvider::Oidc => write!(f, "oidc"),
+        }
+    }
+}
 
 /// Request to authenticate a user and receive a JWT token
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -47,6 +83,64 @@ pub struct ValidateResponse {
     pub expires_at: Option<i64>,
 }
 
+/// Login methods enabled on the server
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuthProvidersResponse {
+    /// Username and password login (local accounts or LDAP)
+    pub password: bool,
+    /// LDAP bind authentication is configured
+    pub ldap: bool,
+    /// OpenID Connect single sign-on is configured
+    pub oidc: bool,
+}
+
+/// Response from starting an OpenID Connect device login
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceLoginStartResponse {
+    /// Opaque code used to poll for the login result
+    pub device_code: String,
+    /// Code the user enters at the verification URI
+    pub user_code: String,
+    /// Page where the user approves the login
+    pub verification_uri: String,
+    /// Verification URI with the user code filled in, if the provider supports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification_uri_complete: Option<String>,
+    /// Seconds until the device code expires
+    pub expires_in: u64,
+    /// Minimum seconds between poll requests
+    pub interval: u64,
+}
+
+/// Request to poll for the result of a device login
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceLoginPollRequest {
+    /// Device code from the start response
+    pub device_code: String,
+}
+
+/// State of a pending device login
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceLoginStatus {
+    /// The user has not approved the login yet
+    Pending,
+    /// Poll less often, the interval should grow by 5 seconds
+    SlowDown,
+    /// The user approved the login
+    Complete,
+}
+
+/// Response from polling a device login
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceLoginPollResponse {
+    /// Current state of the login
+    pub status: DeviceLoginStatus,
+    /// Login result, set once the status is `complete`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login: Option<LoginResponse>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,7 +168,7 @@ mod tests {
         let json = serde_json::to_string(&resp).expect("serializes");
         let back: LoginResponse = serde_json::from_str(&json).expect("deserializes");
         assert_eq!(back.token, "jwt.token.here");
-        assert_eq!(back.is_admin, true);
+        assert!(back.is_admin);
         assert_eq!(back.expires_at, 1700000000);
     }
 
@@ -88,7 +182,7 @@ mod tests {
         };
         let json = serde_json::to_string(&resp).expect("serializes");
         let back: ValidateResponse = serde_json::from_str(&json).expect("deserializes");
-        assert_eq!(back.valid, true);
+        assert!(back.valid);
         assert_eq!(back.username, Some("admin".to_string()));
     }
 
@@ -102,7 +196,27 @@ mod tests {
         };
         let json = serde_json::to_string(&resp).expect("serializes");
         let back: ValidateResponse = serde_json::from_str(&json).expect("deserializes");
-        assert_eq!(back.valid, false);
+        assert!(!back.valid);
         assert!(back.username.is_none());
     }
+
+    #[test]
+    fn test_auth_provider_serde() {
+        assert_eq!(
+            serde_json::to_value(AuthProvider::Ldap).expect("serializes"),
+            serde_json::json!("ldap")
+        );
+        assert_eq!(AuthProvider::default(), AuthProvider::Local);
+        assert_eq!(AuthProvider::all().len(), 3);
+    }
+
+    #[test]
+    fn test_device_login_poll_response_pending_omits_login() {
+        let resp = DeviceLoginPollResponse {
+            status: DeviceLoginStatus::SlowDown,
+            login: None,
+        };
+        let json = serde_json::to_value(&resp).expect("serializes");
+        assert_eq!(json, serde_json::json!({"status": "slow_down"}));
+    }
 }
@@ -0,0 +1,230 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Discovery protocol an advertisement was decoded from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryProtocol {
+    Lldp,
+    Cdp,
+}
+
+impl fmt::Display for DiscoveryProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryProtocol::Lldp => write!(f, "lldp"),
+            DiscoveryProtocol::Cdp => write!(f, "cdp"),
+        }
+    }
+}
+
+/// Identity a node advertised over LLDP or CDP
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DiscoveredNeighbour {
+    pub protocol: DiscoveryProtocol,
+    /// LLDP chassis ID, or the CDP device ID
+    pub chassis_id: String,
+    /// LLDP system name, or the CDP device ID
+    pub system_name: Option<String>,
+    /// Interface the frame was sent from, as named by the node
+    pub port_id: String,
+    /// LLDP port description
+    pub port_description: Option<String>,
+}
+
+impl DiscoveredNeighbour {
+    /// Whether the advertisement was sent from `interface` of `node`.
+    ///
+    /// Nodes advertise their hostname, so the system name is compared to the
+    /// node name without any domain. Advertisements without a system name
+    /// are only matched on the interface.
+    pub fn is_from(&self, node: &str, interface: &str) -> bool {
+        let node_matches = self
+            .system_name
+            .as_deref()
+            .is_none_or(|name| system_name_matches(name, node));
+        let port_matches = interface_names_match(&self.port_id, interface)
+            || self
+                .port_description
+                .as_deref()
+                .is_some_and(|description| interface_names_match(description, interface));
+
+        node_matches && port_matches
+    }
+}
+
+impl fmt::Display for DiscoveredNeighbour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.system_name.as_deref().unwrap_or(&self.chassis_id);
+        write!(f, "{}::{}", name, self.port_id)
+    }
+}
+
+/// Compare an advertised system name to a node name, ignoring case, any
+/// domain and the serial number NX-OS appends to its CDP device ID.
+fn system_name_matches(system_name: &str, node: &str) -> bool {
+    let host = system_name.split('(').next().unwrap_or_default();
+    let host = host.split('.').next().unwrap_or_default();
+    host.trim().eq_ignore_ascii_case(node)
+}
+
+/// Compare an advertised interface name to a manifest interface name.
+///
+/// Names match ignoring case and whitespace, or when one is the other
+/// abbreviated the way vendors shorten them (`Gi0/1`, `Et1`).
+fn interface_names_match(advertised: &str, interface: &str) -> bool {
+    let normalise = |name: &str| {
+        name.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_lowercase()
+    };
+    let (advertised, interface) = (normalise(advertised), normalise(interface));
+    if advertised == interface {
+        return true;
+    }
+
+    let split = |name: &str| {
+        let at = name
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(name.len());
+        let (prefix, number) = name.split_at(at);
+        (prefix.to_string(), number.to_string())
+    };
+    let (advertised_prefix, advertised_number) = split(&advertised);
+    let (interface_prefix, interface_number) = split(&interface);
+
+    !advertised_number.is_empty()
+        && advertised_number == interface_number
+        && !advertised_prefix.is_empty()
+        && !interface_prefix.is_empty()
+        && (interface_prefix.starts_with(&advertised_prefix)
+            || advertised_prefix.starts_with(&interface_prefix))
+}
+
+/// Outcome of checking one interface against the manifest
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CablingStatus {
+    /// The neighbour advertised the interface the manifest describes
+    Verified,
+    /// A different neighbour or interface was advertised
+    Mismatch,
+    /// No LLDP or CDP frame arrived within the capture window
+    NotSeen,
+    /// The link has no host interface to capture on
+    Unsupported,
+}
+
+impl fmt::Display for CablingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CablingStatus::Verified => write!(f, "verified"),
+            CablingStatus::Mismatch => write!(f, "mismatch"),
+            CablingStatus::NotSeen => write!(f, "not seen"),
+            CablingStatus::Unsupported => write!(f, "unsupported"),
+        }
+    }
+}
+
+/// Expected and discovered neighbour of one node interface
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CablingCheck {
+    pub link_index: u16,
+    pub node: String,
+    pub interface: String,
+    /// Peer node the manifest cables the interface to
+    pub expected_node: String,
+    /// Peer interface the manifest cables the interface to
+    pub expected_interface: String,
+    /// What the peer advertised onto the link
+    pub discovered: Option<DiscoveredNeighbour>,
+    pub status: CablingStatus,
+}
+
+/// Request to verify the cabling of a lab
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VerifyCablingRequest {
+    /// Lab ID to verify
+    pub lab_id: String,
+    /// Seconds to listen for advertisements, defaults to `CABLING_CAPTURE_SECS`
+    #[serde(default)]
+    pub wait_secs: Option<u64>,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Cabling of a lab as discovered over LLDP and CDP
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VerifyCablingResponse {
+    /// Lab ID
+    pub lab_id: String,
+    /// When the capture finished, as Unix milliseconds
+    pub checked_at_ms: u64,
+    /// Seconds the capture listened for
+    pub wait_secs: u64,
+    pub checks: Vec<CablingCheck>,
+}
+
+impl VerifyCablingResponse {
+    /// Number of checks with `status`.
+    pub fn count(&self, status: CablingStatus) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lldp(system_name: Option<&str>, port_id: &str) -> DiscoveredNeighbour {
+        DiscoveredNeighbour {
+            protocol: DiscoveryProtocol::Lldp,
+            chassis_id: "52:54:00:12:34:56".to_string(),
+            system_name: system_name.map(str::to_string),
+            port_id: port_id.to_string(),
+            port_description: None,
+        }
+    }
+
+    #[test]
+    fn test_is_from_exact_and_abbreviated() {
+        assert!(lldp(Some("dev01"), "Ethernet1").is_from("dev01", "Ethernet1"));
+        assert!(lldp(Some("DEV01.lab.local"), "Et1").is_from("dev01", "Ethernet1"));
+        assert!(lldp(Some("sw01(9QXOX90PJ62)"), "Gi0/1").is_from("sw01", "GigabitEthernet0/1"));
+        assert!(lldp(None, "swp1").is_from("leaf01", "swp1"));
+    }
+
+    #[test]
+    fn test_is_from_mismatch() {
+        // Off by one, the mapping bug the check exists for.
+        assert!(!lldp(Some("dev01"), "Ethernet2").is_from("dev01", "Ethernet1"));
+        assert!(!lldp(Some("dev02"), "Ethernet1").is_from("dev01", "Ethernet1"));
+        assert!(!lldp(Some("dev01"), "Management1").is_from("dev01", "Ethernet1"));
+        assert!(!lldp(Some("dev01"), "eth").is_from("dev01", "ethernet"));
+    }
+
+    #[test]
+    fn test_is_from_port_description() {
+        let mut neighbour = lldp(Some("dev01"), "52:54:00:aa:bb:cc");
+        assert!(!neighbour.is_from("dev01", "eth1"));
+
+        neighbour.port_description = Some("eth1".to_string());
+        assert!(neighbour.is_from("dev01", "eth1"));
+    }
+
+    #[test]
+    fn test_cabling_status_serde() {
+        assert_eq!(
+            serde_json::to_string(&CablingStatus::NotSeen).unwrap(),
+            "\"not_seen\""
+        );
+        assert_eq!(CablingStatus::NotSeen.to_string(), "not seen");
+        assert_eq!(lldp(None, "eth1").to_string(), "52:54:00:12:34:56::eth1");
+    }
+}
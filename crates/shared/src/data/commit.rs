@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::node::{NodeKind, NodeModel};
+
+/// Request type for capturing a node as a new image version
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NodeCommitRequest {
+    pub lab_id: String,
+    pub node_name: String,
+    /// Version to register the captured image as
+    pub version: String,
+    /// Whether to set the captured image as the default version
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// Response type for the node commit operation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NodeCommitResponse {
+    pub node_name: String,
+    /// The node model the image was registered for
+    pub model: NodeModel,
+    /// The node kind (VirtualMachine or Container)
+    pub kind: NodeKind,
+    /// The version the image was registered as
+    pub version: String,
+    /// Disk path for VMs, image reference for containers
+    pub image: String,
+    /// SHA-256 of the stored disk image (VMs only)
+    #[serde(default)]
+    pub image_sha256: Option<String>,
+    /// Whether the image is now the default version
+    pub default: bool,
+    pub total_time_secs: u64,
+}
@@ -1,6 +1,7 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 
+use clap::ValueEnum;
 use ipnet::{Ipv4Net, Ipv6Net};
 use serde_derive::{Deserialize, Serialize};
 
@@ -9,18 +10,34 @@ use super::container::ContainerImage;
 use super::provider::VmProviders;
 
 use crate::konst::{
+    LDAP_DEFAULT_GROUP_ATTRIBUTE, LDAP_DEFAULT_TIMEOUT_SECS, LDAP_DEFAULT_USER_FILTER,
+    OIDC_DEFAULT_GROUPS_CLAIM, OIDC_DEFAULT_SCOPES, OIDC_DEFAULT_USERNAME_CLAIM,
     OTEL_DEFAULT_ENDPOINT, OTEL_DEFAULT_PROTOCOL, OTEL_DEFAULT_SAMPLE_RATIO,
-    OTEL_DEFAULT_SERVICE_NAME, SHERPA_PASSWORD, SHERPA_SERVER_HTTP_PORT, SHERPA_SERVER_WS_PORT,
-    SHERPA_USERNAME,
+    OTEL_DEFAULT_SERVICE_NAME, SHERPA_DB_PATH, SHERPA_PASSWORD, SHERPA_SERVER_HTTP_PORT,
+    SHERPA_SERVER_WS_PORT, SHERPA_USERNAME,
 };
 use crate::util::path_to_string;
 
+/// How lab boot services (DHCP, TFTP and HTTP for ZTP) are provided.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BootServices {
+    /// Run the `sherpa-router` dnsmasq container on the management network
+    #[default]
+    Container,
+    /// Serve DHCP, TFTP and HTTP from sherpad, no container images needed
+    Builtin,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ZtpServer {
     pub enable: bool,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Where lab DHCP/TFTP/HTTP boot services run (`boot_services = "builtin"`)
+    #[serde(default)]
+    pub boot_services: BootServices,
 }
 impl Default for ZtpServer {
     fn default() -> Self {
@@ -28,6 +45,7 @@ impl Default for ZtpServer {
             enable: true,
             username: Some(SHERPA_USERNAME.to_owned()),
             password: Some(SHERPA_PASSWORD.to_owned()),
+            boot_services: BootServices::default(),
         }
     }
 }
@@ -203,6 +221,142 @@ impl Default for ScannerConfig {
     }
 }
 
+/// Storage engine backing the sherpad database.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseEngine {
+    /// Separate SurrealDB server reached over WebSocket
+    #[default]
+    Remote,
+    /// Embedded in-memory store, discarded when sherpad stops
+    Memory,
+    /// Embedded RocksDB store under `path`
+    Rocksdb,
+    /// Embedded SurrealKV store under `path`
+    Surrealkv,
+}
+
+impl DatabaseEngine {
+    /// Whether the database runs inside sherpad rather than as a separate server
+    pub fn is_embedded(&self) -> bool {
+        !matches!(self, DatabaseEngine::Remote)
+    }
+}
+
+/// Database configuration (`[database]`).
+/// When the section is absent, sherpad connects to a separate SurrealDB server.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// Storage engine
+    pub engine: DatabaseEngine,
+    /// Data directory of the on-disk embedded engines
+    pub path: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            engine: DatabaseEngine::default(),
+            path: SHERPA_DB_PATH.to_owned(),
+        }
+    }
+}
+
+/// User authentication configuration.
+/// When the `[auth]` section is absent, only local password logins are enabled.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Allow password logins for users stored in the local `user` table.
+    /// Disable this when every user must come from LDAP or OIDC.
+    pub local: bool,
+    /// LDAP bind authentication (`[auth.ldap]`)
+    pub ldap: Option<LdapConfig>,
+    /// OpenID Connect single sign-on (`[auth.oidc]`)
+    pub oidc: Option<OidcConfig>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            local: true,
+            ldap: None,
+            oidc: None,
+        }
+    }
+}
+
+/// LDAP bind authentication settings.
+///
+/// Users are looked up under `user_base_dn` with `user_filter`, then
+/// authenticated by binding as the user's DN with their password.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LdapConfig {
+    /// Server URL, e.g. `ldaps://ldap.example.com:636`
+    pub url: String,
+    /// Upgrade a plain `ldap://` connection with StartTLS
+    #[serde(default)]
+    pub starttls: bool,
+    /// Skip TLS certificate validation
+    /// DANGEROUS: Only for development/testing
+    #[serde(default)]
+    pub insecure: bool,
+    /// DN of the service account used to search for users.
+    /// Anonymous search is used when not set.
+    #[serde(default)]
+    pub bind_dn: Option<String>,
+    /// Password of the service account
+    #[serde(default)]
+    pub bind_password: Option<String>,
+    /// Base DN to search for users, e.g. `ou=people,dc=example,dc=com`
+    pub user_base_dn: String,
+    /// Search filter, `{username}` is replaced with the escaped login name
+    #[serde(default = "default_ldap_user_filter")]
+    pub user_filter: String,
+    /// User attribute listing the DNs of the user's groups
+    #[serde(default = "default_ldap_group_attribute")]
+    pub group_attribute: String,
+    /// Members of any of these group DNs are Sherpa admins
+    #[serde(default)]
+    pub admin_groups: Vec<String>,
+    /// Connection and operation timeout in seconds
+    #[serde(default = "default_ldap_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// OpenID Connect single sign-on settings.
+///
+/// The web UI uses the authorization code flow with PKCE, `sherpa login --sso`
+/// uses the device authorization flow.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct OidcConfig {
+    /// Issuer URL, used for discovery at `/.well-known/openid-configuration`
+    pub issuer_url: String,
+    /// Client ID registered with the identity provider
+    pub client_id: String,
+    /// Client secret, not needed for public clients
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Callback URL registered with the identity provider,
+    /// e.g. `https://sherpa.example.com/auth/oidc/callback`
+    pub redirect_url: String,
+    /// Scopes requested from the identity provider
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    /// ID token claim holding the Sherpa username
+    #[serde(default = "default_oidc_username_claim")]
+    pub username_claim: String,
+    /// ID token claim holding the user's groups
+    #[serde(default = "default_oidc_groups_claim")]
+    pub groups_claim: String,
+    /// Members of any of these groups are Sherpa admins
+    #[serde(default)]
+    pub admin_groups: Vec<String>,
+}
+
 /// Full server configuration. All server-specific fields are required.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
@@ -238,6 +392,13 @@ pub struct Config {
     pub otel: OtelConfig,
     #[serde(default)]
     pub scanner: ScannerConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    /// Host interfaces non-admin users may use for external links
+    #[serde(default)]
+    pub host_interfaces: Vec<String>,
 }
 
 fn default_server_ipv4() -> Ipv4Addr {
@@ -252,6 +413,30 @@ fn default_http_port() -> u16 {
     SHERPA_SERVER_HTTP_PORT
 }
 
+fn default_ldap_user_filter() -> String {
+    LDAP_DEFAULT_USER_FILTER.to_owned()
+}
+
+fn default_ldap_group_attribute() -> String {
+    LDAP_DEFAULT_GROUP_ATTRIBUTE.to_owned()
+}
+
+fn default_ldap_timeout_secs() -> u64 {
+    LDAP_DEFAULT_TIMEOUT_SECS
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    OIDC_DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_oidc_username_claim() -> String {
+    OIDC_DEFAULT_USERNAME_CLAIM.to_owned()
+}
+
+fn default_oidc_groups_claim() -> String {
+    OIDC_DEFAULT_GROUPS_CLAIM.to_owned()
+}
+
 fn default_management_prefix() -> Ipv4Net {
     use std::str::FromStr;
     // SAFETY: This is a compile-time constant, so parsing cannot fail.
@@ -297,9 +482,20 @@ mod tests {
     #[test]
     fn test_ztp_server_default() {
         let ztp = ZtpServer::default();
-        assert_eq!(ztp.enable, true);
+        assert!(ztp.enable);
         assert_eq!(ztp.username, Some(SHERPA_USERNAME.to_owned()));
         assert_eq!(ztp.password, Some(SHERPA_PASSWORD.to_owned()));
+        assert_eq!(ztp.boot_services, BootServices::Container);
+    }
+
+    #[test]
+    fn test_ztp_server_boot_services_defaults_to_container() {
+        let ztp: ZtpServer = toml::from_str("enable = true").expect("deserializes");
+        assert_eq!(ztp.boot_services, BootServices::Container);
+
+        let ztp: ZtpServer =
+            toml::from_str("enable = true\nboot_services = \"builtin\"").expect("deserializes");
+        assert_eq!(ztp.boot_services, BootServices::Builtin);
     }
 
     #[test]
@@ -308,10 +504,12 @@ mod tests {
             enable: false,
             username: Some("test".to_string()),
             password: None,
+            boot_services: BootServices::Builtin,
         };
         let json = serde_json::to_string(&ztp).expect("serializes");
         let back: ZtpServer = serde_json::from_str(&json).expect("deserializes");
-        assert_eq!(back.enable, false);
+        assert!(!back.enable);
+        assert_eq!(back.boot_services, BootServices::Builtin);
         assert_eq!(back.username, Some("test".to_string()));
         assert!(back.password.is_none());
     }
@@ -319,9 +517,9 @@ mod tests {
     #[test]
     fn test_configuration_management_default() {
         let cm = ConfigurationManagement::default();
-        assert_eq!(cm.ansible, false);
-        assert_eq!(cm.pyats, false);
-        assert_eq!(cm.nornir, false);
+        assert!(!cm.ansible);
+        assert!(!cm.pyats);
+        assert!(!cm.nornir);
     }
 
     #[test]
@@ -329,18 +527,18 @@ mod tests {
         let sc = ServerConnection::default();
         assert!(sc.url.is_none());
         assert_eq!(sc.timeout_secs, 3);
-        assert_eq!(sc.validate_certs, true);
+        assert!(sc.validate_certs);
         assert!(sc.ca_cert_path.is_none());
-        assert_eq!(sc.insecure, false);
+        assert!(!sc.insecure);
     }
 
     #[test]
     fn test_tls_config_default() {
         let tls = TlsConfig::default();
-        assert_eq!(tls.enabled, true);
+        assert!(tls.enabled);
         assert!(tls.cert_path.is_none());
         assert!(tls.key_path.is_none());
-        assert_eq!(tls.auto_generate_cert, true);
+        assert!(tls.auto_generate_cert);
         assert_eq!(tls.cert_validity_days, 365);
         assert!(tls.san.is_empty());
     }
@@ -480,4 +678,88 @@ mod tests {
         assert_eq!(config.otel.endpoint, "http://localhost:4317");
         assert_eq!(config.otel.service_name, "sherpad");
     }
+
+    #[test]
+    fn test_database_config_defaults_to_remote() {
+        let toml_str = r#"
+            name = "test"
+            vm_provider = "libvirt"
+            qemu_bin = "/usr/bin/qemu-system-x86_64"
+            images_dir = "/opt/sherpa/images"
+            containers_dir = "/opt/sherpa/containers"
+            bins_dir = "/opt/sherpa/bins"
+        "#;
+        let config: Config = toml::from_str(toml_str).expect("deserializes without database");
+        assert_eq!(config.database.engine, DatabaseEngine::Remote);
+        assert!(!config.database.engine.is_embedded());
+        assert_eq!(config.database.path, "/opt/sherpa/db");
+    }
+
+    #[test]
+    fn test_database_config_embedded_engine() {
+        let toml_str = r#"
+            name = "test"
+            vm_provider = "libvirt"
+            qemu_bin = "/usr/bin/qemu-system-x86_64"
+            images_dir = "/opt/sherpa/images"
+            containers_dir = "/opt/sherpa/containers"
+            bins_dir = "/opt/sherpa/bins"
+
+            [database]
+            engine = "surrealkv"
+            path = "/var/lib/sherpa/db"
+        "#;
+        let config: Config = toml::from_str(toml_str).expect("deserializes with database");
+        assert_eq!(config.database.engine, DatabaseEngine::Surrealkv);
+        assert!(config.database.engine.is_embedded());
+        assert_eq!(config.database.path, "/var/lib/sherpa/db");
+    }
+
+    #[test]
+    fn test_auth_config_absent_from_toml() {
+        let toml_str = r#"
+            name = "test"
+            vm_provider = "libvirt"
+            qemu_bin = "/usr/bin/qemu-system-x86_64"
+            images_dir = "/opt/sherpa/images"
+            containers_dir = "/opt/sherpa/containers"
+            bins_dir = "/opt/sherpa/bins"
+        "#;
+        let config: Config = toml::from_str(toml_str).expect("deserializes without auth section");
+        assert!(config.auth.local);
+        assert!(config.auth.ldap.is_none());
+        assert!(config.auth.oidc.is_none());
+    }
+
+    #[test]
+    fn test_auth_config_external_providers_from_toml() {
+        let toml_str = r#"
+            local = false
+
+            [ldap]
+            url = "ldaps://ldap.example.com"
+            user_base_dn = "ou=people,dc=example,dc=com"
+            admin_groups = ["cn=sherpa-admins,ou=groups,dc=example,dc=com"]
+
+            [oidc]
+            issuer_url = "https://sso.example.com/realms/lab"
+            client_id = "sherpa"
+            redirect_url = "https://sherpa.example.com/auth/oidc/callback"
+        "#;
+        let auth: AuthConfig = toml::from_str(toml_str).expect("deserializes");
+        assert!(!auth.local);
+
+        let ldap = auth.ldap.expect("ldap section");
+        assert_eq!(ldap.user_filter, "(uid={username})");
+        assert_eq!(ldap.group_attribute, "memberOf");
+        assert_eq!(ldap.timeout_secs, 10);
+        assert!(ldap.bind_dn.is_none());
+        assert_eq!(ldap.admin_groups.len(), 1);
+
+        let oidc = auth.oidc.expect("oidc section");
+        assert_eq!(oidc.scopes, vec!["openid", "profile", "email"]);
+        assert_eq!(oidc.username_claim, "preferred_username");
+        assert_eq!(oidc.groups_claim, "groups");
+        assert!(oidc.client_secret.is_none());
+    }
 }
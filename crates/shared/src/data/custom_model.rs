@@ -0,0 +1,558 @@
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+use super::cpu::CpuModels;
+use super::disk::DiskBuses;
+use super::node::{
+    BiosTypes, CpuArchitecture, InterfaceType, MachineType, NodeConfig, NodeKind, NodeModel,
+    OsVariant, ZtpMethod,
+};
+use crate::konst::{CUSTOM_MODEL_NAME_MAX_LEN, CUSTOM_MODEL_PREFIX};
+
+/// A user-defined node model, loaded from a TOML definition.
+///
+/// Custom models boot an image of their generic base model (`generic_vm`,
+/// `generic_container` or `generic_unikernel`) and overlay the hardware,
+/// interface naming and ZTP settings from the definition. Manifests refer
+/// to them as `model = "custom:<name>"`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CustomModel {
+    /// Model name, referenced from manifests as `custom:<name>`
+    pub name: String,
+    /// Free-form description shown in `sherpa server image model list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Node kind, which selects the generic base image
+    #[serde(default)]
+    pub kind: NodeKind,
+    /// Base image version used when a manifest node does not set one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    // Hardware overrides, applied over the base image settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_variant: Option<OsVariant>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bios: Option<BiosTypes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_count: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_architecture: Option<CpuArchitecture>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_model: Option<CpuModels>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_type: Option<MachineType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vmx_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdd_bus: Option<DiskBuses>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cdrom_bus: Option<DiskBuses>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_type: Option<InterfaceType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_mtu: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_interface_count: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reserved_interface_count: Option<u8>,
+
+    /// Interface naming scheme
+    #[serde(default)]
+    pub interfaces: InterfaceNaming,
+    /// Zero-touch provisioning settings
+    #[serde(default)]
+    pub ztp: CustomZtp,
+}
+
+/// Interface naming scheme of a custom model.
+///
+/// Index 0 is the management interface. Data interface `n` (1-based) is
+/// named `{prefix}{first_index + n - 1}`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InterfaceNaming {
+    /// Data interface name prefix, e.g. `eth` or `ge-0/0/`
+    #[serde(default = "default_interface_prefix")]
+    pub prefix: String,
+    /// Number appended to the prefix for the first data interface
+    #[serde(default = "default_first_index")]
+    pub first_index: u8,
+    /// Name of the management interface
+    #[serde(default = "default_management_interface")]
+    pub management: String,
+}
+
+fn default_interface_prefix() -> String {
+    "eth".to_owned()
+}
+
+fn default_first_index() -> u8 {
+    1
+}
+
+fn default_management_interface() -> String {
+    "eth0".to_owned()
+}
+
+impl Default for InterfaceNaming {
+    fn default() -> Self {
+        Self {
+            prefix: default_interface_prefix(),
+            first_index: default_first_index(),
+            management: default_management_interface(),
+        }
+    }
+}
+
+impl InterfaceNaming {
+    /// Map an interface name to its index.
+    pub fn to_idx(&self, interface: &str) -> Result<u8> {
+        if interface == self.management {
+            return Ok(0);
+        }
+        let number = interface
+            .strip_prefix(self.prefix.as_str())
+            .and_then(|n| n.parse::<u16>().ok());
+        match number {
+            Some(n) if n >= self.first_index as u16 => {
+                let idx = n - self.first_index as u16 + 1;
+                u8::try_from(idx).map_err(|_| anyhow::anyhow!("Interface index out of range"))
+            }
+            _ => bail!(
+                "Invalid interface name: {} (expected {} or {}<n> with n >= {})",
+                interface,
+                self.management,
+                self.prefix,
+                self.first_index
+            ),
+        }
+    }
+
+    /// Map an interface index to its name.
+    pub fn from_idx(&self, idx: u8) -> Result<String> {
+        if idx == 0 {
+            return Ok(self.management.clone());
+        }
+        let number = self.first_index as u16 + idx as u16 - 1;
+        Ok(format!("{}{}", self.prefix, number))
+    }
+}
+
+/// Zero-touch provisioning settings of a custom model.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CustomZtp {
+    /// Delivery method: `cloud-init`, `cdrom`, `tftp` or `none`
+    #[serde(default = "default_ztp_method")]
+    pub method: ZtpMethod,
+    /// Name of the config file on the ZTP ISO (required for `cdrom`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Config template, rendered when the node is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Override the server's default ZTP username
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Override the server's default ZTP password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+fn default_ztp_method() -> ZtpMethod {
+    ZtpMethod::None
+}
+
+impl Default for CustomZtp {
+    fn default() -> Self {
+        Self {
+            method: default_ztp_method(),
+            filename: None,
+            template: None,
+            username: None,
+            password: None,
+        }
+    }
+}
+
+impl CustomModel {
+    /// Parse and validate a TOML model definition.
+    pub fn from_toml(definition: &str) -> Result<Self> {
+        let model: CustomModel = toml::from_str(definition)
+            .map_err(|e| anyhow::anyhow!("Invalid custom model definition: {}", e))?;
+        model.validate()?;
+        Ok(model)
+    }
+
+    /// The generic model whose images this custom model boots.
+    pub fn base_model(&self) -> NodeModel {
+        match self.kind {
+            NodeKind::VirtualMachine => NodeModel::GenericVm,
+            NodeKind::Container => NodeModel::GenericContainer,
+            NodeKind::Unikernel => NodeModel::GenericUnikernel,
+        }
+    }
+
+    /// Manifest reference for this model, e.g. `custom:vyos`.
+    pub fn reference(&self) -> String {
+        format!("{}{}", CUSTOM_MODEL_PREFIX, self.name)
+    }
+
+    /// Check the definition is usable.
+    pub fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+
+        let naming = &self.interfaces;
+        if naming.prefix.is_empty() {
+            bail!("Custom model '{}': interface prefix is empty", self.name);
+        }
+        if naming.management.is_empty() {
+            bail!(
+                "Custom model '{}': management interface name is empty",
+                self.name
+            );
+        }
+        if naming
+            .management
+            .strip_prefix(naming.prefix.as_str())
+            .and_then(|n| n.parse::<u16>().ok())
+            .is_some_and(|n| n >= naming.first_index as u16)
+        {
+            bail!(
+                "Custom model '{}': management interface '{}' clashes with the data interface names",
+                self.name,
+                naming.management
+            );
+        }
+
+        let ztp = &self.ztp;
+        match (&self.kind, &ztp.method) {
+            (_, ZtpMethod::None) => {}
+            (
+                NodeKind::VirtualMachine,
+                ZtpMethod::CloudInit | ZtpMethod::Cdrom | ZtpMethod::Tftp,
+            ) => {}
+            (NodeKind::VirtualMachine, method) => bail!(
+                "Custom model '{}': ZTP method '{}' is not supported (use cloud-init, cdrom, tftp or none)",
+                self.name,
+                method
+            ),
+            (kind, method) => bail!(
+                "Custom model '{}': ZTP method '{}' is not supported for {} models (use none)",
+                self.name,
+                method,
+                kind
+            ),
+        }
+        if ztp.method != ZtpMethod::None && ztp.template.is_none() {
+            bail!(
+                "Custom model '{}': ZTP method '{}' requires a template",
+                self.name,
+                ztp.method
+            );
+        }
+        if ztp.method == ZtpMethod::Cdrom {
+            match &ztp.filename {
+                Some(f) if !f.is_empty() && !f.contains('/') => {}
+                _ => bail!(
+                    "Custom model '{}': ZTP method 'cdrom' requires a plain config filename",
+                    self.name
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Overlay this definition onto the base image configuration.
+    pub fn apply(&self, base: &NodeConfig) -> NodeConfig {
+        let mut config = base.clone();
+        if let Some(v) = &self.os_variant {
+            config.os_variant = v.clone();
+        }
+        if let Some(v) = &self.bios {
+            config.bios = v.clone();
+        }
+        if let Some(v) = self.cpu_count {
+            config.cpu_count = v;
+        }
+        if let Some(v) = &self.cpu_architecture {
+            config.cpu_architecture = v.clone();
+        }
+        if let Some(v) = &self.cpu_model {
+            config.cpu_model = v.clone();
+        }
+        if let Some(v) = &self.machine_type {
+            config.machine_type = v.clone();
+        }
+        if let Some(v) = self.vmx_enabled {
+            config.vmx_enabled = v;
+        }
+        if let Some(v) = self.memory {
+            config.memory = v;
+        }
+        if let Some(v) = &self.hdd_bus {
+            config.hdd_bus = v.clone();
+        }
+        if let Some(v) = &self.cdrom_bus {
+            config.cdrom_bus = v.clone();
+        }
+        if let Some(v) = &self.interface_type {
+            config.interface_type = v.clone();
+        }
+        if let Some(v) = self.interface_mtu {
+            config.interface_mtu = v;
+        }
+        if let Some(v) = self.data_interface_count {
+            config.data_interface_count = v;
+        }
+        if let Some(v) = self.reserved_interface_count {
+            config.reserved_interface_count = v;
+        }
+
+        config.interface_prefix = self.interfaces.prefix.clone();
+        config.first_interface_index = self.interfaces.first_index;
+
+        config.ztp_enable = self.ztp.method != ZtpMethod::None;
+        config.ztp_method = self.ztp.method.clone();
+        if self.ztp.username.is_some() {
+            config.ztp_username = self.ztp.username.clone();
+        }
+        if self.ztp.password.is_some() {
+            config.ztp_password = self.ztp.password.clone();
+        }
+        config
+    }
+}
+
+/// Split a `custom:<name>` manifest model reference.
+pub fn parse_custom_model_ref(model: &str) -> Option<&str> {
+    model.strip_prefix(CUSTOM_MODEL_PREFIX)
+}
+
+/// Check a custom model name is safe to use as a file name and manifest reference.
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > CUSTOM_MODEL_NAME_MAX_LEN {
+        bail!(
+            "Custom model name must be 1-{} characters",
+            CUSTOM_MODEL_NAME_MAX_LEN
+        );
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        || !name.starts_with(|c: char| c.is_ascii_lowercase())
+    {
+        bail!(
+            "Invalid custom model name: {} (use lowercase letters, digits, '-' and '_', starting with a letter)",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Request type for adding a custom model
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddCustomModelRequest {
+    /// TOML model definition
+    pub definition: String,
+    /// Replace an existing model with the same name
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// Response from adding a custom model
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddCustomModelResponse {
+    /// The stored model definition
+    pub model: CustomModel,
+    /// Whether an existing model was replaced
+    pub replaced: bool,
+}
+
+/// Summary of a custom model for display purposes
+#[derive(Debug, Clone, Serialize, Deserialize, Tabled, JsonSchema)]
+pub struct CustomModelSummary {
+    #[tabled(rename = "Model")]
+    pub reference: String,
+    #[tabled(rename = "Base")]
+    pub base_model: NodeModel,
+    #[tabled(rename = "ZTP")]
+    pub ztp_method: ZtpMethod,
+    #[tabled(rename = "Description")]
+    pub description: String,
+}
+
+impl From<&CustomModel> for CustomModelSummary {
+    fn from(model: &CustomModel) -> Self {
+        Self {
+            reference: model.reference(),
+            base_model: model.base_model(),
+            ztp_method: model.ztp.method.clone(),
+            description: model.description.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Response from listing custom models
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListCustomModelsResponse {
+    /// Custom model definitions, sorted by name
+    pub models: Vec<CustomModel>,
+    /// Total number of models returned
+    pub total: usize,
+}
+
+/// Request type for deleting a custom model
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteCustomModelRequest {
+    /// Name of the model, with or without the `custom:` prefix
+    pub name: String,
+}
+
+/// Response from deleting a custom model
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteCustomModelResponse {
+    /// Name of the deleted model
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VYOS: &str = r#"
+name = "vyos"
+description = "VyOS rolling release"
+cpu_count = 2
+memory = 2048
+data_interface_count = 8
+
+[ztp]
+method = "cdrom"
+filename = "config.boot"
+template = "hostname {{ hostname }}"
+"#;
+
+    #[test]
+    fn test_from_toml_applies_defaults() {
+        let model = CustomModel::from_toml(VYOS).unwrap();
+        assert_eq!(model.name, "vyos");
+        assert_eq!(model.kind, NodeKind::VirtualMachine);
+        assert_eq!(model.base_model(), NodeModel::GenericVm);
+        assert_eq!(model.reference(), "custom:vyos");
+        assert_eq!(model.interfaces.prefix, "eth");
+        assert_eq!(model.interfaces.management, "eth0");
+        assert_eq!(model.ztp.method, ZtpMethod::Cdrom);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_unknown_fields() {
+        let err = CustomModel::from_toml("name = \"x\"\ncolour = \"red\"").unwrap_err();
+        assert!(err.to_string().contains("colour"));
+    }
+
+    #[test]
+    fn test_interface_naming_round_trip() {
+        let naming = InterfaceNaming {
+            prefix: "ge-0/0/".to_owned(),
+            first_index: 0,
+            management: "fxp0".to_owned(),
+        };
+        assert_eq!(naming.to_idx("fxp0").unwrap(), 0);
+        assert_eq!(naming.to_idx("ge-0/0/0").unwrap(), 1);
+        assert_eq!(naming.to_idx("ge-0/0/7").unwrap(), 8);
+        assert_eq!(naming.from_idx(0).unwrap(), "fxp0");
+        assert_eq!(naming.from_idx(1).unwrap(), "ge-0/0/0");
+        assert_eq!(naming.from_idx(8).unwrap(), "ge-0/0/7");
+        assert!(naming.to_idx("eth1").is_err());
+        assert!(naming.to_idx("ge-0/0/x").is_err());
+    }
+
+    #[test]
+    fn test_interface_naming_respects_first_index() {
+        let naming = InterfaceNaming::default();
+        assert!(naming.to_idx("eth0").is_ok());
+        assert_eq!(naming.to_idx("eth1").unwrap(), 1);
+        assert_eq!(naming.from_idx(2).unwrap(), "eth2");
+
+        let naming = InterfaceNaming {
+            management: "mgmt".to_owned(),
+            ..Default::default()
+        };
+        assert!(naming.to_idx("eth0").is_err());
+    }
+
+    #[test]
+    fn test_minimal_definition_has_no_ztp() {
+        let model = CustomModel::from_toml("name = \"x\"").unwrap();
+        assert_eq!(model.ztp.method, ZtpMethod::None);
+        assert!(!model.apply(&NodeConfig::generic_vm()).ztp_enable);
+    }
+
+    #[test]
+    fn test_validate_management_clash() {
+        let definition = "name = \"x\"\n[interfaces]\nfirst_index = 0\n";
+        let err = CustomModel::from_toml(definition).unwrap_err();
+        assert!(err.to_string().contains("clashes"));
+    }
+
+    #[test]
+    fn test_validate_ztp() {
+        let err = CustomModel::from_toml("name = \"x\"\n[ztp]\nmethod = \"tftp\"\n").unwrap_err();
+        assert!(err.to_string().contains("requires a template"));
+
+        let err =
+            CustomModel::from_toml("name = \"x\"\n[ztp]\nmethod = \"cdrom\"\ntemplate = \"a\"\n")
+                .unwrap_err();
+        assert!(err.to_string().contains("filename"));
+
+        let err =
+            CustomModel::from_toml("name = \"x\"\n[ztp]\nmethod = \"usb\"\ntemplate = \"a\"\n")
+                .unwrap_err();
+        assert!(err.to_string().contains("not supported"));
+
+        let err = CustomModel::from_toml(
+            "name = \"x\"\nkind = \"container\"\n[ztp]\nmethod = \"tftp\"\ntemplate = \"a\"\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("container"));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("vyos").is_ok());
+        assert!(validate_name("sonic-vs_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("VyOS").is_err());
+        assert!(validate_name("1vyos").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name(&"a".repeat(CUSTOM_MODEL_NAME_MAX_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_apply_overlays_base_image() {
+        let model = CustomModel::from_toml(VYOS).unwrap();
+        let base = NodeConfig::generic_vm();
+        let config = model.apply(&base);
+        assert_eq!(config.model, NodeModel::GenericVm);
+        assert_eq!(config.cpu_count, 2);
+        assert_eq!(config.memory, 2048);
+        assert_eq!(config.data_interface_count, 8);
+        assert_eq!(config.first_interface_index, 1);
+        assert!(config.ztp_enable);
+        assert_eq!(config.ztp_method, ZtpMethod::Cdrom);
+        assert_eq!(config.interface_mtu, base.interface_mtu);
+    }
+
+    #[test]
+    fn test_parse_custom_model_ref() {
+        assert_eq!(parse_custom_model_ref("custom:vyos"), Some("vyos"));
+        assert_eq!(parse_custom_model_ref("generic_vm"), None);
+    }
+}
@@ -1,15 +1,21 @@
 use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use super::{BridgeKind, LabState, NodeState, RecordId};
+use super::{
+    AuthProvider, BridgeKind, LabRole, LabState, LinkFilter, NodeKind, NodeModel, NodeState,
+    RecordId, TokenScope,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DbUser {
     pub id: Option<RecordId>,
     pub username: String,
+    /// Argon2id hash for local users, empty for externally authenticated users
     pub password_hash: String,
     pub is_admin: bool,
     pub ssh_keys: Vec<String>,
+    #[serde(default)]
+    pub auth_provider: AuthProvider,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
 }
@@ -80,6 +86,9 @@ pub struct DbLink {
     /// Link impairment: bit-flip corruption percent (0.0-100.0).
     #[serde(default)]
     pub corrupt_percent: f32,
+    /// Layer 2 filter policy (P2p and bridged links only).
+    #[serde(default)]
+    pub filter: LinkFilter,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -91,3 +100,77 @@ pub struct DbBridge {
     pub lab: RecordId,
     pub nodes: Vec<RecordId>,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbTeam {
+    pub id: Option<RecordId>,
+    pub name: String,
+    pub owner: RecordId,
+    pub members: Vec<RecordId>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbApiToken {
+    pub id: Option<RecordId>,
+    pub name: String,
+    pub user: RecordId,
+    /// SHA-256 digest of the token, the token itself is never stored.
+    pub token_hash: String,
+    /// First characters of the token, for display.
+    pub prefix: String,
+    pub scopes: Vec<TokenScope>,
+    pub expires_at: Timestamp,
+    pub last_used_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+/// Credentials for a private container registry
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbRegistryCredential {
+    pub id: Option<RecordId>,
+    /// Normalized registry host, unique per server.
+    pub registry: String,
+    pub username: String,
+    /// Password encrypted with the server registry key, never stored in the clear.
+    pub password_encrypted: String,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+/// A schema migration applied to the database
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbSchemaMigration {
+    pub id: Option<RecordId>,
+    pub version: u32,
+    pub name: String,
+    pub applied_at: Timestamp,
+}
+
+/// A node_image record with the labs and nodes that use it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbNodeImageUsage {
+    pub id: RecordId,
+    pub model: NodeModel,
+    pub kind: NodeKind,
+    pub version: String,
+    pub repo: Option<String>,
+    pub default: bool,
+    /// Sorted, de-duplicated lab IDs
+    pub labs: Vec<String>,
+    pub node_count: usize,
+    pub last_used_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DbLabShare {
+    pub id: Option<RecordId>,
+    pub lab: RecordId,
+    /// User the lab is shared with (mutually exclusive with `team`).
+    pub user: Option<RecordId>,
+    /// Team the lab is shared with (mutually exclusive with `user`).
+    pub team: Option<RecordId>,
+    pub role: LabRole,
+    pub created_at: Timestamp,
+}
@@ -1,4 +1,10 @@
-#[derive(Debug)]
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A DHCP lease handed out on a lab management network
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DhcpLease {
     pub expiry: u64,
     pub mac_address: String,
@@ -7,3 +13,78 @@ pub struct DhcpLease {
     pub hostname: String,
     pub client_id: String,
 }
+
+/// Whether a lease belongs to a node of the lab
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LeaseStatus {
+    /// The lease MAC is the management MAC of a node
+    Known,
+    /// No node of the lab has the lease MAC
+    Unknown,
+}
+
+impl fmt::Display for LeaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaseStatus::Known => write!(f, "known"),
+            LeaseStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A DHCP lease on a lab management network, matched to a node
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LabLease {
+    /// Client MAC address
+    pub mac_address: String,
+    /// Leased IPv4 address
+    pub ipv4_address: String,
+    /// Leased IPv6 address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipv6_address: Option<String>,
+    /// Hostname sent by the client, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Lease expiry as Unix seconds, 0 for an infinite lease
+    pub expiry: u64,
+    /// Node with the lease MAC as its management MAC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+    /// Whether the lease belongs to a node of the lab
+    pub status: LeaseStatus,
+}
+
+/// Request to list the DHCP leases of a lab
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LabLeasesRequest {
+    /// Lab ID to list leases for
+    pub lab_id: String,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response with the DHCP leases of a lab
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LabLeasesResponse {
+    /// Lab ID
+    pub lab_id: String,
+    /// Active leases on the lab management network
+    pub leases: Vec<LabLease>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lease_status_serde() {
+        assert_eq!(
+            serde_json::to_string(&LeaseStatus::Unknown).unwrap(),
+            "\"unknown\""
+        );
+        let status: LeaseStatus = serde_json::from_str("\"known\"").unwrap();
+        assert_eq!(status, LeaseStatus::Known);
+        assert_eq!(status.to_string(), "known");
+    }
+}
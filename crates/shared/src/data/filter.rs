@@ -0,0 +1,360 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::konst::FILTER_MAX_RULES;
+
+/// Well-known layer 2 control protocols a link filter can match
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum L2Protocol {
+    /// Link Layer Discovery Protocol, ethertype 0x88cc
+    Lldp,
+    /// Slow protocols (LACP and marker), ethertype 0x8809
+    Lacp,
+    /// Spanning tree BPDUs, IEEE and Cisco PVST+
+    Stp,
+    /// Cisco multicast 01:00:0c:cc:cc:cc, also carries VTP and DTP
+    Cdp,
+    /// 802.1X port authentication (EAPoL), ethertype 0x888e
+    Dot1x,
+}
+
+impl L2Protocol {
+    const ALL: [L2Protocol; 5] = [
+        L2Protocol::Lldp,
+        L2Protocol::Lacp,
+        L2Protocol::Stp,
+        L2Protocol::Cdp,
+        L2Protocol::Dot1x,
+    ];
+
+    /// Frames of the protocol
+    pub fn frames(&self) -> Vec<FrameMatch> {
+        match self {
+            L2Protocol::Lldp => vec![FrameMatch::Ethertype(0x88cc)],
+            L2Protocol::Lacp => vec![FrameMatch::Ethertype(0x8809)],
+            L2Protocol::Stp => vec![
+                FrameMatch::DestMac([0x01, 0x80, 0xc2, 0x00, 0x00, 0x00]),
+                FrameMatch::DestMac([0x01, 0x00, 0x0c, 0xcc, 0xcc, 0xcd]),
+            ],
+            L2Protocol::Cdp => vec![FrameMatch::DestMac([0x01, 0x00, 0x0c, 0xcc, 0xcc, 0xcc])],
+            L2Protocol::Dot1x => vec![FrameMatch::Ethertype(0x888e)],
+        }
+    }
+}
+
+impl fmt::Display for L2Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            L2Protocol::Lldp => write!(f, "lldp"),
+            L2Protocol::Lacp => write!(f, "lacp"),
+            L2Protocol::Stp => write!(f, "stp"),
+            L2Protocol::Cdp => write!(f, "cdp"),
+            L2Protocol::Dot1x => write!(f, "dot1x"),
+        }
+    }
+}
+
+/// One entry of a link filter list.
+///
+/// Written as a protocol name (`lldp`, `lacp`, `stp`, `cdp`, `dot1x`),
+/// `ethertype:<value>` with a hex (`0x88cc`) or decimal value, or
+/// `vlan:<id>`. Ethertypes match the inner ethertype of VLAN tagged frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum L2Match {
+    Protocol(L2Protocol),
+    Ethertype(u16),
+    Vlan(u16),
+}
+
+impl L2Match {
+    /// Frames matched by the entry
+    pub fn frames(&self) -> Vec<FrameMatch> {
+        match self {
+            L2Match::Protocol(protocol) => protocol.frames(),
+            L2Match::Ethertype(ethertype) => vec![FrameMatch::Ethertype(*ethertype)],
+            L2Match::Vlan(vlan) => vec![FrameMatch::Vlan(*vlan)],
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            L2Match::Protocol(_) => {}
+            // Smaller values are 802.3 frame lengths, not ethertypes
+            L2Match::Ethertype(ethertype) if *ethertype < 0x0600 => {
+                bail!("{self} is a frame length, ethertypes start at 0x0600")
+            }
+            L2Match::Vlan(vlan) if !(1..=4094).contains(vlan) => {
+                bail!("{self} is out of range, VLAN IDs are 1-4094")
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for L2Match {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            L2Match::Protocol(protocol) => write!(f, "{protocol}"),
+            L2Match::Ethertype(ethertype) => write!(f, "ethertype:{ethertype:#06x}"),
+            L2Match::Vlan(vlan) => write!(f, "vlan:{vlan}"),
+        }
+    }
+}
+
+impl FromStr for L2Match {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let entry = s.trim().to_lowercase();
+        if let Some(protocol) = L2Protocol::ALL.iter().find(|p| p.to_string() == entry) {
+            return Ok(L2Match::Protocol(*protocol));
+        }
+
+        let invalid = || {
+            anyhow!(
+                "Invalid filter entry '{s}', expected one of lldp, lacp, stp, cdp, dot1x, ethertype:<value> or vlan:<id>"
+            )
+        };
+        let (kind, value) = entry.split_once(':').ok_or_else(invalid)?;
+        let value = match value.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16),
+            None => value.parse(),
+        }
+        .map_err(|_| invalid())?;
+        match kind {
+            "ethertype" => Ok(L2Match::Ethertype(value)),
+            "vlan" => Ok(L2Match::Vlan(value)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for L2Match {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<L2Match> for String {
+    fn from(value: L2Match) -> Self {
+        value.to_string()
+    }
+}
+
+/// Frame property a filter rule matches on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameMatch {
+    Ethertype(u16),
+    DestMac([u8; 6]),
+    Vlan(u16),
+    /// Every frame
+    Any,
+}
+
+/// What happens to a frame matching a filter rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterVerdict {
+    Pass,
+    Drop,
+}
+
+/// Layer 2 filter policy of a link, applied to both directions.
+///
+/// Frames matching `drop` are dropped. When `allow` is set, frames that
+/// match none of its entries are dropped as well. A frame matching both
+/// lists is dropped.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LinkFilter {
+    /// Frames to drop
+    #[serde(default)]
+    #[schemars(with = "Vec<String>")]
+    pub drop: Vec<L2Match>,
+    /// Only pass these frames
+    #[serde(default)]
+    #[schemars(with = "Vec<String>")]
+    pub allow: Vec<L2Match>,
+}
+
+impl LinkFilter {
+    /// Whether the filter passes every frame
+    pub fn is_empty(&self) -> bool {
+        self.drop.is_empty() && self.allow.is_empty()
+    }
+
+    /// Check entry values and that the filter fits the eBPF filter map.
+    pub fn validate(&self) -> Result<()> {
+        for entry in self.drop.iter().chain(&self.allow) {
+            entry.validate()?;
+        }
+        let rules = self.rules().len();
+        if rules > FILTER_MAX_RULES as usize {
+            bail!(
+                "Link filter expands to {} rules, the maximum is {}",
+                rules,
+                FILTER_MAX_RULES
+            );
+        }
+        Ok(())
+    }
+
+    /// Rules in evaluation order, the first rule matching a frame decides
+    /// its verdict. Frames matching no rule pass.
+    pub fn rules(&self) -> Vec<(FrameMatch, FilterVerdict)> {
+        let mut rules = vec![];
+        for (entries, verdict) in [
+            (&self.drop, FilterVerdict::Drop),
+            (&self.allow, FilterVerdict::Pass),
+        ] {
+            for frame in entries.iter().flat_map(L2Match::frames) {
+                if !rules.iter().any(|(existing, _)| *existing == frame) {
+                    rules.push((frame, verdict));
+                }
+            }
+        }
+        if !self.allow.is_empty() {
+            rules.push((FrameMatch::Any, FilterVerdict::Drop));
+        }
+        rules
+    }
+}
+
+impl fmt::Display for LinkFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |entries: &[L2Match]| {
+            entries
+                .iter()
+                .map(L2Match::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match (self.drop.is_empty(), self.allow.is_empty()) {
+            (true, true) => write!(f, "pass all"),
+            (false, true) => write!(f, "drop {}", join(&self.drop)),
+            (true, false) => write!(f, "allow only {}", join(&self.allow)),
+            (false, false) => write!(
+                f,
+                "drop {}; allow only {}",
+                join(&self.drop),
+                join(&self.allow)
+            ),
+        }
+    }
+}
+
+/// Request to replace the filter of a running link
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkFilterRequest {
+    /// Lab ID
+    pub lab_id: String,
+    /// Either end of the link, as "node::interface"
+    pub link: String,
+    /// New filter, empty to pass every frame
+    #[serde(default)]
+    pub filter: LinkFilter,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Result of replacing a link filter
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkFilterResponse {
+    pub lab_id: String,
+    pub link_index: u16,
+    pub filter: LinkFilter,
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_l2_match_parse_roundtrip() {
+        for (entry, expected) in [
+            ("lacp", L2Match::Protocol(L2Protocol::Lacp)),
+            ("DOT1X", L2Match::Protocol(L2Protocol::Dot1x)),
+            ("ethertype:0x88cc", L2Match::Ethertype(0x88cc)),
+            ("ethertype:2048", L2Match::Ethertype(0x0800)),
+            ("vlan:100", L2Match::Vlan(100)),
+        ] {
+            let parsed: L2Match = entry.parse().unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.to_string().parse::<L2Match>().unwrap(), expected);
+        }
+        assert!("bgp".parse::<L2Match>().is_err());
+        assert!("vlan:abc".parse::<L2Match>().is_err());
+    }
+
+    #[test]
+    fn test_link_filter_serde() {
+        let filter: LinkFilter =
+            serde_json::from_str(r#"{"drop": ["lacp", "vlan:20"], "allow": []}"#).unwrap();
+        assert_eq!(
+            filter.drop,
+            vec![L2Match::Protocol(L2Protocol::Lacp), L2Match::Vlan(20)]
+        );
+        assert_eq!(
+            serde_json::to_string(&filter).unwrap(),
+            r#"{"drop":["lacp","vlan:20"],"allow":[]}"#
+        );
+        assert!(serde_json::from_str::<LinkFilter>(r#"{"drop": ["ospf"]}"#).is_err());
+    }
+
+    #[test]
+    fn test_link_filter_rules() {
+        let filter = LinkFilter {
+            drop: vec![L2Match::Protocol(L2Protocol::Stp)],
+            allow: vec![L2Match::Vlan(10)],
+        };
+        let rules = filter.rules();
+        assert_eq!(rules.len(), 4);
+        assert_eq!(
+            rules[0],
+            (
+                FrameMatch::DestMac([0x01, 0x80, 0xc2, 0x00, 0x00, 0x00]),
+                FilterVerdict::Drop
+            )
+        );
+        assert_eq!(rules[2], (FrameMatch::Vlan(10), FilterVerdict::Pass));
+        assert_eq!(rules[3], (FrameMatch::Any, FilterVerdict::Drop));
+        assert!(LinkFilter::default().rules().is_empty());
+    }
+
+    #[test]
+    fn test_link_filter_validate() {
+        let valid = LinkFilter {
+            drop: vec![L2Match::Protocol(L2Protocol::Lldp), L2Match::Vlan(4094)],
+            allow: vec![],
+        };
+        assert!(valid.validate().is_ok());
+
+        for entry in [
+            L2Match::Vlan(0),
+            L2Match::Vlan(4095),
+            L2Match::Ethertype(64),
+        ] {
+            let invalid = LinkFilter {
+                drop: vec![entry],
+                allow: vec![],
+            };
+            assert!(invalid.validate().is_err(), "{entry}");
+        }
+
+        let too_many = LinkFilter {
+            drop: (1..=FILTER_MAX_RULES as u16 + 1)
+                .map(L2Match::Vlan)
+                .collect(),
+            allow: vec![],
+        };
+        assert!(too_many.validate().is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::node::{NodeKind, NodeModel};
+
+/// Storage and lab usage of one image version
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageVersionUsage {
+    pub model: NodeModel,
+    pub kind: NodeKind,
+    pub version: String,
+    pub default: bool,
+    /// Bytes on disk, or the Docker image size for containers.
+    /// None when the image files or Docker image are missing.
+    pub size_bytes: Option<u64>,
+    /// Docker image reference, for containers
+    pub container_image: Option<String>,
+    /// IDs of the labs with nodes using this version
+    pub labs: Vec<String>,
+    /// Number of nodes using this version
+    pub node_count: usize,
+    /// Unix timestamp (seconds) of the last time a node using this version
+    /// was created or removed, or the version was added
+    pub last_used_at: Option<i64>,
+    /// Unix timestamp (seconds) the image files were stored, for disk images.
+    /// Stands in for `last_used_at` on versions added before usage was recorded.
+    pub imported_at: Option<i64>,
+}
+
+/// A file in the libvirt storage pool that no node in the database owns
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OrphanedDisk {
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Response from an image usage report
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageUsageResponse {
+    /// Every image version, ordered by model and version
+    pub images: Vec<ImageVersionUsage>,
+    /// Lab disks left behind in the storage pool
+    pub orphaned_disks: Vec<OrphanedDisk>,
+    /// Total size of all image versions
+    pub total_size_bytes: u64,
+}
+
+/// Request type for pruning unreferenced image versions
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PruneImagesRequest {
+    /// Only prune versions unused for at least this many seconds.
+    /// Versions with no recorded use are kept when this is set.
+    pub unused_for_secs: Option<u64>,
+    /// If true, only report what would be removed
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// An image version removed (or selected for removal) by a prune
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PrunedImage {
+    pub model: NodeModel,
+    pub kind: NodeKind,
+    pub version: String,
+    pub size_bytes: Option<u64>,
+}
+
+/// Response from an image prune
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PruneImagesResponse {
+    pub pruned: Vec<PrunedImage>,
+    /// Total size of the pruned versions
+    pub freed_bytes: u64,
+    pub dry_run: bool,
+    /// Versions that could not be removed, with the reason
+    pub errors: Vec<String>,
+}
@@ -63,6 +63,16 @@ pub struct ImportRequest {
     /// Whether to set this image as the default version
     #[serde(default)]
     pub default: bool,
+    /// Expected digest of the source file, `sha256:<hex>`, `md5:<hex>` or a
+    /// bare hex digest. Falls back to a vendor checksum file next to the source.
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// Compress the converted qcow2 image
+    #[serde(default)]
+    pub compress: bool,
+    /// Sparsify the image with `virt-sparsify` after conversion
+    #[serde(default)]
+    pub sparsify: bool,
 }
 
 /// Response from an image import operation
@@ -80,6 +90,126 @@ pub struct ImportResponse {
     pub image_path: String,
     /// Whether the image was tracked in the database
     pub db_tracked: bool,
+    /// SHA-256 of the stored image file
+    #[serde(default)]
+    pub image_sha256: Option<String>,
+}
+
+/// Request type for starting, or resuming, a chunked image upload
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StartUploadRequest {
+    /// The node model to import the image for
+    pub model: NodeModel,
+    /// Version string for the image
+    pub version: String,
+    /// Name of the file being uploaded
+    pub filename: String,
+    /// Total size of the file in bytes
+    pub size: u64,
+    /// SHA-256 of the whole file, verified before import
+    pub sha256: String,
+    /// Whether to set this image as the default version
+    #[serde(default)]
+    pub default: bool,
+    /// Compress the converted qcow2 image
+    #[serde(default)]
+    pub compress: bool,
+    /// Sparsify the image with `virt-sparsify` after conversion
+    #[serde(default)]
+    pub sparsify: bool,
+}
+
+/// State of a chunked image upload
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UploadStatus {
+    /// Upload identifier, derived from the model, version and file digest
+    pub upload_id: String,
+    /// Total size of the file in bytes
+    pub size: u64,
+    /// Bytes received so far; the next chunk must start at this offset
+    pub received: u64,
+    /// Largest chunk the server accepts, in bytes
+    pub max_chunk_size: u64,
+}
+
+/// Request type for uploading one chunk of an image
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UploadChunkRequest {
+    /// Upload identifier returned when the upload was started
+    pub upload_id: String,
+    /// Offset of the chunk within the file
+    pub offset: u64,
+    /// SHA-256 of the chunk data
+    pub sha256: String,
+    /// Base64 encoded chunk data
+    pub data: String,
+}
+
+/// Response from uploading a chunk
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UploadChunkResponse {
+    /// Upload identifier
+    pub upload_id: String,
+    /// Total size of the file in bytes
+    pub size: u64,
+    /// Bytes received so far
+    pub received: u64,
+    /// Result of the import, present once the final chunk was received
+    pub import: Option<ImportResponse>,
+}
+
+/// Request type for cancelling a chunked upload
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CancelUploadRequest {
+    /// Upload identifier returned when the upload was started
+    pub upload_id: String,
+}
+
+/// Response from cancelling a chunked upload
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CancelUploadResponse {
+    /// Upload identifier
+    pub upload_id: String,
+    /// Whether partial upload data was deleted
+    pub deleted: bool,
+}
+
+/// Request type for verifying the integrity of an imported image
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VerifyImageRequest {
+    /// The node model of the image to verify
+    pub model: NodeModel,
+    /// Version string for the image to verify
+    pub version: String,
+}
+
+/// Outcome of an image integrity check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageIntegrity {
+    /// The image file matches the recorded digest
+    Ok,
+    /// The image file doesn't match the recorded digest
+    Mismatch,
+    /// No digest was recorded when the image was imported
+    Untracked,
+}
+
+/// Response from an image verify operation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VerifyImageResponse {
+    /// The node model that was verified
+    pub model: NodeModel,
+    /// The version that was verified
+    pub version: String,
+    /// Path of the image file on disk
+    pub image_path: String,
+    /// SHA-256 recorded when the image was imported
+    pub expected_sha256: Option<String>,
+    /// SHA-256 of the image file as it is now
+    pub actual_sha256: String,
+    /// Result of the comparison
+    pub status: ImageIntegrity,
 }
 
 /// Request type for scanning on-disk images
@@ -193,16 +323,28 @@ pub struct ContainerPullResponse {
     pub message: String,
 }
 
-/// Request type for downloading a VM image from a URL
+/// Credentials for an OCI registry
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// Request type for downloading a VM or unikernel image from a URL or OCI registry
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DownloadImageRequest {
     /// The node model to download the image for
     pub model: NodeModel,
     /// Version string for the image
     pub version: String,
-    /// Optional download URL (auto-resolved for some models)
+    /// Optional download URL (auto-resolved for some models).
+    /// `oci://<registry>/<repository>:<tag>` or `...@sha256:<digest>`
+    /// pulls the image from an OCI registry.
     pub url: Option<String>,
     /// Whether to set this image as the default version
     #[serde(default)]
     pub default: bool,
+    /// Credentials for the registry of an `oci://` URL
+    #[serde(default)]
+    pub registry_auth: Option<RegistryAuth>,
 }
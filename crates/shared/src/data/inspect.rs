@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::data::{LabInfo, LabState, NodeKind, NodeModel, NodeState};
+use crate::data::{CablingCheck, LabInfo, LabState, NodeKind, NodeModel, NodeState};
 
 /// Request type for inspecting a lab
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -22,6 +22,10 @@ pub struct InspectResponse {
     pub inactive_devices: Vec<String>,
     pub links: Vec<LinkInfo>,
     pub bridges: Vec<BridgeInfo>,
+    /// Expected and discovered neighbour per interface from the latest
+    /// cabling verification, empty until the lab is verified.
+    #[serde(default)]
+    pub cabling: Vec<CablingCheck>,
 }
 
 /// Information about a single device/node
@@ -45,6 +49,9 @@ pub struct LinkInfo {
     pub node_b_name: String,
     pub int_b: String,
     pub kind: String,
+    /// Summary of the active impairment, e.g. `20ms ±5ms, 1% loss`.
+    #[serde(default)]
+    pub impairment: Option<String>,
 }
 
 /// Display-ready information about a shared bridge connecting multiple nodes
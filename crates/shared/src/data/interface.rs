@@ -174,6 +174,7 @@ pub enum ConnectionTypes {
     PeerBridge,    // Peered with another device via a bridge
     PrivateBridge, // Attached to a private bridge
     Reserved,      // Reserved interfaces used by the virtual platform
+    VlanBridge,    // Named tap joined to a VLAN-aware bridge by sherpad
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -186,6 +187,13 @@ pub struct Interface {
     pub interface_connection: Option<InterfaceConnection>,
 }
 
+/// MTU the manifest sets on a data interface, from its link or its node
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InterfaceMtu {
+    pub name: String,
+    pub mtu: u16,
+}
+
 interface_enum!(
     ArubaAoscxInt,
     "ArubaAoscx",
@@ -9,7 +9,7 @@ use serde_derive::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-use super::{BridgeKind, DbNode, NodeKind, NodeModel, NodeState};
+use super::{BridgeKind, DbNode, LabRole, NodeKind, NodeModel, NodeState};
 
 #[derive(Clone, Debug)]
 pub enum PeerSide {
@@ -36,6 +36,9 @@ pub struct PeerInterface {
 #[derive(Clone, Debug)]
 pub struct BridgeInterface {
     pub name: String,
+    /// Named tap of a VLAN-aware bridge port, joined to the bridge by
+    /// sherpad instead of libvirt.
+    pub tap: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -250,6 +253,17 @@ pub struct LabSummary {
     pub node_count: usize,
     /// Current status of the lab
     pub status: LabState,
+    /// Role granted to the user when the lab is shared with them (None for own labs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_role: Option<LabRole>,
+}
+
+impl LabSummary {
+    /// Whether the user may start and stop the lab's nodes.
+    pub fn can_operate(&self) -> bool {
+        self.shared_role
+            .is_none_or(|role| role >= LabRole::Operator)
+    }
 }
 
 /// Response for listing labs
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::BridgeKind;
+
+/// Where the counters of a link were read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStatsSource {
+    /// Counter map of the eBPF redirect programs (P2p links)
+    Ebpf,
+    /// Kernel interface statistics of the link's host interfaces
+    Netlink,
+    /// The link's host interfaces were not found
+    Unavailable,
+}
+
+impl fmt::Display for LinkStatsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkStatsSource::Ebpf => write!(f, "ebpf"),
+            LinkStatsSource::Netlink => write!(f, "netlink"),
+            LinkStatsSource::Unavailable => write!(f, "unavailable"),
+        }
+    }
+}
+
+/// Cumulative traffic counters for one direction of a link
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TrafficCounters {
+    pub packets: u64,
+    pub bytes: u64,
+    /// Packets dropped or not redirected in this direction
+    pub drops: u64,
+}
+
+/// Traffic counters of a lab link
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LinkStats {
+    pub index: u16,
+    pub kind: BridgeKind,
+    pub node_a: String,
+    pub int_a: String,
+    pub node_b: String,
+    pub int_b: String,
+    pub source: LinkStatsSource,
+    /// Traffic sent by node A towards node B
+    pub a_to_b: TrafficCounters,
+    /// Traffic sent by node B towards node A
+    pub b_to_a: TrafficCounters,
+}
+
+/// Per-second rates of a link between two samples
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkRates {
+    pub a_to_b_pps: f64,
+    pub a_to_b_bps: f64,
+    pub b_to_a_pps: f64,
+    pub b_to_a_bps: f64,
+}
+
+impl LinkStats {
+    /// Rates since an earlier sample of the same link taken `elapsed_secs` ago.
+    /// Counters that went backwards (interface recreated) count as zero.
+    pub fn rates_since(&self, previous: &LinkStats, elapsed_secs: f64) -> LinkRates {
+        if elapsed_secs <= 0.0 {
+            return LinkRates::default();
+        }
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / elapsed_secs;
+        LinkRates {
+            a_to_b_pps: rate(self.a_to_b.packets, previous.a_to_b.packets),
+            a_to_b_bps: rate(self.a_to_b.bytes, previous.a_to_b.bytes) * 8.0,
+            b_to_a_pps: rate(self.b_to_a.packets, previous.b_to_a.packets),
+            b_to_a_bps: rate(self.b_to_a.bytes, previous.b_to_a.bytes) * 8.0,
+        }
+    }
+}
+
+/// Request for the traffic counters of a lab's links
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkStatsRequest {
+    /// Lab ID to read link counters for
+    pub lab_id: String,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Traffic counters of a lab's links
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkStatsResponse {
+    /// Lab ID
+    pub lab_id: String,
+    /// When the counters were read, as Unix milliseconds
+    pub sampled_at_ms: u64,
+    pub links: Vec<LinkStats>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(a_to_b: (u64, u64), b_to_a: (u64, u64)) -> LinkStats {
+        LinkStats {
+            index: 0,
+            kind: BridgeKind::P2p,
+            node_a: "dev01".to_string(),
+            int_a: "eth1".to_string(),
+            node_b: "dev02".to_string(),
+            int_b: "eth1".to_string(),
+            source: LinkStatsSource::Ebpf,
+            a_to_b: TrafficCounters {
+                packets: a_to_b.0,
+                bytes: a_to_b.1,
+                drops: 0,
+            },
+            b_to_a: TrafficCounters {
+                packets: b_to_a.0,
+                bytes: b_to_a.1,
+                drops: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_rates_since() {
+        let before = sample((100, 10_000), (50, 5_000));
+        let now = sample((300, 30_000), (50, 5_000));
+
+        let rates = now.rates_since(&before, 2.0);
+        assert_eq!(rates.a_to_b_pps, 100.0);
+        assert_eq!(rates.a_to_b_bps, 80_000.0);
+        assert_eq!(rates.b_to_a_pps, 0.0);
+    }
+
+    #[test]
+    fn test_rates_since_counter_reset() {
+        let before = sample((300, 30_000), (0, 0));
+        let now = sample((10, 1_000), (0, 0));
+
+        assert_eq!(now.rates_since(&before, 1.0), LinkRates::default());
+        assert_eq!(now.rates_since(&before, 0.0), LinkRates::default());
+    }
+
+    #[test]
+    fn test_link_stats_source_serde() {
+        assert_eq!(
+            serde_json::to_string(&LinkStatsSource::Netlink).unwrap(),
+            "\"netlink\""
+        );
+        assert_eq!(LinkStatsSource::Ebpf.to_string(), "ebpf");
+    }
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Which traffic of the source interface is copied to a mirror port
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorDirection {
+    /// Frames received by the source interface
+    Ingress,
+    /// Frames sent by the source interface
+    Egress,
+    /// Frames sent and received by the source interface
+    #[default]
+    Both,
+}
+
+impl MirrorDirection {
+    /// Whether frames received by the source interface are mirrored
+    pub fn includes_ingress(&self) -> bool {
+        matches!(self, MirrorDirection::Ingress | MirrorDirection::Both)
+    }
+
+    /// Whether frames sent by the source interface are mirrored
+    pub fn includes_egress(&self) -> bool {
+        matches!(self, MirrorDirection::Egress | MirrorDirection::Both)
+    }
+}
+
+impl fmt::Display for MirrorDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MirrorDirection::Ingress => write!(f, "ingress"),
+            MirrorDirection::Egress => write!(f, "egress"),
+            MirrorDirection::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Request to start or stop mirroring a link interface to a mirror port
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkMirrorRequest {
+    /// Lab ID
+    pub lab_id: String,
+    /// Mirrored interface, as "node::interface"
+    pub source: String,
+    /// Mirror port declared in the lab manifest, as "node::interface"
+    pub destination: String,
+    #[serde(default)]
+    pub direction: MirrorDirection,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Result of starting or stopping a mirror session
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkMirrorResponse {
+    pub lab_id: String,
+    pub source: String,
+    pub destination: String,
+    pub direction: MirrorDirection,
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_direction_serde() {
+        assert_eq!(
+            serde_json::to_string(&MirrorDirection::Egress).unwrap(),
+            "\"egress\""
+        );
+        let direction: MirrorDirection = serde_json::from_str("\"ingress\"").unwrap();
+        assert_eq!(direction, MirrorDirection::Ingress);
+        assert_eq!(MirrorDirection::default().to_string(), "both");
+    }
+
+    #[test]
+    fn test_mirror_direction_includes() {
+        assert!(MirrorDirection::Both.includes_ingress());
+        assert!(MirrorDirection::Both.includes_egress());
+        assert!(!MirrorDirection::Ingress.includes_egress());
+        assert!(!MirrorDirection::Egress.includes_ingress());
+    }
+}
@@ -1,67 +1,114 @@
+mod api_token;
 mod auth;
+mod cabling;
+mod commit;
 mod config;
 mod container;
 mod cpu;
+mod custom_model;
 mod db;
 mod destroy;
 mod dhcp;
 mod disk;
 mod dns;
 mod download;
+mod filter;
+mod image_usage;
 mod impairment;
 mod import;
 mod inspect;
 mod interface;
 mod lab;
+mod link_stats;
 mod mapping;
+mod mirror;
 mod network;
 mod node;
 mod provider;
 mod record_id;
 mod redeploy;
+mod registry;
+mod share;
 mod ssh;
 mod up;
 mod user;
 mod user_management;
+mod vlan;
 mod vm_action;
 mod ws;
 mod ztp;
 
-pub use auth::{LoginRequest, LoginResponse, ValidateRequest, ValidateResponse};
+pub use api_token::{
+    ApiTokenInfo, CreateApiTokenRequest, CreateApiTokenResponse, ListApiTokensRequest,
+    ListApiTokensResponse, RevokeApiTokenRequest, RevokeApiTokenResponse, TokenScope,
+};
+pub use auth::{
+    AuthProvider, AuthProvidersResponse, DeviceLoginPollRequest, DeviceLoginPollResponse,
+    DeviceLoginStartResponse, DeviceLoginStatus, LoginRequest, LoginResponse, ValidateRequest,
+    ValidateResponse,
+};
 
+pub use cabling::{
+    CablingCheck, CablingStatus, DiscoveredNeighbour, DiscoveryProtocol, VerifyCablingRequest,
+    VerifyCablingResponse,
+};
+pub use commit::{NodeCommitRequest, NodeCommitResponse};
 pub use config::{
-    ClientConfig, Config, ConfigurationManagement, OtelConfig, ScannerConfig, ServerConnection,
-    Sherpa, TlsConfig, ZtpServer,
+    AuthConfig, BootServices, ClientConfig, Config, ConfigurationManagement, DatabaseConfig,
+    DatabaseEngine, LdapConfig, OidcConfig, OtelConfig, ScannerConfig, ServerConnection, Sherpa,
+    TlsConfig, ZtpServer,
 };
 pub use container::{ContainerImage, ContainerModel, ContainerNetworkAttachment};
 pub use cpu::{CpuFeature, CpuFeaturePolicy, CpuModels};
-pub use db::{DbBridge, DbLab, DbLink, DbNode, DbUser};
+pub use custom_model::{
+    AddCustomModelRequest, AddCustomModelResponse, CustomModel, CustomModelSummary, CustomZtp,
+    DeleteCustomModelRequest, DeleteCustomModelResponse, InterfaceNaming, ListCustomModelsResponse,
+    parse_custom_model_ref, validate_name as validate_custom_model_name,
+};
+pub use db::{
+    DbApiToken, DbBridge, DbLab, DbLabShare, DbLink, DbNode, DbNodeImageUsage,
+    DbRegistryCredential, DbSchemaMigration, DbTeam, DbUser,
+};
 pub use destroy::{DestroyError, DestroyRequest, DestroyResponse, DestroySummary};
-pub use dhcp::DhcpLease;
+pub use dhcp::{DhcpLease, LabLease, LabLeasesRequest, LabLeasesResponse, LeaseStatus};
 pub use disk::{DiskBuses, DiskDevices, DiskDrivers, DiskFormats, DiskTargets};
 pub use dns::{Dns, NameServer};
 pub use download::DownloadLabResponse;
+pub use filter::{
+    FilterVerdict, FrameMatch, L2Match, L2Protocol, LinkFilter, LinkFilterRequest,
+    LinkFilterResponse,
+};
+pub use image_usage::{
+    ImageUsageResponse, ImageVersionUsage, OrphanedDisk, PruneImagesRequest, PruneImagesResponse,
+    PrunedImage,
+};
 pub use impairment::{UpdateImpairmentRequest, UpdateImpairmentResponse};
 pub use import::{
-    ContainerPullRequest, ContainerPullResponse, DeleteImageRequest, DeleteImageResponse,
-    DownloadImageRequest, ImageSummary, ImportRequest, ImportResponse, ListImagesRequest,
-    ListImagesResponse, ScanImagesRequest, ScanImagesResponse, ScannedImage,
-    SetDefaultImageRequest, SetDefaultImageResponse, ShowImageRequest, ShowImageResponse,
+    CancelUploadRequest, CancelUploadResponse, ContainerPullRequest, ContainerPullResponse,
+    DeleteImageRequest, DeleteImageResponse, DownloadImageRequest, ImageIntegrity, ImageSummary,
+    ImportRequest, ImportResponse, ListImagesRequest, ListImagesResponse, RegistryAuth,
+    ScanImagesRequest, ScanImagesResponse, ScannedImage, SetDefaultImageRequest,
+    SetDefaultImageResponse, ShowImageRequest, ShowImageResponse, StartUploadRequest,
+    UploadChunkRequest, UploadChunkResponse, UploadStatus, VerifyImageRequest, VerifyImageResponse,
 };
 pub use inspect::{BridgeInfo, DeviceInfo, InspectRequest, InspectResponse, LinkInfo};
 pub use interface::{
     AristaCeosInt, AristaVeosInt, ArubaAoscxInt, CiscoAsavInt, CiscoCat8000vInt, CiscoCat9000vInt,
     CiscoCsr1000vInt, CiscoFtdvInt, CiscoIosvInt, CiscoIosvl2Int, CiscoIosxrv9000Int,
-    CiscoNexus9300vInt, ConnectionTypes, CumulusLinuxInt, EthernetInt, Interface, InterfaceTrait,
-    JuniperVevolvedInt, JuniperVrouterInt, JuniperVsrxv3Int, JuniperVswitchInt, MgmtInterfaces,
-    MikrotikChrInt, NokiaSrlinuxInt, PaloaltoPanosInt,
+    CiscoNexus9300vInt, ConnectionTypes, CumulusLinuxInt, EthernetInt, Interface, InterfaceMtu,
+    InterfaceTrait, JuniperVevolvedInt, JuniperVrouterInt, JuniperVsrxv3Int, JuniperVswitchInt,
+    MgmtInterfaces, MikrotikChrInt, NokiaSrlinuxInt, PaloaltoPanosInt,
 };
 pub use lab::{
     BridgeConnection, BridgeInterface, InterfaceData, InterfaceState, LabBridgeData, LabIdentity,
     LabInfo, LabIsolatedNetwork, LabLinkData, LabNodeData, LabReservedNetwork, LabState,
     LabSummary, ListLabsResponse, NodeInterface, NodeSetupData, PeerInterface, PeerSide,
 };
+pub use link_stats::{
+    LinkRates, LinkStats, LinkStatsRequest, LinkStatsResponse, LinkStatsSource, TrafficCounters,
+};
 pub use mapping::{CloneDisk, InterfaceConnection, NodeConnection, NodeDisk, QemuCommand};
+pub use mirror::{LinkMirrorRequest, LinkMirrorResponse, MirrorDirection};
 pub use network::{BridgeKind, NetworkV4, NetworkV6, SherpaNetwork};
 pub use node::{
     BiosTypes, CpuArchitecture, InterfaceType, MachineType, NodeConfig, NodeKind, NodeModel,
@@ -70,6 +117,16 @@ pub use node::{
 pub use provider::VmProviders;
 pub use record_id::{RecordId, RecordIdKey};
 pub use redeploy::{RedeployRequest, RedeployResponse};
+pub use registry::{
+    DEFAULT_REGISTRY, ListRegistriesResponse, RegistryCredentialInfo, RegistryLoginRequest,
+    RegistryLoginResponse, RegistryLogoutRequest, RegistryLogoutResponse, image_registry,
+    normalize_registry,
+};
+pub use share::{
+    CreateTeamRequest, DeleteTeamRequest, DeleteTeamResponse, LabRole, LabShareInfo,
+    ListLabSharesRequest, ListLabSharesResponse, ListTeamsRequest, ListTeamsResponse,
+    ShareLabRequest, TeamInfo, UnshareLabRequest, UpdateTeamMembersRequest, split_grantee,
+};
 pub use ssh::{SshKeyAlgorithms, SshPublicKey};
 pub use up::{
     NodeInfo, StatusKind, StatusMessage, UpError, UpPhase, UpRequest, UpResponse, UpSummary,
@@ -80,6 +137,7 @@ pub use user_management::{
     DeleteUserRequest, DeleteUserResponse, GetUserInfoRequest, GetUserInfoResponse,
     ListUsersRequest, ListUsersResponse, UserInfo,
 };
+pub use vlan::{PortMode, PortVlan, VlanRange};
 pub use vm_action::{LabNodeActionResponse, NodeActionResult};
 pub use ws::ConnectedMsg;
 pub use ztp::ZtpRecord;
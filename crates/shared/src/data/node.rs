@@ -128,6 +128,12 @@ pub enum NodeModel {
     // Unikernels
     UnikraftUnikernel,
     NanosUnikernel,
+
+    // Custom
+    /// User-defined model, resolved from a custom model definition
+    #[value(skip)]
+    #[strum(disabled)]
+    Custom,
 }
 impl fmt::Display for NodeModel {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -223,6 +229,9 @@ impl fmt::Display for NodeModel {
             // Unikernels
             NodeModel::UnikraftUnikernel => write!(f, "unikraft_unikernel"),
             NodeModel::NanosUnikernel => write!(f, "nanos_unikernel"),
+
+            // Custom
+            NodeModel::Custom => write!(f, "custom"),
         }
     }
 }
@@ -325,6 +334,9 @@ impl std::str::FromStr for NodeModel {
             "unikraft_unikernel" => Ok(NodeModel::UnikraftUnikernel),
             "nanos_unikernel" => Ok(NodeModel::NanosUnikernel),
 
+            // Custom
+            "custom" => Ok(NodeModel::Custom),
+
             _ => Err(format!("Unknown node model: {}", s)),
         }
     }
@@ -749,6 +761,9 @@ pub struct NodeConfig {
     pub reserved_interface_count: u8,
     pub default: bool,
     pub boot_mode: Option<UnikernelBootMode>,
+    /// SHA-256 of the stored image file, recorded on import
+    #[serde(default)]
+    pub image_sha256: Option<String>,
 }
 
 impl Default for NodeConfig {
@@ -785,6 +800,7 @@ impl Default for NodeConfig {
             reserved_interface_count: 0,
             default: false,
             boot_mode: None,
+            image_sha256: None,
         }
     }
 }
@@ -884,6 +900,12 @@ impl NodeConfig {
             // Unikernels
             NodeModel::UnikraftUnikernel => NodeConfig::unikraft_unikernel(),
             NodeModel::NanosUnikernel => NodeConfig::nanos_unikernel(),
+
+            // Custom models overlay a generic image, see `CustomModel::apply`
+            NodeModel::Custom => NodeConfig {
+                model: NodeModel::Custom,
+                ..NodeConfig::generic_vm()
+            },
         }
     }
     pub fn arista_veos() -> NodeConfig {
@@ -919,6 +941,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn arista_ceos() -> NodeConfig {
@@ -954,6 +977,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn aruba_aoscx() -> NodeConfig {
@@ -989,6 +1013,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_asav() -> NodeConfig {
@@ -1024,6 +1049,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_csr1000v() -> NodeConfig {
@@ -1059,6 +1085,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_cat8000v() -> NodeConfig {
@@ -1094,6 +1121,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_cat9000v() -> NodeConfig {
@@ -1129,6 +1157,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_iosxrv9000() -> NodeConfig {
@@ -1164,6 +1193,7 @@ impl NodeConfig {
             reserved_interface_count: 2,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_nexus9300v() -> NodeConfig {
@@ -1199,6 +1229,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_iosv() -> NodeConfig {
@@ -1234,6 +1265,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_iosvl2() -> NodeConfig {
@@ -1269,6 +1301,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_ise() -> NodeConfig {
@@ -1304,6 +1337,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cisco_ftdv() -> NodeConfig {
@@ -1339,6 +1373,7 @@ impl NodeConfig {
             reserved_interface_count: 1,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn juniper_vrouter() -> NodeConfig {
@@ -1374,6 +1409,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn juniper_vswitch() -> NodeConfig {
@@ -1409,6 +1445,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn juniper_vevolved() -> NodeConfig {
@@ -1444,6 +1481,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn juniper_vsrxv3() -> NodeConfig {
@@ -1479,6 +1517,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn alma_linux() -> NodeConfig {
@@ -1514,6 +1553,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn rocky_linux() -> NodeConfig {
@@ -1549,6 +1589,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn alpine_linux() -> NodeConfig {
@@ -1584,6 +1625,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn cumulus_linux() -> NodeConfig {
@@ -1619,6 +1661,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn nokia_srlinux() -> NodeConfig {
@@ -1654,6 +1697,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn centos_linux() -> NodeConfig {
@@ -1689,6 +1733,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn devbox_linux() -> NodeConfig {
@@ -1724,6 +1769,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn fedora_linux() -> NodeConfig {
@@ -1759,6 +1805,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn redhat_linux() -> NodeConfig {
@@ -1794,6 +1841,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn suse_linux() -> NodeConfig {
@@ -1829,6 +1877,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn opensuse_linux() -> NodeConfig {
@@ -1864,6 +1913,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn ubuntu_linux() -> NodeConfig {
@@ -1899,6 +1949,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn kali_linux() -> NodeConfig {
@@ -1934,6 +1985,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn sonic_linux() -> NodeConfig {
@@ -1969,6 +2021,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn flatcar_linux() -> NodeConfig {
@@ -2004,6 +2057,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn free_bsd() -> NodeConfig {
@@ -2039,6 +2093,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn open_bsd() -> NodeConfig {
@@ -2074,6 +2129,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn devbox_windows() -> NodeConfig {
@@ -2109,6 +2165,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn windows_server() -> NodeConfig {
@@ -2144,6 +2201,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn jenkins_server() -> NodeConfig {
@@ -2179,6 +2237,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn nautobot_server() -> NodeConfig {
@@ -2214,6 +2273,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn virt_server() -> NodeConfig {
@@ -2249,6 +2309,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn netbox_server() -> NodeConfig {
@@ -2284,6 +2345,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn infrahub_server() -> NodeConfig {
@@ -2319,6 +2381,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn signoz_server() -> NodeConfig {
@@ -2354,6 +2417,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn forgejo_forge() -> NodeConfig {
@@ -2589,6 +2653,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn paloalto_panos() -> NodeConfig {
@@ -2624,6 +2689,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn frr_linux() -> NodeConfig {
@@ -2659,6 +2725,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: None,
+            image_sha256: None,
         }
     }
     pub fn generic_container() -> NodeConfig {
@@ -2709,6 +2776,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: Some(UnikernelBootMode::DirectKernel),
+            image_sha256: None,
             ..Default::default()
         }
     }
@@ -2735,6 +2803,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: Some(UnikernelBootMode::DirectKernel),
+            image_sha256: None,
             ..Default::default()
         }
     }
@@ -2761,6 +2830,7 @@ impl NodeConfig {
             reserved_interface_count: 0,
             default: true,
             boot_mode: Some(UnikernelBootMode::DiskBoot),
+            image_sha256: None,
             ..Default::default()
         }
     }
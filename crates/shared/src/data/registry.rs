@@ -0,0 +1,134 @@
+//! Container registry credential request and response data structures.
+//!
+//! Credentials are stored on the server, keyed by registry host, and picked
+//! automatically when an image is pulled from that host.
+
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Registry that image references without a host are pulled from
+pub const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// Request to store credentials for a registry
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryLoginRequest {
+    /// Registry host, e.g. harbor.example.com or localhost:5000
+    pub registry: String,
+    pub username: String,
+    pub password: String,
+}
+
+/// Response from storing registry credentials
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryLoginResponse {
+    /// Normalized registry host the credentials are stored under
+    pub registry: String,
+    pub username: String,
+    /// Whether existing credentials for the registry were replaced
+    pub replaced: bool,
+}
+
+/// Stored registry credentials, without the password
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryCredentialInfo {
+    pub registry: String,
+    pub username: String,
+    /// When the credentials were first stored (Unix timestamp)
+    pub created_at: i64,
+    /// When the credentials were last replaced (Unix timestamp)
+    pub updated_at: i64,
+}
+
+/// Response listing stored registry credentials
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListRegistriesResponse {
+    /// Stored credentials, ordered by registry
+    pub registries: Vec<RegistryCredentialInfo>,
+}
+
+/// Request to remove the credentials of a registry
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryLogoutRequest {
+    pub registry: String,
+}
+
+/// Response from removing registry credentials
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegistryLogoutResponse {
+    pub registry: String,
+    /// Whether credentials were stored for the registry
+    pub removed: bool,
+}
+
+/// Normalize a registry host so lookups match however it was typed.
+///
+/// Strips a URL scheme and trailing slash, lowercases the host and maps the
+/// Docker Hub aliases to `docker.io`.
+pub fn normalize_registry(registry: &str) -> Result<String> {
+    let host = registry
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_ascii_lowercase();
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ".-:[]".contains(c));
+    if !valid {
+        bail!(
+            "Invalid registry '{}', expected a host such as harbor.example.com or localhost:5000",
+            registry
+        );
+    }
+
+    Ok(match host.as_str() {
+        "index.docker.io" | "registry-1.docker.io" | "registry.hub.docker.com" => {
+            DEFAULT_REGISTRY.to_string()
+        }
+        _ => host,
+    })
+}
+
+/// The registry host of an image reference such as `ghcr.io/nokia/srlinux`.
+///
+/// Like Docker, the first path component is only a host if it contains a
+/// `.` or `:` or is `localhost`, otherwise the image is on Docker Hub.
+pub fn image_registry(image: &str) -> String {
+    match image.split_once('/') {
+        Some((host, _)) if host.contains(['.', ':']) || host == "localhost" => {
+            normalize_registry(host).unwrap_or_else(|_| host.to_string())
+        }
+        _ => DEFAULT_REGISTRY.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_registry() {
+        assert_eq!(
+            normalize_registry("https://Harbor.Example.com/").unwrap(),
+            "harbor.example.com"
+        );
+        assert_eq!(
+            normalize_registry("localhost:5000").unwrap(),
+            "localhost:5000"
+        );
+        assert_eq!(normalize_registry("index.docker.io").unwrap(), "docker.io");
+        assert!(normalize_registry("").is_err());
+        assert!(normalize_registry("harbor.example.com/project").is_err());
+    }
+
+    #[test]
+    fn test_image_registry() {
+        assert_eq!(image_registry("ghcr.io/nokia/srlinux"), "ghcr.io");
+        assert_eq!(image_registry("localhost:5000/frr"), "localhost:5000");
+        assert_eq!(image_registry("localhost/frr"), "localhost");
+        assert_eq!(image_registry("frrouting/frr"), "docker.io");
+        assert_eq!(image_registry("alpine"), "docker.io");
+    }
+}
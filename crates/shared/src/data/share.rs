@@ -0,0 +1,291 @@
+//! Lab sharing and team request and response data structures.
+//!
+//! A lab is owned by a single user and can be shared with other users or
+//! with teams. Each share grants a [`LabRole`] to the grantee.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Level of access a user has to a lab.
+///
+/// Roles are ordered, a higher role includes every permission of the lower ones.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    EnumIter,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LabRole {
+    /// Inspect the lab and connect to node consoles
+    Viewer,
+    /// Viewer access plus down, resume, redeploy and link impairment
+    Operator,
+    /// Full control of the lab, including destroy and sharing
+    #[value(skip)]
+    Owner,
+}
+
+impl LabRole {
+    /// Roles that can be granted through a share (used by DB schema generation).
+    pub fn shareable() -> Vec<LabRole> {
+        LabRole::iter()
+            .filter(|role| *role != LabRole::Owner)
+            .collect()
+    }
+}
+
+impl fmt::Display for LabRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabRole::Viewer => write!(f, "viewer"),
+            LabRole::Operator => write!(f, "operator"),
+            LabRole::Owner => write!(f, "owner"),
+        }
+    }
+}
+
+impl FromStr for LabRole {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "viewer" => Ok(LabRole::Viewer),
+            "operator" => Ok(LabRole::Operator),
+            "owner" => Ok(LabRole::Owner),
+            _ => Err(anyhow!("Unknown lab role: {}", s)),
+        }
+    }
+}
+
+/// A single lab share, granted to either a user or a team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LabShareInfo {
+    /// Username the lab is shared with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Team the lab is shared with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Role granted by the share
+    pub role: LabRole,
+}
+
+impl LabShareInfo {
+    /// Display name of the grantee, teams are prefixed with `team:`.
+    pub fn grantee(&self) -> String {
+        match (&self.username, &self.team) {
+            (Some(username), _) => username.clone(),
+            (None, Some(team)) => format!("team:{}", team),
+            (None, None) => "-".to_string(),
+        }
+    }
+}
+
+/// Split a grantee display name into a username or a team name.
+///
+/// The inverse of [`LabShareInfo::grantee`]: `team:<name>` is a team,
+/// anything else a username.
+pub fn split_grantee(grantee: &str) -> (Option<String>, Option<String>) {
+    match grantee.strip_prefix("team:") {
+        Some(team) => (None, Some(team.to_string())),
+        None => (Some(grantee.to_string()), None),
+    }
+}
+
+/// Request to share a lab with a user or a team (lab owner or admin only)
+///
+/// Exactly one of `username` or `team` must be set. Sharing with a grantee
+/// that already has a share replaces its role.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShareLabRequest {
+    /// Lab ID to share
+    pub lab_id: String,
+    /// Username to share the lab with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Team to share the lab with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Role to grant
+    pub role: LabRole,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Request to remove a lab share (lab owner or admin only)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UnshareLabRequest {
+    /// Lab ID to remove the share from
+    pub lab_id: String,
+    /// Username to remove
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Team to remove
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Request to list the shares of a lab
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListLabSharesRequest {
+    /// Lab ID to list shares for
+    pub lab_id: String,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response with the shares of a lab
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListLabSharesResponse {
+    /// Lab ID
+    pub lab_id: String,
+    /// Username of the lab owner
+    pub owner: String,
+    /// Shares of the lab
+    pub shares: Vec<LabShareInfo>,
+}
+
+/// Team information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TeamInfo {
+    /// Team name
+    pub name: String,
+    /// Username of the team owner
+    pub owner: String,
+    /// Usernames of the team members
+    pub members: Vec<String>,
+}
+
+/// Request to create a team
+///
+/// The caller becomes the owner of the team.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateTeamRequest {
+    /// Team name (min 3 chars, alphanumeric + ._-)
+    pub name: String,
+    /// Initial team members
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Request to list all teams
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListTeamsRequest {
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response with list of teams
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListTeamsResponse {
+    /// List of teams
+    pub teams: Vec<TeamInfo>,
+}
+
+/// Request to add or remove team members (team owner or admin only)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateTeamMembersRequest {
+    /// Team name
+    pub name: String,
+    /// Usernames to add to the team
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Usernames to remove from the team
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Request to delete a team (team owner or admin only)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteTeamRequest {
+    /// Team name
+    pub name: String,
+    /// Caller's authentication token
+    pub token: String,
+}
+
+/// Response after deleting a team
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteTeamResponse {
+    /// Whether the deletion was successful
+    pub success: bool,
+    /// Name of the deleted team
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lab_role_ordering() {
+        assert!(LabRole::Viewer < LabRole::Operator);
+        assert!(LabRole::Operator < LabRole::Owner);
+    }
+
+    #[test]
+    fn test_lab_role_shareable_excludes_owner() {
+        assert_eq!(
+            LabRole::shareable(),
+            vec![LabRole::Viewer, LabRole::Operator]
+        );
+    }
+
+    #[test]
+    fn test_lab_role_display_roundtrip() {
+        for role in LabRole::iter() {
+            assert_eq!(role.to_string().parse::<LabRole>().unwrap(), role);
+            let json = serde_json::to_string(&role).unwrap();
+            assert_eq!(json, format!("\"{}\"", role));
+        }
+        assert!("admin".parse::<LabRole>().is_err());
+    }
+
+    #[test]
+    fn test_lab_share_info_grantee() {
+        let user_share = LabShareInfo {
+            username: Some("alice".to_string()),
+            team: None,
+            role: LabRole::Viewer,
+        };
+        let team_share = LabShareInfo {
+            username: None,
+            team: Some("students".to_string()),
+            role: LabRole::Operator,
+        };
+        assert_eq!(user_share.grantee(), "alice");
+        assert_eq!(team_share.grantee(), "team:students");
+    }
+
+    #[test]
+    fn test_split_grantee() {
+        assert_eq!(split_grantee("alice"), (Some("alice".to_string()), None));
+        assert_eq!(
+            split_grantee("team:students"),
+            (None, Some("students".to_string()))
+        );
+    }
+}
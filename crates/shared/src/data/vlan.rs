@@ -0,0 +1,180 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+/// Lowest and highest usable VLAN IDs, 0 and 4095 are reserved
+const VLAN_ID_MIN: u16 = 1;
+const VLAN_ID_MAX: u16 = 4094;
+
+/// VLAN mode of a shared bridge port
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortMode {
+    /// Untagged member of one VLAN
+    Access,
+    /// Tagged member of a list of VLANs, with an optional untagged native VLAN
+    Trunk,
+}
+
+impl fmt::Display for PortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortMode::Access => write!(f, "access"),
+            PortMode::Trunk => write!(f, "trunk"),
+        }
+    }
+}
+
+/// A VLAN ID or an inclusive range of VLAN IDs.
+///
+/// Written as a number (`10`) or a string (`"10"`, `"100-199"`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "VlanRangeRepr", into = "VlanRangeRepr")]
+pub struct VlanRange {
+    pub first: u16,
+    pub last: u16,
+}
+
+impl VlanRange {
+    pub fn single(vlan: u16) -> Self {
+        Self {
+            first: vlan,
+            last: vlan,
+        }
+    }
+
+    pub fn contains(&self, vlan: u16) -> bool {
+        (self.first..=self.last).contains(&vlan)
+    }
+
+    /// Whether every ID of the range is a usable VLAN ID
+    pub fn is_valid(&self) -> bool {
+        (VLAN_ID_MIN..=VLAN_ID_MAX).contains(&self.first)
+            && (VLAN_ID_MIN..=VLAN_ID_MAX).contains(&self.last)
+    }
+}
+
+impl fmt::Display for VlanRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.first == self.last {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+impl FromStr for VlanRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid VLAN range '{s}', expected <id> or <first>-<last>");
+        let parse = |id: &str| id.trim().parse::<u16>().map_err(|_| invalid());
+        let range = match s.split_once('-') {
+            Some((first, last)) => Self {
+                first: parse(first)?,
+                last: parse(last)?,
+            },
+            None => Self::single(parse(s)?),
+        };
+        if range.first > range.last {
+            bail!("Invalid VLAN range '{s}', the first ID is above the last");
+        }
+        Ok(range)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum VlanRangeRepr {
+    Id(u16),
+    Range(String),
+}
+
+impl TryFrom<VlanRangeRepr> for VlanRange {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VlanRangeRepr) -> Result<Self> {
+        match value {
+            VlanRangeRepr::Id(vlan) => Ok(Self::single(vlan)),
+            VlanRangeRepr::Range(range) => range.parse(),
+        }
+    }
+}
+
+impl From<VlanRange> for VlanRangeRepr {
+    fn from(value: VlanRange) -> Self {
+        if value.first == value.last {
+            VlanRangeRepr::Id(value.first)
+        } else {
+            VlanRangeRepr::Range(value.to_string())
+        }
+    }
+}
+
+/// VLAN membership of a bridge port, as programmed into the kernel bridge
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortVlan {
+    pub vlans: VlanRange,
+    /// Untagged frames are classified into this VLAN and it egresses
+    /// untagged. Only set on a single VLAN.
+    pub native: bool,
+}
+
+impl fmt::Display for PortVlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.native {
+            write!(f, "{} native", self.vlans)
+        } else {
+            write!(f, "{}", self.vlans)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct Wrapper {
+        vlans: Vec<VlanRange>,
+    }
+
+    #[test]
+    fn test_vlan_range_serde() {
+        let wrapper: Wrapper = toml::from_str(r#"vlans = [10, "20", "100-199"]"#).unwrap();
+        assert_eq!(
+            wrapper.vlans,
+            vec![
+                VlanRange::single(10),
+                VlanRange::single(20),
+                VlanRange {
+                    first: 100,
+                    last: 199
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&wrapper).unwrap(),
+            r#"{"vlans":[10,20,"100-199"]}"#
+        );
+    }
+
+    #[test]
+    fn test_vlan_range_parse_errors() {
+        assert!("200-100".parse::<VlanRange>().is_err());
+        assert!("ten".parse::<VlanRange>().is_err());
+        assert!("10-".parse::<VlanRange>().is_err());
+        assert!(toml::from_str::<Wrapper>(r#"vlans = ["1-2-3"]"#).is_err());
+    }
+
+    #[test]
+    fn test_vlan_range_is_valid() {
+        assert!(VlanRange::single(1).is_valid());
+        assert!("1-4094".parse::<VlanRange>().unwrap().is_valid());
+        assert!(!VlanRange::single(0).is_valid());
+        assert!(!"4000-4095".parse::<VlanRange>().unwrap().is_valid());
+    }
+}
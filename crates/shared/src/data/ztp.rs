@@ -1,8 +1,10 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
 
+use serde::{Deserialize, Serialize};
+
 use super::ZtpMethod;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ZtpRecord {
     pub node_name: String,
     pub config_file: String,
@@ -37,6 +37,8 @@ pub const SHERPA_CONFIG_PATH: &str = "/opt/sherpa/config";
 pub const SHERPA_ENV_PATH: &str = "/opt/sherpa/env";
 pub const SHERPA_SSH_PATH: &str = "/opt/sherpa/ssh";
 pub const SHERPA_IMAGES_PATH: &str = "/opt/sherpa/images";
+pub const SHERPA_IMAGES_IMPORT_PATH: &str = "/opt/sherpa/images/.import";
+pub const SHERPA_IMAGES_UPLOAD_PATH: &str = "/opt/sherpa/images/.uploads";
 pub const SHERPA_CONTAINERS_PATH: &str = "/opt/sherpa/containers";
 pub const SHERPA_BINS_PATH: &str = "/opt/sherpa/bins";
 pub const SHERPA_LABS_PATH: &str = "/opt/sherpa/labs";
@@ -44,6 +46,7 @@ pub const SHERPA_RUN_PATH: &str = "/opt/sherpa/run";
 pub const SHERPA_LOG_PATH: &str = "/opt/sherpa/logs";
 pub const SHERPA_CERTS_PATH: &str = "/opt/sherpa/.certs";
 pub const SHERPA_BLANK_DISK_PATH: &str = "/opt/sherpa/images/blank_disk";
+pub const SHERPA_MODELS_PATH: &str = "/opt/sherpa/models";
 pub const WEB_ASSETS_DIR: &str = "web/static";
 // ============================================================================
 // Full file paths
@@ -88,12 +91,25 @@ pub const BRIDGE_PREFIX: &str = "br";
 pub const VETH_PREFIX: &str = "ve";
 pub const TAP_PREFIX: &str = "tp";
 pub const CONTAINER_VETH_PREFIX: &str = "cv";
+// Reserved node name for the host side of external links, e.g. `host::enp5s0.120`
+pub const EXTERNAL_HOST_NODE: &str = "host";
+// Mirror ports an interface can be copied to, matches the slots of the eBPF mirror map
+pub const MIRROR_MAX_PER_INTERFACE: u32 = 4;
+// Manifest name prefix of the single-member bridges of mirror ports
+pub const MIRROR_PORT_PREFIX: &str = "mirror";
+// Rules a link filter expands to, matches the slots of the eBPF filter map
+pub const FILTER_MAX_RULES: u32 = 16;
+// Seconds a cabling check listens by default, LLDP advertises every 30 seconds
+pub const CABLING_CAPTURE_SECS: u64 = 35;
+// Longest cabling capture, CDP advertises every 60 seconds
+pub const CABLING_CAPTURE_MAX_SECS: u64 = 180;
 
 pub const SHERPA_DB_NAME: &str = "sherpa";
 pub const SHERPA_DB_NAMESPACE: &str = "sherpa";
 pub const SHERPA_DB_USER: &str = "sherpa";
 pub const SHERPA_DB_SERVER: &str = "localhost";
 pub const SHERPA_DB_PORT: u16 = 8000;
+pub const SHERPA_DB_PATH: &str = "/opt/sherpa/db";
 pub const SHERPA_SERVER_WS_PORT: u16 = 3030;
 pub const SHERPA_SERVER_HTTP_PORT: u16 = 3031;
 pub const SHERPA_SERVER_IPV4: &str = "0.0.0.0";
@@ -113,6 +129,9 @@ pub const TELNET_PORT: u16 = 2323;
 pub const BASE_PORT: u16 = 10000;
 pub const HTTP_PORT: u16 = 8080;
 pub const TFTP_PORT: u16 = 69;
+pub const DHCP_SERVER_PORT: u16 = 67;
+pub const DHCP_CLIENT_PORT: u16 = 68;
+pub const DHCPV6_SERVER_PORT: u16 = 547;
 
 pub const KVM_OUI: &str = "52:54:00";
 pub const ARISTA_OUI: &str = "02:01:00";
@@ -134,6 +153,8 @@ pub const _USER_SSH_DIR: &str = "~/.ssh";
 pub const _USER_SSH_PUBLIC_KEY_FILE: &str = "id_rsa.pub";
 pub const TEMP_DIR: &str = ".tmp";
 
+/// Smallest MTU a manifest can set, the IPv6 minimum link MTU
+pub const MTU_MIN: u16 = 1280;
 pub const MTU_STD: u16 = 1500;
 pub const MTU_JUMBO_INT: u16 = 9216;
 pub const MTU_JUMBO_NET: u16 = 9600;
@@ -146,6 +167,9 @@ pub const NODE_CONFIGS_DIR: &str = "configs";
 pub const DNSMASQ_DIR: &str = "dnsmasq";
 pub const DNSMASQ_CONFIG_FILE: &str = "dnsmasq.conf";
 pub const DNSMASQ_LEASES_FILE: &str = "dnsmasq.leases";
+pub const BOOT_SERVICES_DIR: &str = "boot";
+pub const BOOT_SERVICES_CONFIG_FILE: &str = "boot.json";
+pub const BOOT_SERVICES_LEASES_FILE: &str = "leases.json";
 pub const CISCO_ZTP_DIR: &str = "cisco";
 pub const CISCO_IOSXE_ZTP_CONFIG: &str = "iosxe_config.txt";
 pub const CISCO_IOSV_ZTP_CONFIG: &str = "ios_config.txt";
@@ -291,6 +315,9 @@ pub const OTEL_METRIC_WS_CONNECTIONS: &str = "sherpad.ws.connections";
 pub const OTEL_METRIC_RPC_DURATION: &str = "sherpad.rpc.duration";
 pub const OTEL_METRIC_OPERATION_DURATION: &str = "sherpad.operation.duration";
 pub const OTEL_METRIC_ERROR_COUNT: &str = "sherpad.errors";
+pub const OTEL_METRIC_LINK_PACKETS: &str = "sherpad.link.packets";
+pub const OTEL_METRIC_LINK_BYTES: &str = "sherpad.link.bytes";
+pub const OTEL_METRIC_LINK_DROPS: &str = "sherpad.link.drops";
 pub const OTEL_METRIC_EXPORT_INTERVAL_SECS: u64 = 60;
 
 // Sherpad daemon constants
@@ -303,6 +330,32 @@ pub const SHERPAD_PORT: u16 = 3030;
 pub const JWT_SECRET_PATH: &str = "/opt/sherpa/.secret/jwt.secret";
 pub const JWT_TOKEN_EXPIRY_SECONDS: i64 = 604_800; // 7 days
 
+// Registry credential constants
+pub const REGISTRY_KEY_PATH: &str = "/opt/sherpa/.secret/registry.key";
+
+// Personal API token constants
+pub const API_TOKEN_PREFIX: &str = "sherpa_pat_";
+pub const API_TOKEN_DEFAULT_EXPIRY_DAYS: u32 = 90;
+pub const API_TOKEN_MAX_EXPIRY_DAYS: u32 = 365;
+
+// External identity provider defaults
+pub const LDAP_DEFAULT_USER_FILTER: &str = "(uid={username})";
+pub const LDAP_DEFAULT_GROUP_ATTRIBUTE: &str = "memberOf";
+pub const LDAP_DEFAULT_TIMEOUT_SECS: u64 = 10;
+pub const OIDC_DEFAULT_SCOPES: &[&str] = &["openid", "profile", "email"];
+pub const OIDC_DEFAULT_USERNAME_CLAIM: &str = "preferred_username";
+pub const OIDC_DEFAULT_GROUPS_CLAIM: &str = "groups";
+pub const OIDC_LOGIN_STATE_EXPIRY_SECONDS: i64 = 600; // 10 minutes
+
+// Chunked image upload
+pub const IMAGE_UPLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024; // 4 MiB
+pub const IMAGE_UPLOAD_MAX_CHUNK_SIZE: u64 = 16 * 1024 * 1024; // 16 MiB
+pub const IMAGE_UPLOAD_CHUNK_SHA256_HEADER: &str = "x-chunk-sha256";
+
+// Custom node models
+pub const CUSTOM_MODEL_PREFIX: &str = "custom:";
+pub const CUSTOM_MODEL_NAME_MAX_LEN: usize = 32;
+
 // TLS certificate paths
 pub const SHERPA_SERVER_CERT_FILE: &str = "server.crt";
 pub const SHERPA_SERVER_KEY_FILE: &str = "server.key";
@@ -322,6 +375,11 @@ pub const RPC_MSG_AUTH_REQUIRED: &str = "Authentication required";
 pub const RPC_MSG_AUTH_INVALID: &str = "Invalid username or password";
 pub const RPC_MSG_AUTH_ERROR: &str = "Authentication error";
 pub const RPC_MSG_TOKEN_CREATE_FAILED: &str = "Failed to create authentication token";
+pub const RPC_MSG_OIDC_NOT_CONFIGURED: &str = "Single sign-on is not configured on this server";
+pub const RPC_MSG_OIDC_DEVICE_START_FAILED: &str = "Failed to start single sign-on device login";
+pub const RPC_MSG_OIDC_DEVICE_POLL_FAILED: &str = "Single sign-on device login failed";
+pub const RPC_MSG_INVALID_PARAMS_DEVICE_POLL: &str =
+    "Invalid params: expected {device_code: string}";
 
 // Authorization messages
 pub const RPC_MSG_ACCESS_DENIED_LAB: &str =
@@ -350,8 +408,37 @@ pub const RPC_MSG_USER_DELETE_SAFETY_CHECK_FAILED: &str = "Failed to verify user
 pub const RPC_MSG_USER_PASSWORD_UPDATE_FAILED: &str = "Failed to update password";
 pub const RPC_MSG_PASSWORD_VALIDATION_FAILED: &str = "Password validation failed";
 
+// Lab sharing and teams
+pub const RPC_MSG_ACCESS_DENIED_LAB_SHARE: &str =
+    "Access denied: only the lab owner or an administrator can manage lab shares";
+pub const RPC_MSG_ACCESS_DENIED_TEAM: &str =
+    "Access denied: only the team owner or an administrator can modify this team";
+pub const RPC_MSG_INVALID_PARAMS_SHARE_LAB: &str = "Invalid params: expected ShareLabRequest";
+pub const RPC_MSG_INVALID_PARAMS_UNSHARE_LAB: &str = "Invalid params: expected UnshareLabRequest";
+pub const RPC_MSG_INVALID_PARAMS_CREATE_TEAM: &str = "Invalid params: expected CreateTeamRequest";
+pub const RPC_MSG_INVALID_PARAMS_UPDATE_TEAM: &str =
+    "Invalid params: expected UpdateTeamMembersRequest";
+pub const RPC_MSG_INVALID_PARAMS_DELETE_TEAM: &str = "Invalid params: expected DeleteTeamRequest";
+pub const RPC_MSG_LAB_SHARE_FAILED: &str = "Lab share operation failed";
+pub const RPC_MSG_TEAM_CREATE_FAILED: &str = "Failed to create team";
+pub const RPC_MSG_TEAM_LIST_FAILED: &str = "Failed to list teams";
+pub const RPC_MSG_TEAM_UPDATE_FAILED: &str = "Failed to update team";
+pub const RPC_MSG_TEAM_DELETE_FAILED: &str = "Failed to delete team";
+
+// Personal API tokens
+pub const RPC_MSG_ACCESS_DENIED_TOKEN_SCOPE: &str =
+    "Access denied: the API token does not have the scope required for this operation";
+pub const RPC_MSG_INVALID_PARAMS_CREATE_API_TOKEN: &str =
+    "Invalid params: expected CreateApiTokenRequest";
+pub const RPC_MSG_INVALID_PARAMS_REVOKE_API_TOKEN: &str =
+    "Invalid params: expected RevokeApiTokenRequest";
+pub const RPC_MSG_API_TOKEN_CREATE_FAILED: &str = "Failed to create API token";
+pub const RPC_MSG_API_TOKEN_LIST_FAILED: &str = "Failed to list API tokens";
+pub const RPC_MSG_API_TOKEN_REVOKE_FAILED: &str = "Failed to revoke API token";
+
 // Lab operations
 pub const RPC_MSG_LAB_INSPECT_FAILED: &str = "Inspect operation failed";
+pub const RPC_MSG_LAB_LEASES_FAILED: &str = "Failed to list lab leases";
 pub const RPC_MSG_LAB_DESTROY_FAILED: &str = "Destroy operation failed";
 pub const RPC_MSG_LAB_CLEAN_FAILED: &str = "Clean operation failed";
 pub const RPC_MSG_LAB_UP_FAILED: &str = "Up operation failed";
@@ -381,6 +468,39 @@ pub const RPC_MSG_ADMIN_ONLY_IMAGE_SET_DEFAULT: &str =
     "Access denied: only administrators can set the default image";
 pub const RPC_MSG_INVALID_PARAMS_IMAGE_SET_DEFAULT: &str =
     "Invalid params: expected SetDefaultImageRequest";
+pub const RPC_MSG_IMAGE_VERIFY_FAILED: &str = "Image verify operation failed";
+pub const RPC_MSG_ADMIN_ONLY_IMAGE_VERIFY: &str =
+    "Access denied: only administrators can verify images";
+pub const RPC_MSG_INVALID_PARAMS_IMAGE_VERIFY: &str = "Invalid params: expected VerifyImageRequest";
+pub const RPC_MSG_IMAGE_UPLOAD_FAILED: &str = "Image upload operation failed";
+pub const RPC_MSG_ADMIN_ONLY_IMAGE_UPLOAD: &str =
+    "Access denied: only administrators can upload images";
+pub const RPC_MSG_INVALID_PARAMS_UPLOAD_START: &str = "Invalid params: expected StartUploadRequest";
+pub const RPC_MSG_INVALID_PARAMS_UPLOAD_CHUNK: &str = "Invalid params: expected UploadChunkRequest";
+pub const RPC_MSG_INVALID_PARAMS_UPLOAD_CANCEL: &str =
+    "Invalid params: expected CancelUploadRequest";
+pub const RPC_MSG_IMAGE_MODEL_FAILED: &str = "Custom model operation failed";
+pub const RPC_MSG_ADMIN_ONLY_IMAGE_MODEL: &str =
+    "Access denied: only administrators can manage custom models";
+pub const RPC_MSG_INVALID_PARAMS_MODEL_ADD: &str = "Invalid params: expected AddCustomModelRequest";
+pub const RPC_MSG_INVALID_PARAMS_MODEL_DELETE: &str =
+    "Invalid params: expected DeleteCustomModelRequest";
+pub const RPC_MSG_IMAGE_USAGE_FAILED: &str = "Image usage operation failed";
+pub const RPC_MSG_ADMIN_ONLY_IMAGE_USAGE: &str =
+    "Access denied: only administrators can view image usage";
+pub const RPC_MSG_IMAGE_PRUNE_FAILED: &str = "Image prune operation failed";
+pub const RPC_MSG_ADMIN_ONLY_IMAGE_PRUNE: &str =
+    "Access denied: only administrators can prune images";
+pub const RPC_MSG_INVALID_PARAMS_IMAGE_PRUNE: &str = "Invalid params: expected PruneImagesRequest";
+pub const RPC_MSG_REGISTRY_LOGIN_FAILED: &str = "Registry login failed";
+pub const RPC_MSG_REGISTRY_LIST_FAILED: &str = "Failed to list registries";
+pub const RPC_MSG_REGISTRY_LOGOUT_FAILED: &str = "Registry logout failed";
+pub const RPC_MSG_ADMIN_ONLY_REGISTRY: &str =
+    "Access denied: only administrators can manage registry credentials";
+pub const RPC_MSG_INVALID_PARAMS_REGISTRY_LOGIN: &str =
+    "Invalid params: expected RegistryLoginRequest";
+pub const RPC_MSG_INVALID_PARAMS_REGISTRY_LOGOUT: &str =
+    "Invalid params: expected RegistryLogoutRequest";
 
 // Serialization errors
 pub const RPC_MSG_SERIALIZE_FAILED: &str = "Failed to serialize response";
@@ -406,12 +526,26 @@ pub const RPC_MSG_INVALID_PARAMS_NODE_NAME: &str = "Invalid 'node_name' paramete
 pub const RPC_MSG_IMPAIRMENT_UPDATE_FAILED: &str = "Link impairment update failed";
 pub const RPC_MSG_INVALID_PARAMS_IMPAIRMENT: &str =
     "Invalid params: expected lab_id, link_index, and token";
+pub const RPC_MSG_LINK_STATS_FAILED: &str = "Failed to read link stats";
+pub const RPC_MSG_LINK_MIRROR_FAILED: &str = "Failed to update port mirror";
+pub const RPC_MSG_INVALID_PARAMS_LINK_MIRROR: &str = "Invalid params: expected LinkMirrorRequest";
+pub const RPC_MSG_LINK_FILTER_FAILED: &str = "Failed to update link filter";
+pub const RPC_MSG_INVALID_PARAMS_LINK_FILTER: &str = "Invalid params: expected LinkFilterRequest";
+pub const RPC_MSG_VERIFY_CABLING_FAILED: &str = "Cabling verification failed";
+pub const RPC_MSG_INVALID_PARAMS_VERIFY_CABLING: &str =
+    "Invalid params: expected VerifyCablingRequest";
 
 // Redeploy operations
 pub const RPC_MSG_REDEPLOY_FAILED: &str = "Redeploy operation failed";
 pub const RPC_MSG_INVALID_PARAMS_REDEPLOY: &str =
     "Invalid params: expected lab_id, node_name, manifest, and token";
 
+// Node commit operations
+pub const RPC_MSG_NODE_COMMIT_FAILED: &str = "Node commit operation failed";
+pub const RPC_MSG_ADMIN_ONLY_NODE_COMMIT: &str =
+    "Access denied: only administrators can commit nodes to images";
+pub const RPC_MSG_INVALID_PARAMS_NODE_COMMIT: &str = "Invalid params: expected NodeCommitRequest";
+
 // Invalid params messages
 pub const RPC_MSG_INVALID_PARAMS_LAB_ID: &str = "Invalid params: 'lab_id' (string) is required";
 pub const RPC_MSG_INVALID_PARAMS_MANIFEST: &str = "Invalid params: 'manifest' (object) is required";
@@ -0,0 +1,262 @@
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+
+/// Read buffer size used when hashing files
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Digest algorithms accepted for image verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    /// Length of the hex encoded digest.
+    fn hex_len(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sha256 => 64,
+            ChecksumAlgorithm::Md5 => 32,
+        }
+    }
+
+    /// Parse an algorithm name as written by vendors (`SHA256`, `sha-256`, `MD5`).
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('-', "").as_str() {
+            "sha256" => Some(ChecksumAlgorithm::Sha256),
+            "md5" => Some(ChecksumAlgorithm::Md5),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChecksumAlgorithm::Sha256 => write!(f, "sha256"),
+            ChecksumAlgorithm::Md5 => write!(f, "md5"),
+        }
+    }
+}
+
+/// An expected file digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    pub algorithm: ChecksumAlgorithm,
+    /// Lowercase hex digest
+    pub digest: String,
+}
+
+impl Checksum {
+    fn new(algorithm: ChecksumAlgorithm, digest: &str) -> Result<Self> {
+        let digest = digest.trim().to_ascii_lowercase();
+        if digest.len() != algorithm.hex_len() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("Invalid {} digest: '{}'", algorithm, digest);
+        }
+        Ok(Self { algorithm, digest })
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest)
+    }
+}
+
+impl FromStr for Checksum {
+    type Err = anyhow::Error;
+
+    /// Parse `sha256:<hex>`, `md5:<hex>` or a bare hex digest, where the
+    /// algorithm is inferred from the digest length.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some((name, digest)) = s.split_once(':') {
+            let algorithm = ChecksumAlgorithm::from_name(name)
+                .with_context(|| format!("Unsupported checksum algorithm: '{}'", name))?;
+            return Checksum::new(algorithm, digest);
+        }
+        match s.len() {
+            64 => Checksum::new(ChecksumAlgorithm::Sha256, s),
+            32 => Checksum::new(ChecksumAlgorithm::Md5, s),
+            _ => bail!(
+                "Checksum must be a SHA-256 or MD5 hex digest, optionally prefixed with 'sha256:' or 'md5:'"
+            ),
+        }
+    }
+}
+
+/// Calculate the hex digest of a file.
+pub fn file_digest(path: &str, algorithm: ChecksumAlgorithm) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut sha256 = Sha256::new();
+    let mut md5 = md5::Context::new();
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read {}", path))?;
+        if read == 0 {
+            break;
+        }
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => sha256.update(&buffer[..read]),
+            ChecksumAlgorithm::Md5 => md5.consume(&buffer[..read]),
+        }
+    }
+
+    Ok(match algorithm {
+        ChecksumAlgorithm::Sha256 => hex::encode(sha256.finalize()),
+        ChecksumAlgorithm::Md5 => format!("{:x}", md5.compute()),
+    })
+}
+
+/// Calculate the SHA-256 hex digest of a file.
+pub fn file_sha256(path: &str) -> Result<String> {
+    file_digest(path, ChecksumAlgorithm::Sha256)
+}
+
+/// Verify a file against an expected checksum.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or the digest doesn't match
+pub fn verify_file_checksum(path: &str, expected: &Checksum) -> Result<()> {
+    let actual = file_digest(path, expected.algorithm)?;
+    if actual != expected.digest {
+        bail!(
+            "Checksum mismatch for {}: expected {}, got {}:{}",
+            path,
+            expected,
+            expected.algorithm,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Find the checksum of a file in a checksum listing.
+///
+/// Understands the formats vendors ship alongside images:
+/// - `sha256sum`/`md5sum` output: `<hex>  <filename>` (a `*` before the
+///   filename marks binary mode)
+/// - BSD style and OVF manifests: `SHA256(<filename>)= <hex>`
+/// - A single bare digest, which applies to any file
+///
+/// Lines for other algorithms (e.g. SHA1 in older OVF manifests) are ignored.
+pub fn parse_checksum_listing(contents: &str, filename: &str) -> Option<Checksum> {
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    for line in &lines {
+        // BSD / OVF manifest style
+        if let Some((head, digest)) = line.split_once('=')
+            && let Some((name, file)) = head.trim().split_once('(')
+            && file.trim_end().trim_end_matches(')').trim() == filename
+        {
+            if let Some(algorithm) = ChecksumAlgorithm::from_name(name.trim()) {
+                return Checksum::new(algorithm, digest).ok();
+            }
+            continue;
+        }
+
+        // GNU coreutils style
+        if let Some((digest, file)) = line.split_once(char::is_whitespace) {
+            let file = file.trim().trim_start_matches('*');
+            let file = file.rsplit('/').next().unwrap_or(file);
+            if file == filename {
+                return digest.parse().ok();
+            }
+        }
+    }
+
+    match lines.as_slice() {
+        [digest] if !digest.contains(char::is_whitespace) => digest.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    const HELLO_MD5: &str = "5d41402abc4b2a76b9719d911017c592";
+
+    #[test]
+    fn test_parse_checksum_with_prefix_and_bare() {
+        let checksum: Checksum = format!("sha256:{}", HELLO_SHA256).parse().unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Sha256);
+
+        let checksum: Checksum = HELLO_MD5.to_uppercase().parse().unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Md5);
+        assert_eq!(checksum.digest, HELLO_MD5);
+
+        assert!("sha1:abc".parse::<Checksum>().is_err());
+        assert!("sha256:abc".parse::<Checksum>().is_err());
+        assert!("not-a-digest".parse::<Checksum>().is_err());
+    }
+
+    #[test]
+    fn test_file_digest_and_verify() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hello.img");
+        fs::write(&path, "hello").unwrap();
+        let path = path.to_string_lossy();
+
+        assert_eq!(file_sha256(&path).unwrap(), HELLO_SHA256);
+        assert_eq!(
+            file_digest(&path, ChecksumAlgorithm::Md5).unwrap(),
+            HELLO_MD5
+        );
+
+        assert!(verify_file_checksum(&path, &HELLO_MD5.parse().unwrap()).is_ok());
+        let wrong = Checksum::new(ChecksumAlgorithm::Md5, &"0".repeat(32)).unwrap();
+        let err = verify_file_checksum(&path, &wrong).unwrap_err().to_string();
+        assert!(err.contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_parse_checksum_listing_formats() {
+        let gnu = format!("{}  other.vmdk\n{} *disk1.vmdk\n", HELLO_MD5, HELLO_SHA256);
+        assert_eq!(
+            parse_checksum_listing(&gnu, "disk1.vmdk").unwrap().digest,
+            HELLO_SHA256
+        );
+
+        let ovf = format!(
+            "SHA1(disk1.vmdk)= {}\nSHA256(disk1.vmdk)= {}\n",
+            "a".repeat(40),
+            HELLO_SHA256
+        );
+        let checksum = parse_checksum_listing(&ovf, "disk1.vmdk").unwrap();
+        assert_eq!(checksum.algorithm, ChecksumAlgorithm::Sha256);
+
+        let bsd = format!("MD5 (disk1.vmdk) = {}\n", HELLO_MD5);
+        assert_eq!(
+            parse_checksum_listing(&bsd, "disk1.vmdk")
+                .unwrap()
+                .algorithm,
+            ChecksumAlgorithm::Md5
+        );
+
+        assert_eq!(
+            parse_checksum_listing(&format!("{}\n", HELLO_SHA256), "any.qcow2")
+                .unwrap()
+                .digest,
+            HELLO_SHA256
+        );
+        assert!(parse_checksum_listing(&gnu, "missing.vmdk").is_none());
+    }
+}
@@ -8,8 +8,8 @@ use ipnet::Ipv4Net;
 
 use super::file_system::create_file;
 use crate::data::{
-    ClientConfig, Config, ConfigurationManagement, ContainerImage, OtelConfig, ScannerConfig,
-    ServerConnection, TlsConfig, VmProviders, ZtpServer,
+    AuthConfig, BootServices, ClientConfig, Config, ConfigurationManagement, ContainerImage,
+    DatabaseConfig, OtelConfig, ScannerConfig, ServerConnection, TlsConfig, VmProviders, ZtpServer,
 };
 use crate::konst::{
     QEMU_BIN, SHERPA_BINS_PATH, SHERPA_CONFIG_FILE, SHERPA_CONTAINERS_PATH, SHERPA_IMAGES_PATH,
@@ -87,6 +87,7 @@ pub fn default_config() -> Config {
         enable: false,
         username: Some(SHERPA_USERNAME.to_owned()),
         password: Some(SHERPA_PASSWORD.to_owned()),
+        boot_services: BootServices::default(),
     };
 
     let boxes_dir = SHERPA_IMAGES_PATH.to_owned();
@@ -113,6 +114,9 @@ pub fn default_config() -> Config {
         tls: TlsConfig::default(),
         otel: OtelConfig::default(),
         scanner: ScannerConfig::default(),
+        auth: AuthConfig::default(),
+        database: DatabaseConfig::default(),
+        host_interfaces: vec![],
     }
 }
 
@@ -1,43 +1,85 @@
-use anyhow::Result;
+use std::net::Ipv4Addr;
+use std::time::Duration;
 
-use super::get_ipv4_addr;
-use crate::data::{Config, DhcpLease};
-use crate::konst::{DHCP_LEASES_FILE, DHCP_URI_DIR, HTTP_PORT, SHERPA_MANAGEMENT_IP_INDEX};
+use anyhow::{Context, Result};
 
-pub async fn get_dhcp_leases(config: &Config) -> Result<Vec<DhcpLease>> {
-    let lab_router = get_ipv4_addr(&config.management_prefix_ipv4, SHERPA_MANAGEMENT_IP_INDEX)?;
+use crate::data::DhcpLease;
+use crate::konst::{DHCP_LEASES_FILE, DHCP_URI_DIR, HTTP_PORT};
+
+/// Fetch the DHCP leases published by the boot server of a lab.
+///
+/// Both the boot container and the built-in boot services serve the
+/// dnsmasq lease file over HTTP on the lab router address.
+pub async fn get_dhcp_leases(lab_router: Ipv4Addr) -> Result<Vec<DhcpLease>> {
     let url = format!(
         "http://{}:{}/{}/{}",
         lab_router, HTTP_PORT, DHCP_URI_DIR, DHCP_LEASES_FILE,
     );
     // Create a client with a timeout
     let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(1))
+        .timeout(Duration::from_secs(1))
         .build()?;
-    // Attempt to fetch; if it fails, supply empty string instead
-    match client.get(url).send().await {
-        Ok(response) => {
-            let body = response.text().await.unwrap_or_default();
-            let leases: Vec<DhcpLease> = body
-                .lines()
-                .filter_map(|line| {
-                    let fields: Vec<&str> = line.split_whitespace().collect();
-                    if fields.len() == 5 {
-                        Some(DhcpLease {
-                            expiry: fields[0].parse().unwrap_or(0),
-                            mac_address: fields[1].into(),
-                            ipv4_address: fields[2].into(),
-                            ipv6_address: None,
-                            hostname: fields[3].into(),
-                            client_id: fields[4].into(),
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            Ok(leases)
-        }
-        Err(_) => Ok(vec![]),
+    let body = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(format!("Failed to fetch DHCP leases from {url}"))?
+        .text()
+        .await
+        .context("Failed to read DHCP leases")?;
+    Ok(parse_dhcp_leases(&body))
+}
+
+/// Parse a dnsmasq lease file.
+///
+/// Lines are `<expiry> <mac> <ipv4> <hostname> <client-id>`. DHCPv6 entries
+/// and malformed lines are skipped.
+pub fn parse_dhcp_leases(contents: &str) -> Vec<DhcpLease> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 5 || fields[2].parse::<Ipv4Addr>().is_err() {
+                return None;
+            }
+            Some(DhcpLease {
+                expiry: fields[0].parse().unwrap_or(0),
+                mac_address: fields[1].into(),
+                ipv4_address: fields[2].into(),
+                ipv6_address: None,
+                hostname: fields[3].into(),
+                client_id: fields[4].into(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dhcp_leases() {
+        let contents = "\
+1700000000 52:54:00:aa:bb:01 172.31.0.11 dev01 *
+0 52:54:00:aa:bb:02 172.31.0.12 * 01:52:54:00:aa:bb:02
+duid 00:04:de:ad:be:ef
+1700000000 1234 fd00:b00b::11 dev01 00:01:02
+garbage
+";
+        let leases = parse_dhcp_leases(contents);
+        assert_eq!(leases.len(), 2);
+        assert_eq!(leases[0].expiry, 1700000000);
+        assert_eq!(leases[0].mac_address, "52:54:00:aa:bb:01");
+        assert_eq!(leases[0].ipv4_address, "172.31.0.11");
+        assert_eq!(leases[0].hostname, "dev01");
+        assert_eq!(leases[1].expiry, 0);
+        assert_eq!(leases[1].client_id, "01:52:54:00:aa:bb:02");
+    }
+
+    #[test]
+    fn test_parse_empty_dhcp_leases() {
+        assert!(parse_dhcp_leases("").is_empty());
     }
 }
@@ -108,6 +108,22 @@ pub fn delete_dirs(dir_path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Total size in bytes of the regular files under a directory.
+/// Symlinks are not followed, so linked images are not counted twice.
+pub fn dir_size(dir_path: &str) -> Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir_path).with_context(|| format!("Failed to read {dir_path}"))? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            total += dir_size(&path_to_string(&entry.path()))?;
+        } else if file_type.is_file() {
+            total += entry.metadata()?.len();
+        }
+    }
+    Ok(total)
+}
+
 /// Copy a file from a source to a destination.
 /// This will overwrite the destination file if it exists.
 pub fn copy_file(src: &str, dst: &str) -> Result<()> {
@@ -330,11 +346,158 @@ pub fn create_config_archive(src_path: &str, dst_path: &str) -> Result<()> {
 #[cfg(unix)]
 pub fn _convert_iso_qcow2(src_iso: &str, dst_disk: &str) -> Result<()> {
     Command::new("qemu-img")
-        .args(["convert", "-O", "qcow2", src_iso, dst_disk])
+        .args(["convert", "-f", "raw", "-O", "qcow2", src_iso, dst_disk])
         .status()?;
     Ok(())
 }
 
+/// Check whether a file is a tar archive, optionally compressed.
+///
+/// Detects gzip, xz, bzip2 and zstd compression by magic bytes, and plain
+/// tar (including OVA files) by the `ustar` header.
+pub fn is_tar_archive(path: &str) -> Result<bool> {
+    use std::io::Read;
+
+    let mut header = [0u8; 512];
+    let mut file = File::open(path).with_context(|| format!("Failed to open {}", path))?;
+    let mut read = 0;
+    while read < header.len() {
+        let n = file.read(&mut header[read..])?;
+        if n == 0 {
+            break;
+        }
+        read += n;
+    }
+    let header = &header[..read];
+
+    let compressed = header.starts_with(&[0x1f, 0x8b])
+        || header.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00])
+        || header.starts_with(b"BZh")
+        || header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]);
+    let ustar = header.len() >= 262 && &header[257..262] == b"ustar";
+
+    Ok(compressed || ustar)
+}
+
+/// Extract a tar archive (including OVA files) into a directory.
+///
+/// `tar` must be installed on the system.
+#[cfg(unix)]
+pub fn extract_archive(src: &str, dst_dir: &str) -> Result<()> {
+    let status = Command::new("tar")
+        .args(["-xf", src, "-C", dst_dir, "--no-same-owner"])
+        .status()?;
+    if !status.success() {
+        bail!(
+            "tar failed (exit {}): extracting archive {} to {}",
+            status.code().unwrap_or(-1),
+            src,
+            dst_dir
+        );
+    }
+    tracing::debug!(archive = %src, destination = %dst_dir, "Archive extracted");
+    Ok(())
+}
+
+/// Detect the format of a disk image (e.g. `qcow2`, `vmdk`, `vhdx`, `raw`).
+///
+/// Images that reference other files are rejected: a backing file, a qcow2
+/// external data file or vmdk extents outside the image itself. Converting
+/// such an image would read whatever host files it points at.
+///
+/// `qemu-img` must be installed on the system.
+#[cfg(unix)]
+pub fn disk_image_format(path: &str) -> Result<String> {
+    let output = Command::new("qemu-img")
+        .args(["info", "--output=json", path])
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "qemu-img info failed (exit {}): {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("Failed to parse qemu-img info for {}", path))?;
+    check_standalone_image(path, &info)?;
+    info["format"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("qemu-img did not report a format for {}", path))
+}
+
+/// Fail if `qemu-img info` output shows the image at `path` references
+/// other files.
+#[cfg(unix)]
+fn check_standalone_image(path: &str, info: &serde_json::Value) -> Result<()> {
+    if let Some(backing) = info["backing-filename"].as_str() {
+        bail!("Disk image {} has a backing file: {}", path, backing);
+    }
+    let specific = &info["format-specific"]["data"];
+    if let Some(data_file) = specific["data-file"].as_str() {
+        bail!(
+            "Disk image {} has an external data file: {}",
+            path,
+            data_file
+        );
+    }
+    for extent in specific["extents"].as_array().into_iter().flatten() {
+        let filename = extent["filename"].as_str().unwrap_or_default();
+        if filename != path {
+            bail!("Disk image {} has an external extent: {}", path, filename);
+        }
+    }
+    Ok(())
+}
+
+/// Convert a disk image to qcow2, optionally compressing it.
+///
+/// The source format is always passed explicitly, so qemu-img never probes
+/// it again.
+///
+/// `qemu-img` must be installed on the system.
+#[cfg(unix)]
+pub fn convert_disk_image(src: &str, src_format: &str, dst: &str, compress: bool) -> Result<()> {
+    let mut args = vec!["convert", "-f", src_format, "-O", "qcow2"];
+    if compress {
+        args.push("-c");
+    }
+    args.extend([src, dst]);
+
+    let status = Command::new("qemu-img").args(&args).status()?;
+    if !status.success() {
+        bail!(
+            "qemu-img failed (exit {}): converting {} ({}) to {}",
+            status.code().unwrap_or(-1),
+            src,
+            src_format,
+            dst
+        );
+    }
+    tracing::debug!(source = %src, format = %src_format, destination = %dst, compress, "Disk image converted");
+    Ok(())
+}
+
+/// Sparsify a disk image in place, releasing unused blocks.
+///
+/// `virt-sparsify` (libguestfs) must be installed on the system.
+#[cfg(unix)]
+pub fn sparsify_disk_image(path: &str) -> Result<()> {
+    let status = Command::new("virt-sparsify")
+        .args(["--in-place", path])
+        .status()?;
+    if !status.success() {
+        bail!(
+            "virt-sparsify failed (exit {}): {}",
+            status.code().unwrap_or(-1),
+            path
+        );
+    }
+    tracing::debug!(image = %path, "Disk image sparsified");
+    Ok(())
+}
+
 /// Return the expected image filename for a given node kind and boot mode.
 ///
 /// - VMs: `virtioa.qcow2`
@@ -436,6 +599,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_dir_size_counts_nested_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        fs::write(dir.path().join("a.bin"), [0u8; 10])?;
+        fs::create_dir(dir.path().join("nested"))?;
+        fs::write(dir.path().join("nested/b.bin"), [0u8; 5])?;
+        #[cfg(unix)]
+        symlink(dir.path().join("a.bin"), dir.path().join("link.bin"))?;
+        assert_eq!(dir_size(dir.path().to_str().unwrap())?, 15);
+        Ok(())
+    }
+
     #[test]
     fn test_create_file_and_load_file() -> Result<()> {
         let dir = TempDir::new()?;
@@ -500,6 +675,26 @@ mod tests {
         assert!(copy_file("/tmp/sherpa_no_src.txt", "/tmp/sherpa_dst.txt").is_err());
     }
 
+    #[test]
+    fn test_is_tar_archive_detects_headers() -> Result<()> {
+        let dir = TempDir::new()?;
+
+        let gzip = dir.path().join("image.tar.gz");
+        fs::write(&gzip, [0x1f, 0x8b, 0x08, 0x00])?;
+        assert!(is_tar_archive(&path_to_string(&gzip))?);
+
+        let mut header = vec![0u8; 512];
+        header[257..262].copy_from_slice(b"ustar");
+        let ova = dir.path().join("appliance.ova");
+        fs::write(&ova, &header)?;
+        assert!(is_tar_archive(&path_to_string(&ova))?);
+
+        let qcow2 = dir.path().join("disk.qcow2");
+        fs::write(&qcow2, b"QFI\xfb")?;
+        assert!(!is_tar_archive(&path_to_string(&qcow2))?);
+        Ok(())
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_create_symlink() -> Result<()> {
@@ -559,4 +754,42 @@ mod tests {
         assert_eq!(file_mode, 0o660);
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_standalone_image() {
+        let path = "/work/extract/disk.vmdk";
+        let standalone = serde_json::json!({
+            "format": "vmdk",
+            "format-specific": {
+                "type": "vmdk",
+                "data": { "extents": [{ "filename": path }] }
+            }
+        });
+        assert!(check_standalone_image(path, &standalone).is_ok());
+
+        let external_extent = serde_json::json!({
+            "format": "vmdk",
+            "format-specific": {
+                "type": "vmdk",
+                "data": { "extents": [{ "filename": "/etc/shadow" }] }
+            }
+        });
+        assert!(check_standalone_image(path, &external_extent).is_err());
+
+        let backing = serde_json::json!({
+            "format": "qcow2",
+            "backing-filename": "/var/lib/libvirt/images/base.qcow2"
+        });
+        assert!(check_standalone_image(path, &backing).is_err());
+
+        let data_file = serde_json::json!({
+            "format": "qcow2",
+            "format-specific": {
+                "type": "qcow2",
+                "data": { "data-file": "/dev/sda" }
+            }
+        });
+        assert!(check_standalone_image(path, &data_file).is_err());
+    }
 }
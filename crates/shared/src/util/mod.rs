@@ -1,3 +1,4 @@
+mod checksum;
 mod config;
 mod dhcp;
 mod dns;
@@ -18,23 +19,28 @@ mod table;
 mod text;
 mod user;
 
+pub use checksum::{
+    Checksum, ChecksumAlgorithm, file_digest, file_sha256, parse_checksum_listing,
+    verify_file_checksum,
+};
 pub use config::{
     build_client_websocket_url, build_websocket_url, create_client_config, create_config,
     default_config, load_client_config, load_config,
 };
-pub use dhcp::get_dhcp_leases;
+pub use dhcp::{get_dhcp_leases, parse_dhcp_leases};
 pub use dns::{default_dns, default_dns_dual_stack};
 pub use emoji::{Emoji, emoji_error, emoji_success, emoji_warning};
 pub use encode::{base64_decode, base64_encode, base64_encode_file};
 pub use env::{get_server_url, read_env_file_value};
 pub use file_system::{
-    check_file_size, copy_file, create_dir, create_file, delete_dirs, dir_exists, expand_path,
-    file_exists, get_cwd, image_filename, load_file, path_to_string,
+    check_file_size, copy_file, create_dir, create_file, delete_dirs, dir_exists, dir_size,
+    expand_path, file_exists, get_cwd, image_filename, is_tar_archive, load_file, path_to_string,
 };
 #[cfg(unix)]
 pub use file_system::{
-    copy_to_dos_image, copy_to_ext4_image, create_config_archive, create_panos_bootstrap_iso,
-    create_symlink, create_ztp_iso, fix_permissions_recursive, set_file_permissions,
+    convert_disk_image, copy_to_dos_image, copy_to_ext4_image, create_config_archive,
+    create_panos_bootstrap_iso, create_symlink, create_ztp_iso, disk_image_format, extract_archive,
+    fix_permissions_recursive, set_file_permissions, sparsify_disk_image,
 };
 pub use host::{get_fqdn, get_hostname};
 #[cfg(feature = "netinfo")]
@@ -63,10 +69,11 @@ pub use ssh::{
     pub_ssh_key_to_md5_hash, pub_ssh_key_to_sha256_hash, remove_lab_ssh_include,
 };
 pub use table::{
-    CertificateTableInfo, render_bridges_table, render_certificates_table, render_devices_table,
-    render_image_detail_table, render_images_table, render_lab_info_table, render_links_table,
-    render_nodes_table, render_scanned_images_table, render_server_status_table,
-    render_ssh_config_inspection_table,
+    CertificateTableInfo, render_bridges_table, render_cabling_table, render_certificates_table,
+    render_custom_models_table, render_devices_table, render_image_detail_table,
+    render_image_usage_table, render_images_table, render_lab_info_table, render_leases_table,
+    render_link_stats_table, render_links_table, render_nodes_table, render_orphaned_disks_table,
+    render_scanned_images_table, render_server_status_table, render_ssh_config_inspection_table,
 };
-pub use text::split_node_int;
-pub use user::{get_username, sherpa_user};
+pub use text::{format_bitrate, format_bytes, split_node_int};
+pub use user::{get_username, sherpa_user, sherpa_user_from};
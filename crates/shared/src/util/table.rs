@@ -4,8 +4,11 @@ use tabled::{
 };
 
 use super::ssh::SshConfigInspectionEntry;
+use super::text::{format_bitrate, format_bytes};
 use crate::data::{
-    BridgeInfo, DeviceInfo, ImageSummary, LabInfo, LinkInfo, NodeConfig, NodeInfo, ScannedImage,
+    BridgeInfo, CablingCheck, CustomModelSummary, DeviceInfo, ImageSummary, ImageVersionUsage,
+    LabInfo, LabLease, LinkInfo, LinkStatsResponse, LinkStatsSource, NodeConfig, NodeInfo,
+    OrphanedDisk, ScannedImage, TrafficCounters,
 };
 
 /// Represents a row in the SSH config inspection table
@@ -363,6 +366,292 @@ pub fn render_scanned_images_table(images: &[ScannedImage]) -> String {
         .to_string()
 }
 
+/// Renders a table of custom models with their base model and ZTP method
+pub fn render_custom_models_table(models: &[CustomModelSummary]) -> String {
+    Table::new(models)
+        .with(Style::modern())
+        .with(Panel::header("Custom Models"))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
+/// Represents a row in the image usage table
+#[derive(Tabled)]
+struct ImageUsageTableRow {
+    #[tabled(rename = "Model")]
+    model: String,
+
+    #[tabled(rename = "Version")]
+    version: String,
+
+    #[tabled(rename = "Kind")]
+    kind: String,
+
+    #[tabled(rename = "Default")]
+    default: bool,
+
+    #[tabled(rename = "Size")]
+    size: String,
+
+    #[tabled(rename = "Nodes")]
+    nodes: usize,
+
+    #[tabled(rename = "Labs")]
+    labs: String,
+
+    #[tabled(rename = "Last Used")]
+    last_used: String,
+}
+
+/// Renders a table of image versions with their size and the labs using them
+pub fn render_image_usage_table(images: &[ImageVersionUsage]) -> String {
+    let rows: Vec<ImageUsageTableRow> = images
+        .iter()
+        .map(|image| ImageUsageTableRow {
+            model: image.model.to_string(),
+            version: image.version.clone(),
+            kind: image.kind.to_string(),
+            default: image.default,
+            size: image
+                .size_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "missing".to_string()),
+            nodes: image.node_count,
+            labs: if image.labs.is_empty() {
+                "-".to_string()
+            } else {
+                image.labs.join(", ")
+            },
+            last_used: image
+                .last_used_at
+                .and_then(|seconds| jiff::Timestamp::from_second(seconds).ok())
+                .map(|ts| ts.strftime("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        })
+        .collect();
+
+    Table::new(rows)
+        .with(Style::modern())
+        .with(Panel::header("Image Usage"))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
+/// Represents a row in the DHCP leases table
+#[derive(Tabled)]
+struct LeaseTableRow {
+    #[tabled(rename = "MAC Address")]
+    mac_address: String,
+
+    #[tabled(rename = "IPv4")]
+    ipv4_address: String,
+
+    #[tabled(rename = "Hostname")]
+    hostname: String,
+
+    #[tabled(rename = "Node")]
+    node: String,
+
+    #[tabled(rename = "Expires (UTC)")]
+    expires: String,
+}
+
+/// Renders a table of DHCP leases, marking leases no node owns as unknown
+pub fn render_leases_table(leases: &[LabLease]) -> String {
+    let rows: Vec<LeaseTableRow> = leases
+        .iter()
+        .map(|lease| LeaseTableRow {
+            mac_address: lease.mac_address.clone(),
+            ipv4_address: lease.ipv4_address.clone(),
+            hostname: lease.hostname.clone().unwrap_or_else(|| "-".to_string()),
+            node: lease
+                .node_name
+                .clone()
+                .unwrap_or_else(|| format!("({})", lease.status)),
+            expires: match lease.expiry {
+                0 => "never".to_string(),
+                expiry => i64::try_from(expiry)
+                    .ok()
+                    .and_then(|seconds| jiff::Timestamp::from_second(seconds).ok())
+                    .map(|ts| ts.strftime("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            },
+        })
+        .collect();
+
+    Table::new(rows)
+        .with(Style::modern())
+        .with(Panel::header("DHCP Leases"))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
+/// Represents a row in the cabling table
+#[derive(Tabled)]
+struct CablingTableRow {
+    #[tabled(rename = "Link")]
+    link_index: u16,
+
+    #[tabled(rename = "Interface")]
+    interface: String,
+
+    #[tabled(rename = "Expected Neighbour")]
+    expected: String,
+
+    #[tabled(rename = "Discovered Neighbour")]
+    discovered: String,
+
+    #[tabled(rename = "Status")]
+    status: String,
+}
+
+/// Renders a table of the expected and discovered neighbour of each interface
+pub fn render_cabling_table(checks: &[CablingCheck]) -> String {
+    let rows: Vec<CablingTableRow> = checks
+        .iter()
+        .map(|check| CablingTableRow {
+            link_index: check.link_index,
+            interface: format!("{}::{}", check.node, check.interface),
+            expected: format!("{}::{}", check.expected_node, check.expected_interface),
+            discovered: check
+                .discovered
+                .as_ref()
+                .map(|neighbour| format!("{neighbour} ({})", neighbour.protocol))
+                .unwrap_or_else(|| "-".to_string()),
+            status: check.status.to_string(),
+        })
+        .collect();
+
+    Table::new(rows)
+        .with(Style::modern())
+        .with(Panel::header("Cabling"))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
+/// Represents a row in the link stats table
+#[derive(Tabled)]
+struct LinkStatsTableRow {
+    #[tabled(rename = "Link")]
+    index: u16,
+
+    #[tabled(rename = "A Side")]
+    a_side: String,
+
+    #[tabled(rename = "B Side")]
+    b_side: String,
+
+    #[tabled(rename = "A → B")]
+    a_to_b: String,
+
+    #[tabled(rename = "B → A")]
+    b_to_a: String,
+
+    #[tabled(rename = "Drops (A→B / B→A)")]
+    drops: String,
+
+    #[tabled(rename = "Source")]
+    source: String,
+}
+
+fn traffic_totals(counters: &TrafficCounters) -> String {
+    format!(
+        "{} pkts, {}",
+        counters.packets,
+        format_bytes(counters.bytes)
+    )
+}
+
+/// Renders a table of link traffic counters. Given an earlier sample the
+/// traffic columns show rates since that sample, otherwise running totals.
+pub fn render_link_stats_table(
+    stats: &LinkStatsResponse,
+    previous: Option<&LinkStatsResponse>,
+) -> String {
+    let elapsed_secs = previous
+        .map(|previous| stats.sampled_at_ms.saturating_sub(previous.sampled_at_ms) as f64 / 1000.0);
+
+    let rows: Vec<LinkStatsTableRow> = stats
+        .links
+        .iter()
+        .map(|link| {
+            let earlier = previous.and_then(|previous| {
+                previous
+                    .links
+                    .iter()
+                    .find(|earlier| earlier.index == link.index)
+            });
+            let (a_to_b, b_to_a) = match (link.source, earlier, elapsed_secs) {
+                (LinkStatsSource::Unavailable, _, _) => ("-".to_string(), "-".to_string()),
+                (_, Some(earlier), Some(elapsed_secs)) => {
+                    let rates = link.rates_since(earlier, elapsed_secs);
+                    (
+                        format!(
+                            "{} ({:.0} pps)",
+                            format_bitrate(rates.a_to_b_bps),
+                            rates.a_to_b_pps
+                        ),
+                        format!(
+                            "{} ({:.0} pps)",
+                            format_bitrate(rates.b_to_a_bps),
+                            rates.b_to_a_pps
+                        ),
+                    )
+                }
+                _ => (traffic_totals(&link.a_to_b), traffic_totals(&link.b_to_a)),
+            };
+            LinkStatsTableRow {
+                index: link.index,
+                a_side: format!("{}::{}", link.node_a, link.int_a),
+                b_side: format!("{}::{}", link.node_b, link.int_b),
+                a_to_b,
+                b_to_a,
+                drops: format!("{} / {}", link.a_to_b.drops, link.b_to_a.drops),
+                source: link.source.to_string(),
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+        .with(Style::modern())
+        .with(Panel::header(format!("Link Stats - {}", stats.lab_id)))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
+/// Represents a row in the orphaned disks table
+#[derive(Tabled)]
+struct OrphanedDiskTableRow {
+    #[tabled(rename = "Path")]
+    path: String,
+
+    #[tabled(rename = "Size")]
+    size: String,
+}
+
+/// Renders a table of storage pool files that no node owns
+pub fn render_orphaned_disks_table(disks: &[OrphanedDisk]) -> String {
+    let rows: Vec<OrphanedDiskTableRow> = disks
+        .iter()
+        .map(|disk| OrphanedDiskTableRow {
+            path: disk.path.clone(),
+            size: format_bytes(disk.size_bytes),
+        })
+        .collect();
+
+    Table::new(rows)
+        .with(Style::modern())
+        .with(Panel::header("Orphaned Lab Disks"))
+        .with(Modify::new(Rows::first()).with(Alignment::center()))
+        .with(BorderCorrection::span())
+        .to_string()
+}
+
 /// Renders a two-column key-value table with all NodeConfig fields for an image
 pub fn render_image_detail_table(image: &NodeConfig) -> String {
     let rows = vec![
@@ -550,7 +839,10 @@ pub fn render_server_status_table(server: &str, status: &str, tls: &str) -> Stri
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::{NodeKind, NodeModel, NodeState};
+    use crate::data::{
+        BridgeKind, CablingStatus, DiscoveredNeighbour, DiscoveryProtocol, LeaseStatus, LinkStats,
+        NodeKind, NodeModel, NodeState,
+    };
 
     #[test]
     fn test_render_single_node() {
@@ -745,6 +1037,130 @@ mod tests {
         assert!(table.contains("Disks"));
     }
 
+    fn link_stats_sample(sampled_at_ms: u64, a_to_b_bytes: u64) -> LinkStatsResponse {
+        LinkStatsResponse {
+            lab_id: "abcd1234".to_string(),
+            sampled_at_ms,
+            links: vec![
+                LinkStats {
+                    index: 0,
+                    kind: BridgeKind::P2p,
+                    node_a: "dev01".to_string(),
+                    int_a: "eth1".to_string(),
+                    node_b: "dev02".to_string(),
+                    int_b: "eth1".to_string(),
+                    source: LinkStatsSource::Ebpf,
+                    a_to_b: TrafficCounters {
+                        packets: a_to_b_bytes / 100,
+                        bytes: a_to_b_bytes,
+                        drops: 3,
+                    },
+                    b_to_a: TrafficCounters::default(),
+                },
+                LinkStats {
+                    index: 1,
+                    kind: BridgeKind::P2pBridge,
+                    node_a: "dev01".to_string(),
+                    int_a: "eth2".to_string(),
+                    node_b: "dev03".to_string(),
+                    int_b: "eth1".to_string(),
+                    source: LinkStatsSource::Unavailable,
+                    a_to_b: TrafficCounters::default(),
+                    b_to_a: TrafficCounters::default(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_link_stats_table_totals() {
+        let table = render_link_stats_table(&link_stats_sample(0, 2048), None);
+        assert!(table.contains("Link Stats - abcd1234"));
+        assert!(table.contains("dev01::eth1"));
+        assert!(table.contains("20 pkts, 2.0 KiB"));
+        assert!(table.contains("3 / 0"));
+        assert!(table.contains("ebpf"));
+        assert!(table.contains("unavailable"));
+    }
+
+    #[test]
+    fn test_render_link_stats_table_rates() {
+        let previous = link_stats_sample(1_000, 0);
+        let current = link_stats_sample(3_000, 250_000);
+
+        let table = render_link_stats_table(&current, Some(&previous));
+        // 250 kB over 2 seconds
+        assert!(table.contains("1.0 Mbit/s (1250 pps)"));
+        assert!(table.contains("0 bit/s (0 pps)"));
+    }
+
+    #[test]
+    fn test_render_leases_table() {
+        let leases = vec![
+            LabLease {
+                mac_address: "52:54:00:aa:bb:01".to_string(),
+                ipv4_address: "172.31.0.11".to_string(),
+                ipv6_address: None,
+                hostname: Some("router01".to_string()),
+                expiry: 0,
+                node_name: Some("router01".to_string()),
+                status: LeaseStatus::Known,
+            },
+            LabLease {
+                mac_address: "52:54:00:ff:ff:ff".to_string(),
+                ipv4_address: "172.31.0.100".to_string(),
+                ipv6_address: None,
+                hostname: None,
+                expiry: 1_700_000_000,
+                node_name: None,
+                status: LeaseStatus::Unknown,
+            },
+        ];
+
+        let table = render_leases_table(&leases);
+        assert!(table.contains("DHCP Leases"));
+        assert!(table.contains("router01"));
+        assert!(table.contains("never"));
+        assert!(table.contains("(unknown)"));
+        assert!(table.contains("2023-11-14 22:13:20"));
+    }
+
+    #[test]
+    fn test_render_cabling_table() {
+        let checks = vec![
+            CablingCheck {
+                link_index: 0,
+                node: "dev01".to_string(),
+                interface: "Ethernet1".to_string(),
+                expected_node: "dev02".to_string(),
+                expected_interface: "Ethernet2".to_string(),
+                discovered: Some(DiscoveredNeighbour {
+                    protocol: DiscoveryProtocol::Lldp,
+                    chassis_id: "52:54:00:aa:bb:02".to_string(),
+                    system_name: Some("dev02".to_string()),
+                    port_id: "Ethernet3".to_string(),
+                    port_description: None,
+                }),
+                status: CablingStatus::Mismatch,
+            },
+            CablingCheck {
+                link_index: 0,
+                node: "dev02".to_string(),
+                interface: "Ethernet2".to_string(),
+                expected_node: "dev01".to_string(),
+                expected_interface: "Ethernet1".to_string(),
+                discovered: None,
+                status: CablingStatus::NotSeen,
+            },
+        ];
+
+        let table = render_cabling_table(&checks);
+        assert!(table.contains("Cabling"));
+        assert!(table.contains("dev02::Ethernet3 (lldp)"));
+        assert!(table.contains("mismatch"));
+        assert!(table.contains("not seen"));
+    }
+
     #[test]
     fn test_render_lab_info_table() {
         use std::net::Ipv4Addr;
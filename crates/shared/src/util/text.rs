@@ -8,6 +8,38 @@ pub fn split_node_int(text: &str) -> Result<(String, String)> {
     Ok((node.to_string(), interface.to_string()))
 }
 
+/// Format a byte count with binary units, e.g. `1.5 GiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Format a bit rate using decimal (SI) units, as link speeds are quoted.
+pub fn format_bitrate(bits_per_sec: f64) -> String {
+    const UNITS: [&str; 4] = ["bit/s", "kbit/s", "Mbit/s", "Gbit/s"];
+    let mut value = bits_per_sec.max(0.0);
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +69,20 @@ mod tests {
         let result = split_node_int("");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_format_bitrate() {
+        assert_eq!(format_bitrate(0.0), "0 bit/s");
+        assert_eq!(format_bitrate(999.0), "999 bit/s");
+        assert_eq!(format_bitrate(1_500.0), "1.5 kbit/s");
+        assert_eq!(format_bitrate(2_500_000_000.0), "2.5 Gbit/s");
+    }
 }
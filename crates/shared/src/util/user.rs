@@ -14,8 +14,13 @@ pub fn get_username() -> Result<String> {
 }
 /// Returns the default sherpa user and set sudo to True.
 pub fn sherpa_user() -> Result<User> {
+    sherpa_user_from(SHERPA_SSH_PUBLIC_KEY_PATH)
+}
+
+/// Returns the sherpa user with the SSH public key at `key_path`.
+pub fn sherpa_user_from(key_path: &str) -> Result<User> {
     let username = SHERPA_USERNAME;
-    let ssh_public_key = get_ssh_public_key(SHERPA_SSH_PUBLIC_KEY_PATH)?;
+    let ssh_public_key = get_ssh_public_key(key_path)?;
     Ok(User {
         username: username.to_owned(),
         password: Some(SHERPA_PASSWORD.to_owned()),
@@ -1,7 +1,7 @@
 use askama::Template;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
-use shared::data::{Dns, NetworkV4, NetworkV6, User};
+use shared::data::{Dns, InterfaceMtu, NetworkV4, NetworkV6, User};
 
 #[derive(Template)]
 #[template(path = "arista/arista_veos.jinja", ext = "txt")]
@@ -13,6 +13,7 @@ pub struct AristaVeosZtpTemplate {
     pub mgmt_ipv4_address: Option<Ipv4Addr>,
     pub mgmt_ipv6_address: Option<Ipv6Addr>,
     pub mgmt_ipv6: Option<NetworkV6>,
+    pub interface_mtus: Vec<InterfaceMtu>,
 }
 
 #[derive(Template)]
@@ -25,4 +26,5 @@ pub struct AristaCeosZtpTemplate {
     pub mgmt_ipv4_address: Option<Ipv4Addr>,
     pub mgmt_ipv6_address: Option<Ipv6Addr>,
     pub mgmt_ipv6: Option<NetworkV6>,
+    pub interface_mtus: Vec<InterfaceMtu>,
 }
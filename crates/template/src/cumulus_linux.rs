@@ -2,7 +2,7 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 
 use askama::Template;
 
-use shared::data::{Dns, NetworkV4, NetworkV6, User};
+use shared::data::{Dns, InterfaceMtu, NetworkV4, NetworkV6, User};
 
 #[derive(Template)]
 #[template(path = "cumulus/cumulus_linux.jinja", ext = "txt")]
@@ -14,4 +14,5 @@ pub struct CumulusLinuxZtpTemplate {
     pub mgmt_ipv4_address: Option<Ipv4Addr>,
     pub mgmt_ipv6_address: Option<Ipv6Addr>,
     pub mgmt_ipv6: Option<NetworkV6>,
+    pub interface_mtus: Vec<InterfaceMtu>,
 }
@@ -0,0 +1,365 @@
+//! Runtime renderer for custom model ZTP templates.
+//!
+//! Custom model templates are supplied by users at runtime, so they cannot be
+//! compiled with askama. They use a small mustache-style syntax:
+//!
+//! - `{{ name }}` inserts a variable
+//! - `{{#section}} ... {{/section}}` repeats its body for each item in a list
+//!   section, or renders it once when an optional section is present
+//! - `{{^section}} ... {{/section}}` renders its body when the section is empty
+//!
+//! Section tags on a line of their own do not leave a blank line behind.
+//! Unknown variables and sections are errors, so mistakes surface when the
+//! model is added rather than as a silently broken config.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::{Result, anyhow, bail};
+use ipnet::{Ipv4Net, Ipv6Net};
+
+use shared::data::{
+    Dns, InterfaceMtu, NameServer, NetworkV4, NetworkV6, SshKeyAlgorithms, SshPublicKey, User,
+};
+
+/// Values available to a custom model ZTP template.
+///
+/// Variables: `hostname`, `domain`, `username`, `ssh_public_key`,
+/// `mgmt_interface`, `mgmt_ipv4_address`, `mgmt_ipv4_prefix_length`,
+/// `mgmt_ipv4_netmask` and `mgmt_ipv4_gateway`.
+///
+/// Sections: `password` (`password`), `ipv6` (`mgmt_ipv6_address`,
+/// `mgmt_ipv6_prefix_length`, `mgmt_ipv6_gateway`), `name_servers`
+/// (`address`) and `interfaces` (`name`, `index`, `mtu`).
+pub struct CustomZtpTemplate {
+    pub hostname: String,
+    pub user: User,
+    pub dns: Dns,
+    pub mgmt_interface: String,
+    pub mgmt_ipv4: NetworkV4,
+    pub mgmt_ipv4_address: Ipv4Addr,
+    pub mgmt_ipv6_address: Option<Ipv6Addr>,
+    pub mgmt_ipv6: Option<NetworkV6>,
+    /// Data interface names and MTUs, in index order
+    pub interfaces: Vec<InterfaceMtu>,
+}
+
+impl CustomZtpTemplate {
+    /// Render a template source with this node's values.
+    pub fn render(&self, source: &str) -> Result<String> {
+        let nodes = parse(source)?;
+        let context = self.context();
+        let mut output = String::new();
+        render_nodes(&nodes, &mut vec![&context], &mut output)?;
+        Ok(output)
+    }
+
+    /// Check a template source parses and only uses known names.
+    pub fn check(source: &str) -> Result<()> {
+        Self::sample()?.render(source).map(|_| ())
+    }
+
+    fn context(&self) -> Context {
+        let mut context = Context::default();
+        context.set("hostname", &self.hostname);
+        context.set("domain", &self.dns.domain);
+        context.set("username", &self.user.username);
+        context.set(
+            "ssh_public_key",
+            format!(
+                "{} {}",
+                self.user.ssh_public_key.algorithm, self.user.ssh_public_key.key
+            ),
+        );
+        context.set("mgmt_interface", &self.mgmt_interface);
+        context.set("mgmt_ipv4_address", self.mgmt_ipv4_address);
+        context.set("mgmt_ipv4_prefix_length", self.mgmt_ipv4.prefix_length);
+        context.set("mgmt_ipv4_netmask", self.mgmt_ipv4.subnet_mask);
+        context.set("mgmt_ipv4_gateway", self.mgmt_ipv4.first);
+
+        let password = self
+            .user
+            .password
+            .iter()
+            .map(|password| {
+                let mut item = Context::default();
+                item.set("password", password);
+                item
+            })
+            .collect();
+        context.sections.insert("password", password);
+
+        let ipv6 = match (&self.mgmt_ipv6_address, &self.mgmt_ipv6) {
+            (Some(address), Some(network)) => {
+                let mut item = Context::default();
+                item.set("mgmt_ipv6_address", address);
+                item.set("mgmt_ipv6_prefix_length", network.prefix_length);
+                item.set("mgmt_ipv6_gateway", network.first);
+                vec![item]
+            }
+            _ => vec![],
+        };
+        context.sections.insert("ipv6", ipv6);
+
+        let name_servers = self
+            .dns
+            .name_servers
+            .iter()
+            .map(|server| {
+                let mut item = Context::default();
+                item.set("address", server.ipv4_address);
+                item
+            })
+            .collect();
+        context.sections.insert("name_servers", name_servers);
+
+        let interfaces = self
+            .interfaces
+            .iter()
+            .enumerate()
+            .map(|(i, interface)| {
+                let mut item = Context::default();
+                item.set("name", &interface.name);
+                item.set("index", i + 1);
+                item.set("mtu", interface.mtu);
+                item
+            })
+            .collect();
+        context.sections.insert("interfaces", interfaces);
+
+        context
+    }
+
+    /// Placeholder values with every optional section populated.
+    fn sample() -> Result<Self> {
+        Ok(Self {
+            hostname: "node".to_owned(),
+            user: User {
+                username: "sherpa".to_owned(),
+                password: Some("password".to_owned()),
+                ssh_public_key: SshPublicKey {
+                    algorithm: SshKeyAlgorithms::SshEd25519,
+                    key: "key".to_owned(),
+                    comment: None,
+                },
+                sudo: true,
+            },
+            dns: Dns {
+                domain: "sherpa.lab.local".to_owned(),
+                name_servers: vec![NameServer {
+                    name: "ns".to_owned(),
+                    ipv4_address: Ipv4Addr::new(192, 0, 2, 1),
+                    ipv6_address: None,
+                }],
+            },
+            mgmt_interface: "eth0".to_owned(),
+            mgmt_ipv4: NetworkV4 {
+                prefix: "192.0.2.0/24".parse::<Ipv4Net>()?,
+                first: Ipv4Addr::new(192, 0, 2, 1),
+                last: Ipv4Addr::new(192, 0, 2, 254),
+                boot_server: Ipv4Addr::new(192, 0, 2, 1),
+                network: Ipv4Addr::new(192, 0, 2, 0),
+                subnet_mask: Ipv4Addr::new(255, 255, 255, 0),
+                hostmask: Ipv4Addr::new(0, 0, 0, 255),
+                prefix_length: 24,
+            },
+            mgmt_ipv4_address: Ipv4Addr::new(192, 0, 2, 10),
+            mgmt_ipv6_address: Some("2001:db8::10".parse()?),
+            mgmt_ipv6: Some(NetworkV6 {
+                prefix: "2001:db8::/64".parse::<Ipv6Net>()?,
+                first: "2001:db8::1".parse()?,
+                last: "2001:db8::ffff".parse()?,
+                boot_server: "2001:db8::1".parse()?,
+                network: "2001:db8::".parse()?,
+                prefix_length: 64,
+            }),
+            interfaces: vec![InterfaceMtu {
+                name: "eth1".to_owned(),
+                mtu: 1500,
+            }],
+        })
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    values: HashMap<&'static str, String>,
+    sections: HashMap<&'static str, Vec<Context>>,
+}
+
+impl Context {
+    fn set(&mut self, name: &'static str, value: impl ToString) {
+        self.values.insert(name, value.to_string());
+    }
+}
+
+enum Node {
+    Text(String),
+    Var {
+        name: String,
+        line: usize,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        line: usize,
+        children: Vec<Node>,
+    },
+}
+
+struct OpenSection {
+    name: String,
+    inverted: bool,
+    line: usize,
+    parent: Vec<Node>,
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse(source: &str) -> Result<Vec<Node>> {
+    let mut stack: Vec<OpenSection> = Vec::new();
+    let mut current: Vec<Node> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("{{") {
+        let start = pos + found;
+        let line = line_of(source, start);
+        let end = source[start + 2..]
+            .find("}}")
+            .map(|e| start + 2 + e)
+            .ok_or_else(|| anyhow!("Unclosed tag on line {}", line))?;
+        let tag = source[start + 2..end].trim();
+        let segment_start = pos;
+        let mut text = &source[pos..start];
+        pos = end + 2;
+
+        if tag.starts_with(['#', '^', '/']) {
+            // A section tag alone on its line drops the whole line
+            let line_start = text.rfind('\n').map(|i| i + 1);
+            let at_line_start = line_start.is_some()
+                || segment_start == 0
+                || source[..segment_start].ends_with('\n');
+            let leading = &text[line_start.unwrap_or(0)..];
+            let rest = &source[pos..];
+            let line_end = rest.find('\n');
+            let trailing = &rest[..line_end.unwrap_or(rest.len())];
+            if at_line_start && leading.trim().is_empty() && trailing.trim().is_empty() {
+                text = &text[..line_start.unwrap_or(0)];
+                pos += line_end.map(|i| i + 1).unwrap_or(rest.len());
+            }
+        }
+        if !text.is_empty() {
+            current.push(Node::Text(text.to_owned()));
+        }
+
+        if let Some(name) = tag.strip_prefix('#').or_else(|| tag.strip_prefix('^')) {
+            let name = name.trim();
+            if !valid_name(name) {
+                bail!("Invalid section name '{}' on line {}", name, line);
+            }
+            stack.push(OpenSection {
+                name: name.to_owned(),
+                inverted: tag.starts_with('^'),
+                line,
+                parent: std::mem::take(&mut current),
+            });
+        } else if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            let open = stack
+                .pop()
+                .ok_or_else(|| anyhow!("Unexpected '{{{{/{}}}}}' on line {}", name, line))?;
+            if open.name != name {
+                bail!(
+                    "Section '{}' opened on line {} is closed by '{}' on line {}",
+                    open.name,
+                    open.line,
+                    name,
+                    line
+                );
+            }
+            let children = std::mem::replace(&mut current, open.parent);
+            current.push(Node::Section {
+                name: open.name,
+                inverted: open.inverted,
+                line: open.line,
+                children,
+            });
+        } else {
+            if !valid_name(tag) {
+                bail!("Invalid variable name '{}' on line {}", tag, line);
+            }
+            current.push(Node::Var {
+                name: tag.to_owned(),
+                line,
+            });
+        }
+    }
+
+    if let Some(open) = stack.last() {
+        bail!(
+            "Section '{}' opened on line {} is never closed",
+            open.name,
+            open.line
+        );
+    }
+    if pos < source.len() {
+        current.push(Node::Text(source[pos..].to_owned()));
+    }
+    Ok(current)
+}
+
+fn render_nodes<'a>(
+    nodes: &'a [Node],
+    scopes: &mut Vec<&'a Context>,
+    output: &mut String,
+) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Var { name, line } => {
+                let value = scopes
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.values.get(name.as_str()))
+                    .ok_or_else(|| {
+                        anyhow!("Unknown template variable '{}' on line {}", name, line)
+                    })?;
+                output.push_str(value);
+            }
+            Node::Section {
+                name,
+                inverted,
+                line,
+                children,
+            } => {
+                let items = scopes
+                    .iter()
+                    .rev()
+                    .copied()
+                    .find_map(|scope| scope.sections.get(name.as_str()))
+                    .ok_or_else(|| {
+                        anyhow!("Unknown template section '{}' on line {}", name, line)
+                    })?;
+                if *inverted {
+                    if items.is_empty() {
+                        render_nodes(children, scopes, output)?;
+                    }
+                    continue;
+                }
+                for item in items {
+                    scopes.push(item);
+                    let result = render_nodes(children, scopes, output);
+                    scopes.pop();
+                    result?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 
 use askama::Template;
 
-use shared::data::{NetworkV4, NetworkV6, User};
+use shared::data::{InterfaceMtu, NetworkV4, NetworkV6, User};
 
 #[derive(Template)]
 #[template(path = "juniper/juniper_junos.jinja", ext = "txt")]
@@ -14,4 +14,5 @@ pub struct JunipervJunosZtpTemplate {
     pub mgmt_ipv4_address: Option<Ipv4Addr>,
     pub mgmt_ipv6_address: Option<Ipv6Addr>,
     pub mgmt_ipv6: Option<NetworkV6>,
+    pub interface_mtus: Vec<InterfaceMtu>,
 }
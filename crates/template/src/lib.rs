@@ -13,6 +13,7 @@ mod cisco_nxos;
 mod cloud_init;
 mod cloudbase_init;
 mod cumulus_linux;
+mod custom;
 mod dnsmasq;
 mod domain;
 mod frr;
@@ -43,6 +44,7 @@ pub use cloudbase_init::{
     CloudbaseInitConfig, CloudbaseInitNetwork, CloudbaseInitUser, CloudbaseWriteFile,
 };
 pub use cumulus_linux::CumulusLinuxZtpTemplate;
+pub use custom::CustomZtpTemplate;
 pub use dnsmasq::DnsmasqTemplate;
 pub use domain::{BootServer, DomainTemplate, UnikernelDomainTemplate};
 pub use frr::{FrrDaemonsTemplate, FrrStartupTemplate, FrrZtpTemplate};
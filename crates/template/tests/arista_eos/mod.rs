@@ -2,6 +2,7 @@ use std::net::Ipv4Addr;
 
 use askama::Template;
 
+use shared::data::InterfaceMtu;
 use template::{AristaCeosZtpTemplate, AristaVeosZtpTemplate};
 
 use crate::helpers;
@@ -98,6 +99,40 @@ lldp run
 end
 !";
 
+const EXPECTED_CEOS_INTERFACE_MTUS: &str = "\
+!
+hostname ceos01
+dns domain lab.sherpa.local
+ip name-server 172.20.0.1
+!
+no aaa root
+!
+service routing protocols model multi-agent
+!
+aaa authorization exec default local
+!
+username sherpa privilege 15 secret Everest1953!
+username sherpa ssh-key ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQ
+!
+ip route 0.0.0.0/0 172.20.0.1
+!
+interface Management0
+   ip address 172.20.0.10/24
+!
+interface Ethernet1
+   mtu 9216
+!
+interface Ethernet2
+   mtu 9216
+!
+management api http-commands
+   no shutdown
+!
+lldp run
+!
+end
+!";
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -112,6 +147,7 @@ fn test_veos_static_ipv4() {
         mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
         mgmt_ipv6_address: None,
         mgmt_ipv6: None,
+        interface_mtus: vec![],
     };
     let output = t.render().expect("template renders");
     assert_eq!(output, EXPECTED_VEOS_STATIC_IPV4);
@@ -127,6 +163,7 @@ fn test_ceos_static_ipv4() {
         mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
         mgmt_ipv6_address: None,
         mgmt_ipv6: None,
+        interface_mtus: vec![],
     };
     let output = t.render().expect("template renders");
     assert_eq!(output, EXPECTED_CEOS_STATIC_IPV4);
@@ -142,7 +179,30 @@ fn test_veos_dual_stack() {
         mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
         mgmt_ipv6_address: Some("fd00::10".parse().expect("valid")),
         mgmt_ipv6: Some(helpers::test_network_v6()),
+        interface_mtus: vec![],
     };
     let output = t.render().expect("template renders");
     assert_eq!(output, EXPECTED_VEOS_DUAL_STACK);
 }
+
+#[test]
+fn test_ceos_interface_mtus() {
+    let t = AristaCeosZtpTemplate {
+        hostname: "ceos01".to_string(),
+        user: helpers::test_user(),
+        dns: helpers::test_dns(),
+        mgmt_ipv4: helpers::test_network_v4(),
+        mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
+        mgmt_ipv6_address: None,
+        mgmt_ipv6: None,
+        interface_mtus: ["eth1", "eth2"]
+            .iter()
+            .map(|name| InterfaceMtu {
+                name: name.to_string(),
+                mtu: 9216,
+            })
+            .collect(),
+    };
+    let output = t.render().expect("template renders");
+    assert_eq!(output, EXPECTED_CEOS_INTERFACE_MTUS);
+}
@@ -54,6 +54,7 @@ fn test_static_ipv4() {
         mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
         mgmt_ipv6_address: None,
         mgmt_ipv6: None,
+        interface_mtus: vec![],
     };
     let output = t.render().expect("template renders");
     assert_eq!(output, EXPECTED_STATIC_IPV4);
@@ -0,0 +1,135 @@
+use std::net::Ipv4Addr;
+
+use shared::data::InterfaceMtu;
+use template::CustomZtpTemplate;
+
+use crate::helpers;
+
+const VYOS_TEMPLATE: &str = "\
+set system host-name {{ hostname }}
+set system login user {{ username }} authentication public-keys sherpa key '{{ ssh_public_key }}'
+{{#password}}
+set system login user {{ username }} authentication plaintext-password '{{ password }}'
+{{/password}}
+set interfaces ethernet {{ mgmt_interface }} address {{ mgmt_ipv4_address }}/{{ mgmt_ipv4_prefix_length }}
+{{#ipv6}}
+set interfaces ethernet {{ mgmt_interface }} address {{ mgmt_ipv6_address }}/{{ mgmt_ipv6_prefix_length }}
+{{/ipv6}}
+set protocols static route 0.0.0.0/0 next-hop {{ mgmt_ipv4_gateway }}
+{{#name_servers}}
+set system name-server {{ address }}
+{{/name_servers}}
+{{#interfaces}}
+set interfaces ethernet {{ name }} description data{{ index }}
+{{/interfaces}}
+{{^interfaces}}
+# no data interfaces
+{{/interfaces}}
+";
+
+const EXPECTED_VYOS: &str = "\
+set system host-name vyos01
+set system login user sherpa authentication public-keys sherpa key 'ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAABAQ'
+set system login user sherpa authentication plaintext-password 'Everest1953!'
+set interfaces ethernet eth0 address 172.20.0.10/24
+set protocols static route 0.0.0.0/0 next-hop 172.20.0.1
+set system name-server 172.20.0.1
+set interfaces ethernet eth1 description data1
+set interfaces ethernet eth2 description data2
+";
+
+fn vyos_template() -> CustomZtpTemplate {
+    CustomZtpTemplate {
+        hostname: "vyos01".to_string(),
+        user: helpers::test_user(),
+        dns: helpers::test_dns(),
+        mgmt_interface: "eth0".to_string(),
+        mgmt_ipv4: helpers::test_network_v4(),
+        mgmt_ipv4_address: Ipv4Addr::new(172, 20, 0, 10),
+        mgmt_ipv6_address: None,
+        mgmt_ipv6: None,
+        interfaces: ["eth1", "eth2"]
+            .iter()
+            .map(|name| InterfaceMtu {
+                name: name.to_string(),
+                mtu: 1500,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_custom_template_renders() {
+    let rendered = vyos_template().render(VYOS_TEMPLATE).unwrap();
+    assert_eq!(rendered, EXPECTED_VYOS);
+}
+
+#[test]
+fn test_custom_template_optional_sections() {
+    let mut t = vyos_template();
+    t.user.password = None;
+    t.mgmt_ipv6 = Some(helpers::test_network_v6());
+    t.mgmt_ipv6_address = Some("fd00::10".parse().unwrap());
+    t.interfaces.clear();
+    let rendered = t.render(VYOS_TEMPLATE).unwrap();
+    assert!(!rendered.contains("plaintext-password"));
+    assert!(rendered.contains("address fd00::10/64"));
+    assert!(rendered.contains("# no data interfaces"));
+}
+
+#[test]
+fn test_custom_template_interface_mtu() {
+    let mut t = vyos_template();
+    t.interfaces[1].mtu = 9216;
+    let rendered = t
+        .render("{{#interfaces}}set interfaces ethernet {{ name }} mtu {{ mtu }}\n{{/interfaces}}")
+        .unwrap();
+    assert_eq!(
+        rendered,
+        "set interfaces ethernet eth1 mtu 1500\nset interfaces ethernet eth2 mtu 9216\n"
+    );
+}
+
+#[test]
+fn test_custom_template_inline_sections() {
+    let rendered = vyos_template()
+        .render("user {{ username }}{{#password}} secret {{ password }}{{/password}}")
+        .unwrap();
+    assert_eq!(rendered, "user sherpa secret Everest1953!");
+}
+
+#[test]
+fn test_custom_template_unknown_variable() {
+    let err = vyos_template()
+        .render("line one\nhostname {{ host_name }}")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unknown template variable 'host_name' on line 2"
+    );
+}
+
+#[test]
+fn test_custom_template_section_variable_out_of_scope() {
+    let err = vyos_template().render("{{ address }}").unwrap_err();
+    assert!(err.to_string().contains("address"));
+}
+
+#[test]
+fn test_custom_template_syntax_errors() {
+    assert!(CustomZtpTemplate::check("{{ hostname").is_err());
+    assert!(CustomZtpTemplate::check("{{#interfaces}}{{ name }}").is_err());
+    assert!(CustomZtpTemplate::check("{{#interfaces}}{{/ipv6}}").is_err());
+    assert!(CustomZtpTemplate::check("{{/interfaces}}").is_err());
+    assert!(CustomZtpTemplate::check("{{#vlans}}{{/vlans}}").is_err());
+    assert!(CustomZtpTemplate::check("{{ host-name }}").is_err());
+}
+
+#[test]
+fn test_custom_template_check_accepts_all_names() {
+    CustomZtpTemplate::check(VYOS_TEMPLATE).unwrap();
+    CustomZtpTemplate::check(
+        "{{ domain }} {{ mgmt_ipv4_netmask }}{{#ipv6}}{{ mgmt_ipv6_gateway }}{{/ipv6}}",
+    )
+    .unwrap();
+}
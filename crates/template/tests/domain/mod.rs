@@ -117,3 +117,24 @@ fn test_domain_renders_with_peer_interface() {
     // No isolated network referenced
     assert!(!output.contains("isolated"));
 }
+
+#[test]
+fn test_domain_renders_with_vlan_bridge_interface() {
+    let mut domain = base_domain_template();
+    domain.interfaces.push(Interface {
+        name: "tpv0-a10736e8".to_string(),
+        num: 1,
+        mtu: 1500,
+        mac_address: "52:54:00:dd:ee:ff".to_string(),
+        connection_type: ConnectionTypes::VlanBridge,
+        interface_connection: None,
+    });
+
+    let output = domain
+        .render()
+        .expect("domain template should render with VLAN bridge interface");
+    assert!(output.contains("<target dev='tpv0-a10736e8'/>"));
+    assert!(output.contains("ua-net-dev01-a10736e8-vlan-bridge-tpv0-a10736e8"));
+    // libvirt must not attach the tap to a bridge itself
+    assert!(!output.contains("<source bridge="));
+}
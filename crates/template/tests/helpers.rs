@@ -89,5 +89,6 @@ pub fn test_node_config(model: NodeModel) -> NodeConfig {
         reserved_interface_count: 0,
         default: false,
         boot_mode: None,
+        image_sha256: None,
     }
 }
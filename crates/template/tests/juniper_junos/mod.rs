@@ -2,6 +2,7 @@ use std::net::Ipv4Addr;
 
 use askama::Template;
 
+use shared::data::InterfaceMtu;
 use template::JunipervJunosZtpTemplate;
 
 use crate::helpers;
@@ -68,7 +69,36 @@ fn test_static_ipv4() {
         mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
         mgmt_ipv6_address: None,
         mgmt_ipv6: None,
+        interface_mtus: vec![],
     };
     let output = t.render().expect("template renders");
     assert_eq!(output, EXPECTED_STATIC_IPV4);
 }
+
+#[test]
+fn test_interface_mtus() {
+    let t = JunipervJunosZtpTemplate {
+        hostname: "vsrx01".to_string(),
+        user: helpers::test_user(),
+        mgmt_interface: "fxp0".to_string(),
+        mgmt_ipv4: helpers::test_network_v4(),
+        mgmt_ipv4_address: Some(Ipv4Addr::new(172, 20, 0, 10)),
+        mgmt_ipv6_address: None,
+        mgmt_ipv6: None,
+        interface_mtus: vec![InterfaceMtu {
+            name: "ge-0/0/0".to_string(),
+            mtu: 9216,
+        }],
+    };
+    let output = t.render().expect("template renders");
+    // The Junos MTU includes the 14 byte Ethernet header
+    assert!(output.contains(
+        "
+    }
+    ge-0/0/0 {
+        mtu 9230;
+    }
+}
+protocols {"
+    ));
+}
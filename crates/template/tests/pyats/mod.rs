@@ -47,6 +47,7 @@ fn test_pyats_inventory_from_manifest() {
         bridges: None,
         ztp_server: None,
         config_management: None,
+        mirrors: None,
     };
 
     let node_config = helpers::test_node_config(NodeModel::CiscoIosv);
@@ -12,6 +12,7 @@ mod cisco_nxos;
 mod cloud_init;
 mod cloudbase_init;
 mod cumulus_linux;
+mod custom;
 mod dnsmasq;
 mod domain;
 mod frr;
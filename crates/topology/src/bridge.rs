@@ -1,7 +1,8 @@
 use anyhow::Result;
 use serde_derive::{Deserialize, Serialize};
 
-use shared::data::NodeModel;
+use shared::data::{NodeModel, PortMode, PortVlan, VlanRange};
+use shared::konst::{BRIDGE_PREFIX, TAP_PREFIX};
 use shared::util::split_node_int;
 
 /// Bridge connection in manifest format
@@ -26,9 +27,25 @@ impl BridgeLink {
 pub struct Bridge {
     pub name: String,
     pub links: Vec<String>,
+    /// VLAN settings of members, any entry makes the bridge VLAN-aware
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<BridgePort>,
 }
 
 impl Bridge {
+    /// Whether the bridge filters frames by VLAN
+    pub fn is_vlan_aware(&self) -> bool {
+        !self.ports.is_empty()
+    }
+
+    /// VLAN settings of a member, `None` for members left in the default VLAN
+    pub fn port(&self, node: &str, interface: &str) -> Option<&BridgePort> {
+        self.ports
+            .iter()
+            .find(|port| split_node_int(&port.link).is_ok_and(|(n, i)| n == node && i == interface))
+    }
+
     /// Parse all links in this bridge
     pub fn parse_links(&self) -> Result<BridgeExpanded> {
         let bridge_links = self
@@ -44,6 +61,118 @@ impl Bridge {
     }
 }
 
+/// VLAN settings of one member of a shared bridge
+/// {link = "node_name::interface_name", mode = "access", vlan = 10}
+/// {link = "node_name::interface_name", mode = "trunk", allowed_vlans = [10, "100-199"], native_vlan = 1}
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct BridgePort {
+    /// Bridge member as "node_name::interface_name"
+    pub link: String,
+    pub mode: PortMode,
+    /// VLAN of an access port
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+    /// VLANs a trunk port carries tagged
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_vlans: Vec<VlanRange>,
+    /// VLAN a trunk port carries untagged
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub native_vlan: Option<u16>,
+}
+
+impl BridgePort {
+    /// Kernel bridge VLAN entries of the port.
+    ///
+    /// The native VLAN is cut out of the allowed ranges so it is only
+    /// programmed once, untagged.
+    pub fn vlans(&self) -> Vec<PortVlan> {
+        let native = match self.mode {
+            PortMode::Access => self.vlan,
+            PortMode::Trunk => self.native_vlan,
+        };
+
+        let mut vlans = vec![];
+        if self.mode == PortMode::Trunk {
+            for range in &self.allowed_vlans {
+                let pieces = match native {
+                    Some(native) if range.contains(native) => [
+                        (range.first < native).then(|| VlanRange {
+                            first: range.first,
+                            last: native - 1,
+                        }),
+                        (native < range.last).then(|| VlanRange {
+                            first: native + 1,
+                            last: range.last,
+                        }),
+                    ],
+                    _ => [Some(*range), None],
+                };
+                vlans.extend(pieces.into_iter().flatten().map(|vlans| PortVlan {
+                    vlans,
+                    native: false,
+                }));
+            }
+        }
+        if let Some(native) = native {
+            vlans.push(PortVlan {
+                vlans: VlanRange::single(native),
+                native: true,
+            });
+        }
+        vlans
+    }
+}
+
+/// Host tap of a member of a VLAN-aware bridge.
+///
+/// VM members of VLAN-aware bridges are connected through a named tap
+/// instead of a libvirt bridge interface, so their VLANs can be programmed
+/// after the VM starts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BridgePortTap {
+    pub bridge_name: String,
+    pub node: String,
+    pub interface: String,
+    pub tap_name: String,
+    pub vlans: Vec<PortVlan>,
+}
+
+/// Taps of the members of every VLAN-aware bridge, numbered across the lab
+/// in manifest order. Members without `ports` settings are untagged in the
+/// default VLAN 1.
+pub fn bridge_port_taps(bridges: &Option<Vec<Bridge>>, lab_id: &str) -> Vec<BridgePortTap> {
+    let mut taps = vec![];
+    for (bridge_idx, bridge) in bridges.iter().flatten().enumerate() {
+        if !bridge.is_vlan_aware() {
+            continue;
+        }
+        for link in &bridge.links {
+            let Ok((node, interface)) = split_node_int(link) else {
+                continue;
+            };
+            let vlans = match bridge.port(&node, &interface) {
+                Some(port) => port.vlans(),
+                None => vec![PortVlan {
+                    vlans: VlanRange::single(1),
+                    native: true,
+                }],
+            };
+            taps.push(BridgePortTap {
+                bridge_name: format!("{}s{}-{}", BRIDGE_PREFIX, bridge_idx, lab_id),
+                tap_name: format!("{}v{}-{}", TAP_PREFIX, taps.len(), lab_id),
+                node,
+                interface,
+                vlans,
+            });
+        }
+    }
+    taps
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct BridgeExpanded {
     pub name: String,
@@ -65,6 +194,9 @@ pub struct BridgeDetailed {
     pub libvirt_name: String,
     pub index: u16,
     pub links: Vec<BridgeLinkDetailed>,
+    /// Host interface enslaved to the bridge, set for external links
+    #[serde(default)]
+    pub external_interface: Option<String>,
 }
 
 /// Detailed bridge connection with all resolved information
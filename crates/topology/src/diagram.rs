@@ -0,0 +1,728 @@
+//! Topology diagram rendering (Graphviz DOT, Mermaid and SVG).
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use shared::data::{NodeModel, NodeState};
+
+use super::bridge::BridgeDetailed;
+use super::link::LinkDetailed;
+use super::node::NodeExpanded;
+
+const NODE_WIDTH: f64 = 128.0;
+const NODE_HEIGHT: f64 = 44.0;
+const BRIDGE_WIDTH: f64 = 96.0;
+const BRIDGE_HEIGHT: f64 = 26.0;
+const PARALLEL_LINK_GAP: f64 = 14.0;
+
+/// A node in a topology diagram.
+#[derive(Clone, Debug)]
+pub struct DiagramNode {
+    pub name: String,
+    pub model: NodeModel,
+    /// Runtime state, if known. Nodes without a state use a neutral colour.
+    pub state: Option<NodeState>,
+    /// Link target for the node in SVG output.
+    pub href: Option<String>,
+}
+
+/// A point-to-point link in a topology diagram.
+#[derive(Clone, Debug)]
+pub struct DiagramLink {
+    pub node_a: String,
+    pub int_a: String,
+    pub node_b: String,
+    pub int_b: String,
+    /// Impairment summary shown as a badge on the link.
+    pub impairment: Option<String>,
+    /// Whether both ends are running, if known.
+    pub up: Option<bool>,
+}
+
+/// A shared bridge and the node interfaces attached to it.
+#[derive(Clone, Debug)]
+pub struct DiagramBridge {
+    pub name: String,
+    /// Member `(node, interface)` pairs. The interface may be unknown.
+    pub members: Vec<(String, Option<String>)>,
+}
+
+/// Topology of a lab, ready to be rendered.
+#[derive(Clone, Debug, Default)]
+pub struct Diagram {
+    pub name: String,
+    pub nodes: Vec<DiagramNode>,
+    pub links: Vec<DiagramLink>,
+    pub bridges: Vec<DiagramBridge>,
+}
+
+impl Diagram {
+    /// Build a diagram from processed manifest data.
+    pub fn from_manifest(
+        name: &str,
+        nodes: &[NodeExpanded],
+        links: &[LinkDetailed],
+        bridges: &[BridgeDetailed],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            nodes: nodes
+                .iter()
+                .map(|node| DiagramNode {
+                    name: node.name.clone(),
+                    model: node.model,
+                    state: None,
+                    href: None,
+                })
+                .collect(),
+            links: links
+                .iter()
+                .map(|link| DiagramLink {
+                    node_a: link.node_a.clone(),
+                    int_a: link.int_a.clone(),
+                    node_b: link.node_b.clone(),
+                    int_b: link.int_b.clone(),
+                    impairment: link.impairment.as_ref().and_then(|imp| {
+                        impairment_label(
+                            imp.delay.unwrap_or(0).saturating_mul(1000),
+                            imp.jitter.unwrap_or(0).saturating_mul(1000),
+                            imp.loss_percent.unwrap_or(0.0),
+                            imp.reorder_percent.unwrap_or(0.0),
+                            imp.corrupt_percent.unwrap_or(0.0),
+                        )
+                    }),
+                    up: None,
+                })
+                .collect(),
+            bridges: bridges
+                .iter()
+                .map(|bridge| DiagramBridge {
+                    name: bridge.manifest_name.clone(),
+                    members: bridge
+                        .links
+                        .iter()
+                        .map(|member| {
+                            (
+                                member.node_name.clone(),
+                                Some(member.interface_name.clone()),
+                            )
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Render as a Graphviz DOT graph.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "graph \"{}\" {{", dot_escape(&self.name));
+        let _ = writeln!(out, "  graph [overlap=false, splines=true];");
+        let _ = writeln!(
+            out,
+            "  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\", fontcolor=\"white\"];"
+        );
+        let _ = writeln!(out, "  edge [fontname=\"Helvetica\", fontsize=10];");
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "  \"{}\" [label=\"{}\\n{}\", fillcolor=\"{}\"];",
+                dot_escape(&node.name),
+                dot_escape(&node.name),
+                node.model,
+                state_colour(node.state)
+            );
+        }
+        for bridge in &self.bridges {
+            let _ = writeln!(
+                out,
+                "  \"bridge:{}\" [label=\"{}\", shape=ellipse, style=dashed, fontcolor=\"black\"];",
+                dot_escape(&bridge.name),
+                dot_escape(&bridge.name)
+            );
+        }
+        for link in &self.links {
+            let mut attrs = format!(
+                "taillabel=\"{}\", headlabel=\"{}\"",
+                dot_escape(&link.int_a),
+                dot_escape(&link.int_b)
+            );
+            if let Some(impairment) = &link.impairment {
+                let _ = write!(attrs, ", label=\"{}\"", dot_escape(impairment));
+            }
+            if link.up == Some(false) {
+                attrs.push_str(", style=dashed");
+            }
+            let _ = writeln!(
+                out,
+                "  \"{}\" -- \"{}\" [{}];",
+                dot_escape(&link.node_a),
+                dot_escape(&link.node_b),
+                attrs
+            );
+        }
+        for bridge in &self.bridges {
+            for (node, interface) in &bridge.members {
+                let label = interface
+                    .as_deref()
+                    .map(|i| format!(" [taillabel=\"{}\"]", dot_escape(i)))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "  \"{}\" -- \"bridge:{}\"{};",
+                    dot_escape(node),
+                    dot_escape(&bridge.name),
+                    label
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.name.as_str(), format!("n{i}")))
+            .collect();
+        let node_id = |name: &str| {
+            ids.get(name)
+                .cloned()
+                .unwrap_or_else(|| format!("x_{}", mermaid_id(name)))
+        };
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "  {}[\"{}<br/><small>{}</small>\"]",
+                node_id(&node.name),
+                mermaid_escape(&node.name),
+                node.model
+            );
+        }
+        for (i, bridge) in self.bridges.iter().enumerate() {
+            let _ = writeln!(out, "  b{}((\"{}\"))", i, mermaid_escape(&bridge.name));
+        }
+        for link in &self.links {
+            let mut label = format!(
+                "{} - {}",
+                mermaid_escape(&link.int_a),
+                mermaid_escape(&link.int_b)
+            );
+            if let Some(impairment) = &link.impairment {
+                let _ = write!(label, "<br/>{}", mermaid_escape(impairment));
+            }
+            let edge = if link.up == Some(false) { "-.-" } else { "---" };
+            let _ = writeln!(
+                out,
+                "  {} {}|\"{}\"| {}",
+                node_id(&link.node_a),
+                edge,
+                label,
+                node_id(&link.node_b)
+            );
+        }
+        for (i, bridge) in self.bridges.iter().enumerate() {
+            for (node, interface) in &bridge.members {
+                match interface {
+                    Some(interface) => {
+                        let _ = writeln!(
+                            out,
+                            "  {} ---|\"{}\"| b{}",
+                            node_id(node),
+                            mermaid_escape(interface),
+                            i
+                        );
+                    }
+                    None => {
+                        let _ = writeln!(out, "  {} --- b{}", node_id(node), i);
+                    }
+                }
+            }
+        }
+
+        // Colour nodes by state when any state is known.
+        if self.nodes.iter().any(|node| node.state.is_some()) {
+            for state in [
+                NodeState::Running,
+                NodeState::Created,
+                NodeState::Starting,
+                NodeState::Stopped,
+                NodeState::Failed,
+                NodeState::Unknown,
+            ] {
+                let members: Vec<String> = self
+                    .nodes
+                    .iter()
+                    .filter(|node| node.state == Some(state))
+                    .map(|node| node_id(&node.name))
+                    .collect();
+                if members.is_empty() {
+                    continue;
+                }
+                let _ = writeln!(
+                    out,
+                    "  classDef {} fill:{},color:#fff",
+                    state,
+                    state_colour(Some(state))
+                );
+                let _ = writeln!(out, "  class {} {}", members.join(","), state);
+            }
+        }
+        out
+    }
+
+    /// Render as a standalone SVG document.
+    ///
+    /// Nodes are placed on a circle with shared bridges inside it. Nodes with
+    /// an `href` are clickable and every element carries a hover tooltip.
+    pub fn to_svg(&self) -> String {
+        let layout = Layout::new(self.nodes.len(), self.bridges.len());
+        let node_pos: HashMap<&str, (f64, f64)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.name.as_str(), layout.nodes[i]))
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" class="sherpa-topology" viewBox="0 0 {:.0} {:.0}" width="100%" font-family="Helvetica, Arial, sans-serif" role="img" aria-label="Topology of {}">"#,
+            layout.width,
+            layout.height,
+            xml_escape(&self.name)
+        );
+        out.push_str(concat!(
+            "<style>",
+            ".node:hover rect{stroke:#0f172a;stroke-width:3}",
+            ".link:hover line{stroke-width:4}",
+            ".iface{font-size:10px;fill:#334155;paint-order:stroke;stroke:#fff;stroke-width:3px}",
+            "</style>\n"
+        ));
+
+        // Bridge members first so links and nodes are drawn on top.
+        for (i, bridge) in self.bridges.iter().enumerate() {
+            let (bx, by) = layout.bridges[i];
+            for (node, interface) in &bridge.members {
+                let Some(&(nx, ny)) = node_pos.get(node.as_str()) else {
+                    continue;
+                };
+                let member = interface
+                    .as_deref()
+                    .map_or(node.to_string(), |i| format!("{node}::{i}"));
+                let _ = writeln!(
+                    out,
+                    r##"<g class="link"><title>{} - {}</title><line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#94a3b8" stroke-width="2"/>"##,
+                    xml_escape(&member),
+                    xml_escape(&bridge.name),
+                    nx,
+                    ny,
+                    bx,
+                    by
+                );
+                if let Some(interface) = interface {
+                    let (lx, ly) = lerp((nx, ny), (bx, by), 0.3);
+                    let _ = writeln!(
+                        out,
+                        r#"<text class="iface" x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                        lx,
+                        ly,
+                        xml_escape(interface)
+                    );
+                }
+                out.push_str("</g>\n");
+            }
+        }
+
+        // Offset parallel links between the same pair of nodes.
+        let mut pair_count: HashMap<(&str, &str), usize> = HashMap::new();
+        for link in &self.links {
+            *pair_count.entry(pair_key(link)).or_default() += 1;
+        }
+        let mut pair_seen: HashMap<(&str, &str), usize> = HashMap::new();
+
+        for link in &self.links {
+            let (Some(&a), Some(&b)) = (
+                node_pos.get(link.node_a.as_str()),
+                node_pos.get(link.node_b.as_str()),
+            ) else {
+                continue;
+            };
+            let key = pair_key(link);
+            let total = pair_count.get(&key).copied().unwrap_or(1);
+            let seen = pair_seen.entry(key).or_default();
+            let offset = (*seen as f64 - (total as f64 - 1.0) / 2.0) * PARALLEL_LINK_GAP;
+            *seen += 1;
+            let (a, b) = offset_segment(a, b, offset);
+
+            let (stroke, dash) = match link.up {
+                Some(false) => ("#cbd5e1", r#" stroke-dasharray="6 4""#),
+                _ => ("#64748b", ""),
+            };
+            let _ = writeln!(
+                out,
+                r#"<g class="link"><title>{}::{} - {}::{}{}</title><line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"{}/>"#,
+                xml_escape(&link.node_a),
+                xml_escape(&link.int_a),
+                xml_escape(&link.node_b),
+                xml_escape(&link.int_b),
+                link.impairment
+                    .as_deref()
+                    .map(|i| format!(" ({})", xml_escape(i)))
+                    .unwrap_or_default(),
+                a.0,
+                a.1,
+                b.0,
+                b.1,
+                stroke,
+                dash
+            );
+            for (pos, interface) in [
+                (lerp(a, b, 0.25), &link.int_a),
+                (lerp(a, b, 0.75), &link.int_b),
+            ] {
+                let _ = writeln!(
+                    out,
+                    r#"<text class="iface" x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                    pos.0,
+                    pos.1,
+                    xml_escape(interface)
+                );
+            }
+            if let Some(impairment) = &link.impairment {
+                let (mx, my) = lerp(a, b, 0.5);
+                let width = impairment.chars().count() as f64 * 6.0 + 12.0;
+                let _ = writeln!(
+                    out,
+                    r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="16" rx="8" fill="#fef3c7" stroke="#f59e0b"/><text x="{:.1}" y="{:.1}" font-size="10" fill="#92400e" text-anchor="middle">{}</text>"##,
+                    mx - width / 2.0,
+                    my - 8.0,
+                    width,
+                    mx,
+                    my + 3.5,
+                    xml_escape(impairment)
+                );
+            }
+            out.push_str("</g>\n");
+        }
+
+        for (i, bridge) in self.bridges.iter().enumerate() {
+            let (x, y) = layout.bridges[i];
+            let _ = writeln!(
+                out,
+                r##"<g class="bridge"><title>Bridge {}</title><rect x="{:.1}" y="{:.1}" width="{BRIDGE_WIDTH}" height="{BRIDGE_HEIGHT}" rx="13" fill="#f8fafc" stroke="#64748b" stroke-dasharray="4 3"/><text x="{:.1}" y="{:.1}" font-size="11" fill="#334155" text-anchor="middle">{}</text></g>"##,
+                xml_escape(&bridge.name),
+                x - BRIDGE_WIDTH / 2.0,
+                y - BRIDGE_HEIGHT / 2.0,
+                x,
+                y + 4.0,
+                xml_escape(&bridge.name)
+            );
+        }
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let (x, y) = layout.nodes[i];
+            let state = node
+                .state
+                .map(|state| format!(" ({state})"))
+                .unwrap_or_default();
+            let mut group = format!(
+                r##"<g class="node"><title>{} - {}{}</title><rect x="{:.1}" y="{:.1}" width="{NODE_WIDTH}" height="{NODE_HEIGHT}" rx="8" fill="{}" stroke="#1e293b" stroke-width="1"/><text x="{:.1}" y="{:.1}" font-size="13" font-weight="bold" fill="#fff" text-anchor="middle">{}</text><text x="{:.1}" y="{:.1}" font-size="10" fill="#fff" text-anchor="middle">{}</text></g>"##,
+                xml_escape(&node.name),
+                node.model,
+                state,
+                x - NODE_WIDTH / 2.0,
+                y - NODE_HEIGHT / 2.0,
+                state_colour(node.state),
+                x,
+                y - 3.0,
+                xml_escape(&node.name),
+                x,
+                y + 13.0,
+                node.model
+            );
+            if let Some(href) = &node.href {
+                group = format!(r#"<a href="{}">{}</a>"#, xml_escape(href), group);
+            }
+            out.push_str(&group);
+            out.push('\n');
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+/// Summarise link impairment values for display, e.g. `20ms ±5ms, 1% loss`.
+///
+/// Returns `None` when no impairment is configured.
+pub fn impairment_label(
+    delay_us: u32,
+    jitter_us: u32,
+    loss_percent: f32,
+    reorder_percent: f32,
+    corrupt_percent: f32,
+) -> Option<String> {
+    let mut parts = vec![];
+    if delay_us > 0 {
+        let mut delay = format!("{}ms", f64::from(delay_us) / 1000.0);
+        if jitter_us > 0 {
+            let _ = write!(delay, " ±{}ms", f64::from(jitter_us) / 1000.0);
+        }
+        parts.push(delay);
+    }
+    if loss_percent > 0.0 {
+        parts.push(format!("{loss_percent}% loss"));
+    }
+    if reorder_percent > 0.0 {
+        parts.push(format!("{reorder_percent}% reorder"));
+    }
+    if corrupt_percent > 0.0 {
+        parts.push(format!("{corrupt_percent}% corrupt"));
+    }
+    (!parts.is_empty()).then(|| parts.join(", "))
+}
+
+/// Fill colour for a node state.
+fn state_colour(state: Option<NodeState>) -> &'static str {
+    match state {
+        None => "#2563eb",
+        Some(NodeState::Running) => "#16a34a",
+        Some(NodeState::Created | NodeState::Starting) => "#d97706",
+        Some(NodeState::Failed) => "#dc2626",
+        Some(NodeState::Stopped | NodeState::Unknown) => "#6b7280",
+    }
+}
+
+/// Positions of nodes and bridges in the SVG canvas.
+struct Layout {
+    width: f64,
+    height: f64,
+    nodes: Vec<(f64, f64)>,
+    bridges: Vec<(f64, f64)>,
+}
+
+impl Layout {
+    fn new(node_count: usize, bridge_count: usize) -> Self {
+        let circumference = node_count as f64 * (NODE_WIDTH + 40.0);
+        let radius = (circumference / (2.0 * PI)).max(160.0);
+        let width = 2.0 * radius + NODE_WIDTH + 80.0;
+        let height = 2.0 * radius + NODE_HEIGHT + 80.0;
+        let centre = (width / 2.0, height / 2.0);
+
+        let ring = |count: usize, radius: f64| -> Vec<(f64, f64)> {
+            (0..count)
+                .map(|i| {
+                    let angle = -PI / 2.0 + 2.0 * PI * i as f64 / count as f64;
+                    (
+                        centre.0 + radius * angle.cos(),
+                        centre.1 + radius * angle.sin(),
+                    )
+                })
+                .collect()
+        };
+
+        let bridges = match bridge_count {
+            0 => vec![],
+            1 => vec![centre],
+            count => ring(count, radius * 0.45),
+        };
+
+        Self {
+            width,
+            height,
+            nodes: ring(node_count, radius),
+            bridges,
+        }
+    }
+}
+
+fn pair_key(link: &DiagramLink) -> (&str, &str) {
+    if link.node_a <= link.node_b {
+        (&link.node_a, &link.node_b)
+    } else {
+        (&link.node_b, &link.node_a)
+    }
+}
+
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Shift a segment sideways by `offset` pixels.
+fn offset_segment(a: (f64, f64), b: (f64, f64), offset: f64) -> ((f64, f64), (f64, f64)) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 || offset == 0.0 {
+        return (a, b);
+    }
+    let (nx, ny) = (-dy / length * offset, dx / length * offset);
+    ((a.0 + nx, a.1 + ny), (b.0 + nx, b.1 + ny))
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn mermaid_id(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagram() -> Diagram {
+        Diagram {
+            name: "lab".to_string(),
+            nodes: vec![
+                DiagramNode {
+                    name: "r1".to_string(),
+                    model: NodeModel::CiscoIosv,
+                    state: Some(NodeState::Running),
+                    href: Some("/labs/abc/nodes/r1".to_string()),
+                },
+                DiagramNode {
+                    name: "r2".to_string(),
+                    model: NodeModel::AristaVeos,
+                    state: Some(NodeState::Stopped),
+                    href: None,
+                },
+            ],
+            links: vec![DiagramLink {
+                node_a: "r1".to_string(),
+                int_a: "gig0/1".to_string(),
+                node_b: "r2".to_string(),
+                int_b: "eth1".to_string(),
+                impairment: impairment_label(20_000, 5_000, 1.0, 0.0, 0.0),
+                up: Some(false),
+            }],
+            bridges: vec![DiagramBridge {
+                name: "lan".to_string(),
+                members: vec![
+                    ("r1".to_string(), Some("gig0/2".to_string())),
+                    ("r2".to_string(), None),
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_impairment_label() {
+        assert_eq!(impairment_label(0, 0, 0.0, 0.0, 0.0), None);
+        assert_eq!(
+            impairment_label(20_000, 5_000, 1.0, 0.0, 0.0).as_deref(),
+            Some("20ms ±5ms, 1% loss")
+        );
+        assert_eq!(
+            impairment_label(500, 0, 0.0, 2.5, 0.1).as_deref(),
+            Some("0.5ms, 2.5% reorder, 0.1% corrupt")
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = diagram().to_dot();
+        assert!(dot.starts_with("graph \"lab\" {"));
+        assert!(dot.contains("\"r1\" [label=\"r1\\ncisco_iosv\", fillcolor=\"#16a34a\"];"));
+        assert!(dot.contains(
+            "\"r1\" -- \"r2\" [taillabel=\"gig0/1\", headlabel=\"eth1\", label=\"20ms ±5ms, 1% loss\", style=dashed];"
+        ));
+        assert!(dot.contains("\"r1\" -- \"bridge:lan\" [taillabel=\"gig0/2\"];"));
+        assert!(dot.contains("\"r2\" -- \"bridge:lan\";"));
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = diagram().to_mermaid();
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("n0[\"r1<br/><small>cisco_iosv</small>\"]"));
+        assert!(mermaid.contains("b0((\"lan\"))"));
+        assert!(mermaid.contains("n0 -.-|\"gig0/1 - eth1<br/>20ms ±5ms, 1% loss\"| n1"));
+        assert!(mermaid.contains("n0 ---|\"gig0/2\"| b0"));
+        assert!(mermaid.contains("class n0 running"));
+        assert!(mermaid.contains("class n1 stopped"));
+    }
+
+    #[test]
+    fn test_to_mermaid_without_state_has_no_classes() {
+        let mut diagram = diagram();
+        for node in &mut diagram.nodes {
+            node.state = None;
+        }
+        assert!(!diagram.to_mermaid().contains("classDef"));
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let svg = diagram().to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<a href=\"/labs/abc/nodes/r1\">"));
+        assert!(svg.contains("fill=\"#16a34a\""));
+        assert!(svg.contains("stroke-dasharray=\"6 4\""));
+        assert!(svg.contains("20ms ±5ms, 1% loss"));
+        assert!(svg.contains("<title>Bridge lan</title>"));
+    }
+
+    #[test]
+    fn test_to_svg_escapes_text() {
+        let mut diagram = diagram();
+        diagram.nodes[0].name = "r<1>".to_string();
+        diagram.links[0].node_a = "r<1>".to_string();
+        let svg = diagram.to_svg();
+        assert!(svg.contains("r&lt;1&gt;"));
+        assert!(!svg.contains("r<1>"));
+    }
+
+    #[test]
+    fn test_from_manifest() {
+        let nodes = vec![
+            NodeExpanded {
+                name: "dev01".to_string(),
+                model: NodeModel::UbuntuLinux,
+                ..Default::default()
+            },
+            NodeExpanded {
+                name: "dev02".to_string(),
+                model: NodeModel::UbuntuLinux,
+                ..Default::default()
+            },
+        ];
+        let links = vec![LinkDetailed {
+            node_a: "dev01".to_string(),
+            int_a: "eth1".to_string(),
+            node_b: "dev02".to_string(),
+            int_b: "eth1".to_string(),
+            impairment: Some(crate::link::ManifestImpairment {
+                delay: Some(10),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
+        let diagram = Diagram::from_manifest("lab", &nodes, &links, &[]);
+        assert_eq!(diagram.nodes.len(), 2);
+        assert_eq!(diagram.links[0].impairment.as_deref(), Some("10ms"));
+        assert!(diagram.nodes.iter().all(|node| node.state.is_none()));
+    }
+}
@@ -2,14 +2,19 @@
 #![cfg_attr(not(test), forbid(unsafe_code))]
 
 mod bridge;
+mod diagram;
 mod link;
 mod manifest;
+mod mirror;
 mod node;
 
 // re-export
 pub use bridge::{
     Bridge, BridgeDetailed, BridgeExpanded, BridgeLink, BridgeLinkDetailed, BridgeLinkExpanded,
+    BridgePort, BridgePortTap, bridge_port_taps,
 };
-pub use link::{Link, Link2, LinkDetailed, LinkExpanded};
+pub use diagram::{Diagram, DiagramBridge, DiagramLink, DiagramNode, impairment_label};
+pub use link::{ExternalLink, Link, Link2, LinkDetailed, LinkExpanded};
 pub use manifest::Manifest;
+pub use mirror::{Mirror, MirrorExpanded, mirror_ports};
 pub use node::{Node, NodeExpanded, StartupScript, TextFile, TextFileData, VolumeMount};
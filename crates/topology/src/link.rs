@@ -1,7 +1,8 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use serde_derive::{Deserialize, Serialize};
 
-use shared::data::NodeModel;
+use shared::data::{LinkFilter, NodeModel};
+use shared::konst::EXTERNAL_HOST_NODE;
 use shared::util::split_node_int;
 
 /// Manifest Link
@@ -37,6 +38,8 @@ pub struct LinkExpanded {
     pub int_b: String,
     pub p2p: bool,
     pub impairment: Option<ManifestImpairment>,
+    pub filter: Option<LinkFilter>,
+    pub mtu: Option<u16>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -54,6 +57,16 @@ pub struct LinkDetailed {
     pub link_idx: u16,
     pub p2p: bool,
     pub impairment: Option<ManifestImpairment>,
+    pub filter: Option<LinkFilter>,
+    pub mtu: Option<u16>,
+}
+
+/// Link from a node interface to a host interface
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExternalLink {
+    pub node: String,
+    pub interface: String,
+    pub host_interface: String,
 }
 
 /// Manifest Link
@@ -68,12 +81,66 @@ pub struct Link2 {
     pub dst: String,
     pub p2p: Option<bool>,
     pub impairment: Option<ManifestImpairment>,
+    pub filter: Option<LinkFilter>,
+    /// MTU of both ends of the link, overrides the node `mtu`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
 }
 
 impl Link2 {
+    /// Whether one side of the link is a host interface (`host::<interface>`)
+    pub fn is_external(&self) -> bool {
+        [&self.src, &self.dst].iter().any(|side| {
+            side.split_once("::")
+                .is_some_and(|(node, _)| node == EXTERNAL_HOST_NODE)
+        })
+    }
+
+    /// Expand a link to a host interface.
+    ///
+    /// The host side can be either `src` or `dst`. External links are
+    /// bridged, so `p2p`, `impairment`, `filter` and `mtu` are not supported.
+    pub fn expand_external(&self) -> Result<ExternalLink> {
+        let (node_a, int_a) = split_node_int(&self.src)?;
+        let (node_b, int_b) = split_node_int(&self.dst)?;
+
+        let (node, interface, host_interface) =
+            match (node_a == EXTERNAL_HOST_NODE, node_b == EXTERNAL_HOST_NODE) {
+                (false, true) => (node_a, int_a, int_b),
+                (true, false) => (node_b, int_b, int_a),
+                _ => bail!(
+                    "External link must connect a node to the host: {} <-> {}",
+                    self.src,
+                    self.dst
+                ),
+            };
+        if self.p2p.is_some()
+            || self.impairment.is_some()
+            || self.filter.is_some()
+            || self.mtu.is_some()
+        {
+            bail!(
+                "External link {}::{} does not support p2p, impairment, filter or mtu",
+                node,
+                interface
+            );
+        }
+
+        Ok(ExternalLink {
+            node,
+            interface,
+            host_interface,
+        })
+    }
+
     pub fn expand(&self) -> Result<LinkExpanded> {
         let (node_a, int_a) = split_node_int(&self.src)?;
         let (node_b, int_b) = split_node_int(&self.dst)?;
+        if let Some(filter) = &self.filter {
+            filter
+                .validate()
+                .with_context(|| format!("Link {} <-> {} filter", self.src, self.dst))?;
+        }
 
         Ok(LinkExpanded {
             node_a,
@@ -82,6 +149,77 @@ impl Link2 {
             int_b,
             p2p: self.p2p.unwrap_or(false),
             impairment: self.impairment.clone(),
+            filter: self.filter.clone(),
+            mtu: self.mtu,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(src: &str, dst: &str) -> Link2 {
+        Link2 {
+            src: src.to_string(),
+            dst: dst.to_string(),
+            p2p: None,
+            impairment: None,
+            filter: None,
+            mtu: None,
+        }
+    }
+
+    #[test]
+    fn test_expand_external_either_side() {
+        for external in [
+            link("r1::eth3", "host::enp5s0.120"),
+            link("host::enp5s0.120", "r1::eth3"),
+        ] {
+            assert!(external.is_external());
+            let expanded = external.expand_external().unwrap();
+            assert_eq!(expanded.node, "r1");
+            assert_eq!(expanded.interface, "eth3");
+            assert_eq!(expanded.host_interface, "enp5s0.120");
+        }
+        assert!(!link("r1::eth1", "r2::eth1").is_external());
+    }
+
+    #[test]
+    fn test_expand_external_rejects_invalid_links() {
+        assert!(
+            link("host::enp5s0", "host::enp6s0")
+                .expand_external()
+                .is_err()
+        );
+
+        let mut p2p = link("r1::eth3", "host::enp5s0");
+        p2p.p2p = Some(true);
+        assert!(p2p.expand_external().is_err());
+
+        let mut filtered = link("r1::eth3", "host::enp5s0");
+        filtered.filter = Some(LinkFilter::default());
+        assert!(filtered.expand_external().is_err());
+
+        let mut jumbo = link("r1::eth3", "host::enp5s0");
+        jumbo.mtu = Some(9216);
+        assert!(jumbo.expand_external().is_err());
+    }
+
+    #[test]
+    fn test_expand_validates_filter() {
+        let mut filtered = link("r1::eth1", "r2::eth1");
+        filtered.filter = Some(LinkFilter {
+            drop: vec!["lacp".parse().unwrap()],
+            allow: vec![],
+        });
+        assert!(filtered.expand().unwrap().filter.is_some());
+
+        filtered.filter = Some(LinkFilter {
+            drop: vec!["vlan:4095".parse().unwrap()],
+            allow: vec![],
+        });
+        let err = filtered.expand().unwrap_err();
+        assert!(format!("{err:#}").contains("VLAN IDs are 1-4094"));
+    }
+}
@@ -1,13 +1,18 @@
 use std::fs;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use serde_derive::{Deserialize, Serialize};
-use toml_edit::{Array, DocumentMut, InlineTable, Item, Value};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, Value};
 
 use super::bridge::Bridge;
-use super::link::Link2;
+use super::link::{Link2, LinkExpanded};
+use super::mirror::{Mirror, mirror_ports};
 use super::node::Node;
-use shared::data::{ConfigurationManagement, NodeModel, ZtpServer};
+use shared::data::{
+    ConfigurationManagement, NodeModel, ZtpServer, parse_custom_model_ref,
+    validate_custom_model_name,
+};
+use shared::konst::CUSTOM_MODEL_PREFIX;
 use shared::util::{generate_lab_name, load_file as load_file_util};
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -26,6 +31,9 @@ pub struct Manifest {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_management: Option<ConfigurationManagement>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirrors: Option<Vec<Mirror>>,
 }
 
 impl Manifest {
@@ -48,6 +56,8 @@ impl Manifest {
             dst: format!("{}::{}", dev02.name.clone(), "eth1"),
             p2p: None,
             impairment: None,
+            filter: None,
+            mtu: None,
         }];
 
         let nodes: Vec<Node> = vec![dev01, dev02];
@@ -84,7 +94,10 @@ impl Manifest {
             let mut device_table = InlineTable::new();
             device_table.decor_mut().set_prefix("\n  ");
             device_table.insert("name", Value::from(device.name.as_str()));
-            device_table.insert("model", Value::from(device.model.to_string()));
+            device_table.insert("model", Value::from(device.model_reference()));
+            if let Some(version) = &device.version {
+                device_table.insert("version", Value::from(version.as_str()));
+            }
             devices_array.push_formatted(Value::from(device_table));
         }
 
@@ -107,20 +120,212 @@ impl Manifest {
             doc["links"] = Item::Value(Value::Array(link_array));
         }
 
+        // Add bridges array if present
+        if let Some(bridges) = &self.bridges {
+            let mut bridge_array = Array::new();
+            bridge_array.set_trailing_comma(true);
+            bridge_array.set_trailing("\n");
+            bridge_array.decor_mut().set_suffix("\n");
+
+            for bridge in bridges {
+                let mut members = Array::new();
+                for link in &bridge.links {
+                    members.push(link.as_str());
+                }
+                let mut bridge_table = InlineTable::new();
+                bridge_table.decor_mut().set_prefix("\n  ");
+                bridge_table.insert("name", Value::from(bridge.name.as_str()));
+                bridge_table.insert("links", Value::Array(members));
+                bridge_array.push_formatted(Value::from(bridge_table));
+            }
+            doc["bridges"] = Item::Value(Value::Array(bridge_array));
+        }
+
+        // Add mirrors array if present
+        if let Some(mirrors) = &self.mirrors {
+            let mut mirror_array = Array::new();
+            mirror_array.set_trailing_comma(true);
+            mirror_array.set_trailing("\n");
+            mirror_array.decor_mut().set_suffix("\n");
+
+            for mirror in mirrors {
+                let mut mirror_table = InlineTable::new();
+                mirror_table.decor_mut().set_prefix("\n  ");
+                mirror_table.insert("source", Value::from(mirror.source.as_str()));
+                mirror_table.insert("direction", Value::from(mirror.direction.to_string()));
+                mirror_table.insert("destination", Value::from(mirror.destination.as_str()));
+                mirror_array.push_formatted(Value::from(mirror_table));
+            }
+            doc["mirrors"] = Item::Value(Value::Array(mirror_array));
+        }
+
         fs::write(file_path, doc.to_string())?;
         Ok(())
     }
 
+    /// Links between lab nodes, without the external links.
+    pub fn expanded_links(&self) -> Result<Vec<LinkExpanded>> {
+        self.links
+            .iter()
+            .flatten()
+            .filter(|l| !l.is_external())
+            .map(Link2::expand)
+            .collect()
+    }
+
+    /// Lab bridge index of a mirror port.
+    ///
+    /// Mirror port bridges are numbered after the manifest bridges and the
+    /// external links, in the order their destinations first appear.
+    pub fn mirror_port_index(&self, destination: &str) -> Option<u16> {
+        let first_index = self.bridges.as_ref().map_or(0, |b| b.len())
+            + self
+                .links
+                .iter()
+                .flatten()
+                .filter(|l| l.is_external())
+                .count();
+        mirror_ports(&self.mirrors)
+            .iter()
+            .position(|port| port == destination)
+            .map(|position| (first_index + position) as u16)
+    }
+
     pub fn load_file(file_path: &str) -> Result<Manifest> {
         let file_contents = load_file_util(file_path)?;
-        let manifest: Manifest = toml::from_str(&file_contents)?;
+        Manifest::from_toml_str(&file_contents)
+    }
+
+    /// Parse a TOML manifest, expanding `model = "custom:<name>"` references.
+    pub fn from_toml_str(contents: &str) -> Result<Manifest> {
+        let manifest: Manifest = if contents.contains(CUSTOM_MODEL_PREFIX) {
+            let mut doc: DocumentMut = contents.parse()?;
+            expand_custom_model_refs_toml(&mut doc)?;
+            toml::from_str(&doc.to_string())?
+        } else {
+            toml::from_str(contents)?
+        };
+        manifest.check_custom_models()?;
         Ok(manifest)
     }
+
+    /// Parse a JSON manifest, expanding `model = "custom:<name>"` references.
+    pub fn from_json(mut value: serde_json::Value) -> Result<Manifest> {
+        expand_custom_model_refs_json(&mut value)?;
+        let manifest: Manifest = serde_json::from_value(value)?;
+        manifest.check_custom_models()?;
+        Ok(manifest)
+    }
+
+    fn check_custom_models(&self) -> Result<()> {
+        for node in &self.nodes {
+            match (node.model, &node.custom_model) {
+                (NodeModel::Custom, Some(name)) => validate_custom_model_name(name)?,
+                (NodeModel::Custom, None) => bail!(
+                    "Node '{}': custom models are referenced as model = \"custom:<name>\"",
+                    node.name
+                ),
+                (_, Some(_)) => bail!(
+                    "Node '{}': custom_model is only valid with model = \"custom\"",
+                    node.name
+                ),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn split_custom_model_ref(node_name: &str, model: &str, has_custom_model: bool) -> Result<String> {
+    let name = parse_custom_model_ref(model).unwrap_or_default();
+    if has_custom_model {
+        bail!(
+            "Node '{}': set either model = \"{}\" or custom_model, not both",
+            node_name,
+            model
+        );
+    }
+    Ok(name.to_owned())
+}
+
+fn expand_custom_model_refs_toml(doc: &mut DocumentMut) -> Result<()> {
+    fn expand_inline(table: &mut InlineTable) -> Result<()> {
+        let Some(model) = table.get("model").and_then(|m| m.as_str()) else {
+            return Ok(());
+        };
+        if parse_custom_model_ref(model).is_none() {
+            return Ok(());
+        }
+        let node_name = table
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+        let name = split_custom_model_ref(node_name, model, table.contains_key("custom_model"))?;
+        table.insert("model", Value::from(NodeModel::Custom.to_string()));
+        table.insert("custom_model", Value::from(name));
+        Ok(())
+    }
+    fn expand_table(table: &mut Table) -> Result<()> {
+        let Some(model) = table.get("model").and_then(|m| m.as_str()) else {
+            return Ok(());
+        };
+        if parse_custom_model_ref(model).is_none() {
+            return Ok(());
+        }
+        let node_name = table
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+        let name = split_custom_model_ref(node_name, model, table.contains_key("custom_model"))?;
+        table.insert("model", toml_edit::value(NodeModel::Custom.to_string()));
+        table.insert("custom_model", toml_edit::value(name));
+        Ok(())
+    }
+
+    match doc.get_mut("nodes") {
+        Some(Item::Value(Value::Array(nodes))) => {
+            for node in nodes.iter_mut() {
+                if let Value::InlineTable(table) = node {
+                    expand_inline(table)?;
+                }
+            }
+        }
+        Some(Item::ArrayOfTables(nodes)) => {
+            for table in nodes.iter_mut() {
+                expand_table(table)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn expand_custom_model_refs_json(value: &mut serde_json::Value) -> Result<()> {
+    let Some(nodes) = value.get_mut("nodes").and_then(|n| n.as_array_mut()) else {
+        return Ok(());
+    };
+    for node in nodes.iter_mut().filter_map(|n| n.as_object_mut()) {
+        let Some(model) = node.get("model").and_then(|m| m.as_str()) else {
+            continue;
+        };
+        if parse_custom_model_ref(model).is_none() {
+            continue;
+        }
+        let node_name = node
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default();
+        let name = split_custom_model_ref(node_name, model, node.contains_key("custom_model"))?;
+        node.insert("model".to_owned(), NodeModel::Custom.to_string().into());
+        node.insert("custom_model".to_owned(), name.into());
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::data::{L2Match, L2Protocol, MirrorDirection};
 
     #[test]
     fn test_manifest_deserialize_ready_timeout() {
@@ -193,4 +398,149 @@ nodes = [
         assert_eq!(manifest.nodes[1].skip_ready_check, Some(true));
         assert_eq!(manifest.nodes[2].skip_ready_check, Some(false));
     }
+
+    #[test]
+    fn test_manifest_custom_model_reference() {
+        let toml_str = r#"
+name = "my-lab"
+
+nodes = [
+  { name = "r1", model = "custom:vyos" },
+  { name = "r2", model = "cisco_iosv" },
+]
+"#;
+        let manifest = Manifest::from_toml_str(toml_str).expect("Failed to parse manifest");
+        assert_eq!(manifest.nodes[0].model, NodeModel::Custom);
+        assert_eq!(manifest.nodes[0].custom_model, Some("vyos".to_string()));
+        assert_eq!(manifest.nodes[0].model_reference(), "custom:vyos");
+        assert_eq!(manifest.nodes[1].custom_model, None);
+        assert_eq!(manifest.nodes[1].model_reference(), "cisco_iosv");
+    }
+
+    #[test]
+    fn test_manifest_custom_model_array_of_tables() {
+        let toml_str = r#"
+name = "my-lab"
+
+[[nodes]]
+name = "r1"
+model = "custom:vyos"
+"#;
+        let manifest = Manifest::from_toml_str(toml_str).expect("Failed to parse manifest");
+        assert_eq!(manifest.nodes[0].model, NodeModel::Custom);
+        assert_eq!(manifest.nodes[0].custom_model, Some("vyos".to_string()));
+    }
+
+    #[test]
+    fn test_manifest_custom_model_json_round_trip() {
+        let toml_str = r#"
+name = "my-lab"
+nodes = [{ name = "r1", model = "custom:vyos" }]
+"#;
+        let manifest = Manifest::from_toml_str(toml_str).expect("Failed to parse manifest");
+        let value = serde_json::to_value(&manifest).expect("serializes");
+        let manifest = Manifest::from_json(value).expect("Failed to parse JSON manifest");
+        assert_eq!(manifest.nodes[0].custom_model, Some("vyos".to_string()));
+
+        let value = serde_json::json!({
+            "name": "my-lab",
+            "nodes": [{ "name": "r1", "model": "custom:vyos" }],
+        });
+        let manifest = Manifest::from_json(value).expect("Failed to parse JSON manifest");
+        assert_eq!(manifest.nodes[0].model, NodeModel::Custom);
+        assert_eq!(manifest.nodes[0].custom_model, Some("vyos".to_string()));
+    }
+
+    #[test]
+    fn test_manifest_deserialize_mirrors() {
+        let toml_str = r#"
+name = "my-lab"
+
+nodes = [
+  { name = "r1", model = "cisco_iosv" },
+  { name = "r2", model = "cisco_iosv" },
+  { name = "ids01", model = "ubuntu_linux" },
+]
+
+links = [
+  { src = "r1::eth1", dst = "r2::eth1" },
+  { src = "r1::eth2", dst = "host::enp5s0" },
+]
+
+bridges = [
+  { name = "lan", links = ["r1::eth3", "r2::eth3"] },
+]
+
+mirrors = [
+  { source = "r1::eth1", direction = "egress", destination = "ids01::eth1" },
+  { source = "r2::eth1", destination = "ids01::eth2" },
+  { source = "r1::eth1", direction = "ingress", destination = "ids01::eth1" },
+]
+"#;
+        let manifest = Manifest::from_toml_str(toml_str).expect("Failed to parse manifest");
+        let mirrors = manifest.mirrors.as_ref().expect("mirrors are parsed");
+        assert_eq!(mirrors.len(), 3);
+        assert_eq!(mirrors[0].direction, MirrorDirection::Egress);
+        assert_eq!(mirrors[1].direction, MirrorDirection::Both);
+
+        // One manifest bridge and one external link come first
+        assert_eq!(manifest.mirror_port_index("ids01::eth1"), Some(2));
+        assert_eq!(manifest.mirror_port_index("ids01::eth2"), Some(3));
+        assert_eq!(manifest.mirror_port_index("r1::eth1"), None);
+    }
+
+    #[test]
+    fn test_manifest_mirror_rejects_unknown_direction() {
+        let toml_str = r#"
+name = "my-lab"
+nodes = [{ name = "r1", model = "cisco_iosv" }]
+mirrors = [{ source = "r1::eth1", direction = "sideways", destination = "r1::eth2" }]
+"#;
+        assert!(Manifest::from_toml_str(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_manifest_deserialize_link_filter() {
+        let toml_str = r#"
+name = "my-lab"
+nodes = [
+  { name = "r1", model = "cisco_iosv" },
+  { name = "r2", model = "cisco_iosv" },
+]
+links = [
+  { src = "r1::eth1", dst = "r2::eth1", p2p = true, filter = { drop = ["lacp", "ethertype:0x88cc"] } },
+  { src = "r1::eth2", dst = "r2::eth2", filter = { allow = ["vlan:10"] } },
+]
+"#;
+        let manifest = Manifest::from_toml_str(toml_str).expect("Failed to parse manifest");
+        let links = manifest.links.as_ref().expect("links are parsed");
+        let filter = links[0].filter.as_ref().expect("filter is parsed");
+        assert_eq!(
+            filter.drop,
+            vec![
+                L2Match::Protocol(L2Protocol::Lacp),
+                L2Match::Ethertype(0x88cc)
+            ]
+        );
+        assert_eq!(
+            links[1].filter.as_ref().map(|f| f.allow.clone()),
+            Some(vec![L2Match::Vlan(10)])
+        );
+
+        let bad = toml_str.replace("vlan:10", "vlan:ten");
+        assert!(Manifest::from_toml_str(&bad).is_err());
+    }
+
+    #[test]
+    fn test_manifest_custom_model_invalid() {
+        for toml_str in [
+            "name = \"l\"\nnodes = [{ name = \"r1\", model = \"custom\" }]",
+            "name = \"l\"\nnodes = [{ name = \"r1\", model = \"custom:\" }]",
+            "name = \"l\"\nnodes = [{ name = \"r1\", model = \"custom:Bad\" }]",
+            "name = \"l\"\nnodes = [{ name = \"r1\", model = \"custom:a\", custom_model = \"b\" }]",
+            "name = \"l\"\nnodes = [{ name = \"r1\", model = \"cisco_iosv\", custom_model = \"b\" }]",
+        ] {
+            assert!(Manifest::from_toml_str(toml_str).is_err(), "{toml_str}");
+        }
+    }
 }
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use serde_derive::{Deserialize, Serialize};
+
+use shared::data::MirrorDirection;
+use shared::util::split_node_int;
+
+/// Manifest port mirror session
+/// Expected format: "node_name::interface_name" for source and destination
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mirror {
+    /// Link interface to copy traffic from
+    pub source: String,
+    #[serde(default)]
+    pub direction: MirrorDirection,
+    /// Mirror port receiving the copied traffic
+    pub destination: String,
+}
+
+impl Mirror {
+    pub fn expand(&self) -> Result<MirrorExpanded> {
+        let (source_node, source_interface) = split_node_int(&self.source)?;
+        let (destination_node, destination_interface) = split_node_int(&self.destination)?;
+
+        Ok(MirrorExpanded {
+            source_node,
+            source_interface,
+            destination_node,
+            destination_interface,
+            direction: self.direction,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MirrorExpanded {
+    pub source_node: String,
+    pub source_interface: String,
+    pub destination_node: String,
+    pub destination_interface: String,
+    pub direction: MirrorDirection,
+}
+
+/// Distinct mirror destinations, in manifest order.
+///
+/// Each destination is a mirror port, connected to a single-member lab
+/// bridge that mirrored traffic is sent to.
+pub fn mirror_ports(mirrors: &Option<Vec<Mirror>>) -> Vec<String> {
+    let mut ports: Vec<String> = vec![];
+    for mirror in mirrors.iter().flatten() {
+        if !ports.contains(&mirror.destination) {
+            ports.push(mirror.destination.clone());
+        }
+    }
+    ports
+}
@@ -1,20 +1,31 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::RangeInclusive;
 
+use anyhow::Result;
 use serde_derive::{Deserialize, Serialize};
 
-use shared::data::NodeModel;
+use super::link::LinkExpanded;
+use shared::data::{CustomModel, InterfaceMtu, NodeModel};
+use shared::konst::CUSTOM_MODEL_PREFIX;
+use shared::util;
 
 #[derive(Clone, Debug, Deserialize, Default, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Node {
     pub name: String,
     pub model: NodeModel,
+    /// Name of the custom model definition, set from `model = "custom:<name>"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_model: Option<String>,
     pub image: Option<String>,
     pub version: Option<String>,
     pub cpu_count: Option<u8>,
     pub memory: Option<u16>,
     pub boot_disk_size: Option<u16>,
     pub data_interface_count: Option<u8>,
+    /// MTU of the data interfaces, defaults to the interface MTU of the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
     pub ipv4_address: Option<Ipv4Addr>,
     pub ipv6_address: Option<Ipv6Addr>,
     pub text_files: Option<Vec<TextFile>>,
@@ -47,12 +58,20 @@ pub struct NodeExpanded {
     pub index: u16,
     pub name: String,
     pub model: NodeModel,
+    /// Resolved custom model definition, set by the server for custom nodes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_model: Option<CustomModel>,
     pub image: Option<String>,
     pub version: Option<String>,
     pub cpu_count: Option<u8>,
     pub memory: Option<u16>,
     pub boot_disk_size: Option<u16>,
     pub data_interface_count: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u16>,
+    /// MTUs the manifest sets on data interfaces, resolved by the server
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interface_mtus: Vec<InterfaceMtu>,
     pub ipv4_address: Option<Ipv4Addr>,
     pub ipv6_address: Option<Ipv6Addr>,
     pub text_files: Option<Vec<TextFileData>>,
@@ -74,6 +93,79 @@ pub struct NodeExpanded {
     pub ready_port: Option<u16>,
 }
 
+impl Node {
+    /// Model as written in a manifest, e.g. `arista_veos` or `custom:vyos`.
+    pub fn model_reference(&self) -> String {
+        match &self.custom_model {
+            Some(name) => format!("{}{}", CUSTOM_MODEL_PREFIX, name),
+            None => self.model.to_string(),
+        }
+    }
+}
+
+impl NodeExpanded {
+    /// Map an interface name to its index, honouring custom model naming.
+    ///
+    /// Offline tools do not have custom model definitions, so unresolved
+    /// custom nodes use the trailing number of the interface name.
+    pub fn interface_to_idx(&self, interface: &str) -> Result<u8> {
+        match (&self.custom_model, self.model) {
+            (Some(custom), _) => custom.interfaces.to_idx(interface),
+            (None, NodeModel::Custom) => {
+                let digits = interface
+                    .trim_start_matches(|c: char| !c.is_ascii_digit())
+                    .rsplit(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .unwrap_or_default();
+                digits
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid interface name: {}", interface))
+            }
+            (None, model) => util::interface_to_idx(&model, interface),
+        }
+    }
+
+    /// Map an interface index to its name, honouring custom model naming.
+    pub fn interface_from_idx(&self, idx: u8) -> Result<String> {
+        match &self.custom_model {
+            Some(custom) => custom.interfaces.from_idx(idx),
+            None => util::interface_from_idx(&self.model, idx),
+        }
+    }
+
+    /// MTU the manifest sets on a data interface of the node.
+    pub fn interface_mtu(&self, interface: &str) -> Option<u16> {
+        self.interface_mtus
+            .iter()
+            .find(|i| i.name == interface)
+            .map(|i| i.mtu)
+    }
+
+    /// MTUs the manifest sets on the data interfaces `data_interfaces`.
+    ///
+    /// An interface takes the `mtu` of its link, or else the `mtu` of this
+    /// node. Validation keeps the two ends of a link equal.
+    pub fn data_interface_mtus(
+        &self,
+        data_interfaces: RangeInclusive<u8>,
+        links: &[LinkExpanded],
+    ) -> Result<Vec<InterfaceMtu>> {
+        let mut mtus = vec![];
+        for idx in data_interfaces {
+            let name = self.interface_from_idx(idx)?;
+            let link_mtu = links.iter().find_map(|link| {
+                let connected = (link.node_a == self.name && link.int_a == name)
+                    || (link.node_b == self.name && link.int_b == name);
+                if connected { link.mtu } else { None }
+            });
+            if let Some(mtu) = link_mtu.or(self.mtu) {
+                mtus.push(InterfaceMtu { name, mtu });
+            }
+        }
+        Ok(mtus)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Default, Serialize)]
 pub struct StartupScript {
     pub filename: String,
@@ -1,7 +1,7 @@
 use std::net::{Ipv4Addr, Ipv6Addr};
 
-use shared::data::NodeModel;
-use topology::{Bridge, Link2, Manifest, Node};
+use shared::data::{MirrorDirection, NodeModel, PortMode, PortVlan, VlanRange};
+use topology::{Bridge, Link2, Manifest, Mirror, Node, bridge_port_taps};
 
 // ============================================================================
 // Expected TOML manifests
@@ -216,7 +216,7 @@ fn test_parse_full_manifest() {
 
     // ZTP server
     let ztp = manifest.ztp_server.as_ref().expect("has ztp_server");
-    assert_eq!(ztp.enable, true);
+    assert!(ztp.enable);
     assert_eq!(ztp.username, Some("admin".to_string()));
     assert_eq!(ztp.password, Some("secret123".to_string()));
 
@@ -225,9 +225,9 @@ fn test_parse_full_manifest() {
         .config_management
         .as_ref()
         .expect("has config_management");
-    assert_eq!(cm.ansible, true);
-    assert_eq!(cm.pyats, true);
-    assert_eq!(cm.nornir, false);
+    assert!(cm.ansible);
+    assert!(cm.pyats);
+    assert!(!cm.nornir);
 }
 
 #[test]
@@ -330,10 +330,13 @@ fn test_write_load_roundtrip() {
             dst: "r2::eth1".to_string(),
             p2p: None,
             impairment: None,
+            filter: None,
+            mtu: None,
         }]),
         bridges: None,
         ztp_server: None,
         config_management: None,
+        mirrors: None,
     };
 
     let tmp_path = "/tmp/sherpa_test_manifest_roundtrip.toml";
@@ -355,6 +358,82 @@ fn test_write_load_roundtrip() {
     std::fs::remove_file(tmp_path).ok();
 }
 
+#[test]
+fn test_write_load_roundtrip_with_version_and_bridges() {
+    let manifest = Manifest {
+        name: "roundtrip-bridges".to_string(),
+        nodes: vec![
+            Node {
+                name: "r1".to_string(),
+                model: NodeModel::AristaCeos,
+                version: Some("4.32.0F".to_string()),
+                ..Default::default()
+            },
+            Node {
+                name: "r2".to_string(),
+                model: NodeModel::AristaCeos,
+                ..Default::default()
+            },
+        ],
+        bridges: Some(vec![Bridge {
+            name: "lan".to_string(),
+            links: vec!["r1::eth1".to_string(), "r2::eth1".to_string()],
+            ports: vec![],
+        }]),
+        ..Default::default()
+    };
+
+    let tmp_path = "/tmp/sherpa_test_manifest_roundtrip_bridges.toml";
+    manifest.write_file(tmp_path).expect("writes file");
+    let loaded = Manifest::load_file(tmp_path).expect("loads file");
+    std::fs::remove_file(tmp_path).ok();
+
+    assert_eq!(loaded.nodes[0].version.as_deref(), Some("4.32.0F"));
+    assert_eq!(loaded.nodes[1].version, None);
+    assert!(loaded.links.is_none());
+
+    let bridges = loaded.bridges.as_ref().expect("has bridges");
+    assert_eq!(bridges.len(), 1);
+    assert_eq!(bridges[0].name, "lan");
+    assert_eq!(bridges[0].links, vec!["r1::eth1", "r2::eth1"]);
+}
+
+#[test]
+fn test_write_load_roundtrip_with_mirrors() {
+    let manifest = Manifest {
+        name: "roundtrip-mirrors".to_string(),
+        nodes: vec![
+            Node {
+                name: "r1".to_string(),
+                model: NodeModel::CiscoIosv,
+                ..Default::default()
+            },
+            Node {
+                name: "ids01".to_string(),
+                model: NodeModel::UbuntuLinux,
+                ..Default::default()
+            },
+        ],
+        mirrors: Some(vec![Mirror {
+            source: "r1::eth1".to_string(),
+            direction: MirrorDirection::Ingress,
+            destination: "ids01::eth1".to_string(),
+        }]),
+        ..Default::default()
+    };
+
+    let tmp_path = "/tmp/sherpa_test_manifest_roundtrip_mirrors.toml";
+    manifest.write_file(tmp_path).expect("writes file");
+    let loaded = Manifest::load_file(tmp_path).expect("loads file");
+    std::fs::remove_file(tmp_path).ok();
+
+    let mirrors = loaded.mirrors.as_ref().expect("has mirrors");
+    assert_eq!(mirrors.len(), 1);
+    assert_eq!(mirrors[0].source, "r1::eth1");
+    assert_eq!(mirrors[0].direction, MirrorDirection::Ingress);
+    assert_eq!(mirrors[0].destination, "ids01::eth1");
+}
+
 // ============================================================================
 // Tests — Link2::expand()
 // ============================================================================
@@ -366,6 +445,8 @@ fn test_link2_expand() {
         dst: "switch1::GigabitEthernet0/1".to_string(),
         p2p: None,
         impairment: None,
+        filter: None,
+        mtu: None,
     };
     let expanded = link.expand().expect("expands");
     assert_eq!(expanded.node_a, "router1");
@@ -381,6 +462,8 @@ fn test_link2_expand_missing_separator() {
         dst: "switch1::eth1".to_string(),
         p2p: None,
         impairment: None,
+        filter: None,
+        mtu: None,
     };
     let result = link.expand();
     assert!(result.is_err());
@@ -399,6 +482,7 @@ fn test_bridge_parse_links() {
             "switch1::eth5".to_string(),
             "server1::eth2".to_string(),
         ],
+        ports: vec![],
     };
     let expanded = bridge.parse_links().expect("parses");
     assert_eq!(expanded.name, "br0");
@@ -419,11 +503,99 @@ fn test_bridge_parse_links_invalid_format() {
             "router1::eth3".to_string(),
             "bad-format-no-separator".to_string(),
         ],
+        ports: vec![],
     };
     let result = bridge.parse_links();
     assert!(result.is_err());
 }
 
+const MANIFEST_WITH_VLAN_BRIDGE: &str = r#"
+name = "vlan-lab"
+
+nodes = [
+  { name = "agg01", model = "ubuntu_linux" },
+  { name = "ce01", model = "ubuntu_linux" },
+  { name = "ce02", model = "ubuntu_linux" },
+]
+
+[[bridges]]
+name = "flat"
+links = ["ce01::eth2", "ce02::eth2"]
+
+[[bridges]]
+name = "agg"
+links = ["agg01::eth1", "ce01::eth1", "ce02::eth1"]
+ports = [
+  { link = "agg01::eth1", mode = "trunk", allowed_vlans = [10, "100-199"], native_vlan = 150 },
+  { link = "ce01::eth1", mode = "access", vlan = 10 },
+]
+"#;
+
+fn vlan(first: u16, last: u16, native: bool) -> PortVlan {
+    PortVlan {
+        vlans: VlanRange { first, last },
+        native,
+    }
+}
+
+#[test]
+fn test_parse_vlan_bridge_ports() {
+    let manifest: Manifest = toml::from_str(MANIFEST_WITH_VLAN_BRIDGE).expect("parses");
+    let bridges = manifest.bridges.as_ref().expect("has bridges");
+    assert!(!bridges[0].is_vlan_aware());
+    assert!(bridges[1].is_vlan_aware());
+
+    let trunk = bridges[1].port("agg01", "eth1").expect("has trunk port");
+    assert_eq!(trunk.mode, PortMode::Trunk);
+    assert_eq!(
+        trunk.vlans(),
+        vec![
+            vlan(10, 10, false),
+            vlan(100, 149, false),
+            vlan(151, 199, false),
+            vlan(150, 150, true),
+        ]
+    );
+    let access = bridges[1].port("ce01", "eth1").expect("has access port");
+    assert_eq!(access.vlans(), vec![vlan(10, 10, true)]);
+    assert!(bridges[1].port("ce02", "eth1").is_none());
+}
+
+#[test]
+fn test_parse_vlan_bridge_port_rejects_unknown_field() {
+    let toml_str = r#"
+name = "vlan-lab"
+nodes = []
+
+[[bridges]]
+name = "agg"
+links = ["ce01::eth1"]
+ports = [{ link = "ce01::eth1", mode = "access", vlan_id = 10 }]
+"#;
+    assert!(toml::from_str::<Manifest>(toml_str).is_err());
+}
+
+#[test]
+fn test_bridge_port_taps() {
+    let manifest: Manifest = toml::from_str(MANIFEST_WITH_VLAN_BRIDGE).expect("parses");
+    let taps = bridge_port_taps(&manifest.bridges, "abcd1234");
+
+    let names: Vec<(&str, &str, &str)> = taps
+        .iter()
+        .map(|t| (t.node.as_str(), t.bridge_name.as_str(), t.tap_name.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("agg01", "brs1-abcd1234", "tpv0-abcd1234"),
+            ("ce01", "brs1-abcd1234", "tpv1-abcd1234"),
+            ("ce02", "brs1-abcd1234", "tpv2-abcd1234"),
+        ]
+    );
+    // Members without port settings stay untagged in the default VLAN
+    assert_eq!(taps[2].vlans, vec![vlan(1, 1, true)]);
+}
+
 // ============================================================================
 // Tests — Node defaults
 // ============================================================================
@@ -481,3 +653,72 @@ fn test_parse_text_files_rejects_unknown_fields() {
     let result = toml::from_str::<Manifest>(MANIFEST_WITH_TEXT_FILES_UNKNOWN_FIELD);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_node_expanded_custom_interface_names() {
+    let custom = shared::data::CustomModel::from_toml(
+        "name = \"junos\"\n[interfaces]\nprefix = \"ge-0/0/\"\nfirst_index = 0\nmanagement = \"fxp0\"\n",
+    )
+    .unwrap();
+    let node = topology::NodeExpanded {
+        name: "r1".to_string(),
+        model: NodeModel::Custom,
+        custom_model: Some(custom),
+        ..Default::default()
+    };
+    assert_eq!(node.interface_to_idx("fxp0").unwrap(), 0);
+    assert_eq!(node.interface_to_idx("ge-0/0/2").unwrap(), 3);
+    assert_eq!(node.interface_from_idx(3).unwrap(), "ge-0/0/2");
+
+    let builtin = topology::NodeExpanded {
+        model: NodeModel::CiscoIosv,
+        ..Default::default()
+    };
+    assert_eq!(builtin.interface_to_idx("gig0/1").unwrap(), 1);
+
+    // Without the definition, offline tools fall back to the trailing number
+    let unresolved = topology::NodeExpanded {
+        model: NodeModel::Custom,
+        ..Default::default()
+    };
+    assert_eq!(unresolved.interface_to_idx("ge-0/0/2").unwrap(), 2);
+    assert!(unresolved.interface_to_idx("mgmt").is_err());
+}
+
+#[test]
+fn test_node_expanded_data_interface_mtus() {
+    let toml_str = r#"
+name = "evpn-lab"
+nodes = [
+  { name = "leaf01", model = "arista_ceos", mtu = 9216 },
+  { name = "spine01", model = "arista_ceos" },
+]
+links = [
+  { src = "leaf01::eth1", dst = "spine01::eth1" },
+  { src = "leaf01::eth2", dst = "spine01::eth2", mtu = 1500 },
+]
+"#;
+    let manifest: Manifest = toml::from_str(toml_str).expect("parses");
+    let links = manifest.expanded_links().unwrap();
+    let node = |name: &str| {
+        let node = manifest.nodes.iter().find(|n| n.name == name).unwrap();
+        topology::NodeExpanded {
+            name: node.name.clone(),
+            model: node.model,
+            mtu: node.mtu,
+            ..Default::default()
+        }
+    };
+
+    let leaf_mtus = node("leaf01").data_interface_mtus(1..=3, &links).unwrap();
+    let leaf_mtus: Vec<(&str, u16)> = leaf_mtus.iter().map(|i| (i.name.as_str(), i.mtu)).collect();
+    assert_eq!(
+        leaf_mtus,
+        vec![("eth1", 9216), ("eth2", 1500), ("eth3", 9216)]
+    );
+
+    // The spine only takes the MTU its links set
+    let spine_mtus = node("spine01").data_interface_mtus(1..=3, &links).unwrap();
+    assert_eq!(spine_mtus.len(), 1);
+    assert_eq!(spine_mtus[0].name, "eth2");
+}
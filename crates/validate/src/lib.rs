@@ -7,6 +7,8 @@ mod environment;
 mod interface_count;
 mod ipv6;
 mod link;
+mod mirror;
+mod mtu;
 mod node_image;
 mod version;
 
@@ -16,8 +18,10 @@ pub use environment::validate_environment_variables;
 pub use interface_count::{effective_data_interface_count, validate_data_interface_count_override};
 pub use ipv6::validate_manifest_ipv6_addresses;
 pub use link::{
-    check_bridge_device, check_duplicate_interface_link, check_interface_bounds, check_link_device,
-    check_mgmt_usage,
+    check_bridge_device, check_bridge_ports, check_duplicate_interface_link, check_external_links,
+    check_interface_bounds, check_link_device, check_mgmt_usage,
 };
+pub use mirror::check_mirrors;
+pub use mtu::check_mtus;
 pub use node_image::validate_node_image_update;
-pub use version::validate_and_resolve_node_versions;
+pub use version::{missing_container_images, validate_and_resolve_node_versions};
@@ -2,8 +2,17 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::{Result, bail};
 
-use shared::data::NodeModel;
-use topology::{BridgeDetailed, LinkDetailed, Node};
+use shared::data::{NodeModel, PortMode, VlanRange};
+use shared::konst::EXTERNAL_HOST_NODE;
+use shared::util::split_node_int;
+use topology::{Bridge, BridgeDetailed, LinkDetailed, Node};
+
+/// Longest Linux interface name (IFNAMSIZ without the trailing NUL)
+const MAX_INTERFACE_NAME_LEN: usize = 15;
+
+/// Members of VLAN-aware bridges in a lab, keeps the `tpv{n}-{lab_id}` tap
+/// names within `MAX_INTERFACE_NAME_LEN`
+const MAX_VLAN_BRIDGE_MEMBERS: usize = 1000;
 
 /// Checks if any links or bridges use the management interface (index 0) on a node.
 /// Returns an error if a link or bridge attempts to use the management interface.
@@ -252,11 +261,135 @@ pub fn check_interface_bounds(
     Ok(())
 }
 
+/// Check external links to host interfaces.
+///
+/// The `host` node name is reserved for external links, host interface names
+/// must be valid Linux interface names and each host interface can only be
+/// connected once.
+pub fn check_external_links(devices: &[Node], bridges: &[BridgeDetailed]) -> Result<()> {
+    if devices.iter().any(|d| d.name == EXTERNAL_HOST_NODE) {
+        bail!("Manifest node name '{EXTERNAL_HOST_NODE}' is reserved for external links");
+    }
+
+    let mut host_interfaces: HashSet<&str> = HashSet::new();
+    for host_interface in bridges
+        .iter()
+        .filter_map(|b| b.external_interface.as_deref())
+    {
+        let valid = !host_interface.is_empty()
+            && host_interface.len() <= MAX_INTERFACE_NAME_LEN
+            && !host_interface
+                .chars()
+                .any(|c| c == '/' || c == ':' || c.is_whitespace());
+        if !valid {
+            bail!("Manifest external link - '{host_interface}' is not a valid host interface name");
+        }
+        if !host_interfaces.insert(host_interface) {
+            bail!(
+                "Manifest external link - host interface '{host_interface}' is used by more than one link"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check the VLAN settings of shared bridge members.
+///
+/// Each `ports` entry must name a member of its bridge once. Access ports
+/// need a `vlan` and trunk ports `allowed_vlans`, and every VLAN ID must be
+/// within 1-4094.
+pub fn check_bridge_ports(bridges: &[Bridge]) -> Result<()> {
+    let mut vlan_members = 0;
+    for bridge in bridges.iter().filter(|b| b.is_vlan_aware()) {
+        let members = bridge
+            .links
+            .iter()
+            .map(|link| split_node_int(link))
+            .collect::<Result<Vec<_>>>()?;
+        vlan_members += members.len();
+
+        let mut configured: HashSet<(String, String)> = HashSet::new();
+        for port in &bridge.ports {
+            let member = split_node_int(&port.link)?;
+            if !members.contains(&member) {
+                bail!(
+                    "Manifest bridge '{}' - port '{}' is not a member of the bridge",
+                    bridge.name,
+                    port.link
+                );
+            }
+            if !configured.insert(member) {
+                bail!(
+                    "Manifest bridge '{}' - port '{}' is configured more than once",
+                    bridge.name,
+                    port.link
+                );
+            }
+
+            let vlans = match port.mode {
+                PortMode::Access => {
+                    if !port.allowed_vlans.is_empty() || port.native_vlan.is_some() {
+                        bail!(
+                            "Manifest bridge '{}' - access port '{}' only takes 'vlan', allowed_vlans and native_vlan are trunk settings",
+                            bridge.name,
+                            port.link
+                        );
+                    }
+                    let Some(vlan) = port.vlan else {
+                        bail!(
+                            "Manifest bridge '{}' - access port '{}' has no 'vlan'",
+                            bridge.name,
+                            port.link
+                        );
+                    };
+                    vec![VlanRange::single(vlan)]
+                }
+                PortMode::Trunk => {
+                    if port.vlan.is_some() {
+                        bail!(
+                            "Manifest bridge '{}' - trunk port '{}' takes 'allowed_vlans' and 'native_vlan', not 'vlan'",
+                            bridge.name,
+                            port.link
+                        );
+                    }
+                    if port.allowed_vlans.is_empty() {
+                        bail!(
+                            "Manifest bridge '{}' - trunk port '{}' has no 'allowed_vlans'",
+                            bridge.name,
+                            port.link
+                        );
+                    }
+                    port.allowed_vlans
+                        .iter()
+                        .copied()
+                        .chain(port.native_vlan.map(VlanRange::single))
+                        .collect()
+                }
+            };
+            if let Some(invalid) = vlans.iter().find(|range| !range.is_valid()) {
+                bail!(
+                    "Manifest bridge '{}' - port '{}' VLAN '{}' is out of range, VLAN IDs are 1-4094",
+                    bridge.name,
+                    port.link,
+                    invalid
+                );
+            }
+        }
+    }
+
+    if vlan_members > MAX_VLAN_BRIDGE_MEMBERS {
+        bail!(
+            "Manifest bridges - {vlan_members} members of VLAN-aware bridges, the maximum is {MAX_VLAN_BRIDGE_MEMBERS}"
+        );
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use anyhow::Result;
-    use topology::BridgeLinkDetailed;
+    use topology::{BridgeLinkDetailed, BridgePort};
 
     /// Helper to create a test link
     fn create_link(node_a: &str, int_a: u8, node_b: &str, int_b: u8) -> LinkDetailed {
@@ -274,6 +407,8 @@ mod tests {
             link_idx: 0,
             p2p: false,
             impairment: None,
+            filter: None,
+            mtu: None,
         }
     }
 
@@ -534,6 +669,7 @@ mod tests {
             bridge_name: bridge_name.to_string(),
             libvirt_name: format!("test_{}", bridge_name),
             index: 0,
+            external_interface: None,
             links: connections
                 .into_iter()
                 .map(|(node_name, interface_index)| BridgeLinkDetailed {
@@ -713,6 +849,58 @@ mod tests {
         check_bridge_device(&devices, &bridges)
     }
 
+    // ============================================================================
+    // check_external_links tests
+    // ============================================================================
+
+    fn create_external(host_interface: &str, node_name: &str) -> BridgeDetailed {
+        BridgeDetailed {
+            external_interface: Some(host_interface.to_string()),
+            ..create_bridge(&format!("host-{host_interface}"), vec![(node_name, 3)])
+        }
+    }
+
+    #[test]
+    fn test_check_external_links_valid() -> Result<()> {
+        let devices = vec![Node {
+            name: "rocky1".to_string(),
+            model: NodeModel::RockyLinux,
+            ..Default::default()
+        }];
+        let bridges = vec![
+            create_bridge("br1", vec![("rocky1", 1)]),
+            create_external("enp5s0.120", "rocky1"),
+        ];
+
+        check_external_links(&devices, &bridges)
+    }
+
+    #[test]
+    fn test_check_external_links_reserved_node_name() {
+        let devices = vec![Node {
+            name: "host".to_string(),
+            model: NodeModel::RockyLinux,
+            ..Default::default()
+        }];
+
+        let err = check_external_links(&devices, &[]).unwrap_err().to_string();
+        assert!(err.contains("reserved"));
+    }
+
+    #[test]
+    fn test_check_external_links_invalid_and_duplicate_interfaces() {
+        let bridges = vec![create_external("enp5s0.120.very-long", "rocky1")];
+        let err = check_external_links(&[], &bridges).unwrap_err().to_string();
+        assert!(err.contains("not a valid host interface name"));
+
+        let bridges = vec![
+            create_external("enp5s0", "rocky1"),
+            create_external("enp5s0", "rocky2"),
+        ];
+        let err = check_external_links(&[], &bridges).unwrap_err().to_string();
+        assert!(err.contains("more than one link"));
+    }
+
     // ============================================================================
     // check_link_device tests
     // ============================================================================
@@ -763,4 +951,88 @@ mod tests {
 
         check_link_device(&devices, &links)
     }
+
+    fn port(
+        link: &str,
+        mode: PortMode,
+        vlan: Option<u16>,
+        allowed_vlans: &[&str],
+        native_vlan: Option<u16>,
+    ) -> BridgePort {
+        BridgePort {
+            link: link.to_string(),
+            mode,
+            vlan,
+            allowed_vlans: allowed_vlans.iter().map(|v| v.parse().unwrap()).collect(),
+            native_vlan,
+        }
+    }
+
+    fn vlan_bridge(ports: Vec<BridgePort>) -> Bridge {
+        Bridge {
+            name: "agg".to_string(),
+            links: vec!["agg01::eth1".to_string(), "ce01::eth1".to_string()],
+            ports,
+        }
+    }
+
+    #[test]
+    fn test_check_bridge_ports_valid() -> Result<()> {
+        check_bridge_ports(&[vlan_bridge(vec![
+            port(
+                "agg01::eth1",
+                PortMode::Trunk,
+                None,
+                &["10", "100-199"],
+                Some(1),
+            ),
+            port("ce01::eth1", PortMode::Access, Some(10), &[], None),
+        ])])
+    }
+
+    #[test]
+    fn test_check_bridge_ports_invalid() {
+        let access = |vlan| port("ce01::eth1", PortMode::Access, vlan, &[], None);
+        let cases = [
+            (
+                vec![port("pe01::eth1", PortMode::Access, Some(10), &[], None)],
+                "not a member",
+            ),
+            (vec![access(Some(10)), access(Some(20))], "more than once"),
+            (vec![access(None)], "has no 'vlan'"),
+            (
+                vec![port("ce01::eth1", PortMode::Access, Some(10), &[], Some(1))],
+                "trunk settings",
+            ),
+            (
+                vec![port("agg01::eth1", PortMode::Trunk, None, &[], None)],
+                "no 'allowed_vlans'",
+            ),
+            (
+                vec![port(
+                    "agg01::eth1",
+                    PortMode::Trunk,
+                    Some(10),
+                    &["10"],
+                    None,
+                )],
+                "not 'vlan'",
+            ),
+            (
+                vec![port(
+                    "agg01::eth1",
+                    PortMode::Trunk,
+                    None,
+                    &["4000-4095"],
+                    None,
+                )],
+                "out of range",
+            ),
+            (vec![access(Some(0))], "out of range"),
+        ];
+        for (ports, expected) in cases {
+            let err = check_bridge_ports(&[vlan_bridge(ports)]).unwrap_err();
+            assert!(err.to_string().contains(expected), "{expected}: {err}");
+        }
+    }
 }
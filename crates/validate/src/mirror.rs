@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail};
+
+use shared::konst::MIRROR_MAX_PER_INTERFACE;
+use topology::{LinkDetailed, Mirror};
+
+/// Check manifest port mirror sessions.
+///
+/// Sources must be an interface of a point-to-point or bridged link, a
+/// session can only be declared once, and the frames received by a link
+/// interface can be copied to at most `MIRROR_MAX_PER_INTERFACE` mirror
+/// ports. Mirror ports themselves are checked with the lab bridges.
+pub fn check_mirrors(mirrors: &[Mirror], links: &[LinkDetailed]) -> Result<()> {
+    let mut sessions: HashSet<(&str, &str)> = HashSet::new();
+    let mut received: HashMap<(String, String), HashSet<&str>> = HashMap::new();
+
+    for mirror in mirrors {
+        let expanded = mirror.expand()?;
+        if !sessions.insert((mirror.source.as_str(), mirror.destination.as_str())) {
+            bail!(
+                "Manifest mirror - {} -> {} is defined more than once",
+                mirror.source,
+                mirror.destination
+            );
+        }
+
+        let source = (expanded.source_node, expanded.source_interface);
+        let Some(peer) = links.iter().find_map(|link| {
+            if (&link.node_a, &link.int_a) == (&source.0, &source.1) {
+                Some((link.node_b.clone(), link.int_b.clone()))
+            } else if (&link.node_b, &link.int_b) == (&source.0, &source.1) {
+                Some((link.node_a.clone(), link.int_a.clone()))
+            } else {
+                None
+            }
+        }) else {
+            bail!(
+                "Manifest mirror - source '{}' is not an interface of a lab link",
+                mirror.source
+            );
+        };
+
+        // Frames sent by the source are the frames received by its peer
+        let mut receivers = vec![];
+        if mirror.direction.includes_ingress() {
+            receivers.push(source);
+        }
+        if mirror.direction.includes_egress() {
+            receivers.push(peer);
+        }
+        for receiver in receivers {
+            let destinations = received.entry(receiver.clone()).or_default();
+            destinations.insert(mirror.destination.as_str());
+            if destinations.len() > MIRROR_MAX_PER_INTERFACE as usize {
+                bail!(
+                    "Manifest mirror - traffic received by '{}::{}' is mirrored to more than {} ports",
+                    receiver.0,
+                    receiver.1,
+                    MIRROR_MAX_PER_INTERFACE
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::data::{MirrorDirection, NodeModel};
+
+    fn create_link(node_a: &str, int_a: &str, node_b: &str, int_b: &str) -> LinkDetailed {
+        LinkDetailed {
+            node_a: node_a.to_string(),
+            node_a_model: NodeModel::RockyLinux,
+            int_a: int_a.to_string(),
+            node_b: node_b.to_string(),
+            node_b_model: NodeModel::RockyLinux,
+            int_b: int_b.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn create_mirror(source: &str, direction: MirrorDirection, destination: &str) -> Mirror {
+        Mirror {
+            source: source.to_string(),
+            direction,
+            destination: destination.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_check_mirrors_valid() -> Result<()> {
+        let links = vec![create_link("r1", "eth1", "r2", "eth1")];
+        let mirrors = vec![
+            create_mirror("r1::eth1", MirrorDirection::Both, "ids01::eth1"),
+            create_mirror("r2::eth1", MirrorDirection::Ingress, "ids01::eth2"),
+        ];
+
+        check_mirrors(&mirrors, &links)
+    }
+
+    #[test]
+    fn test_check_mirrors_source_not_a_link() {
+        let links = vec![create_link("r1", "eth1", "r2", "eth1")];
+        let mirrors = vec![create_mirror(
+            "r1::eth2",
+            MirrorDirection::Both,
+            "ids01::eth1",
+        )];
+
+        let err = check_mirrors(&mirrors, &links).unwrap_err().to_string();
+        assert!(err.contains("not an interface of a lab link"));
+    }
+
+    #[test]
+    fn test_check_mirrors_duplicate_session() {
+        let links = vec![create_link("r1", "eth1", "r2", "eth1")];
+        let mirrors = vec![
+            create_mirror("r1::eth1", MirrorDirection::Ingress, "ids01::eth1"),
+            create_mirror("r1::eth1", MirrorDirection::Egress, "ids01::eth1"),
+        ];
+
+        let err = check_mirrors(&mirrors, &links).unwrap_err().to_string();
+        assert!(err.contains("more than once"));
+    }
+
+    #[test]
+    fn test_check_mirrors_too_many_ports() {
+        let links = vec![create_link("r1", "eth1", "r2", "eth1")];
+        // Egress of r2::eth1 is the traffic received by r1::eth1
+        let mut mirrors: Vec<Mirror> = (1..=4)
+            .map(|i| {
+                create_mirror(
+                    "r1::eth1",
+                    MirrorDirection::Ingress,
+                    &format!("ids01::eth{i}"),
+                )
+            })
+            .collect();
+        check_mirrors(&mirrors, &links).expect("four ports fit");
+
+        mirrors.push(create_mirror(
+            "r2::eth1",
+            MirrorDirection::Egress,
+            "ids02::eth1",
+        ));
+        let err = check_mirrors(&mirrors, &links).unwrap_err().to_string();
+        assert!(err.contains("r1::eth1"));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+
+use shared::konst::{MTU_JUMBO_NET, MTU_MIN};
+use topology::{LinkDetailed, Node};
+
+/// Check the MTUs set in the manifest.
+///
+/// Node and link MTUs must be within `MTU_MIN` and `MTU_JUMBO_NET`, the MTU
+/// of the host side of the links. A link without an `mtu` of its own must
+/// join two interfaces of the same MTU once one of its nodes sets one.
+/// `image_mtus` holds the interface MTU of the image of each node; ends
+/// without an image MTU are only compared when both nodes set an MTU.
+pub fn check_mtus(
+    nodes: &[Node],
+    links: &[LinkDetailed],
+    image_mtus: &HashMap<String, u16>,
+) -> Result<()> {
+    for node in nodes {
+        if let Some(mtu) = node.mtu {
+            check_mtu(&format!("node '{}'", node.name), mtu)?;
+        }
+    }
+
+    let node_mtu = |name: &str| nodes.iter().find(|n| n.name == name).and_then(|n| n.mtu);
+    for link in links {
+        let name = format!(
+            "link {}::{} <-> {}::{}",
+            link.node_a, link.int_a, link.node_b, link.int_b
+        );
+        if let Some(mtu) = link.mtu {
+            check_mtu(&name, mtu)?;
+            continue;
+        }
+
+        let (set_a, set_b) = (node_mtu(&link.node_a), node_mtu(&link.node_b));
+        if set_a.is_none() && set_b.is_none() {
+            continue;
+        }
+        let mtu_a = set_a.or_else(|| image_mtus.get(&link.node_a).copied());
+        let mtu_b = set_b.or_else(|| image_mtus.get(&link.node_b).copied());
+        if let (Some(mtu_a), Some(mtu_b)) = (mtu_a, mtu_b)
+            && mtu_a != mtu_b
+        {
+            bail!(
+                "Manifest {} - MTU mismatch, '{}' uses {} and '{}' uses {}. Set the same mtu on both nodes or set mtu on the link",
+                name,
+                link.node_a,
+                mtu_a,
+                link.node_b,
+                mtu_b
+            );
+        }
+    }
+    Ok(())
+}
+
+fn check_mtu(name: &str, mtu: u16) -> Result<()> {
+    if !(MTU_MIN..=MTU_JUMBO_NET).contains(&mtu) {
+        bail!("Manifest {name} - MTU {mtu} is outside {MTU_MIN}-{MTU_JUMBO_NET}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, mtu: Option<u16>) -> Node {
+        Node {
+            name: name.to_string(),
+            mtu,
+            ..Default::default()
+        }
+    }
+
+    fn link(mtu: Option<u16>) -> LinkDetailed {
+        LinkDetailed {
+            node_a: "leaf01".to_string(),
+            int_a: "eth1".to_string(),
+            node_b: "spine01".to_string(),
+            int_b: "eth1".to_string(),
+            mtu,
+            ..Default::default()
+        }
+    }
+
+    fn image_mtus(leaf: u16, spine: u16) -> HashMap<String, u16> {
+        HashMap::from([("leaf01".to_string(), leaf), ("spine01".to_string(), spine)])
+    }
+
+    #[test]
+    fn test_check_mtus_valid() {
+        // Image defaults may differ while no node sets an MTU
+        let nodes = [node("leaf01", None), node("spine01", None)];
+        assert!(check_mtus(&nodes, &[link(None)], &image_mtus(1500, 9216)).is_ok());
+
+        // A node MTU matching the image MTU of its peer
+        let nodes = [node("leaf01", Some(9216)), node("spine01", None)];
+        assert!(check_mtus(&nodes, &[link(None)], &image_mtus(1500, 9216)).is_ok());
+
+        // A link MTU overrides both nodes
+        let nodes = [node("leaf01", Some(9000)), node("spine01", Some(1500))];
+        assert!(check_mtus(&nodes, &[link(Some(9216))], &image_mtus(1500, 1500)).is_ok());
+    }
+
+    #[test]
+    fn test_check_mtus_invalid() {
+        let nodes = [node("leaf01", Some(9216)), node("spine01", None)];
+        let err = check_mtus(&nodes, &[link(None)], &image_mtus(1500, 1500))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("MTU mismatch, 'leaf01' uses 9216 and 'spine01' uses 1500"));
+
+        // Both nodes set an MTU, no image MTUs known
+        let nodes = [node("leaf01", Some(9216)), node("spine01", Some(9000))];
+        assert!(check_mtus(&nodes, &[link(None)], &HashMap::new()).is_err());
+
+        let nodes = [node("leaf01", None), node("spine01", None)];
+        let err = check_mtus(&nodes, &[link(Some(9700))], &HashMap::new())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("MTU 9700 is outside 1280-9600"));
+
+        let nodes = [node("leaf01", Some(576))];
+        assert!(check_mtus(&nodes, &[], &HashMap::new()).is_err());
+    }
+}
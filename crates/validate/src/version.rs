@@ -34,19 +34,7 @@ pub fn validate_and_resolve_node_versions(
     docker_images: &[String],
 ) -> Result<Vec<Node>> {
     let mut validated_nodes = Vec::new();
-
-    // Build lookup map for faster access, preferring default images per model
-    let mut config_map: HashMap<NodeModel, &NodeConfig> = HashMap::new();
-    for config in node_images {
-        config_map
-            .entry(config.model)
-            .and_modify(|existing| {
-                if config.default && !existing.default {
-                    *existing = config;
-                }
-            })
-            .or_insert(config);
-    }
+    let config_map = default_configs(node_images);
 
     for node in nodes {
         let mut updated_node = node.clone();
@@ -111,6 +99,69 @@ pub fn validate_and_resolve_node_versions(
     Ok(validated_nodes)
 }
 
+/// Build a lookup of node_image per model, preferring the default image
+fn default_configs(node_images: &[NodeConfig]) -> HashMap<NodeModel, &NodeConfig> {
+    let mut config_map: HashMap<NodeModel, &NodeConfig> = HashMap::new();
+    for config in node_images {
+        config_map
+            .entry(config.model)
+            .and_modify(|existing| {
+                if config.default && !existing.default {
+                    *existing = config;
+                }
+            })
+            .or_insert(config);
+    }
+    config_map
+}
+
+/// Find the container images used by nodes that are not in local Docker
+///
+/// Versions are resolved as in [`validate_and_resolve_node_versions`]. Nodes
+/// whose model or version has no node_image are skipped, the validation
+/// reports them.
+///
+/// # Arguments
+/// * `nodes` - List of nodes from manifest
+/// * `node_images` - Pre-fetched list of all node configs from DB
+/// * `docker_images` - List of local Docker images (format: "repo:tag")
+///
+/// # Returns
+/// De-duplicated `(repo, tag)` pairs in node order
+pub fn missing_container_images(
+    nodes: &[Node],
+    node_images: &[NodeConfig],
+    docker_images: &[String],
+) -> Vec<(String, String)> {
+    let config_map = default_configs(node_images);
+    let mut missing: Vec<(String, String)> = Vec::new();
+
+    for node in nodes {
+        let Some(node_image) = config_map.get(&node.model) else {
+            continue;
+        };
+        if node_image.kind != NodeKind::Container {
+            continue;
+        }
+        let version = node.version.as_deref().unwrap_or(&node_image.version);
+        let repo = node_images
+            .iter()
+            .find(|c| c.model == node.model && c.version == version)
+            .and_then(|c| c.repo.clone());
+        let Some(repo) = repo else {
+            continue;
+        };
+
+        let image = (repo, version.to_string());
+        if !docker_images.contains(&format!("{}:{}", image.0, image.1)) && !missing.contains(&image)
+        {
+            missing.push(image);
+        }
+    }
+
+    missing
+}
+
 /// Validate that a version exists in node_image for the given model
 fn validate_version_in_db(
     model: &NodeModel,
@@ -258,6 +309,7 @@ mod tests {
             reserved_interface_count: 0,
             default: false,
             boot_mode: None,
+            image_sha256: None,
         }
     }
 
@@ -265,8 +317,8 @@ mod tests {
     fn test_validate_version_in_db_found() {
         let model = NodeModel::AristaVeos;
         let configs = vec![
-            create_test_node_image(model.clone(), "4.28.0F", NodeKind::VirtualMachine),
-            create_test_node_image(model.clone(), "4.29.2F", NodeKind::VirtualMachine),
+            create_test_node_image(model, "4.28.0F", NodeKind::VirtualMachine),
+            create_test_node_image(model, "4.29.2F", NodeKind::VirtualMachine),
         ];
 
         let result = validate_version_in_db(&model, "4.28.0F", &configs);
@@ -277,8 +329,8 @@ mod tests {
     fn test_validate_version_in_db_not_found() {
         let model = NodeModel::AristaVeos;
         let configs = vec![
-            create_test_node_image(model.clone(), "4.28.0F", NodeKind::VirtualMachine),
-            create_test_node_image(model.clone(), "4.29.2F", NodeKind::VirtualMachine),
+            create_test_node_image(model, "4.28.0F", NodeKind::VirtualMachine),
+            create_test_node_image(model, "4.29.2F", NodeKind::VirtualMachine),
         ];
 
         let result = validate_version_in_db(&model, "4.30.0F", &configs);
@@ -578,4 +630,31 @@ mod tests {
         assert_eq!(resolved[0].version, Some("4.32.0F".to_string()));
         assert_eq!(resolved[1].version, Some("4.31.0F".to_string()));
     }
+
+    #[test]
+    fn test_missing_container_images() {
+        let nodes = vec![
+            create_test_node("ceos1", NodeModel::AristaCeos, None),
+            create_test_node("ceos2", NodeModel::AristaCeos, None),
+            create_test_node("ceos3", NodeModel::AristaCeos, Some("4.31.0F")),
+            create_test_node("veos1", NodeModel::AristaVeos, None),
+            create_test_node("srl1", NodeModel::NokiaSrlinux, None),
+        ];
+        let mut default =
+            create_test_node_image(NodeModel::AristaCeos, "4.32.0F", NodeKind::Container);
+        default.default = true;
+        let configs = vec![
+            create_test_node_image(NodeModel::AristaCeos, "4.31.0F", NodeKind::Container),
+            default,
+            create_test_node_image(NodeModel::AristaVeos, "4.28.0F", NodeKind::VirtualMachine),
+        ];
+        let docker_images = vec!["test-repo:4.31.0F".to_string()];
+
+        let missing = missing_container_images(&nodes, &configs, &docker_images);
+
+        assert_eq!(
+            missing,
+            vec![("test-repo".to_string(), "4.32.0F".to_string())]
+        );
+    }
 }